@@ -0,0 +1,254 @@
+use crate::types::{Action, Candidate, Decision, UtilityWeights};
+use std::collections::{BTreeMap, HashMap};
+use void_reckoning_combat::resolve::{resolve_battles, BattleSetup};
+use void_reckoning_economy::types::{EconomicReport, ResourceState, SCALE_FACTOR};
+use void_reckoning_pathfinder::GraphTopology;
+
+/// Auto-resolve runs per predicted battle.
+pub const DEFAULT_SAMPLES: u32 = 16;
+/// Route cost at which the distance penalty reaches half its weight.
+const ROUTE_COST_SCALE: f32 = 10.0;
+/// Fixed cost of committing to a war, before the battle odds.
+const WAR_COMMITMENT: f32 = 0.5;
+
+/// What the AI knows about the acting faction (its report and treasury)
+/// and the map.
+pub struct DecisionContext<'a> {
+    pub report: &'a EconomicReport,
+    pub treasury: ResourceState,
+    pub topology: &'a GraphTopology,
+    /// From `GraphTopology::closeness_centrality`; missing systems count as 0.
+    pub centrality: &'a HashMap<String, f32>,
+}
+
+impl DecisionContext<'_> {
+    /// Income in credits, at least 1, to put gains and costs on one scale.
+    fn income_scale(&self) -> f32 {
+        credits(&self.report.total_income).max(1.0)
+    }
+
+    fn centrality(&self, system: &str) -> f32 {
+        self.centrality.get(system).copied().unwrap_or(0.0)
+    }
+
+    /// Cost of the cheapest route, or None if there is none.
+    fn route_cost(&self, from: &str, to: &str) -> Option<f32> {
+        self.topology.find_path(from, to, None).map(|(_, cost)| cost)
+    }
+}
+
+pub struct DecisionEngine {
+    pub weights: UtilityWeights,
+    /// Auto-resolve runs per predicted battle; more is steadier and slower.
+    pub samples: u32,
+    pub seed: u64,
+    /// Threads for the predictions (0 = one per core).
+    pub workers: usize,
+}
+
+impl Default for DecisionEngine {
+    fn default() -> Self {
+        Self::new(UtilityWeights::default())
+    }
+}
+
+impl DecisionEngine {
+    pub fn new(weights: UtilityWeights) -> Self {
+        Self { weights, samples: DEFAULT_SAMPLES, seed: 0, workers: 0 }
+    }
+
+    /// Scores every candidate and returns them best first: feasible before
+    /// infeasible, then by score, ties broken by id. All predicted battles
+    /// are auto-resolved in one parallel batch.
+    pub fn rank(&self, context: &DecisionContext<'_>, candidates: &[Candidate]) -> Vec<Decision> {
+        let battles: Vec<(&BattleSetup, u8)> = candidates.iter().filter_map(|c| c.action.battle()).collect();
+        let mut predictions = self.predict(&battles).into_iter();
+
+        let mut decisions: Vec<Decision> = candidates
+            .iter()
+            .map(|candidate| {
+                let win_probability = candidate.action.battle().and_then(|_| predictions.next());
+                self.score(context, candidate, win_probability)
+            })
+            .collect();
+        decisions.sort_by(|a, b| {
+            b.feasible
+                .cmp(&a.feasible)
+                .then(b.score.total_cmp(&a.score))
+                .then_with(|| a.id.cmp(&b.id))
+        });
+        decisions
+    }
+
+    /// Win rate of `side` in each battle over `samples` auto-resolve runs;
+    /// draws and mutual destruction count as half a win.
+    pub fn predict(&self, battles: &[(&BattleSetup, u8)]) -> Vec<f32> {
+        let samples = self.samples.max(1) as usize;
+        let setups: Vec<BattleSetup> = battles
+            .iter()
+            .flat_map(|(battle, _)| {
+                // Without a fixed seed each run gets its own, derived from ours.
+                let mut battle = (*battle).clone();
+                battle.seed = None;
+                std::iter::repeat_n(battle, samples)
+            })
+            .collect();
+        let outcomes = resolve_battles(&setups, self.seed, self.workers);
+        outcomes
+            .chunks(samples)
+            .zip(battles)
+            .map(|(runs, (_, side))| {
+                let wins: f32 = runs
+                    .iter()
+                    .map(|o| match o.winner {
+                        Some(winner) if winner == *side => 1.0,
+                        Some(_) => 0.0,
+                        None => 0.5,
+                    })
+                    .sum();
+                wins / samples as f32
+            })
+            .collect()
+    }
+
+    fn score(&self, context: &DecisionContext<'_>, candidate: &Candidate, win_probability: Option<f32>) -> Decision {
+        let w = &self.weights;
+        let mut factors = BTreeMap::new();
+        let mut feasible = true;
+        if context.report.is_insolvent {
+            factors.insert("insolvency".to_string(), -w.risk_aversion);
+        }
+
+        match &candidate.action {
+            Action::Build { cost, value, .. } => {
+                feasible = covers(&context.treasury, cost);
+                let liquidity = credits(&context.treasury).max(0.0) + context.income_scale();
+                factors.insert("value".to_string(), w.economy * value);
+                factors.insert("spend".to_string(), -w.risk_aversion * credits(cost) / liquidity);
+            }
+            Action::MoveFleet { from, to, .. } => match context.route_cost(from, to) {
+                Some(cost) => {
+                    factors.insert("position".to_string(), w.expansion * context.centrality(to));
+                    factors.insert("distance".to_string(), -w.risk_aversion * distance_penalty(cost));
+                }
+                None => feasible = false,
+            },
+            Action::DeclareWar { .. } => {
+                factors.insert("commitment".to_string(), -w.risk_aversion * WAR_COMMITMENT);
+            }
+            Action::EstablishTradeRoute { from, to, value } => match context.route_cost(from, to) {
+                Some(cost) => {
+                    factors.insert("income".to_string(), w.trade * credits(value) / context.income_scale());
+                    let hubs = (context.centrality(from) + context.centrality(to)) / 2.0;
+                    factors.insert("hubs".to_string(), w.trade * hubs);
+                    factors.insert("distance".to_string(), -w.risk_aversion * distance_penalty(cost));
+                }
+                None => feasible = false,
+            },
+        }
+        if let Some(p) = win_probability {
+            factors.insert("combat".to_string(), w.military * (2.0 * p - 1.0));
+            factors.insert("defeat_risk".to_string(), -w.risk_aversion * (1.0 - p) / 2.0);
+        }
+
+        Decision {
+            id: candidate.id.clone(),
+            kind: candidate.action.kind().to_string(),
+            score: if feasible { factors.values().sum() } else { 0.0 },
+            feasible,
+            factors,
+            win_probability,
+        }
+    }
+}
+
+fn credits(resources: &ResourceState) -> f32 {
+    (resources.credits as f64 / SCALE_FACTOR as f64) as f32
+}
+
+fn distance_penalty(cost: f32) -> f32 {
+    cost / (cost + ROUTE_COST_SCALE)
+}
+
+fn covers(treasury: &ResourceState, cost: &ResourceState) -> bool {
+    treasury.credits >= cost.credits
+        && treasury.minerals >= cost.minerals
+        && treasury.energy >= cost.energy
+        && treasury.research >= cost.research
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use void_reckoning_combat::resolve::{UnitSetup, WeaponSetup};
+    use void_reckoning_combat::{CoverType, UnitId, WeaponType};
+
+    fn unit(id: u32, faction_idx: u8) -> UnitSetup {
+        UnitSetup {
+            id: UnitId(id),
+            name: String::new(),
+            faction_idx,
+            max_hp: 100.0,
+            x: faction_idx as f32 * 50.0,
+            y: id as f32 * 10.0,
+            weapons: vec![WeaponSetup {
+                name: "Gun".to_string(),
+                weapon_type: WeaponType::Kinetic,
+                range: 500.0,
+                damage: 25.0,
+                accuracy: 1.0,
+                cooldown: 1.0,
+                aoe_radius: 0.0,
+            }],
+            speed: 10.0,
+            evasion: 0.0,
+            shields: 0.0,
+            armor: 0.0,
+            cover: CoverType::None,
+        }
+    }
+
+    fn battle(ours: u32, theirs: u32) -> Option<BattleSetup> {
+        let units = (0..ours).map(|i| unit(i, 0)).chain((0..theirs).map(|i| unit(ours + i, 1))).collect();
+        Some(BattleSetup { name: String::new(), width: 500.0, height: 500.0, units, max_turns: None, seed: None, end_conditions: Default::default() })
+    }
+
+    #[test]
+    fn ranks_winnable_moves_to_hubs_first() {
+        let mut topology = GraphTopology::new();
+        for spoke in ["A", "B", "C"] {
+            topology.add_edge("Hub", spoke, 1.0);
+            topology.add_edge(spoke, "Hub", 1.0);
+        }
+        let centrality = topology.closeness_centrality();
+        let report = EconomicReport {
+            faction_name: "Imperium".to_string(),
+            total_income: ResourceState::new(100.0, 0.0, 0.0, 0.0),
+            total_upkeep: ResourceState::default(),
+            net_profit: ResourceState::new(100.0, 0.0, 0.0, 0.0),
+            income_by_category: BTreeMap::new(),
+            is_insolvent: false,
+            active_nodes: 1,
+        };
+        let context = DecisionContext {
+            report: &report,
+            treasury: ResourceState::new(50.0, 0.0, 0.0, 0.0),
+            topology: &topology,
+            centrality: &centrality,
+        };
+        let candidates: Vec<Candidate> = vec![
+            Candidate { id: "to-hub".to_string(), action: Action::MoveFleet { fleet: "f".to_string(), from: "A".to_string(), to: "Hub".to_string(), battle: battle(6, 2), side: 0 } },
+            Candidate { id: "to-b".to_string(), action: Action::MoveFleet { fleet: "f".to_string(), from: "A".to_string(), to: "B".to_string(), battle: battle(2, 6), side: 0 } },
+            Candidate { id: "nowhere".to_string(), action: Action::MoveFleet { fleet: "f".to_string(), from: "A".to_string(), to: "Mars".to_string(), battle: None, side: 0 } },
+            Candidate { id: "dreadnought".to_string(), action: Action::Build { item: "Dreadnought".to_string(), cost: ResourceState::new(500.0, 0.0, 0.0, 0.0), value: 5.0 } },
+        ];
+
+        let engine = DecisionEngine::default();
+        let decisions = engine.rank(&context, &candidates);
+        let order: Vec<&str> = decisions.iter().map(|d| d.id.as_str()).collect();
+        assert_eq!(order[..2], ["to-hub", "to-b"]);
+        assert!(decisions[0].win_probability.unwrap() > 0.9);
+        assert!(decisions[2..].iter().all(|d| !d.feasible));
+        assert_eq!(engine.rank(&context, &candidates)[0].score, decisions[0].score);
+    }
+}
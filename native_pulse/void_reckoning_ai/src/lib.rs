@@ -0,0 +1,14 @@
+//! Utility AI: scores a faction's candidate actions (build, move a fleet,
+//! declare war, open a trade route) from its economic report, the map's
+//! centrality and auto-resolved battle predictions, and ranks them.
+
+pub mod types;
+pub mod engine;
+
+pub use engine::{DecisionContext, DecisionEngine};
+pub use types::{Action, Candidate, Decision, UtilityWeights};
+
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Version of the `Candidate`/`Decision` layouts; bumped on incompatible
+/// changes.
+pub const SCHEMA_VERSION: u32 = 1;
@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use void_reckoning_combat::resolve::BattleSetup;
+use void_reckoning_economy::types::ResourceState;
+
+/// Something a faction could do this turn. Battles are the fight the AI
+/// expects the action to start, with the acting faction as `side`.
+/// Externally tagged (`{"Build": {...}}`): resource amounts are `i128`,
+/// which internally tagged enums can't carry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Action {
+    Build {
+        item: String,
+        cost: ResourceState,
+        /// Utility of having the item, before its cost is weighed.
+        #[serde(default = "default_value")]
+        value: f32,
+    },
+    MoveFleet {
+        fleet: String,
+        from: String,
+        to: String,
+        #[serde(default)]
+        battle: Option<BattleSetup>,
+        #[serde(default)]
+        side: u8,
+    },
+    DeclareWar {
+        target: String,
+        #[serde(default)]
+        battle: Option<BattleSetup>,
+        #[serde(default)]
+        side: u8,
+    },
+    EstablishTradeRoute {
+        from: String,
+        to: String,
+        /// Expected income per turn.
+        value: ResourceState,
+    },
+}
+
+fn default_value() -> f32 {
+    1.0
+}
+
+impl Action {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Action::Build { .. } => "Build",
+            Action::MoveFleet { .. } => "MoveFleet",
+            Action::DeclareWar { .. } => "DeclareWar",
+            Action::EstablishTradeRoute { .. } => "EstablishTradeRoute",
+        }
+    }
+
+    /// The predicted battle and the acting faction's side in it.
+    pub fn battle(&self) -> Option<(&BattleSetup, u8)> {
+        match self {
+            Action::MoveFleet { battle: Some(battle), side, .. } | Action::DeclareWar { battle: Some(battle), side, .. } => {
+                Some((battle, *side))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Candidate {
+    pub id: String,
+    pub action: Action,
+}
+
+/// How much a faction's personality cares about each kind of gain; the
+/// same candidates rank differently under different weights.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UtilityWeights {
+    pub economy: f32,
+    pub expansion: f32,
+    pub military: f32,
+    pub trade: f32,
+    /// Penalty per unit of risk: spending the treasury down, long routes,
+    /// fights that might be lost.
+    pub risk_aversion: f32,
+}
+
+impl Default for UtilityWeights {
+    fn default() -> Self {
+        Self { economy: 1.0, expansion: 1.0, military: 1.0, trade: 1.0, risk_aversion: 1.0 }
+    }
+}
+
+/// A scored candidate. `factors` break the score down by term, so callers
+/// can see why an action ranked where it did.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Decision {
+    pub id: String,
+    pub kind: String,
+    pub score: f32,
+    /// False if the action can't be carried out (e.g. no route); such
+    /// decisions score 0 and rank last.
+    pub feasible: bool,
+    pub factors: BTreeMap<String, f32>,
+    /// Auto-resolve win rate, for actions that start a battle.
+    pub win_probability: Option<f32>,
+}
@@ -35,7 +35,7 @@ impl InvariantValidator for HealthInvariantValidator {
              ValidationResult {
                 category: ValidationCategory::CrossSystem,
                 severity: ValidationSeverity::Info,
-                entity_id: "global".to_string(),
+                entity_id: "global".into(),
                 message: "Health invariant satisfied".to_string(),
                 rule_name: self.name().to_string(),
                 file_path: None,
@@ -45,7 +45,7 @@ impl InvariantValidator for HealthInvariantValidator {
              ValidationResult {
                 category: ValidationCategory::CrossSystem,
                 severity: ValidationSeverity::Critical,
-                entity_id: "global".to_string(),
+                entity_id: "global".into(),
                 message: format!("Health invariant violations: {}", violations.join(", ")),
                 rule_name: self.name().to_string(),
                 file_path: None,
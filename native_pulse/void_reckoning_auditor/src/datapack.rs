@@ -0,0 +1,315 @@
+//! Data packs: the base game data plus ordered mod overlays, merged into
+//! one set of `Registries`.
+//!
+//! A pack maps registry names to entries keyed by id. A plain value sets
+//! the entry (adding or replacing it); an object with a single `$`-key
+//! says what the pack means to do, so the merge can report when that
+//! isn't what happened:
+//!
+//! - `{"$add": value}`: a new entry; clashing with an existing one is a
+//!   conflict (the later pack still wins).
+//! - `{"$override": value}`: replaces an entry that should exist.
+//! - `{"$patch": object}`: JSON merge patch (RFC 7396) onto an existing
+//!   entry; `null` removes a field.
+//! - `{"$delete": true}`: removes the entry.
+//!
+//! Packs apply in load order. Two packs other than the base touching the
+//! same entry are reported as contested, the later one winning.
+
+use crate::registry::{Registries, RegistryKind};
+use serde::de::Deserializer;
+use serde::ser::Serializer;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use void_reckoning_shared::errors::EngineError;
+
+/// What a pack does to one entry.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    Set(Value),
+    Add(Value),
+    Override(Value),
+    Patch(Value),
+    Delete,
+}
+
+impl Change {
+    fn from_value(value: Value) -> Self {
+        if let Value::Object(map) = &value {
+            if map.len() == 1 {
+                let (key, inner) = map.iter().next().expect("one entry");
+                match key.as_str() {
+                    "$add" => return Change::Add(inner.clone()),
+                    "$override" => return Change::Override(inner.clone()),
+                    "$patch" => return Change::Patch(inner.clone()),
+                    "$delete" => return Change::Delete,
+                    _ => {}
+                }
+            }
+        }
+        Change::Set(value)
+    }
+
+    fn to_value(&self) -> Value {
+        let tagged = |key: &str, value: &Value| Value::Object(Map::from_iter([(key.to_string(), value.clone())]));
+        match self {
+            Change::Set(value) => value.clone(),
+            Change::Add(value) => tagged("$add", value),
+            Change::Override(value) => tagged("$override", value),
+            Change::Patch(value) => tagged("$patch", value),
+            Change::Delete => tagged("$delete", &Value::Bool(true)),
+        }
+    }
+}
+
+impl Serialize for Change {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_value().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Change {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Value::deserialize(deserializer).map(Change::from_value)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DataPack {
+    pub id: String,
+    #[serde(default)]
+    pub version: String,
+    /// Packs that must be loaded before this one.
+    #[serde(default)]
+    pub requires: Vec<String>,
+    /// Registry name (`"weapons"`, `"buildings"`, ...) to entries by id.
+    #[serde(default)]
+    pub registries: BTreeMap<String, BTreeMap<String, Change>>,
+}
+
+impl DataPack {
+    pub fn from_json(json: &str) -> Result<Self, EngineError> {
+        serde_json::from_str(json).map_err(EngineError::json)
+    }
+
+    /// Reads a pack directory: `pack.json` (id, version, requires and
+    /// optionally inline registries) plus one `<registry>.json` file per
+    /// registry it changes, e.g. `weapons.json`.
+    pub fn from_dir(dir: &Path) -> Result<Self, EngineError> {
+        let read = |path: &Path| {
+            std::fs::read_to_string(path).map_err(|e| EngineError::Registry(format!("cannot read {}: {}", path.display(), e)))
+        };
+        let mut pack = Self::from_json(&read(&dir.join("pack.json"))?)?;
+        for kind in RegistryKind::ALL {
+            let path = dir.join(format!("{}.json", kind.as_str()));
+            if path.is_file() {
+                let entries: BTreeMap<String, Change> = serde_json::from_str(&read(&path)?)
+                    .map_err(|e| EngineError::Registry(format!("{}: {}", path.display(), e)))?;
+                pack.registries.entry(kind.as_str().to_string()).or_default().extend(entries);
+            }
+        }
+        Ok(pack)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConflictKind {
+    /// `$add` of an id an earlier pack already has; replaced anyway.
+    AlreadyExists,
+    /// `$override`, `$patch` or `$delete` of an id no earlier pack has.
+    /// An override adds the entry; patches and deletes do nothing.
+    MissingTarget,
+    /// More than one mod changed the entry; the last listed won.
+    Contested,
+    /// A `requires` pack isn't loaded before this one.
+    MissingDependency,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Conflict {
+    pub kind: ConflictKind,
+    /// Empty for `MissingDependency`.
+    pub registry: String,
+    /// The entry, or the missing pack for `MissingDependency`.
+    pub id: String,
+    /// Packs involved, in load order.
+    pub packs: Vec<String>,
+}
+
+/// What a merge produced besides the registries themselves.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MergeReport {
+    pub packs: Vec<String>,
+    pub conflicts: Vec<Conflict>,
+    /// Registry to entry id to the packs that touched it, in load order.
+    /// Deleted entries keep their history.
+    pub origins: BTreeMap<String, BTreeMap<String, Vec<String>>>,
+}
+
+/// Base data and mods in load order; the first pack is the base.
+#[derive(Debug, Clone, Default)]
+pub struct DataPackLoader {
+    packs: Vec<DataPack>,
+}
+
+impl DataPackLoader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a pack after those already loaded; a pack with the same id
+    /// replaces the earlier one in place.
+    pub fn push(&mut self, pack: DataPack) {
+        match self.packs.iter_mut().find(|p| p.id == pack.id) {
+            Some(existing) => *existing = pack,
+            None => self.packs.push(pack),
+        }
+    }
+
+    pub fn remove(&mut self, id: &str) -> Option<DataPack> {
+        let index = self.packs.iter().position(|p| p.id == id)?;
+        Some(self.packs.remove(index))
+    }
+
+    pub fn packs(&self) -> &[DataPack] {
+        &self.packs
+    }
+
+    /// Applies every pack in order. Unknown registry names are an error;
+    /// everything else that doesn't line up is a conflict in the report.
+    pub fn merge(&self) -> Result<(Registries, MergeReport), EngineError> {
+        let mut merged: HashMap<RegistryKind, Map<String, Value>> = HashMap::new();
+        let mut report = MergeReport { packs: self.packs.iter().map(|p| p.id.clone()).collect(), ..MergeReport::default() };
+
+        for (position, pack) in self.packs.iter().enumerate() {
+            for required in &pack.requires {
+                if !self.packs[..position].iter().any(|p| &p.id == required) {
+                    report.conflicts.push(Conflict {
+                        kind: ConflictKind::MissingDependency,
+                        registry: String::new(),
+                        id: required.clone(),
+                        packs: vec![pack.id.clone()],
+                    });
+                }
+            }
+            for (name, entries) in &pack.registries {
+                let kind = RegistryKind::parse(name)
+                    .ok_or_else(|| EngineError::Registry(format!("pack '{}': unknown registry type '{}'", pack.id, name)))?;
+                let registry = merged.entry(kind).or_default();
+                let origins = report.origins.entry(name.clone()).or_default();
+                for (id, change) in entries {
+                    let history = origins.entry(id.clone()).or_default();
+                    let conflict = |kind: ConflictKind, history: &[String]| Conflict {
+                        kind,
+                        registry: name.clone(),
+                        id: id.clone(),
+                        packs: history.iter().cloned().chain([pack.id.clone()]).collect(),
+                    };
+                    let exists = registry.contains_key(id);
+                    match change {
+                        Change::Add(_) if exists => report.conflicts.push(conflict(ConflictKind::AlreadyExists, history)),
+                        Change::Override(_) | Change::Patch(_) | Change::Delete if !exists => {
+                            report.conflicts.push(conflict(ConflictKind::MissingTarget, history))
+                        }
+                        _ => {}
+                    }
+                    let base = self.packs.first().map(|p| p.id.as_str());
+                    if position > 0 && history.iter().any(|p| Some(p.as_str()) != base) {
+                        report.conflicts.push(conflict(ConflictKind::Contested, history));
+                    }
+                    match change {
+                        Change::Set(value) | Change::Add(value) | Change::Override(value) => {
+                            registry.insert(id.clone(), value.clone());
+                        }
+                        Change::Patch(patch) => {
+                            if let Some(entry) = registry.get_mut(id) {
+                                merge_patch(entry, patch);
+                            }
+                        }
+                        Change::Delete => {
+                            registry.remove(id);
+                        }
+                    }
+                    history.push(pack.id.clone());
+                }
+            }
+        }
+
+        let mut registries = Registries::new();
+        for kind in RegistryKind::ALL {
+            if let Some(data) = merged.remove(&kind) {
+                registries.replace(kind, data);
+            }
+        }
+        Ok((registries, report))
+    }
+}
+
+/// RFC 7396 JSON merge patch.
+fn merge_patch(target: &mut Value, patch: &Value) {
+    let Value::Object(fields) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    let Value::Object(target) = target else { return };
+    for (key, value) in fields {
+        match value {
+            Value::Null => {
+                target.remove(key);
+            }
+            _ => merge_patch(target.entry(key.clone()).or_insert(Value::Null), value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn pack(value: Value) -> DataPack {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn overlays_layer_over_the_base_and_report_conflicts() {
+        let mut loader = DataPackLoader::new();
+        loader.push(pack(json!({"id": "base", "registries": {"weapons": {
+            "Lasgun": {"damage": 5, "range": 30},
+            "Bolter": {"damage": 9, "range": 25},
+        }}})));
+        loader.push(pack(json!({"id": "balance", "requires": ["base"], "registries": {"weapons": {
+            "Lasgun": {"$patch": {"damage": 6, "range": null}},
+            "Bolter": {"$delete": true},
+            "Meltagun": {"$override": {"damage": 40}},
+        }}})));
+        loader.push(pack(json!({"id": "more_guns", "requires": ["xenos"], "registries": {"weapons": {
+            "Lasgun": {"$add": {"damage": 7}},
+        }}})));
+
+        let (registries, report) = loader.merge().unwrap();
+        assert_eq!(registries.weapons.get("Lasgun"), Some(&json!({"damage": 7})));
+        assert!(!registries.weapons.contains_key("Bolter"));
+        assert_eq!(registries.weapons.get("Meltagun"), Some(&json!({"damage": 40})));
+        assert_eq!(report.origins["weapons"]["Lasgun"], vec!["base", "balance", "more_guns"]);
+
+        let kinds: Vec<_> = report.conflicts.iter().map(|c| (c.kind, c.id.as_str())).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (ConflictKind::MissingTarget, "Meltagun"),
+                (ConflictKind::MissingDependency, "xenos"),
+                (ConflictKind::AlreadyExists, "Lasgun"),
+                (ConflictKind::Contested, "Lasgun"),
+            ]
+        );
+
+        loader.push(pack(json!({"id": "broken", "registries": {"spaceships": {}}})));
+        assert!(loader.merge().is_err());
+    }
+}
@@ -1,16 +1,29 @@
-use crate::rules::{ValidationRule, ValidationContext, FieldExistenceRule, TypeValidationRule, ReferenceIntegrityRule};
-use crate::types::{ValidationResult, ValidationSummary, ValidationReport, EntityType, ValidationSeverity};
-use crate::registry::Registries;
+use crate::rules::{
+    ValidationRule, ValidationContext, FieldExistenceRule, TypeValidationRule, ReferenceIntegrityRule,
+    LocalizationKeyRule, AssetPathRule, CompositeRule, CompositeRuleSpec,
+};
+use crate::types::{
+    ValidationResult, ValidationSummary, ValidationReport, EntityType, ValidationSeverity, ValidationCategory,
+    SaveValidationReport, MemoryStats,
+};
+use crate::consistency::{InvariantValidator, HealthInvariantValidator};
+use crate::registry::{Registries, RegistryKind, RegistryReload};
 use std::sync::Arc;
-use serde_json::Value;
+use serde_json::{Map, Value};
 
 use void_reckoning_shared::{Event, EventLog, EventSeverity, CorrelationContext};
+use void_reckoning_shared::ids::EntityId;
+use void_reckoning_shared::scope::ContextStack;
+use void_reckoning_shared::simtime::SimTime;
+use void_reckoning_shared::span::Span;
 
 pub struct ValidationEngine {
     rules: Vec<Arc<dyn ValidationRule>>,
+    invariants: Vec<Arc<dyn InvariantValidator>>,
     registries: Arc<Registries>,
     pub event_log: Option<EventLog>,
-    pub current_context: CorrelationContext,
+    /// Correlation contexts; nested operations push scopes onto this.
+    pub contexts: ContextStack,
 }
 
 impl ValidationEngine {
@@ -19,13 +32,20 @@ impl ValidationEngine {
             Arc::new(FieldExistenceRule),
             Arc::new(TypeValidationRule),
             Arc::new(ReferenceIntegrityRule),
+            Arc::new(LocalizationKeyRule),
+            Arc::new(AssetPathRule),
         ];
         
+        let invariants: Vec<Arc<dyn InvariantValidator>> = vec![
+            Arc::new(HealthInvariantValidator),
+        ];
+
         Self {
             rules,
+            invariants,
             registries,
             event_log: None,
-            current_context: CorrelationContext::new(),
+            contexts: ContextStack::default(),
         }
     }
     
@@ -34,17 +54,131 @@ impl ValidationEngine {
     }
 
     pub fn set_correlation_context(&mut self, context: CorrelationContext) {
-        self.current_context = context;
+        self.contexts.set_root(context);
+    }
+
+    /// Registers a declarative composite rule. A spec with `apply` replaces the
+    /// named rule with a gated version of itself, so it only runs in scope.
+    pub fn add_composite_rule(&mut self, spec: CompositeRuleSpec) -> Result<(), String> {
+        match (&spec.require, spec.apply.clone()) {
+            (Some(_), None) => {
+                self.rules.push(Arc::new(CompositeRule::new(spec)));
+                Ok(())
+            }
+            (None, Some(target)) => {
+                let slot = self.rules.iter_mut()
+                    .find(|r| r.name() == target)
+                    .ok_or_else(|| format!("Composite rule '{}' targets unknown rule '{}'", spec.name, target))?;
+                let inner = Arc::clone(slot);
+                *slot = Arc::new(CompositeRule::gating(spec, inner));
+                Ok(())
+            }
+            _ => Err(format!("Composite rule '{}' must set exactly one of 'require' or 'apply'", spec.name)),
+        }
+    }
+
+    /// Adds an invariant for `validate_state` to check after the built-in
+    /// ones, e.g. a game-specific rule a fuzzer should hold the engines to.
+    pub fn add_invariant(&mut self, invariant: Arc<dyn InvariantValidator>) {
+        self.invariants.push(invariant);
+    }
+
+    pub fn registries(&self) -> &Arc<Registries> {
+        &self.registries
+    }
+
+    pub fn memory_stats(&self) -> MemoryStats {
+        MemoryStats {
+            rules: self.rules.len(),
+            invariants: self.invariants.len(),
+            registry_entries: self.registries.entry_count(),
+            approx_bytes: self.registries.approx_bytes(),
+        }
+    }
+
+    /// Hash of the registries and the active rule set (names, in order,
+    /// and whether each is enabled), stable across processes. Lockstep
+    /// clients compare it to confirm they validate against the same data.
+    pub fn state_hash(&self) -> u64 {
+        let rules: Vec<(&str, bool)> = self.rules.iter().map(|r| (r.name(), r.is_enabled())).collect();
+        self.registries.hash_with(&rules)
+    }
+
+    /// Swaps in a whole new set of registries (e.g. a shared data registry)
+    /// and refreshes every rule that depends on one.
+    pub fn set_registries(&mut self, registries: Arc<Registries>) {
+        self.registries = registries;
+        for rule in &self.rules {
+            if !rule.registry_dependencies().is_empty() {
+                rule.on_registry_reload(&self.registries);
+            }
+        }
+    }
+
+    /// Swaps in new data for a single registry without rebuilding the engine.
+    /// Only rules depending on that registry are refreshed; an unchanged
+    /// payload (same content hash) is a no-op.
+    pub fn reload_registry(&mut self, kind: RegistryKind, data: Map<String, Value>) -> RegistryReload {
+        let changed = Arc::make_mut(&mut self.registries).replace(kind, data);
+
+        let mut refreshed_rules = Vec::new();
+        if changed {
+            for rule in &self.rules {
+                if rule.registry_dependencies().contains(&kind) {
+                    rule.on_registry_reload(&self.registries);
+                    refreshed_rules.push(rule.name().to_string());
+                }
+            }
+        }
+
+        let reload = RegistryReload {
+            registry: kind,
+            changed,
+            version: self.registries.version,
+            content_hash: self.registries.content_hash(kind),
+            refreshed_rules,
+        };
+
+        if let Some(log) = &self.event_log {
+            let message = if changed {
+                format!(
+                    "Registry '{}' reloaded (version {}, hash {:016x}); refreshed rules: [{}]",
+                    kind.as_str(), reload.version, reload.content_hash, reload.refreshed_rules.join(", ")
+                )
+            } else {
+                format!("Registry '{}' reload skipped: content unchanged", kind.as_str())
+            };
+            let evt = Event::new(
+                EventSeverity::Info,
+                "Auditor".to_string(),
+                message,
+                self.contexts.current().child(),
+                Some(kind.as_str().to_string())
+            )
+            .with_field("registry", kind.as_str())
+            .with_field("changed", changed)
+            .with_field("version", reload.version)
+            .with_field("content_hash", format!("{:016x}", reload.content_hash));
+            log.add(evt);
+        }
+
+        reload
     }
     
     pub fn validate_entity(
         &self,
-        entity_id: String,
+        entity_id: impl Into<EntityId>,
         entity_type: EntityType,
         data: Value,
         universe_id: String,
         turn: u64,
     ) -> Vec<ValidationResult> {
+        let entity_id = entity_id.into();
+        let sim_time = SimTime::at_turn(Some(turn));
+        let span = Span::start("auditor.validate_entity", &self.contexts.current())
+            .with_attribute("entity_id", entity_id.as_str())
+            .at(sim_time);
+        let _scope = self.contexts.enter_context(span.context.clone());
         let context = ValidationContext {
             entity_id,
             entity_type,
@@ -60,34 +194,130 @@ impl ValidationEngine {
             if rule.is_enabled() {
                 let result = rule.validate(&context);
                 if result.severity != ValidationSeverity::Info {
-                    if let Some(log) = &self.event_log {
-                        let severity = match result.severity {
-                            ValidationSeverity::Warning => EventSeverity::Warning,
-                            ValidationSeverity::Error => EventSeverity::Error,
-                            ValidationSeverity::Critical => EventSeverity::Critical,
-                            _ => EventSeverity::Info,
-                        };
-                        
-                        let evt = Event::new(
-                            severity,
-                            "Auditor".to_string(),
-                            format!("[Rule: {}] {}", result.rule_name, result.message),
-                            self.current_context.child(),
-                            Some(result.entity_id.clone())
-                        );
-                        log.add(evt);
-                    }
+                    self.log_result(&result, &span.context, sim_time);
                     results.push(result);
                 }
             }
         }
+
+        if let Some(log) = &self.event_log {
+            log.end_span(span.with_attribute("violations", results.len()));
+        }
         
         results
     }
     
+    fn log_result(&self, result: &ValidationResult, parent: &CorrelationContext, sim_time: SimTime) {
+        if let Some(log) = &self.event_log {
+            let severity = match result.severity {
+                ValidationSeverity::Warning => EventSeverity::Warning,
+                ValidationSeverity::Error => EventSeverity::Error,
+                ValidationSeverity::Critical => EventSeverity::Critical,
+                _ => EventSeverity::Info,
+            };
+
+            let evt = Event::new(
+                severity,
+                "Auditor".to_string(),
+                format!("[Rule: {}] {}", result.rule_name, result.message),
+                parent.child(),
+                Some(result.entity_id.to_string())
+            )
+            .with_field("entity_id", result.entity_id.as_str())
+            .with_field("rule", result.rule_name.as_str())
+            .with_field("category", serde_json::to_value(result.category).unwrap_or_default())
+            .at(sim_time);
+            log.add(evt);
+        }
+    }
+
+    /// Runs every invariant validator against a runtime state snapshot.
+    /// Only violations are returned.
+    pub fn validate_state(&self, state: &Value) -> Vec<ValidationResult> {
+        let sim_time = SimTime::at_turn(state.get("turn").and_then(|v| v.as_u64()));
+        let span = Span::start("auditor.validate_state", &self.contexts.current()).at(sim_time);
+        let _scope = self.contexts.enter_context(span.context.clone());
+        let mut results = Vec::new();
+        for invariant in &self.invariants {
+            let result = invariant.validate(state);
+            if result.severity != ValidationSeverity::Info {
+                self.log_result(&result, &span.context, sim_time);
+                results.push(result);
+            }
+        }
+
+        if let Some(log) = &self.event_log {
+            log.end_span(span.with_attribute("violations", results.len()));
+        }
+        results
+    }
+
+    /// Validates a complete campaign save in one pass: every entity definition
+    /// against the registries/rules, then the runtime snapshot against the
+    /// invariants. Malformed entity entries are reported rather than aborting.
+    pub fn validate_save(&self, save: &Value) -> SaveValidationReport {
+        let universe_id = save.get("universe_id").and_then(|v| v.as_str()).unwrap_or("unknown").to_string();
+        let turn = save.get("turn").and_then(|v| v.as_u64()).unwrap_or(0);
+        let span = Span::start("auditor.validate_save", &self.contexts.current())
+            .with_attribute("universe_id", universe_id.as_str())
+            .at(SimTime::at_turn(Some(turn)));
+        let _scope = self.contexts.enter_context(span.context.clone());
+
+        let mut malformed = Vec::new();
+        let mut entities = Vec::new();
+        let entries = save.get("entities").and_then(|v| v.as_array()).map(|v| v.as_slice()).unwrap_or(&[]);
+
+        for (idx, entry) in entries.iter().enumerate() {
+            let id = entry.get("id").and_then(|v| v.as_str());
+            let entity_type = entry.get("type").and_then(|v| v.as_str()).and_then(EntityType::parse);
+
+            match (id, entity_type, entry.get("data")) {
+                (Some(id), Some(entity_type), Some(data)) => {
+                    entities.push((id.into(), entity_type, data.clone()));
+                }
+                _ => malformed.push(ValidationResult {
+                    category: ValidationCategory::Campaign,
+                    severity: ValidationSeverity::Critical,
+                    entity_id: id.map_or_else(|| format!("entities[{}]", idx).into(), EntityId::new),
+                    message: "Malformed save entry: expected 'id', known 'type' and 'data'".to_string(),
+                    rule_name: "save_structure".to_string(),
+                    file_path: None,
+                    timestamp: 0,
+                }),
+            }
+        }
+
+        let mut data_errors = self.validate_batch(entities, universe_id, turn);
+        data_errors.summary.total_checks += malformed.len();
+        data_errors.summary.critical += malformed.len();
+        for result in &malformed {
+            self.log_result(result, &span.context, span.sim_time);
+        }
+        malformed.append(&mut data_errors.results);
+        data_errors.results = malformed;
+
+        let state_corruption = match save.get("state") {
+            Some(state) => self.validate_state(state),
+            None => Vec::new(),
+        };
+
+        let is_loadable = data_errors.summary.critical == 0
+            && !state_corruption.iter().any(|r| r.severity == ValidationSeverity::Critical);
+
+        if let Some(log) = &self.event_log {
+            log.end_span(span.with_attribute("loadable", is_loadable));
+        }
+
+        SaveValidationReport {
+            data_errors,
+            state_corruption,
+            is_loadable,
+        }
+    }
+
     pub fn validate_batch(
         &self,
-        entities: Vec<(String, EntityType, Value)>,
+        entities: Vec<(EntityId, EntityType, Value)>,
         universe_id: String,
         turn: u64,
     ) -> ValidationReport {
@@ -132,3 +362,29 @@ impl ValidationEngine {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_malformed_save_entry_blocks_loading() {
+        let engine = ValidationEngine::new(Arc::new(Registries::new()));
+        let unit = json!({"id": "u1", "type": "unit", "data": {"name": "Guard", "tier": 1, "armor": 10, "speed": 5}});
+        let save = json!({"universe_id": "alpha", "turn": 3, "entities": [unit.clone(), {"id": "u2", "type": "unit"}]});
+
+        let report = engine.validate_save(&save);
+        assert!(!report.is_loadable);
+        assert_eq!(report.data_errors.summary.critical, 1);
+        let malformed = &report.data_errors.results[0];
+        assert_eq!((malformed.entity_id.as_str(), malformed.rule_name.as_str()), ("u2", "save_structure"));
+
+        // Without an id the entry is named by its position
+        let save = json!({"entities": [unit.clone(), {"type": "unit", "data": {}}]});
+        assert_eq!(engine.validate_save(&save).data_errors.results[0].entity_id.as_str(), "entities[1]");
+
+        let save = json!({"universe_id": "alpha", "turn": 3, "entities": [unit]});
+        assert!(engine.validate_save(&save).is_loadable);
+    }
+}
@@ -3,5 +3,11 @@ pub mod registry;
 pub mod rules;
 pub mod engine;
 pub mod consistency;
+pub mod datapack;
 pub mod scheduler;
 
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Version of the `ValidationReport`/`SaveValidationReport` layouts;
+/// bumped on incompatible changes.
+pub const SCHEMA_VERSION: u32 = 1;
+
@@ -1,4 +1,65 @@
+use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
+use std::collections::HashMap;
+use void_reckoning_shared::desync::{extend_hash, hash_bytes};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RegistryKind {
+    Buildings,
+    Technology,
+    Factions,
+    Weapons,
+    Abilities,
+    Localization,
+    Assets,
+}
+
+impl RegistryKind {
+    pub const ALL: [RegistryKind; 7] = [
+        RegistryKind::Buildings,
+        RegistryKind::Technology,
+        RegistryKind::Factions,
+        RegistryKind::Weapons,
+        RegistryKind::Abilities,
+        RegistryKind::Localization,
+        RegistryKind::Assets,
+    ];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "buildings" => Some(RegistryKind::Buildings),
+            "technology" => Some(RegistryKind::Technology),
+            "factions" => Some(RegistryKind::Factions),
+            "weapons" => Some(RegistryKind::Weapons),
+            "abilities" => Some(RegistryKind::Abilities),
+            "localization" => Some(RegistryKind::Localization),
+            "assets" => Some(RegistryKind::Assets),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RegistryKind::Buildings => "buildings",
+            RegistryKind::Technology => "technology",
+            RegistryKind::Factions => "factions",
+            RegistryKind::Weapons => "weapons",
+            RegistryKind::Abilities => "abilities",
+            RegistryKind::Localization => "localization",
+            RegistryKind::Assets => "assets",
+        }
+    }
+}
+
+/// Outcome of replacing one registry's contents.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegistryReload {
+    pub registry: RegistryKind,
+    pub changed: bool,
+    pub version: u64,
+    pub content_hash: u64,
+    pub refreshed_rules: Vec<String>,
+}
 
 #[derive(Debug, Clone)]
 pub struct Registries {
@@ -7,6 +68,14 @@ pub struct Registries {
     pub factions: Map<String, Value>,
     pub weapons: Map<String, Value>,
     pub abilities: Map<String, Value>,
+    /// Localization table: string key -> translated text.
+    pub localization: Map<String, Value>,
+    /// Asset manifest: known icon/model paths (values are ignored).
+    pub assets: Map<String, Value>,
+    /// Bumped every time a registry's content actually changes.
+    pub version: u64,
+    /// Content hash per registry (FNV-1a over the canonical JSON encoding).
+    pub hashes: HashMap<RegistryKind, u64>,
 }
 
 impl Registries {
@@ -17,6 +86,128 @@ impl Registries {
             factions: Map::new(),
             weapons: Map::new(),
             abilities: Map::new(),
+            localization: Map::new(),
+            assets: Map::new(),
+            version: 0,
+            hashes: HashMap::new(),
         }
     }
+
+    pub fn get(&self, kind: RegistryKind) -> &Map<String, Value> {
+        match kind {
+            RegistryKind::Buildings => &self.buildings,
+            RegistryKind::Technology => &self.technology,
+            RegistryKind::Factions => &self.factions,
+            RegistryKind::Weapons => &self.weapons,
+            RegistryKind::Abilities => &self.abilities,
+            RegistryKind::Localization => &self.localization,
+            RegistryKind::Assets => &self.assets,
+        }
+    }
+
+    fn get_mut(&mut self, kind: RegistryKind) -> &mut Map<String, Value> {
+        match kind {
+            RegistryKind::Buildings => &mut self.buildings,
+            RegistryKind::Technology => &mut self.technology,
+            RegistryKind::Factions => &mut self.factions,
+            RegistryKind::Weapons => &mut self.weapons,
+            RegistryKind::Abilities => &mut self.abilities,
+            RegistryKind::Localization => &mut self.localization,
+            RegistryKind::Assets => &mut self.assets,
+        }
+    }
+
+    /// Replaces a registry's contents. Returns false (and leaves the version
+    /// untouched) when the new data hashes identically to the current data.
+    pub fn replace(&mut self, kind: RegistryKind, data: Map<String, Value>) -> bool {
+        let hash = hash_registry(&data);
+        if self.content_hash(kind) == hash {
+            return false;
+        }
+
+        *self.get_mut(kind) = data;
+        self.hashes.insert(kind, hash);
+        self.version += 1;
+        true
+    }
+
+    pub fn content_hash(&self, kind: RegistryKind) -> u64 {
+        self.hashes
+            .get(&kind)
+            .copied()
+            .unwrap_or_else(|| hash_registry(self.get(kind)))
+    }
+
+    /// Entries across all registries.
+    pub fn entry_count(&self) -> usize {
+        RegistryKind::ALL.iter().map(|&kind| self.get(kind).len()).sum()
+    }
+
+    /// Rough heap + inline footprint of every registry's JSON values.
+    pub fn approx_bytes(&self) -> usize {
+        RegistryKind::ALL
+            .iter()
+            .flat_map(|&kind| self.get(kind).iter())
+            .map(|(key, value)| key.len() + value_footprint(value))
+            .sum()
+    }
+
+    /// Combined hash of every registry, stable across processes.
+    pub fn combined_hash(&self) -> u64 {
+        let hashes: Vec<u8> = RegistryKind::ALL.iter().flat_map(|kind| self.content_hash(*kind).to_le_bytes()).collect();
+        hash_bytes(&hashes)
+    }
+
+    /// `combined_hash` extended with the JSON encoding of `extra`.
+    pub fn hash_with(&self, extra: &impl serde::Serialize) -> u64 {
+        let encoded = serde_json::to_vec(extra).unwrap_or_default();
+        extend_hash(self.combined_hash(), &encoded)
+    }
+}
+
+impl Default for Registries {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn value_footprint(value: &Value) -> usize {
+    std::mem::size_of::<Value>()
+        + match value {
+            Value::String(s) => s.len(),
+            Value::Array(items) => items.iter().map(value_footprint).sum(),
+            Value::Object(map) => map.iter().map(|(k, v)| k.len() + value_footprint(v)).sum(),
+            _ => 0,
+        }
+}
+
+fn hash_registry(data: &Map<String, Value>) -> u64 {
+    // serde_json::Map is ordered by key, so the encoding is canonical.
+    let encoded = serde_json::to_string(data).unwrap_or_default();
+    hash_bytes(encoded.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_replace_tracks_version_and_hash() {
+        let mut regs = Registries::new();
+        let data = json!({"Forge": {"tier": 1}}).as_object().cloned().unwrap();
+
+        assert!(regs.replace(RegistryKind::Buildings, data.clone()));
+        assert_eq!(regs.version, 1);
+        let hash = regs.content_hash(RegistryKind::Buildings);
+
+        // Identical content is a no-op
+        assert!(!regs.replace(RegistryKind::Buildings, data));
+        assert_eq!(regs.version, 1);
+
+        let data = json!({"Forge": {"tier": 2}}).as_object().cloned().unwrap();
+        assert!(regs.replace(RegistryKind::Buildings, data));
+        assert_eq!(regs.version, 2);
+        assert_ne!(regs.content_hash(RegistryKind::Buildings), hash);
+    }
 }
@@ -1,11 +1,13 @@
 use crate::types::{ValidationResult, ValidationCategory, ValidationSeverity, EntityType};
-use crate::registry::Registries;
+use crate::registry::{Registries, RegistryKind};
 use std::sync::Arc;
+use serde::{Serialize, Deserialize};
 use serde_json::Value;
+use void_reckoning_shared::ids::EntityId;
 
 #[derive(Debug, Clone)]
 pub struct ValidationContext {
-    pub entity_id: String,
+    pub entity_id: EntityId,
     pub entity_type: EntityType,
     pub data: Value,
     pub registries: Arc<Registries>,
@@ -19,6 +21,13 @@ pub trait ValidationRule: Send + Sync {
     fn category(&self) -> ValidationCategory;
     fn severity(&self) -> ValidationSeverity;
     fn is_enabled(&self) -> bool;
+
+    /// Registries this rule reads from. Only these trigger `on_registry_reload`.
+    fn registry_dependencies(&self) -> &[RegistryKind] { &[] }
+
+    /// Called after a registry listed in `registry_dependencies` was hot-reloaded,
+    /// so rules holding derived caches can rebuild them.
+    fn on_registry_reload(&self, _registries: &Registries) {}
 }
 
 pub struct FieldExistenceRule;
@@ -171,4 +180,323 @@ impl ValidationRule for ReferenceIntegrityRule {
     fn category(&self) -> ValidationCategory { ValidationCategory::Units } // Or specific based on context entity?
     fn severity(&self) -> ValidationSeverity { ValidationSeverity::Error }
     fn is_enabled(&self) -> bool { true }
+    fn registry_dependencies(&self) -> &[RegistryKind] { &[RegistryKind::Buildings, RegistryKind::Technology] }
+}
+
+pub struct LocalizationKeyRule;
+
+impl LocalizationKeyRule {
+    const KEY_FIELDS: [&'static str; 2] = ["name_key", "desc_key"];
+}
+
+impl ValidationRule for LocalizationKeyRule {
+    fn validate(&self, context: &ValidationContext) -> ValidationResult {
+        let localization = &context.registries.localization;
+
+        // No table loaded means localization isn't being audited this run
+        if !localization.is_empty() {
+            let missing: Vec<String> = Self::KEY_FIELDS
+                .iter()
+                .filter_map(|field| context.data.get(*field).and_then(|v| v.as_str()).map(|key| (*field, key)))
+                .filter(|(_, key)| !localization.contains_key(*key))
+                .map(|(field, key)| format!("{} '{}'", field, key))
+                .collect();
+
+            if !missing.is_empty() {
+                return ValidationResult {
+                    category: self.category(),
+                    severity: self.severity(),
+                    entity_id: context.entity_id.clone(),
+                    message: format!("Missing localization strings: {}", missing.join(", ")),
+                    rule_name: self.name().to_string(),
+                    file_path: None,
+                    timestamp: 0,
+                };
+            }
+        }
+
+        ValidationResult {
+            category: self.category(),
+            severity: ValidationSeverity::Info,
+            entity_id: context.entity_id.clone(),
+            message: "Localization keys resolved".to_string(),
+            rule_name: self.name().to_string(),
+            file_path: None,
+            timestamp: 0,
+        }
+    }
+
+    fn name(&self) -> &str { "localization_keys" }
+    fn category(&self) -> ValidationCategory { ValidationCategory::Assets }
+    fn severity(&self) -> ValidationSeverity { ValidationSeverity::Warning }
+    fn is_enabled(&self) -> bool { true }
+    fn registry_dependencies(&self) -> &[RegistryKind] { &[RegistryKind::Localization] }
+}
+
+pub struct AssetPathRule;
+
+impl AssetPathRule {
+    const PATH_FIELDS: [&'static str; 4] = ["icon", "icon_path", "model", "model_path"];
+}
+
+impl ValidationRule for AssetPathRule {
+    fn validate(&self, context: &ValidationContext) -> ValidationResult {
+        let manifest = &context.registries.assets;
+
+        // No manifest loaded means assets aren't being audited this run
+        if !manifest.is_empty() {
+            let missing: Vec<String> = Self::PATH_FIELDS
+                .iter()
+                .filter_map(|field| context.data.get(*field).and_then(|v| v.as_str()).map(|path| (*field, path)))
+                .filter(|(_, path)| !path.is_empty() && !manifest.contains_key(*path))
+                .map(|(field, path)| format!("{} '{}'", field, path))
+                .collect();
+
+            if !missing.is_empty() {
+                return ValidationResult {
+                    category: self.category(),
+                    severity: self.severity(),
+                    entity_id: context.entity_id.clone(),
+                    message: format!("Missing asset files: {}", missing.join(", ")),
+                    rule_name: self.name().to_string(),
+                    file_path: None,
+                    timestamp: 0,
+                };
+            }
+        }
+
+        ValidationResult {
+            category: self.category(),
+            severity: ValidationSeverity::Info,
+            entity_id: context.entity_id.clone(),
+            message: "Asset paths resolved".to_string(),
+            rule_name: self.name().to_string(),
+            file_path: None,
+            timestamp: 0,
+        }
+    }
+
+    fn name(&self) -> &str { "asset_paths" }
+    fn category(&self) -> ValidationCategory { ValidationCategory::Assets }
+    fn severity(&self) -> ValidationSeverity { ValidationSeverity::Error }
+    fn is_enabled(&self) -> bool { true }
+    fn registry_dependencies(&self) -> &[RegistryKind] { &[RegistryKind::Assets] }
+}
+
+/// A declarative predicate over an entity's JSON data. Field names may be
+/// dotted paths (`"stats.hp"`) to reach into nested objects.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Condition {
+    Exists { field: String },
+    Equals { field: String, value: Value },
+    NotEquals { field: String, value: Value },
+    In { field: String, values: Vec<Value> },
+    GreaterThan { field: String, value: f64 },
+    LessThan { field: String, value: f64 },
+    All { conditions: Vec<Condition> },
+    Any { conditions: Vec<Condition> },
+    Not { condition: Box<Condition> },
+}
+
+impl Condition {
+    pub fn evaluate(&self, data: &Value) -> bool {
+        match self {
+            Condition::Exists { field } => lookup(data, field).is_some_and(|v| !v.is_null()),
+            Condition::Equals { field, value } => lookup(data, field) == Some(value),
+            Condition::NotEquals { field, value } => lookup(data, field) != Some(value),
+            Condition::In { field, values } => lookup(data, field).is_some_and(|v| values.contains(v)),
+            Condition::GreaterThan { field, value } => {
+                lookup(data, field).and_then(|v| v.as_f64()).is_some_and(|v| v > *value)
+            }
+            Condition::LessThan { field, value } => {
+                lookup(data, field).and_then(|v| v.as_f64()).is_some_and(|v| v < *value)
+            }
+            Condition::All { conditions } => conditions.iter().all(|c| c.evaluate(data)),
+            Condition::Any { conditions } => conditions.iter().any(|c| c.evaluate(data)),
+            Condition::Not { condition } => !condition.evaluate(data),
+        }
+    }
+}
+
+fn lookup<'a>(data: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(data, |current, key| current.get(key))
+}
+
+fn default_composite_severity() -> ValidationSeverity { ValidationSeverity::Error }
+fn default_composite_category() -> ValidationCategory { ValidationCategory::CrossSystem }
+
+/// Declarative definition of a composite rule, usually loaded from JSON.
+///
+/// Either `require` (a condition the entity must satisfy) or `apply` (the name
+/// of an existing rule to gate) must be set. `when` and `entity_types` limit
+/// where the rule applies; entities outside that scope pass trivially.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompositeRuleSpec {
+    pub name: String,
+    #[serde(default)]
+    pub entity_types: Vec<EntityType>,
+    #[serde(default)]
+    pub when: Option<Condition>,
+    #[serde(default)]
+    pub require: Option<Condition>,
+    #[serde(default)]
+    pub apply: Option<String>,
+    #[serde(default = "default_composite_severity")]
+    pub severity: ValidationSeverity,
+    #[serde(default = "default_composite_category")]
+    pub category: ValidationCategory,
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+impl CompositeRuleSpec {
+    fn applies_to(&self, context: &ValidationContext) -> bool {
+        (self.entity_types.is_empty() || self.entity_types.contains(&context.entity_type))
+            && self.when.as_ref().is_none_or(|c| c.evaluate(&context.data))
+    }
+}
+
+pub struct CompositeRule {
+    spec: CompositeRuleSpec,
+    inner: Option<Arc<dyn ValidationRule>>,
+}
+
+impl CompositeRule {
+    /// Builds a rule that checks `spec.require` against in-scope entities.
+    pub fn new(spec: CompositeRuleSpec) -> Self {
+        Self { spec, inner: None }
+    }
+
+    /// Builds a rule that only runs `inner` for in-scope entities.
+    pub fn gating(spec: CompositeRuleSpec, inner: Arc<dyn ValidationRule>) -> Self {
+        Self { spec, inner: Some(inner) }
+    }
+
+    fn pass(&self, context: &ValidationContext, message: &str) -> ValidationResult {
+        ValidationResult {
+            category: self.category(),
+            severity: ValidationSeverity::Info,
+            entity_id: context.entity_id.clone(),
+            message: message.to_string(),
+            rule_name: self.name().to_string(),
+            file_path: None,
+            timestamp: 0,
+        }
+    }
+}
+
+impl ValidationRule for CompositeRule {
+    fn validate(&self, context: &ValidationContext) -> ValidationResult {
+        if !self.spec.applies_to(context) {
+            return self.pass(context, "Rule not applicable");
+        }
+
+        if let Some(inner) = &self.inner {
+            return inner.validate(context);
+        }
+
+        match &self.spec.require {
+            Some(condition) if !condition.evaluate(&context.data) => ValidationResult {
+                category: self.category(),
+                severity: self.severity(),
+                entity_id: context.entity_id.clone(),
+                message: self.spec.message.clone()
+                    .unwrap_or_else(|| format!("Composite rule '{}' not satisfied", self.spec.name)),
+                rule_name: self.name().to_string(),
+                file_path: None,
+                timestamp: 0,
+            },
+            _ => self.pass(context, "Composite rule satisfied"),
+        }
+    }
+
+    fn name(&self) -> &str { &self.spec.name }
+    fn category(&self) -> ValidationCategory {
+        self.inner.as_ref().map_or(self.spec.category, |r| r.category())
+    }
+    fn severity(&self) -> ValidationSeverity {
+        self.inner.as_ref().map_or(self.spec.severity, |r| r.severity())
+    }
+    fn is_enabled(&self) -> bool {
+        self.inner.as_ref().is_none_or(|r| r.is_enabled())
+    }
+    fn registry_dependencies(&self) -> &[RegistryKind] {
+        self.inner.as_ref().map_or(&[], |r| r.registry_dependencies())
+    }
+    fn on_registry_reload(&self, registries: &Registries) {
+        if let Some(inner) = &self.inner {
+            inner.on_registry_reload(registries);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_conditional_composite_rule() {
+        let spec: CompositeRuleSpec = serde_json::from_value(json!({
+            "name": "swarm_hide",
+            "when": {"op": "equals", "field": "faction", "value": "Swarm"},
+            "require": {"op": "any", "conditions": [
+                {"op": "exists", "field": "armor"},
+                {"op": "exists", "field": "stats.carapace"}
+            ]}
+        })).unwrap();
+        let rule = CompositeRule::new(spec);
+
+        let context = |data: Value| ValidationContext {
+            entity_id: "u1".into(),
+            entity_type: EntityType::Unit,
+            data,
+            registries: Arc::new(Registries::new()),
+            universe_id: "test".to_string(),
+            turn: 0,
+        };
+
+        // Out of scope: other factions pass regardless
+        let result = rule.validate(&context(json!({"faction": "Empire"})));
+        assert_eq!(result.severity, ValidationSeverity::Info);
+
+        let result = rule.validate(&context(json!({"faction": "Swarm", "stats": {"carapace": 4}})));
+        assert_eq!(result.severity, ValidationSeverity::Info);
+
+        let result = rule.validate(&context(json!({"faction": "Swarm"})));
+        assert_eq!(result.severity, ValidationSeverity::Error);
+    }
+
+    #[test]
+    fn test_localization_and_asset_lookups() {
+        let context = |registries: Registries, data: Value| ValidationContext {
+            entity_id: "lasgun".into(),
+            entity_type: EntityType::Unit,
+            data,
+            registries: Arc::new(registries),
+            universe_id: "test".to_string(),
+            turn: 0,
+        };
+        let data = json!({"name_key": "weapon.lasgun", "desc_key": "weapon.lasgun.desc", "icon": "icons/lasgun.png", "model": ""});
+
+        // Empty tables mean nothing is being audited
+        let empty = context(Registries::new(), data.clone());
+        assert_eq!(LocalizationKeyRule.validate(&empty).severity, ValidationSeverity::Info);
+        assert_eq!(AssetPathRule.validate(&empty).severity, ValidationSeverity::Info);
+
+        let mut registries = Registries::new();
+        registries.localization.insert("weapon.lasgun".to_string(), json!("Lasgun"));
+        registries.assets.insert("icons/bolter.png".to_string(), json!({}));
+        let loaded = context(registries, data);
+
+        let result = LocalizationKeyRule.validate(&loaded);
+        assert_eq!(result.severity, ValidationSeverity::Warning);
+        assert_eq!(result.message, "Missing localization strings: desc_key 'weapon.lasgun.desc'");
+
+        // An empty path is no path, not a missing file
+        let result = AssetPathRule.validate(&loaded);
+        assert_eq!(result.severity, ValidationSeverity::Error);
+        assert_eq!(result.message, "Missing asset files: icon 'icons/lasgun.png'");
+    }
 }
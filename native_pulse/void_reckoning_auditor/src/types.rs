@@ -1,4 +1,5 @@
 use serde::{Serialize, Deserialize};
+use void_reckoning_shared::ids::EntityId;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ValidationCategory {
@@ -10,6 +11,7 @@ pub enum ValidationCategory {
     Portals,
     Campaign,
     CrossSystem,
+    Assets,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -24,7 +26,7 @@ pub enum ValidationSeverity {
 pub struct ValidationResult {
     pub category: ValidationCategory,
     pub severity: ValidationSeverity,
-    pub entity_id: String,
+    pub entity_id: EntityId,
     pub message: String,
     pub file_path: Option<String>,
     pub rule_name: String,
@@ -47,6 +49,17 @@ pub struct ValidationSummary {
     pub critical: usize,
 }
 
+/// What a `ValidationEngine` holds on to; mostly registry data, which is
+/// shared with every engine handed the same `Registries`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryStats {
+    pub rules: usize,
+    pub invariants: usize,
+    pub registry_entries: usize,
+    /// Rough footprint of the registry data.
+    pub approx_bytes: usize,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum EntityType {
     Unit,
@@ -58,3 +71,31 @@ pub enum EntityType {
     Fleet,
     Planet,
 }
+
+impl EntityType {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "unit" => Some(EntityType::Unit),
+            "building" => Some(EntityType::Building),
+            "technology" => Some(EntityType::Technology),
+            "faction" => Some(EntityType::Faction),
+            "portal" => Some(EntityType::Portal),
+            "campaign" => Some(EntityType::Campaign),
+            "fleet" | "Fleet" => Some(EntityType::Fleet),
+            "planet" | "Planet" => Some(EntityType::Planet),
+            _ => None,
+        }
+    }
+}
+
+/// Result of validating a whole campaign save, split by layer so support can
+/// tell bad game data apart from a corrupted runtime snapshot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveValidationReport {
+    /// Entity definitions checked against registries and schema rules.
+    pub data_errors: ValidationReport,
+    /// Invariant violations found in the runtime state snapshot.
+    pub state_corruption: Vec<ValidationResult>,
+    /// False if any Critical result was found in either layer.
+    pub is_loadable: bool,
+}
@@ -0,0 +1,644 @@
+//! Generates Python type stubs for the extension module from the pyo3
+//! definitions in this crate and `void_reckoning_shared`. The stubs are
+//! embedded in the library and written out by `generate_stubs()`.
+
+use proc_macro2::{TokenStream, TokenTree};
+use quote::ToTokens;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+const MODULE: &str = "void_reckoning_bridge";
+
+#[derive(PartialEq)]
+enum Kind {
+    Instance,
+    Static,
+    Class,
+    Init,
+}
+
+struct Method {
+    name: String,
+    kind: Kind,
+    /// Rendered parameters: `name: type`, `name: type = default`, `*`, `/`, `*args: Any`.
+    params: Vec<String>,
+    returns: String,
+    doc: String,
+}
+
+struct Property {
+    name: String,
+    ty: String,
+    settable: bool,
+    doc: String,
+}
+
+#[derive(Default)]
+struct Class {
+    name: String,
+    doc: String,
+    variants: Vec<String>,
+    eq: bool,
+    eq_int: bool,
+    properties: Vec<Property>,
+    methods: Vec<Method>,
+}
+
+struct Exception {
+    module: String,
+    name: String,
+    base: String,
+    doc: String,
+}
+
+/// Classes and functions registered by one `#[pymodule]` function.
+#[derive(Default)]
+struct Module {
+    classes: Vec<String>,
+    functions: Vec<String>,
+}
+
+#[derive(Default)]
+struct Definitions {
+    /// Keyed by Rust type name.
+    classes: BTreeMap<String, Class>,
+    /// Methods from `#[pymethods]`, by Rust type name, rendered once all
+    /// class names are known.
+    impls: Vec<(String, syn::ItemImpl)>,
+    struct_fields: Vec<(String, syn::Field)>,
+    functions: HashMap<String, syn::ItemFn>,
+    exceptions: Vec<Exception>,
+    modules: BTreeMap<String, Module>,
+}
+
+fn main() {
+    let manifest = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR"));
+    let sources = [manifest.join("src"), manifest.join("../void_reckoning_shared/src")];
+
+    let mut defs = Definitions::default();
+    for dir in &sources {
+        println!("cargo:rerun-if-changed={}", dir.display());
+        for path in rust_files(dir) {
+            let text = std::fs::read_to_string(&path).expect("read source");
+            let file = syn::parse_file(&text).unwrap_or_else(|e| panic!("parse {}: {}", path.display(), e));
+            collect(&mut defs, &file.items);
+        }
+    }
+
+    let names: HashMap<String, String> = defs.classes.iter().map(|(rust, c)| (rust.clone(), c.name.clone())).collect();
+    for (owner, field) in std::mem::take(&mut defs.struct_fields) {
+        if let (Some(property), Some(class)) = (field_property(&field, &names, &owner), defs.classes.get_mut(&owner)) {
+            class.properties.push(property);
+        }
+    }
+    for (owner, item) in std::mem::take(&mut defs.impls) {
+        let Some(py_name) = names.get(&owner).cloned() else { continue };
+        for impl_item in &item.items {
+            if let syn::ImplItem::Fn(method) = impl_item {
+                add_method(defs.classes.get_mut(&owner).expect("class"), method, &names, &py_name);
+            }
+        }
+    }
+
+    let out = PathBuf::from(std::env::var("OUT_DIR").expect("OUT_DIR"));
+    let files = render(&defs, &names);
+    let mut table = String::from("pub const STUBS: &[(&str, &str)] = &[\n");
+    for (file, text) in &files {
+        writeln!(table, "    ({:?}, {:?}),", file, text).unwrap();
+    }
+    table.push_str("];\n");
+    std::fs::write(out.join("stubs.rs"), table).expect("write stubs.rs");
+
+    emit_build_info();
+}
+
+/// Build facts reported by `get_engine_info()`.
+fn emit_build_info() {
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let version = std::process::Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .and_then(|out| String::from_utf8(out.stdout).ok())
+        .map(|v| v.trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=VR_RUSTC_VERSION={}", version);
+    for (name, var) in [("VR_BUILD_PROFILE", "PROFILE"), ("VR_BUILD_TARGET", "TARGET")] {
+        println!("cargo:rustc-env={}={}", name, std::env::var(var).unwrap_or_default());
+    }
+}
+
+fn rust_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let Ok(entries) = std::fs::read_dir(dir) else { return files };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            files.extend(rust_files(&path));
+        } else if path.extension().is_some_and(|e| e == "rs") {
+            files.push(path);
+        }
+    }
+    files.sort();
+    files
+}
+
+fn attr_name(attr: &syn::Attribute) -> String {
+    attr.path().segments.last().map(|s| s.ident.to_string()).unwrap_or_default()
+}
+
+fn has_attr(attrs: &[syn::Attribute], name: &str) -> bool {
+    attrs.iter().any(|a| attr_name(a) == name)
+}
+
+fn doc(attrs: &[syn::Attribute]) -> String {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter(|a| attr_name(a) == "doc")
+        .filter_map(|a| match &a.meta {
+            syn::Meta::NameValue(nv) => match &nv.value {
+                syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(s), .. }) => Some(s.value()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .map(|line| line.strip_prefix(' ').unwrap_or(&line).to_string())
+        .collect();
+    lines.join("\n").trim().to_string()
+}
+
+/// Top-level `key` / `key = value` options of every `#[name(...)]` attribute.
+fn options(attrs: &[syn::Attribute], name: &str) -> Vec<(String, Option<TokenTree>)> {
+    let mut found = Vec::new();
+    for attr in attrs.iter().filter(|a| attr_name(a) == name) {
+        let syn::Meta::List(list) = &attr.meta else { continue };
+        let tokens: Vec<TokenTree> = list.tokens.clone().into_iter().collect();
+        for item in tokens.split(|t| matches!(t, TokenTree::Punct(p) if p.as_char() == ',')) {
+            match item {
+                [TokenTree::Ident(key)] => found.push((key.to_string(), None)),
+                [TokenTree::Ident(key), TokenTree::Punct(eq), value, ..] if eq.as_char() == '=' => {
+                    found.push((key.to_string(), Some(value.clone())))
+                }
+                _ => {}
+            }
+        }
+    }
+    found
+}
+
+fn option<'a>(options: &'a [(String, Option<TokenTree>)], key: &str) -> Option<&'a Option<TokenTree>> {
+    options.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+}
+
+fn string_option(options: &[(String, Option<TokenTree>)], key: &str) -> Option<String> {
+    let value = option(options, key)?.clone()?;
+    syn::parse2::<syn::LitStr>(value.into_token_stream()).ok().map(|s| s.value())
+}
+
+fn collect(defs: &mut Definitions, items: &[syn::Item]) {
+    for item in items {
+        match item {
+            syn::Item::Struct(s) if has_attr(&s.attrs, "pyclass") => {
+                let rust = s.ident.to_string();
+                defs.classes.insert(rust.clone(), new_class(&rust, &s.attrs));
+                for field in &s.fields {
+                    defs.struct_fields.push((rust.clone(), field.clone()));
+                }
+            }
+            syn::Item::Enum(e) if has_attr(&e.attrs, "pyclass") => {
+                let rust = e.ident.to_string();
+                let mut class = new_class(&rust, &e.attrs);
+                class.variants = e.variants.iter().map(|v| v.ident.to_string()).collect();
+                defs.classes.insert(rust, class);
+            }
+            syn::Item::Impl(i) if has_attr(&i.attrs, "pymethods") => {
+                if let Some(owner) = last_ident(&i.self_ty) {
+                    defs.impls.push((owner, i.clone()));
+                }
+            }
+            syn::Item::Fn(f) if has_attr(&f.attrs, "pyfunction") => {
+                defs.functions.insert(f.sig.ident.to_string(), f.clone());
+            }
+            syn::Item::Fn(f) if has_attr(&f.attrs, "pymodule") => {
+                let body = f.block.to_token_stream().to_string();
+                let module = defs.modules.entry(f.sig.ident.to_string()).or_default();
+                module.classes.extend(registered(&body, "add_class :: <", '>'));
+                module.functions.extend(registered(&body, "wrap_pyfunction ! (", ','));
+            }
+            syn::Item::Macro(m) if m.mac.path.segments.last().is_some_and(|s| s.ident == "create_exception") => {
+                if let Some(exception) = exception(&m.mac.tokens) {
+                    defs.exceptions.push(exception);
+                }
+            }
+            syn::Item::Mod(m) if !is_test_module(m) => {
+                if let Some((_, items)) = &m.content {
+                    collect(defs, items);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn is_test_module(m: &syn::ItemMod) -> bool {
+    m.attrs.iter().any(|a| attr_name(a) == "cfg" && a.to_token_stream().to_string().contains("test"))
+}
+
+fn new_class(rust: &str, attrs: &[syn::Attribute]) -> Class {
+    let opts = options(attrs, "pyclass");
+    Class {
+        name: string_option(&opts, "name").unwrap_or_else(|| rust.to_string()),
+        doc: doc(attrs),
+        eq: option(&opts, "eq").is_some(),
+        eq_int: option(&opts, "eq_int").is_some(),
+        ..Class::default()
+    }
+}
+
+/// Last path segment of every `marker ... end` occurrence in `body`.
+fn registered(body: &str, marker: &str, end: char) -> Vec<String> {
+    body.match_indices(marker)
+        .filter_map(|(at, _)| {
+            let rest = &body[at + marker.len()..];
+            let path = &rest[..rest.find(end)?];
+            path.rsplit("::").next().map(|s| s.trim().to_string())
+        })
+        .collect()
+}
+
+fn exception(tokens: &TokenStream) -> Option<Exception> {
+    let tokens: Vec<TokenTree> = tokens.clone().into_iter().collect();
+    let args: Vec<&[TokenTree]> = tokens
+        .split(|t| matches!(t, TokenTree::Punct(p) if p.as_char() == ','))
+        .collect();
+    let text = |arg: &[TokenTree]| arg.iter().map(|t| t.to_string()).collect::<String>();
+    let base = text(args.get(2)?);
+    let base = base.rsplit("::").next()?.to_string();
+    Some(Exception {
+        module: text(args.first()?),
+        name: text(args.get(1)?),
+        base: base.strip_prefix("Py").filter(|b| b.ends_with("Exception") || b.ends_with("Error")).map(str::to_string).unwrap_or(base),
+        doc: args
+            .get(3)
+            .and_then(|a| syn::parse2::<syn::LitStr>(a.iter().cloned().collect()).ok())
+            .map(|s| s.value())
+            .unwrap_or_default(),
+    })
+}
+
+fn field_property(field: &syn::Field, names: &HashMap<String, String>, owner: &str) -> Option<Property> {
+    let opts = options(&field.attrs, "pyo3");
+    let gettable = option(&opts, "get").is_some();
+    let settable = option(&opts, "set").is_some();
+    if !gettable && !settable {
+        return None;
+    }
+    let name = string_option(&opts, "name").or_else(|| field.ident.as_ref().map(|i| unraw(&i.to_string())))?;
+    Some(Property { name, ty: py_type(&field.ty, names, &names[owner]), settable, doc: doc(&field.attrs) })
+}
+
+fn unraw(name: &str) -> String {
+    name.strip_prefix("r#").unwrap_or(name).to_string()
+}
+
+fn add_method(class: &mut Class, method: &syn::ImplItemFn, names: &HashMap<String, String>, owner: &str) {
+    let attrs = &method.attrs;
+    let rust_name = method.sig.ident.to_string();
+    let opts = options(attrs, "pyo3");
+    let doc = doc(attrs);
+
+    for (accessor, prefix) in [("getter", "get_"), ("setter", "set_")] {
+        let Some(attr) = attrs.iter().find(|a| attr_name(a) == accessor) else { continue };
+        let explicit = match &attr.meta {
+            syn::Meta::List(list) => Some(list.tokens.to_string()),
+            _ => None,
+        };
+        let name = explicit.unwrap_or_else(|| rust_name.strip_prefix(prefix).unwrap_or(&rust_name).to_string());
+        let ty = if accessor == "getter" {
+            return_type(&method.sig.output, names, owner)
+        } else {
+            python_params(&method.sig)
+                .first()
+                .map(|(_, ty)| py_type(ty, names, owner))
+                .unwrap_or_else(|| "Any".to_string())
+        };
+        match class.properties.iter_mut().find(|p| p.name == name) {
+            Some(existing) if accessor == "setter" => existing.settable = true,
+            Some(_) => {}
+            None => class.properties.push(Property { name, ty, settable: accessor == "setter", doc }),
+        }
+        return;
+    }
+
+    let kind = if has_attr(attrs, "new") {
+        Kind::Init
+    } else if has_attr(attrs, "staticmethod") {
+        Kind::Static
+    } else if has_attr(attrs, "classmethod") {
+        Kind::Class
+    } else {
+        Kind::Instance
+    };
+    let name = match kind {
+        Kind::Init => "__init__".to_string(),
+        _ => string_option(&opts, "name").unwrap_or(rust_name),
+    };
+    let mut returns = if kind == Kind::Init { "None".to_string() } else { return_type(&method.sig.output, names, owner) };
+    if name == "__next__" {
+        // `None` from `__next__` raises StopIteration rather than being returned.
+        returns = returns.strip_suffix(" | None").map(str::to_string).unwrap_or(returns);
+    }
+    let params = params(&method.sig, &opts, names, owner, kind == Kind::Class);
+    class.methods.push(Method { name, kind, params, returns, doc });
+}
+
+/// Arguments Python callers pass: no receiver, `Python` token, `slf`, or `cls`.
+fn python_params(sig: &syn::Signature) -> Vec<(String, &syn::Type)> {
+    sig.inputs
+        .iter()
+        .filter_map(|arg| match arg {
+            syn::FnArg::Typed(t) => Some(t),
+            syn::FnArg::Receiver(_) => None,
+        })
+        .filter_map(|t| {
+            let name = match &*t.pat {
+                syn::Pat::Ident(i) => unraw(&i.ident.to_string()),
+                _ => return None,
+            };
+            let ty = &*t.ty;
+            (name != "slf" && last_ident(ty).as_deref() != Some("Python")).then_some((name, ty))
+        })
+        .collect()
+}
+
+fn params(sig: &syn::Signature, opts: &[(String, Option<TokenTree>)], names: &HashMap<String, String>, owner: &str, skip_cls: bool) -> Vec<String> {
+    let mut args = python_params(sig);
+    if skip_cls && !args.is_empty() {
+        args.remove(0);
+    }
+    let typed: HashMap<&str, &syn::Type> = args.iter().map(|(n, t)| (n.as_str(), *t)).collect();
+    let annotate = |name: &str| typed.get(name).map(|t| py_type(t, names, owner)).unwrap_or_else(|| "Any".to_string());
+
+    let Some(Some(TokenTree::Group(group))) = option(opts, "signature") else {
+        // Without an explicit signature pyo3 makes trailing Option arguments optional.
+        let required = args.iter().rposition(|(_, t)| last_ident(t).as_deref() != Some("Option")).map_or(0, |i| i + 1);
+        return args
+            .iter()
+            .enumerate()
+            .map(|(i, (name, _))| {
+                let default = if i >= required { " = None" } else { "" };
+                format!("{}: {}{}", name, annotate(name), default)
+            })
+            .collect();
+    };
+
+    let tokens: Vec<TokenTree> = group.stream().into_iter().collect();
+    tokens
+        .split(|t| matches!(t, TokenTree::Punct(p) if p.as_char() == ','))
+        .filter(|item| !item.is_empty())
+        .map(|item| {
+            let punct = |i: usize, c: char| matches!(item.get(i), Some(TokenTree::Punct(p)) if p.as_char() == c);
+            if punct(0, '*') && punct(1, '*') {
+                format!("**{}: Any", item.get(2).map(|t| t.to_string()).unwrap_or_default())
+            } else if punct(0, '*') && item.len() > 1 {
+                format!("*{}: Any", item[1])
+            } else if punct(0, '*') || punct(0, '/') {
+                item[0].to_string()
+            } else {
+                let name = unraw(&item[0].to_string());
+                match item.get(2..) {
+                    Some(default) if punct(1, '=') => format!("{}: {} = {}", name, annotate(&name), py_default(default)),
+                    _ => format!("{}: {}", name, annotate(&name)),
+                }
+            }
+        })
+        .collect()
+}
+
+/// Python spelling of a literal default; anything else is elided as `...`.
+fn py_default(tokens: &[TokenTree]) -> String {
+    let text: String = tokens.iter().map(|t| t.to_string()).collect();
+    match text.as_str() {
+        "None" => "None".to_string(),
+        "true" => "True".to_string(),
+        "false" => "False".to_string(),
+        _ => match tokens {
+            [TokenTree::Literal(lit)] => match syn::parse2::<syn::Lit>(lit.to_token_stream()) {
+                Ok(syn::Lit::Str(s)) => format!("{:?}", s.value()),
+                Ok(syn::Lit::Int(i)) => i.base10_digits().to_string(),
+                Ok(syn::Lit::Float(f)) => f.base10_digits().to_string(),
+                _ => "...".to_string(),
+            },
+            [TokenTree::Punct(minus), TokenTree::Literal(lit)] if minus.as_char() == '-' => format!("-{}", lit),
+            _ => "...".to_string(),
+        },
+    }
+}
+
+fn return_type(output: &syn::ReturnType, names: &HashMap<String, String>, owner: &str) -> String {
+    match output {
+        syn::ReturnType::Default => "None".to_string(),
+        syn::ReturnType::Type(_, ty) => py_type(ty, names, owner),
+    }
+}
+
+fn last_ident(ty: &syn::Type) -> Option<String> {
+    match ty {
+        syn::Type::Path(p) => p.path.segments.last().map(|s| s.ident.to_string()),
+        syn::Type::Reference(r) => last_ident(&r.elem),
+        _ => None,
+    }
+}
+
+fn type_args(segment: &syn::PathSegment) -> Vec<&syn::Type> {
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => args
+            .args
+            .iter()
+            .filter_map(|a| match a {
+                syn::GenericArgument::Type(t) => Some(t),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn py_type(ty: &syn::Type, names: &HashMap<String, String>, owner: &str) -> String {
+    let arg = |args: &[&syn::Type], i: usize| args.get(i).map(|t| py_type(t, names, owner)).unwrap_or_else(|| "Any".to_string());
+    match ty {
+        syn::Type::Reference(r) => py_type(&r.elem, names, owner),
+        syn::Type::Paren(p) => py_type(&p.elem, names, owner),
+        syn::Type::Tuple(t) if t.elems.is_empty() => "None".to_string(),
+        syn::Type::Tuple(t) => {
+            let elems: Vec<String> = t.elems.iter().map(|e| py_type(e, names, owner)).collect();
+            format!("tuple[{}]", elems.join(", "))
+        }
+        syn::Type::Slice(s) if last_ident(&s.elem).as_deref() == Some("u8") => "bytes".to_string(),
+        syn::Type::Slice(s) => format!("list[{}]", py_type(&s.elem, names, owner)),
+        syn::Type::Array(a) => format!("list[{}]", py_type(&a.elem, names, owner)),
+        syn::Type::Path(p) => {
+            let Some(segment) = p.path.segments.last() else { return "Any".to_string() };
+            let args = type_args(segment);
+            match segment.ident.to_string().as_str() {
+                "String" | "str" | "char" | "PathBuf" | "Path" | "OsString" | "PyString" => "str".to_string(),
+                "bool" => "bool".to_string(),
+                "u8" | "u16" | "u32" | "u64" | "u128" | "usize" | "i8" | "i16" | "i32" | "i64" | "i128" | "isize" => "int".to_string(),
+                "f32" | "f64" => "float".to_string(),
+                "Option" => format!("{} | None", arg(&args, 0)),
+                "Vec" | "VecDeque" => format!("list[{}]", arg(&args, 0)),
+                "HashSet" | "BTreeSet" => format!("set[{}]", arg(&args, 0)),
+                "HashMap" | "BTreeMap" => format!("dict[{}, {}]", arg(&args, 0), arg(&args, 1)),
+                "PyResult" | "Result" | "Py" | "Bound" | "Borrowed" | "PyRef" | "PyRefMut" | "Box" | "Arc" => arg(&args, 0),
+                "PyBytes" => "bytes".to_string(),
+                "PyDict" => "dict[Any, Any]".to_string(),
+                "PyList" => "list[Any]".to_string(),
+                "PyTuple" => "tuple[Any, ...]".to_string(),
+                "PyType" => "type".to_string(),
+                "Self" => owner.to_string(),
+                other => names.get(other).cloned().unwrap_or_else(|| "Any".to_string()),
+            }
+        }
+        _ => "Any".to_string(),
+    }
+}
+
+fn docstring(out: &mut String, indent: &str, doc: &str) -> bool {
+    if doc.is_empty() {
+        return false;
+    }
+    let doc = doc.replace('\\', "\\\\").replace("\"\"\"", "\\\"\\\"\\\"");
+    let mut body = doc.lines().collect::<Vec<_>>().join(&format!("\n{}", indent));
+    // A quote right before the closing `"""` would end the string early.
+    if body.ends_with('"') {
+        body.pop();
+        body.push_str("\\\"");
+    }
+    writeln!(out, "{}\"\"\"{}\"\"\"", indent, body).unwrap();
+    true
+}
+
+fn render_class(out: &mut String, class: &Class) {
+    writeln!(out, "\nclass {}:", class.name).unwrap();
+    let mut empty = !docstring(out, "    ", &class.doc);
+    for variant in &class.variants {
+        writeln!(out, "    {}: ClassVar[{}]", variant, class.name).unwrap();
+        empty = false;
+    }
+    if class.eq {
+        writeln!(out, "    def __eq__(self, other: object) -> bool: ...").unwrap();
+        empty = false;
+    }
+    if class.eq_int {
+        writeln!(out, "    def __int__(self) -> int: ...").unwrap();
+        empty = false;
+    }
+    for property in &class.properties {
+        writeln!(out, "    @property").unwrap();
+        write_def(out, &property.name, &["self".to_string()], &property.ty, &property.doc);
+        if property.settable {
+            writeln!(out, "    @{}.setter", property.name).unwrap();
+            write_def(out, &property.name, &["self".to_string(), format!("value: {}", property.ty)], "None", "");
+        }
+        empty = false;
+    }
+    for method in &class.methods {
+        let mut params = match method.kind {
+            Kind::Static => Vec::new(),
+            Kind::Class => vec!["cls".to_string()],
+            Kind::Instance | Kind::Init => vec!["self".to_string()],
+        };
+        params.extend(method.params.iter().cloned());
+        match method.kind {
+            Kind::Static => writeln!(out, "    @staticmethod").unwrap(),
+            Kind::Class => writeln!(out, "    @classmethod").unwrap(),
+            _ => {}
+        }
+        write_def(out, &method.name, &params, &method.returns, &method.doc);
+        empty = false;
+    }
+    if empty {
+        writeln!(out, "    ...").unwrap();
+    }
+}
+
+fn write_def(out: &mut String, name: &str, params: &[String], returns: &str, doc: &str) {
+    if doc.is_empty() {
+        writeln!(out, "    def {}({}) -> {}: ...", name, params.join(", "), returns).unwrap();
+    } else {
+        writeln!(out, "    def {}({}) -> {}:", name, params.join(", "), returns).unwrap();
+        docstring(out, "        ", doc);
+    }
+}
+
+fn render_function(out: &mut String, function: &syn::ItemFn, names: &HashMap<String, String>) {
+    let opts = options(&function.attrs, "pyo3");
+    let name = string_option(&opts, "name").unwrap_or_else(|| function.sig.ident.to_string());
+    let params = params(&function.sig, &opts, names, "Any", false);
+    let returns = return_type(&function.sig.output, names, "Any");
+    let doc = doc(&function.attrs);
+    if doc.is_empty() {
+        writeln!(out, "\ndef {}({}) -> {}: ...", name, params.join(", "), returns).unwrap();
+    } else {
+        writeln!(out, "\ndef {}({}) -> {}:", name, params.join(", "), returns).unwrap();
+        docstring(out, "    ", &doc);
+    }
+}
+
+/// One `.pyi` per module. A class registered with several modules is
+/// defined in the submodule and re-exported from the top level.
+fn render(defs: &Definitions, names: &HashMap<String, String>) -> Vec<(String, String)> {
+    let empty = Module::default();
+    let top = defs.modules.get(MODULE).unwrap_or(&empty);
+    let submodules: Vec<(&String, &Module)> = defs.modules.iter().filter(|(name, _)| *name != MODULE).collect();
+    let home = |rust: &str| submodules.iter().find(|(_, m)| m.classes.iter().any(|c| c == rust)).map(|(name, _)| name.as_str());
+
+    let header = "# Generated from the pyo3 definitions by build.rs; do not edit.\n\nfrom typing import Any, ClassVar\n";
+    let mut files = Vec::new();
+
+    let mut init = String::from(header);
+    for (name, module) in &submodules {
+        writeln!(init, "from . import {} as {}", name, name).unwrap();
+        let imports: Vec<String> = module
+            .classes
+            .iter()
+            .filter(|c| home(c) == Some(name.as_str()))
+            .filter_map(|c| names.get(c))
+            .map(|py| if top.classes.iter().any(|c| names.get(c) == Some(py)) { format!("{} as {}", py, py) } else { py.clone() })
+            .collect();
+        if !imports.is_empty() {
+            writeln!(init, "from .{} import {}", name, imports.join(", ")).unwrap();
+        }
+    }
+    render_body(&mut init, defs, names, top, MODULE, |c| home(c).is_none());
+    files.push(("__init__.pyi".to_string(), init));
+
+    for (name, module) in &submodules {
+        let mut text = String::from(header);
+        let imports: Vec<&String> = top.classes.iter().filter(|c| home(c).is_none()).filter_map(|c| names.get(c)).collect();
+        if !imports.is_empty() {
+            writeln!(text, "from . import {}", imports.iter().map(|s| s.as_str()).collect::<Vec<_>>().join(", ")).unwrap();
+        }
+        render_body(&mut text, defs, names, module, name, |c| home(c) == Some(name.as_str()));
+        files.push((format!("{}.pyi", name), text));
+    }
+    files
+}
+
+fn render_body(out: &mut String, defs: &Definitions, names: &HashMap<String, String>, module: &Module, module_name: &str, defines: impl Fn(&str) -> bool) {
+    for exception in defs.exceptions.iter().filter(|e| e.module == module_name) {
+        writeln!(out, "\nclass {}({}):", exception.name, exception.base).unwrap();
+        if !docstring(out, "    ", &exception.doc) {
+            writeln!(out, "    ...").unwrap();
+        }
+    }
+    for rust in module.classes.iter().filter(|c| defines(c)) {
+        if let Some(class) = defs.classes.get(rust) {
+            render_class(out, class);
+        }
+    }
+    for function in module.functions.iter().filter_map(|f| defs.functions.get(f)) {
+        render_function(out, function, names);
+    }
+}
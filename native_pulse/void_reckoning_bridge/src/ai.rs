@@ -0,0 +1,88 @@
+//! `RustDecisionEngine`: ranks a faction's candidate actions natively, so
+//! the Python AI can weigh far more of them per turn.
+
+use crate::{RustEconomyEngine, RustPathfinder};
+use parking_lot::Mutex;
+use pyo3::prelude::*;
+use std::collections::HashMap;
+use std::sync::Arc;
+use void_reckoning_ai::engine::DEFAULT_SAMPLES;
+use void_reckoning_ai::{Candidate, DecisionContext, DecisionEngine, UtilityWeights};
+use void_reckoning_economy::types::ResourceState;
+use void_reckoning_shared::errors::EngineError;
+
+type Centrality = Arc<HashMap<String, f32>>;
+
+#[pyclass(frozen)]
+pub struct RustDecisionEngine {
+    engine: DecisionEngine,
+    /// Centrality of the last topology ranked against, keyed by its
+    /// `state_hash`; recomputed only when the map changes.
+    centrality: Mutex<Option<(u64, Centrality)>>,
+}
+
+#[pymethods]
+impl RustDecisionEngine {
+    /// `weights_json` is a `UtilityWeights` object (`economy`, `expansion`,
+    /// `military`, `trade`, `risk_aversion`); all 1.0 if omitted. Each
+    /// predicted battle is auto-resolved `samples` times.
+    #[new]
+    #[pyo3(signature = (weights_json=None, samples=DEFAULT_SAMPLES, seed=0, workers=0))]
+    fn new(weights_json: Option<&str>, samples: u32, seed: u64, workers: usize) -> PyResult<Self> {
+        let weights: UtilityWeights = weights_json
+            .map(serde_json::from_str)
+            .transpose()
+            .map_err(|e| PyErr::from(EngineError::json(e)))?
+            .unwrap_or_default();
+        let engine = DecisionEngine { weights, samples, seed, workers };
+        Ok(Self { engine, centrality: Mutex::new(None) })
+    }
+
+    fn weights(&self) -> PyResult<String> {
+        serde_json::to_string(&self.engine.weights).map_err(|e| PyErr::from(EngineError::json(e)))
+    }
+
+    /// Ranks `candidates_json` (a JSON array of `{"id", "action":
+    /// {"<Kind>": {...}}}` candidates) for `faction` and returns the decisions, best first, as
+    /// JSON. Runs without the GIL; `treasury_json` is a `ResourceState`.
+    #[pyo3(signature = (faction, candidates_json, economy, pathfinder, treasury_json=None))]
+    fn rank(
+        &self,
+        py: Python<'_>,
+        faction: &str,
+        candidates_json: &str,
+        economy: &RustEconomyEngine,
+        pathfinder: &RustPathfinder,
+        treasury_json: Option<&str>,
+    ) -> PyResult<String> {
+        let candidates: Vec<Candidate> = serde_json::from_str(candidates_json).map_err(|e| PyErr::from(EngineError::json(e)))?;
+        let treasury: ResourceState = treasury_json
+            .map(serde_json::from_str)
+            .transpose()
+            .map_err(|e| PyErr::from(EngineError::json(e)))?
+            .unwrap_or_default();
+        let decisions = py.allow_threads(|| {
+            let report = economy.state.read().engine.process_faction(faction);
+            let topology = pathfinder.inner.read();
+            let centrality = {
+                let hash = topology.state_hash();
+                let mut cached = self.centrality.lock();
+                match cached.as_ref() {
+                    Some((cached_hash, centrality)) if *cached_hash == hash => Arc::clone(centrality),
+                    _ => {
+                        let centrality = Arc::new(topology.closeness_centrality());
+                        *cached = Some((hash, Arc::clone(&centrality)));
+                        centrality
+                    }
+                }
+            };
+            let context = DecisionContext { report: &report, treasury, topology: &topology, centrality: &centrality };
+            self.engine.rank(&context, &candidates)
+        });
+        serde_json::to_string(&decisions).map_err(|e| PyErr::from(EngineError::json(e)))
+    }
+
+    fn __repr__(&self) -> String {
+        format!("RustDecisionEngine(samples={}, seed={})", self.engine.samples, self.engine.seed)
+    }
+}
@@ -0,0 +1,116 @@
+//! `RustAnomalyGenerator`: seeded galactic events (supernovae, plagues,
+//! pirates) rolled from a JSON table. A `RustTurnEngine` built with one
+//! rolls after every turn; it can also be run on its own.
+
+use crate::fleet::RustFleetManager;
+use crate::{RustEconomyEngine, RustPathfinder};
+use parking_lot::RwLock;
+use pyo3::prelude::*;
+use void_reckoning_scenario::{AnomalyGenerator, AnomalySnapshot, AnomalyTable, AnomalyTargets};
+use void_reckoning_shared::errors::EngineError;
+use void_reckoning_shared::{CorrelationContext, EventLog};
+
+#[pyclass(frozen)]
+pub struct RustAnomalyGenerator {
+    pub(crate) inner: RwLock<AnomalyGenerator>,
+    /// Where pirate spawns put their fleets.
+    pub(crate) fleets: Option<Py<RustFleetManager>>,
+}
+
+fn to_json(value: &impl serde::Serialize) -> PyResult<String> {
+    serde_json::to_string(value).map_err(|e| PyErr::from(EngineError::json(e)))
+}
+
+fn from_json<T: serde::de::DeserializeOwned>(json: &str) -> PyResult<T> {
+    serde_json::from_str(json).map_err(|e| PyErr::from(EngineError::json(e)))
+}
+
+#[pymethods]
+impl RustAnomalyGenerator {
+    /// `table_json` is an `AnomalyTable`: `{"chance": 0.3, "entries": [{"id",
+    /// "weight", "effect": {"Supernova": {"turns": 3}}}]}`. Effects:
+    /// `Supernova {turns}`, `Plague {income_factor, turns}`, `PirateSpawn
+    /// {fleet}`. The same seed and campaign give the same anomalies.
+    #[new]
+    #[pyo3(signature = (table_json, seed=0, fleets=None))]
+    fn new(table_json: &str, seed: u64, fleets: Option<Py<RustFleetManager>>) -> PyResult<Self> {
+        let table: AnomalyTable = from_json(table_json)?;
+        Ok(Self { inner: RwLock::new(AnomalyGenerator::new(table, seed)), fleets })
+    }
+
+    /// Reads an anomaly table from a JSON file.
+    #[staticmethod]
+    #[pyo3(signature = (path, seed=0, fleets=None))]
+    fn load(py: Python<'_>, path: &str, seed: u64, fleets: Option<Py<RustFleetManager>>) -> PyResult<Self> {
+        let table = py
+            .allow_threads(|| std::fs::read_to_string(path))
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("IO error: {}", e)))?;
+        Self::new(&table, seed, fleets)
+    }
+
+    /// Restores a generator, including its random state and the anomalies
+    /// in effect, from `to_json` output.
+    #[staticmethod]
+    #[pyo3(signature = (snapshot_json, fleets=None))]
+    fn from_json(snapshot_json: &str, fleets: Option<Py<RustFleetManager>>) -> PyResult<Self> {
+        let snapshot: AnomalySnapshot = from_json(snapshot_json)?;
+        Ok(Self { inner: RwLock::new(AnomalyGenerator::from_snapshot(snapshot)), fleets })
+    }
+
+    fn to_json(&self) -> PyResult<String> {
+        to_json(&self.inner.read().snapshot())
+    }
+
+    #[doc = state_hash_doc!()]
+    fn state_hash(&self) -> u64 {
+        self.inner.read().state_hash()
+    }
+
+    /// Events are written here; without a log of its own the generator
+    /// writes into the turn engine's.
+    #[pyo3(signature = (capacity=None))]
+    fn enable_event_logging(&self, capacity: Option<usize>) -> EventLog {
+        let log = EventLog::with_capacity(capacity);
+        self.inner.write().set_event_log(log.clone());
+        log
+    }
+
+    fn set_correlation_context(&self, context: &CorrelationContext) {
+        self.inner.write().set_correlation_context(context.clone());
+    }
+
+    fn table(&self) -> PyResult<String> {
+        to_json(self.inner.read().table())
+    }
+
+    /// Replaces the table; anomalies in effect run their course.
+    fn set_table(&self, table_json: &str) -> PyResult<()> {
+        let table: AnomalyTable = from_json(table_json)?;
+        self.inner.write().set_table(table);
+        Ok(())
+    }
+
+    /// Timed anomalies in effect, as JSON.
+    fn active(&self) -> PyResult<String> {
+        to_json(&self.inner.read().active())
+    }
+
+    /// Ends anomalies that run out by `turn`, rolls for a new one, and
+    /// returns the `AnomalyReport` as JSON.
+    fn run(&self, py: Python<'_>, turn: u64, economy: &RustEconomyEngine, pathfinder: &RustPathfinder) -> PyResult<String> {
+        let fleets = self.fleets.as_ref().map(|f| f.get());
+        let report = py.allow_threads(|| {
+            let mut economy = economy.state.write();
+            let mut topology = pathfinder.inner.write();
+            let mut fleets = fleets.map(|f| f.inner.write());
+            let targets = AnomalyTargets { topology: &mut topology, economy: &mut economy.engine, fleets: fleets.as_deref_mut() };
+            self.inner.write().run(turn, targets)
+        });
+        to_json(&report)
+    }
+
+    fn __repr__(&self) -> String {
+        let engine = self.inner.read();
+        format!("RustAnomalyGenerator(entries={}, active={})", engine.table().entries.len(), engine.active().len())
+    }
+}
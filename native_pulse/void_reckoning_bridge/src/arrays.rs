@@ -0,0 +1,97 @@
+//! Bulk array transfer between the combat engine and numpy. Avoids building a
+//! Python tuple per unit: state goes out as one packed buffer viewed through a
+//! structured dtype, and updates come in through the buffer protocol.
+
+use pyo3::buffer::PyBuffer;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use std::collections::HashMap;
+use void_reckoning_combat::{BattleState, CombatUnit, UnitId};
+
+/// Packed little-endian record layout of `get_state_array`, 22 bytes per unit.
+const UNIT_STATE_DTYPE: [(&str, &str); 7] = [
+    ("id", "<u4"),
+    ("x", "<f4"),
+    ("y", "<f4"),
+    ("hp", "<f4"),
+    ("shields", "<f4"),
+    ("faction", "u1"),
+    ("alive", "?"),
+];
+const UNIT_STATE_RECORD_SIZE: usize = 22;
+
+/// Read-only numpy structured array with one record per unit. The array is a
+/// view over a single bytes object, so no per-unit Python objects are made.
+pub fn unit_state_array<'py>(py: Python<'py>, units: &[CombatUnit]) -> PyResult<Bound<'py, PyAny>> {
+    let mut buffer = Vec::with_capacity(units.len() * UNIT_STATE_RECORD_SIZE);
+    for unit in units {
+        buffer.extend_from_slice(&unit.id.get().to_le_bytes());
+        buffer.extend_from_slice(&unit.position.0.to_le_bytes());
+        buffer.extend_from_slice(&unit.position.1.to_le_bytes());
+        buffer.extend_from_slice(&unit.hp.to_le_bytes());
+        buffer.extend_from_slice(&unit.shields.to_le_bytes());
+        buffer.push(unit.faction_idx);
+        buffer.push(unit.is_alive as u8);
+    }
+
+    let numpy = py.import("numpy")?;
+    let dtype = numpy.call_method1("dtype", (UNIT_STATE_DTYPE.to_vec(),))?;
+    numpy.call_method1("frombuffer", (PyBytes::new(py, &buffer), dtype))
+}
+
+/// Copies a contiguous buffer of floats (float32 or float64) into a Vec.
+fn read_floats(obj: &Bound<'_, PyAny>) -> PyResult<Vec<f32>> {
+    let py = obj.py();
+    if let Ok(buffer) = PyBuffer::<f32>::get(obj) {
+        return buffer.to_vec(py);
+    }
+    let buffer = PyBuffer::<f64>::get(obj)
+        .map_err(|_| PyValueError::new_err("positions must be a contiguous float32 or float64 array"))?;
+    Ok(buffer.to_vec(py)?.into_iter().map(|v| v as f32).collect())
+}
+
+/// Copies a contiguous buffer of unit ids (uint32, int32, int64 or uint64) into a Vec.
+fn read_ids(obj: &Bound<'_, PyAny>) -> PyResult<Vec<u32>> {
+    let py = obj.py();
+    if let Ok(buffer) = PyBuffer::<u32>::get(obj) {
+        return buffer.to_vec(py);
+    }
+    let wide: Vec<i64> = if let Ok(buffer) = PyBuffer::<i64>::get(obj) {
+        buffer.to_vec(py)?
+    } else if let Ok(buffer) = PyBuffer::<u64>::get(obj) {
+        buffer.to_vec(py)?.into_iter().map(|v| v as i64).collect()
+    } else if let Ok(buffer) = PyBuffer::<i32>::get(obj) {
+        buffer.to_vec(py)?.into_iter().map(i64::from).collect()
+    } else {
+        return Err(PyValueError::new_err("ids must be a contiguous integer array"));
+    };
+    wide.into_iter()
+        .map(|v| u32::try_from(v).map_err(|_| PyValueError::new_err(format!("unit id {} out of range", v))))
+        .collect()
+}
+
+/// Applies `positions` (shape (N, 2) or flat 2N) to the units listed in `ids`.
+/// Unknown ids are skipped; returns how many units were moved.
+pub fn apply_positions(state: &mut BattleState, ids: &Bound<'_, PyAny>, positions: &Bound<'_, PyAny>) -> PyResult<usize> {
+    let ids = read_ids(ids)?;
+    let coords = read_floats(positions)?;
+    if coords.len() != ids.len() * 2 {
+        return Err(PyValueError::new_err(format!(
+            "expected {} coordinates for {} ids, got {}",
+            ids.len() * 2,
+            ids.len(),
+            coords.len()
+        )));
+    }
+
+    let index: HashMap<UnitId, usize> = state.units.iter().enumerate().map(|(i, u)| (u.id, i)).collect();
+    let mut moved = 0;
+    for (id, xy) in ids.iter().zip(coords.chunks_exact(2)) {
+        if let Some(&i) = index.get(&UnitId(*id)) {
+            state.units[i].position = (xy[0], xy[1]);
+            moved += 1;
+        }
+    }
+    Ok(moved)
+}
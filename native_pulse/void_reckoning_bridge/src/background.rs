@@ -0,0 +1,209 @@
+//! Background battle runs: a worker thread steps a shared `BattleEngine`
+//! to completion, reporting progress to Python between steps and
+//! publishing a `RustStateView` of the battle at the same points.
+
+use crate::views::RustStateView;
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::prelude::*;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Condvar, Mutex, PoisonError, Weak};
+use std::time::Duration;
+use void_reckoning_combat::engine::BattleEngine;
+use void_reckoning_combat::resolve::BattleOutcome;
+use void_reckoning_shared::errors::EngineError;
+use void_reckoning_shared::published::Published;
+
+/// The engine a run steps; `parking_lot` so a panic mid-step doesn't poison it.
+type SharedEngine = parking_lot::Mutex<BattleEngine>;
+
+/// Runs still able to touch Python (their progress callbacks), so
+/// `reset_module_state` can stop them. The only process-wide state the
+/// bridge keeps; everything else lives on the instances.
+static LIVE_RUNS: parking_lot::Mutex<Vec<Weak<RunState>>> = parking_lot::const_mutex(Vec::new());
+
+#[derive(Default)]
+struct RunState {
+    cancel_requested: AtomicBool,
+    cancelled: AtomicBool,
+    turn: AtomicU32,
+    outcome: Mutex<Option<BattleOutcome>>,
+    finished: Mutex<bool>,
+    finished_signal: Condvar,
+}
+
+/// Handle to a battle running on a background thread.
+#[pyclass(frozen)]
+#[derive(Clone)]
+pub struct BattleRun {
+    state: Arc<RunState>,
+}
+
+impl BattleRun {
+    pub fn spawn(
+        engine: Arc<SharedEngine>,
+        published: Arc<Published<RustStateView>>,
+        max_turns: u32,
+        progress_every: u32,
+        progress: Option<PyObject>,
+    ) -> PyResult<Self> {
+        let state = Arc::new(RunState::default());
+        let worker_state = Arc::clone(&state);
+        std::thread::Builder::new()
+            .name("battle-run".to_string())
+            .spawn(move || run(&engine, &published, &worker_state, max_turns, progress_every, progress))
+            .map_err(|e| PyRuntimeError::new_err(format!("Could not start battle thread: {}", e)))?;
+        let mut live = LIVE_RUNS.lock();
+        live.retain(|run| run.strong_count() > 0);
+        live.push(Arc::downgrade(&state));
+        Ok(Self { state })
+    }
+
+    /// Cancels the run and blocks (without the GIL) until the worker exits.
+    pub fn stop(&self, py: Python<'_>) {
+        self.cancel();
+        self.wait(py, None);
+    }
+
+    pub fn is_running(&self) -> bool {
+        !*self.state.finished.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Stops every run started in this process that is still going and
+    /// returns how many there were.
+    pub fn stop_all(py: Python<'_>) -> usize {
+        let live: Vec<_> = LIVE_RUNS.lock().drain(..).filter_map(|run| run.upgrade()).collect();
+        live.into_iter()
+            .map(|state| Self { state })
+            .filter(Self::is_running)
+            .inspect(|run| run.stop(py))
+            .count()
+    }
+}
+
+/// Publishes a copy of the battle as it stands between steps.
+pub(crate) fn publish(engine: &SharedEngine, published: &Published<RustStateView>) -> u64 {
+    let view = {
+        let engine = engine.lock();
+        RustStateView::at_turn(engine.campaign_turn()).with_battle(&engine.state)
+    };
+    published.publish(view)
+}
+
+fn run(
+    engine: &SharedEngine,
+    published: &Published<RustStateView>,
+    state: &RunState,
+    max_turns: u32,
+    progress_every: u32,
+    progress: Option<PyObject>,
+) {
+    let mut continues = engine.lock().state.units.iter().any(|u| u.is_alive);
+    let mut cancelled = false;
+    while continues {
+        if state.cancel_requested.load(Ordering::Acquire) {
+            cancelled = true;
+            break;
+        }
+        // Hold the lock for one step only, so Python can read the battle in between.
+        let (turn, living) = {
+            let mut engine = engine.lock();
+            if engine.state.turn >= max_turns {
+                break;
+            }
+            continues = engine.step();
+            (engine.state.turn, engine.state.units.iter().filter(|u| u.is_alive).count())
+        };
+        state.turn.store(turn, Ordering::Release);
+
+        let due = !continues || (progress_every > 0 && turn % progress_every == 0);
+        if due {
+            publish(engine, published);
+        }
+        if let (true, Some(callback)) = (due, &progress) {
+            let keep_going = Python::with_gil(|py| match callback.call1(py, (turn, living)) {
+                Ok(result) => result.extract::<bool>(py).unwrap_or(true),
+                Err(err) => {
+                    err.print(py);
+                    true
+                }
+            });
+            if !keep_going {
+                state.cancel_requested.store(true, Ordering::Release);
+            }
+        }
+    }
+
+    // A battle that ended on its own was published with its last step.
+    if continues {
+        publish(engine, published);
+    }
+    let outcome = {
+        let engine = engine.lock();
+        engine.outcome(&engine.state.run_id, continues && !cancelled)
+    };
+    state.cancelled.store(cancelled, Ordering::Release);
+    *state.outcome.lock().unwrap_or_else(PoisonError::into_inner) = Some(outcome);
+    *state.finished.lock().unwrap_or_else(PoisonError::into_inner) = true;
+    state.finished_signal.notify_all();
+}
+
+#[pymethods]
+impl BattleRun {
+    /// Asks the worker to stop after the step in progress.
+    fn cancel(&self) {
+        self.state.cancel_requested.store(true, Ordering::Release);
+    }
+
+    #[getter]
+    fn running(&self) -> bool {
+        self.is_running()
+    }
+
+    #[getter]
+    fn cancelled(&self) -> bool {
+        self.state.cancelled.load(Ordering::Acquire)
+    }
+
+    /// Last completed battle turn.
+    #[getter]
+    fn turn(&self) -> u32 {
+        self.state.turn.load(Ordering::Acquire)
+    }
+
+    /// Blocks (without the GIL) until the run ends or `timeout` seconds
+    /// pass. Returns True if the run has finished.
+    #[pyo3(signature = (timeout=None))]
+    fn wait(&self, py: Python<'_>, timeout: Option<f64>) -> bool {
+        let state = Arc::clone(&self.state);
+        py.allow_threads(move || {
+            let finished = state.finished.lock().unwrap_or_else(PoisonError::into_inner);
+            match timeout {
+                Some(secs) => {
+                    let limit = Duration::from_secs_f64(secs.max(0.0));
+                    let (finished, _) = state
+                        .finished_signal
+                        .wait_timeout_while(finished, limit, |done| !*done)
+                        .unwrap_or_else(PoisonError::into_inner);
+                    *finished
+                }
+                None => *state
+                    .finished_signal
+                    .wait_while(finished, |done| !*done)
+                    .unwrap_or_else(PoisonError::into_inner),
+            }
+        })
+    }
+
+    /// The `BattleOutcome` as JSON once the run has finished, else None.
+    fn result(&self) -> PyResult<Option<String>> {
+        let outcome = self.state.outcome.lock().unwrap_or_else(PoisonError::into_inner);
+        outcome
+            .as_ref()
+            .map(|o| serde_json::to_string(o).map_err(|e| PyErr::from(EngineError::json(e))))
+            .transpose()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("BattleRun(turn={}, running={})", self.turn(), self.is_running())
+    }
+}
@@ -0,0 +1,581 @@
+//! Whole-campaign save archive: every engine the bridge exposes, written as one
+//! versioned binary file so Python doesn't have to rebuild each engine by hand.
+//!
+//! Saves use the shared chunked container (`savegame`): one chunk per engine,
+//! versioned with that crate's schema version, so a corrupted battle costs
+//! that battle rather than the campaign, and chunks from older releases pass
+//! through `migrations()` on load. Archives written before the container
+//! (`VRCA` version 1) still load.
+
+use crate::turn::TurnScope;
+use crate::{combine_hashes, RustAuditor, RustCombatEngine, RustEconomyEngine, RustPathfinder};
+use parking_lot::RwLock;
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use void_reckoning_auditor::registry::{Registries, RegistryKind};
+use void_reckoning_combat::engine::BattleEngine;
+use void_reckoning_combat::BattleState;
+use void_reckoning_economy::engine::IncomeEngine;
+use void_reckoning_economy::trade::{TradeRoute, TradeRouteManager};
+use void_reckoning_economy::types::{EconomicNode, GlobalEconomicRules};
+use void_reckoning_pathfinder::{GraphTopology, TopologySnapshot};
+use void_reckoning_shared::desync::{DesyncReport, StateDigest, SubsystemDigest};
+use void_reckoning_shared::errors::EngineError;
+use void_reckoning_shared::published::Published;
+use void_reckoning_shared::savegame::{MigrationRegistry, SaveGame};
+use void_reckoning_shared::{ingest, CorrelationContext, EventLog};
+
+const LEGACY_MAGIC: &[u8; 4] = b"VRCA";
+const LEGACY_VERSION: u32 = 1;
+/// Version of the "campaign" and "log" chunks.
+pub(crate) const FORMAT_VERSION: u32 = 2;
+
+#[derive(Serialize, Deserialize)]
+struct BattleSnapshot {
+    state: BattleState,
+    campaign_turn: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EconomySnapshot {
+    rules: GlobalEconomicRules,
+    nodes: Vec<EconomicNode>,
+    routes: Vec<TradeRoute>,
+    campaign_turn: Option<u64>,
+}
+
+/// Registries travel as JSON text; bincode cannot encode `serde_json::Value`.
+#[derive(Serialize, Deserialize)]
+struct AuditorSnapshot {
+    registries: Vec<(RegistryKind, String)>,
+    initialized: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LogSnapshot {
+    capacity: Option<usize>,
+    events: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CampaignArchive {
+    turn: Option<u64>,
+    topology: Option<TopologySnapshot>,
+    battles: Vec<(String, BattleSnapshot)>,
+    economy: Option<EconomySnapshot>,
+    auditor: Option<AuditorSnapshot>,
+    event_logs: Vec<(String, LogSnapshot)>,
+}
+
+fn archive_error(e: impl std::fmt::Display) -> PyErr {
+    EngineError::serialization("Campaign archive", e).into()
+}
+
+/// Current version of each chunk kind.
+fn chunk_versions() -> BTreeMap<&'static str, u32> {
+    BTreeMap::from([
+        ("campaign", FORMAT_VERSION),
+        ("pathfinder", void_reckoning_pathfinder::SCHEMA_VERSION),
+        ("economy", void_reckoning_economy::SCHEMA_VERSION),
+        ("auditor", void_reckoning_auditor::SCHEMA_VERSION),
+        ("battle", void_reckoning_combat::SCHEMA_VERSION),
+        ("log", FORMAT_VERSION),
+    ])
+}
+
+/// Upgrades for chunks written by earlier releases. Bumping a chunk's
+/// version means registering the step from the old one here.
+fn migrations() -> MigrationRegistry {
+    MigrationRegistry::new()
+}
+
+fn tracked_hash(py: Python<'_>, engine: &PyObject) -> PyResult<u64> {
+    engine.call_method0(py, "state_hash")?.extract(py)
+}
+
+/// State as a JSON value for field digests. Goes through text because
+/// `serde_json::to_value` rejects the economy's `i128` amounts.
+fn state_value(state: &impl Serialize) -> PyResult<Value> {
+    serde_json::to_string(state)
+        .and_then(|json| serde_json::from_str(&json))
+        .map_err(|e| PyErr::from(EngineError::json(e)))
+}
+
+fn decode_chunk<T: serde::de::DeserializeOwned>(payload: &[u8]) -> Result<T, String> {
+    bincode::deserialize(payload).map_err(|e| e.to_string())
+}
+
+/// Bundle of engines saved and restored together. Engines are held by
+/// reference, so the archive always captures their state at `save` time.
+/// Composite auditor rules and engine event-log attachments are not part of
+/// the archive; re-register them after `load`.
+#[pyclass]
+#[derive(Default)]
+pub struct RustCampaignState {
+    #[pyo3(get, set)]
+    pub turn: Option<u64>,
+    pathfinder: Option<Py<RustPathfinder>>,
+    economy: Option<Py<RustEconomyEngine>>,
+    auditor: Option<Py<RustAuditor>>,
+    battles: BTreeMap<String, Py<RustCombatEngine>>,
+    event_logs: BTreeMap<String, EventLog>,
+    /// Engines hashed with the campaign but not saved with it.
+    tracked: BTreeMap<String, PyObject>,
+    /// Chunks the last load skipped, with the reason.
+    damaged: Vec<(String, String)>,
+}
+
+#[pymethods]
+impl RustCampaignState {
+    #[new]
+    #[pyo3(signature = (turn=None))]
+    pub fn new(turn: Option<u64>) -> Self {
+        Self { turn, ..Self::default() }
+    }
+
+    #[getter]
+    fn pathfinder(&self, py: Python<'_>) -> Option<Py<RustPathfinder>> {
+        self.pathfinder.as_ref().map(|p| p.clone_ref(py))
+    }
+
+    #[setter]
+    fn set_pathfinder(&mut self, pathfinder: Option<Py<RustPathfinder>>) {
+        self.pathfinder = pathfinder;
+    }
+
+    #[getter]
+    fn economy(&self, py: Python<'_>) -> Option<Py<RustEconomyEngine>> {
+        self.economy.as_ref().map(|e| e.clone_ref(py))
+    }
+
+    #[setter]
+    fn set_economy(&mut self, economy: Option<Py<RustEconomyEngine>>) {
+        self.economy = economy;
+    }
+
+    #[getter]
+    fn auditor(&self, py: Python<'_>) -> Option<Py<RustAuditor>> {
+        self.auditor.as_ref().map(|a| a.clone_ref(py))
+    }
+
+    #[setter]
+    fn set_auditor(&mut self, auditor: Option<Py<RustAuditor>>) {
+        self.auditor = auditor;
+    }
+
+    /// Adds (or replaces) an active battle under `name`.
+    fn add_battle(&mut self, name: String, engine: Py<RustCombatEngine>) {
+        self.battles.insert(name, engine);
+    }
+
+    fn remove_battle(&mut self, name: &str) -> Option<Py<RustCombatEngine>> {
+        self.battles.remove(name)
+    }
+
+    fn get_battle(&self, py: Python<'_>, name: &str) -> Option<Py<RustCombatEngine>> {
+        self.battles.get(name).map(|b| b.clone_ref(py))
+    }
+
+    fn battle_names(&self) -> Vec<String> {
+        self.battles.keys().cloned().collect()
+    }
+
+    /// Hashes `engine` with the campaign under `name` from now on: any
+    /// engine with a `state_hash()` (diplomacy, fleets, colonies, the turn
+    /// engine...), its `to_json()` fields digested too if it has one.
+    /// Tracked engines are not saved; track them again after `load`.
+    fn track(&mut self, name: String, engine: PyObject) {
+        self.tracked.insert(name, engine);
+    }
+
+    fn untrack(&mut self, name: &str) -> Option<PyObject> {
+        self.tracked.remove(name)
+    }
+
+    fn tracked_names(&self) -> Vec<String> {
+        self.tracked.keys().cloned().collect()
+    }
+
+    /// Adds (or replaces) an event log under `name`.
+    fn add_event_log(&mut self, name: String, log: EventLog) {
+        self.event_logs.insert(name, log);
+    }
+
+    fn get_event_log(&self, name: &str) -> Option<EventLog> {
+        self.event_logs.get(name).cloned()
+    }
+
+    fn event_log_names(&self) -> Vec<String> {
+        self.event_logs.keys().cloned().collect()
+    }
+
+    /// `begin_turn` over every engine in the campaign, stamped with `turn`.
+    /// Without `context` the turn starts a new trace.
+    #[pyo3(signature = (context=None))]
+    fn begin_turn(&self, py: Python<'_>, context: Option<CorrelationContext>) -> PyResult<TurnScope> {
+        let context = context.unwrap_or_default();
+        let battles = self.battles.iter().map(|(name, engine)| (name.clone(), engine)).collect();
+        TurnScope::begin(py, &context, self.turn, self.pathfinder.as_ref(), self.economy.as_ref(), self.auditor.as_ref(), battles)
+    }
+
+    /// Puts every battle in lockstep mode, each seeded from `seed` and its
+    /// name so clients calling this with the same seed roll alike. `None`
+    /// turns lockstep mode off.
+    #[pyo3(signature = (seed=None))]
+    fn set_deterministic(&self, seed: Option<u64>) {
+        for (name, battle) in &self.battles {
+            let battle_seed = seed.map(|seed| combine_hashes([seed, combine_hashes(name.bytes().map(u64::from))]));
+            battle.get().set_deterministic(battle_seed);
+        }
+    }
+
+    /// State hash of each engine: `pathfinder`, `economy` and `auditor`
+    /// (None when unset), `battles` by name and tracked engines under their
+    /// own names. Diff two clients' results to find which engine diverged.
+    fn state_hashes<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let hashes = PyDict::new(py);
+        hashes.set_item("pathfinder", self.pathfinder.as_ref().map(|p| p.get().state_hash(py)))?;
+        hashes.set_item("economy", self.economy.as_ref().map(|e| e.get().state_hash(py)))?;
+        hashes.set_item("auditor", self.auditor.as_ref().map(|a| a.get().state_hash(py)))?;
+        let battles = PyDict::new(py);
+        for (name, battle) in &self.battles {
+            battles.set_item(name, battle.get().state_hash(py))?;
+        }
+        hashes.set_item("battles", battles)?;
+        for (name, engine) in &self.tracked {
+            hashes.set_item(name, tracked_hash(py, engine)?)?;
+        }
+        Ok(hashes)
+    }
+
+    /// All of `state_hashes()` folded into one number (plus the campaign
+    /// turn), cheap to exchange every turn in lockstep play.
+    fn state_hash(&self, py: Python<'_>) -> PyResult<u64> {
+        let mut hashes = vec![self.turn.unwrap_or(u64::MAX)];
+        hashes.extend(self.pathfinder.as_ref().map(|p| p.get().state_hash(py)));
+        hashes.extend(self.economy.as_ref().map(|e| e.get().state_hash(py)));
+        hashes.extend(self.auditor.as_ref().map(|a| a.get().state_hash(py)));
+        for (name, battle) in &self.battles {
+            hashes.push(combine_hashes(name.bytes().map(u64::from)));
+            hashes.push(battle.get().state_hash(py));
+        }
+        for (name, engine) in &self.tracked {
+            hashes.push(combine_hashes(name.bytes().map(u64::from)));
+            hashes.push(tracked_hash(py, engine)?);
+        }
+        Ok(combine_hashes(hashes))
+    }
+
+    /// JSON `StateDigest` to send to peers: each engine's state hash with
+    /// hashes of its state's fields, so a mismatch can be traced to the
+    /// field. Battles appear as `battle:<name>`.
+    fn state_digest(&self, py: Python<'_>) -> PyResult<String> {
+        serde_json::to_string(&self.digest(py)?).map_err(|e| PyErr::from(EngineError::json(e)))
+    }
+
+    /// Compares this campaign against a peer's `state_digest()`. In sync,
+    /// returns None; otherwise writes a Critical `Desync` event naming the
+    /// diverged engines and their differing fields to every campaign event
+    /// log and returns the JSON `DesyncReport`.
+    fn check_desync(&self, py: Python<'_>, remote_digest_json: &str) -> PyResult<Option<String>> {
+        let remote: StateDigest = serde_json::from_str(remote_digest_json).map_err(|e| PyErr::from(EngineError::json(e)))?;
+        let report = DesyncReport::compare(&self.digest(py)?, &remote);
+        if report.is_empty() {
+            return Ok(None);
+        }
+        let event = report.to_event(CorrelationContext::new());
+        for log in self.event_logs.values() {
+            log.add(event.clone());
+        }
+        serde_json::to_string(&report).map(Some).map_err(|e| PyErr::from(EngineError::json(e)))
+    }
+
+    /// Encodes the campaign as a chunked save, one chunk per engine.
+    fn to_bytes<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        let bytes = self.encode()?;
+        Ok(PyBytes::new(py, &bytes))
+    }
+
+    /// Decodes a save. Chunks that fail their checksum or can't be migrated
+    /// are left out and listed by `damaged_chunks()`; with `strict` any
+    /// damage is an error instead.
+    #[staticmethod]
+    #[pyo3(signature = (data, strict=false))]
+    fn from_bytes(py: Python<'_>, data: &[u8], strict: bool) -> PyResult<Self> {
+        Self::decode(py, data, strict)
+    }
+
+    fn save(&self, py: Python<'_>, path: &str) -> PyResult<()> {
+        let bytes = self.encode()?;
+        py.allow_threads(|| std::fs::write(path, bytes))
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("IO error: {}", e)))
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (path, strict=false))]
+    fn load(py: Python<'_>, path: &str, strict: bool) -> PyResult<Self> {
+        let bytes = py
+            .allow_threads(|| std::fs::read(path))
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("IO error: {}", e)))?;
+        Self::decode(py, &bytes, strict)
+    }
+
+    /// `(chunk, reason)` for each chunk the load that produced this campaign
+    /// had to skip, e.g. `("battle:front", "checksum mismatch")`.
+    fn damaged_chunks(&self) -> Vec<(String, String)> {
+        self.damaged.clone()
+    }
+
+    /// Closes every engine the campaign holds, flushes its event logs and
+    /// lets go of all of them. `with` blocks call this on exit.
+    fn close(&mut self, py: Python<'_>) {
+        if let Some(pathfinder) = self.pathfinder.take() {
+            pathfinder.get().close(py);
+        }
+        if let Some(economy) = self.economy.take() {
+            economy.get().close(py);
+        }
+        if let Some(auditor) = self.auditor.take() {
+            auditor.get().close(py);
+        }
+        for battle in std::mem::take(&mut self.battles).into_values() {
+            battle.get().close(py);
+        }
+        for log in std::mem::take(&mut self.event_logs).into_values() {
+            py.allow_threads(|| log.flush());
+        }
+        self.tracked.clear();
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    #[pyo3(signature = (_exc_type=None, _exc_value=None, _traceback=None))]
+    fn __exit__(&mut self, py: Python<'_>, _exc_type: Option<PyObject>, _exc_value: Option<PyObject>, _traceback: Option<PyObject>) -> bool {
+        self.close(py);
+        false
+    }
+}
+
+impl RustCampaignState {
+    fn battle_snapshot(engine: &RustCombatEngine) -> BattleSnapshot {
+        let engine = engine.engine();
+        BattleSnapshot { state: engine.state.clone(), campaign_turn: engine.campaign_turn() }
+    }
+
+    fn economy_snapshot(&self) -> Option<EconomySnapshot> {
+        self.economy.as_ref().map(|e| {
+            let economy = e.get().state.read();
+            EconomySnapshot {
+                rules: economy.engine.rules().clone(),
+                nodes: economy.engine.nodes().to_vec(),
+                routes: economy.trade_manager.routes().to_vec(),
+                campaign_turn: economy.engine.campaign_turn(),
+            }
+        })
+    }
+
+    fn digest(&self, py: Python<'_>) -> PyResult<StateDigest> {
+        let mut digest = StateDigest::new(self.turn);
+        if let Some(pathfinder) = &self.pathfinder {
+            let state = state_value(&pathfinder.get().inner.read().snapshot())?;
+            digest.insert("pathfinder", SubsystemDigest::with_fields(pathfinder.get().state_hash(py), &state));
+        }
+        if let (Some(economy), Some(snapshot)) = (&self.economy, self.economy_snapshot()) {
+            digest.insert("economy", SubsystemDigest::with_fields(economy.get().state_hash(py), &state_value(&snapshot)?));
+        }
+        if let Some(auditor) = &self.auditor {
+            let registries = {
+                let state = auditor.get().state.read();
+                RegistryKind::ALL
+                    .iter()
+                    .map(|&kind| serde_json::to_value(state.registries.get(kind)).map(|registry| (format!("{:?}", kind), registry)))
+                    .collect::<Result<serde_json::Map<_, _>, _>>()
+                    .map_err(|e| PyErr::from(EngineError::json(e)))?
+            };
+            digest.insert("auditor", SubsystemDigest::with_fields(auditor.get().state_hash(py), &Value::Object(registries)));
+        }
+        for (name, battle) in &self.battles {
+            let state = state_value(&Self::battle_snapshot(battle.get()).state)?;
+            digest.insert(format!("battle:{}", name), SubsystemDigest::with_fields(battle.get().state_hash(py), &state));
+        }
+        for (name, engine) in &self.tracked {
+            let hash = tracked_hash(py, engine)?;
+            let engine = engine.bind(py);
+            let subsystem = match engine.hasattr("to_json")? {
+                true => {
+                    let json: String = engine.call_method0("to_json")?.extract()?;
+                    let state: Value = serde_json::from_str(&json).map_err(|e| PyErr::from(EngineError::json(e)))?;
+                    SubsystemDigest::with_fields(hash, &state)
+                }
+                false => SubsystemDigest::new(hash),
+            };
+            digest.insert(name.clone(), subsystem);
+        }
+        Ok(digest)
+    }
+
+    fn encode(&self) -> PyResult<Vec<u8>> {
+        let topology = self.pathfinder.as_ref().map(|p| p.get().inner.read().snapshot());
+
+        let battles = self.battles.iter().map(|(name, engine)| (name.clone(), Self::battle_snapshot(engine.get()))).collect();
+
+        let economy = self.economy_snapshot();
+
+        let auditor = match &self.auditor {
+            Some(a) => {
+                let auditor = a.get().state.read();
+                let registries = RegistryKind::ALL
+                    .iter()
+                    .map(|&kind| serde_json::to_string(auditor.registries.get(kind)).map(|json| (kind, json)))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(archive_error)?;
+                Some(AuditorSnapshot { registries, initialized: auditor.engine.is_some() })
+            }
+            None => None,
+        };
+
+        let event_logs = self
+            .event_logs
+            .iter()
+            .map(|(name, log)| {
+                ingest::encode_bincode(&log.get_all())
+                    .map(|events| (name.clone(), LogSnapshot { capacity: log.capacity(), events }))
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(archive_error)?;
+
+        let archive = CampaignArchive { turn: self.turn, topology, battles, economy, auditor, event_logs };
+        let versions = chunk_versions();
+        let mut save = SaveGame::new(env!("CARGO_PKG_VERSION"));
+        let mut add = |name: String, kind: &str, payload: Result<Vec<u8>, bincode::Error>| -> PyResult<()> {
+            save.add_chunk(name, versions[kind], payload.map_err(archive_error)?);
+            Ok(())
+        };
+        add("campaign".to_string(), "campaign", bincode::serialize(&archive.turn))?;
+        if let Some(topology) = &archive.topology {
+            add("pathfinder".to_string(), "pathfinder", bincode::serialize(topology))?;
+        }
+        if let Some(economy) = &archive.economy {
+            add("economy".to_string(), "economy", bincode::serialize(economy))?;
+        }
+        if let Some(auditor) = &archive.auditor {
+            add("auditor".to_string(), "auditor", bincode::serialize(auditor))?;
+        }
+        for (name, battle) in &archive.battles {
+            add(format!("battle:{}", name), "battle", bincode::serialize(battle))?;
+        }
+        for (name, log) in &archive.event_logs {
+            add(format!("log:{}", name), "log", bincode::serialize(log))?;
+        }
+        save.to_bytes().map_err(archive_error)
+    }
+
+    /// Reads the pre-container format: `VRCA` + version + one bincode archive.
+    fn decode_legacy(body: &[u8]) -> PyResult<CampaignArchive> {
+        let (header, body) = body.split_at_checked(8).ok_or_else(|| archive_error("truncated archive"))?;
+        let version = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+        if version != LEGACY_VERSION {
+            return Err(archive_error(format!("unsupported version {} (expected {})", version, LEGACY_VERSION)));
+        }
+        bincode::deserialize(body).map_err(archive_error)
+    }
+
+    /// Reads a chunked save, moving chunks that don't decode into
+    /// `damaged` alongside those the container already rejected.
+    fn decode_chunks(bytes: &[u8], damaged: &mut Vec<(String, String)>) -> PyResult<CampaignArchive> {
+        let mut save = SaveGame::from_bytes(bytes).map_err(archive_error)?;
+        migrations().upgrade(&mut save, &chunk_versions());
+        damaged.extend(save.damaged.drain(..).map(|d| (d.name, d.reason)));
+
+        let mut archive =
+            CampaignArchive { turn: None, topology: None, battles: Vec::new(), economy: None, auditor: None, event_logs: Vec::new() };
+        for chunk in save.chunks {
+            let payload = chunk.payload.as_slice();
+            let decoded = match chunk.name.split_once(':') {
+                None if chunk.name == "campaign" => decode_chunk(payload).map(|turn| archive.turn = turn),
+                None if chunk.name == "pathfinder" => decode_chunk(payload).map(|t| archive.topology = Some(t)),
+                None if chunk.name == "economy" => decode_chunk(payload).map(|e| archive.economy = Some(e)),
+                None if chunk.name == "auditor" => decode_chunk(payload).map(|a| archive.auditor = Some(a)),
+                Some(("battle", name)) => decode_chunk(payload).map(|b| archive.battles.push((name.to_string(), b))),
+                Some(("log", name)) => decode_chunk(payload).map(|l| archive.event_logs.push((name.to_string(), l))),
+                // Chunks from a newer release that this one doesn't know.
+                _ => Err("unknown chunk".to_string()),
+            };
+            if let Err(reason) = decoded {
+                damaged.push((chunk.name, reason));
+            }
+        }
+        Ok(archive)
+    }
+
+    fn decode(py: Python<'_>, bytes: &[u8], strict: bool) -> PyResult<Self> {
+        let mut damaged = Vec::new();
+        let archive = if bytes.starts_with(LEGACY_MAGIC) {
+            Self::decode_legacy(bytes)?
+        } else {
+            Self::decode_chunks(bytes, &mut damaged)?
+        };
+        if strict && !damaged.is_empty() {
+            let names: Vec<&str> = damaged.iter().map(|(name, _)| name.as_str()).collect();
+            return Err(archive_error(format!("damaged chunks: {}", names.join(", "))));
+        }
+
+        let pathfinder = archive
+            .topology
+            .map(|topology| Py::new(py, RustPathfinder { inner: RwLock::new(GraphTopology::from_snapshot(topology)), published: Published::default() }))
+            .transpose()?;
+
+        let mut battles = BTreeMap::new();
+        for (name, snapshot) in archive.battles {
+            let mut inner = BattleEngine::from_state(snapshot.state);
+            inner.set_campaign_turn(snapshot.campaign_turn);
+            battles.insert(name, Py::new(py, RustCombatEngine::from_engine(inner))?);
+        }
+
+        let economy = archive
+            .economy
+            .map(|snapshot| {
+                let mut engine = IncomeEngine::new(snapshot.rules);
+                for node in snapshot.nodes {
+                    engine.add_node(node);
+                }
+                engine.set_campaign_turn(snapshot.campaign_turn);
+                let mut trade_manager = TradeRouteManager::new();
+                for route in snapshot.routes {
+                    trade_manager.add_route(route);
+                }
+                Py::new(py, RustEconomyEngine::from_parts(engine, trade_manager))
+            })
+            .transpose()?;
+
+        let auditor = match archive.auditor {
+            Some(snapshot) => {
+                let mut registries = Registries::new();
+                for (kind, json) in snapshot.registries {
+                    registries.replace(kind, serde_json::from_str(&json).map_err(archive_error)?);
+                }
+                let auditor = RustAuditor::with_registries(Arc::new(registries));
+                if snapshot.initialized {
+                    auditor.initialize()?;
+                }
+                Some(Py::new(py, auditor)?)
+            }
+            None => None,
+        };
+
+        let mut event_logs = BTreeMap::new();
+        for (name, snapshot) in archive.event_logs {
+            let log = EventLog::from_events(ingest::decode_bincode(&snapshot.events).map_err(archive_error)?);
+            log.set_capacity(snapshot.capacity);
+            event_logs.insert(name, log);
+        }
+
+        Ok(Self { turn: archive.turn, pathfinder, economy, auditor, battles, event_logs, tracked: BTreeMap::new(), damaged })
+    }
+}
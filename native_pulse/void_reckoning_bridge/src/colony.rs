@@ -0,0 +1,180 @@
+//! `RustColonyEngine`: habitability, colony ships, settlers in transit and
+//! population growth. A `RustTurnEngine` built with one runs it every turn;
+//! it can also be advanced on its own.
+
+use crate::{RustEconomyEngine, RustPathfinder};
+use parking_lot::RwLock;
+use pyo3::exceptions::{PyKeyError, PyValueError};
+use pyo3::prelude::*;
+use std::collections::{BTreeMap, HashMap};
+use void_reckoning_colony::{ColonizationOrder, Colony, ColonyEngine, ColonyRules, ColonySnapshot, TransferOrder};
+use void_reckoning_economy::types::ResourceState;
+use void_reckoning_shared::errors::EngineError;
+use void_reckoning_shared::ids::{FactionId, SystemId};
+use void_reckoning_shared::{CorrelationContext, EventLog};
+
+#[pyclass(frozen)]
+pub struct RustColonyEngine {
+    pub(crate) inner: RwLock<ColonyEngine>,
+}
+
+fn to_json(value: &impl serde::Serialize) -> PyResult<String> {
+    serde_json::to_string(value).map_err(|e| PyErr::from(EngineError::json(e)))
+}
+
+fn from_json<T: serde::de::DeserializeOwned>(json: &str) -> PyResult<T> {
+    serde_json::from_str(json).map_err(|e| PyErr::from(EngineError::json(e)))
+}
+
+#[pymethods]
+impl RustColonyEngine {
+    /// `rules_json` is a `ColonyRules` (`max_population`, `growth_rate`,
+    /// `settlers_per_ship`, `bootstrap_cost`, `income_per_pop`,
+    /// `travel_speed`, `colony_ship_item`); fields left out keep their
+    /// defaults.
+    #[new]
+    #[pyo3(signature = (rules_json=None))]
+    fn new(rules_json: Option<&str>) -> PyResult<Self> {
+        let rules: ColonyRules = rules_json.map(from_json).transpose()?.unwrap_or_default();
+        Ok(Self { inner: RwLock::new(ColonyEngine::new(rules)) })
+    }
+
+    /// Restores an engine from `to_json` output.
+    #[staticmethod]
+    fn from_json(snapshot_json: &str) -> PyResult<Self> {
+        let snapshot: ColonySnapshot = from_json(snapshot_json)?;
+        Ok(Self { inner: RwLock::new(ColonyEngine::from_snapshot(snapshot)) })
+    }
+
+    fn to_json(&self) -> PyResult<String> {
+        to_json(&self.inner.read().snapshot())
+    }
+
+    #[doc = state_hash_doc!()]
+    fn state_hash(&self) -> u64 {
+        self.inner.read().state_hash()
+    }
+
+    /// Events are written here; without a log of its own the engine writes
+    /// into the turn engine's.
+    #[pyo3(signature = (capacity=None))]
+    fn enable_event_logging(&self, capacity: Option<usize>) -> EventLog {
+        let log = EventLog::with_capacity(capacity);
+        self.inner.write().set_event_log(log.clone());
+        log
+    }
+
+    fn set_correlation_context(&self, context: &CorrelationContext) {
+        self.inner.write().set_correlation_context(context.clone());
+    }
+
+    #[getter]
+    fn turn(&self) -> u64 {
+        self.inner.read().turn()
+    }
+
+    fn rules(&self) -> PyResult<String> {
+        to_json(self.inner.read().rules())
+    }
+
+    fn set_rules(&self, rules_json: &str) -> PyResult<()> {
+        let rules: ColonyRules = from_json(rules_json)?;
+        self.inner.write().set_rules(rules);
+        Ok(())
+    }
+
+    /// Overrides `system`'s habitability (0 to 1); None goes back to the
+    /// terrain's (Plains 1, Forest 0.8, Water 0.6, Mountain 0.4, Space 0).
+    #[pyo3(signature = (system, habitability=None))]
+    fn set_habitability(&self, system: &str, habitability: Option<f32>) {
+        self.inner.write().set_habitability(system, habitability);
+    }
+
+    fn habitability(&self, system: &str, pathfinder: &RustPathfinder) -> f32 {
+        self.inner.read().habitability(system, &pathfinder.inner.read())
+    }
+
+    /// Adds a colony (a homeworld, say) without colonizing it.
+    fn add_colony(&self, planet: SystemId, faction: FactionId, population: f32) {
+        self.inner.write().add_colony(Colony { planet, faction, population });
+    }
+
+    fn remove_colony(&self, planet: &str) -> PyResult<Option<String>> {
+        self.inner.write().remove_colony(planet).map(|c| to_json(&c)).transpose()
+    }
+
+    /// The colony on `planet` as JSON. Raises KeyError if there is none.
+    fn colony(&self, planet: &str) -> PyResult<String> {
+        let engine = self.inner.read();
+        let colony = engine.colony(planet).ok_or_else(|| PyKeyError::new_err(format!("No colony on {}", planet)))?;
+        to_json(colony)
+    }
+
+    /// Every colony, in planet order, as a JSON array.
+    fn colonies(&self) -> PyResult<String> {
+        to_json(&self.inner.read().colonies().collect::<Vec<_>>())
+    }
+
+    fn grant_colony_ships(&self, faction: &str, ships: u32) {
+        self.inner.write().grant_colony_ships(faction, ships);
+    }
+
+    fn colony_ships(&self, faction: &str) -> u32 {
+        self.inner.read().colony_ships(faction)
+    }
+
+    /// Settlers in transit, as a JSON array.
+    fn voyages(&self) -> PyResult<String> {
+        to_json(&self.inner.read().voyages())
+    }
+
+    /// Launches a JSON `ColonizationOrder` (`id`, `faction`, `from`, `to`,
+    /// `ships`) and returns the turn it lands on. Raises ValueError if it
+    /// can't be launched.
+    fn colonize(&self, py: Python<'_>, order_json: &str, pathfinder: &RustPathfinder) -> PyResult<u64> {
+        let order: ColonizationOrder = from_json(order_json)?;
+        py.allow_threads(|| self.inner.write().colonize(&order, &pathfinder.inner.read())).map_err(PyValueError::new_err)
+    }
+
+    /// Sends a JSON `TransferOrder` (`id`, `from`, `to`, `population`) and
+    /// returns the turn it arrives on. Raises ValueError if it can't be
+    /// sent.
+    fn transfer(&self, py: Python<'_>, order_json: &str, pathfinder: &RustPathfinder) -> PyResult<u64> {
+        let order: TransferOrder = from_json(order_json)?;
+        py.allow_threads(|| self.inner.write().transfer(&order, &pathfinder.inner.read())).map_err(PyValueError::new_err)
+    }
+
+    /// Advances the colonies on their own, outside a turn engine, and
+    /// returns the `ColonyTurnReport` as JSON. A colony ship lands only if
+    /// its faction's entry in `treasuries` (faction to JSON
+    /// `ResourceState`) covers the bootstrap cost; what each paid is under
+    /// `bootstrap_paid`.
+    #[pyo3(signature = (turn, economy, pathfinder, treasuries=None, ships_built=None))]
+    fn advance_turn(
+        &self,
+        py: Python<'_>,
+        turn: u64,
+        economy: &RustEconomyEngine,
+        pathfinder: &RustPathfinder,
+        treasuries: Option<HashMap<FactionId, String>>,
+        ships_built: Option<HashMap<FactionId, u32>>,
+    ) -> PyResult<String> {
+        let mut treasuries: BTreeMap<FactionId, ResourceState> = treasuries
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(faction, json)| Ok((faction, from_json(&json)?)))
+            .collect::<PyResult<_>>()?;
+        let ships_built: BTreeMap<FactionId, u32> = ships_built.unwrap_or_default().into_iter().collect();
+        let report = py.allow_threads(|| {
+            let mut economy = economy.state.write();
+            let topology = pathfinder.inner.read();
+            self.inner.write().advance_turn(turn, ships_built, &topology, &mut economy.engine, &mut treasuries)
+        });
+        to_json(&report)
+    }
+
+    fn __repr__(&self) -> String {
+        let engine = self.inner.read();
+        format!("RustColonyEngine(turn={}, colonies={}, voyages={})", engine.turn(), engine.colonies().count(), engine.voyages().len())
+    }
+}
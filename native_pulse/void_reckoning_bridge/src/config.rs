@@ -0,0 +1,343 @@
+//! Keyword-argument configuration objects for the engines. Each is validated
+//! when constructed, so a bad value fails at the call that introduced it
+//! rather than deep inside a battle or turn.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use void_reckoning_combat::resolve::{UnitSetup, WeaponSetup};
+use void_reckoning_combat::{CombatUnit, CoverType, UnitId, WeaponType};
+use void_reckoning_economy::types::{GlobalEconomicRules, SCALE_FACTOR};
+
+fn invalid(message: String) -> PyErr {
+    PyValueError::new_err(message)
+}
+
+fn check_non_negative(name: &str, value: f32) -> PyResult<()> {
+    if value.is_finite() && value >= 0.0 {
+        Ok(())
+    } else {
+        Err(invalid(format!("{} must be a non-negative number, got {}", name, value)))
+    }
+}
+
+pub(crate) fn check_fraction(name: &str, value: f64) -> PyResult<()> {
+    if (0.0..=1.0).contains(&value) {
+        Ok(())
+    } else {
+        Err(invalid(format!("{} must be between 0 and 1, got {}", name, value)))
+    }
+}
+
+fn parse_weapon_type(name: &str) -> PyResult<WeaponType> {
+    match name {
+        "Kinetic" => Ok(WeaponType::Kinetic),
+        "Energy" => Ok(WeaponType::Energy),
+        "Missile" => Ok(WeaponType::Missile),
+        "Beam" => Ok(WeaponType::Beam),
+        "Fighter" => Ok(WeaponType::Fighter),
+        _ => Err(invalid(format!(
+            "Unknown weapon type '{}' (expected Kinetic, Energy, Missile, Beam or Fighter)",
+            name
+        ))),
+    }
+}
+
+fn parse_cover(name: &str) -> PyResult<CoverType> {
+    match name {
+        "None" => Ok(CoverType::None),
+        "Light" => Ok(CoverType::Light),
+        "Heavy" => Ok(CoverType::Heavy),
+        "Fortified" => Ok(CoverType::Fortified),
+        _ => Err(invalid(format!("Unknown cover '{}' (expected None, Light, Heavy or Fortified)", name))),
+    }
+}
+
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct WeaponSpec {
+    #[pyo3(get)]
+    pub name: String,
+    pub weapon_type: WeaponType,
+    #[pyo3(get)]
+    pub range: f32,
+    #[pyo3(get)]
+    pub damage: f32,
+    #[pyo3(get)]
+    pub accuracy: f32,
+    #[pyo3(get)]
+    pub cooldown: f32,
+    /// Splash radius; 0 for a single-target weapon.
+    #[pyo3(get)]
+    pub aoe_radius: f32,
+}
+
+#[pymethods]
+impl WeaponSpec {
+    #[new]
+    #[pyo3(signature = (name, range, damage, weapon_type="Kinetic", accuracy=1.0, cooldown=1.0, aoe_radius=0.0))]
+    pub fn new(name: String, range: f32, damage: f32, weapon_type: &str, accuracy: f32, cooldown: f32, aoe_radius: f32) -> PyResult<Self> {
+        check_non_negative("range", range)?;
+        check_non_negative("damage", damage)?;
+        check_non_negative("cooldown", cooldown)?;
+        check_non_negative("aoe_radius", aoe_radius)?;
+        check_fraction("accuracy", accuracy as f64)?;
+        Ok(Self { name, weapon_type: parse_weapon_type(weapon_type)?, range, damage, accuracy, cooldown, aoe_radius })
+    }
+
+    #[getter]
+    fn weapon_type(&self) -> String {
+        format!("{:?}", self.weapon_type)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "WeaponSpec(name={:?}, weapon_type={:?}, range={}, damage={})",
+            self.name, self.weapon_type, self.range, self.damage
+        )
+    }
+}
+
+impl WeaponSpec {
+    fn to_setup(&self) -> WeaponSetup {
+        WeaponSetup {
+            name: self.name.clone(),
+            weapon_type: self.weapon_type,
+            range: self.range,
+            damage: self.damage,
+            accuracy: self.accuracy,
+            cooldown: self.cooldown,
+            aoe_radius: self.aoe_radius,
+        }
+    }
+}
+
+/// A unit ready to be placed in a battle. Everything but identity, faction
+/// and hit points has a default; weapons can be passed up front or added
+/// with `add_weapon`.
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct UnitSpec {
+    #[pyo3(get)]
+    pub id: UnitId,
+    #[pyo3(get)]
+    pub name: String,
+    #[pyo3(get)]
+    pub faction_idx: u8,
+    #[pyo3(get)]
+    pub max_hp: f32,
+    #[pyo3(get)]
+    pub x: f32,
+    #[pyo3(get)]
+    pub y: f32,
+    #[pyo3(get)]
+    pub weapons: Vec<WeaponSpec>,
+    #[pyo3(get)]
+    pub speed: f32,
+    #[pyo3(get)]
+    pub evasion: f32,
+    #[pyo3(get)]
+    pub shields: f32,
+    #[pyo3(get)]
+    pub armor: f32,
+    pub cover: CoverType,
+}
+
+#[pymethods]
+impl UnitSpec {
+    #[new]
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (id, faction_idx, max_hp, name=String::new(), x=0.0, y=0.0, weapons=Vec::new(), speed=0.0, evasion=0.0, shields=0.0, armor=0.0, cover="None"))]
+    pub fn new(
+        id: UnitId,
+        faction_idx: u8,
+        max_hp: f32,
+        name: String,
+        x: f32,
+        y: f32,
+        weapons: Vec<WeaponSpec>,
+        speed: f32,
+        evasion: f32,
+        shields: f32,
+        armor: f32,
+        cover: &str,
+    ) -> PyResult<Self> {
+        if !(max_hp.is_finite() && max_hp > 0.0) {
+            return Err(invalid(format!("max_hp must be positive, got {}", max_hp)));
+        }
+        if !(x.is_finite() && y.is_finite()) {
+            return Err(invalid(format!("position must be finite, got ({}, {})", x, y)));
+        }
+        check_non_negative("speed", speed)?;
+        check_non_negative("shields", shields)?;
+        check_non_negative("armor", armor)?;
+        check_fraction("evasion", evasion as f64)?;
+        let name = if name.is_empty() { format!("Unit {}", id) } else { name };
+        Ok(Self {
+            id,
+            name,
+            faction_idx,
+            max_hp,
+            x,
+            y,
+            weapons,
+            speed,
+            evasion,
+            shields,
+            armor,
+            cover: parse_cover(cover)?,
+        })
+    }
+
+    pub fn add_weapon(&mut self, weapon: WeaponSpec) {
+        self.weapons.push(weapon);
+    }
+
+    #[getter]
+    fn cover(&self) -> String {
+        format!("{:?}", self.cover)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "UnitSpec(id={}, name={:?}, faction_idx={}, max_hp={}, weapons={})",
+            self.id,
+            self.name,
+            self.faction_idx,
+            self.max_hp,
+            self.weapons.len()
+        )
+    }
+}
+
+impl UnitSpec {
+    pub fn to_setup(&self) -> UnitSetup {
+        UnitSetup {
+            id: self.id,
+            name: self.name.clone(),
+            faction_idx: self.faction_idx,
+            max_hp: self.max_hp,
+            x: self.x,
+            y: self.y,
+            weapons: self.weapons.iter().map(WeaponSpec::to_setup).collect(),
+            speed: self.speed,
+            evasion: self.evasion,
+            shields: self.shields,
+            armor: self.armor,
+            cover: self.cover,
+        }
+    }
+
+    pub fn build(&self) -> CombatUnit {
+        self.to_setup().build()
+    }
+}
+
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct BattleConfig {
+    #[pyo3(get)]
+    pub width: f32,
+    #[pyo3(get)]
+    pub height: f32,
+    /// Seeds damage rolls; entropy-seeded when `None`.
+    #[pyo3(get)]
+    pub seed: Option<u64>,
+    #[pyo3(get)]
+    pub campaign_turn: Option<u64>,
+}
+
+#[pymethods]
+impl BattleConfig {
+    #[new]
+    #[pyo3(signature = (width=1000.0, height=1000.0, seed=None, campaign_turn=None))]
+    pub fn new(width: f32, height: f32, seed: Option<u64>, campaign_turn: Option<u64>) -> PyResult<Self> {
+        if !(width.is_finite() && width > 0.0 && height.is_finite() && height > 0.0) {
+            return Err(invalid(format!("battlefield must have positive size, got {} x {}", width, height)));
+        }
+        Ok(Self { width, height, seed, campaign_turn })
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "BattleConfig(width={}, height={}, seed={:?}, campaign_turn={:?})",
+            self.width, self.height, self.seed, self.campaign_turn
+        )
+    }
+}
+
+/// Global economy rules as plain fractions; `GlobalEconomicRules` holds the
+/// same values in fixed point.
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct EconomyConfig {
+    #[pyo3(get)]
+    pub orbit_discount: f64,
+    #[pyo3(get)]
+    pub garrison_discount: f64,
+    /// Fleets per planet before the navy penalty applies.
+    #[pyo3(get)]
+    pub navy_penalty_ratio: u32,
+    #[pyo3(get)]
+    pub navy_penalty_rate: f64,
+    #[pyo3(get)]
+    pub vassal_tribute_rate: f64,
+    #[pyo3(get)]
+    pub fleet_upkeep_scalar: f64,
+}
+
+#[pymethods]
+impl EconomyConfig {
+    #[new]
+    #[pyo3(signature = (orbit_discount=0.5, garrison_discount=0.25, navy_penalty_ratio=4, navy_penalty_rate=0.05, vassal_tribute_rate=0.2, fleet_upkeep_scalar=1.0))]
+    pub fn new(
+        orbit_discount: f64,
+        garrison_discount: f64,
+        navy_penalty_ratio: u32,
+        navy_penalty_rate: f64,
+        vassal_tribute_rate: f64,
+        fleet_upkeep_scalar: f64,
+    ) -> PyResult<Self> {
+        check_fraction("orbit_discount", orbit_discount)?;
+        check_fraction("garrison_discount", garrison_discount)?;
+        check_fraction("navy_penalty_rate", navy_penalty_rate)?;
+        check_fraction("vassal_tribute_rate", vassal_tribute_rate)?;
+        check_non_negative("fleet_upkeep_scalar", fleet_upkeep_scalar as f32)?;
+        if navy_penalty_ratio == 0 {
+            return Err(invalid("navy_penalty_ratio must be at least 1".to_string()));
+        }
+        Ok(Self {
+            orbit_discount,
+            garrison_discount,
+            navy_penalty_ratio,
+            navy_penalty_rate,
+            vassal_tribute_rate,
+            fleet_upkeep_scalar,
+        })
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "EconomyConfig(orbit_discount={}, garrison_discount={}, navy_penalty_ratio={}, navy_penalty_rate={}, vassal_tribute_rate={}, fleet_upkeep_scalar={})",
+            self.orbit_discount,
+            self.garrison_discount,
+            self.navy_penalty_ratio,
+            self.navy_penalty_rate,
+            self.vassal_tribute_rate,
+            self.fleet_upkeep_scalar
+        )
+    }
+}
+
+impl EconomyConfig {
+    pub fn to_rules(&self) -> GlobalEconomicRules {
+        let scaled = |v: f64| (v * SCALE_FACTOR as f64).round() as i128;
+        GlobalEconomicRules {
+            orbit_discount_scaled: scaled(self.orbit_discount),
+            garrison_discount_scaled: scaled(self.garrison_discount),
+            navy_penalty_ratio: self.navy_penalty_ratio,
+            navy_penalty_rate_scaled: scaled(self.navy_penalty_rate),
+            vassal_tribute_rate_scaled: scaled(self.vassal_tribute_rate),
+            fleet_upkeep_scalar_scaled: scaled(self.fleet_upkeep_scalar),
+        }
+    }
+}
@@ -0,0 +1,81 @@
+//! `RustDataPacks`: base game data and mod overlays, merged in load order
+//! into a `RustDataRegistry` that the auditor, combat and economy engines
+//! read from.
+
+use crate::registry::RustDataRegistry;
+use parking_lot::RwLock;
+use pyo3::prelude::*;
+use std::path::Path;
+use void_reckoning_auditor::datapack::{DataPack, DataPackLoader, MergeReport};
+use void_reckoning_shared::errors::EngineError;
+
+#[pyclass(frozen)]
+#[derive(Default)]
+pub struct RustDataPacks {
+    loader: RwLock<DataPackLoader>,
+}
+
+fn report_json(report: &MergeReport) -> PyResult<String> {
+    serde_json::to_string(report).map_err(|e| PyErr::from(EngineError::json(e)))
+}
+
+#[pymethods]
+impl RustDataPacks {
+    #[new]
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a pack (JSON `{"id", "version", "requires", "registries"}`)
+    /// after those loaded so far; the first pack is the base game. Adding
+    /// a pack id again replaces it where it stands. Returns the id.
+    fn add(&self, pack_json: &str) -> PyResult<String> {
+        let pack = DataPack::from_json(pack_json)?;
+        let id = pack.id.clone();
+        self.loader.write().push(pack);
+        Ok(id)
+    }
+
+    /// Adds a pack directory: `pack.json` plus a `<registry>.json` per
+    /// registry it changes. Returns the id.
+    fn add_dir(&self, py: Python<'_>, path: &str) -> PyResult<String> {
+        let pack = py.allow_threads(|| DataPack::from_dir(Path::new(path)))?;
+        let id = pack.id.clone();
+        self.loader.write().push(pack);
+        Ok(id)
+    }
+
+    fn remove(&self, pack_id: &str) -> bool {
+        self.loader.write().remove(pack_id).is_some()
+    }
+
+    /// Pack ids in load order.
+    fn pack_ids(&self) -> Vec<String> {
+        self.loader.read().packs().iter().map(|p| p.id.clone()).collect()
+    }
+
+    /// Merges the packs without installing them; returns the JSON
+    /// `MergeReport` (conflicts and which packs touched each entry).
+    fn check(&self, py: Python<'_>) -> PyResult<String> {
+        let (_, report) = py.allow_threads(|| self.loader.read().merge())?;
+        report_json(&report)
+    }
+
+    /// Merges the packs into `registry`, replacing every registry in it;
+    /// only registries whose content changed bump its version. Pass the
+    /// registry to the engines again to pick up the merge. Returns the
+    /// JSON `MergeReport`.
+    fn apply(&self, py: Python<'_>, registry: &RustDataRegistry) -> PyResult<String> {
+        let (merged, report) = py.allow_threads(|| self.loader.read().merge())?;
+        registry.install(&merged);
+        report_json(&report)
+    }
+
+    fn __len__(&self) -> usize {
+        self.loader.read().packs().len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("RustDataPacks(packs={:?})", self.pack_ids())
+    }
+}
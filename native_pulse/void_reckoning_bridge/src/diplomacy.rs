@@ -0,0 +1,153 @@
+//! `RustDiplomacyEngine`: treaties, opinion and wars, with helpers that
+//! push their effect into a combat engine.
+
+use crate::RustCombatEngine;
+use parking_lot::RwLock;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use void_reckoning_combat::resolve::BattleOutcome;
+use void_reckoning_diplomacy::{DiplomacyEngine, DiplomacySnapshot, OpinionModifier, TreatyKind};
+use void_reckoning_shared::errors::EngineError;
+use void_reckoning_shared::ids::FactionId;
+use void_reckoning_shared::{CorrelationContext, EventLog};
+
+#[pyclass(frozen)]
+pub struct RustDiplomacyEngine {
+    pub(crate) inner: RwLock<DiplomacyEngine>,
+}
+
+fn treaty_kind(kind: &str) -> PyResult<TreatyKind> {
+    TreatyKind::parse(kind).ok_or_else(|| PyValueError::new_err(format!("Unknown treaty kind: {}", kind)))
+}
+
+#[pymethods]
+impl RustDiplomacyEngine {
+    #[new]
+    fn new() -> Self {
+        Self { inner: RwLock::new(DiplomacyEngine::new()) }
+    }
+
+    /// Restores an engine from `to_json` output.
+    #[staticmethod]
+    fn from_json(snapshot_json: &str) -> PyResult<Self> {
+        let snapshot: DiplomacySnapshot = serde_json::from_str(snapshot_json).map_err(|e| PyErr::from(EngineError::json(e)))?;
+        Ok(Self { inner: RwLock::new(DiplomacyEngine::from_snapshot(snapshot)) })
+    }
+
+    fn to_json(&self) -> PyResult<String> {
+        serde_json::to_string(self.inner.read().snapshot()).map_err(|e| PyErr::from(EngineError::json(e)))
+    }
+
+    #[doc = state_hash_doc!()]
+    fn state_hash(&self) -> u64 {
+        self.inner.read().state_hash()
+    }
+
+    #[pyo3(signature = (capacity=None))]
+    fn enable_event_logging(&self, capacity: Option<usize>) -> EventLog {
+        let log = EventLog::with_capacity(capacity);
+        self.inner.write().set_event_log(log.clone());
+        log
+    }
+
+    fn set_correlation_context(&self, context: &CorrelationContext) {
+        self.inner.write().set_correlation_context(context.clone());
+    }
+
+    #[getter]
+    fn turn(&self) -> u64 {
+        self.inner.read().turn()
+    }
+
+    /// Kinds: `non_aggression` (or `nap`), `alliance`, `vassalage`
+    /// (`from` is the overlord) and `embargo` (`from` refuses trade).
+    /// Raises ValueError if the treaty can't be signed.
+    fn sign_treaty(&self, kind: &str, from: &str, to: &str) -> PyResult<()> {
+        let kind = treaty_kind(kind)?;
+        self.inner.write().sign_treaty(kind, from, to).map_err(PyValueError::new_err)
+    }
+
+    /// Returns False if there was no such treaty.
+    fn break_treaty(&self, kind: &str, from: &str, to: &str) -> PyResult<bool> {
+        let kind = treaty_kind(kind)?;
+        Ok(self.inner.write().break_treaty(kind, from, to))
+    }
+
+    fn has_treaty(&self, kind: &str, a: &str, b: &str) -> PyResult<bool> {
+        let kind = treaty_kind(kind)?;
+        Ok(self.inner.read().has_treaty(kind, a, b))
+    }
+
+    /// Every treaty in force, as JSON.
+    fn treaties(&self) -> PyResult<String> {
+        serde_json::to_string(self.inner.read().treaties()).map_err(|e| PyErr::from(EngineError::json(e)))
+    }
+
+    /// `value` shifts `from`'s opinion of `to`, shrinking by `decay` per
+    /// turn (0 = permanent).
+    #[pyo3(signature = (from, to, name, value, decay=0))]
+    fn add_opinion_modifier(&self, from: FactionId, to: FactionId, name: String, value: i32, decay: i32) {
+        self.inner.write().add_modifier(OpinionModifier { name, from, to, value, decay });
+    }
+
+    fn opinion(&self, from: &str, to: &str) -> i32 {
+        self.inner.read().opinion(from, to)
+    }
+
+    fn declare_war(&self, attacker: &str, defender: &str) -> PyResult<()> {
+        self.inner.write().declare_war(attacker, defender).map_err(PyValueError::new_err)
+    }
+
+    /// Ends the war and returns it (with its final score) as JSON, or None
+    /// if the two weren't at war.
+    fn make_peace(&self, a: &str, b: &str) -> PyResult<Option<String>> {
+        self.inner
+            .write()
+            .make_peace(a, b)
+            .map(|war| serde_json::to_string(&war).map_err(|e| PyErr::from(EngineError::json(e))))
+            .transpose()
+    }
+
+    fn at_war(&self, a: &str, b: &str) -> bool {
+        self.inner.read().at_war(a, b)
+    }
+
+    fn war_score(&self, faction: &str, enemy: &str) -> i32 {
+        self.inner.read().war_score(faction, enemy)
+    }
+
+    /// Everything between `a` and `b` as a JSON `Relation`.
+    fn relation(&self, a: &str, b: &str) -> PyResult<String> {
+        serde_json::to_string(&self.inner.read().relation(a, b)).map_err(|e| PyErr::from(EngineError::json(e)))
+    }
+
+    /// Scores a `BattleOutcome` (JSON) for the wars fought in it;
+    /// `roster[i]` names combat faction `i`. Returns
+    /// `(attacker, defender, score_change)` per war touched.
+    fn record_battle(&self, outcome_json: &str, roster: Vec<FactionId>) -> PyResult<Vec<(FactionId, FactionId, i32)>> {
+        let outcome: BattleOutcome = serde_json::from_str(outcome_json).map_err(|e| PyErr::from(EngineError::json(e)))?;
+        Ok(self.inner.write().record_battle(&outcome, &roster))
+    }
+
+    /// Sets which of `engine`'s factions (named by `roster`) are allied.
+    fn apply_to_combat(&self, engine: &RustCombatEngine, roster: Vec<FactionId>) {
+        let hostility = self.inner.read().hostility(&roster);
+        engine.engine().set_hostility(hostility);
+    }
+
+    /// Moves to the next turn and decays opinion. `RustTurnEngine` does
+    /// this itself when given the engine.
+    fn advance_turn(&self) {
+        self.inner.write().advance_turn();
+    }
+
+    fn __repr__(&self) -> String {
+        let engine = self.inner.read();
+        format!(
+            "RustDiplomacyEngine(turn={}, treaties={}, wars={})",
+            engine.turn(),
+            engine.treaties().len(),
+            engine.wars().len()
+        )
+    }
+}
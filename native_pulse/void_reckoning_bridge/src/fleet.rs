@@ -0,0 +1,189 @@
+//! `RustFleetManager`: persistent fleets, their movement and logistics, and
+//! the battles fought where hostile fleets meet.
+
+use crate::diplomacy::RustDiplomacyEngine;
+use crate::{RustCombatEngine, RustPathfinder};
+use parking_lot::RwLock;
+use pyo3::exceptions::{PyKeyError, PyValueError};
+use pyo3::prelude::*;
+use std::collections::{BTreeMap, HashMap};
+use void_reckoning_fleet::{BattleRoster, Fleet, FleetEngine, FleetSnapshot, LogisticsRules};
+use void_reckoning_shared::errors::EngineError;
+use void_reckoning_shared::{CorrelationContext, EventLog};
+
+#[pyclass(frozen)]
+pub struct RustFleetManager {
+    pub(crate) inner: RwLock<FleetEngine>,
+}
+
+fn to_json(value: &impl serde::Serialize) -> PyResult<String> {
+    serde_json::to_string(value).map_err(|e| PyErr::from(EngineError::json(e)))
+}
+
+fn from_json<T: serde::de::DeserializeOwned>(json: &str) -> PyResult<T> {
+    serde_json::from_str(json).map_err(|e| PyErr::from(EngineError::json(e)))
+}
+
+fn unknown_fleet(id: &str) -> PyErr {
+    PyKeyError::new_err(format!("Unknown fleet: {}", id))
+}
+
+/// Runs `f` with the hostility rule: `diplomacy`'s if given, else every
+/// other faction is an enemy.
+pub(crate) fn with_hostility<R>(diplomacy: Option<&RustDiplomacyEngine>, f: impl FnOnce(&dyn Fn(&str, &str) -> bool) -> R) -> R {
+    match diplomacy {
+        Some(diplomacy) => {
+            let diplomacy = diplomacy.inner.read();
+            f(&|a, b| diplomacy.hostile(a, b))
+        }
+        None => f(&|a, b| a != b),
+    }
+}
+
+#[pymethods]
+impl RustFleetManager {
+    /// `rules_json` is a `LogisticsRules` object; defaults apply if omitted.
+    #[new]
+    #[pyo3(signature = (rules_json=None))]
+    fn new(rules_json: Option<&str>) -> PyResult<Self> {
+        let rules: LogisticsRules = rules_json.map(from_json).transpose()?.unwrap_or_default();
+        Ok(Self { inner: RwLock::new(FleetEngine::new(rules)) })
+    }
+
+    /// Restores a manager from `to_json` output.
+    #[staticmethod]
+    fn from_json(snapshot_json: &str) -> PyResult<Self> {
+        let snapshot: FleetSnapshot = from_json(snapshot_json)?;
+        Ok(Self { inner: RwLock::new(FleetEngine::from_snapshot(snapshot)) })
+    }
+
+    fn to_json(&self) -> PyResult<String> {
+        to_json(&self.inner.read().snapshot())
+    }
+
+    #[doc = state_hash_doc!()]
+    fn state_hash(&self) -> u64 {
+        self.inner.read().state_hash()
+    }
+
+    #[pyo3(signature = (capacity=None))]
+    fn enable_event_logging(&self, capacity: Option<usize>) -> EventLog {
+        let log = EventLog::with_capacity(capacity);
+        self.inner.write().set_event_log(log.clone());
+        log
+    }
+
+    fn set_correlation_context(&self, context: &CorrelationContext) {
+        self.inner.write().set_correlation_context(context.clone());
+    }
+
+    #[getter]
+    fn turn(&self) -> u64 {
+        self.inner.read().turn()
+    }
+
+    /// Adds (or replaces) a fleet from JSON. Ship stacks use the combat
+    /// `UnitSetup` layout as their template.
+    fn add_fleet(&self, fleet_json: &str) -> PyResult<()> {
+        let fleet: Fleet = from_json(fleet_json)?;
+        self.inner.write().add_fleet(fleet);
+        Ok(())
+    }
+
+    fn remove_fleet(&self, fleet_id: &str) -> bool {
+        self.inner.write().remove_fleet(fleet_id).is_some()
+    }
+
+    /// The fleet as JSON, or None if there is no such fleet.
+    fn fleet(&self, fleet_id: &str) -> PyResult<Option<String>> {
+        self.inner.read().fleet(fleet_id).map(to_json).transpose()
+    }
+
+    /// Every fleet, in id order, as a JSON array.
+    fn fleets(&self) -> PyResult<String> {
+        to_json(&self.inner.read().fleets().collect::<Vec<_>>())
+    }
+
+    /// Routes the fleet to `destination` and returns the route's cost.
+    /// Raises `PathNotFound` if it can't get there.
+    fn order_move(&self, py: Python<'_>, fleet_id: &str, destination: &str, pathfinder: &RustPathfinder) -> PyResult<f32> {
+        py.allow_threads(|| {
+            let mut engine = self.inner.write();
+            let fleet = engine.fleet_mut(fleet_id).ok_or_else(|| unknown_fleet(fleet_id))?;
+            Ok(fleet.order_move(destination, &pathfinder.inner.read())?)
+        })
+    }
+
+    fn halt(&self, fleet_id: &str) -> PyResult<()> {
+        let mut engine = self.inner.write();
+        engine.fleet_mut(fleet_id).ok_or_else(|| unknown_fleet(fleet_id))?.halt();
+        Ok(())
+    }
+
+    /// Moves fleets, applies supply, fuel and attrition, and returns the
+    /// `FleetTurnReport` as JSON. `system_owners` maps systems to the
+    /// faction owning them; fleets resupply only in their own.
+    #[pyo3(signature = (system_owners, diplomacy=None))]
+    fn advance_turn(&self, py: Python<'_>, system_owners: HashMap<String, String>, diplomacy: Option<&RustDiplomacyEngine>) -> PyResult<String> {
+        let owners: BTreeMap<String, String> = system_owners.into_iter().collect();
+        let report = py.allow_threads(|| with_hostility(diplomacy, |hostile| self.inner.write().advance_turn(&owners, hostile)));
+        to_json(&report)
+    }
+
+    /// Systems where hostile fleets meet, as a JSON array.
+    #[pyo3(signature = (diplomacy=None))]
+    fn engagements(&self, diplomacy: Option<&RustDiplomacyEngine>) -> PyResult<String> {
+        to_json(&with_hostility(diplomacy, |hostile| self.inner.read().engagements(hostile)))
+    }
+
+    /// Builds the battle at `system` for stepping from Python. Returns the
+    /// engine and the JSON `BattleRoster` to hand back to `apply_battle`.
+    #[pyo3(signature = (system, seed, diplomacy=None))]
+    fn start_battle(&self, system: &str, seed: u64, diplomacy: Option<&RustDiplomacyEngine>) -> PyResult<(RustCombatEngine, String)> {
+        let (engine, roster) = with_hostility(diplomacy, |hostile| {
+            let fleets = self.inner.read();
+            let engagement = fleets
+                .engagements(hostile)
+                .into_iter()
+                .find(|e| e.system == system)
+                .ok_or_else(|| PyValueError::new_err(format!("No engagement at {}", system)))?;
+            Ok::<_, PyErr>(fleets.build_battle(&engagement, seed, hostile))
+        })?;
+        Ok((RustCombatEngine::from_engine(engine), to_json(&roster)?))
+    }
+
+    /// Writes a battle from `start_battle` back into the fleets; returns
+    /// the ids of fleets destroyed.
+    fn apply_battle(&self, roster_json: &str, engine: &RustCombatEngine) -> PyResult<Vec<String>> {
+        let roster: BattleRoster = from_json(roster_json)?;
+        let battle = engine.engine();
+        Ok(self.inner.write().apply_battle(&roster, &battle.state.units))
+    }
+
+    /// Fights every engagement to completion without the GIL and returns
+    /// `[{"roster": ..., "outcome": ...}]` as JSON. With `diplomacy`, allies
+    /// hold fire and the results count toward war score.
+    #[pyo3(signature = (seed, diplomacy=None))]
+    fn resolve_engagements(&self, py: Python<'_>, seed: u64, diplomacy: Option<&RustDiplomacyEngine>) -> PyResult<String> {
+        let results = py.allow_threads(|| {
+            let results = with_hostility(diplomacy, |hostile| self.inner.write().resolve_engagements(seed, hostile));
+            if let Some(diplomacy) = diplomacy {
+                let mut diplomacy = diplomacy.inner.write();
+                for (roster, outcome) in &results {
+                    diplomacy.record_battle(outcome, &roster.factions);
+                }
+            }
+            results
+        });
+        let results: Vec<_> = results
+            .into_iter()
+            .map(|(roster, outcome)| serde_json::json!({ "roster": roster, "outcome": outcome }))
+            .collect();
+        to_json(&results)
+    }
+
+    fn __repr__(&self) -> String {
+        let engine = self.inner.read();
+        format!("RustFleetManager(turn={}, fleets={})", engine.turn(), engine.fleets().count())
+    }
+}
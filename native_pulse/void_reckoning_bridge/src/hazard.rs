@@ -0,0 +1,147 @@
+//! `RustHazardLayer`: ion storms, nebulae and other space weather over
+//! regions of the map, set on the pathfinder and trade routes as named
+//! modifiers. A `RustTurnEngine` built with one advances it after every
+//! turn; it can also be advanced on its own.
+
+use crate::{RustCombatEngine, RustEconomyEngine, RustPathfinder};
+use parking_lot::RwLock;
+use pyo3::prelude::*;
+use void_reckoning_scenario::{HazardLayer, HazardSnapshot, HazardTable};
+use void_reckoning_shared::errors::EngineError;
+use void_reckoning_shared::{CorrelationContext, EventLog};
+
+#[pyclass(frozen)]
+pub struct RustHazardLayer {
+    pub(crate) inner: RwLock<HazardLayer>,
+}
+
+fn to_json(value: &impl serde::Serialize) -> PyResult<String> {
+    serde_json::to_string(value).map_err(|e| PyErr::from(EngineError::json(e)))
+}
+
+fn from_json<T: serde::de::DeserializeOwned>(json: &str) -> PyResult<T> {
+    serde_json::from_str(json).map_err(|e| PyErr::from(EngineError::json(e)))
+}
+
+#[pymethods]
+impl RustHazardLayer {
+    /// `table_json` is a `HazardTable`: `{"chance": 0.2, "kinds": [{"id",
+    /// "weight", "turns", "lane_factor", "accuracy_factor", "trade_factor",
+    /// "spread", "max_systems"}]}`; factors default to 1. The same seed and
+    /// map give the same weather.
+    #[new]
+    #[pyo3(signature = (table_json, seed=0))]
+    fn new(table_json: &str, seed: u64) -> PyResult<Self> {
+        let table: HazardTable = from_json(table_json)?;
+        Ok(Self { inner: RwLock::new(HazardLayer::new(table, seed)) })
+    }
+
+    /// Reads a hazard table from a JSON file.
+    #[staticmethod]
+    #[pyo3(signature = (path, seed=0))]
+    fn load(py: Python<'_>, path: &str, seed: u64) -> PyResult<Self> {
+        let table = py
+            .allow_threads(|| std::fs::read_to_string(path))
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("IO error: {}", e)))?;
+        Self::new(&table, seed)
+    }
+
+    /// Restores a layer, including its random state and the hazards in
+    /// effect, from `to_json` output. Call `apply` to set their modifiers
+    /// on a fresh pathfinder.
+    #[staticmethod]
+    fn from_json(snapshot_json: &str) -> PyResult<Self> {
+        let snapshot: HazardSnapshot = from_json(snapshot_json)?;
+        Ok(Self { inner: RwLock::new(HazardLayer::from_snapshot(snapshot)) })
+    }
+
+    fn to_json(&self) -> PyResult<String> {
+        to_json(&self.inner.read().snapshot())
+    }
+
+    #[doc = state_hash_doc!()]
+    fn state_hash(&self) -> u64 {
+        self.inner.read().state_hash()
+    }
+
+    /// Events are written here; without a log of its own the layer writes
+    /// into the turn engine's.
+    #[pyo3(signature = (capacity=None))]
+    fn enable_event_logging(&self, capacity: Option<usize>) -> EventLog {
+        let log = EventLog::with_capacity(capacity);
+        self.inner.write().set_event_log(log.clone());
+        log
+    }
+
+    fn set_correlation_context(&self, context: &CorrelationContext) {
+        self.inner.write().set_correlation_context(context.clone());
+    }
+
+    fn table(&self) -> PyResult<String> {
+        to_json(self.inner.read().table())
+    }
+
+    fn set_table(&self, table_json: &str) -> PyResult<()> {
+        let table: HazardTable = from_json(table_json)?;
+        self.inner.write().set_table(table);
+        Ok(())
+    }
+
+    /// Hazards in effect, as JSON.
+    fn active(&self) -> PyResult<String> {
+        to_json(&self.inner.read().active())
+    }
+
+    /// Dissipates, spreads and forms hazards for `turn`, sets their
+    /// modifiers on the pathfinder (and the economy's trade routes, when
+    /// given) and returns the `HazardReport` as JSON.
+    #[pyo3(signature = (turn, pathfinder, economy=None))]
+    fn advance(&self, py: Python<'_>, turn: u64, pathfinder: &RustPathfinder, economy: Option<&RustEconomyEngine>) -> PyResult<String> {
+        let report = py.allow_threads(|| {
+            let mut economy = economy.map(|e| e.state.write());
+            let mut topology = pathfinder.inner.write();
+            self.inner.write().advance(turn, &mut topology, economy.as_mut().map(|e| &mut e.trade_manager))
+        });
+        to_json(&report)
+    }
+
+    /// Sets the active hazards' modifiers again, e.g. after
+    /// `sync_topology` rebuilt the map.
+    #[pyo3(signature = (pathfinder, economy=None))]
+    fn apply(&self, py: Python<'_>, pathfinder: &RustPathfinder, economy: Option<&RustEconomyEngine>) {
+        py.allow_threads(|| {
+            let mut economy = economy.map(|e| e.state.write());
+            let mut topology = pathfinder.inner.write();
+            self.inner.read().apply(&mut topology, economy.as_mut().map(|e| &mut e.trade_manager));
+        });
+    }
+
+    fn lane_factor(&self, from: &str, to: &str) -> f32 {
+        self.inner.read().lane_factor(from, to)
+    }
+
+    fn accuracy_factor(&self, system: &str) -> f32 {
+        self.inner.read().accuracy_factor(system)
+    }
+
+    fn trade_factor(&self, from: &str, to: &str) -> f32 {
+        self.inner.read().trade_factor(from, to)
+    }
+
+    /// Scales the accuracy of every weapon in `battle` by the weather over
+    /// `system`; returns the factor.
+    fn apply_to_battle(&self, system: &str, battle: &RustCombatEngine) -> f32 {
+        let factor = self.inner.read().accuracy_factor(system);
+        battle.engine().scale_accuracy(factor);
+        factor
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.read().active().len()
+    }
+
+    fn __repr__(&self) -> String {
+        let layer = self.inner.read();
+        format!("RustHazardLayer(kinds={}, active={})", layer.table().kinds.len(), layer.active().len())
+    }
+}
@@ -0,0 +1,66 @@
+//! Version, capability and build-info introspection, so the host can refuse
+//! an incompatible native build up front.
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use serde_json::json;
+use void_reckoning_shared::pyvalue::value_to_py;
+
+/// Describes this native build:
+///
+/// - `version`: the bridge version.
+/// - `crates`: version of every engine crate linked in.
+/// - `features`: Cargo features enabled per crate (e.g. combat's
+///   `parallel`); capabilities not listed were not compiled in.
+/// - `schemas`: layout version of each subsystem's serialized data, bumped
+///   on incompatible changes.
+/// - `build`: compiler, target triple and profile.
+#[pyfunction]
+pub fn get_engine_info(py: Python<'_>) -> PyResult<Bound<'_, PyDict>> {
+    let info = json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "crates": {
+            "bridge": env!("CARGO_PKG_VERSION"),
+            "shared": void_reckoning_shared::VERSION,
+            "pathfinder": void_reckoning_pathfinder::VERSION,
+            "combat": void_reckoning_combat::VERSION,
+            "economy": void_reckoning_economy::VERSION,
+            "auditor": void_reckoning_auditor::VERSION,
+            "orchestrator": void_reckoning_orchestrator::VERSION,
+            "diplomacy": void_reckoning_diplomacy::VERSION,
+            "fleet": void_reckoning_fleet::VERSION,
+            "ai": void_reckoning_ai::VERSION,
+            "scenario": void_reckoning_scenario::VERSION,
+            "colony": void_reckoning_colony::VERSION,
+            "world": void_reckoning_world::VERSION,
+            "tournament": void_reckoning_tournament::VERSION,
+        },
+        "features": {
+            "combat": void_reckoning_combat::enabled_features(),
+            "economy": void_reckoning_economy::enabled_features(),
+        },
+        "schemas": {
+            "pathfinder.topology": void_reckoning_pathfinder::SCHEMA_VERSION,
+            "combat.battle": void_reckoning_combat::SCHEMA_VERSION,
+            "economy": void_reckoning_economy::SCHEMA_VERSION,
+            "auditor.report": void_reckoning_auditor::SCHEMA_VERSION,
+            "orchestrator.turn_report": void_reckoning_orchestrator::SCHEMA_VERSION,
+            "diplomacy.snapshot": void_reckoning_diplomacy::SCHEMA_VERSION,
+            "fleet.snapshot": void_reckoning_fleet::SCHEMA_VERSION,
+            "ai.decision": void_reckoning_ai::SCHEMA_VERSION,
+            "scenario.script": void_reckoning_scenario::SCHEMA_VERSION,
+            "colony.snapshot": void_reckoning_colony::SCHEMA_VERSION,
+            "world.snapshot": void_reckoning_world::SCHEMA_VERSION,
+            "tournament.results": void_reckoning_tournament::SCHEMA_VERSION,
+            "observability.snapshot": void_reckoning_shared::SNAPSHOT_FORMAT_VERSION,
+            "campaign.archive": crate::campaign::FORMAT_VERSION,
+            "save.container": void_reckoning_shared::savegame::SAVE_FORMAT_VERSION,
+        },
+        "build": {
+            "rustc": env!("VR_RUSTC_VERSION"),
+            "target": env!("VR_BUILD_TARGET"),
+            "profile": env!("VR_BUILD_PROFILE"),
+        },
+    });
+    Ok(value_to_py(py, &info)?.into_bound(py).downcast_into::<PyDict>()?)
+}
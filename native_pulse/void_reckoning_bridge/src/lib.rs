@@ -1,76 +1,684 @@
 use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict};
 use serde_json::Value;
+use parking_lot::{Mutex, MutexGuard, RwLock};
 use std::collections::HashMap;
 use std::sync::Arc;
+use void_reckoning_shared::bus::EventBus;
+use void_reckoning_shared::columnar;
+use void_reckoning_shared::errors::EngineError;
+use void_reckoning_shared::ids::{EntityId, FactionId, SystemId, UnitId};
+use void_reckoning_shared::msgpack;
+use void_reckoning_shared::published::Published;
+use void_reckoning_shared::scope::PyContextScope;
+use void_reckoning_shared::simtime::SimClock;
+use void_reckoning_shared::EventLog;
+use reader::RustTopologyReader;
+use views::RustStateView;
+
+/// Flushes a log detached by `close()`, without holding the GIL.
+fn flush_log(py: Python<'_>, log: Option<EventLog>) {
+    if let Some(log) = log {
+        py.allow_threads(|| log.flush());
+    }
+}
+
+fn msgpack_error(e: String) -> PyErr {
+    EngineError::serialization("Msgpack", e).into()
+}
+
+/// Enables logging on an engine slot if needed and forwards it onto `bus`.
+fn attach_bus(slot: &mut Option<EventLog>, bus: &EventBus) -> EventLog {
+    let log = slot.get_or_insert_with(EventLog::new).clone();
+    bus.attach(&log);
+    log
+}
 
 // --- Pathfinder ---
-use void_reckoning_pathfinder::GraphTopology;
+use void_reckoning_pathfinder::{GraphTopology, LaneInfo, LaneKind, LaneMetrics, PathLimits, PathQuery, RouteOptions, RouteWeights, ZocRule};
 
-#[pyclass]
+#[derive(FromPyObject)]
+enum BatchQuery {
+    Profiled(String, String, Option<String>),
+    Pair(String, String),
+}
+
+/// Queries share a read lock taken without the GIL, so several threads can
+/// search at once; edits take the write lock and wait for them. Threads
+/// that shouldn't wait on edits at all search a `reader()` copy.
+#[pyclass(frozen)]
 pub struct RustPathfinder {
-    pub inner: GraphTopology,
+    pub inner: RwLock<GraphTopology>,
+    pub(crate) published: Published<RustStateView>,
 }
 
 #[pymethods]
 impl RustPathfinder {
+    /// With `undirected`, every lane added runs both ways.
     #[new]
-    pub fn new() -> Self {
+    #[pyo3(signature = (undirected=false))]
+    pub fn new(undirected: bool) -> Self {
         RustPathfinder {
-            inner: GraphTopology::new(),
+            inner: RwLock::new(if undirected { GraphTopology::undirected() } else { GraphTopology::new() }),
+            published: Published::default(),
         }
     }
 
-    fn add_node(&mut self, id: String, terrain: Option<String>) {
-        self.inner.add_node(id, terrain);
+    #[pyo3(signature = (id, terrain=None))]
+    fn add_node(&self, id: String, terrain: Option<String>) {
+        self.inner.write().add_node(id, terrain);
+    }
+
+    /// Registers a terrain kind `add_node` accepts by name; mods register
+    /// theirs before the profiles that price them.
+    fn register_terrain(&self, name: &str) {
+        self.inner.write().register_terrain(name);
+    }
+
+    /// Registers a movement profile usable wherever a `profile` is taken:
+    /// entering a node costs the lane's weight times `costs[terrain]` (1
+    /// when unlisted, `float("inf")` for impassable), e.g.
+    /// `register_profile("Amphibious", {"Water": 1.2, "Mountain": inf})`.
+    /// Raises `ValueError` for a built-in name, an unregistered terrain or
+    /// a negative cost.
+    fn register_profile(&self, name: &str, costs: std::collections::BTreeMap<String, f32>) -> PyResult<()> {
+        self.inner.write().register_profile(name, costs).map_err(pyo3::exceptions::PyValueError::new_err)?;
+        Ok(())
+    }
+
+    /// Adds a node at `(x, y)`, or moves an existing one there. Once
+    /// every node has a position, searches steer by straight-line distance.
+    #[pyo3(signature = (id, x, y, terrain=None))]
+    fn add_node_with_position(&self, id: String, x: f32, y: f32, terrain: Option<String>) {
+        self.inner.write().add_node_with_position(id, terrain, x, y);
+    }
+
+    fn position(&self, id: &str) -> Option<(f32, f32)> {
+        self.inner.read().position(id)
+    }
+
+    /// Sets the danger the `"Cautious"` profile pays to enter `id`, on
+    /// top of lane costs; False if there is no such node.
+    fn set_node_danger(&self, id: &str, danger: f32) -> bool {
+        self.inner.write().set_node_danger(id, danger)
+    }
+
+    fn node_danger(&self, id: &str) -> Option<f32> {
+        self.inner.read().node_danger(id)
+    }
+
+    /// Gives `id` to `owner`, or to nobody; its zone of control covers it
+    /// and its neighbours. False if there is no such node.
+    #[pyo3(signature = (id, owner=None))]
+    fn set_node_owner(&self, id: &str, owner: Option<FactionId>) -> bool {
+        self.inner.write().set_node_owner(id, owner)
+    }
+
+    fn node_owner(&self, id: &str) -> Option<String> {
+        self.inner.read().node_owner(id).map(str::to_string)
+    }
+
+    /// Contested systems are in every faction's zone of control.
+    #[pyo3(signature = (id, contested=true))]
+    fn set_contested(&self, id: &str, contested: bool) -> bool {
+        self.inner.write().set_contested(id, contested)
+    }
+
+    fn is_contested(&self, id: &str) -> bool {
+        self.inner.read().is_contested(id)
+    }
+
+    /// Systems in the zone of control of any `hostile` faction.
+    fn zone_of_control(&self, hostile: Vec<String>) -> Vec<String> {
+        self.inner.read().zone_of_control(&hostile)
+    }
+
+    /// Adds `u` -> `v`, and `v` -> `u` too if the pathfinder is undirected.
+    fn add_edge(&self, u: String, v: String, weight: f32) {
+        self.inner.write().add_edge(&u, &v, weight);
+    }
+
+    /// Adds a two-way lane between `u` and `v` in one call.
+    fn add_edge_bidirectional(&self, u: String, v: String, weight: f32) {
+        self.inner.write().add_edge_bidirectional(&u, &v, weight);
+    }
+
+    #[getter]
+    fn undirected(&self) -> bool {
+        self.inner.read().is_undirected()
+    }
+
+    /// Sets the cost of every `u` -> `v` edge without rebuilding the map;
+    /// on a lane with modifiers this is the unmodified cost. Returns how
+    /// many edges there were.
+    fn update_edge_weight(&self, u: &str, v: &str, weight: f32) -> usize {
+        self.inner.write().set_edge_weight(u, v, weight)
+    }
+
+    /// Removes every `u` -> `v` edge and its lane modifiers; returns how
+    /// many edges there were.
+    fn remove_edge(&self, u: &str, v: &str) -> usize {
+        self.inner.write().remove_edge(u, v)
+    }
+
+    /// Removes a system and every lane touching it; False if it wasn't there.
+    fn remove_node(&self, id: &str) -> bool {
+        self.inner.write().remove_node(id)
+    }
+
+    /// Multiplies the cost of the `u` -> `v` lane by `factor` on behalf of
+    /// `source`, replacing any factor `source` had there. Returns how many
+    /// edges the lane has (0 if it doesn't exist).
+    fn set_lane_modifier(&self, u: &str, v: &str, source: &str, factor: f32) -> usize {
+        self.inner.write().set_lane_modifier(u, v, source, factor)
+    }
+
+    /// Describes the `u` -> `v` lane: `kind` is `"Hyperlane"`, `"Wormhole"`
+    /// or `"Slipstream"`, `capacity` what congestion models may allow
+    /// through per turn. Returns how many edges it covers (0 if there's no
+    /// lane). Raises `ValueError` for an unknown kind.
+    #[pyo3(signature = (u, v, kind="Hyperlane", capacity=None, owner=None))]
+    fn set_lane_info(&self, u: &str, v: &str, kind: &str, capacity: Option<u32>, owner: Option<FactionId>) -> PyResult<usize> {
+        let info = LaneInfo { kind: lane_kind(kind)?, capacity, owner };
+        Ok(self.inner.write().set_lane_info(u, v, info))
+    }
+
+    /// `(kind, capacity, owner)` of the `u` -> `v` lane; None if there's
+    /// no lane.
+    fn lane_info(&self, u: &str, v: &str) -> Option<(String, Option<u32>, Option<FactionId>)> {
+        self.inner.read().lane_info(u, v).map(|info| (format!("{:?}", info.kind), info.capacity, info.owner))
+    }
+
+    /// Sets the travel time (None: the lane's cost) and risk that
+    /// `find_path(weights=...)` sees on the `u` -> `v` lane. Returns how
+    /// many edges it covers (0 if there's no lane). Raises ValueError on
+    /// a negative or non-finite time or risk.
+    #[pyo3(signature = (u, v, time=None, risk=0.0))]
+    fn set_lane_metrics(&self, u: &str, v: &str, time: Option<f32>, risk: f32) -> PyResult<usize> {
+        self.inner.write().set_lane_metrics(u, v, LaneMetrics { time, risk }).map_err(pyo3::exceptions::PyValueError::new_err)
+    }
+
+    /// `(time, risk)` of the `u` -> `v` lane; None if there's no lane.
+    fn lane_metrics(&self, u: &str, v: &str) -> Option<(Option<f32>, f32)> {
+        self.inner.read().lane_metrics(u, v).map(|m| (m.time, m.risk))
+    }
+
+    /// `(from, to)` of every lane of `kind`.
+    fn lanes_of_kind(&self, kind: &str) -> PyResult<Vec<(String, String)>> {
+        Ok(self.inner.read().lanes_of_kind(lane_kind(kind)?))
     }
 
-    fn add_edge(&mut self, u: String, v: String, weight: f32) {
-        self.inner.add_edge(&u, &v, weight);
+    /// `(from, to)` of every lane `faction` owns.
+    fn lanes_owned_by(&self, faction: &str) -> Vec<(String, String)> {
+        self.inner.read().lanes_owned_by(faction)
+    }
+
+    /// Takes every factor `source` set off its lanes; returns how many.
+    fn remove_lane_modifiers(&self, source: &str) -> usize {
+        self.inner.write().remove_lane_modifiers(source)
+    }
+
+    /// Every lane modifier as `(from, to, source, factor)`, in the order
+    /// they were first set.
+    fn lane_modifiers(&self) -> Vec<(SystemId, SystemId, String, f32)> {
+        self.inner.read().lane_modifiers().iter().map(|m| (m.from.clone(), m.to.clone(), m.source.clone(), m.factor)).collect()
+    }
+
+    /// The `u` -> `v` cost before lane modifiers; None if there's no lane.
+    fn base_weight(&self, u: &str, v: &str) -> Option<f32> {
+        self.inner.read().base_weight(u, v)
     }
     
-    fn clear(&mut self) {
-        self.inner.clear();
+    fn clear(&self) {
+        self.inner.write().clear();
+    }
+
+    /// Routes around the `avoid` systems and every system a
+    /// `blocked_factions` faction owns, by `system_owners` (system ->
+    /// faction) or, without it, the owners set with `set_node_owner`;
+    /// ValueError if neither names any owner. The start and end are never
+    /// avoided. `lane_kinds` (e.g. `["Wormhole"]`)
+    /// limits the route to those kinds of lane. `max_hops` and `max_cost`
+    /// (fuel) keep only routes within them, the cheapest of which is
+    /// returned. `weights` (`{"cost", "time", "risk"}`, missing ones 0)
+    /// trades lane cost against `set_lane_metrics` time and risk plus
+    /// system danger; the cost returned is then the weighted one.
+    #[pyo3(signature = (start, end, profile=None, avoid=None, blocked_factions=None, system_owners=None, lane_kinds=None, max_hops=None, max_cost=None, weights=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn find_path(
+        &self,
+        py: Python<'_>,
+        start: String,
+        end: String,
+        profile: Option<String>,
+        avoid: Option<Vec<String>>,
+        blocked_factions: Option<Vec<String>>,
+        system_owners: Option<HashMap<String, String>>,
+        lane_kinds: Option<Vec<String>>,
+        max_hops: Option<usize>,
+        max_cost: Option<f32>,
+        weights: Option<HashMap<String, f32>>,
+    ) -> PyResult<Option<(Vec<String>, f32)>> {
+        py.allow_threads(|| {
+            let topology = self.inner.read();
+            let options = route_options(&topology, avoid, blocked_factions, system_owners, lane_kinds, max_hops, max_cost, weights)?;
+            Ok(topology.find_path_with(&start, &end, profile, &options))
+        })
     }
 
+    /// How far along the cheapest route to `end` a fleet gets within
+    /// `max_hops` jumps and `max_cost` fuel: `(path, cost)` up to the last
+    /// system it reaches, which is `end` only if the fuel suffices. None
+    /// if there is no route at all.
+    #[pyo3(signature = (start, end, max_hops=None, max_cost=None, profile=None))]
+    fn find_path_partial(
+        &self,
+        py: Python<'_>,
+        start: String,
+        end: String,
+        max_hops: Option<usize>,
+        max_cost: Option<f32>,
+        profile: Option<String>,
+    ) -> Option<(Vec<String>, f32)> {
+        let limits = PathLimits { max_hops, max_cost };
+        py.allow_threads(|| self.inner.read().find_path_partial(&start, &end, profile, limits))
+    }
+
+    /// `find_path` with the reasons: `{"path", "cost", "hops"}`, one hop
+    /// per lane with `from`, `to`, `base_cost`, `modifiers` (`[source,
+    /// factor]` pairs), `lane_cost`, `terrain`, `terrain_multiplier`,
+    /// `danger`, `cost` and `cumulative`. None if there is no route.
     #[pyo3(signature = (start, end, profile=None))]
-    fn find_path(&self, start: String, end: String, profile: Option<String>) -> Option<(Vec<String>, f32)> {
-        self.inner.find_path(&start, &end, profile)
+    fn find_path_detailed<'py>(&self, py: Python<'py>, start: String, end: String, profile: Option<String>) -> PyResult<Option<Bound<'py, PyDict>>> {
+        let detailed = py.allow_threads(|| {
+            let topology = self.inner.read();
+            let (path, cost) = topology.find_path(&start, &end, profile.clone())?;
+            let hops = topology.explain_path(&path, profile)?;
+            Some(serde_json::json!({ "path": path, "cost": cost, "hops": hops }))
+        });
+        let Some(detailed) = detailed else {
+            return Ok(None);
+        };
+        Ok(Some(void_reckoning_shared::pyvalue::value_to_py(py, &detailed)?.into_bound(py).downcast_into::<PyDict>()?))
+    }
+
+    /// `find_path` honouring the `hostile` factions' zones of control:
+    /// with no `penalty` a route stops where it enters one, so it only
+    /// enters at its end; with one, each system entered in a zone costs
+    /// that much more.
+    #[pyo3(signature = (start, end, hostile, penalty=None, profile=None))]
+    fn find_path_zoc(
+        &self,
+        py: Python<'_>,
+        start: String,
+        end: String,
+        hostile: Vec<String>,
+        penalty: Option<f32>,
+        profile: Option<String>,
+    ) -> Option<(Vec<String>, f32)> {
+        let rule = penalty.map_or(ZocRule::EndsMovement, ZocRule::Penalty);
+        py.allow_threads(|| self.inner.read().find_path_zoc(&start, &end, profile, &hostile, rule))
+    }
+
+    /// One `(path, cost)` from `start` through `waypoints` in order to
+    /// `end`, for patrols and supply runs; None if any leg has no route.
+    #[pyo3(signature = (start, waypoints, end, profile=None))]
+    fn find_route_via(&self, py: Python<'_>, start: String, waypoints: Vec<String>, end: String, profile: Option<String>) -> Option<(Vec<String>, f32)> {
+        py.allow_threads(|| self.inner.read().find_route_via(&start, &waypoints, &end, profile))
+    }
+
+    /// `(system, score)` ranked by betweenness centrality, highest (the
+    /// worst choke points) first; scores run 0 to 1. `top` keeps the first
+    /// few.
+    #[pyo3(signature = (top=None))]
+    fn betweenness_centrality(&self, py: Python<'_>, top: Option<usize>) -> Vec<(String, f32)> {
+        let mut ranked = py.allow_threads(|| self.inner.read().betweenness_centrality());
+        ranked.truncate(top.unwrap_or(usize::MAX));
+        ranked
+    }
+
+    /// `{system: component id}`: systems share an id when open lanes join
+    /// them either way, so systems a collapse cut off get a new one.
+    fn connected_components(&self, py: Python<'_>) -> HashMap<String, usize> {
+        py.allow_threads(|| self.inner.read().connected_components())
+    }
+
+    /// Whether open lanes lead from `a` to `b`, in their direction.
+    fn is_reachable(&self, py: Python<'_>, a: String, b: String) -> bool {
+        py.allow_threads(|| self.inner.read().is_reachable(&a, &b))
+    }
+
+    /// `(system, cost)` for every system reachable from `start` for at most
+    /// `max_cost`, cheapest first, the start included at 0.
+    #[pyo3(signature = (start, max_cost, profile=None))]
+    fn reachable_within(&self, py: Python<'_>, start: String, max_cost: f32, profile: Option<String>) -> Vec<(String, f32)> {
+        py.allow_threads(|| self.inner.read().reachable_within(&start, max_cost, profile))
+    }
+
+    /// Up to `k` loopless `(path, cost)` routes, cheapest first; empty
+    /// when there are none.
+    #[pyo3(signature = (start, end, k, profile=None))]
+    fn find_k_paths(&self, py: Python<'_>, start: String, end: String, k: usize, profile: Option<String>) -> Vec<(Vec<String>, f32)> {
+        py.allow_threads(|| self.inner.read().find_k_paths(&start, &end, k, profile))
+    }
+
+    /// Like `find_path`, but raises `PathNotFound` instead of returning `None`.
+    #[pyo3(signature = (start, end, profile=None))]
+    fn require_path(&self, py: Python<'_>, start: String, end: String, profile: Option<String>) -> PyResult<(Vec<String>, f32)> {
+        py.allow_threads(|| self.inner.read().find_path(&start, &end, profile))
+            .ok_or_else(|| EngineError::PathNotFound { start, end }.into())
+    }
+
+    /// Resolves many `(start, end)` or `(start, end, profile)` queries in
+    /// one call, in parallel and without holding the GIL; `profile` applies
+    /// to queries that don't name their own. Results line up with
+    /// `queries`; unreachable pairs yield `None`.
+    #[pyo3(signature = (queries, profile=None))]
+    fn find_paths_batch(&self, py: Python<'_>, queries: Vec<BatchQuery>, profile: Option<String>) -> Vec<Option<(Vec<String>, f32)>> {
+        let queries = path_queries(queries, profile);
+        py.allow_threads(|| self.inner.read().find_paths_batch(&queries))
+    }
+
+    /// A `RustTopologyReader` on a copy of the topology as it is now, for
+    /// AI and trade threads to search without waiting on edits here (or
+    /// holding them up). Copies the whole graph: take one per turn, not
+    /// one per query.
+    fn reader(&self, py: Python<'_>) -> RustTopologyReader {
+        RustTopologyReader::new(py.allow_threads(|| self.inner.read().clone()))
     }
     
-    fn sync_topology(&mut self, systems: Vec<(String, Vec<String>)>) {
-        self.inner.clear();
+    /// Nodes (terrain, positions, danger), edges, weights and lane
+    /// modifiers as JSON, for save games.
+    fn to_json(&self, py: Python<'_>) -> PyResult<String> {
+        py.allow_threads(|| self.inner.read().to_json()).map_err(|e| PyErr::from(EngineError::json(e)))
+    }
+
+    /// A pathfinder holding the topology `to_json` wrote.
+    #[staticmethod]
+    fn from_json(json: &str) -> PyResult<Self> {
+        let pathfinder = Self::default();
+        pathfinder.restore(json)?;
+        Ok(pathfinder)
+    }
+
+    /// Replaces the topology with the one `to_json` wrote, in place, so
+    /// engines sharing this pathfinder see the loaded map. Raises
+    /// `SerializationError` and leaves the topology alone on bad JSON.
+    fn restore(&self, json: &str) -> PyResult<()> {
+        let topology = GraphTopology::from_json(json).map_err(|e| PyErr::from(EngineError::json(e)))?;
+        *self.inner.write() = topology;
+        Ok(())
+    }
+
+    /// Writes `to_json` to `path`.
+    fn save(&self, py: Python<'_>, path: String) -> PyResult<()> {
+        let json = self.to_json(py)?;
+        py.allow_threads(|| std::fs::write(&path, json))
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("Save error: {}", e)))
+    }
+
+    /// The topology as Graphviz DOT, with terrain and current weights.
+    fn export_dot(&self) -> String {
+        self.inner.read().export_dot()
+    }
+
+    /// The topology as GraphML, with terrain, positions and weights.
+    fn export_graphml(&self) -> String {
+        self.inner.read().export_graphml()
+    }
+
+    /// Writes `export_dot` (`format="dot"`) or `export_graphml`
+    /// (`format="graphml"`) to `path`, to open in Graphviz or yEd.
+    #[pyo3(signature = (path, format="dot"))]
+    fn export_graph(&self, py: Python<'_>, path: String, format: &str) -> PyResult<()> {
+        let text = match format {
+            "dot" => self.export_dot(),
+            "graphml" => self.export_graphml(),
+            _ => return Err(pyo3::exceptions::PyValueError::new_err(format!("unknown export format '{}'; expected 'dot' or 'graphml'", format))),
+        };
+        py.allow_threads(|| std::fs::write(&path, text))
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("Save error: {}", e)))
+    }
+
+    /// Replaces the topology with the one `save` wrote to `path`.
+    fn load(&self, py: Python<'_>, path: String) -> PyResult<()> {
+        let json = py
+            .allow_threads(|| std::fs::read_to_string(&path))
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("IO error: {}", e)))?;
+        self.restore(&json)
+    }
+
+    /// Replaces the whole topology under one write lock, so concurrent
+    /// queries see either the old graph or the new one. For a change to a
+    /// few lanes, `update_edge_weight`, `remove_edge` and `remove_node`
+    /// avoid the rebuild.
+    fn sync_topology(&self, systems: Vec<(String, Vec<String>)>) {
+        let mut topology = self.inner.write();
+        topology.clear();
         for (sys_id, connections) in systems {
-            self.inner.add_node(sys_id.clone(), None);
+            topology.add_node(sys_id.clone(), None);
             for target in connections {
-                self.inner.add_edge(&sys_id, &target, 1.0);
+                topology.add_edge(&sys_id, &target, 1.0);
             }
         }
     }
 
-    fn set_correlation_context(&mut self, context: &void_reckoning_shared::CorrelationContext) {
-        self.inner.run_id = context.span_id.clone();
+    fn set_correlation_context(&self, context: &void_reckoning_shared::CorrelationContext) {
+        self.inner.write().run_id = context.span_id.clone();
+    }
+
+    /// Copies the topology into a new `RustStateView` for `view()` and
+    /// returns its version. A turn engine publishes at the end of each turn.
+    fn publish(&self, py: Python<'_>) -> u64 {
+        py.allow_threads(|| {
+            let view = RustStateView::at_turn(None).with_topology(&self.inner.read());
+            self.published.publish(view)
+        })
+    }
+
+    /// The last published view; never waits on a turn or an edit in
+    /// progress. None before the first `publish`.
+    fn view(&self) -> Option<RustStateView> {
+        RustStateView::latest(&self.published)
+    }
+
+    /// `hits`, `misses`, `invalidations`, `entries` and `capacity` of the
+    /// `find_path` cache, which any change to the graph empties.
+    fn path_cache_stats<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let stats = self.inner.read().path_cache_stats();
+        reports::memory_stats_dict(py, &stats, None)
+    }
+
+    /// Caps the `find_path` cache, evicting the least recently used
+    /// routes; 0 turns caching off.
+    fn set_path_cache_capacity(&self, capacity: usize) {
+        self.inner.write().set_path_cache_capacity(capacity);
+    }
+
+    /// `nodes`, `edges` and `approx_bytes` held by the topology.
+    fn get_memory_stats<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let stats = py.allow_threads(|| self.inner.read().memory_stats());
+        reports::memory_stats_dict(py, &stats, None)
+    }
+
+    /// Platform-independent hash of the graph, for lockstep clients to
+    /// compare each turn.
+    fn state_hash(&self, py: Python<'_>) -> u64 {
+        py.allow_threads(|| self.inner.read().state_hash())
+    }
+
+    /// Frees the topology. The pathfinder stays usable but empty; `with`
+    /// blocks call this on exit.
+    fn close(&self, _py: Python<'_>) {
+        *self.inner.write() = GraphTopology::new();
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    #[pyo3(signature = (_exc_type=None, _exc_value=None, _traceback=None))]
+    fn __exit__(&self, py: Python<'_>, _exc_type: Option<PyObject>, _exc_value: Option<PyObject>, _traceback: Option<PyObject>) -> bool {
+        self.close(py);
+        false
+    }
+}
+
+fn lane_kind(name: &str) -> PyResult<LaneKind> {
+    LaneKind::parse(name).ok_or_else(|| {
+        pyo3::exceptions::PyValueError::new_err(format!("unknown lane kind '{}': expected Hyperlane, Wormhole or Slipstream", name))
+    })
+}
+
+impl BatchQuery {
+    fn resolve(self, profile: &Option<String>) -> PathQuery {
+        match self {
+            BatchQuery::Profiled(start, end, own) => (start, end, own.or_else(|| profile.clone())),
+            BatchQuery::Pair(start, end) => (start, end, profile.clone()),
+        }
+    }
+}
+
+fn path_queries(queries: Vec<BatchQuery>, profile: Option<String>) -> Vec<PathQuery> {
+    queries.into_iter().map(|query| query.resolve(&profile)).collect()
+}
+
+/// `find_path`'s keyword arguments as `RouteOptions`; `blocked_factions`
+/// without `system_owners` uses the owners stored on `topology`.
+#[allow(clippy::too_many_arguments)]
+fn route_options(
+    topology: &GraphTopology,
+    avoid: Option<Vec<String>>,
+    blocked_factions: Option<Vec<String>>,
+    system_owners: Option<HashMap<String, String>>,
+    lane_kinds: Option<Vec<String>>,
+    max_hops: Option<usize>,
+    max_cost: Option<f32>,
+    weights: Option<HashMap<String, f32>>,
+) -> PyResult<RouteOptions> {
+    let mut avoid = avoid.unwrap_or_default();
+    if let Some(blocked) = blocked_factions {
+        let owners: Vec<(String, String)> = match system_owners {
+            Some(owners) => owners.into_iter().collect(),
+            None => topology
+                .node_ids()
+                .into_iter()
+                .filter_map(|id| topology.node_owner(&id).map(str::to_string).map(|owner| (id, owner)))
+                .collect(),
+        };
+        if owners.is_empty() {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "blocked_factions needs system_owners or owners set with set_node_owner",
+            ));
+        }
+        avoid.extend(owners.into_iter().filter(|(_, owner)| blocked.contains(owner)).map(|(system, _)| system));
+    }
+    Ok(RouteOptions {
+        avoid,
+        lane_kinds: lane_kinds.map(|kinds| kinds.iter().map(|kind| lane_kind(kind)).collect::<PyResult<Vec<_>>>()).transpose()?,
+        limits: PathLimits { max_hops, max_cost },
+        weights: weights.map(|w| route_weights(&w)).transpose()?,
+    })
+}
+
+/// `{"cost", "time", "risk"}` as `RouteWeights`, missing ones 0.
+fn route_weights(weights: &HashMap<String, f32>) -> PyResult<RouteWeights> {
+    if let Some(name) = weights.keys().find(|name| !["cost", "time", "risk"].contains(&name.as_str())) {
+        return Err(pyo3::exceptions::PyValueError::new_err(format!("unknown route weight '{}': expected cost, time or risk", name)));
+    }
+    let weight = |name: &str| weights.get(name).copied().unwrap_or(0.0);
+    Ok(RouteWeights { cost: weight("cost"), time: weight("time"), risk: weight("risk") })
+}
+
+impl Default for RustPathfinder {
+    fn default() -> Self {
+        Self::new(false)
     }
 }
 
 // --- Combat ---
 use void_reckoning_combat::engine::BattleEngine;
-use void_reckoning_combat::{CombatUnit, Weapon, WeaponType};
+use void_reckoning_combat::resolve::{BattleSetup, DEFAULT_MAX_TURNS};
+use void_reckoning_combat::victory::{EndConditions, ObjectiveZone};
+use void_reckoning_combat::targeting::Hostility;
+use background::BattleRun;
+use pyo3::exceptions::PyRuntimeError;
+use void_reckoning_combat::{BattleState, CombatUnit, Weapon, WeaponType};
+use config::{BattleConfig, EconomyConfig, UnitSpec};
 
-#[pyclass]
+/// Every call locks the engine for its own duration only, so concurrent
+/// callers (and a background run) interleave step by step.
+#[pyclass(frozen)]
 pub struct RustCombatEngine {
-    pub inner: BattleEngine,
+    /// Shared with the worker thread of a background run, which locks it
+    /// one step at a time so the engine stays readable while it runs.
+    inner: Arc<Mutex<BattleEngine>>,
+    active_run: Mutex<Option<BattleRun>>,
+    /// Weapon templates for `equip`.
+    registry: Mutex<Option<Arc<Registries>>>,
+    /// Shared with background runs, which publish at their progress points.
+    published: Arc<Published<RustStateView>>,
 }
 
 #[pymethods]
 impl RustCombatEngine {
+    /// Either pass `width`/`height` (and a `seed` for reproducible damage
+    /// rolls) directly or a `BattleConfig`; the config wins when both are
+    /// given.
     #[new]
-    pub fn new(width: f32, height: f32) -> Self {
-        RustCombatEngine {
-            inner: BattleEngine::new(width, height),
+    #[pyo3(signature = (width=None, height=None, config=None, seed=None))]
+    pub fn new(width: Option<f32>, height: Option<f32>, config: Option<BattleConfig>, seed: Option<u64>) -> PyResult<Self> {
+        let config = match config {
+            Some(config) => config,
+            None => BattleConfig::new(width.unwrap_or(1000.0), height.unwrap_or(1000.0), seed, None)?,
+        };
+        let mut inner = match config.seed {
+            Some(seed) => BattleEngine::new_with_seed(config.width, config.height, seed),
+            None => BattleEngine::new(config.width, config.height),
+        };
+        inner.set_campaign_turn(config.campaign_turn);
+        Ok(Self::from_engine(inner))
+    }
+
+    fn add_unit_spec(&self, spec: &UnitSpec) {
+        self.engine().add_unit(spec.build());
+    }
+
+    fn add_units(&self, specs: Vec<UnitSpec>) {
+        for spec in &specs {
+            self.engine().add_unit(spec.build());
         }
     }
+
+    /// Multiplies the accuracy of every weapon on the field by `factor`.
+    fn scale_accuracy(&self, factor: f32) {
+        self.engine().scale_accuracy(factor);
+    }
+
+    /// Uses `registry`'s weapons as templates for `equip`.
+    fn set_data_registry(&self, registry: &RustDataRegistry) {
+        *self.registry.lock() = Some(registry.shared());
+    }
+
+    /// Adds weapons to a unit from their templates in the data registry.
+    fn equip(&self, unit_id: UnitId, weapon_ids: Vec<String>) -> PyResult<()> {
+        let registries = self.registry.lock().clone().ok_or(EngineError::NotInitialized("Data registry"))?;
+        let weapons = weapon_ids
+            .iter()
+            .map(|id| registry::weapon_template(&registries, id))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut engine = self.engine();
+        let unit = engine
+            .state
+            .get_unit_mut(unit_id)
+            .ok_or_else(|| pyo3::exceptions::PyValueError::new_err(format!("Unknown unit: {}", unit_id)))?;
+        unit.weapons.extend(weapons);
+        Ok(())
+    }
     
-    fn add_unit(&mut self, id: u32, name: String, faction_idx: u8, max_hp: f32, x: f32, y: f32, weapons: Vec<(String, String, f32, f32, f32, f32)>, speed: f32, evasion: f32, shields_max: f32, armor: f32, cover_val: Option<u8>) {
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (id, name, faction_idx, max_hp, x, y, weapons, speed, evasion, shields_max, armor, cover_val=None))]
+    fn add_unit(&self, id: UnitId, name: String, faction_idx: u8, max_hp: f32, x: f32, y: f32, weapons: Vec<(String, String, f32, f32, f32, f32)>, speed: f32, evasion: f32, shields_max: f32, armor: f32, cover_val: Option<u8>) {
         let mut unit = CombatUnit::new(id, name, faction_idx, max_hp);
         unit.position = (x, y);
         unit.speed = speed;
@@ -102,212 +710,998 @@ impl RustCombatEngine {
                  accuracy,
                  cooldown,
                  current_cooldown: 0.0,
+                 aoe_radius: 0.0,
              };
              unit.weapons.push(weapon);
         }
         
-        self.inner.add_unit(unit);
+        self.engine().add_unit(unit);
     }
     
-    fn set_unit_cover(&mut self, id: u32, cover_val: u8) {
-        self.inner.set_unit_cover(id, cover_val);
+    fn set_unit_cover(&self, id: UnitId, cover_val: u8) {
+        self.engine().set_unit_cover(id, cover_val);
     }
     
-    fn step(&mut self) -> bool {
-        self.inner.step()
+    /// Seeds damage rolls so the battle replays identically.
+    fn set_seed(&self, seed: u64) {
+        self.engine().set_seed(seed);
+    }
+
+    /// Lockstep mode: with a `seed`, damage rolls come from it and movement
+    /// avoids platform-dependent math, so the battle steps identically on
+    /// every client. `None` turns it off.
+    #[pyo3(signature = (seed=None))]
+    fn set_deterministic(&self, seed: Option<u64>) {
+        self.engine().set_deterministic(seed);
+    }
+
+    #[getter]
+    fn deterministic(&self) -> bool {
+        self.engine().is_deterministic()
+    }
+
+    /// Whether splash also hits the attacker's own and allied units.
+    #[getter]
+    fn friendly_fire(&self) -> bool {
+        self.engine().friendly_fire
+    }
+
+    #[setter]
+    fn set_friendly_fire(&self, enabled: bool) {
+        self.engine().friendly_fire = enabled;
+    }
+
+    /// The seed damage rolls were last seeded with; None if never seeded.
+    #[getter]
+    fn seed(&self) -> Option<u64> {
+        self.engine().seed()
+    }
+
+    /// Faction index pairs that don't fight each other; replaces any
+    /// earlier alliances. Everyone else is hostile.
+    fn set_alliances(&self, pairs: Vec<(u8, u8)>) {
+        self.engine().set_hostility(Hostility::from_alliances(pairs));
+    }
+
+    /// Platform-independent hash of the battle (turn, unit vitals,
+    /// positions, targets, cooldowns); equal on every client in sync.
+    fn state_hash(&self, py: Python<'_>) -> u64 {
+        py.allow_threads(|| self.engine().state.state_hash())
+    }
+
+    /// Advances one turn and returns the `BattleOutcome` so far, which is
+    /// falsy once the battle has ended.
+    fn step(&self, py: Python<'_>) -> reports::PyBattleOutcome {
+        py.allow_threads(|| {
+            let mut engine = self.inner.lock();
+            engine.step();
+            engine.outcome("", false)
+        })
+        .into()
+    }
+
+    /// Ends the battle early on top of running out of hostile factions:
+    /// after `max_turns`, when morale breaks (a faction below this share
+    /// of its hit points routs), when one faction alone holds `objective`
+    /// (`(x, y, radius, turns)`), or once `damage_threshold` of all hit
+    /// points on the field are lost. Replaces earlier conditions.
+    #[pyo3(signature = (max_turns=None, morale_break=None, objective=None, damage_threshold=None))]
+    fn set_end_conditions(
+        &self,
+        max_turns: Option<u32>,
+        morale_break: Option<f32>,
+        objective: Option<(f32, f32, f32, u32)>,
+        damage_threshold: Option<f32>,
+    ) -> PyResult<()> {
+        for (name, value) in [("morale_break", morale_break), ("damage_threshold", damage_threshold)] {
+            if let Some(value) = value {
+                config::check_fraction(name, value as f64)?;
+            }
+        }
+        let objective = objective.map(|(x, y, radius, turns)| ObjectiveZone { center: (x, y), radius, turns });
+        self.engine().set_end_conditions(EndConditions { max_turns, morale_break, objective, damage_threshold });
+        Ok(())
+    }
+
+    /// Copies the battle into a new `RustStateView` for `view()` and
+    /// returns its version. Background runs publish at every progress
+    /// point and at the end.
+    fn publish(&self, py: Python<'_>) -> u64 {
+        py.allow_threads(|| background::publish(&self.inner, &self.published))
+    }
+
+    /// The last published view; never waits on a step in progress. None
+    /// before the first `publish`.
+    fn view(&self) -> Option<RustStateView> {
+        RustStateView::latest(&self.published)
     }
     
-    fn get_unit_status(&self, id: u32) -> Option<(f32, f32, bool)> {
-        if let Some(u) = self.inner.state.get_unit(id) {
-            Some((u.hp, u.shields, u.is_alive))
-        } else {
-            None
+    /// Steps the battle to completion on a background thread and returns
+    /// a `BattleRun` handle at once. Every `progress_every` turns (and at
+    /// the end) `progress(turn, living_units)` is called; returning `False`
+    /// from it cancels the run, as does `BattleRun.cancel()`. The engine
+    /// can be inspected while the run is in flight.
+    #[pyo3(signature = (max_turns=None, progress_every=10, progress=None))]
+    fn run_to_completion_async(&self, max_turns: Option<u32>, progress_every: u32, progress: Option<PyObject>) -> PyResult<BattleRun> {
+        let mut active_run = self.active_run.lock();
+        if active_run.as_ref().is_some_and(BattleRun::is_running) {
+            return Err(PyRuntimeError::new_err("A background run is already in progress for this battle"));
         }
+        let run = BattleRun::spawn(
+            Arc::clone(&self.inner),
+            Arc::clone(&self.published),
+            max_turns.unwrap_or(DEFAULT_MAX_TURNS),
+            progress_every,
+            progress,
+        )?;
+        *active_run = Some(run.clone());
+        Ok(run)
+    }
+
+    /// Living unit ids within `r` of `(x, y)`, nearest first.
+    fn get_units_in_radius(&self, x: f32, y: f32, r: f32) -> Vec<UnitId> {
+        self.engine().units_in_radius(x, y, r)
+    }
+
+    fn get_nearest_enemy(&self, unit_id: UnitId) -> Option<UnitId> {
+        self.engine().nearest_enemy(unit_id)
+    }
+
+    fn get_unit_status(&self, id: UnitId) -> Option<(f32, f32, bool)> {
+        self.engine().state.get_unit(id).map(|u| (u.hp, u.shields, u.is_alive))
     }
     
-    fn get_state(&self) -> Vec<(u32, f32, f32, f32, bool)> {
-        self.inner.state.units.iter().map(|u| (u.id, u.position.0, u.position.1, u.hp, u.is_alive)).collect()
+    fn get_state(&self) -> Vec<(UnitId, f32, f32, f32, bool)> {
+        self.engine().state.units.iter().map(|u| (u.id, u.position.0, u.position.1, u.hp, u.is_alive)).collect()
+    }
+
+    /// numpy structured array (id, x, y, hp, shields, faction, alive), one record per unit.
+    fn get_state_array<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        arrays::unit_state_array(py, &self.engine().state.units)
     }
 
-    fn set_correlation_context(&mut self, context: &void_reckoning_shared::CorrelationContext) {
+    /// Moves the units in `ids` to `positions` (numpy (N, 2) float array).
+    /// Returns the number of units found and moved.
+    fn set_positions(&self, ids: &Bound<'_, PyAny>, positions: &Bound<'_, PyAny>) -> PyResult<usize> {
+        arrays::apply_positions(&mut self.engine().state, ids, positions)
+    }
+
+    fn set_correlation_context(&self, context: &void_reckoning_shared::CorrelationContext) {
         // Delegate to the inner engine which now supports full context
-        self.inner.set_correlation_context(context.clone());
+        self.engine().set_correlation_context(context.clone());
+    }
+
+    /// `with engine.context_scope() as ctx:` parents everything emitted in the
+    /// block under `ctx` (a fresh child of the current context unless given).
+    #[pyo3(signature = (context=None))]
+    fn context_scope(&self, context: Option<void_reckoning_shared::CorrelationContext>) -> PyContextScope {
+        PyContextScope::new(self.engine().obs.contexts.clone(), context)
+    }
+
+    /// Moves the battle's clock to `turn`, starting one of its own if it
+    /// has none; None detaches the clock.
+    #[pyo3(signature = (turn=None))]
+    fn set_campaign_turn(&self, turn: Option<u64>) {
+        self.engine().set_campaign_turn(turn);
+    }
+
+    /// Runs the battle on the campaign's `SimClock`: each step is one of
+    /// its ticks, and its turn and tick are stamped on events.
+    #[pyo3(signature = (clock=None))]
+    fn set_clock(&self, clock: Option<SimClock>) {
+        self.engine().set_clock(clock);
+    }
+
+    /// Unit, weapon and event counts and `approx_bytes` held by the battle.
+    /// Dead units are never removed, so a long-lived engine whose
+    /// `dead_units` keeps climbing is leaking.
+    fn get_memory_stats<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let (stats, events) = py.allow_threads(|| {
+            let engine = self.engine();
+            (engine.state.memory_stats(), engine.obs.event_log.as_ref().map(EventLog::len))
+        });
+        reports::memory_stats_dict(py, &stats, events)
+    }
+
+    /// Shots, hits, damage dealt and taken and kills per unit id, plus
+    /// every kill in order as `{turn, attacker_id, target_id, weapon}`.
+    fn get_battle_statistics<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let ledger = py.allow_threads(|| self.engine().state.ledger.clone());
+        reports::battle_statistics_dict(py, &ledger)
+    }
+
+    /// With event logging on, emits a `Memory` event carrying
+    /// `get_memory_stats()` every `turns` turns (None to stop).
+    #[pyo3(signature = (turns=None))]
+    fn set_memory_event_interval(&self, turns: Option<u32>) {
+        self.engine().set_memory_event_interval(turns);
     }
     
     fn get_event_log(&self) -> Option<void_reckoning_shared::EventLog> {
-        self.inner.event_log.clone()
+        self.engine().obs.event_log.clone()
     }
     
-    fn enable_event_logging(&mut self) -> void_reckoning_shared::EventLog {
-        let log = void_reckoning_shared::EventLog::new();
-        self.inner.set_event_log(log.clone());
+    #[pyo3(signature = (capacity=None))]
+    fn enable_event_logging(&self, capacity: Option<usize>) -> void_reckoning_shared::EventLog {
+        let log = void_reckoning_shared::EventLog::with_capacity(capacity);
+        self.engine().set_event_log(log.clone());
         log
     }
+
+    /// Publishes this engine's events onto `bus` (enabling logging if necessary).
+    fn attach_event_bus(&self, bus: &EventBus) -> EventLog {
+        attach_bus(&mut self.engine().obs.event_log, bus)
+    }
+
+    /// Stops any background run, flushes the event log and its sinks, and
+    /// frees the battle state. The engine stays usable but empty; `with`
+    /// blocks call this on exit, and calling it twice is harmless.
+    fn close(&self, py: Python<'_>) {
+        // Take the run out first: stopping it waits for the worker, which
+        // needs the engine lock.
+        let run = self.active_run.lock().take();
+        if let Some(run) = run {
+            run.stop(py);
+        }
+        let log = {
+            let mut engine = self.engine();
+            let (width, height) = engine.state.grid_size;
+            engine.state = BattleState::new(width, height);
+            engine.obs.event_log.take()
+        };
+        flush_log(py, log);
+        *self.registry.lock() = None;
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    #[pyo3(signature = (_exc_type=None, _exc_value=None, _traceback=None))]
+    fn __exit__(&self, py: Python<'_>, _exc_type: Option<PyObject>, _exc_value: Option<PyObject>, _traceback: Option<PyObject>) -> bool {
+        self.close(py);
+        false
+    }
+}
+
+impl RustCombatEngine {
+    pub fn from_engine(engine: BattleEngine) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(engine)),
+            active_run: Mutex::new(None),
+            registry: Mutex::new(None),
+            published: Arc::new(Published::default()),
+        }
+    }
+
+    pub fn engine(&self) -> MutexGuard<'_, BattleEngine> {
+        self.inner.lock()
+    }
+}
+
+/// Returns the module to a just-imported state between tests: stops any
+/// background battle runs still in flight (so their progress callbacks
+/// can't fire into a later test) and reports what was reset.
+///
+/// Engines, logs and registries hold no shared state (each has its own
+/// RNG, sampler and caches), so instances never need resetting; drop them
+/// or `close()` them. The module can't be imported into a second
+/// sub-interpreter: PyO3 refuses with ImportError.
+#[pyfunction]
+fn reset_module_state(py: Python<'_>) -> PyResult<Bound<'_, PyDict>> {
+    let reset = serde_json::json!({ "battle_runs_stopped": BattleRun::stop_all(py) });
+    Ok(void_reckoning_shared::pyvalue::value_to_py(py, &reset)?.into_bound(py).downcast_into::<PyDict>()?)
+}
+
+/// Builds and runs every battle in `battle_setups_json` (a JSON array of
+/// `BattleSetup`s) to completion on worker threads, without the GIL. Returns
+/// the `BattleOutcome`s as a JSON array in setup order; a given `seed`
+/// always produces the same outcomes.
+#[pyfunction]
+#[pyo3(signature = (battle_setups_json, seed, workers=0))]
+fn resolve_battles(py: Python<'_>, battle_setups_json: &str, seed: u64, workers: usize) -> PyResult<String> {
+    py.allow_threads(|| {
+        let setups: Vec<BattleSetup> = serde_json::from_str(battle_setups_json).map_err(EngineError::json)?;
+        let outcomes = void_reckoning_combat::resolve::resolve_battles(&setups, seed, workers);
+        serde_json::to_string(&outcomes).map_err(|e| EngineError::json(e).into())
+    })
 }
 
 // --- Auditor ---
 use void_reckoning_auditor::engine::ValidationEngine;
-use void_reckoning_auditor::registry::Registries;
-use void_reckoning_auditor::types::EntityType;
+use void_reckoning_auditor::registry::{Registries, RegistryKind, RegistryReload};
+use void_reckoning_auditor::rules::CompositeRuleSpec;
+use void_reckoning_auditor::types::{EntityType, MemoryStats as AuditorMemoryStats, ValidationReport, ValidationResult};
+use reports::{PyEconomicReport, PyResources, PyValidationReport, PyValidationResult};
+use registry::RustDataRegistry;
+
+/// Docstring for every engine's `state_hash`; `desync::hash_json` of the
+/// snapshot `to_json` returns.
+macro_rules! state_hash_doc {
+    () => {
+        "FNV-1a of the `to_json` state; equal on every platform for equal state."
+    };
+}
 
+mod ai;
+mod anomaly;
+mod arrays;
+mod background;
+mod campaign;
+mod colony;
+mod config;
+mod datapack;
+mod diplomacy;
+mod fleet;
+mod hazard;
+mod info;
+mod names;
+mod reader;
+mod registry;
+mod replan;
+mod reports;
+mod scenario;
+mod stubs;
+mod tournament;
+mod turn;
+mod turn_engine;
+mod views;
+mod world;
 pub mod observability;
 
-#[pyclass]
-pub struct RustAuditor {
+struct AuditorState {
     engine: Option<ValidationEngine>,
     registries: Arc<Registries>,
 }
 
+/// Validation shares a read lock taken without the GIL, so batches from
+/// several threads run in parallel; registry changes take the write lock.
+#[pyclass(frozen)]
+pub struct RustAuditor {
+    state: RwLock<AuditorState>,
+}
+
 #[pymethods]
 impl RustAuditor {
     #[new]
     pub fn new() -> Self {
-        Self {
-            engine: None,
-            registries: Arc::new(void_reckoning_auditor::registry::Registries::new()),
-        }
+        Self::with_registries(Arc::new(Registries::new()))
     }
 
-    pub fn load_registry(&mut self, registry_type: String, data_json: String) -> PyResult<()> {
-        let data: serde_json::Map<String, Value> = serde_json::from_str(&data_json)
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("JSON error: {}", e)))?;
-        
-        let regs = Arc::make_mut(&mut self.registries);
-        match registry_type.as_str() {
-            "buildings" => regs.buildings = data,
-            "technology" => regs.technology = data,
-            "factions" => regs.factions = data,
-            "weapons" => regs.weapons = data,
-            "abilities" => regs.abilities = data,
-            _ => return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>("Unknown registry type")),
-        }
+    pub fn load_registry(&self, registry_type: String, data_json: String) -> PyResult<()> {
+        let (kind, data) = parse_registry(&registry_type, &data_json)?;
+        Arc::make_mut(&mut self.state.write().registries).replace(kind, data);
         Ok(())
     }
 
-    pub fn initialize(&mut self) -> PyResult<()> {
-        self.engine = Some(ValidationEngine::new(Arc::clone(&self.registries)));
+    /// Hot-reloads a single registry on a live engine and returns the reload
+    /// summary as JSON. Before `initialize()` this behaves like `load_registry`.
+    pub fn reload_registry(&self, registry_type: String, data_json: String) -> PyResult<String> {
+        let (kind, data) = parse_registry(&registry_type, &data_json)?;
+        let reload = self.state.write().apply_registry(kind, data);
+        serde_json::to_string(&reload)
+            .map_err(|e| PyErr::from(EngineError::json(e)))
+    }
+
+    /// Loads the asset manifest (icon/model paths known to exist on disk).
+    pub fn load_asset_manifest(&self, paths: Vec<String>) -> PyResult<String> {
+        let data: serde_json::Map<String, Value> = paths.into_iter().map(|p| (p, Value::Bool(true))).collect();
+        let reload = self.state.write().apply_registry(RegistryKind::Assets, data);
+        serde_json::to_string(&reload)
+            .map_err(|e| PyErr::from(EngineError::json(e)))
+    }
+
+    pub fn registry_version(&self) -> u64 {
+        self.state.read().registries.version
+    }
+
+    #[pyo3(signature = (registry_type=None))]
+    pub fn registry_hash(&self, registry_type: Option<String>) -> PyResult<u64> {
+        let state = self.state.read();
+        match registry_type {
+            Some(name) => {
+                let kind = registry_kind(&name)?;
+                Ok(state.registries.content_hash(kind))
+            }
+            None => Ok(state.registries.combined_hash()),
+        }
+    }
+
+    pub fn initialize(&self) -> PyResult<()> {
+        let mut state = self.state.write();
+        state.engine = Some(ValidationEngine::new(Arc::clone(&state.registries)));
         Ok(())
     }
 
-    pub fn enable_event_logging(&mut self) -> PyResult<void_reckoning_shared::EventLog> {
-        let engine = self.engine.as_mut().ok_or_else(|| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Auditor not initialized"))?;
-        let log = void_reckoning_shared::EventLog::new();
+    /// Validates against `registry` instead of data loaded into this auditor.
+    /// Works before or after `initialize()`.
+    pub fn set_data_registry(&self, registry: &RustDataRegistry) {
+        let mut state = self.state.write();
+        state.registries = registry.shared();
+        if let Some(engine) = state.engine.as_mut() {
+            engine.set_registries(registry.shared());
+        }
+    }
+
+    #[pyo3(signature = (capacity=None))]
+    pub fn enable_event_logging(&self, capacity: Option<usize>) -> PyResult<void_reckoning_shared::EventLog> {
+        let mut state = self.state.write();
+        let engine = state.engine_mut()?;
+        let log = void_reckoning_shared::EventLog::with_capacity(capacity);
         engine.set_event_log(log.clone());
         Ok(log)
     }
 
-    pub fn validate_entity(&self, id: String, entity_type: String, data_json: String, universe_id: String, turn: u64) -> PyResult<String> {
-        let engine = self.engine.as_ref().ok_or_else(|| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Auditor not initialized"))?;
-        let data: Value = serde_json::from_str(&data_json)
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("JSON error: {}", e)))?;
-        
-        let ent_type = match entity_type.as_str() {
-            "unit" => EntityType::Unit,
-            "building" => EntityType::Building,
-            "technology" => EntityType::Technology,
-            "faction" => EntityType::Faction,
-            "portal" => EntityType::Portal,
-            "campaign" => EntityType::Campaign,
-            "fleet" | "Fleet" => EntityType::Fleet,
-            "planet" | "Planet" => EntityType::Planet,
-            _ => return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Unknown entity type: {}", entity_type))),
-        };
+    /// Publishes this engine's events onto `bus` (enabling logging if necessary).
+    pub fn attach_event_bus(&self, bus: &EventBus) -> PyResult<EventLog> {
+        let mut state = self.state.write();
+        let engine = state.engine_mut()?;
+        Ok(attach_bus(&mut engine.event_log, bus))
+    }
 
-        let results = engine.validate_entity(id, ent_type, data, universe_id, turn);
+    pub fn validate_entity(&self, id: EntityId, entity_type: String, data_json: String, universe_id: String, turn: u64) -> PyResult<String> {
+        let results = self.check_entity(id, entity_type, data_json, universe_id, turn)?;
         let result_json = serde_json::to_string(&results)
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Serialization error: {}", e)))?;
+            .map_err(|e| PyErr::from(EngineError::json(e)))?;
         
         Ok(result_json)
     }
 
-    pub fn set_correlation_context(&mut self, context: &void_reckoning_shared::CorrelationContext) -> PyResult<()> {
-        let engine = self.engine.as_mut().ok_or_else(|| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Auditor not initialized"))?;
+    /// `validate_entity` returning `ValidationResult` objects.
+    pub fn validate_entity_results(&self, id: EntityId, entity_type: String, data_json: String, universe_id: String, turn: u64) -> PyResult<Vec<PyValidationResult>> {
+        let results = self.check_entity(id, entity_type, data_json, universe_id, turn)?;
+        Ok(results.into_iter().map(PyValidationResult::from).collect())
+    }
+
+    /// Validates `(id, entity_type, data_json)` triples in one call and returns
+    /// the aggregated `ValidationReport` as JSON. Runs without the GIL.
+    pub fn validate_batch(&self, py: Python<'_>, entities: Vec<(EntityId, String, String)>, universe_id: String, turn: u64) -> PyResult<String> {
+        let report = self.run_batch(py, entities, universe_id, turn)?;
+        serde_json::to_string(&report)
+            .map_err(|e| PyErr::from(EngineError::json(e)))
+    }
+
+    /// `validate_batch` returning a `ValidationReport` object.
+    pub fn validate_batch_report(&self, py: Python<'_>, entities: Vec<(EntityId, String, String)>, universe_id: String, turn: u64) -> PyResult<PyValidationReport> {
+        Ok(self.run_batch(py, entities, universe_id, turn)?.into())
+    }
+
+    /// `validate_batch` findings as a `pyarrow.Table`, one row per result.
+    pub fn validate_batch_arrow<'py>(&self, py: Python<'py>, entities: Vec<(EntityId, String, String)>, universe_id: String, turn: u64) -> PyResult<Bound<'py, PyAny>> {
+        let report = self.run_batch(py, entities, universe_id, turn)?;
+        columnar::arrow_table(&reports::validation_result_columns(py, &report.results)?)
+    }
+
+    /// Registers composite rules from a JSON array of `CompositeRuleSpec`s.
+    pub fn add_composite_rules(&self, rules_json: String) -> PyResult<()> {
+        let mut state = self.state.write();
+        let engine = state.engine_mut()?;
+        let specs: Vec<CompositeRuleSpec> = serde_json::from_str(&rules_json)
+            .map_err(|e| PyErr::from(EngineError::json(e)))?;
+
+        for spec in specs {
+            engine.add_composite_rule(spec)
+                .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)?;
+        }
+        Ok(())
+    }
+
+    /// `load_registry` with a msgpack-encoded map instead of JSON text.
+    pub fn load_registry_msgpack(&self, registry_type: String, data: &[u8]) -> PyResult<()> {
+        let kind = registry_kind(&registry_type)?;
+        let data: serde_json::Map<String, Value> = msgpack::from_msgpack(data).map_err(msgpack_error)?;
+        Arc::make_mut(&mut self.state.write().registries).replace(kind, data);
+        Ok(())
+    }
+
+    /// `validate_save` taking and returning msgpack bytes.
+    pub fn validate_save_msgpack<'py>(&self, py: Python<'py>, save: &[u8]) -> PyResult<Bound<'py, PyBytes>> {
+        let bytes = py.allow_threads(|| {
+            let state = self.state.read();
+            let engine = state.engine()?;
+            let save: Value = msgpack::from_msgpack(save).map_err(msgpack_error)?;
+            msgpack::to_msgpack(&engine.validate_save(&save)).map_err(msgpack_error)
+        })?;
+        Ok(PyBytes::new(py, &bytes))
+    }
+
+    /// Validates a full campaign save (`{"universe_id", "turn", "entities": [...], "state": {...}}`)
+    /// and returns a layered `SaveValidationReport` as JSON.
+    pub fn validate_save(&self, py: Python<'_>, save_json: String) -> PyResult<String> {
+        py.allow_threads(|| {
+            let state = self.state.read();
+            let engine = state.engine()?;
+            let save: Value = serde_json::from_str(&save_json)
+                .map_err(|e| PyErr::from(EngineError::json(e)))?;
+
+            let report = engine.validate_save(&save);
+            serde_json::to_string(&report)
+                .map_err(|e| PyErr::from(EngineError::json(e)))
+        })
+    }
+
+    pub fn set_correlation_context(&self, context: &void_reckoning_shared::CorrelationContext) -> PyResult<()> {
+        let mut state = self.state.write();
+        let engine = state.engine_mut()?;
         engine.set_correlation_context(context.clone());
         Ok(())
     }
+
+    /// `with auditor.context_scope() as ctx:` parents everything emitted in the
+    /// block under `ctx` (a fresh child of the current context unless given).
+    #[pyo3(signature = (context=None))]
+    pub fn context_scope(&self, context: Option<void_reckoning_shared::CorrelationContext>) -> PyResult<PyContextScope> {
+        let state = self.state.read();
+        let engine = state.engine()?;
+        Ok(PyContextScope::new(engine.contexts.clone(), context))
+    }
+
+    /// Rule, registry-entry and event counts and `approx_bytes` of registry
+    /// data. Works before `initialize()`.
+    pub fn get_memory_stats<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let (stats, events) = py.allow_threads(|| {
+            let state = self.state.read();
+            match &state.engine {
+                Some(engine) => (engine.memory_stats(), engine.event_log.as_ref().map(EventLog::len)),
+                None => {
+                    let stats = AuditorMemoryStats {
+                        registry_entries: state.registries.entry_count(),
+                        approx_bytes: state.registries.approx_bytes(),
+                        ..AuditorMemoryStats::default()
+                    };
+                    (stats, None)
+                }
+            }
+        });
+        reports::memory_stats_dict(py, &stats, events)
+    }
+
+    /// Hash of the registries and active rules, stable across processes.
+    /// Before `initialize()` it covers the registries only.
+    pub fn state_hash(&self, py: Python<'_>) -> u64 {
+        py.allow_threads(|| {
+            let state = self.state.read();
+            match &state.engine {
+                Some(engine) => engine.state_hash(),
+                None => state.registries.combined_hash(),
+            }
+        })
+    }
+
+    /// Flushes the event log and drops the engine and registries; call
+    /// `initialize()` again to reuse the auditor. `with` blocks call this on
+    /// exit.
+    pub fn close(&self, py: Python<'_>) {
+        let log = {
+            let mut state = self.state.write();
+            state.registries = Arc::new(Registries::new());
+            state.engine.take().and_then(|engine| engine.event_log)
+        };
+        flush_log(py, log);
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    #[pyo3(signature = (_exc_type=None, _exc_value=None, _traceback=None))]
+    fn __exit__(&self, py: Python<'_>, _exc_type: Option<PyObject>, _exc_value: Option<PyObject>, _traceback: Option<PyObject>) -> bool {
+        self.close(py);
+        false
+    }
+}
+
+impl Default for RustAuditor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RustAuditor {
+    pub(crate) fn with_registries(registries: Arc<Registries>) -> Self {
+        Self { state: RwLock::new(AuditorState { engine: None, registries }) }
+    }
+
+    fn check_entity(&self, id: EntityId, entity_type: String, data_json: String, universe_id: String, turn: u64) -> PyResult<Vec<ValidationResult>> {
+        let state = self.state.read();
+        let engine = state.engine()?;
+        let data: Value = serde_json::from_str(&data_json)
+            .map_err(|e| PyErr::from(EngineError::json(e)))?;
+
+        let ent_type = EntityType::parse(&entity_type)
+            .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Unknown entity type: {}", entity_type)))?;
+
+        Ok(engine.validate_entity(id, ent_type, data, universe_id, turn))
+    }
+
+    /// Parses `(id, entity_type, data_json)` triples and validates them without the GIL.
+    fn run_batch(&self, py: Python<'_>, entities: Vec<(EntityId, String, String)>, universe_id: String, turn: u64) -> PyResult<ValidationReport> {
+        py.allow_threads(|| {
+            let state = self.state.read();
+            let engine = state.engine()?;
+            let batch = entities
+                .into_iter()
+                .map(|(id, entity_type, data_json)| {
+                    let ent_type = EntityType::parse(&entity_type)
+                        .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Unknown entity type: {}", entity_type)))?;
+                    let data: Value = serde_json::from_str(&data_json)
+                        .map_err(|e| PyErr::from(EngineError::json(e)))?;
+                    Ok((id, ent_type, data))
+                })
+                .collect::<PyResult<Vec<_>>>()?;
+            Ok(engine.validate_batch(batch, universe_id, turn))
+        })
+    }
+}
+
+impl AuditorState {
+    fn engine(&self) -> Result<&ValidationEngine, EngineError> {
+        self.engine.as_ref().ok_or(EngineError::NotInitialized("Auditor"))
+    }
+
+    fn engine_mut(&mut self) -> Result<&mut ValidationEngine, EngineError> {
+        self.engine.as_mut().ok_or(EngineError::NotInitialized("Auditor"))
+    }
+
+    fn apply_registry(&mut self, kind: RegistryKind, data: serde_json::Map<String, Value>) -> RegistryReload {
+        match self.engine.as_mut() {
+            Some(engine) => {
+                let reload = engine.reload_registry(kind, data);
+                self.registries = Arc::clone(engine.registries());
+                reload
+            }
+            None => {
+                let regs = Arc::make_mut(&mut self.registries);
+                let changed = regs.replace(kind, data);
+                RegistryReload {
+                    registry: kind,
+                    changed,
+                    version: regs.version,
+                    content_hash: regs.content_hash(kind),
+                    refreshed_rules: Vec::new(),
+                }
+            }
+        }
+    }
+}
+
+fn registry_kind(name: &str) -> Result<RegistryKind, EngineError> {
+    RegistryKind::parse(name).ok_or_else(|| EngineError::Registry(format!("unknown registry type '{}'", name)))
+}
+
+fn parse_registry(registry_type: &str, data_json: &str) -> PyResult<(RegistryKind, serde_json::Map<String, Value>)> {
+    let kind = registry_kind(registry_type)?;
+    let data: serde_json::Map<String, Value> = serde_json::from_str(data_json)
+        .map_err(|e| PyErr::from(EngineError::json(e)))?;
+    Ok((kind, data))
 }
 
 // --- Economy ---
 use void_reckoning_economy::engine::IncomeEngine;
-use void_reckoning_economy::types::{EconomicNode, GlobalEconomicRules};
+use void_reckoning_economy::logistics::{self, LogisticsRules};
+use void_reckoning_economy::types::{EconomicNode, GlobalEconomicRules, NodeType, ResourceState, SCALE_FACTOR};
 use void_reckoning_economy::trade::{TradeRoute, TradeRouteManager};
 
-#[pyclass]
-pub struct RustEconomyEngine {
+struct EconomyState {
     engine: IncomeEngine,
     trade_manager: TradeRouteManager,
+    /// Building yields for `add_node_from_buildings`.
+    registry: Option<Arc<Registries>>,
+}
+
+/// Reports share a read lock taken without the GIL; edits and trade
+/// recalculation take the write lock.
+#[pyclass(frozen)]
+pub struct RustEconomyEngine {
+    state: RwLock<EconomyState>,
+    published: Published<RustStateView>,
 }
 
 #[pymethods]
 impl RustEconomyEngine {
     #[new]
-    pub fn new() -> Self {
-        Self {
-            engine: IncomeEngine::new(GlobalEconomicRules::default()),
-            trade_manager: TradeRouteManager::new(),
-        }
+    #[pyo3(signature = (config=None))]
+    pub fn new(config: Option<EconomyConfig>) -> Self {
+        let rules = config.map(|c| c.to_rules()).unwrap_or_default();
+        Self::from_parts(IncomeEngine::new(rules), TradeRouteManager::new())
     }
 
-    pub fn set_rules(&mut self, rules_json: String) -> PyResult<()> {
+    pub fn set_config(&self, config: &EconomyConfig) {
+        self.state.write().engine.set_rules(config.to_rules());
+    }
+
+    pub fn set_rules(&self, rules_json: String) -> PyResult<()> {
         let rules: GlobalEconomicRules = serde_json::from_str(&rules_json)
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("JSON error: {}", e)))?;
-        self.engine.set_rules(rules);
+            .map_err(|e| PyErr::from(EngineError::json(e)))?;
+        self.state.write().engine.set_rules(rules);
         Ok(())
     }
 
-    pub fn add_node(&mut self, node_json: String) -> PyResult<()> {
+    pub fn add_node(&self, node_json: String) -> PyResult<()> {
         let node: EconomicNode = serde_json::from_str(&node_json)
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("JSON error: {}", e)))?;
-        self.engine.add_node(node);
+            .map_err(|e| PyErr::from(EngineError::json(e)))?;
+        self.state.write().engine.add_node(node);
+        Ok(())
+    }
+
+    /// Uses `registry`'s buildings for `add_node_from_buildings`.
+    pub fn set_data_registry(&self, registry: &RustDataRegistry) {
+        self.state.write().registry = Some(registry.shared());
+    }
+
+    /// Adds a node whose income and upkeep are the summed yields of
+    /// `buildings` in the data registry. `node_type` is `"Planet"`,
+    /// `"Fleet"`, `"Army"` or `"Station"`.
+    #[pyo3(signature = (id, owner_faction, node_type, buildings, efficiency=1.0))]
+    pub fn add_node_from_buildings(&self, id: SystemId, owner_faction: FactionId, node_type: String, buildings: Vec<String>, efficiency: f64) -> PyResult<()> {
+        let mut state = self.state.write();
+        let registries = state.registry.clone().ok_or(EngineError::NotInitialized("Data registry"))?;
+        let node_type: NodeType = serde_json::from_value(Value::String(node_type.clone()))
+            .map_err(|_| pyo3::exceptions::PyValueError::new_err(format!("Unknown node type: {}", node_type)))?;
+        let (mut base_income, mut base_upkeep) = (ResourceState::default(), ResourceState::default());
+        for building in &buildings {
+            let (income, upkeep) = registry::building_yield(&registries, building)?;
+            base_income.add(&income);
+            base_upkeep.add(&upkeep);
+        }
+        state.engine.add_node(EconomicNode {
+            id,
+            owner_faction,
+            node_type,
+            base_income,
+            base_upkeep,
+            efficiency_scaled: (efficiency * SCALE_FACTOR as f64) as i128,
+            modifiers: Vec::new(),
+        });
         Ok(())
     }
 
-    pub fn add_trade_route(&mut self, route_json: String) -> PyResult<()> {
+    /// Adds every node in a msgpack-encoded array; returns how many were added.
+    pub fn add_nodes_msgpack(&self, data: &[u8]) -> PyResult<usize> {
+        let nodes: Vec<EconomicNode> = msgpack::from_msgpack(data).map_err(msgpack_error)?;
+        let count = nodes.len();
+        let mut state = self.state.write();
+        for node in nodes {
+            state.engine.add_node(node);
+        }
+        Ok(count)
+    }
+
+    pub fn add_trade_route(&self, route_json: String) -> PyResult<()> {
         let route: TradeRoute = serde_json::from_str(&route_json)
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("JSON error: {}", e)))?;
-        self.trade_manager.add_route(route);
+            .map_err(|e| PyErr::from(EngineError::json(e)))?;
+        self.state.write().trade_manager.add_route(route);
         Ok(())
     }
 
-    pub fn calculate_trade(&mut self, pathfinder: &RustPathfinder) -> PyResult<String> {
-        self.trade_manager.calculate_efficiencies(&pathfinder.inner);
-        let reports = self.trade_manager.get_total_trade_income();
+    pub fn calculate_trade(&self, py: Python<'_>, pathfinder: &RustPathfinder) -> PyResult<String> {
+        let reports = py.allow_threads(|| self.trade_income(pathfinder, |manager| manager.get_total_trade_income()));
         let reports_json = serde_json::to_string(&reports)
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Serialization error: {}", e)))?;
+            .map_err(|e| PyErr::from(EngineError::json(e)))?;
         Ok(reports_json)
     }
 
+    /// `calculate_trade` as a dict of system id to `Resources`.
+    pub fn calculate_trade_income(&self, py: Python<'_>, pathfinder: &RustPathfinder) -> HashMap<String, PyResources> {
+        let income = py.allow_threads(|| self.trade_income(pathfinder, |manager| manager.get_total_trade_income()));
+        income.into_iter().map(|(system, state)| (system, state.into())).collect()
+    }
+
+    /// Routes `faction`'s travelling resources over the pathfinder's lanes
+    /// for one turn without changing anything; returns the `SupplyReport`
+    /// as JSON. `rules_json` is a `LogisticsRules`, minerals and energy
+    /// over uncapped lanes by default.
+    #[pyo3(signature = (pathfinder, faction, rules_json=None))]
+    pub fn plan_supply(&self, py: Python<'_>, pathfinder: &RustPathfinder, faction: &str, rules_json: Option<&str>) -> PyResult<String> {
+        let rules: LogisticsRules = match rules_json {
+            Some(json) => serde_json::from_str(json).map_err(|e| PyErr::from(EngineError::json(e)))?,
+            None => LogisticsRules::default(),
+        };
+        let report = py.allow_threads(|| {
+            let state = self.state.read();
+            logistics::plan_supply(&state.engine, &pathfinder.inner.read(), &rules, faction)
+        });
+        serde_json::to_string(&report).map_err(|e| PyErr::from(EngineError::json(e)))
+    }
+
+    /// Adds every route in a msgpack-encoded array; returns how many were added.
+    pub fn add_trade_routes_msgpack(&self, data: &[u8]) -> PyResult<usize> {
+        let routes: Vec<TradeRoute> = msgpack::from_msgpack(data).map_err(msgpack_error)?;
+        let count = routes.len();
+        let mut state = self.state.write();
+        for route in routes {
+            state.trade_manager.add_route(route);
+        }
+        Ok(count)
+    }
+
+    /// `calculate_trade` returning msgpack bytes.
+    pub fn calculate_trade_msgpack<'py>(&self, py: Python<'py>, pathfinder: &RustPathfinder) -> PyResult<Bound<'py, PyBytes>> {
+        let bytes = py.allow_threads(|| self.trade_income(pathfinder, |manager| msgpack::to_msgpack(&manager.get_total_trade_income())));
+        Ok(PyBytes::new(py, &bytes.map_err(msgpack_error)?))
+    }
+
     pub fn process_faction(&self, faction_name: String) -> PyResult<String> {
-        let report = self.engine.process_faction(&faction_name);
+        let report = self.state.read().engine.process_faction(&faction_name);
         let report_json = serde_json::to_string(&report)
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Serialization error: {}", e)))?;
+            .map_err(|e| PyErr::from(EngineError::json(e)))?;
         Ok(report_json)
     }
 
-    pub fn process_all(&self) -> PyResult<String> {
-        let reports = self.engine.process_all();
+    /// `process_faction` returning an `EconomicReport` object.
+    pub fn process_faction_report(&self, faction_name: String) -> PyEconomicReport {
+        self.state.read().engine.process_faction(&faction_name).into()
+    }
+
+    pub fn process_all(&self, py: Python<'_>) -> PyResult<String> {
+        let reports = py.allow_threads(|| self.state.read().engine.process_all());
         let reports_json = serde_json::to_string(&reports)
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Serialization error: {}", e)))?;
+            .map_err(|e| PyErr::from(EngineError::json(e)))?;
         Ok(reports_json)
     }
 
-    pub fn enable_event_logging(&mut self) -> void_reckoning_shared::EventLog {
-        let log = void_reckoning_shared::EventLog::new();
-        self.engine.set_event_log(log.clone());
+    /// `process_all` as a dict of faction name to `EconomicReport`.
+    pub fn process_all_reports(&self, py: Python<'_>) -> HashMap<String, PyEconomicReport> {
+        let reports = py.allow_threads(|| self.state.read().engine.process_all());
+        reports.into_iter().map(|(faction, report)| (faction, report.into())).collect()
+    }
+
+    /// `process_all` as a dict of column lists, one row per faction.
+    pub fn process_all_columns<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let reports = py.allow_threads(|| self.state.read().engine.process_all());
+        reports::economic_report_columns(py, &reports)
+    }
+
+    /// `process_all` as a `pyarrow.Table` (requires pyarrow).
+    pub fn process_all_arrow<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        columnar::arrow_table(&self.process_all_columns(py)?)
+    }
+
+    /// `process_all` returning msgpack bytes.
+    pub fn process_all_msgpack<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        let bytes = py.allow_threads(|| msgpack::to_msgpack(&self.state.read().engine.process_all()));
+        Ok(PyBytes::new(py, &bytes.map_err(msgpack_error)?))
+    }
+
+    #[pyo3(signature = (capacity=None))]
+    pub fn enable_event_logging(&self, capacity: Option<usize>) -> void_reckoning_shared::EventLog {
+        let log = void_reckoning_shared::EventLog::with_capacity(capacity);
+        self.state.write().engine.set_event_log(log.clone());
         log
     }
 
-    pub fn set_correlation_context(&mut self, context: &void_reckoning_shared::CorrelationContext) {
-        self.engine.set_correlation_context(context.clone());
+    /// Publishes this engine's events onto `bus` (enabling logging if necessary).
+    pub fn attach_event_bus(&self, bus: &EventBus) -> EventLog {
+        attach_bus(&mut self.state.write().engine.obs.event_log, bus)
+    }
+
+    pub fn set_correlation_context(&self, context: &void_reckoning_shared::CorrelationContext) {
+        self.state.write().engine.set_correlation_context(context.clone());
+    }
+
+    /// `with engine.context_scope() as ctx:` parents everything emitted in the
+    /// block under `ctx` (a fresh child of the current context unless given).
+    #[pyo3(signature = (context=None))]
+    pub fn context_scope(&self, context: Option<void_reckoning_shared::CorrelationContext>) -> PyContextScope {
+        PyContextScope::new(self.state.read().engine.obs.contexts.clone(), context)
+    }
+
+    /// Moves the economy's clock to `turn`, starting one of its own if it
+    /// has none; None detaches the clock.
+    #[pyo3(signature = (turn=None))]
+    pub fn set_campaign_turn(&self, turn: Option<u64>) {
+        self.state.write().engine.set_campaign_turn(turn);
+    }
+
+    /// Stamps the campaign `SimClock`'s turn and tick on economy events. A
+    /// turn engine hands the economy its own clock every turn.
+    #[pyo3(signature = (clock=None))]
+    pub fn set_clock(&self, clock: Option<SimClock>) {
+        self.state.write().engine.set_clock(clock);
+    }
+
+    /// Node, modifier, trade-route and event counts and `approx_bytes`.
+    pub fn get_memory_stats<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let (stats, events) = py.allow_threads(|| {
+            let state = self.state.read();
+            let mut stats = state.engine.memory_stats();
+            let trade = state.trade_manager.memory_stats();
+            stats.routes = trade.routes;
+            stats.approx_bytes += trade.approx_bytes;
+            (stats, state.engine.obs.event_log.as_ref().map(EventLog::len))
+        });
+        reports::memory_stats_dict(py, &stats, events)
+    }
+
+    /// Platform-independent hash of the nodes, rules and trade routes, for
+    /// lockstep clients to compare each turn.
+    pub fn state_hash(&self, py: Python<'_>) -> u64 {
+        py.allow_threads(|| {
+            let state = self.state.read();
+            combine_hashes([state.engine.state_hash(), state.trade_manager.state_hash()])
+        })
+    }
+
+    /// Copies the nodes, rules and trade routes into a new `RustStateView`
+    /// for `view()` and returns its version. A turn engine publishes at the
+    /// end of each turn.
+    pub fn publish(&self, py: Python<'_>) -> u64 {
+        py.allow_threads(|| {
+            let state = self.state.read();
+            self.published.publish(RustStateView::at_turn(None).with_economy(&state.engine, &state.trade_manager))
+        })
+    }
+
+    /// The last published view; never waits on a turn or an edit in
+    /// progress. None before the first `publish`.
+    pub fn view(&self) -> Option<RustStateView> {
+        RustStateView::latest(&self.published)
+    }
+
+    /// With event logging on, emits a `Memory` event carrying node counts
+    /// and `approx_bytes` every `calls` calls to `process_all` (None to stop).
+    #[pyo3(signature = (calls=None))]
+    pub fn set_memory_event_interval(&self, calls: Option<u64>) {
+        self.state.write().engine.set_memory_event_interval(calls);
+    }
+
+    /// Flushes the event log and frees all nodes and trade routes; the rules
+    /// are kept. `with` blocks call this on exit.
+    pub fn close(&self, py: Python<'_>) {
+        let log = {
+            let mut state = self.state.write();
+            let log = state.engine.obs.event_log.take();
+            state.engine = IncomeEngine::new(state.engine.rules().clone());
+            state.trade_manager = TradeRouteManager::new();
+            state.registry = None;
+            log
+        };
+        flush_log(py, log);
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    #[pyo3(signature = (_exc_type=None, _exc_value=None, _traceback=None))]
+    fn __exit__(&self, py: Python<'_>, _exc_type: Option<PyObject>, _exc_value: Option<PyObject>, _traceback: Option<PyObject>) -> bool {
+        self.close(py);
+        false
+    }
+}
+
+impl Default for RustEconomyEngine {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl RustEconomyEngine {
+    pub(crate) fn from_parts(engine: IncomeEngine, trade_manager: TradeRouteManager) -> Self {
+        Self { state: RwLock::new(EconomyState { engine, trade_manager, registry: None }), published: Published::default() }
+    }
+
+    /// Recalculates route efficiencies against `pathfinder` and reports on
+    /// the result. Call without the GIL: locks the economy, then the topology.
+    fn trade_income<T>(&self, pathfinder: &RustPathfinder, report: impl FnOnce(&TradeRouteManager) -> T) -> T {
+        let mut state = self.state.write();
+        state.trade_manager.calculate_efficiencies(&pathfinder.inner.read());
+        report(&state.trade_manager)
     }
 }
 
 // --- Observability ---
-use void_reckoning_shared::{CausalGraph, Event};
+use void_reckoning_shared::{ingest, CausalGraph, Event};
+use void_reckoning_shared::escalation::EscalationRule;
+use void_reckoning_shared::integrity::IntegrityReport;
+use void_reckoning_shared::retention::RetentionPolicy;
+use void_reckoning_shared::summary::{SpanOffender, TraceSummary};
 
-#[pyclass]
+/// Queries share a read lock; ingestion parses without the GIL or the lock
+/// and only takes the write lock to insert.
+#[pyclass(frozen)]
 pub struct RustCausalGraph {
-    pub inner: CausalGraph,
+    pub inner: RwLock<CausalGraph>,
 }
 
 #[pymethods]
@@ -315,43 +1709,243 @@ impl RustCausalGraph {
     #[new]
     pub fn new() -> Self {
         RustCausalGraph {
-            inner: CausalGraph::new(),
+            inner: RwLock::new(CausalGraph::new()),
+        }
+    }
+
+    fn add_event_json(&self, json_str: String) -> PyResult<()> {
+        self.inner.write().add_event_json(&json_str)
+    }
+
+    fn add_events_jsonl(&self, py: Python<'_>, text: &str) -> PyResult<usize> {
+        let events = py
+            .allow_threads(|| ingest::parse_jsonl(text))
+            .map_err(|e| PyErr::from(EngineError::json(e)))?;
+        Ok(self.insert(events))
+    }
+
+    fn add_events_bincode(&self, py: Python<'_>, data: &[u8]) -> PyResult<usize> {
+        let events = py
+            .allow_threads(|| ingest::decode_bincode(data))
+            .map_err(|e| PyErr::from(EngineError::serialization("Bincode", e)))?;
+        Ok(self.insert(events))
+    }
+
+    #[staticmethod]
+    fn from_event_log(log: &EventLog) -> Self {
+        RustCausalGraph {
+            inner: RwLock::new(CausalGraph::from_event_log(log)),
         }
     }
 
-    fn add_event_json(&mut self, json_str: String) -> PyResult<()> {
-        self.inner.add_event_json(&json_str)
+    fn to_bytes<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyBytes>> {
+        let bytes = py.allow_threads(|| self.inner.read().to_snapshot_bytes()).map_err(snapshot_error)?;
+        Ok(PyBytes::new(py, &bytes))
+    }
+
+    #[staticmethod]
+    fn from_bytes(py: Python<'_>, data: &[u8]) -> PyResult<Self> {
+        Ok(RustCausalGraph {
+            inner: RwLock::new(CausalGraph::from_bytes(py, data)?),
+        })
+    }
+
+    fn save(&self, py: Python<'_>, path: String) -> PyResult<()> {
+        py.allow_threads(|| {
+            let bytes = self.inner.read().to_snapshot_bytes().map_err(snapshot_error)?;
+            std::fs::write(&path, bytes)
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("Save error: {}", e)))
+        })
+    }
+
+    #[staticmethod]
+    fn load(py: Python<'_>, path: String) -> PyResult<Self> {
+        Ok(RustCausalGraph {
+            inner: RwLock::new(CausalGraph::load(py, &path)?),
+        })
     }
 
     fn get_causal_chain(&self, span_id: String) -> Vec<Event> {
-        self.inner.get_causal_chain(span_id)
+        self.inner.read().get_causal_chain(span_id)
     }
 
     fn get_consequences(&self, span_id: String) -> Vec<Event> {
-        self.inner.get_consequences(span_id)
+        self.inner.read().get_consequences(span_id)
     }
     
     fn size(&self) -> usize {
-        self.inner.size()
+        self.inner.read().size()
+    }
+
+    fn get_span(&self, span_id: String) -> Option<void_reckoning_shared::span::Span> {
+        self.inner.read().get_span(&span_id)
     }
+
+    fn get_spans(&self) -> Vec<void_reckoning_shared::span::Span> {
+        self.inner.read().get_spans()
+    }
+
+    fn lowest_common_ancestor(&self, span_a: String, span_b: String) -> Option<Event> {
+        self.inner.read().lowest_common_ancestor(&span_a, &span_b)
+    }
+
+    fn path_between(&self, span_a: String, span_b: String) -> Vec<Event> {
+        self.inner.read().path_between(&span_a, &span_b)
+    }
+
+    fn check_integrity(&self) -> IntegrityReport {
+        self.inner.read().check_integrity()
+    }
+
+    fn repair(&self) -> IntegrityReport {
+        self.inner.write().repair()
+    }
+
+    #[pyo3(signature = (trace_id, top_n=5))]
+    fn summarize_trace(&self, trace_id: String, top_n: usize) -> TraceSummary {
+        self.inner.read().summarize_trace(&trace_id, top_n)
+    }
+
+    fn add_escalation_rule(&self, rule: EscalationRule) {
+        self.inner.write().add_escalation_rule(rule);
+    }
+
+    fn clear_escalation_rules(&self) {
+        self.inner.write().clear_escalation_rules();
+    }
+
+    fn escalation_rules(&self) -> Vec<EscalationRule> {
+        self.inner.read().escalation_rules()
+    }
+
+    fn set_retention(&self, policy: RetentionPolicy) -> usize {
+        self.inner.write().set_retention(policy)
+    }
+
+    fn prune(&self) -> usize {
+        self.inner.write().prune()
+    }
+
+    fn prune_before(&self, cutoff: f64) -> usize {
+        self.inner.write().prune_before(cutoff)
+    }
+
+    fn memory_usage(&self) -> usize {
+        self.inner.read().memory_usage()
+    }
+
+    fn pruned_count(&self) -> u64 {
+        self.inner.read().pruned_count()
+    }
+
+    /// `events`, `pruned` and `approx_bytes`, in the same shape as the
+    /// engines' `get_memory_stats()`.
+    fn get_memory_stats<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let graph = self.inner.read();
+        let stats = serde_json::json!({"pruned": graph.pruned_count(), "approx_bytes": graph.memory_usage()});
+        reports::memory_stats_dict(py, &stats, Some(graph.size()))
+    }
+
+    fn to_chrome_trace(&self) -> String {
+        self.inner.read().to_chrome_trace()
+    }
+
+    fn write_chrome_trace(&self, path: String) -> PyResult<()> {
+        self.inner.read().write_chrome_trace(&path)
+    }
+
+    fn export_dot(&self, root_span: String) -> PyResult<String> {
+        self.inner.read().export_dot(&root_span)
+    }
+
+    fn export_json_tree(&self, root_span: String) -> PyResult<String> {
+        self.inner.read().export_json_tree(&root_span)
+    }
+}
+
+impl Default for RustCausalGraph {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RustCausalGraph {
+    fn insert(&self, events: Vec<Event>) -> usize {
+        let count = events.len();
+        self.inner.write().add_events(events);
+        count
+    }
+}
+
+/// Folds engine state hashes into one, order-sensitively (FNV-1a over
+/// their little-endian bytes).
+pub(crate) fn combine_hashes(hashes: impl IntoIterator<Item = u64>) -> u64 {
+    let bytes: Vec<u8> = hashes.into_iter().flat_map(u64::to_le_bytes).collect();
+    void_reckoning_shared::desync::hash_bytes(&bytes)
+}
+
+fn snapshot_error(message: String) -> PyErr {
+    EngineError::serialization("Snapshot", message).into()
 }
 
 /// A Python module implemented in Rust.
-#[pymodule]
+#[pymodule(gil_used = false)]
 fn void_reckoning_bridge(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<RustPathfinder>()?;
     m.add_class::<RustCombatEngine>()?;
     m.add_class::<RustAuditor>()?;
     m.add_class::<RustEconomyEngine>()?;
     m.add_class::<RustCausalGraph>()?; // New
+    m.add_class::<campaign::RustCampaignState>()?;
+    m.add_class::<BattleRun>()?;
+    m.add_class::<config::WeaponSpec>()?;
+    m.add_class::<config::UnitSpec>()?;
+    m.add_class::<config::BattleConfig>()?;
+    m.add_class::<config::EconomyConfig>()?;
+    m.add_class::<RustDataRegistry>()?;
+    m.add_class::<datapack::RustDataPacks>()?;
+    m.add_class::<turn::TurnScope>()?;
+    m.add_class::<turn_engine::RustTurnEngine>()?;
+    m.add_class::<RustStateView>()?;
+    m.add_class::<diplomacy::RustDiplomacyEngine>()?;
+    m.add_class::<fleet::RustFleetManager>()?;
+    m.add_class::<ai::RustDecisionEngine>()?;
+    m.add_class::<scenario::RustScenario>()?;
+    m.add_class::<colony::RustColonyEngine>()?;
+    m.add_class::<anomaly::RustAnomalyGenerator>()?;
+    m.add_class::<hazard::RustHazardLayer>()?;
+    m.add_class::<replan::RustRoutePlanner>()?;
+    m.add_class::<RustTopologyReader>()?;
+    m.add_class::<names::RustNameGenerator>()?;
+    m.add_class::<world::RustWorld>()?;
+    m.add_class::<tournament::RustTournamentResult>()?;
+    m.add_class::<PyResources>()?;
+    m.add_class::<reports::PyBattleOutcome>()?;
+    m.add_class::<PyEconomicReport>()?;
+    m.add_class::<PyValidationResult>()?;
+    m.add_class::<PyValidationReport>()?;
+    m.add_function(wrap_pyfunction!(resolve_battles, m)?)?;
+    m.add_function(wrap_pyfunction!(tournament::run_battle_tournament, m)?)?;
+    m.add_function(wrap_pyfunction!(tournament::run_economy_tournament, m)?)?;
+    m.add_function(wrap_pyfunction!(tournament::run_fuzz, m)?)?;
+    m.add_function(wrap_pyfunction!(stubs::generate_stubs, m)?)?;
+    m.add_function(wrap_pyfunction!(info::get_engine_info, m)?)?;
+    m.add_function(wrap_pyfunction!(turn::begin_turn, m)?)?;
+    m.add_function(wrap_pyfunction!(reset_module_state, m)?)?;
     
     // Add shared classes for correct type mapping
     m.add_class::<void_reckoning_shared::Event>()?;
     m.add_class::<void_reckoning_shared::CorrelationContext>()?;
     m.add_class::<void_reckoning_shared::EventSeverity>()?;
+    m.add_class::<RetentionPolicy>()?;
+    m.add_class::<IntegrityReport>()?;
+    m.add_class::<TraceSummary>()?;
+    m.add_class::<SpanOffender>()?;
+    void_reckoning_shared::errors::register(m)?;
     
     // Submodule for observability
-    let obs_submodule = PyModule::new_bound(m.py(), "observability")?;
+    let obs_submodule = PyModule::new(m.py(), "observability")?;
+    obs_submodule.gil_used(false)?;
     observability::observability(&obs_submodule)?;
     m.add_submodule(&obs_submodule)?;
     
@@ -0,0 +1,81 @@
+//! `RustNameGenerator`: reproducible procedural names for systems,
+//! planets, characters and fleets, and short lore lines, from per-faction
+//! phoneme and grammar tables.
+
+use parking_lot::RwLock;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use void_reckoning_scenario::{NameGenerator, NameKind, NameRequest};
+use void_reckoning_shared::errors::EngineError;
+
+#[pyclass(frozen)]
+pub struct RustNameGenerator {
+    inner: RwLock<NameGenerator>,
+}
+
+fn kind(name: &str) -> PyResult<NameKind> {
+    NameKind::parse(name).ok_or_else(|| {
+        PyValueError::new_err(format!("unknown name kind '{}': expected system, planet, character, fleet or lore", name))
+    })
+}
+
+#[pymethods]
+impl RustNameGenerator {
+    /// `tables_json` is a `NameTables`: `{"factions": {"Imperium":
+    /// {"syllables": {"onsets", "nuclei", "codas"}, "min_syllables",
+    /// "max_syllables", "templates": {"planet": ["{parent} {numeral}"]},
+    /// "words": {"title": ["Lord"]}}}, "fallback": "Imperium"}`. Raises
+    /// `RegistryError` for a table that can't produce names.
+    #[new]
+    #[pyo3(signature = (tables_json, seed=0))]
+    fn new(tables_json: &str, seed: u64) -> PyResult<Self> {
+        Ok(Self { inner: RwLock::new(NameGenerator::from_json(tables_json, seed)?) })
+    }
+
+    /// Reads name tables from a JSON file.
+    #[staticmethod]
+    #[pyo3(signature = (path, seed=0))]
+    fn load(py: Python<'_>, path: &str, seed: u64) -> PyResult<Self> {
+        let tables = py
+            .allow_threads(|| std::fs::read_to_string(path))
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("IO error: {}", e)))?;
+        Self::new(&tables, seed)
+    }
+
+    fn tables(&self) -> PyResult<String> {
+        serde_json::to_string(self.inner.read().tables()).map_err(|e| PyErr::from(EngineError::json(e)))
+    }
+
+    /// Replaces the tables, keeping the seed.
+    fn set_tables(&self, tables_json: &str) -> PyResult<()> {
+        let seed = self.inner.read().seed();
+        *self.inner.write() = NameGenerator::from_json(tables_json, seed)?;
+        Ok(())
+    }
+
+    #[getter]
+    fn seed(&self) -> u64 {
+        self.inner.read().seed()
+    }
+
+    /// The name of `key` (a system id, a fleet id, ...) as `faction` would
+    /// call it; always the same for the same seed and arguments. `parent`
+    /// and `index` fill `{parent}` and `{numeral}`/`{number}`.
+    #[pyo3(signature = (kind, faction, key, parent=None, index=None))]
+    fn name(&self, kind: &str, faction: &str, key: &str, parent: Option<String>, index: Option<u32>) -> PyResult<String> {
+        let request = NameRequest { kind: self::kind(kind)?, faction: faction.to_string(), key: key.to_string(), parent, index };
+        Ok(self.inner.read().name(&request)?)
+    }
+
+    /// Names a JSON array of `NameRequest`s (`{"kind", "faction", "key",
+    /// "parent", "index"}`) with no two alike, without the GIL.
+    fn names(&self, py: Python<'_>, requests_json: &str) -> PyResult<Vec<String>> {
+        let requests: Vec<NameRequest> = serde_json::from_str(requests_json).map_err(|e| PyErr::from(EngineError::json(e)))?;
+        Ok(py.allow_threads(|| self.inner.read().names(&requests))?)
+    }
+
+    fn __repr__(&self) -> String {
+        let names = self.inner.read();
+        format!("RustNameGenerator(factions={}, seed={})", names.tables().factions.len(), names.seed())
+    }
+}
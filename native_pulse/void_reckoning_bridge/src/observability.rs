@@ -1,5 +1,13 @@
 use pyo3::prelude::*;
 use void_reckoning_shared::{CorrelationContext, Event, EventLog, EventSeverity};
+use void_reckoning_shared::bus::{EventBus, EventFilter};
+use void_reckoning_shared::callback::CallbackSubscription;
+use void_reckoning_shared::cursor::EventCursor;
+use void_reckoning_shared::escalation::EscalationRule;
+use void_reckoning_shared::scope::PyContextScope;
+use void_reckoning_shared::simtime::{CalendarDate, SimClock, SimTime};
+use void_reckoning_shared::sink::JsonlFileSink;
+use void_reckoning_shared::span::Span;
 
 #[pymodule]
 pub fn observability(m: &Bound<'_, PyModule>) -> PyResult<()> {
@@ -7,5 +15,16 @@ pub fn observability(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<Event>()?;
     m.add_class::<EventLog>()?;
     m.add_class::<EventSeverity>()?;
+    m.add_class::<JsonlFileSink>()?;
+    m.add_class::<Span>()?;
+    m.add_class::<EventBus>()?;
+    m.add_class::<EventFilter>()?;
+    m.add_class::<CallbackSubscription>()?;
+    m.add_class::<SimTime>()?;
+    m.add_class::<SimClock>()?;
+    m.add_class::<CalendarDate>()?;
+    m.add_class::<PyContextScope>()?;
+    m.add_class::<EscalationRule>()?;
+    m.add_class::<EventCursor>()?;
     Ok(())
 }
@@ -0,0 +1,70 @@
+//! `RustTopologyReader`: a frozen copy of a `RustPathfinder`'s topology.
+//! Its searches skip the path cache and take no locks, so AI, trade and UI
+//! threads search it side by side while the turn edits the live
+//! pathfinder, and every search sees the map as it was when the copy was
+//! taken.
+
+use crate::{path_queries, route_options, BatchQuery};
+use pyo3::prelude::*;
+use std::collections::HashMap;
+use std::sync::Arc;
+use void_reckoning_pathfinder::GraphTopology;
+
+#[pyclass(frozen)]
+#[derive(Clone)]
+pub struct RustTopologyReader {
+    topology: Arc<GraphTopology>,
+}
+
+impl RustTopologyReader {
+    pub(crate) fn new(topology: GraphTopology) -> Self {
+        Self { topology: Arc::new(topology) }
+    }
+}
+
+#[pymethods]
+impl RustTopologyReader {
+    /// As `RustPathfinder.find_path`.
+    #[pyo3(signature = (start, end, profile=None, avoid=None, blocked_factions=None, system_owners=None, lane_kinds=None, max_hops=None, max_cost=None, weights=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn find_path(
+        &self,
+        py: Python<'_>,
+        start: String,
+        end: String,
+        profile: Option<String>,
+        avoid: Option<Vec<String>>,
+        blocked_factions: Option<Vec<String>>,
+        system_owners: Option<HashMap<String, String>>,
+        lane_kinds: Option<Vec<String>>,
+        max_hops: Option<usize>,
+        max_cost: Option<f32>,
+        weights: Option<HashMap<String, f32>>,
+    ) -> PyResult<Option<(Vec<String>, f32)>> {
+        py.allow_threads(|| {
+            let options = route_options(&self.topology, avoid, blocked_factions, system_owners, lane_kinds, max_hops, max_cost, weights)?;
+            Ok(self.topology.find_path_uncached(&start, &end, profile, &options))
+        })
+    }
+
+    /// As `RustPathfinder.find_paths_batch`.
+    #[pyo3(signature = (queries, profile=None))]
+    fn find_paths_batch(&self, py: Python<'_>, queries: Vec<BatchQuery>, profile: Option<String>) -> Vec<Option<(Vec<String>, f32)>> {
+        let queries = path_queries(queries, profile);
+        py.allow_threads(|| self.topology.find_paths_batch_uncached(&queries))
+    }
+
+    #[pyo3(signature = (start, max_cost, profile=None))]
+    fn reachable_within(&self, py: Python<'_>, start: String, max_cost: f32, profile: Option<String>) -> Vec<(String, f32)> {
+        py.allow_threads(|| self.topology.reachable_within(&start, max_cost, profile))
+    }
+
+    fn is_reachable(&self, py: Python<'_>, a: String, b: String) -> bool {
+        py.allow_threads(|| self.topology.is_reachable(&a, &b))
+    }
+
+    /// The topology's `state_hash` when the copy was taken.
+    fn state_hash(&self) -> u64 {
+        self.topology.state_hash()
+    }
+}
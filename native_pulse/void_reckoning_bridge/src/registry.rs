@@ -0,0 +1,134 @@
+//! Game data loaded once and shared by every engine that needs it.
+
+use crate::{msgpack_error, parse_registry, registry_kind};
+use pyo3::prelude::*;
+use serde_json::Value;
+use parking_lot::RwLock;
+use std::sync::Arc;
+use void_reckoning_auditor::registry::{Registries, RegistryKind};
+use void_reckoning_combat::resolve::WeaponSetup;
+use void_reckoning_combat::Weapon;
+use void_reckoning_economy::types::ResourceState;
+use void_reckoning_shared::errors::EngineError;
+use void_reckoning_shared::msgpack;
+
+/// Registries (buildings, weapons, factions, ...) loaded once and handed to
+/// `RustAuditor`, `RustCombatEngine` (weapon templates) and
+/// `RustEconomyEngine` (building yields) without copying.
+///
+/// Engines keep the data they were given; after loading more, pass the
+/// registry to them again to pick up the change. Loads swap in a new
+/// snapshot under a short write lock, so readers on other threads never see
+/// a half-loaded registry.
+#[pyclass(frozen)]
+#[derive(Default)]
+pub struct RustDataRegistry {
+    registries: RwLock<Arc<Registries>>,
+}
+
+#[pymethods]
+impl RustDataRegistry {
+    #[new]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces one registry (`"weapons"`, `"buildings"`, ...) with a JSON
+    /// object keyed by id.
+    pub fn load(&self, registry_type: String, data_json: String) -> PyResult<()> {
+        let (kind, data) = parse_registry(&registry_type, &data_json)?;
+        Arc::make_mut(&mut self.registries.write()).replace(kind, data);
+        Ok(())
+    }
+
+    /// `load` with a msgpack-encoded map instead of JSON text.
+    pub fn load_msgpack(&self, registry_type: String, data: &[u8]) -> PyResult<()> {
+        let kind = registry_kind(&registry_type)?;
+        let data: serde_json::Map<String, Value> = msgpack::from_msgpack(data).map_err(msgpack_error)?;
+        Arc::make_mut(&mut self.registries.write()).replace(kind, data);
+        Ok(())
+    }
+
+    /// Bumped every time a registry's content actually changes.
+    #[getter]
+    pub fn version(&self) -> u64 {
+        self.registries.read().version
+    }
+
+    #[pyo3(signature = (registry_type=None))]
+    pub fn hash(&self, registry_type: Option<String>) -> PyResult<u64> {
+        let registries = self.shared();
+        match registry_type {
+            Some(name) => Ok(registries.content_hash(registry_kind(&name)?)),
+            None => Ok(registries.combined_hash()),
+        }
+    }
+
+    /// Ids in one registry, sorted.
+    pub fn keys(&self, registry_type: String) -> PyResult<Vec<String>> {
+        let mut keys: Vec<String> = self.shared().get(registry_kind(&registry_type)?).keys().cloned().collect();
+        keys.sort();
+        Ok(keys)
+    }
+
+    /// One entry as JSON, or None if the id is unknown.
+    pub fn get(&self, registry_type: String, id: String) -> PyResult<Option<String>> {
+        let registries = self.shared();
+        registries
+            .get(registry_kind(&registry_type)?)
+            .get(&id)
+            .map(|v| serde_json::to_string(v).map_err(|e| PyErr::from(EngineError::json(e))))
+            .transpose()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("RustDataRegistry(version={})", self.version())
+    }
+}
+
+impl RustDataRegistry {
+    pub fn shared(&self) -> Arc<Registries> {
+        Arc::clone(&self.registries.read())
+    }
+
+    /// Replaces every registry with `merged`'s, as one swap.
+    pub(crate) fn install(&self, merged: &Registries) {
+        let mut registries = self.registries.write();
+        let registries = Arc::make_mut(&mut registries);
+        for kind in RegistryKind::ALL {
+            registries.replace(kind, merged.get(kind).clone());
+        }
+    }
+}
+
+fn entry<'a>(registries: &'a Registries, kind: RegistryKind, id: &str) -> Result<&'a Value, EngineError> {
+    registries
+        .get(kind)
+        .get(id)
+        .ok_or_else(|| EngineError::Registry(format!("no '{}' entry named '{}'", kind.as_str(), id)))
+}
+
+/// Builds a weapon from its template in the weapons registry, e.g.
+/// `{"range": 40, "damage": 12, "weapon_type": "Energy"}`. The id doubles as
+/// the name unless the template sets one.
+pub fn weapon_template(registries: &Registries, id: &str) -> Result<Weapon, EngineError> {
+    let mut template = entry(registries, RegistryKind::Weapons, id)?.clone();
+    if let Value::Object(fields) = &mut template {
+        fields.entry("name").or_insert_with(|| Value::String(id.to_string()));
+    }
+    let setup: WeaponSetup = serde_json::from_value(template)
+        .map_err(|e| EngineError::Registry(format!("weapon '{}': {}", id, e)))?;
+    Ok(setup.build())
+}
+
+/// `(income, upkeep)` of one building from its `yield` and `upkeep`
+/// objects, e.g. `{"yield": {"credits": 5, "research": 2}}`. Missing
+/// resources count as zero.
+pub fn building_yield(registries: &Registries, id: &str) -> Result<(ResourceState, ResourceState), EngineError> {
+    let building = entry(registries, RegistryKind::Buildings, id)?;
+    let resources = |key: &str| {
+        let amount = |name: &str| building.get(key).and_then(|r| r.get(name)).and_then(Value::as_f64).unwrap_or(0.0);
+        ResourceState::new(amount("credits"), amount("minerals"), amount("energy"), amount("research"))
+    };
+    Ok((resources("yield"), resources("upkeep")))
+}
@@ -0,0 +1,55 @@
+//! `RustRoutePlanner`: a fleet's route kept on a `RustPathfinder` and
+//! repaired, rather than searched again, as lanes are blockaded or closed
+//! and the fleet moves along it.
+
+use crate::RustPathfinder;
+use parking_lot::Mutex;
+use pyo3::prelude::*;
+use void_reckoning_pathfinder::Replanner;
+
+#[pyclass(frozen)]
+pub struct RustRoutePlanner {
+    pathfinder: Py<RustPathfinder>,
+    inner: Mutex<Replanner>,
+}
+
+#[pymethods]
+impl RustRoutePlanner {
+    /// A route from `start` to `goal` on `pathfinder`'s map; `profile` as
+    /// for `RustPathfinder.find_path`.
+    #[new]
+    #[pyo3(signature = (pathfinder, start, goal, profile=None))]
+    fn new(pathfinder: Py<RustPathfinder>, start: &str, goal: &str, profile: Option<String>) -> Self {
+        Self { pathfinder, inner: Mutex::new(Replanner::new(start, goal, profile)) }
+    }
+
+    /// `(path, cost)` from where the fleet is to the goal on the map as it
+    /// is now, or None while there is no route. The first call searches;
+    /// later ones repair around lanes changed since.
+    fn path(&self, py: Python<'_>) -> Option<(Vec<String>, f32)> {
+        let pathfinder = self.pathfinder.get();
+        py.allow_threads(|| self.inner.lock().path(&pathfinder.inner.read()))
+    }
+
+    /// The fleet has arrived at `system`; the next `path` starts there.
+    fn move_to(&self, system: &str) {
+        self.inner.lock().move_to(system);
+    }
+
+    #[getter]
+    fn start(&self) -> String {
+        self.inner.lock().start().to_string()
+    }
+
+    #[getter]
+    fn goal(&self) -> String {
+        self.inner.lock().goal().to_string()
+    }
+
+    /// Systems the last `path` expanded; a repair expands far fewer than
+    /// the first search.
+    #[getter]
+    fn expanded(&self) -> usize {
+        self.inner.lock().last_expanded()
+    }
+}
@@ -0,0 +1,418 @@
+//! Engine reports as typed Python objects and as columnar (dataframe-ready)
+//! dicts, for callers that don't want to re-parse the JSON forms.
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use void_reckoning_auditor::types::{ValidationReport, ValidationResult};
+use void_reckoning_combat::ledger::BattleLedger;
+use void_reckoning_combat::resolve::BattleOutcome;
+use void_reckoning_combat::UnitId;
+use void_reckoning_economy::types::{EconomicReport, ResourceState};
+use void_reckoning_shared::columnar::set_column;
+use void_reckoning_shared::errors::EngineError;
+use void_reckoning_shared::pyvalue::value_to_py;
+
+fn to_json(value: &impl serde::Serialize) -> PyResult<String> {
+    serde_json::to_string(value).map_err(|e| PyErr::from(EngineError::json(e)))
+}
+
+fn resources_value(state: &ResourceState) -> Value {
+    let (credits, minerals, energy, research) = state.to_floats();
+    json!({"credits": credits, "minerals": minerals, "energy": energy, "research": research})
+}
+
+/// Credits, minerals, energy and research as plain (unscaled) floats.
+#[pyclass(frozen, name = "Resources")]
+#[derive(Clone)]
+pub struct PyResources {
+    state: ResourceState,
+}
+
+impl From<ResourceState> for PyResources {
+    fn from(state: ResourceState) -> Self {
+        Self { state }
+    }
+}
+
+#[pymethods]
+impl PyResources {
+    #[getter]
+    fn credits(&self) -> f64 {
+        self.state.to_floats().0
+    }
+
+    #[getter]
+    fn minerals(&self) -> f64 {
+        self.state.to_floats().1
+    }
+
+    #[getter]
+    fn energy(&self) -> f64 {
+        self.state.to_floats().2
+    }
+
+    #[getter]
+    fn research(&self) -> f64 {
+        self.state.to_floats().3
+    }
+
+    fn to_dict(&self, py: Python<'_>) -> PyResult<PyObject> {
+        value_to_py(py, &resources_value(&self.state))
+    }
+
+    fn __repr__(&self) -> String {
+        let (credits, minerals, energy, research) = self.state.to_floats();
+        format!("Resources(credits={:?}, minerals={:?}, energy={:?}, research={:?})", credits, minerals, energy, research)
+    }
+}
+
+/// One faction's income for the turn, as returned by
+/// `RustEconomyEngine.process_all_reports`. `to_json` gives the same
+/// (fixed-point) JSON as `process_faction`.
+#[pyclass(frozen, name = "EconomicReport")]
+pub struct PyEconomicReport {
+    report: EconomicReport,
+}
+
+impl From<EconomicReport> for PyEconomicReport {
+    fn from(report: EconomicReport) -> Self {
+        Self { report }
+    }
+}
+
+#[pymethods]
+impl PyEconomicReport {
+    #[getter]
+    fn faction_name(&self) -> &str {
+        &self.report.faction_name
+    }
+
+    #[getter]
+    fn total_income(&self) -> PyResources {
+        self.report.total_income.into()
+    }
+
+    #[getter]
+    fn total_upkeep(&self) -> PyResources {
+        self.report.total_upkeep.into()
+    }
+
+    #[getter]
+    fn net_profit(&self) -> PyResources {
+        self.report.net_profit.into()
+    }
+
+    #[getter]
+    fn income_by_category(&self) -> HashMap<String, PyResources> {
+        self.report.income_by_category.iter().map(|(k, v)| (k.clone(), (*v).into())).collect()
+    }
+
+    #[getter]
+    fn is_insolvent(&self) -> bool {
+        self.report.is_insolvent
+    }
+
+    #[getter]
+    fn active_nodes(&self) -> usize {
+        self.report.active_nodes
+    }
+
+    /// Attributes as a dict, resources as unscaled floats.
+    fn to_dict(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let r = &self.report;
+        let by_category: serde_json::Map<String, Value> =
+            r.income_by_category.iter().map(|(k, v)| (k.clone(), resources_value(v))).collect();
+        let value = json!({
+            "faction_name": r.faction_name,
+            "total_income": resources_value(&r.total_income),
+            "total_upkeep": resources_value(&r.total_upkeep),
+            "net_profit": resources_value(&r.net_profit),
+            "income_by_category": by_category,
+            "is_insolvent": r.is_insolvent,
+            "active_nodes": r.active_nodes,
+        });
+        value_to_py(py, &value)
+    }
+
+    fn to_json(&self) -> PyResult<String> {
+        to_json(&self.report)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "EconomicReport(faction_name='{}', net_credits={:?}, is_insolvent={}, active_nodes={})",
+            self.report.faction_name,
+            self.report.net_profit.to_floats().0,
+            if self.report.is_insolvent { "True" } else { "False" },
+            self.report.active_nodes
+        )
+    }
+}
+
+/// One validation finding. `category` and `severity` are the variant names
+/// (`"Units"`, `"Error"`, ...).
+#[pyclass(frozen, name = "ValidationResult")]
+pub struct PyValidationResult {
+    result: ValidationResult,
+}
+
+impl From<ValidationResult> for PyValidationResult {
+    fn from(result: ValidationResult) -> Self {
+        Self { result }
+    }
+}
+
+#[pymethods]
+impl PyValidationResult {
+    #[getter]
+    fn category(&self) -> String {
+        format!("{:?}", self.result.category)
+    }
+
+    #[getter]
+    fn severity(&self) -> String {
+        format!("{:?}", self.result.severity)
+    }
+
+    #[getter]
+    fn entity_id(&self) -> &str {
+        &self.result.entity_id
+    }
+
+    #[getter]
+    fn message(&self) -> &str {
+        &self.result.message
+    }
+
+    #[getter]
+    fn file_path(&self) -> Option<&str> {
+        self.result.file_path.as_deref()
+    }
+
+    #[getter]
+    fn rule_name(&self) -> &str {
+        &self.result.rule_name
+    }
+
+    #[getter]
+    fn timestamp(&self) -> u64 {
+        self.result.timestamp
+    }
+
+    fn to_dict(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let value = serde_json::to_value(&self.result).map_err(|e| PyErr::from(EngineError::json(e)))?;
+        value_to_py(py, &value)
+    }
+
+    fn to_json(&self) -> PyResult<String> {
+        to_json(&self.result)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ValidationResult(severity='{:?}', rule_name='{}', entity_id='{}')",
+            self.result.severity, self.result.rule_name, self.result.entity_id
+        )
+    }
+}
+
+/// Aggregated batch validation, as returned by
+/// `RustAuditor.validate_batch_report`. The summary counts are flattened
+/// onto the report.
+#[pyclass(frozen, name = "ValidationReport")]
+pub struct PyValidationReport {
+    report: ValidationReport,
+}
+
+impl From<ValidationReport> for PyValidationReport {
+    fn from(report: ValidationReport) -> Self {
+        Self { report }
+    }
+}
+
+#[pymethods]
+impl PyValidationReport {
+    #[getter]
+    fn results(&self) -> Vec<PyValidationResult> {
+        self.report.results.iter().cloned().map(PyValidationResult::from).collect()
+    }
+
+    #[getter]
+    fn correlation_id(&self) -> &str {
+        &self.report.correlation_id
+    }
+
+    #[getter]
+    fn total_checks(&self) -> usize {
+        self.report.summary.total_checks
+    }
+
+    #[getter]
+    fn passed(&self) -> usize {
+        self.report.summary.passed
+    }
+
+    #[getter]
+    fn warnings(&self) -> usize {
+        self.report.summary.warnings
+    }
+
+    #[getter]
+    fn errors(&self) -> usize {
+        self.report.summary.errors
+    }
+
+    #[getter]
+    fn critical(&self) -> usize {
+        self.report.summary.critical
+    }
+
+    /// Same layout as the `validate_batch` JSON.
+    fn to_dict(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let value = serde_json::to_value(&self.report).map_err(|e| PyErr::from(EngineError::json(e)))?;
+        value_to_py(py, &value)
+    }
+
+    fn to_json(&self) -> PyResult<String> {
+        to_json(&self.report)
+    }
+
+    fn __len__(&self) -> usize {
+        self.report.results.len()
+    }
+
+    fn __repr__(&self) -> String {
+        let s = &self.report.summary;
+        format!(
+            "ValidationReport(total_checks={}, passed={}, warnings={}, errors={}, critical={})",
+            s.total_checks, s.passed, s.warnings, s.errors, s.critical
+        )
+    }
+}
+
+/// Where a battle stands after `RustCombatEngine.step()`. Truthy while
+/// the battle goes on, so `while battle.step():` runs it to the end.
+#[pyclass(frozen, name = "BattleOutcome")]
+pub struct PyBattleOutcome {
+    outcome: BattleOutcome,
+}
+
+impl From<BattleOutcome> for PyBattleOutcome {
+    fn from(outcome: BattleOutcome) -> Self {
+        Self { outcome }
+    }
+}
+
+#[pymethods]
+impl PyBattleOutcome {
+    #[getter]
+    fn ongoing(&self) -> bool {
+        self.outcome.reason.is_none()
+    }
+
+    #[getter]
+    fn turns(&self) -> u32 {
+        self.outcome.turns
+    }
+
+    #[getter]
+    fn winner(&self) -> Option<u8> {
+        self.outcome.winner
+    }
+
+    /// `Annihilation`, `TurnLimit`, `MoraleCollapse`, `ObjectiveHeld` or
+    /// `DamageThreshold`; None while ongoing.
+    #[getter]
+    fn reason(&self) -> Option<String> {
+        self.outcome.reason.map(|r| format!("{:?}", r))
+    }
+
+    #[getter]
+    fn timed_out(&self) -> bool {
+        self.outcome.timed_out
+    }
+
+    #[getter]
+    fn survivors(&self) -> Vec<UnitId> {
+        self.outcome.survivors.clone()
+    }
+
+    #[getter]
+    fn casualties(&self) -> Vec<UnitId> {
+        self.outcome.casualties.clone()
+    }
+
+    fn to_json(&self) -> PyResult<String> {
+        to_json(&self.outcome)
+    }
+
+    fn __bool__(&self) -> bool {
+        self.ongoing()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("BattleOutcome(turns={}, winner={:?}, reason={:?})", self.outcome.turns, self.outcome.winner, self.outcome.reason)
+    }
+}
+
+/// Adds `<prefix>_credits`, `_minerals`, `_energy` and `_research` float columns.
+fn resource_columns<'py>(columns: &Bound<'py, PyDict>, prefix: &str, states: &[&ResourceState]) -> PyResult<()> {
+    let floats: Vec<(f64, f64, f64, f64)> = states.iter().map(|s| s.to_floats()).collect();
+    set_column(columns, &format!("{}_credits", prefix), floats.iter().map(|f| f.0).collect())?;
+    set_column(columns, &format!("{}_minerals", prefix), floats.iter().map(|f| f.1).collect())?;
+    set_column(columns, &format!("{}_energy", prefix), floats.iter().map(|f| f.2).collect())?;
+    set_column(columns, &format!("{}_research", prefix), floats.iter().map(|f| f.3).collect())
+}
+
+/// `stats` as a dict, plus `events` (buffered event-log entries) when the
+/// engine has a log.
+pub fn memory_stats_dict<'py>(py: Python<'py>, stats: &impl serde::Serialize, events: Option<usize>) -> PyResult<Bound<'py, PyDict>> {
+    let mut value = serde_json::to_value(stats).map_err(|e| PyErr::from(EngineError::json(e)))?;
+    if let (Value::Object(fields), Some(events)) = (&mut value, events) {
+        fields.insert("events".to_string(), events.into());
+    }
+    Ok(value_to_py(py, &value)?.into_bound(py).downcast_into::<PyDict>()?)
+}
+
+/// `{"units": {id: tally}, "kills": [...]}` with integer unit ids.
+pub fn battle_statistics_dict<'py>(py: Python<'py>, ledger: &BattleLedger) -> PyResult<Bound<'py, PyDict>> {
+    let units = PyDict::new(py);
+    for (id, tally) in &ledger.units {
+        let tally = serde_json::to_value(tally).map_err(|e| PyErr::from(EngineError::json(e)))?;
+        units.set_item(id, value_to_py(py, &tally)?)?;
+    }
+    let kills = serde_json::to_value(&ledger.kills).map_err(|e| PyErr::from(EngineError::json(e)))?;
+    let stats = PyDict::new(py);
+    stats.set_item("units", units)?;
+    stats.set_item("kills", value_to_py(py, &kills)?)?;
+    Ok(stats)
+}
+
+/// One row per faction, sorted by name, with unscaled resource amounts.
+pub fn economic_report_columns<'py>(py: Python<'py>, reports: &BTreeMap<String, EconomicReport>) -> PyResult<Bound<'py, PyDict>> {
+    let rows: Vec<&EconomicReport> = reports.values().collect();
+
+    let columns = PyDict::new(py);
+    set_column(&columns, "faction", rows.iter().map(|r| r.faction_name.as_str()).collect())?;
+    resource_columns(&columns, "income", &rows.iter().map(|r| &r.total_income).collect::<Vec<_>>())?;
+    resource_columns(&columns, "upkeep", &rows.iter().map(|r| &r.total_upkeep).collect::<Vec<_>>())?;
+    resource_columns(&columns, "net", &rows.iter().map(|r| &r.net_profit).collect::<Vec<_>>())?;
+    set_column(&columns, "is_insolvent", rows.iter().map(|r| r.is_insolvent).collect())?;
+    set_column(&columns, "active_nodes", rows.iter().map(|r| r.active_nodes).collect())?;
+    Ok(columns)
+}
+
+/// One row per validation finding.
+pub fn validation_result_columns<'py>(py: Python<'py>, results: &[ValidationResult]) -> PyResult<Bound<'py, PyDict>> {
+    let columns = PyDict::new(py);
+    set_column(&columns, "entity_id", results.iter().map(|r| r.entity_id.as_str()).collect())?;
+    set_column(&columns, "severity", results.iter().map(|r| format!("{:?}", r.severity)).collect())?;
+    set_column(&columns, "category", results.iter().map(|r| format!("{:?}", r.category)).collect())?;
+    set_column(&columns, "rule_name", results.iter().map(|r| r.rule_name.as_str()).collect())?;
+    set_column(&columns, "message", results.iter().map(|r| r.message.as_str()).collect())?;
+    set_column(&columns, "file_path", results.iter().map(|r| r.file_path.as_deref()).collect())?;
+    set_column(&columns, "timestamp", results.iter().map(|r| r.timestamp).collect())?;
+    Ok(columns)
+}
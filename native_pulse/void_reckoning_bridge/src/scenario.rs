@@ -0,0 +1,106 @@
+//! `RustScenario`: scenario scripts (triggers and effects, as JSON) that a
+//! `RustTurnEngine` runs natively after every turn.
+
+use crate::fleet::RustFleetManager;
+use parking_lot::RwLock;
+use pyo3::prelude::*;
+use void_reckoning_orchestrator::TurnReport;
+use void_reckoning_scenario::{Scenario, ScenarioEngine, ScenarioSnapshot};
+use void_reckoning_shared::errors::EngineError;
+use void_reckoning_shared::{CorrelationContext, EventLog};
+
+#[pyclass(frozen)]
+pub struct RustScenario {
+    pub(crate) inner: RwLock<ScenarioEngine>,
+    /// Where `SpawnFleet` effects put their fleets.
+    pub(crate) fleets: Option<Py<RustFleetManager>>,
+}
+
+fn to_json(value: &impl serde::Serialize) -> PyResult<String> {
+    serde_json::to_string(value).map_err(|e| PyErr::from(EngineError::json(e)))
+}
+
+fn from_json<T: serde::de::DeserializeOwned>(json: &str) -> PyResult<T> {
+    serde_json::from_str(json).map_err(|e| PyErr::from(EngineError::json(e)))
+}
+
+#[pymethods]
+impl RustScenario {
+    /// `script_json` is a `Scenario`: `{"name", "rules": [{"id", "trigger":
+    /// {"OnTurn": {"turn": 5}}, "effects": [{"Message": {"text": ...}}],
+    /// "repeat": false}]}`.
+    #[new]
+    #[pyo3(signature = (script_json, fleets=None))]
+    fn new(script_json: &str, fleets: Option<Py<RustFleetManager>>) -> PyResult<Self> {
+        let scenario: Scenario = from_json(script_json)?;
+        Ok(Self { inner: RwLock::new(ScenarioEngine::new(scenario)), fleets })
+    }
+
+    /// Reads a scenario script from a JSON file.
+    #[staticmethod]
+    #[pyo3(signature = (path, fleets=None))]
+    fn load(py: Python<'_>, path: &str, fleets: Option<Py<RustFleetManager>>) -> PyResult<Self> {
+        let script = py
+            .allow_threads(|| std::fs::read_to_string(path))
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("IO error: {}", e)))?;
+        Self::new(&script, fleets)
+    }
+
+    /// Restores a scenario, including which rules have fired, from
+    /// `to_json` output.
+    #[staticmethod]
+    #[pyo3(signature = (snapshot_json, fleets=None))]
+    fn from_json(snapshot_json: &str, fleets: Option<Py<RustFleetManager>>) -> PyResult<Self> {
+        let snapshot: ScenarioSnapshot = from_json(snapshot_json)?;
+        Ok(Self { inner: RwLock::new(ScenarioEngine::from_snapshot(snapshot)), fleets })
+    }
+
+    fn to_json(&self) -> PyResult<String> {
+        to_json(&self.inner.read().snapshot())
+    }
+
+    #[doc = state_hash_doc!()]
+    fn state_hash(&self) -> u64 {
+        self.inner.read().state_hash()
+    }
+
+    /// Events are written here; without a log of its own the scenario
+    /// writes into the turn engine's.
+    #[pyo3(signature = (capacity=None))]
+    fn enable_event_logging(&self, capacity: Option<usize>) -> EventLog {
+        let log = EventLog::with_capacity(capacity);
+        self.inner.write().set_event_log(log.clone());
+        log
+    }
+
+    fn set_correlation_context(&self, context: &CorrelationContext) {
+        self.inner.write().set_correlation_context(context.clone());
+    }
+
+    #[getter]
+    fn name(&self) -> String {
+        self.inner.read().scenario().name.clone()
+    }
+
+    /// The rules as JSON.
+    fn rules(&self) -> PyResult<String> {
+        to_json(&self.inner.read().scenario().rules)
+    }
+
+    /// Ids of one-shot rules that have fired.
+    fn fired_rules(&self) -> Vec<String> {
+        self.inner.read().fired().iter().cloned().collect()
+    }
+
+    /// The firings a JSON `TurnReport` would set off, without applying
+    /// them, as JSON.
+    fn evaluate(&self, report_json: &str) -> PyResult<String> {
+        let report: TurnReport = from_json(report_json)?;
+        to_json(&self.inner.read().evaluate(&report))
+    }
+
+    fn __repr__(&self) -> String {
+        let engine = self.inner.read();
+        format!("RustScenario(name={:?}, rules={}, fired={})", engine.scenario().name, engine.scenario().rules.len(), engine.fired().len())
+    }
+}
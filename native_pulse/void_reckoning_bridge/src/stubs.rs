@@ -0,0 +1,24 @@
+//! Type stubs generated by `build.rs` from the pyo3 definitions.
+
+use pyo3::exceptions::PyIOError;
+use pyo3::prelude::*;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+include!(concat!(env!("OUT_DIR"), "/stubs.rs"));
+
+/// Returns the `.pyi` stubs for this module as `{file name: contents}`.
+/// With `directory`, also writes them as a PEP 561 stub package,
+/// `<directory>/void_reckoning_bridge-stubs/`, which mypy and IDEs pick up
+/// once it is on the search path.
+#[pyfunction]
+#[pyo3(signature = (directory=None))]
+pub fn generate_stubs(directory: Option<PathBuf>) -> PyResult<BTreeMap<String, String>> {
+    if let Some(directory) = directory {
+        let package = directory.join("void_reckoning_bridge-stubs");
+        std::fs::create_dir_all(&package)
+            .and_then(|_| STUBS.iter().try_for_each(|(file, text)| std::fs::write(package.join(file), text)))
+            .map_err(|e| PyIOError::new_err(format!("IO error: {}", e)))?;
+    }
+    Ok(STUBS.iter().map(|(file, text)| (file.to_string(), text.to_string())).collect())
+}
@@ -0,0 +1,110 @@
+//! Balance tournaments: seed sweeps and parameter grids over battles and
+//! economy scenarios, run on every core without the GIL. Results come
+//! back as JSON, CSV or columns for a dataframe. Invariant fuzzing runs on
+//! the same pool.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use void_reckoning_shared::columnar;
+use void_reckoning_shared::errors::EngineError;
+use void_reckoning_shared::pyvalue::value_to_py;
+use void_reckoning_tournament::{BattleTournament, EconomyTournament, FuzzSpec, Fuzzer, GridError, Table};
+
+/// Aggregated tournament results: one row per faction per matchup (or per
+/// faction per turn of an economy scenario, or per failed fuzz run), a
+/// column per grid axis.
+#[pyclass(frozen)]
+pub struct RustTournamentResult {
+    json: String,
+    table: Table,
+}
+
+fn grid_error(e: GridError) -> PyErr {
+    PyValueError::new_err(e.to_string())
+}
+
+#[pymethods]
+impl RustTournamentResult {
+    /// The `MatchupStats` or `IncomeCurve`s as a JSON array, or the
+    /// `FuzzReport`.
+    fn to_json(&self) -> String {
+        self.json.clone()
+    }
+
+    fn to_csv(&self) -> String {
+        self.table.to_csv()
+    }
+
+    fn column_names(&self) -> Vec<String> {
+        self.table.columns.clone()
+    }
+
+    /// The table as a dict of column lists.
+    fn columns<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let columns = PyDict::new(py);
+        for (index, name) in self.table.columns.iter().enumerate() {
+            let values = self.table.column(index).map(|value| value_to_py(py, value)).collect::<PyResult<Vec<_>>>()?;
+            columnar::set_column(&columns, name, values)?;
+        }
+        Ok(columns)
+    }
+
+    /// The table as a `pyarrow.Table` (requires pyarrow).
+    fn to_arrow<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        columnar::arrow_table(&self.columns(py)?)
+    }
+
+    fn __len__(&self) -> usize {
+        self.table.rows.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("RustTournamentResult(rows={})", self.table.rows.len())
+    }
+}
+
+/// Fights every matchup in `spec_json` (a `BattleTournament`: `matchups`
+/// as `BattleSetup`s, optional `grid` axes, `seeds` per grid point and a
+/// base `seed`) on `workers` threads (0 = one per core).
+#[pyfunction]
+#[pyo3(signature = (spec_json, workers=0))]
+pub fn run_battle_tournament(py: Python<'_>, spec_json: &str, workers: usize) -> PyResult<RustTournamentResult> {
+    py.allow_threads(|| {
+        let tournament: BattleTournament = serde_json::from_str(spec_json).map_err(EngineError::json)?;
+        let stats = tournament.run(workers).map_err(grid_error)?;
+        let json = serde_json::to_string(&stats).map_err(EngineError::json)?;
+        Ok(RustTournamentResult { json, table: Table::battles(&stats) })
+    })
+}
+
+/// Runs every scenario in `spec_json` (an `EconomyTournament`:
+/// `scenarios` and optional `grid` axes) through the turn engine on
+/// `workers` threads (0 = one per core), recording income curves.
+#[pyfunction]
+#[pyo3(signature = (spec_json, workers=0))]
+pub fn run_economy_tournament(py: Python<'_>, spec_json: &str, workers: usize) -> PyResult<RustTournamentResult> {
+    py.allow_threads(|| {
+        let tournament: EconomyTournament = serde_json::from_str(spec_json).map_err(EngineError::json)?;
+        let curves = tournament.run(workers).map_err(grid_error)?;
+        let json = serde_json::to_string(&curves).map_err(EngineError::json)?;
+        Ok(RustTournamentResult { json, table: Table::income(&curves) })
+    })
+}
+
+/// Fuzzes `spec_json` (a `FuzzSpec`: `runs` campaigns seeded from `seed`,
+/// each `turns` long with `systems`, `factions`, `nodes`, `routes` and
+/// `units`, optional `registries` contents) against the auditor's
+/// invariants on `workers` threads (0 = one per core). The JSON is the
+/// `FuzzReport`; the table has a row per failure, shrunk to its smallest
+/// reproduction.
+#[pyfunction]
+#[pyo3(signature = (spec_json, workers=0))]
+pub fn run_fuzz(py: Python<'_>, spec_json: &str, workers: usize) -> PyResult<RustTournamentResult> {
+    py.allow_threads(|| {
+        let spec: FuzzSpec = serde_json::from_str(spec_json).map_err(EngineError::json)?;
+        let report = Fuzzer::new(spec).run(workers);
+        let json = serde_json::to_string(&report).map_err(EngineError::json)?;
+        Ok(RustTournamentResult { json, table: Table::fuzz(&report) })
+    })
+}
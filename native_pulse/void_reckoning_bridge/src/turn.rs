@@ -0,0 +1,184 @@
+//! One call to put every engine on the same trace for a campaign turn,
+//! instead of a `set_correlation_context` per engine.
+
+use crate::{RustAuditor, RustCombatEngine, RustEconomyEngine, RustPathfinder};
+use pyo3::prelude::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+use void_reckoning_shared::CorrelationContext;
+
+/// Handle for one campaign turn. `context` is the turn's span (a child of
+/// the context passed to `begin_turn`); each engine runs under its own
+/// child of it, so their events are siblings in the same trace.
+///
+/// `end()` (or leaving a `with` block) puts the engines back on the
+/// context the turn was started from.
+#[pyclass(frozen)]
+pub struct TurnScope {
+    parent: CorrelationContext,
+    context: CorrelationContext,
+    turn: Option<u64>,
+    pathfinder: Option<(Py<RustPathfinder>, CorrelationContext)>,
+    economy: Option<(Py<RustEconomyEngine>, CorrelationContext)>,
+    auditor: Option<(Py<RustAuditor>, CorrelationContext)>,
+    battles: Vec<(String, Py<RustCombatEngine>, CorrelationContext)>,
+    ended: AtomicBool,
+}
+
+#[pymethods]
+impl TurnScope {
+    #[getter]
+    fn context(&self) -> CorrelationContext {
+        self.context.clone()
+    }
+
+    #[getter]
+    fn parent(&self) -> CorrelationContext {
+        self.parent.clone()
+    }
+
+    #[getter]
+    fn turn(&self) -> Option<u64> {
+        self.turn
+    }
+
+    #[getter]
+    fn ended(&self) -> bool {
+        self.ended.load(Ordering::Acquire)
+    }
+
+    /// Context handed to `engine` (`"pathfinder"`, `"economy"`, `"auditor"`,
+    /// a battle name, or `"combat"` for the engine given to `begin_turn`),
+    /// or None if it isn't part of this turn.
+    fn context_for(&self, engine: &str) -> Option<CorrelationContext> {
+        let context = match engine {
+            "pathfinder" => self.pathfinder.as_ref().map(|(_, c)| c),
+            "economy" => self.economy.as_ref().map(|(_, c)| c),
+            "auditor" => self.auditor.as_ref().map(|(_, c)| c),
+            name => self.battles.iter().find(|(n, _, _)| n == name).map(|(_, _, c)| c),
+        };
+        context.cloned()
+    }
+
+    /// A fresh child of the turn context, for work done in Python.
+    fn child(&self) -> CorrelationContext {
+        self.context.child()
+    }
+
+    /// Restores the parent context on every engine. Calling it twice is
+    /// harmless.
+    fn end(&self) -> PyResult<()> {
+        if self.ended.swap(true, Ordering::AcqRel) {
+            return Ok(());
+        }
+        if let Some((auditor, _)) = &self.auditor {
+            auditor.get().set_correlation_context(&self.parent)?;
+        }
+        if let Some((pathfinder, _)) = &self.pathfinder {
+            pathfinder.get().set_correlation_context(&self.parent);
+        }
+        if let Some((economy, _)) = &self.economy {
+            economy.get().set_correlation_context(&self.parent);
+        }
+        for (_, battle, _) in &self.battles {
+            battle.get().set_correlation_context(&self.parent);
+        }
+        Ok(())
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    #[pyo3(signature = (_exc_type=None, _exc_value=None, _traceback=None))]
+    fn __exit__(&self, _exc_type: Option<PyObject>, _exc_value: Option<PyObject>, _traceback: Option<PyObject>) -> PyResult<bool> {
+        self.end()?;
+        Ok(false)
+    }
+
+    fn __repr__(&self) -> String {
+        let turn = self.turn.map_or_else(|| "None".to_string(), |t| t.to_string());
+        let ended = if self.ended() { "True" } else { "False" };
+        format!("TurnScope(turn={}, trace_id='{}', ended={})", turn, self.context.trace_id, ended)
+    }
+}
+
+impl TurnScope {
+    /// Fans child contexts of a new turn span out to the given engines and
+    /// stamps `turn` on combat and economy events. The auditor goes first,
+    /// so an uninitialized one fails the call before anything changes.
+    pub(crate) fn begin(
+        py: Python<'_>,
+        context: &CorrelationContext,
+        turn: Option<u64>,
+        pathfinder: Option<&Py<RustPathfinder>>,
+        economy: Option<&Py<RustEconomyEngine>>,
+        auditor: Option<&Py<RustAuditor>>,
+        battles: Vec<(String, &Py<RustCombatEngine>)>,
+    ) -> PyResult<Self> {
+        let turn_context = context.child();
+
+        let auditor = auditor
+            .map(|a| -> PyResult<_> {
+                let child = turn_context.child();
+                a.get().set_correlation_context(&child)?;
+                Ok((a.clone_ref(py), child))
+            })
+            .transpose()?;
+        let pathfinder = pathfinder.map(|p| {
+            let child = turn_context.child();
+            p.get().set_correlation_context(&child);
+            (p.clone_ref(py), child)
+        });
+        let economy = economy.map(|e| {
+            let child = turn_context.child();
+            let engine = e.get();
+            engine.set_correlation_context(&child);
+            if turn.is_some() {
+                engine.set_campaign_turn(turn);
+            }
+            (e.clone_ref(py), child)
+        });
+        let battles = battles
+            .into_iter()
+            .map(|(name, b)| {
+                let child = turn_context.child();
+                let engine = b.get();
+                engine.set_correlation_context(&child);
+                if turn.is_some() {
+                    engine.set_campaign_turn(turn);
+                }
+                (name, b.clone_ref(py), child)
+            })
+            .collect();
+
+        Ok(Self {
+            parent: context.clone(),
+            context: turn_context,
+            turn,
+            pathfinder,
+            economy,
+            auditor,
+            battles,
+            ended: AtomicBool::new(false),
+        })
+    }
+}
+
+/// Starts a campaign turn under `context`: every engine given gets its own
+/// child of one turn span (same trace), and `turn`, if set, is stamped on
+/// combat and economy events. Returns the `TurnScope`; use it as a context
+/// manager to restore `context` on the engines afterwards.
+#[pyfunction]
+#[pyo3(signature = (context, pathfinder=None, combat=None, economy=None, auditor=None, turn=None))]
+pub fn begin_turn(
+    py: Python<'_>,
+    context: &CorrelationContext,
+    pathfinder: Option<Py<RustPathfinder>>,
+    combat: Option<Py<RustCombatEngine>>,
+    economy: Option<Py<RustEconomyEngine>>,
+    auditor: Option<Py<RustAuditor>>,
+    turn: Option<u64>,
+) -> PyResult<TurnScope> {
+    let battles = combat.as_ref().map(|c| ("combat".to_string(), c)).into_iter().collect();
+    TurnScope::begin(py, context, turn, pathfinder.as_ref(), economy.as_ref(), auditor.as_ref(), battles)
+}
@@ -0,0 +1,537 @@
+//! `RustTurnEngine`: a whole campaign turn (queued actions, invasions, trade, income,
+//! upkeep, production, colonization, audits, diplomacy, then hazards, anomalies and scenario
+//! scripts) in one native call over the Python-side engines.
+
+use crate::anomaly::RustAnomalyGenerator;
+use crate::colony::RustColonyEngine;
+use crate::reports::PyResources;
+use crate::diplomacy::RustDiplomacyEngine;
+use crate::fleet::RustFleetManager;
+use crate::hazard::RustHazardLayer;
+use crate::scenario::RustScenario;
+use crate::views::RustStateView;
+use crate::{EconomyState, RustAuditor, RustEconomyEngine, RustPathfinder};
+use parking_lot::Mutex;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use void_reckoning_auditor::types::EntityType;
+use void_reckoning_economy::logistics::LogisticsRules;
+use void_reckoning_economy::types::ResourceState;
+use void_reckoning_orchestrator::replay::DEFAULT_KEYFRAME_INTERVAL;
+use void_reckoning_pathfinder::GraphTopology;
+use void_reckoning_orchestrator::{Action, InvasionOrder, ProductionOrder, ReplayEngines, TurnEngine, TurnEngines, TurnReport, VictoryRules};
+use void_reckoning_scenario::{AnomalyReport, AnomalyTargets, HazardReport, ScenarioReport, ScenarioTargets};
+use void_reckoning_shared::errors::EngineError;
+use void_reckoning_shared::ids::{EntityId, FactionId};
+use void_reckoning_shared::published::Published;
+use void_reckoning_shared::simtime::SimClock;
+use void_reckoning_shared::{CorrelationContext, EventLog};
+
+/// A turn report with the hazard, anomaly and scenario reports alongside.
+#[derive(Serialize)]
+struct ScriptedTurn<'a> {
+    #[serde(flatten)]
+    report: &'a TurnReport,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hazards: Option<&'a HazardReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    anomalies: Option<&'a AnomalyReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    scenario: Option<&'a ScenarioReport>,
+}
+
+/// Owns the state kept between turns (treasuries, production queue,
+/// pending audits) and runs turns over the engines it was built with.
+/// Those engines stay usable from Python in between.
+#[pyclass(frozen)]
+pub struct RustTurnEngine {
+    engine: Mutex<TurnEngine>,
+    pathfinder: Py<RustPathfinder>,
+    economy: Py<RustEconomyEngine>,
+    auditor: Option<Py<RustAuditor>>,
+    diplomacy: Option<Py<RustDiplomacyEngine>>,
+    scenario: Option<Py<RustScenario>>,
+    colonies: Option<Py<RustColonyEngine>>,
+    anomalies: Option<Py<RustAnomalyGenerator>>,
+    hazards: Option<Py<RustHazardLayer>>,
+    fleets: Option<Py<RustFleetManager>>,
+    published: Published<RustStateView>,
+}
+
+#[pymethods]
+impl RustTurnEngine {
+    #[new]
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (pathfinder, economy, auditor=None, universe_id="campaign".to_string(), diplomacy=None, scenario=None, colonies=None, anomalies=None, hazards=None, fleets=None))]
+    fn new(
+        pathfinder: Py<RustPathfinder>,
+        economy: Py<RustEconomyEngine>,
+        auditor: Option<Py<RustAuditor>>,
+        universe_id: String,
+        diplomacy: Option<Py<RustDiplomacyEngine>>,
+        scenario: Option<Py<RustScenario>>,
+        colonies: Option<Py<RustColonyEngine>>,
+        anomalies: Option<Py<RustAnomalyGenerator>>,
+        hazards: Option<Py<RustHazardLayer>>,
+        fleets: Option<Py<RustFleetManager>>,
+    ) -> Self {
+        Self {
+            engine: Mutex::new(TurnEngine::new(universe_id)),
+            pathfinder,
+            economy,
+            auditor,
+            diplomacy,
+            scenario,
+            colonies,
+            anomalies,
+            hazards,
+            fleets,
+            published: Published::default(),
+        }
+    }
+
+    /// Last turn run (0 before the first); the next `run_turn` is `turn + 1`.
+    #[getter]
+    fn turn(&self) -> u64 {
+        self.engine.lock().turn()
+    }
+
+    #[setter]
+    fn set_turn(&self, turn: u64) {
+        self.engine.lock().set_turn(turn);
+    }
+
+    /// The campaign's `SimClock`. Hand it to battles, logs and other
+    /// engines so they all read this engine's turn.
+    #[getter]
+    fn clock(&self) -> SimClock {
+        self.engine.lock().clock().clone()
+    }
+
+    /// Runs the campaign on `clock` from its current turn. Ends any
+    /// recording.
+    #[setter]
+    fn set_clock(&self, clock: SimClock) {
+        self.engine.lock().set_clock(clock);
+    }
+
+    /// Hash of the turn engine's own state (treasuries, queues, pending
+    /// audits, verdict); the engines it runs over hash separately.
+    fn state_hash(&self) -> u64 {
+        self.engine.lock().state_hash()
+    }
+
+    /// Events of every phase, plus those of engines that have no log of
+    /// their own, are written here.
+    #[pyo3(signature = (capacity=None))]
+    fn enable_event_logging(&self, capacity: Option<usize>) -> EventLog {
+        let log = EventLog::with_capacity(capacity);
+        self.engine.lock().set_event_log(log.clone());
+        log
+    }
+
+    fn set_correlation_context(&self, context: &CorrelationContext) {
+        self.engine.lock().set_correlation_context(context.clone());
+    }
+
+    /// Replaces `faction`'s treasury with a JSON `ResourceState`.
+    fn set_treasury(&self, faction: FactionId, resources_json: &str) -> PyResult<()> {
+        let resources: ResourceState = serde_json::from_str(resources_json).map_err(|e| PyErr::from(EngineError::json(e)))?;
+        self.engine.lock().set_treasury(faction, resources);
+        Ok(())
+    }
+
+    fn treasury(&self, faction: &str) -> PyResources {
+        self.engine.lock().treasury(faction).into()
+    }
+
+    fn treasuries(&self) -> HashMap<FactionId, PyResources> {
+        self.engine.lock().treasuries().iter().map(|(k, v)| (k.clone(), (*v).into())).collect()
+    }
+
+    /// Appends a JSON `ProductionOrder` (`id`, `faction`, `item`, `cost`).
+    fn queue_production(&self, order_json: &str) -> PyResult<()> {
+        let order: ProductionOrder = serde_json::from_str(order_json).map_err(|e| PyErr::from(EngineError::json(e)))?;
+        self.engine.lock().queue_production(order);
+        Ok(())
+    }
+
+    /// Orders not yet paid for, in queue order, as JSON.
+    fn production_queue(&self) -> PyResult<String> {
+        serde_json::to_string(self.engine.lock().production_queue()).map_err(|e| PyErr::from(EngineError::json(e)))
+    }
+
+    /// Queues a JSON `InvasionOrder` (`id`, `planet`, `attacker`, `troops`,
+    /// `bombardment`, `garrison`) for the next turn's invasion phase.
+    fn queue_invasion(&self, order_json: &str) -> PyResult<()> {
+        let order: InvasionOrder = serde_json::from_str(order_json).map_err(|e| PyErr::from(EngineError::json(e)))?;
+        self.engine.lock().queue_invasion(order);
+        Ok(())
+    }
+
+    /// Invasions queued for the next turn, as JSON.
+    fn invasion_queue(&self) -> PyResult<String> {
+        serde_json::to_string(self.engine.lock().pending_invasions()).map_err(|e| PyErr::from(EngineError::json(e)))
+    }
+
+    /// Queues a JSON `Action` (`{"MoveFleet": {"faction", "fleet",
+    /// "destination"}}`, `{"BuildStructure": {"faction", "node",
+    /// "building"}}`, `{"SetPolicy": {"faction", "policy",
+    /// "multiplier_scaled"}}` or `{"Produce": {<ProductionOrder>}}`) for the
+    /// start of the next turn and returns its id. The turn report lists
+    /// every action's `ActionOutcome` under `"actions"`.
+    fn submit(&self, action_json: &str) -> PyResult<u64> {
+        let action: Action = serde_json::from_str(action_json).map_err(|e| PyErr::from(EngineError::json(e)))?;
+        Ok(self.engine.lock().submit(action))
+    }
+
+    /// Actions submitted since the last turn, as JSON `[{"id", "action"}]`.
+    fn pending_actions(&self) -> PyResult<String> {
+        serde_json::to_string(self.engine.lock().pending_actions()).map_err(|e| PyErr::from(EngineError::json(e)))
+    }
+
+    /// Why a JSON `Action` would be rejected if resolved now; empty if it
+    /// would be applied. Changes nothing.
+    fn check_action(&self, py: Python<'_>, action_json: &str) -> PyResult<Vec<String>> {
+        let action: Action = serde_json::from_str(action_json).map_err(|e| PyErr::from(EngineError::json(e)))?;
+        let (pathfinder, economy) = (self.pathfinder.get(), self.economy.get());
+        let auditor = self.auditor.as_ref().map(|a| a.get());
+        let fleets = self.fleets.as_ref().map(|f| f.get());
+        py.allow_threads(|| -> PyResult<_> {
+            let mut turns = self.engine.lock();
+            let mut economy = economy.state.write();
+            let topology = pathfinder.inner.read();
+            let mut auditor = auditor.map(|a| a.state.write());
+            let auditor = match auditor.as_mut() {
+                Some(state) => Some(state.engine_mut()?),
+                None => None,
+            };
+            let mut fleets = fleets.map(|f| f.inner.write());
+            let economy = &mut *economy;
+            Ok(turns.check_action(
+                &action,
+                TurnEngines {
+                    topology: &topology,
+                    economy: &mut economy.engine,
+                    trade: &mut economy.trade_manager,
+                    auditor,
+                    diplomacy: None,
+                    colonies: None,
+                    fleets: fleets.as_deref_mut(),
+                },
+            ))
+        })
+    }
+
+    /// Resolves a JSON `InvasionOrder` now and returns the
+    /// `InvasionOutcome` as JSON. Raises ValueError if the order can't be
+    /// carried out (unknown planet, own planet, or not at war).
+    fn invade(&self, py: Python<'_>, order_json: &str) -> PyResult<String> {
+        let order: InvasionOrder = serde_json::from_str(order_json).map_err(|e| PyErr::from(EngineError::json(e)))?;
+        let (pathfinder, economy) = (self.pathfinder.get(), self.economy.get());
+        let diplomacy = self.diplomacy.as_ref().map(|d| d.get());
+        let outcome = py.allow_threads(|| {
+            let mut turns = self.engine.lock();
+            let mut economy = economy.state.write();
+            let topology = pathfinder.inner.read();
+            let diplomacy = diplomacy.map(|d| d.inner.read());
+            turns.invade(&order, &topology, &mut economy.engine, diplomacy.as_deref())
+        });
+        let outcome = outcome.map_err(PyValueError::new_err)?;
+        serde_json::to_string(&outcome).map_err(|e| PyErr::from(EngineError::json(e)))
+    }
+
+    /// Queues an entity for the next turn's audit phase.
+    fn queue_audit(&self, entity_id: EntityId, entity_type: &str, data_json: &str) -> PyResult<()> {
+        let ent_type = EntityType::parse(entity_type)
+            .ok_or_else(|| PyValueError::new_err(format!("Unknown entity type: {}", entity_type)))?;
+        let data: Value = serde_json::from_str(data_json).map_err(|e| PyErr::from(EngineError::json(e)))?;
+        self.engine.lock().queue_audit(entity_id, ent_type, data);
+        Ok(())
+    }
+
+    #[getter]
+    fn pending_audits(&self) -> usize {
+        self.engine.lock().pending_audits()
+    }
+
+    /// Sets the JSON `VictoryRules` (`{"victory": [{"Conquest": {"share":
+    /// 0.6}}, ...], "defeat": ["Elimination", ...]}`) checked at the end of
+    /// every turn, from no progress. None stops checking.
+    #[pyo3(signature = (rules_json=None))]
+    fn set_victory_rules(&self, rules_json: Option<&str>) -> PyResult<()> {
+        let rules: Option<VictoryRules> = rules_json
+            .map(serde_json::from_str)
+            .transpose()
+            .map_err(|e| PyErr::from(EngineError::json(e)))?;
+        self.engine.lock().set_victory_rules(rules);
+        Ok(())
+    }
+
+    fn victory_rules(&self) -> PyResult<Option<String>> {
+        let engine = self.engine.lock();
+        engine
+            .victory_rules()
+            .map(serde_json::to_string)
+            .transpose()
+            .map_err(|e| PyErr::from(EngineError::json(e)))
+    }
+
+    /// Sets the JSON `LogisticsRules` (`{"resources": ["Minerals",
+    /// "Energy"], "default_capacity": null, "capacities": [{"from", "to",
+    /// "capacity"}]}`, capacities scaled) routing physical resources over
+    /// the lanes each turn; the turn report gives each faction's
+    /// `SupplyReport` under `"supply"`. None pools everything again.
+    #[pyo3(signature = (rules_json=None))]
+    fn set_logistics_rules(&self, rules_json: Option<&str>) -> PyResult<()> {
+        let rules: Option<LogisticsRules> = rules_json
+            .map(serde_json::from_str)
+            .transpose()
+            .map_err(|e| PyErr::from(EngineError::json(e)))?;
+        self.engine.lock().set_logistics_rules(rules);
+        Ok(())
+    }
+
+    fn logistics_rules(&self) -> PyResult<Option<String>> {
+        let engine = self.engine.lock();
+        engine
+            .logistics_rules()
+            .map(serde_json::to_string)
+            .transpose()
+            .map_err(|e| PyErr::from(EngineError::json(e)))
+    }
+
+    /// The `Verdict` (`winner`, `condition`, `turn`) as JSON, once a
+    /// faction has won.
+    fn verdict(&self) -> PyResult<Option<String>> {
+        let engine = self.engine.lock();
+        engine.verdict().map(serde_json::to_string).transpose().map_err(|e| PyErr::from(EngineError::json(e)))
+    }
+
+    /// Runs the next turn without the GIL and returns the `TurnReport` as
+    /// JSON, with the hazards' `HazardReport` under `"hazards"`, the
+    /// anomalies' `AnomalyReport` under `"anomalies"` and the scenario's
+    /// `ScenarioReport` under `"scenario"` when there are those. Hazards
+    /// advance first (their trade modifiers count from the next turn), then
+    /// anomalies roll, then scripts run. The engines are locked for the whole turn. Raises
+    /// `EngineNotInitialized` if the auditor hasn't been initialized.
+    fn run_turn(&self, py: Python<'_>) -> PyResult<String> {
+        let (pathfinder, economy) = (self.pathfinder.get(), self.economy.get());
+        let auditor = self.auditor.as_ref().map(|a| a.get());
+        let diplomacy = self.diplomacy.as_ref().map(|d| d.get());
+        let colonies = self.colonies.as_ref().map(|c| c.get());
+        let scenario = self.scenario.as_ref().map(|s| s.get());
+        let anomalies = self.anomalies.as_ref().map(|a| a.get());
+        let hazards = self.hazards.as_ref().map(|h| h.get());
+        let fleets = self.fleets.as_ref().map(|f| f.get());
+        let (report, weather, struck, scripted) = py.allow_threads(|| -> PyResult<_> {
+            let mut turns = self.engine.lock();
+            let mut economy = economy.state.write();
+            let topology = pathfinder.inner.read();
+            let mut auditor = auditor.map(|a| a.state.write());
+            let auditor = match auditor.as_mut() {
+                Some(state) => Some(state.engine_mut()?),
+                None => None,
+            };
+            let mut diplomacy = diplomacy.map(|d| d.inner.write());
+            let mut colonies = colonies.map(|c| c.inner.write());
+            let mut fleets = fleets.map(|f| f.inner.write());
+            let economy = &mut *economy;
+            let report = turns.run_turn(TurnEngines {
+                topology: &topology,
+                economy: &mut economy.engine,
+                trade: &mut economy.trade_manager,
+                auditor,
+                diplomacy: diplomacy.as_deref_mut(),
+                colonies: colonies.as_deref_mut(),
+                fleets: fleets.as_deref_mut(),
+            });
+            drop(fleets);
+            drop(topology);
+            let weather = hazards.map(|hazards| {
+                let mut topology = pathfinder.inner.write();
+                let mut layer = hazards.inner.write();
+                let lent = layer.event_log.is_none() && turns.event_log.is_some();
+                if lent {
+                    layer.event_log = turns.event_log.clone();
+                }
+                let weather = layer.advance(report.turn, &mut topology, Some(&mut economy.trade_manager));
+                if lent {
+                    layer.event_log = None;
+                }
+                weather
+            });
+            let struck = anomalies.map(|anomalies| {
+                let mut topology = pathfinder.inner.write();
+                let mut fleets = anomalies.fleets.as_ref().map(|f| f.get().inner.write());
+                let mut generator = anomalies.inner.write();
+                // Without a log of its own, the generator writes into the turn's.
+                let lent = generator.event_log.is_none() && turns.event_log.is_some();
+                if lent {
+                    generator.event_log = turns.event_log.clone();
+                }
+                let struck = generator.run(
+                    report.turn,
+                    AnomalyTargets { topology: &mut topology, economy: &mut economy.engine, fleets: fleets.as_deref_mut() },
+                );
+                if lent {
+                    generator.event_log = None;
+                }
+                struck
+            });
+            let scripted = scenario.map(|scenario| {
+                let mut topology = pathfinder.inner.write();
+                let mut fleets = scenario.fleets.as_ref().map(|f| f.get().inner.write());
+                scenario.inner.write().run(
+                    &report,
+                    ScenarioTargets { turns: &mut turns, topology: &mut topology, fleets: fleets.as_deref_mut() },
+                )
+            });
+            self.publish_view(&turns, &pathfinder.inner.read(), economy);
+            Ok((report, weather, struck, scripted))
+        })?;
+        let report =
+            ScriptedTurn { report: &report, hazards: weather.as_ref(), anomalies: struck.as_ref(), scenario: scripted.as_ref() };
+        serde_json::to_string(&report).map_err(|e| PyErr::from(EngineError::json(e)))
+    }
+
+    /// Starts recording every command given to this engine, keyframing
+    /// the engines every `keyframe_interval` turns (and whenever they were
+    /// edited directly between turns), so `rewind_to` can re-simulate the
+    /// campaign to any later turn. Replaces any earlier recording.
+    #[pyo3(signature = (keyframe_interval=DEFAULT_KEYFRAME_INTERVAL))]
+    fn start_recording(&self, py: Python<'_>, keyframe_interval: u64) {
+        let (pathfinder, economy) = (self.pathfinder.get(), self.economy.get());
+        let diplomacy = self.diplomacy.as_ref().map(|d| d.get());
+        let colonies = self.colonies.as_ref().map(|c| c.get());
+        let fleets = self.fleets.as_ref().map(|f| f.get());
+        py.allow_threads(|| {
+            let mut turns = self.engine.lock();
+            let mut economy = economy.state.write();
+            let topology = pathfinder.inner.read();
+            let mut diplomacy = diplomacy.map(|d| d.inner.write());
+            let mut colonies = colonies.map(|c| c.inner.write());
+            let mut fleets = fleets.map(|f| f.inner.write());
+            let economy = &mut *economy;
+            let engines = TurnEngines {
+                topology: &topology,
+                economy: &mut economy.engine,
+                trade: &mut economy.trade_manager,
+                auditor: None,
+                diplomacy: diplomacy.as_deref_mut(),
+                colonies: colonies.as_deref_mut(),
+                fleets: fleets.as_deref_mut(),
+            };
+            turns.start_recording(keyframe_interval, &engines);
+        });
+    }
+
+    fn stop_recording(&self) {
+        self.engine.lock().stop_recording();
+    }
+
+    #[getter]
+    fn is_recording(&self) -> bool {
+        self.engine.lock().recording().is_some()
+    }
+
+    /// Recorded commands as JSON, `{"<turn>": [{"<Command>": {...}}, ...]}`,
+    /// keyed by the turn they were given after. None when not recording.
+    fn recorded_commands(&self) -> PyResult<Option<String>> {
+        let engine = self.engine.lock();
+        engine
+            .recording()
+            .map(|r| serde_json::to_string(r.commands()))
+            .transpose()
+            .map_err(|e| PyErr::from(EngineError::json(e)))
+    }
+
+    /// Turns the recording holds keyframes for.
+    fn keyframe_turns(&self) -> Vec<u64> {
+        self.engine.lock().recording().map(|r| r.keyframe_turns()).unwrap_or_default()
+    }
+
+    /// Re-simulates the campaign to just after `turn` ran, before any
+    /// command given after it, and returns the re-run turns' reports as a
+    /// JSON array. The recording then continues from `turn`. Raises
+    /// ValueError when not recording, for turns outside the recording, or
+    /// if the re-run doesn't reproduce the recorded state.
+    fn rewind_to(&self, py: Python<'_>, turn: u64) -> PyResult<String> {
+        let (pathfinder, economy) = (self.pathfinder.get(), self.economy.get());
+        let auditor = self.auditor.as_ref().map(|a| a.get());
+        let diplomacy = self.diplomacy.as_ref().map(|d| d.get());
+        let colonies = self.colonies.as_ref().map(|c| c.get());
+        let fleets = self.fleets.as_ref().map(|f| f.get());
+        let reports = py.allow_threads(|| -> PyResult<_> {
+            let mut turns = self.engine.lock();
+            let mut economy = economy.state.write();
+            let mut topology = pathfinder.inner.write();
+            let mut auditor = auditor.map(|a| a.state.write());
+            let auditor = match auditor.as_mut() {
+                Some(state) => Some(state.engine_mut()?),
+                None => None,
+            };
+            let mut diplomacy = diplomacy.map(|d| d.inner.write());
+            let mut colonies = colonies.map(|c| c.inner.write());
+            let mut fleets = fleets.map(|f| f.inner.write());
+            let economy = &mut *economy;
+            let engines = ReplayEngines {
+                topology: &mut topology,
+                economy: &mut economy.engine,
+                trade: &mut economy.trade_manager,
+                auditor,
+                diplomacy: diplomacy.as_deref_mut(),
+                colonies: colonies.as_deref_mut(),
+                fleets: fleets.as_deref_mut(),
+            };
+            turns.rewind_to(turn, engines).map_err(PyValueError::new_err)
+        })?;
+        serde_json::to_string(&reports).map_err(|e| PyErr::from(EngineError::json(e)))
+    }
+
+    /// Publishes a `RustStateView` of the topology, economy and treasuries
+    /// as they stand now (as every `run_turn` does when it finishes) and
+    /// returns its version.
+    fn publish(&self, py: Python<'_>) -> u64 {
+        let (pathfinder, economy) = (self.pathfinder.get(), self.economy.get());
+        py.allow_threads(|| {
+            let turns = self.engine.lock();
+            let economy = economy.state.read();
+            let topology = pathfinder.inner.read();
+            self.publish_view(&turns, &topology, &economy)
+        })
+    }
+
+    /// The view published at the end of the last turn: topology, economy
+    /// and treasuries all as that turn left them. Safe to call from another
+    /// thread while a turn runs; never waits for it. The pathfinder's and
+    /// economy's own `view()` hold the same copy under their own versions.
+    fn view(&self) -> Option<RustStateView> {
+        RustStateView::latest(&self.published)
+    }
+
+    fn __repr__(&self) -> String {
+        let engine = self.engine.lock();
+        format!(
+            "RustTurnEngine(turn={}, factions={}, queued_orders={})",
+            engine.turn(),
+            engine.treasuries().len(),
+            engine.production_queue().len()
+        )
+    }
+}
+
+impl RustTurnEngine {
+    fn publish_view(&self, turns: &TurnEngine, topology: &GraphTopology, economy: &EconomyState) -> u64 {
+        let view = RustStateView::at_turn(Some(turns.turn()))
+            .with_topology(topology)
+            .with_economy(&economy.engine, &economy.trade_manager)
+            .with_treasuries(turns.treasuries());
+        self.pathfinder.get().published.publish(view.clone());
+        self.economy.get().published.publish(view.clone());
+        self.published.publish(view)
+    }
+}
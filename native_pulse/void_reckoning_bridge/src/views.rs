@@ -0,0 +1,134 @@
+//! `RustStateView`: a read-only copy of pathfinder, economy or battle
+//! state, published by the thread that runs them (at the end of a turn,
+//! at a background battle's progress points, or on `publish()`). A UI or
+//! network thread reads views without waiting on the engines' locks and
+//! never sees a turn half-applied.
+
+use crate::reports::PyResources;
+use pyo3::prelude::*;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use void_reckoning_combat::BattleState;
+use void_reckoning_economy::engine::IncomeEngine;
+use void_reckoning_economy::trade::{TradeRoute, TradeRouteManager};
+use void_reckoning_economy::types::{EconomicNode, GlobalEconomicRules, ResourceState};
+use void_reckoning_pathfinder::{GraphTopology, TopologySnapshot};
+use void_reckoning_shared::errors::EngineError;
+use void_reckoning_shared::ids::{FactionId, UnitId};
+use void_reckoning_shared::published::Published;
+
+/// `(id, x, y, hp, alive)`.
+type UnitRow = (UnitId, f32, f32, f32, bool);
+
+#[derive(Serialize)]
+pub(crate) struct EconomyView {
+    rules: GlobalEconomicRules,
+    nodes: Vec<EconomicNode>,
+    routes: Vec<TradeRoute>,
+}
+
+impl EconomyView {
+    pub(crate) fn capture(engine: &IncomeEngine, trade: &TradeRouteManager) -> Self {
+        Self { rules: engine.rules().clone(), nodes: engine.nodes().to_vec(), routes: trade.routes().to_vec() }
+    }
+}
+
+/// Whatever parts of the state the publisher holds; the rest are None.
+#[pyclass(frozen)]
+#[derive(Clone, Default)]
+pub struct RustStateView {
+    version: u64,
+    turn: Option<u64>,
+    topology: Option<Arc<TopologySnapshot>>,
+    economy: Option<Arc<EconomyView>>,
+    treasuries: Option<Arc<BTreeMap<FactionId, ResourceState>>>,
+    battle: Option<Arc<BattleState>>,
+}
+
+fn to_json(value: &impl Serialize) -> PyResult<String> {
+    serde_json::to_string(value).map_err(|e| PyErr::from(EngineError::json(e)))
+}
+
+impl RustStateView {
+    pub(crate) fn at_turn(turn: Option<u64>) -> Self {
+        Self { turn, ..Self::default() }
+    }
+
+    pub(crate) fn with_topology(mut self, topology: &GraphTopology) -> Self {
+        self.topology = Some(Arc::new(topology.snapshot()));
+        self
+    }
+
+    pub(crate) fn with_economy(mut self, engine: &IncomeEngine, trade: &TradeRouteManager) -> Self {
+        self.economy = Some(Arc::new(EconomyView::capture(engine, trade)));
+        self
+    }
+
+    pub(crate) fn with_treasuries(mut self, treasuries: &BTreeMap<FactionId, ResourceState>) -> Self {
+        self.treasuries = Some(Arc::new(treasuries.clone()));
+        self
+    }
+
+    pub(crate) fn with_battle(mut self, battle: &BattleState) -> Self {
+        self.battle = Some(Arc::new(battle.clone()));
+        self
+    }
+
+    /// The latest view in `published`, stamped with its version.
+    pub(crate) fn latest(published: &Published<RustStateView>) -> Option<Self> {
+        published.load_versioned().map(|(version, view)| Self { version, ..(*view).clone() })
+    }
+}
+
+#[pymethods]
+impl RustStateView {
+    /// How many views its publisher has published, this one included.
+    #[getter]
+    fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Campaign turn the view was taken at, if there is one.
+    #[getter]
+    fn turn(&self) -> Option<u64> {
+        self.turn
+    }
+
+    /// The `TopologySnapshot` as JSON.
+    fn topology(&self) -> PyResult<Option<String>> {
+        self.topology.as_deref().map(to_json).transpose()
+    }
+
+    /// `{"rules", "nodes", "routes"}` as JSON.
+    fn economy(&self) -> PyResult<Option<String>> {
+        self.economy.as_deref().map(to_json).transpose()
+    }
+
+    fn treasuries(&self) -> Option<HashMap<FactionId, PyResources>> {
+        self.treasuries.as_ref().map(|t| t.iter().map(|(k, v)| (k.clone(), (*v).into())).collect())
+    }
+
+    /// The `BattleState` as JSON.
+    fn battle(&self) -> PyResult<Option<String>> {
+        self.battle.as_deref().map(to_json).transpose()
+    }
+
+    /// `(id, x, y, hp, alive)` per unit, as `RustCombatEngine.get_state`.
+    fn units(&self) -> Option<Vec<UnitRow>> {
+        self.battle.as_ref().map(|b| b.units.iter().map(|u| (u.id, u.position.0, u.position.1, u.hp, u.is_alive)).collect())
+    }
+
+    fn __repr__(&self) -> String {
+        let parts: Vec<&str> = [
+            ("topology", self.topology.is_some()),
+            ("economy", self.economy.is_some()),
+            ("treasuries", self.treasuries.is_some()),
+            ("battle", self.battle.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(name, held)| held.then_some(name))
+        .collect();
+        format!("RustStateView(version={}, turn={:?}, parts=[{}])", self.version, self.turn, parts.join(", "))
+    }
+}
@@ -0,0 +1,147 @@
+//! `RustWorld`: campaign entities with composable components, fought over
+//! by the combat engine and paid out by the economy without copying them
+//! between the two. Entities cross to Python as integers.
+
+use crate::diplomacy::RustDiplomacyEngine;
+use crate::fleet::with_hostility;
+use crate::hazard::RustHazardLayer;
+use crate::{RustCombatEngine, RustEconomyEngine};
+use parking_lot::RwLock;
+use pyo3::exceptions::PyKeyError;
+use pyo3::prelude::*;
+use void_reckoning_combat::resolve::UnitSetup;
+use void_reckoning_economy::types::EconomicNode;
+use void_reckoning_shared::errors::EngineError;
+use void_reckoning_world::{CampaignWorld, Entity, EntityRecord, WorldBattle, WorldSnapshot};
+
+#[pyclass(frozen)]
+pub struct RustWorld {
+    pub(crate) inner: RwLock<CampaignWorld>,
+}
+
+fn to_json(value: &impl serde::Serialize) -> PyResult<String> {
+    serde_json::to_string(value).map_err(|e| PyErr::from(EngineError::json(e)))
+}
+
+fn from_json<T: serde::de::DeserializeOwned>(json: &str) -> PyResult<T> {
+    serde_json::from_str(json).map_err(|e| PyErr::from(EngineError::json(e)))
+}
+
+fn unknown_entity(entity: u64) -> PyErr {
+    PyKeyError::new_err(format!("Unknown entity: {}", Entity::from_bits(entity)))
+}
+
+#[pymethods]
+impl RustWorld {
+    #[new]
+    fn new() -> Self {
+        Self { inner: RwLock::new(CampaignWorld::new()) }
+    }
+
+    /// Rebuilds a world from `to_json` output; entities get new handles.
+    #[staticmethod]
+    fn from_json(snapshot_json: &str) -> PyResult<Self> {
+        let snapshot: WorldSnapshot = from_json(snapshot_json)?;
+        Ok(Self { inner: RwLock::new(CampaignWorld::from_snapshot(snapshot)) })
+    }
+
+    fn to_json(&self) -> PyResult<String> {
+        to_json(&self.inner.read().snapshot())
+    }
+
+    #[doc = state_hash_doc!()]
+    fn state_hash(&self) -> u64 {
+        self.inner.read().state_hash()
+    }
+
+    /// Spawns an entity from a JSON `EntityRecord`: any of `name`,
+    /// `owner`, `location`, `health`, `weapons`, `maneuver` and `yield`.
+    fn spawn(&self, record_json: &str) -> PyResult<u64> {
+        let record: EntityRecord = from_json(record_json)?;
+        Ok(self.inner.write().spawn(record).to_bits())
+    }
+
+    /// Spawns an entity for a JSON `EconomicNode`.
+    fn spawn_node(&self, node_json: &str) -> PyResult<u64> {
+        let node: EconomicNode = from_json(node_json)?;
+        Ok(self.inner.write().spawn_node(&node).to_bits())
+    }
+
+    /// Spawns a unit entity from a JSON `UnitSetup`.
+    fn spawn_unit(&self, unit_json: &str, owner: &str, location: &str) -> PyResult<u64> {
+        let unit: UnitSetup = from_json(unit_json)?;
+        Ok(self.inner.write().spawn_unit(&unit, owner, location).to_bits())
+    }
+
+    /// Attaches the components in a JSON `EntityRecord` to `entity`,
+    /// replacing any of the same kind.
+    fn attach(&self, entity: u64, record_json: &str) -> PyResult<()> {
+        let record: EntityRecord = from_json(record_json)?;
+        match self.inner.write().attach(Entity::from_bits(entity), record) {
+            true => Ok(()),
+            false => Err(unknown_entity(entity)),
+        }
+    }
+
+    fn despawn(&self, entity: u64) -> bool {
+        self.inner.write().despawn(Entity::from_bits(entity))
+    }
+
+    /// The entity's components as a JSON `EntityRecord`.
+    fn get(&self, entity: u64) -> PyResult<String> {
+        let record = self.inner.read().record(Entity::from_bits(entity)).ok_or_else(|| unknown_entity(entity))?;
+        to_json(&record)
+    }
+
+    fn find(&self, name: &str) -> Option<u64> {
+        self.inner.read().find(name).map(Entity::to_bits)
+    }
+
+    fn entities(&self) -> Vec<u64> {
+        self.inner.read().world.entities().map(Entity::to_bits).collect()
+    }
+
+    /// Writes every entity with a `yield` into the economy as a node;
+    /// returns how many.
+    fn sync_economy(&self, py: Python<'_>, economy: &RustEconomyEngine) -> usize {
+        py.allow_threads(|| {
+            let mut economy = economy.state.write();
+            self.inner.read().sync_economy(&mut economy.engine)
+        })
+    }
+
+    /// Builds the battle at `system` from the entities there, with weapon
+    /// accuracy scaled by the weather there when `hazards` is given.
+    /// Returns the engine and the JSON `WorldBattle` to hand back to
+    /// `apply_battle`.
+    #[pyo3(signature = (system, seed=0, diplomacy=None, hazards=None))]
+    fn start_battle(
+        &self,
+        system: &str,
+        seed: u64,
+        diplomacy: Option<&RustDiplomacyEngine>,
+        hazards: Option<&RustHazardLayer>,
+    ) -> PyResult<(RustCombatEngine, String)> {
+        let (mut engine, battle) = with_hostility(diplomacy, |hostile| self.inner.read().build_battle(system, seed, hostile));
+        if let Some(hazards) = hazards {
+            engine.scale_accuracy(hazards.inner.read().accuracy_factor(system));
+        }
+        Ok((RustCombatEngine::from_engine(engine), to_json(&battle)?))
+    }
+
+    /// Writes a battle from `start_battle` back into its entities; returns
+    /// those destroyed.
+    fn apply_battle(&self, battle_json: &str, engine: &RustCombatEngine) -> PyResult<Vec<u64>> {
+        let battle: WorldBattle = from_json(battle_json)?;
+        let combat = engine.engine();
+        Ok(self.inner.write().apply_battle(&battle, &combat.state.units).into_iter().map(Entity::to_bits).collect())
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.read().world.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("RustWorld(entities={})", self.inner.read().world.len())
+    }
+}
@@ -0,0 +1,250 @@
+#ifndef VOID_RECKONING_H
+#define VOID_RECKONING_H
+
+/* Generated by cbindgen from void_reckoning_capi; do not edit. */
+
+/* Generated with cbindgen:0.26.0 */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+typedef enum VrStatus {
+  VR_STATUS_OK = 0,
+  VR_STATUS_NULL_ARGUMENT = 1,
+  VR_STATUS_INVALID_UTF8 = 2,
+  VR_STATUS_INVALID_PAYLOAD = 3,
+  VR_STATUS_NOT_FOUND = 4,
+  VR_STATUS_ENGINE_FAILURE = 5,
+  VR_STATUS_PANIC = 6,
+} VrStatus;
+
+/**
+ * Encoding of payloads passed in and returned.
+ */
+typedef enum VrFormat {
+  VR_FORMAT_JSON = 0,
+  VR_FORMAT_BINCODE = 1,
+} VrFormat;
+
+/**
+ * Opaque battle handle.
+ */
+typedef struct VrBattle VrBattle;
+
+/**
+ * Opaque economy handle: income engine plus trade routes.
+ */
+typedef struct VrEconomy VrEconomy;
+
+/**
+ * Opaque pathfinder handle.
+ */
+typedef struct VrPathfinder VrPathfinder;
+
+/**
+ * Bytes owned by the library; release with `vr_buffer_free`. JSON
+ * payloads are UTF-8 and not NUL-terminated.
+ */
+typedef struct VrBuffer {
+  uint8_t *data;
+  size_t len;
+} VrBuffer;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Builds a battle from a JSON `BattleSetup`. `seed` is used unless the
+ * setup carries its own.
+ *
+ * # Safety
+ * `setup_json` must be NUL-terminated; `out` must be valid for writes.
+ */
+VrStatus vr_battle_new(const char *setup_json, uint64_t seed, VrBattle **out);
+
+/**
+ * # Safety
+ * `battle` must be null or a handle from `vr_battle_new` that has not
+ * been freed.
+ */
+void vr_battle_free(VrBattle *battle);
+
+/**
+ * Advances one turn; `continues` is set to false once at most one faction
+ * is left.
+ *
+ * # Safety
+ * `battle` must be a live handle; `continues` must be valid for writes.
+ */
+VrStatus vr_battle_step(VrBattle *battle, bool *continues);
+
+/**
+ * Writes the full `BattleState` to `out`.
+ *
+ * # Safety
+ * `battle` must be a live handle; `out` must be valid for writes.
+ */
+VrStatus vr_battle_state(VrBattle *battle, VrFormat format, VrBuffer *out);
+
+/**
+ * Steps until the battle ends or `max_turns` (0 = the setup's limit) is
+ * reached, then writes the `BattleOutcome` to `out`.
+ *
+ * # Safety
+ * `battle` must be a live handle; `out` must be valid for writes.
+ */
+VrStatus vr_battle_run(VrBattle *battle, uint32_t max_turns, VrFormat format, VrBuffer *out);
+
+/**
+ * Resolves a JSON array of `BattleSetup`s on `workers` threads (0 = one per
+ * core) and writes the `BattleOutcome`s, in setup order, to `out`.
+ *
+ * # Safety
+ * `setups_json` must be NUL-terminated; `out` must be valid for writes.
+ */
+VrStatus vr_resolve_battles(const char *setups_json,
+                            uint64_t seed,
+                            uint32_t workers,
+                            VrFormat format,
+                            VrBuffer *out);
+
+/**
+ * Creates an economy from JSON `GlobalEconomicRules`, or the defaults when
+ * `rules_json` is null.
+ *
+ * # Safety
+ * `rules_json` must be null or NUL-terminated; `out` must be valid for writes.
+ */
+VrStatus vr_economy_new(const char *rules_json, VrEconomy **out);
+
+/**
+ * # Safety
+ * `economy` must be null or a handle from `vr_economy_new` that has not
+ * been freed.
+ */
+void vr_economy_free(VrEconomy *economy);
+
+/**
+ * Adds every node in a JSON array of `EconomicNode`s.
+ *
+ * # Safety
+ * `economy` must be a live handle; `nodes_json` must be NUL-terminated.
+ */
+VrStatus vr_economy_add_nodes(VrEconomy *economy, const char *nodes_json);
+
+/**
+ * Adds every route in a JSON array of `TradeRoute`s.
+ *
+ * # Safety
+ * `economy` must be a live handle; `routes_json` must be NUL-terminated.
+ */
+VrStatus vr_economy_add_trade_routes(VrEconomy *economy, const char *routes_json);
+
+/**
+ * Writes the per-faction `EconomicReport`s to `out`.
+ *
+ * # Safety
+ * `economy` must be a live handle; `out` must be valid for writes.
+ */
+VrStatus vr_economy_process_all(VrEconomy *economy, VrFormat format, VrBuffer *out);
+
+/**
+ * Recomputes trade route efficiencies over `pathfinder`'s topology and
+ * writes the trade income per faction to `out`.
+ *
+ * # Safety
+ * Both handles must be live; `out` must be valid for writes.
+ */
+VrStatus vr_economy_calculate_trade(VrEconomy *economy,
+                                    VrPathfinder *pathfinder,
+                                    VrFormat format,
+                                    VrBuffer *out);
+
+VrPathfinder *vr_pathfinder_new(void);
+
+/**
+ * # Safety
+ * `pathfinder` must be null or a handle from `vr_pathfinder_new` /
+ * `vr_pathfinder_from_snapshot` that has not been freed.
+ */
+void vr_pathfinder_free(VrPathfinder *pathfinder);
+
+/**
+ * Adds a node; `terrain` may be null.
+ *
+ * # Safety
+ * `pathfinder` must be a live handle; strings must be NUL-terminated.
+ */
+VrStatus vr_pathfinder_add_node(VrPathfinder *pathfinder, const char *id, const char *terrain);
+
+/**
+ * # Safety
+ * `pathfinder` must be a live handle; strings must be NUL-terminated.
+ */
+VrStatus vr_pathfinder_add_edge(VrPathfinder *pathfinder,
+                                const char *from,
+                                const char *to,
+                                float weight);
+
+/**
+ * Writes `{"path": [...], "cost": ...}` as JSON to `out`, or returns
+ * `NotFound` if the nodes are not connected. `profile` may be null.
+ *
+ * # Safety
+ * `pathfinder` must be a live handle; strings must be NUL-terminated;
+ * `out` must be valid for writes.
+ */
+VrStatus vr_pathfinder_find_path(VrPathfinder *pathfinder,
+                                 const char *start,
+                                 const char *end,
+                                 const char *profile,
+                                 VrBuffer *out);
+
+/**
+ * Serializes the topology (nodes, terrain and edges) to `out`.
+ *
+ * # Safety
+ * `pathfinder` must be a live handle; `out` must be valid for writes.
+ */
+VrStatus vr_pathfinder_snapshot(VrPathfinder *pathfinder, VrFormat format, VrBuffer *out);
+
+/**
+ * Builds a pathfinder from a payload written by `vr_pathfinder_snapshot`.
+ *
+ * # Safety
+ * `data` must point to `len` readable bytes; `out` must be valid for writes.
+ */
+VrStatus vr_pathfinder_from_snapshot(const uint8_t *data,
+                                     size_t len,
+                                     VrFormat format,
+                                     VrPathfinder **out);
+
+/**
+ * Message for the most recent failed call on this thread, or null. Valid
+ * until the next failing call on the same thread.
+ */
+const char *vr_last_error(void);
+
+/**
+ * Library version as a NUL-terminated string.
+ */
+const char *vr_version(void);
+
+/**
+ * Releases a buffer returned by this library. Freeing an empty buffer is a
+ * no-op.
+ *
+ * # Safety
+ * `buffer` must come from this library and not have been freed already.
+ */
+void vr_buffer_free(VrBuffer buffer);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* VOID_RECKONING_H */
@@ -0,0 +1,98 @@
+use crate::{from_json, guard, handle, required_str, write_out, write_payload, FfiError, VrBuffer, VrFormat, VrStatus};
+use std::ffi::c_char;
+use void_reckoning_combat::engine::BattleEngine;
+use void_reckoning_combat::resolve::{resolve_battles, BattleSetup, DEFAULT_MAX_TURNS};
+
+/// Opaque battle handle.
+pub struct VrBattle {
+    engine: BattleEngine,
+    name: String,
+    max_turns: u32,
+}
+
+/// Builds a battle from a JSON `BattleSetup`. `seed` is used unless the
+/// setup carries its own.
+///
+/// # Safety
+/// `setup_json` must be NUL-terminated; `out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn vr_battle_new(setup_json: *const c_char, seed: u64, out: *mut *mut VrBattle) -> VrStatus {
+    guard(|| {
+        if out.is_null() {
+            return Err(FfiError::NullArgument("out"));
+        }
+        let setup: BattleSetup = from_json(required_str(setup_json, "setup_json")?)?;
+        let battle = VrBattle {
+            engine: setup.build(seed),
+            name: setup.name.clone(),
+            max_turns: setup.max_turns.unwrap_or(DEFAULT_MAX_TURNS),
+        };
+        write_out(out, Box::into_raw(Box::new(battle)))
+    })
+}
+
+/// # Safety
+/// `battle` must be null or a handle from `vr_battle_new` that has not
+/// been freed.
+#[no_mangle]
+pub unsafe extern "C" fn vr_battle_free(battle: *mut VrBattle) {
+    if !battle.is_null() {
+        drop(Box::from_raw(battle));
+    }
+}
+
+/// Advances one turn; `continues` is set to false once at most one faction
+/// is left.
+///
+/// # Safety
+/// `battle` must be a live handle; `continues` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn vr_battle_step(battle: *mut VrBattle, continues: *mut bool) -> VrStatus {
+    guard(|| {
+        let battle = handle(battle, "battle")?;
+        if continues.is_null() {
+            return Err(FfiError::NullArgument("continues"));
+        }
+        write_out(continues, battle.engine.step())
+    })
+}
+
+/// Writes the full `BattleState` to `out`.
+///
+/// # Safety
+/// `battle` must be a live handle; `out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn vr_battle_state(battle: *mut VrBattle, format: VrFormat, out: *mut VrBuffer) -> VrStatus {
+    guard(|| write_payload(out, &handle(battle, "battle")?.engine.state, format))
+}
+
+/// Steps until the battle ends or `max_turns` (0 = the setup's limit) is
+/// reached, then writes the `BattleOutcome` to `out`.
+///
+/// # Safety
+/// `battle` must be a live handle; `out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn vr_battle_run(battle: *mut VrBattle, max_turns: u32, format: VrFormat, out: *mut VrBuffer) -> VrStatus {
+    guard(|| {
+        let battle = handle(battle, "battle")?;
+        if out.is_null() {
+            return Err(FfiError::NullArgument("out"));
+        }
+        let max_turns = if max_turns == 0 { battle.max_turns } else { max_turns };
+        let outcome = battle.engine.run_to_completion(&battle.name, max_turns);
+        write_payload(out, &outcome, format)
+    })
+}
+
+/// Resolves a JSON array of `BattleSetup`s on `workers` threads (0 = one per
+/// core) and writes the `BattleOutcome`s, in setup order, to `out`.
+///
+/// # Safety
+/// `setups_json` must be NUL-terminated; `out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn vr_resolve_battles(setups_json: *const c_char, seed: u64, workers: u32, format: VrFormat, out: *mut VrBuffer) -> VrStatus {
+    guard(|| {
+        let setups: Vec<BattleSetup> = from_json(required_str(setups_json, "setups_json")?)?;
+        write_payload(out, &resolve_battles(&setups, seed, workers as usize), format)
+    })
+}
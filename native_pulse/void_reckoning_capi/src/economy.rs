@@ -0,0 +1,103 @@
+use crate::pathfinder::VrPathfinder;
+use crate::{from_json, guard, handle, optional_str, required_str, write_out, write_payload, FfiError, VrBuffer, VrFormat, VrStatus};
+use std::ffi::c_char;
+use void_reckoning_economy::engine::IncomeEngine;
+use void_reckoning_economy::trade::{TradeRoute, TradeRouteManager};
+use void_reckoning_economy::types::{EconomicNode, GlobalEconomicRules};
+
+/// Opaque economy handle: income engine plus trade routes.
+pub struct VrEconomy {
+    engine: IncomeEngine,
+    trade_manager: TradeRouteManager,
+}
+
+/// Creates an economy from JSON `GlobalEconomicRules`, or the defaults when
+/// `rules_json` is null.
+///
+/// # Safety
+/// `rules_json` must be null or NUL-terminated; `out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn vr_economy_new(rules_json: *const c_char, out: *mut *mut VrEconomy) -> VrStatus {
+    guard(|| {
+        if out.is_null() {
+            return Err(FfiError::NullArgument("out"));
+        }
+        let rules: GlobalEconomicRules = match optional_str(rules_json, "rules_json")? {
+            Some(text) => from_json(text)?,
+            None => GlobalEconomicRules::default(),
+        };
+        let economy = VrEconomy { engine: IncomeEngine::new(rules), trade_manager: TradeRouteManager::new() };
+        write_out(out, Box::into_raw(Box::new(economy)))
+    })
+}
+
+/// # Safety
+/// `economy` must be null or a handle from `vr_economy_new` that has not
+/// been freed.
+#[no_mangle]
+pub unsafe extern "C" fn vr_economy_free(economy: *mut VrEconomy) {
+    if !economy.is_null() {
+        drop(Box::from_raw(economy));
+    }
+}
+
+/// Adds every node in a JSON array of `EconomicNode`s.
+///
+/// # Safety
+/// `economy` must be a live handle; `nodes_json` must be NUL-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn vr_economy_add_nodes(economy: *mut VrEconomy, nodes_json: *const c_char) -> VrStatus {
+    guard(|| {
+        let economy = handle(economy, "economy")?;
+        let nodes: Vec<EconomicNode> = from_json(required_str(nodes_json, "nodes_json")?)?;
+        for node in nodes {
+            economy.engine.add_node(node);
+        }
+        Ok(())
+    })
+}
+
+/// Adds every route in a JSON array of `TradeRoute`s.
+///
+/// # Safety
+/// `economy` must be a live handle; `routes_json` must be NUL-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn vr_economy_add_trade_routes(economy: *mut VrEconomy, routes_json: *const c_char) -> VrStatus {
+    guard(|| {
+        let economy = handle(economy, "economy")?;
+        let routes: Vec<TradeRoute> = from_json(required_str(routes_json, "routes_json")?)?;
+        for route in routes {
+            economy.trade_manager.add_route(route);
+        }
+        Ok(())
+    })
+}
+
+/// Writes the per-faction `EconomicReport`s to `out`.
+///
+/// # Safety
+/// `economy` must be a live handle; `out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn vr_economy_process_all(economy: *mut VrEconomy, format: VrFormat, out: *mut VrBuffer) -> VrStatus {
+    guard(|| write_payload(out, &handle(economy, "economy")?.engine.process_all(), format))
+}
+
+/// Recomputes trade route efficiencies over `pathfinder`'s topology and
+/// writes the trade income per faction to `out`.
+///
+/// # Safety
+/// Both handles must be live; `out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn vr_economy_calculate_trade(
+    economy: *mut VrEconomy,
+    pathfinder: *mut VrPathfinder,
+    format: VrFormat,
+    out: *mut VrBuffer,
+) -> VrStatus {
+    guard(|| {
+        let economy = handle(economy, "economy")?;
+        let pathfinder = handle(pathfinder, "pathfinder")?;
+        economy.trade_manager.calculate_efficiencies(&pathfinder.0);
+        write_payload(out, &economy.trade_manager.get_total_trade_income(), format)
+    })
+}
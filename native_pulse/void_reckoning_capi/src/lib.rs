@@ -0,0 +1,243 @@
+//! C ABI over the pathfinder, combat and economy engines for hosts other
+//! than Python. Engines are opaque handles; structured data crosses the
+//! boundary as JSON or bincode payloads. Every call returns a `VrStatus`,
+//! and on failure `vr_last_error()` describes what went wrong.
+//!
+//! The header is generated with cbindgen (see `cbindgen.toml`) into
+//! `include/void_reckoning.h`.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use void_reckoning_shared::errors::EngineError;
+
+pub mod combat;
+pub mod economy;
+pub mod pathfinder;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VrStatus {
+    Ok = 0,
+    NullArgument = 1,
+    InvalidUtf8 = 2,
+    InvalidPayload = 3,
+    NotFound = 4,
+    EngineFailure = 5,
+    Panic = 6,
+}
+
+/// Encoding of payloads passed in and returned.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VrFormat {
+    Json = 0,
+    Bincode = 1,
+}
+
+/// Bytes owned by the library; release with `vr_buffer_free`. JSON
+/// payloads are UTF-8 and not NUL-terminated.
+#[repr(C)]
+pub struct VrBuffer {
+    pub data: *mut u8,
+    pub len: usize,
+}
+
+impl VrBuffer {
+    fn from_vec(bytes: Vec<u8>) -> Self {
+        let len = bytes.len();
+        let data = Box::into_raw(bytes.into_boxed_slice()) as *mut u8;
+        Self { data, len }
+    }
+}
+
+pub(crate) enum FfiError {
+    NullArgument(&'static str),
+    InvalidUtf8(&'static str),
+    Engine(EngineError),
+}
+
+impl From<EngineError> for FfiError {
+    fn from(err: EngineError) -> Self {
+        FfiError::Engine(err)
+    }
+}
+
+impl FfiError {
+    fn status(&self) -> VrStatus {
+        match self {
+            FfiError::NullArgument(_) => VrStatus::NullArgument,
+            FfiError::InvalidUtf8(_) => VrStatus::InvalidUtf8,
+            FfiError::Engine(EngineError::Serialization { .. }) => VrStatus::InvalidPayload,
+            FfiError::Engine(EngineError::PathNotFound { .. }) => VrStatus::NotFound,
+            FfiError::Engine(_) => VrStatus::EngineFailure,
+        }
+    }
+
+    fn message(&self) -> String {
+        match self {
+            FfiError::NullArgument(name) => format!("{} must not be null", name),
+            FfiError::InvalidUtf8(name) => format!("{} is not valid UTF-8", name),
+            FfiError::Engine(err) => err.to_string(),
+        }
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: String) {
+    let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|e| *e.borrow_mut() = Some(message));
+}
+
+/// Runs an FFI body, turning errors and panics into a status code.
+pub(crate) fn guard(body: impl FnOnce() -> Result<(), FfiError>) -> VrStatus {
+    match catch_unwind(AssertUnwindSafe(body)) {
+        Ok(Ok(())) => VrStatus::Ok,
+        Ok(Err(err)) => {
+            set_last_error(err.message());
+            err.status()
+        }
+        Err(panic) => {
+            let message = panic
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            set_last_error(format!("panic: {}", message));
+            VrStatus::Panic
+        }
+    }
+}
+
+/// Borrows a required NUL-terminated UTF-8 argument.
+///
+/// # Safety
+/// `ptr` must be null or point to a NUL-terminated string that outlives `'a`.
+pub(crate) unsafe fn required_str<'a>(ptr: *const c_char, name: &'static str) -> Result<&'a str, FfiError> {
+    optional_str(ptr, name)?.ok_or(FfiError::NullArgument(name))
+}
+
+/// Like `required_str`, but null means "not given".
+///
+/// # Safety
+/// As for `required_str`.
+pub(crate) unsafe fn optional_str<'a>(ptr: *const c_char, name: &'static str) -> Result<Option<&'a str>, FfiError> {
+    if ptr.is_null() {
+        return Ok(None);
+    }
+    CStr::from_ptr(ptr).to_str().map(Some).map_err(|_| FfiError::InvalidUtf8(name))
+}
+
+/// # Safety
+/// `ptr` must be null or a live handle created by this library.
+pub(crate) unsafe fn handle<'a, T>(ptr: *mut T, name: &'static str) -> Result<&'a mut T, FfiError> {
+    ptr.as_mut().ok_or(FfiError::NullArgument(name))
+}
+
+/// # Safety
+/// `out` must be null or valid for writes.
+pub(crate) unsafe fn write_out<T>(out: *mut T, value: T) -> Result<(), FfiError> {
+    if out.is_null() {
+        return Err(FfiError::NullArgument("out"));
+    }
+    out.write(value);
+    Ok(())
+}
+
+pub(crate) fn from_json<T: DeserializeOwned>(text: &str) -> Result<T, FfiError> {
+    serde_json::from_str(text).map_err(|e| EngineError::json(e).into())
+}
+
+pub(crate) fn decode<T: DeserializeOwned>(bytes: &[u8], format: VrFormat) -> Result<T, FfiError> {
+    match format {
+        VrFormat::Json => serde_json::from_slice(bytes).map_err(EngineError::json),
+        VrFormat::Bincode => bincode::deserialize(bytes).map_err(|e| EngineError::serialization("Bincode", e)),
+    }
+    .map_err(FfiError::from)
+}
+
+pub(crate) fn encode<T: Serialize>(value: &T, format: VrFormat) -> Result<Vec<u8>, FfiError> {
+    match format {
+        VrFormat::Json => serde_json::to_vec(value).map_err(EngineError::json),
+        VrFormat::Bincode => bincode::serialize(value).map_err(|e| EngineError::serialization("Bincode", e)),
+    }
+    .map_err(FfiError::from)
+}
+
+/// Encodes `value` into a new buffer stored at `out`.
+///
+/// # Safety
+/// `out` must be null or valid for writes.
+pub(crate) unsafe fn write_payload<T: Serialize>(out: *mut VrBuffer, value: &T, format: VrFormat) -> Result<(), FfiError> {
+    if out.is_null() {
+        return Err(FfiError::NullArgument("out"));
+    }
+    write_out(out, VrBuffer::from_vec(encode(value, format)?))
+}
+
+/// Message for the most recent failed call on this thread, or null. Valid
+/// until the next failing call on the same thread.
+#[no_mangle]
+pub extern "C" fn vr_last_error() -> *const c_char {
+    LAST_ERROR.with(|e| e.borrow().as_ref().map_or(std::ptr::null(), |m| m.as_ptr()))
+}
+
+/// Library version as a NUL-terminated string.
+#[no_mangle]
+pub extern "C" fn vr_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr() as *const c_char
+}
+
+/// Releases a buffer returned by this library. Freeing an empty buffer is a
+/// no-op.
+///
+/// # Safety
+/// `buffer` must come from this library and not have been freed already.
+#[no_mangle]
+pub unsafe extern "C" fn vr_buffer_free(buffer: VrBuffer) {
+    if !buffer.data.is_null() {
+        drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(buffer.data, buffer.len)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::pathfinder::*;
+    use super::*;
+
+    fn c(s: &str) -> CString {
+        CString::new(s).unwrap()
+    }
+
+    #[test]
+    fn test_find_path_through_c_abi() {
+        unsafe {
+            let pathfinder = vr_pathfinder_new();
+            for id in ["Sol", "Vega", "Rigel"] {
+                assert_eq!(vr_pathfinder_add_node(pathfinder, c(id).as_ptr(), std::ptr::null()), VrStatus::Ok);
+            }
+            vr_pathfinder_add_edge(pathfinder, c("Sol").as_ptr(), c("Vega").as_ptr(), 2.0);
+            vr_pathfinder_add_edge(pathfinder, c("Vega").as_ptr(), c("Rigel").as_ptr(), 3.0);
+
+            let mut out = VrBuffer { data: std::ptr::null_mut(), len: 0 };
+            let status = vr_pathfinder_find_path(pathfinder, c("Sol").as_ptr(), c("Rigel").as_ptr(), std::ptr::null(), &mut out);
+            assert_eq!(status, VrStatus::Ok);
+            let route: serde_json::Value = serde_json::from_slice(std::slice::from_raw_parts(out.data, out.len)).unwrap();
+            assert_eq!(route["path"], serde_json::json!(["Sol", "Vega", "Rigel"]));
+            vr_buffer_free(out);
+
+            let mut out = VrBuffer { data: std::ptr::null_mut(), len: 0 };
+            let status = vr_pathfinder_find_path(pathfinder, c("Rigel").as_ptr(), c("Nowhere").as_ptr(), std::ptr::null(), &mut out);
+            assert_eq!(status, VrStatus::NotFound);
+            assert!(!vr_last_error().is_null());
+
+            assert_eq!(vr_pathfinder_add_node(pathfinder, std::ptr::null(), std::ptr::null()), VrStatus::NullArgument);
+            vr_pathfinder_free(pathfinder);
+        }
+    }
+}
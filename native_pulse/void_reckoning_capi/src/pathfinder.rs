@@ -0,0 +1,106 @@
+use crate::{decode, guard, handle, optional_str, required_str, write_out, write_payload, FfiError, VrBuffer, VrFormat, VrStatus};
+use serde::Serialize;
+use std::ffi::c_char;
+use void_reckoning_pathfinder::{GraphTopology, TopologySnapshot};
+use void_reckoning_shared::errors::EngineError;
+
+/// Opaque pathfinder handle.
+pub struct VrPathfinder(pub(crate) GraphTopology);
+
+#[derive(Serialize)]
+struct Route<'a> {
+    path: &'a [String],
+    cost: f32,
+}
+
+#[no_mangle]
+pub extern "C" fn vr_pathfinder_new() -> *mut VrPathfinder {
+    Box::into_raw(Box::new(VrPathfinder(GraphTopology::new())))
+}
+
+/// # Safety
+/// `pathfinder` must be null or a handle from `vr_pathfinder_new` /
+/// `vr_pathfinder_from_snapshot` that has not been freed.
+#[no_mangle]
+pub unsafe extern "C" fn vr_pathfinder_free(pathfinder: *mut VrPathfinder) {
+    if !pathfinder.is_null() {
+        drop(Box::from_raw(pathfinder));
+    }
+}
+
+/// Adds a node; `terrain` may be null.
+///
+/// # Safety
+/// `pathfinder` must be a live handle; strings must be NUL-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn vr_pathfinder_add_node(pathfinder: *mut VrPathfinder, id: *const c_char, terrain: *const c_char) -> VrStatus {
+    guard(|| {
+        let pathfinder = handle(pathfinder, "pathfinder")?;
+        let id = required_str(id, "id")?;
+        let terrain = optional_str(terrain, "terrain")?;
+        pathfinder.0.add_node(id.to_string(), terrain.map(str::to_string));
+        Ok(())
+    })
+}
+
+/// # Safety
+/// `pathfinder` must be a live handle; strings must be NUL-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn vr_pathfinder_add_edge(pathfinder: *mut VrPathfinder, from: *const c_char, to: *const c_char, weight: f32) -> VrStatus {
+    guard(|| {
+        let pathfinder = handle(pathfinder, "pathfinder")?;
+        pathfinder.0.add_edge(required_str(from, "from")?, required_str(to, "to")?, weight);
+        Ok(())
+    })
+}
+
+/// Writes `{"path": [...], "cost": ...}` as JSON to `out`, or returns
+/// `NotFound` if the nodes are not connected. `profile` may be null.
+///
+/// # Safety
+/// `pathfinder` must be a live handle; strings must be NUL-terminated;
+/// `out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn vr_pathfinder_find_path(
+    pathfinder: *mut VrPathfinder,
+    start: *const c_char,
+    end: *const c_char,
+    profile: *const c_char,
+    out: *mut VrBuffer,
+) -> VrStatus {
+    guard(|| {
+        let pathfinder = handle(pathfinder, "pathfinder")?;
+        let (start, end) = (required_str(start, "start")?, required_str(end, "end")?);
+        let profile = optional_str(profile, "profile")?.map(str::to_string);
+        let (path, cost) = pathfinder.0.find_path(start, end, profile).ok_or_else(|| EngineError::PathNotFound {
+            start: start.to_string(),
+            end: end.to_string(),
+        })?;
+        write_payload(out, &Route { path: &path, cost }, VrFormat::Json)
+    })
+}
+
+/// Serializes the topology (nodes, terrain and edges) to `out`.
+///
+/// # Safety
+/// `pathfinder` must be a live handle; `out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn vr_pathfinder_snapshot(pathfinder: *mut VrPathfinder, format: VrFormat, out: *mut VrBuffer) -> VrStatus {
+    guard(|| write_payload(out, &handle(pathfinder, "pathfinder")?.0.snapshot(), format))
+}
+
+/// Builds a pathfinder from a payload written by `vr_pathfinder_snapshot`.
+///
+/// # Safety
+/// `data` must point to `len` readable bytes; `out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn vr_pathfinder_from_snapshot(data: *const u8, len: usize, format: VrFormat, out: *mut *mut VrPathfinder) -> VrStatus {
+    guard(|| {
+        if data.is_null() || out.is_null() {
+            return Err(FfiError::NullArgument(if data.is_null() { "data" } else { "out" }));
+        }
+        let snapshot: TopologySnapshot = decode(std::slice::from_raw_parts(data, len), format)?;
+        let pathfinder = Box::new(VrPathfinder(GraphTopology::from_snapshot(snapshot)));
+        write_out(out, Box::into_raw(pathfinder))
+    })
+}
@@ -0,0 +1,457 @@
+use crate::types::{
+    ColonizationOrder, Colony, ColonyRules, ColonySnapshot, ColonyTurnReport, FailedVoyage, TransferOrder, Voyage, VoyageKind,
+};
+use std::collections::BTreeMap;
+use void_reckoning_economy::engine::IncomeEngine;
+use void_reckoning_economy::types::{EconomicModifier, EconomicNode, NodeType, ResourceState, SCALE_FACTOR};
+use void_reckoning_pathfinder::{GraphTopology, TerrainType};
+use void_reckoning_shared::desync;
+use void_reckoning_shared::ids::{FactionId, SystemId};
+use void_reckoning_shared::scope::ContextStack;
+use void_reckoning_shared::simtime::SimTime;
+use void_reckoning_shared::span::Span;
+use void_reckoning_shared::{CorrelationContext, Event, EventLog, EventSeverity};
+
+/// Name of the economy modifier that carries a colony's population income.
+pub const POPULATION_MODIFIER: &str = "Population";
+
+/// Habitability of a system with none set: open ground is best, rock and
+/// ocean harder, empty space not habitable at all. Terrain a mod
+/// registered counts as middling until the system sets its own.
+pub fn terrain_habitability(terrain: TerrainType) -> f32 {
+    match terrain {
+        TerrainType::Plains => 1.0,
+        TerrainType::Forest => 0.8,
+        TerrainType::Water => 0.6,
+        TerrainType::Custom(_) => 0.5,
+        TerrainType::Mountain => 0.4,
+        TerrainType::Space => 0.0,
+    }
+}
+
+pub struct ColonyEngine {
+    turn: u64,
+    rules: ColonyRules,
+    colonies: BTreeMap<SystemId, Colony>,
+    habitability: BTreeMap<SystemId, f32>,
+    colony_ships: BTreeMap<FactionId, u32>,
+    voyages: Vec<Voyage>,
+    /// Falls back to the turn engine's log when None and run by one.
+    pub event_log: Option<EventLog>,
+    /// Correlation contexts; nested operations push scopes onto this.
+    pub contexts: ContextStack,
+}
+
+impl ColonyEngine {
+    pub fn new(rules: ColonyRules) -> Self {
+        Self::from_snapshot(ColonySnapshot { rules, ..ColonySnapshot::default() })
+    }
+
+    pub fn from_snapshot(snapshot: ColonySnapshot) -> Self {
+        Self {
+            turn: snapshot.turn,
+            rules: snapshot.rules,
+            colonies: snapshot.colonies.into_iter().map(|c| (c.planet.clone(), c)).collect(),
+            habitability: snapshot.habitability,
+            colony_ships: snapshot.colony_ships,
+            voyages: snapshot.voyages,
+            event_log: None,
+            contexts: ContextStack::default(),
+        }
+    }
+
+    pub fn snapshot(&self) -> ColonySnapshot {
+        ColonySnapshot {
+            turn: self.turn,
+            rules: self.rules.clone(),
+            colonies: self.colonies.values().cloned().collect(),
+            habitability: self.habitability.clone(),
+            colony_ships: self.colony_ships.clone(),
+            voyages: self.voyages.clone(),
+        }
+    }
+
+    /// Replaces the colony state, keeping the logging setup.
+    pub fn restore(&mut self, snapshot: ColonySnapshot) {
+        let ColonyEngine { event_log, contexts, .. } = std::mem::replace(self, Self::from_snapshot(snapshot));
+        self.event_log = event_log;
+        self.contexts = contexts;
+    }
+
+    /// FNV-1a of the snapshot's JSON encoding.
+    pub fn state_hash(&self) -> u64 {
+        desync::hash_json(&self.snapshot())
+    }
+
+    pub fn set_event_log(&mut self, log: EventLog) {
+        self.event_log = Some(log);
+    }
+
+    pub fn set_correlation_context(&mut self, context: CorrelationContext) {
+        self.contexts.set_root(context);
+    }
+
+    /// Last turn advanced to (0 before the first).
+    pub fn turn(&self) -> u64 {
+        self.turn
+    }
+
+    pub fn rules(&self) -> &ColonyRules {
+        &self.rules
+    }
+
+    pub fn set_rules(&mut self, rules: ColonyRules) {
+        self.rules = rules;
+    }
+
+    /// Overrides `system`'s habitability (clamped to 0..=1); None goes
+    /// back to its terrain's.
+    pub fn set_habitability(&mut self, system: &str, habitability: Option<f32>) {
+        match habitability {
+            Some(value) => self.habitability.insert(system.into(), value.clamp(0.0, 1.0)),
+            None => self.habitability.remove(system),
+        };
+    }
+
+    /// 0 (uninhabitable) to 1; systems not in `topology` are 0.
+    pub fn habitability(&self, system: &str, topology: &GraphTopology) -> f32 {
+        match self.habitability.get(system) {
+            Some(value) => *value,
+            None => topology.terrain(system).map_or(0.0, terrain_habitability),
+        }
+    }
+
+    /// Most population `planet` can hold.
+    pub fn capacity(&self, planet: &str, topology: &GraphTopology) -> f32 {
+        self.rules.max_population * self.habitability(planet, topology)
+    }
+
+    /// Adds a colony (a homeworld, say) outside of colonization, replacing
+    /// any on the same planet. Its node is created on the next turn if the
+    /// economy has none.
+    pub fn add_colony(&mut self, colony: Colony) {
+        self.colonies.insert(colony.planet.clone(), colony);
+    }
+
+    pub fn remove_colony(&mut self, planet: &str) -> Option<Colony> {
+        self.colonies.remove(planet)
+    }
+
+    pub fn colony(&self, planet: &str) -> Option<&Colony> {
+        self.colonies.get(planet)
+    }
+
+    pub fn colonies(&self) -> impl Iterator<Item = &Colony> {
+        self.colonies.values()
+    }
+
+    pub fn grant_colony_ships(&mut self, faction: &str, ships: u32) {
+        *self.colony_ships.entry(faction.into()).or_default() += ships;
+    }
+
+    pub fn colony_ships(&self, faction: &str) -> u32 {
+        self.colony_ships.get(faction).copied().unwrap_or(0)
+    }
+
+    pub fn voyages(&self) -> &[Voyage] {
+        &self.voyages
+    }
+
+    /// Launches colony ships: spends `order.ships`, takes their settlers
+    /// from the source colony, and returns the turn they land on.
+    pub fn colonize(&mut self, order: &ColonizationOrder, topology: &GraphTopology) -> Result<u64, String> {
+        let settlers = order.ships as f32 * self.rules.settlers_per_ship;
+        if order.ships == 0 {
+            return Err(format!("Colonization {}: no ships sent", order.id));
+        }
+        if self.colony_ships(&order.faction) < order.ships {
+            return Err(format!(
+                "Colonization {}: {} has {} colony ships, needs {}",
+                order.id,
+                order.faction,
+                self.colony_ships(&order.faction),
+                order.ships
+            ));
+        }
+        if self.colonies.contains_key(&order.to) {
+            return Err(format!("Colonization {}: {} is already colonized", order.id, order.to));
+        }
+        if self.habitability(&order.to, topology) <= 0.0 {
+            return Err(format!("Colonization {}: {} is not habitable", order.id, order.to));
+        }
+        self.check_source(&order.id, &order.from, &order.faction, settlers)?;
+        let arrival_turn = self.arrival_turn(&order.id, &order.from, &order.to, topology)?;
+
+        *self.colony_ships.entry(order.faction.clone()).or_default() -= order.ships;
+        self.launch(Voyage {
+            id: order.id.clone(),
+            kind: VoyageKind::Colonize,
+            faction: order.faction.clone(),
+            from: order.from.clone(),
+            to: order.to.clone(),
+            population: settlers,
+            arrival_turn,
+        });
+        Ok(arrival_turn)
+    }
+
+    /// Sends population from one colony to another of the same faction and
+    /// returns the turn it arrives on.
+    pub fn transfer(&mut self, order: &TransferOrder, topology: &GraphTopology) -> Result<u64, String> {
+        if order.population <= 0.0 {
+            return Err(format!("Transfer {}: nobody to move", order.id));
+        }
+        let faction = self
+            .colonies
+            .get(&order.to)
+            .map(|c| c.faction.clone())
+            .ok_or_else(|| format!("Transfer {}: {} is not a colony", order.id, order.to))?;
+        self.check_source(&order.id, &order.from, &faction, order.population)?;
+        let arrival_turn = self.arrival_turn(&order.id, &order.from, &order.to, topology)?;
+
+        self.launch(Voyage {
+            id: order.id.clone(),
+            kind: VoyageKind::Transfer,
+            faction,
+            from: order.from.clone(),
+            to: order.to.clone(),
+            population: order.population,
+            arrival_turn,
+        });
+        Ok(arrival_turn)
+    }
+
+    /// Advances to `turn`: credits `ships_built`, lands voyages due by
+    /// then, grows every colony, and brings the economy's planet nodes in
+    /// line with the colonies. Bootstrap costs come out of `treasuries`; a
+    /// colony ship whose faction can't pay is lost.
+    pub fn advance_turn(
+        &mut self,
+        turn: u64,
+        ships_built: BTreeMap<FactionId, u32>,
+        topology: &GraphTopology,
+        economy: &mut IncomeEngine,
+        treasuries: &mut BTreeMap<FactionId, ResourceState>,
+    ) -> ColonyTurnReport {
+        self.turn = turn;
+        let sim_time = SimTime::at_turn(Some(turn));
+        let span = Span::start("colony.turn", &self.contexts.current()).at(sim_time);
+        let mut report = ColonyTurnReport { turn, ..ColonyTurnReport::default() };
+
+        for (faction, ships) in ships_built {
+            self.grant_colony_ships(&faction, ships);
+            report.ships_built.insert(faction, ships);
+        }
+
+        // Colonies captured or razed since last turn follow the economy
+        // before anyone lands on them.
+        self.sync_owners(economy, &span);
+
+        let (due, in_transit) = std::mem::take(&mut self.voyages).into_iter().partition(|v| v.arrival_turn <= turn);
+        self.voyages = in_transit;
+        for voyage in due {
+            match self.land(&voyage, topology, economy, treasuries, &mut report) {
+                Ok(message) => self.emit(EventSeverity::Info, message, &span, &voyage.to),
+                Err(reason) => {
+                    self.emit(EventSeverity::Warning, format!("Voyage {} lost: {}", voyage.id, reason), &span, &voyage.to);
+                    report.failed.push(FailedVoyage { id: voyage.id, reason });
+                }
+            }
+        }
+
+        let capacities: BTreeMap<SystemId, f32> =
+            self.colonies.keys().map(|planet| (planet.clone(), self.capacity(planet, topology))).collect();
+        for colony in self.colonies.values_mut() {
+            let capacity = capacities[&colony.planet];
+            if capacity > 0.0 {
+                colony.population += self.rules.growth_rate * colony.population * (1.0 - colony.population / capacity);
+            }
+            report.population.insert(colony.planet.clone(), colony.population);
+        }
+
+        self.sync_economy(economy);
+        if let Some(log) = &self.event_log {
+            log.end_span(
+                span.with_attribute("founded", report.founded.len())
+                    .with_attribute("failed", report.failed.len())
+                    .with_attribute("colonies", self.colonies.len()),
+            );
+        }
+        report
+    }
+
+    /// Gives every colony a planet node owned by its faction, carrying its
+    /// population income in the `POPULATION_MODIFIER` modifier.
+    pub fn sync_economy(&self, economy: &mut IncomeEngine) {
+        for colony in self.colonies.values() {
+            if economy.node_mut(&colony.planet).is_none() {
+                economy.add_node(EconomicNode {
+                    id: colony.planet.clone(),
+                    owner_faction: colony.faction.clone(),
+                    node_type: NodeType::Planet,
+                    base_income: ResourceState::default(),
+                    base_upkeep: ResourceState::default(),
+                    efficiency_scaled: SCALE_FACTOR,
+                    modifiers: Vec::new(),
+                });
+            }
+            let Some(node) = economy.node_mut(&colony.planet) else {
+                continue;
+            };
+            let mut income = self.rules.income_per_pop;
+            income.multiply_fixed((colony.population as f64 * SCALE_FACTOR as f64) as i128);
+            node.modifiers.retain(|m| m.name != POPULATION_MODIFIER);
+            node.modifiers.push(EconomicModifier {
+                name: POPULATION_MODIFIER.to_string(),
+                multiplier_scaled: SCALE_FACTOR,
+                flat_bonus: income,
+            });
+        }
+    }
+
+    fn sync_owners(&mut self, economy: &IncomeEngine, span: &Span) {
+        let mut changes = Vec::new();
+        for colony in self.colonies.values_mut() {
+            let Some(node) = economy.nodes().iter().find(|n| n.id == colony.planet) else {
+                continue;
+            };
+            if node.owner_faction != colony.faction {
+                let message = format!("{} passed from {} to {}", colony.planet, colony.faction, node.owner_faction);
+                changes.push((colony.planet.clone(), message));
+                colony.faction = node.owner_faction.clone();
+            }
+        }
+        for (planet, message) in changes {
+            self.emit(EventSeverity::Info, message, span, &planet);
+        }
+    }
+
+    fn land(
+        &mut self,
+        voyage: &Voyage,
+        topology: &GraphTopology,
+        economy: &mut IncomeEngine,
+        treasuries: &mut BTreeMap<FactionId, ResourceState>,
+        report: &mut ColonyTurnReport,
+    ) -> Result<String, String> {
+        let capacity = self.capacity(&voyage.to, topology);
+        match voyage.kind {
+            VoyageKind::Transfer => {
+                let colony = self
+                    .colonies
+                    .get_mut(&voyage.to)
+                    .filter(|c| c.faction == voyage.faction)
+                    .ok_or_else(|| format!("{} is no longer a {} colony", voyage.to, voyage.faction))?;
+                colony.population = (colony.population + voyage.population).min(capacity.max(colony.population));
+                report.transferred.push(voyage.id.clone());
+                Ok(format!("{} settlers reached {}", voyage.population, voyage.to))
+            }
+            VoyageKind::Colonize => {
+                if self.colonies.contains_key(&voyage.to) {
+                    return Err(format!("{} was colonized first", voyage.to));
+                }
+                if let Some(node) = economy.nodes().iter().find(|n| n.id == voyage.to && n.owner_faction != voyage.faction) {
+                    return Err(format!("{} is held by {}", voyage.to, node.owner_faction));
+                }
+                if capacity <= 0.0 {
+                    return Err(format!("{} is not habitable", voyage.to));
+                }
+                let treasury = treasuries.entry(voyage.faction.clone()).or_default();
+                if !covers(treasury, &self.rules.bootstrap_cost) {
+                    return Err(format!("{} can't pay the bootstrap cost", voyage.faction));
+                }
+                treasury.subtract(&self.rules.bootstrap_cost);
+                report.bootstrap_paid.entry(voyage.faction.clone()).or_default().add(&self.rules.bootstrap_cost);
+                self.colonies.insert(
+                    voyage.to.clone(),
+                    Colony { planet: voyage.to.clone(), faction: voyage.faction.clone(), population: voyage.population.min(capacity) },
+                );
+                report.founded.push(voyage.to.clone());
+                Ok(format!("{} founded a colony on {}", voyage.faction, voyage.to))
+            }
+        }
+    }
+
+    fn check_source(&self, id: &str, from: &str, faction: &str, population: f32) -> Result<(), String> {
+        let source = self.colonies.get(from).ok_or_else(|| format!("{}: {} is not a colony", id, from))?;
+        if faction != source.faction {
+            return Err(format!("{}: {} belongs to {}", id, from, source.faction));
+        }
+        // A colony can't empty itself out.
+        if source.population <= population {
+            return Err(format!("{}: {} has {} population, needs more than {}", id, from, source.population, population));
+        }
+        Ok(())
+    }
+
+    fn arrival_turn(&self, id: &str, from: &str, to: &str, topology: &GraphTopology) -> Result<u64, String> {
+        let (_, cost) = topology.find_path(from, to, None).ok_or_else(|| format!("{}: no route from {} to {}", id, from, to))?;
+        let turns = (cost / self.rules.travel_speed.max(f32::MIN_POSITIVE)).ceil().max(1.0);
+        Ok(self.turn + turns as u64)
+    }
+
+    fn launch(&mut self, voyage: Voyage) {
+        if let Some(source) = self.colonies.get_mut(&voyage.from) {
+            source.population -= voyage.population;
+        }
+        self.voyages.push(voyage);
+    }
+
+    fn emit(&self, severity: EventSeverity, message: String, span: &Span, entity: &str) {
+        if let Some(log) = &self.event_log {
+            let evt = Event::new(severity, "Colony".to_string(), message, span.context.child(), Some(entity.to_string()))
+                .at(span.sim_time);
+            log.add(evt);
+        }
+    }
+}
+
+fn covers(treasury: &ResourceState, cost: &ResourceState) -> bool {
+    treasury.credits >= cost.credits
+        && treasury.minerals >= cost.minerals
+        && treasury.energy >= cost.energy
+        && treasury.research >= cost.research
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use void_reckoning_economy::types::GlobalEconomicRules;
+
+    #[test]
+    fn colony_ship_founds_a_paying_colony() {
+        let mut topology = GraphTopology::new();
+        topology.add_node("Terra".to_string(), Some("Plains".to_string()));
+        topology.add_node("Eden".to_string(), Some("Forest".to_string()));
+        topology.add_edge("Terra", "Eden", 7.0);
+        let mut economy = IncomeEngine::new(GlobalEconomicRules::default());
+        let mut treasuries = BTreeMap::from([("Imperium".into(), ResourceState { credits: 150 * SCALE_FACTOR, ..ResourceState::default() })]);
+
+        let mut colonies = ColonyEngine::new(ColonyRules { settlers_per_ship: 2.0, ..ColonyRules::default() });
+        colonies.add_colony(Colony { planet: "Terra".into(), faction: "Imperium".into(), population: 5.0 });
+        let order = ColonizationOrder {
+            id: "c1".to_string(),
+            faction: "Imperium".into(),
+            from: "Terra".into(),
+            to: "Eden".into(),
+            ships: 1,
+        };
+        assert!(colonies.colonize(&order, &topology).is_err());
+
+        let built = BTreeMap::from([("Imperium".into(), 1)]);
+        colonies.advance_turn(1, built, &topology, &mut economy, &mut treasuries);
+        assert_eq!(colonies.colonize(&order, &topology), Ok(3));
+        assert_eq!(colonies.colony_ships("Imperium"), 0);
+
+        colonies.advance_turn(2, BTreeMap::new(), &topology, &mut economy, &mut treasuries);
+        assert!(colonies.colony("Eden").is_none());
+        let report = colonies.advance_turn(3, BTreeMap::new(), &topology, &mut economy, &mut treasuries);
+        assert_eq!(report.founded, vec!["Eden"]);
+        assert_eq!(treasuries["Imperium"].credits, 50 * SCALE_FACTOR);
+        // 2 settlers grow by 10% of the room left under 8.
+        assert!((report.population["Eden"] - 2.15).abs() < 1e-4);
+        let eden = economy.process_faction("Imperium");
+        assert_eq!(eden.active_nodes, 2);
+        assert!(eden.total_income.credits > 0);
+    }
+}
@@ -0,0 +1,17 @@
+//! Colonization and population: how habitable each system is, colony
+//! ships that carry settlers out to found new colonies, population moved
+//! between a faction's planets, and the bootstrap cost a new colony pays
+//! on landing. Colonies are economy planet nodes; their population adds
+//! to the node's income. Voyages take as many turns as the pathfinder's
+//! route cost divided by the travel speed.
+
+pub mod types;
+pub mod engine;
+
+pub use engine::ColonyEngine;
+pub use types::{ColonizationOrder, Colony, ColonyRules, ColonySnapshot, ColonyTurnReport, FailedVoyage, TransferOrder, Voyage, VoyageKind};
+
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Version of the `ColonySnapshot`/`ColonyTurnReport` layouts; bumped on
+/// incompatible changes.
+pub const SCHEMA_VERSION: u32 = 1;
@@ -0,0 +1,135 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use void_reckoning_economy::types::{ResourceState, SCALE_FACTOR};
+use void_reckoning_shared::ids::{FactionId, SystemId};
+
+/// Tunables for colonization. Any field left out of the JSON takes its
+/// default.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ColonyRules {
+    /// Population a fully habitable planet holds; a planet's capacity is
+    /// this times its habitability.
+    pub max_population: f32,
+    /// Share of the room left under capacity a colony grows by each turn.
+    pub growth_rate: f32,
+    /// Settlers one colony ship carries, taken from the source colony.
+    pub settlers_per_ship: f32,
+    /// Paid from the founding faction's treasury when a colony ship lands.
+    pub bootstrap_cost: ResourceState,
+    /// Added to a colony's node income per unit of population.
+    pub income_per_pop: ResourceState,
+    /// Route cost covered per turn by colony ships and transfers.
+    pub travel_speed: f32,
+    /// Production item that yields a colony ship when its order completes.
+    pub colony_ship_item: String,
+}
+
+impl Default for ColonyRules {
+    fn default() -> Self {
+        Self {
+            max_population: 10.0,
+            growth_rate: 0.1,
+            settlers_per_ship: 1.0,
+            bootstrap_cost: ResourceState { credits: 100 * SCALE_FACTOR, ..ResourceState::default() },
+            income_per_pop: ResourceState { credits: 2 * SCALE_FACTOR, ..ResourceState::default() },
+            travel_speed: 5.0,
+            colony_ship_item: "ColonyShip".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Colony {
+    /// Economy node and pathfinder system the colony sits on.
+    pub planet: SystemId,
+    pub faction: FactionId,
+    pub population: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VoyageKind {
+    /// Settlers founding a colony where they land.
+    Colonize,
+    /// Population joining a colony of the same faction.
+    Transfer,
+}
+
+/// Settlers in transit.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Voyage {
+    pub id: String,
+    pub kind: VoyageKind,
+    pub faction: FactionId,
+    pub from: SystemId,
+    pub to: SystemId,
+    pub population: f32,
+    pub arrival_turn: u64,
+}
+
+/// Sends colony ships from one of the faction's colonies to found a new
+/// one at `to`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColonizationOrder {
+    pub id: String,
+    pub faction: FactionId,
+    pub from: SystemId,
+    pub to: SystemId,
+    #[serde(default = "one_ship")]
+    pub ships: u32,
+}
+
+fn one_ship() -> u32 {
+    1
+}
+
+/// Moves `population` between two colonies of the same faction.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferOrder {
+    pub id: String,
+    pub from: SystemId,
+    pub to: SystemId,
+    pub population: f32,
+}
+
+/// A voyage that landed without effect, and why. Its settlers are lost.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FailedVoyage {
+    pub id: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ColonyTurnReport {
+    pub turn: u64,
+    /// Colony ships credited this turn by faction.
+    pub ships_built: BTreeMap<FactionId, u32>,
+    /// Planets colonized this turn.
+    pub founded: Vec<SystemId>,
+    /// Ids of transfers that arrived.
+    pub transferred: Vec<String>,
+    pub failed: Vec<FailedVoyage>,
+    /// Bootstrap costs paid by faction.
+    pub bootstrap_paid: BTreeMap<FactionId, ResourceState>,
+    /// Population after growth, by planet.
+    pub population: BTreeMap<SystemId, f32>,
+}
+
+/// Serializable copy of a `ColonyEngine`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ColonySnapshot {
+    #[serde(default)]
+    pub turn: u64,
+    #[serde(default)]
+    pub rules: ColonyRules,
+    #[serde(default)]
+    pub colonies: Vec<Colony>,
+    /// Habitability set per system, overriding its terrain's.
+    #[serde(default)]
+    pub habitability: BTreeMap<SystemId, f32>,
+    /// Colony ships in stock by faction.
+    #[serde(default)]
+    pub colony_ships: BTreeMap<FactionId, u32>,
+    #[serde(default)]
+    pub voyages: Vec<Voyage>,
+}
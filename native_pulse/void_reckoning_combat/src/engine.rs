@@ -1,41 +1,224 @@
-use crate::{BattleState, CombatUnit, Weapon};
+use crate::{BattleState, CombatUnit, UnitId};
 use crate::mechanics::{DamageSource, Armor};
-use crate::targeting::find_best_target;
-use rand::thread_rng;
+use crate::targeting::{find_best_target_spatial_with, Hostility, SpatialHash};
+use crate::victory::{BattleEnd, EndConditions};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
+#[cfg(feature = "observability")]
 use void_reckoning_shared::{Event, EventLog, EventSeverity, CorrelationContext};
-use std::sync::Arc;
+#[cfg(feature = "observability")]
+use void_reckoning_shared::scope::ContextStack;
+#[cfg(feature = "observability")]
+use void_reckoning_shared::simtime::{SimClock, SimTime};
+#[cfg(feature = "observability")]
+use void_reckoning_shared::span::Span;
+
+/// Cell size for spatial queries; roughly one typical weapon range.
+const SPATIAL_CELL_SIZE: f32 = 50.0;
+
+/// How a damage event reached its target, for the ledger.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Impact {
+    Hit,
+    Splash,
+    /// Splash on an ally, only possible under friendly fire.
+    FriendlySplash,
+}
+
+/// Everything the `observability` feature adds to a battle.
+#[cfg(feature = "observability")]
+#[derive(Default)]
+pub struct Observability {
+    pub event_log: Option<EventLog>,
+    /// Correlation contexts; nested operations push scopes onto this.
+    pub contexts: ContextStack,
+    /// Campaign clock: each step is one tick of it, and its turn and tick
+    /// are stamped on emitted events.
+    clock: Option<SimClock>,
+    /// Emit a `Memory` event every this many turns (None = never).
+    pub memory_event_interval: Option<u32>,
+}
 
 pub struct BattleEngine {
     pub state: BattleState,
-    pub event_log: Option<EventLog>,
-    pub current_context: CorrelationContext,
+    #[cfg(feature = "observability")]
+    pub obs: Observability,
+    /// Damage rolls; entropy-seeded unless `set_seed` is called.
+    rng: StdRng,
+    /// What `rng` was last seeded with, for recording alongside a replay.
+    seed: Option<u64>,
+    /// Lockstep mode: movement avoids platform-dependent trig so every
+    /// client computes bit-identical positions.
+    deterministic: bool,
+    /// Faction pairs that don't target each other; set from diplomacy.
+    pub hostility: Hostility,
+    /// Splash also hits the attacker's own and allied units.
+    pub friendly_fire: bool,
+    /// Checked after every step, on top of the battle running out of
+    /// hostile factions.
+    pub end_conditions: EndConditions,
+    /// Objective holder and for how many turns in a row.
+    objective_hold: Option<(u8, u32)>,
+    /// Why the last step ended the battle, if it did.
+    end: Option<BattleEnd>,
 }
 
 impl BattleEngine {
     pub fn new(width: f32, height: f32) -> Self {
         Self {
             state: BattleState::new(width, height),
-            event_log: None,
-            current_context: CorrelationContext::new(),
+            #[cfg(feature = "observability")]
+            obs: Observability::default(),
+            rng: StdRng::from_entropy(),
+            seed: None,
+            deterministic: false,
+            hostility: Hostility::default(),
+            friendly_fire: false,
+            end_conditions: EndConditions::default(),
+            objective_hold: None,
+            end: None,
         }
     }
+
+    /// `new` with damage rolls drawn from `seed`: the same seed and units
+    /// fight the same battle every time.
+    pub fn new_with_seed(width: f32, height: f32, seed: u64) -> Self {
+        let mut engine = Self::new(width, height);
+        engine.set_seed(seed);
+        engine
+    }
     
+    /// Resumes a battle from a previously saved `BattleState`.
+    pub fn from_state(state: BattleState) -> Self {
+        let (width, height) = state.grid_size;
+        let mut engine = Self::new(width, height);
+        engine.state = state;
+        engine
+    }
+
+    /// Makes damage rolls reproducible: same seed and setup, same battle.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+        self.seed = Some(seed);
+    }
+
+    /// The last seed given, or None while rolls are entropy-seeded.
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// `Some(seed)` turns on lockstep mode: damage rolls come from `seed`
+    /// and movement uses only correctly-rounded float operations, so the
+    /// same battle steps identically on every platform (compare
+    /// `BattleState::state_hash` to check). `None` turns it off; the RNG
+    /// keeps its current state.
+    pub fn set_deterministic(&mut self, seed: Option<u64>) {
+        if let Some(seed) = seed {
+            self.set_seed(seed);
+        }
+        self.deterministic = seed.is_some();
+    }
+
+    pub fn is_deterministic(&self) -> bool {
+        self.deterministic
+    }
+
+    /// Replaces the end conditions; objective progress starts over.
+    pub fn set_end_conditions(&mut self, conditions: EndConditions) {
+        self.end_conditions = conditions;
+        self.objective_hold = None;
+    }
+
+    /// How and to whom the battle ended, once a step has ended it.
+    pub fn end(&self) -> Option<BattleEnd> {
+        self.end
+    }
+
+    #[cfg(feature = "observability")]
     pub fn set_event_log(&mut self, log: EventLog) {
-        self.event_log = Some(log);
+        self.obs.event_log = Some(log);
     }
 
+    #[cfg(feature = "observability")]
     pub fn set_correlation_context(&mut self, context: CorrelationContext) {
-        self.current_context = context;
         // Also update the run_id in state for legacy compatibility if needed
-        self.state.run_id = self.current_context.trace_id.clone();
+        self.state.run_id = context.trace_id.clone();
+        self.obs.contexts.set_root(context);
+    }
+
+    /// Allied factions stop targeting each other from the next step; a
+    /// battle with no hostile pair left standing is over.
+    pub fn set_hostility(&mut self, hostility: Hostility) {
+        self.hostility = hostility;
+    }
+
+    /// Runs on the campaign's clock (shared with the other engines), or
+    /// on none with None.
+    #[cfg(feature = "observability")]
+    pub fn set_clock(&mut self, clock: Option<SimClock>) {
+        self.obs.clock = clock;
+    }
+
+    #[cfg(feature = "observability")]
+    pub fn clock(&self) -> Option<&SimClock> {
+        self.obs.clock.as_ref()
+    }
+
+    /// Moves the clock to `turn` (and every engine sharing it), starting
+    /// one of the battle's own if it has none. None detaches the clock.
+    pub fn set_campaign_turn(&mut self, turn: Option<u64>) {
+        #[cfg(feature = "observability")]
+        match (turn, &self.obs.clock) {
+            (Some(turn), Some(clock)) => clock.set_turn(turn),
+            (Some(turn), None) => self.obs.clock = Some(SimClock::at_turn(turn)),
+            (None, _) => self.obs.clock = None,
+        }
+        #[cfg(not(feature = "observability"))]
+        let _ = turn;
+    }
+
+    pub fn campaign_turn(&self) -> Option<u64> {
+        #[cfg(feature = "observability")]
+        {
+            self.obs.clock.as_ref().map(SimClock::turn)
+        }
+        #[cfg(not(feature = "observability"))]
+        {
+            None
+        }
+    }
+
+    #[cfg(feature = "observability")]
+    pub fn set_memory_event_interval(&mut self, turns: Option<u32>) {
+        self.obs.memory_event_interval = turns.filter(|&n| n > 0);
     }
 
     pub fn add_unit(&mut self, unit: CombatUnit) {
         self.state.add_unit(unit);
     }
+
+    /// Multiplies the accuracy of every weapon on the field by `factor`,
+    /// e.g. for an ion storm over the system. Units added later keep theirs.
+    pub fn scale_accuracy(&mut self, factor: f32) {
+        for weapon in self.state.units.iter_mut().flat_map(|u| u.weapons.iter_mut()) {
+            weapon.accuracy *= factor;
+        }
+    }
     
-    pub fn set_unit_cover(&mut self, unit_id: u32, cover_val: u8) {
+    /// Living unit ids within `radius` of `(x, y)`, nearest first.
+    pub fn units_in_radius(&self, x: f32, y: f32, radius: f32) -> Vec<UnitId> {
+        SpatialHash::build(&self.state, SPATIAL_CELL_SIZE).units_in_radius(&self.state, (x, y), radius)
+    }
+
+    /// Nearest living enemy of `unit_id`; `None` if the unit is unknown or
+    /// no enemy is left.
+    pub fn nearest_enemy(&self, unit_id: UnitId) -> Option<UnitId> {
+        let unit = self.state.get_unit(unit_id)?;
+        SpatialHash::build(&self.state, SPATIAL_CELL_SIZE).nearest_hostile(&self.state, unit, &self.hostility)
+    }
+
+    pub fn set_unit_cover(&mut self, unit_id: UnitId, cover_val: u8) {
         if let Some(unit) = self.state.get_unit_mut(unit_id) {
             unit.cover = match cover_val {
                 1 => crate::CoverType::Light,
@@ -46,39 +229,38 @@ impl BattleEngine {
         }
     }
 
+    /// Advances the battle one turn. Returns false once it has ended; an
+    /// ended battle stays as it was, so further steps do nothing.
     pub fn step(&mut self) -> bool {
+        if self.end.is_some() {
+            return false;
+        }
+        #[cfg(feature = "observability")]
+        let span = Span::start("combat.step", &self.obs.contexts.current());
+        #[cfg(feature = "observability")]
+        let _scope = self.obs.contexts.enter_context(span.context.clone());
         self.state.turn += 1;
         self.state.time_elapsed += 1.0; // Assume 1s tick for now
+        #[cfg(feature = "observability")]
+        let sim_time = match &self.obs.clock {
+            Some(clock) => SimTime::at_turn(Some(clock.turn())).with_tick(clock.advance_tick()),
+            None => SimTime::default().with_tick(self.state.turn as u64),
+        }
+        .with_engine_time(self.state.time_elapsed as f64);
+        #[cfg(feature = "observability")]
+        let span = span.at(sim_time);
 
-        let mut rng = thread_rng();
-        let mut damage_events: Vec<(u32, f32, crate::mechanics::DamageType)> = Vec::new();
+        // (attacker, weapon_idx, target, amount, type, impact)
+        let mut damage_events: Vec<(UnitId, usize, UnitId, f32, crate::mechanics::DamageType, Impact)> = Vec::new();
+
+        // Targeting needs read access to all units while we mutate one of them,
+        // so each pass collects intents first and applies them afterwards.
 
-        // 1. Movement & Targeting & Attack Declaration
-        // We need to iterate immutable to find targets/intent, then mutable to update?
-        // Or just indices.
-        
-        // Optimize: Separate targeting/movement logic to avoid massive loop?
-        // For now, simple iteration.
-        
-        let unit_count = self.state.units.len();
-        
-        for i in 0..unit_count {
-            // Re-borrow self for each unit context
-            // Rust ownership makes this tricky if we want to read "all units" while mutating "this unit".
-            // Standard pattern: Separate state.
-            
-            // We can't do: for unit in &mut self.state.units { find_target(unit, &self.state.units) }
-            // So we'll collect intents first.
-            
-            // Just resolve targeting first
-            // But targeting needs read access to all units.
-        }
-        
         // PASS 0: Movement
         let mut moves: Vec<(usize, (f32, f32))> = Vec::new();
         
         // Snapshot positions for safe lookup
-        let unit_positions: std::collections::HashMap<u32, (f32, f32)> = self.state.units.iter()
+        let unit_positions: std::collections::HashMap<UnitId, (f32, f32)> = self.state.units.iter()
             .map(|u| (u.id, u.position))
             .collect();
 
@@ -86,23 +268,27 @@ impl BattleEngine {
             if !unit.is_alive { continue; }
             if unit.speed <= 0.0 { continue; }
 
-            if let Some(tid) = unit.target_id {
-                if let Some(target_pos) = unit_positions.get(&tid) {
-                    let dx = target_pos.0 - unit.position.0;
-                    let dy = target_pos.1 - unit.position.1;
-                    let dist = (dx * dx + dy * dy).sqrt();
-                    
-                    // Simple logic: Move to range 20.0
-                    let desired_range = 20.0;
-                    
-                    if dist > desired_range {
-                        let move_dist = unit.speed.min(dist - desired_range);
-                        if move_dist > 0.0 {
-                            let angle = dy.atan2(dx);
-                            let new_x = unit.position.0 + move_dist * angle.cos();
-                            let new_y = unit.position.1 + move_dist * angle.sin();
-                            moves.push((idx, (new_x, new_y)));
-                        }
+            if let Some(tid) = unit.target_id
+                && let Some(target_pos) = unit_positions.get(&tid)
+            {
+                let dx = target_pos.0 - unit.position.0;
+                let dy = target_pos.1 - unit.position.1;
+                let dist = (dx * dx + dy * dy).sqrt();
+
+                // Simple logic: Move to range 20.0
+                let desired_range = 20.0;
+
+                if dist > desired_range {
+                    let move_dist = unit.speed.min(dist - desired_range);
+                    if move_dist > 0.0 && self.deterministic {
+                        // +, -, *, / and sqrt round the same everywhere; atan2/cos/sin don't.
+                        let scale = move_dist / dist;
+                        moves.push((idx, (unit.position.0 + dx * scale, unit.position.1 + dy * scale)));
+                    } else if move_dist > 0.0 {
+                        let angle = dy.atan2(dx);
+                        let new_x = unit.position.0 + move_dist * angle.cos();
+                        let new_y = unit.position.1 + move_dist * angle.sin();
+                        moves.push((idx, (new_x, new_y)));
                     }
                 }
             }
@@ -112,10 +298,12 @@ impl BattleEngine {
             self.state.units[idx].position = new_pos;
         }
 
+        // Rebuilt once positions are final for the tick; targeting and the
+        // unit lookups below go through it instead of scanning every unit.
+        let hash = SpatialHash::build(&self.state, SPATIAL_CELL_SIZE);
+
         // PASS 1: Targeting Updates (Read-Only State -> Write Target ID)
-        use crate::targeting::find_best_target;
-        
-        let mut new_targets: Vec<(usize, u32)> = Vec::new();
+        let mut new_targets: Vec<(usize, Option<UnitId>)> = Vec::new();
         
         for (idx, unit) in self.state.units.iter().enumerate() {
             if !unit.is_alive { continue; }
@@ -124,41 +312,28 @@ impl BattleEngine {
             let needs_target = match unit.target_id {
                 None => true,
                 Some(tid) => {
-                    // Check if target exists and is alive
-                     self.state.units.iter().find(|u| u.id == tid).map(|u| !u.is_alive).unwrap_or(true)
+                    // Check if target exists, is alive and is still hostile
+                    hash.unit(&self.state, tid)
+                        .map(|u| !u.is_alive || !self.hostility.is_hostile(u.faction_idx, unit.faction_idx))
+                        .unwrap_or(true)
                 }
             };
             
             if needs_target {
-                if let Some(target_id) = find_best_target(unit, &self.state) {
-                    new_targets.push((idx, target_id));
-                }
+                // None drops a target that is dead or now allied
+                new_targets.push((idx, find_best_target_spatial_with(unit, &self.state, &hash, &self.hostility)));
             }
         }
         
         // Apply targets
         for (idx, target_id) in new_targets {
-            self.state.units[idx].target_id = Some(target_id);
+            self.state.units[idx].target_id = target_id;
         }
 
-        // PASS 2: Combat Action (Calculate Output Damage)
-        // Read unit + Read target position -> Generate Damage Event
-        for unit in &mut self.state.units {
-            if !unit.is_alive { continue; }
-            if let Some(target_id) = unit.target_id {
-                 // We need target data (position, etc)
-                 // But we are holding a mutable ref to `unit`.
-                 // We can't easily find `target` in `self.state.units` simultaneously via iterator.
-                 // We need to look it up.
-            }
-        }
-        
-        // Simpler approach:
-        // Clone meaningful combat data specifically for the read-phase? No too slow.
-        // Index-based loop.
-        
         // PASS 2: Combat Action (Calculate Output Damage)
         let mut fired_weapons: Vec<(usize, usize)> = Vec::new(); // (unit_idx, weapon_idx)
+        #[cfg(feature = "observability")]
+        let mut misses: Vec<(UnitId, UnitId, usize, f32)> = Vec::new(); // (attacker, target, weapon_idx, chance)
 
         for i in 0..self.state.units.len() {
              let attacker = &self.state.units[i];
@@ -166,8 +341,7 @@ impl BattleEngine {
              
              let tid = attacker.target_id.unwrap();
              
-             // Find target (scan?) 
-             let target_data = self.state.units.iter().find(|u| u.id == tid);
+             let target_data = hash.unit(&self.state, tid);
              
              if let Some(target) = target_data {
                  // Check Range - Distance calculation needed here or assume cached?
@@ -183,58 +357,126 @@ impl BattleEngine {
 
                      // Check cooldown
                      if weapon.current_cooldown <= 0.0 {
-                         let dmg = weapon.calculate_damage(&mut rng);
-                         let dtype = weapon.get_damage_type();
-                         damage_events.push((tid, dmg, dtype));
+                         // A miss still spends the shot; only hits roll damage.
+                         let chance = weapon.hit_chance(target.evasion, dist);
+                         if self.rng.r#gen::<f32>() < chance {
+                             let dmg = weapon.calculate_damage(&mut self.rng);
+                             let dtype = weapon.get_damage_type();
+                             damage_events.push((attacker.id, w_idx, tid, dmg, dtype, Impact::Hit));
+                             if weapon.aoe_radius > 0.0 {
+                                 for id in hash.units_in_radius(&self.state, target.position, weapon.aoe_radius) {
+                                     let Some(other) = hash.unit(&self.state, id) else { continue };
+                                     let hostile = self.hostility.is_hostile(other.faction_idx, attacker.faction_idx);
+                                     if id == tid || id == attacker.id || (!self.friendly_fire && !hostile) {
+                                         continue;
+                                     }
+                                     let impact = if hostile { Impact::Splash } else { Impact::FriendlySplash };
+                                     let (sx, sy) = (other.position.0 - target.position.0, other.position.1 - target.position.1);
+                                     let falloff = 1.0 - (sx * sx + sy * sy).sqrt() / weapon.aoe_radius;
+                                     damage_events.push((attacker.id, w_idx, id, dmg * falloff, dtype, impact));
+                                 }
+                             }
+                         } else {
+                             #[cfg(feature = "observability")]
+                             misses.push((attacker.id, tid, w_idx, chance));
+                         }
                          fired_weapons.push((i, w_idx));
                      }
                  }
              }
         }
         
+        #[cfg(feature = "observability")]
+        if let Some(log) = &self.obs.event_log {
+            for (attacker_id, target_id, w_idx, chance) in misses {
+                let weapon = self.state.get_unit(attacker_id).and_then(|u| u.weapons.get(w_idx)).map(|w| w.name.clone()).unwrap_or_default();
+                let evt = Event::new(
+                    EventSeverity::Debug,
+                    "Combat".to_string(),
+                    format!("Unit {} missed Unit {}", attacker_id, target_id),
+                    span.context.child(),
+                    None
+                )
+                .with_field("attacker_id", attacker_id.get())
+                .with_field("target_id", target_id.get())
+                .with_field("weapon", weapon)
+                .with_field("hit_chance", chance)
+                .at(sim_time);
+                log.add(evt);
+            }
+        }
+
         // Apply cooldown resets
         for (u_idx, w_idx) in fired_weapons {
-            if let Some(unit) = self.state.units.get_mut(u_idx) {
-                if let Some(weapon) = unit.weapons.get_mut(w_idx) {
-                    weapon.current_cooldown = weapon.cooldown;
-                }
+            if let Some(unit) = self.state.units.get_mut(u_idx)
+                && let Some(weapon) = unit.weapons.get_mut(w_idx)
+            {
+                weapon.current_cooldown = weapon.cooldown;
+                self.state.ledger.record_shot(unit.id);
             }
         }
         
         // PASS 3: Apply Damage
-        let mut total_destroyed = 0;
-        for (target_id, amount, dtype) in damage_events {
-            if let Some(target) = self.state.units.iter_mut().find(|u| u.id == target_id) {
-                if target.is_alive {
-                    let actual_loss = target.mitigate_damage(amount, dtype);
-                    
-                    // Simple HP/Shield logic
-                    if target.shields > 0.0 && matches!(dtype, crate::mechanics::DamageType::Energy) {
-                        target.shields -= actual_loss;
-                        if target.shields < 0.0 {
-                            target.hp += target.shields; // Carry over
-                            target.shields = 0.0;
-                        }
-                    } else {
-                        target.hp -= actual_loss;
-                    }
+        for (attacker_id, w_idx, target_id, amount, dtype, impact) in damage_events {
+            let slot = hash
+                .slot(target_id)
+                .filter(|&idx| self.state.units.get(idx).is_some_and(|u| u.id == target_id))
+                .or_else(|| self.state.units.iter().position(|u| u.id == target_id));
+            let mut killed = false;
+            if let Some(target) = slot.map(|idx| &mut self.state.units[idx])
+                && target.is_alive
+            {
+                let actual_loss = target.mitigate_damage(amount, dtype);
 
-                    if target.hp <= 0.0 {
-                        target.is_alive = false;
-                        target.hp = 0.0;
-                        total_destroyed += 1;
-                        
-                        if let Some(log) = &self.event_log {
-                            let evt = Event::new(
-                                EventSeverity::Info,
-                                "Combat".to_string(),
-                                format!("Unit {} destroyed by Unit {}", target_id, "Unknown"), // Context missing for attacker ID here
-                                self.current_context.child(), // Use child context for causal tracing
-                                None
-                            );
-                            log.add(evt);
-                        }
+                // Simple HP/Shield logic
+                if target.shields > 0.0 && matches!(dtype, crate::mechanics::DamageType::Energy) {
+                    target.shields -= actual_loss;
+                    if target.shields < 0.0 {
+                        target.hp += target.shields; // Carry over
+                        target.shields = 0.0;
                     }
+                } else {
+                    target.hp -= actual_loss;
+                }
+
+                if target.hp <= 0.0 {
+                    target.is_alive = false;
+                    target.hp = 0.0;
+                    killed = true;
+                }
+                match impact {
+                    Impact::Hit => self.state.ledger.record_hit(attacker_id, target_id, actual_loss),
+                    Impact::Splash => self.state.ledger.record_splash(attacker_id, target_id, actual_loss),
+                    Impact::FriendlySplash => self.state.ledger.record_friendly_splash(attacker_id, target_id, actual_loss),
+                }
+            }
+
+            if killed {
+                let weapon = hash
+                    .unit(&self.state, attacker_id)
+                    .and_then(|u| u.weapons.get(w_idx))
+                    .map(|w| w.name.clone())
+                    .unwrap_or_default();
+                if impact == Impact::FriendlySplash {
+                    self.state.ledger.record_teamkill(self.state.turn, attacker_id, target_id, &weapon);
+                } else {
+                    self.state.ledger.record_kill(self.state.turn, attacker_id, target_id, &weapon);
+                }
+
+                #[cfg(feature = "observability")]
+                if let Some(log) = &self.obs.event_log {
+                    let evt = Event::new(
+                        EventSeverity::Info,
+                        "Combat".to_string(),
+                        format!("Unit {} destroyed by Unit {}", target_id, attacker_id),
+                        span.context.child(), // Use child context for causal tracing
+                        None
+                    )
+                    .with_field("target_id", target_id.get())
+                    .with_field("attacker_id", attacker_id.get())
+                    .with_field("weapon", weapon)
+                    .at(sim_time);
+                    log.add(evt);
                 }
             }
         }
@@ -248,13 +490,53 @@ impl BattleEngine {
              }
         }
 
-        // Return true if battle should continue (units > 0)
-        // Check if multiple factions still alive
-        let factions: std::collections::HashSet<u8> = self.state.units.iter()
-            .filter(|u| u.is_alive)
-            .map(|u| u.faction_idx)
-            .collect();
-            
-        factions.len() > 1
+        // Return true if battle should continue: no end condition met
+        self.end = self.end_conditions.evaluate(&self.state, &self.hostility, &mut self.objective_hold);
+        let continues = self.end.is_none();
+
+        #[cfg(feature = "observability")]
+        if let (Some(log), Some(end)) = (&self.obs.event_log, self.end) {
+            let evt = Event::new(
+                EventSeverity::Info,
+                "Combat".to_string(),
+                format!("Battle ended by {:?} on turn {}", end.reason, self.state.turn),
+                span.context.child(),
+                None,
+            )
+            .with_field("reason", format!("{:?}", end.reason))
+            .with_field("winner", end.winner)
+            .at(sim_time);
+            log.add(evt);
+        }
+
+        #[cfg(feature = "observability")]
+        if let (Some(log), Some(every)) = (&self.obs.event_log, self.obs.memory_event_interval)
+            && self.state.turn.is_multiple_of(every)
+        {
+            let stats = self.state.memory_stats();
+            let evt = Event::new(
+                EventSeverity::Debug,
+                "Memory".to_string(),
+                format!("Battle holds {} units ({} dead), ~{} bytes", stats.units, stats.dead_units, stats.approx_bytes),
+                span.context.child(),
+                None,
+            )
+            .with_field("units", stats.units)
+            .with_field("dead_units", stats.dead_units)
+            .with_field("weapons", stats.weapons)
+            .with_field("approx_bytes", stats.approx_bytes)
+            .at(sim_time);
+            log.add(evt);
+        }
+
+        #[cfg(feature = "observability")]
+        if let Some(log) = &self.obs.event_log {
+            log.end_span(
+                span.with_attribute("turn", self.state.turn)
+                    .with_attribute("alive_factions", self.state.units.iter().filter(|u| u.is_alive).map(|u| u.faction_idx).collect::<std::collections::BTreeSet<_>>().len())
+            );
+        }
+
+        continues
     }
 }
@@ -0,0 +1,84 @@
+//! Who did what to whom over a battle: damage dealt and taken per unit and
+//! every kill, for after-action reports.
+
+use crate::UnitId;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// One unit's part in the battle so far.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct UnitTally {
+    pub shots_fired: u32,
+    pub hits: u32,
+    /// To enemies, after armor, shields and cover; splash included.
+    pub damage_dealt: f32,
+    pub damage_taken: f32,
+    pub kills: u32,
+    /// Splash that landed on allies under friendly fire.
+    #[serde(default)]
+    pub friendly_damage: f32,
+    /// Allies killed by that splash.
+    #[serde(default)]
+    pub teamkills: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Kill {
+    pub turn: u32,
+    pub attacker_id: UnitId,
+    pub target_id: UnitId,
+    pub weapon: String,
+    /// The target was an ally caught in the attacker's splash.
+    #[serde(default)]
+    pub friendly: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BattleLedger {
+    /// By unit id; units that never fired or were hit are absent.
+    pub units: BTreeMap<UnitId, UnitTally>,
+    /// In the order they happened.
+    pub kills: Vec<Kill>,
+}
+
+impl BattleLedger {
+    pub fn unit(&self, id: UnitId) -> Option<&UnitTally> {
+        self.units.get(&id)
+    }
+
+    pub fn record_shot(&mut self, attacker_id: UnitId) {
+        self.units.entry(attacker_id).or_default().shots_fired += 1;
+    }
+
+    /// A hit from `attacker_id` that took `amount` off `target_id`.
+    pub fn record_hit(&mut self, attacker_id: UnitId, target_id: UnitId, amount: f32) {
+        let attacker = self.units.entry(attacker_id).or_default();
+        attacker.hits += 1;
+        attacker.damage_dealt += amount;
+        self.units.entry(target_id).or_default().damage_taken += amount;
+    }
+
+    /// Splash from a hit on another unit: damage, but not a hit of its own.
+    pub fn record_splash(&mut self, attacker_id: UnitId, target_id: UnitId, amount: f32) {
+        self.units.entry(attacker_id).or_default().damage_dealt += amount;
+        self.units.entry(target_id).or_default().damage_taken += amount;
+    }
+
+    /// Splash that caught an ally: taken like any damage, but kept out of
+    /// the attacker's `damage_dealt`.
+    pub fn record_friendly_splash(&mut self, attacker_id: UnitId, target_id: UnitId, amount: f32) {
+        self.units.entry(attacker_id).or_default().friendly_damage += amount;
+        self.units.entry(target_id).or_default().damage_taken += amount;
+    }
+
+    pub fn record_kill(&mut self, turn: u32, attacker_id: UnitId, target_id: UnitId, weapon: &str) {
+        self.units.entry(attacker_id).or_default().kills += 1;
+        self.kills.push(Kill { turn, attacker_id, target_id, weapon: weapon.to_string(), friendly: false });
+    }
+
+    /// An ally killed by friendly fire; counted as a teamkill, not a kill.
+    pub fn record_teamkill(&mut self, turn: u32, attacker_id: UnitId, target_id: UnitId, weapon: &str) {
+        self.units.entry(attacker_id).or_default().teamkills += 1;
+        self.kills.push(Kill { turn, attacker_id, target_id, weapon: weapon.to_string(), friendly: true });
+    }
+}
@@ -1,10 +1,34 @@
 pub mod mechanics;
 pub mod targeting;
 pub mod engine;
-use std::collections::HashMap;
+pub mod resolve;
+pub mod stats;
+pub mod lockstep;
+pub mod ledger;
+pub mod victory;
+
+use serde::{Deserialize, Serialize};
+pub use void_reckoning_ids::UnitId;
+
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Version of the `BattleState`/`BattleSetup`/`BattleOutcome` layouts;
+/// bumped on incompatible changes.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Cargo features this build was compiled with.
+pub fn enabled_features() -> Vec<&'static str> {
+    let mut features = Vec::new();
+    if cfg!(feature = "observability") {
+        features.push("observability");
+    }
+    if cfg!(feature = "parallel") {
+        features.push("parallel");
+    }
+    features
+}
 
 /// Enumeration of Weapon Types for damage calculation context
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum WeaponType {
     Kinetic,
     Energy,
@@ -14,19 +38,25 @@ pub enum WeaponType {
 }
 
 /// A lightweight representation of a weapon system on a unit.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Weapon {
     pub name: String,
     pub weapon_type: WeaponType,
     pub range: f32,
     pub damage: f32,
+    /// Chance each shot lands, before target evasion and range.
     pub accuracy: f32,
     pub cooldown: f32,
     pub current_cooldown: f32,
+    /// Splash radius around the impact point; other units inside take the
+    /// hit's damage scaled down linearly to nothing at the edge. 0 hits
+    /// the target alone.
+    #[serde(default)]
+    pub aoe_radius: f32,
 }
 
 /// Cover bonuses for mitigation
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum CoverType {
     None,
     Light, // 25% Damage Reduction
@@ -35,9 +65,9 @@ pub enum CoverType {
 }
 
 /// A flattened, memory-efficient representation of a combat unit.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CombatUnit {
-    pub id: u32,
+    pub id: UnitId,
     pub name: String,
     pub faction_idx: u8, // 0-255 index into faction list
     
@@ -57,7 +87,7 @@ pub struct CombatUnit {
     // State
     pub position: (f32, f32), // Grid coordinates
     pub velocity: (f32, f32), // Movement vector
-    pub target_id: Option<u32>, // Current target
+    pub target_id: Option<UnitId>, // Current target
     pub is_alive: bool,
     
     // Context
@@ -65,7 +95,7 @@ pub struct CombatUnit {
 }
 
 impl CombatUnit {
-    pub fn new(id: u32, name: String, faction_idx: u8, max_hp: f32) -> Self {
+    pub fn new(id: UnitId, name: String, faction_idx: u8, max_hp: f32) -> Self {
         Self {
             id,
             name,
@@ -93,12 +123,16 @@ impl CombatUnit {
 }
 
 /// The main container for a battle simulation state.
+#[derive(Clone, Serialize, Deserialize)]
 pub struct BattleState {
     pub units: Vec<CombatUnit>,
     pub grid_size: (f32, f32),
     pub turn: u32,
     pub time_elapsed: f32,
     pub run_id: String,
+    /// Shots, damage and kills so far.
+    #[serde(default)]
+    pub ledger: ledger::BattleLedger,
 }
 
 impl BattleState {
@@ -109,6 +143,7 @@ impl BattleState {
             turn: 0,
             time_elapsed: 0.0,
             run_id: uuid::Uuid::new_v4().to_string(),
+            ledger: ledger::BattleLedger::default(),
         }
     }
     
@@ -116,11 +151,11 @@ impl BattleState {
         self.units.push(unit);
     }
     
-    pub fn get_unit(&self, id: u32) -> Option<&CombatUnit> {
+    pub fn get_unit(&self, id: UnitId) -> Option<&CombatUnit> {
         self.units.iter().find(|u| u.id == id)
     }
     
-    pub fn get_unit_mut(&mut self, id: u32) -> Option<&mut CombatUnit> {
+    pub fn get_unit_mut(&mut self, id: UnitId) -> Option<&mut CombatUnit> {
         self.units.iter_mut().find(|u| u.id == id)
     }
 }
@@ -0,0 +1,108 @@
+//! Lockstep support: a hash of battle state that is identical on every
+//! platform, so clients stepping the same battle can confirm each turn
+//! that they still agree.
+
+use crate::BattleState;
+use void_reckoning_hash::{extend_hash, FNV_OFFSET};
+
+/// FNV-1a over explicitly little-endian fields; unlike `std::hash`, the
+/// result doesn't depend on the process or platform.
+pub struct StateHasher(u64);
+
+impl Default for StateHasher {
+    fn default() -> Self {
+        Self(FNV_OFFSET)
+    }
+}
+
+impl StateHasher {
+    pub fn write(&mut self, bytes: &[u8]) {
+        self.0 = extend_hash(self.0, bytes);
+    }
+
+    pub fn write_u32(&mut self, value: u32) {
+        self.write(&value.to_le_bytes());
+    }
+
+    pub fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    /// Hashes the exact bits, except that -0.0 and 0.0 hash alike.
+    pub fn write_f32(&mut self, value: f32) {
+        self.write_u32(if value == 0.0 { 0 } else { value.to_bits() });
+    }
+
+    pub fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+impl BattleState {
+    /// Hash of everything that decides how the battle continues: the turn
+    /// and, in id order, each unit's vitals, position, target and weapon
+    /// cooldowns. Names and `run_id` are left out, so two clients that
+    /// built the same battle independently agree.
+    pub fn state_hash(&self) -> u64 {
+        let mut hasher = StateHasher::default();
+        hasher.write_u32(self.turn);
+        let mut units: Vec<_> = self.units.iter().collect();
+        units.sort_by_key(|u| u.id);
+        for unit in units {
+            hasher.write_u32(unit.id.get());
+            hasher.write(&[unit.faction_idx, unit.is_alive as u8]);
+            for value in [unit.hp, unit.shields, unit.armor, unit.position.0, unit.position.1] {
+                hasher.write_f32(value);
+            }
+            hasher.write_u64(unit.target_id.map_or(u64::MAX, |id| u64::from(id.get())));
+            for weapon in &unit.weapons {
+                hasher.write_f32(weapon.current_cooldown);
+            }
+        }
+        hasher.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::BattleEngine;
+    use crate::{CombatUnit, UnitId, Weapon, WeaponType};
+
+    fn battle(seed: u64) -> BattleEngine {
+        let mut engine = BattleEngine::new_with_seed(200.0, 200.0, seed);
+        engine.set_deterministic(Some(seed));
+        for id in 0..6 {
+            let mut unit = CombatUnit::new(UnitId(id), format!("U{}", id), (id % 2) as u8, 120.0);
+            unit.position = (id as f32 * 30.0, (id % 3) as f32 * 17.0);
+            unit.speed = 3.0;
+            unit.weapons.push(Weapon {
+                name: "Gun".to_string(),
+                weapon_type: WeaponType::Kinetic,
+                range: 25.0,
+                damage: 9.0,
+                accuracy: 1.0,
+                cooldown: 1.0,
+                current_cooldown: 0.0,
+                aoe_radius: 0.0,
+            });
+            engine.add_unit(unit);
+        }
+        engine
+    }
+
+    #[test]
+    fn deterministic_battles_hash_alike_every_turn() {
+        let (mut a, mut b) = (battle(11), battle(11));
+        assert_eq!(a.state.state_hash(), b.state.state_hash());
+        for _ in 0..40 {
+            a.step();
+            b.step();
+            assert_eq!(a.state.state_hash(), b.state.state_hash());
+        }
+        let mut other = battle(12);
+        for _ in 0..40 {
+            other.step();
+        }
+        assert_ne!(a.state.state_hash(), other.state.state_hash());
+    }
+}
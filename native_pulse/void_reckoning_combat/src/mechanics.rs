@@ -7,10 +7,17 @@ pub enum DamageType {
     Explosive,
 }
 
+/// How much less likely a shot at the very edge of a weapon's range is to
+/// land than one fired point-blank.
+pub const RANGE_FALLOFF: f32 = 0.25;
+
 pub trait DamageSource {
     fn calculate_damage(&self, rng: &mut impl Rng) -> f32;
     fn get_damage_type(&self) -> DamageType;
     fn get_accuracy(&self) -> f32;
+    /// Chance in [0, 1] that a shot at a target with `evasion`, `distance`
+    /// away, lands.
+    fn hit_chance(&self, evasion: f32, distance: f32) -> f32;
 }
 
 pub trait Armor {
@@ -38,6 +45,12 @@ impl DamageSource for crate::Weapon {
     fn get_accuracy(&self) -> f32 {
         self.accuracy
     }
+
+    fn hit_chance(&self, evasion: f32, distance: f32) -> f32 {
+        let reach = if self.range > 0.0 { (distance / self.range).clamp(0.0, 1.0) } else { 0.0 };
+        let chance = self.accuracy * (1.0 - evasion.clamp(0.0, 1.0)) * (1.0 - RANGE_FALLOFF * reach);
+        chance.clamp(0.0, 1.0)
+    }
 }
 
 // Implementation for CombatUnit
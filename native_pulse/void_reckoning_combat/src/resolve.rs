@@ -0,0 +1,376 @@
+//! Auto-resolve: build battles from declarative setups and run them to
+//! completion, many at once, on a pool of worker threads.
+
+use crate::engine::BattleEngine;
+use crate::victory::{EndConditions, EndReason};
+use crate::{CombatUnit, CoverType, UnitId, Weapon, WeaponType};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+#[cfg(feature = "parallel")]
+use std::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(feature = "parallel")]
+use std::sync::Mutex;
+
+/// Battles that neither side wins within this many turns end as a draw.
+pub const DEFAULT_MAX_TURNS: u32 = 500;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeaponSetup {
+    pub name: String,
+    #[serde(default = "default_weapon_type")]
+    pub weapon_type: WeaponType,
+    pub range: f32,
+    pub damage: f32,
+    #[serde(default = "default_accuracy")]
+    pub accuracy: f32,
+    #[serde(default = "default_cooldown")]
+    pub cooldown: f32,
+    #[serde(default)]
+    pub aoe_radius: f32,
+}
+
+impl WeaponSetup {
+    pub fn build(&self) -> Weapon {
+        Weapon {
+            name: self.name.clone(),
+            weapon_type: self.weapon_type,
+            range: self.range,
+            damage: self.damage,
+            accuracy: self.accuracy,
+            cooldown: self.cooldown,
+            current_cooldown: 0.0,
+            aoe_radius: self.aoe_radius,
+        }
+    }
+}
+
+fn default_weapon_type() -> WeaponType {
+    WeaponType::Kinetic
+}
+
+fn default_accuracy() -> f32 {
+    1.0
+}
+
+fn default_cooldown() -> f32 {
+    1.0
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnitSetup {
+    pub id: UnitId,
+    #[serde(default)]
+    pub name: String,
+    pub faction_idx: u8,
+    pub max_hp: f32,
+    #[serde(default)]
+    pub x: f32,
+    #[serde(default)]
+    pub y: f32,
+    #[serde(default)]
+    pub weapons: Vec<WeaponSetup>,
+    #[serde(default)]
+    pub speed: f32,
+    #[serde(default)]
+    pub evasion: f32,
+    #[serde(default)]
+    pub shields: f32,
+    #[serde(default)]
+    pub armor: f32,
+    #[serde(default = "default_cover")]
+    pub cover: CoverType,
+}
+
+fn default_cover() -> CoverType {
+    CoverType::None
+}
+
+impl UnitSetup {
+    pub fn build(&self) -> CombatUnit {
+        let mut unit = CombatUnit::new(self.id, self.name.clone(), self.faction_idx, self.max_hp);
+        unit.position = (self.x, self.y);
+        unit.speed = self.speed;
+        unit.evasion = self.evasion;
+        unit.shields = self.shields;
+        unit.max_shields = self.shields;
+        unit.armor = self.armor;
+        unit.cover = self.cover;
+        unit.weapons = self.weapons.iter().map(WeaponSetup::build).collect();
+        unit
+    }
+}
+
+/// Everything needed to fight one battle. `seed` overrides the per-battle
+/// seed `resolve_battles` would otherwise derive.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BattleSetup {
+    #[serde(default)]
+    pub name: String,
+    pub width: f32,
+    pub height: f32,
+    pub units: Vec<UnitSetup>,
+    #[serde(default)]
+    pub max_turns: Option<u32>,
+    #[serde(default)]
+    pub seed: Option<u64>,
+    #[serde(default)]
+    pub end_conditions: EndConditions,
+}
+
+impl BattleSetup {
+    pub fn build(&self, seed: u64) -> BattleEngine {
+        let mut engine = BattleEngine::new_with_seed(self.width, self.height, self.seed.unwrap_or(seed));
+        engine.set_end_conditions(self.end_conditions);
+        for unit in &self.units {
+            engine.add_unit(unit.build());
+        }
+        engine
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FactionResult {
+    pub faction_idx: u8,
+    pub units: usize,
+    pub survivors: usize,
+    pub hp_remaining: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BattleOutcome {
+    pub name: String,
+    pub turns: u32,
+    /// The only faction left standing, or the one an end condition
+    /// favoured; `None` for a draw or mutual destruction.
+    pub winner: Option<u8>,
+    /// True if the battle hit `max_turns` with several factions still alive.
+    pub timed_out: bool,
+    pub survivors: Vec<UnitId>,
+    pub casualties: Vec<UnitId>,
+    /// One entry per faction, by faction index.
+    pub factions: Vec<FactionResult>,
+    /// What ended the battle; `None` while it is still going.
+    #[serde(default)]
+    pub reason: Option<EndReason>,
+}
+
+impl BattleEngine {
+    /// Steps until at most one faction remains or `max_turns` is reached.
+    pub fn run_to_completion(&mut self, name: &str, max_turns: u32) -> BattleOutcome {
+        let mut continues = self.state.units.iter().any(|u| u.is_alive);
+        while continues && self.state.turn < max_turns {
+            continues = self.step();
+        }
+        self.outcome(name, continues)
+    }
+
+    /// Summarises the battle as it stands; `timed_out` marks a battle that
+    /// was stopped with several factions still fighting. An end condition
+    /// that ended it decides the winner.
+    pub fn outcome(&self, name: &str, timed_out: bool) -> BattleOutcome {
+        let mut factions: BTreeMap<u8, FactionResult> = BTreeMap::new();
+        let (mut survivors, mut casualties) = (Vec::new(), Vec::new());
+        for unit in &self.state.units {
+            let entry = factions.entry(unit.faction_idx).or_insert(FactionResult {
+                faction_idx: unit.faction_idx,
+                units: 0,
+                survivors: 0,
+                hp_remaining: 0.0,
+            });
+            entry.units += 1;
+            if unit.is_alive {
+                entry.survivors += 1;
+                entry.hp_remaining += unit.hp;
+                survivors.push(unit.id);
+            } else {
+                casualties.push(unit.id);
+            }
+        }
+
+        let mut standing = factions.values().filter(|f| f.survivors > 0).map(|f| f.faction_idx);
+        let winner = match (self.end(), standing.next(), standing.next()) {
+            (Some(end), _, _) => end.winner,
+            (None, Some(only), None) => Some(only),
+            _ => None,
+        };
+        let reason = match self.end() {
+            Some(end) => Some(end.reason),
+            None => timed_out.then_some(EndReason::TurnLimit),
+        };
+        BattleOutcome {
+            name: name.to_string(),
+            turns: self.state.turn,
+            winner,
+            timed_out: timed_out || reason == Some(EndReason::TurnLimit),
+            survivors,
+            casualties,
+            factions: factions.into_values().collect(),
+            reason,
+        }
+    }
+}
+
+/// Per-battle seed: independent of which worker picks the battle up.
+fn battle_seed(seed: u64, index: usize) -> u64 {
+    seed.wrapping_add((index as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15))
+}
+
+fn resolve_one(setup: &BattleSetup, seed: u64, index: usize, prepare: &impl Fn(&mut BattleEngine)) -> BattleOutcome {
+    let mut engine = setup.build(battle_seed(seed, index));
+    prepare(&mut engine);
+    let max_turns = setup.max_turns.or(setup.end_conditions.max_turns).unwrap_or(DEFAULT_MAX_TURNS);
+    engine.run_to_completion(&setup.name, max_turns)
+}
+
+/// Resolves every setup on up to `workers` threads (0 = one per core) and
+/// returns outcomes in setup order. Results depend only on `seed` and the
+/// setups, not on the thread count.
+pub fn resolve_battles(setups: &[BattleSetup], seed: u64, workers: usize) -> Vec<BattleOutcome> {
+    resolve_battles_with(setups, seed, workers, |_| {})
+}
+
+/// Like `resolve_battles`, but calls `prepare` on each engine before it
+/// runs, e.g. to attach an event log or correlation context.
+#[cfg(feature = "parallel")]
+pub fn resolve_battles_with(
+    setups: &[BattleSetup],
+    seed: u64,
+    workers: usize,
+    prepare: impl Fn(&mut BattleEngine) + Sync,
+) -> Vec<BattleOutcome> {
+    let workers = match workers {
+        0 => std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+        n => n,
+    }
+    .min(setups.len())
+    .max(1);
+
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<BattleOutcome>>> = Mutex::new(vec![None; setups.len()]);
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| {
+                loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(setup) = setups.get(index) else { break };
+                    let outcome = resolve_one(setup, seed, index, &prepare);
+                    if let Ok(mut results) = results.lock() {
+                        results[index] = Some(outcome);
+                    }
+                }
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .unwrap_or_default()
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+/// Serial fallback for targets without threads (wasm32); `workers` is
+/// ignored and outcomes match the threaded version.
+#[cfg(not(feature = "parallel"))]
+pub fn resolve_battles_with(
+    setups: &[BattleSetup],
+    seed: u64,
+    _workers: usize,
+    prepare: impl Fn(&mut BattleEngine),
+) -> Vec<BattleOutcome> {
+    setups
+        .iter()
+        .enumerate()
+        .map(|(index, setup)| resolve_one(setup, seed, index, &prepare))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::victory::ObjectiveZone;
+
+    fn skirmish(name: &str) -> BattleSetup {
+        let gun = WeaponSetup {
+            name: "Autocannon".to_string(),
+            weapon_type: WeaponType::Kinetic,
+            range: 50.0,
+            damage: 12.0,
+            accuracy: 1.0,
+            cooldown: 1.0,
+            aoe_radius: 0.0,
+        };
+        let unit = |id: UnitId, faction_idx: u8, x: f32| UnitSetup {
+            id,
+            name: format!("U{}", id),
+            faction_idx,
+            max_hp: 100.0,
+            x,
+            y: 0.0,
+            weapons: vec![gun.clone()],
+            speed: 5.0,
+            evasion: 0.0,
+            shields: 0.0,
+            armor: 0.0,
+            cover: CoverType::None,
+        };
+        BattleSetup {
+            name: name.to_string(),
+            width: 100.0,
+            height: 100.0,
+            units: vec![unit(UnitId(1), 0, 0.0), unit(UnitId(2), 0, 5.0), unit(UnitId(3), 1, 30.0)],
+            max_turns: None,
+            seed: None,
+            end_conditions: EndConditions::default(),
+        }
+    }
+
+    #[test]
+    fn test_parallel_resolution_is_deterministic() {
+        let setups: Vec<BattleSetup> = (0..6).map(|i| skirmish(&format!("b{}", i))).collect();
+        let serial = resolve_battles(&setups, 42, 1);
+        let parallel = resolve_battles(&setups, 42, 4);
+
+        assert_eq!(serial, parallel);
+        assert_eq!(serial.len(), 6);
+        assert_eq!(serial[3].name, "b3");
+        assert!(serial.iter().all(|o| o.winner == Some(0) && !o.timed_out));
+    }
+
+    #[test]
+    fn end_conditions_settle_stalemates() {
+        // Out of range of each other and unable to move: a stalemate.
+        let mut stalemate = skirmish("s");
+        for unit in &mut stalemate.units {
+            unit.speed = 0.0;
+            unit.x *= 3.0;
+        }
+        stalemate.end_conditions.max_turns = Some(12);
+        let outcome = &resolve_battles(&[stalemate.clone()], 1, 1)[0];
+        assert_eq!((outcome.turns, outcome.winner, outcome.reason), (12, None, Some(EndReason::TurnLimit)));
+        assert!(outcome.timed_out);
+
+        stalemate.end_conditions.objective = Some(ObjectiveZone { center: (90.0, 0.0), radius: 5.0, turns: 3 });
+        let outcome = &resolve_battles(&[stalemate], 1, 1)[0];
+        assert_eq!((outcome.turns, outcome.winner, outcome.reason), (3, Some(1), Some(EndReason::ObjectiveHeld)));
+
+        let fought = &resolve_battles(&[skirmish("f")], 1, 1)[0];
+        assert_eq!(fought.reason, Some(EndReason::Annihilation));
+    }
+
+    #[test]
+    fn steps_after_the_end_change_nothing() {
+        let mut setup = skirmish("held");
+        setup.end_conditions.objective = Some(ObjectiveZone { center: (30.0, 0.0), radius: 5.0, turns: 2 });
+        let mut engine = setup.build(1);
+        while engine.step() {}
+        let ended = engine.outcome("held", false);
+        assert_eq!((ended.turns, ended.reason), (2, Some(EndReason::ObjectiveHeld)));
+
+        for _ in 0..5 {
+            assert!(!engine.step());
+        }
+        assert_eq!(engine.outcome("held", false), ended);
+    }
+}
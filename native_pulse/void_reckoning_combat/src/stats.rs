@@ -0,0 +1,70 @@
+//! Memory footprint of a battle, for spotting state that only ever grows
+//! (dead units are kept in `BattleState` until the battle is dropped).
+
+use crate::{BattleState, CombatUnit, Weapon};
+use serde::{Deserialize, Serialize};
+use std::mem::size_of;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryStats {
+    pub units: usize,
+    pub living_units: usize,
+    pub dead_units: usize,
+    pub weapons: usize,
+    /// Rough heap + inline footprint of the units and their weapons.
+    pub approx_bytes: usize,
+}
+
+fn unit_footprint(unit: &CombatUnit) -> usize {
+    unit.name.len()
+        + unit.weapons.capacity() * size_of::<Weapon>()
+        + unit.weapons.iter().map(|w| w.name.len()).sum::<usize>()
+}
+
+impl BattleState {
+    pub fn memory_stats(&self) -> MemoryStats {
+        let living_units = self.units.iter().filter(|u| u.is_alive).count();
+        MemoryStats {
+            units: self.units.len(),
+            living_units,
+            dead_units: self.units.len() - living_units,
+            weapons: self.units.iter().map(|u| u.weapons.len()).sum(),
+            approx_bytes: size_of::<BattleState>()
+                + self.run_id.len()
+                + self.units.capacity() * size_of::<CombatUnit>()
+                + self.units.iter().map(unit_footprint).sum::<usize>(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{UnitId, WeaponType};
+
+    #[test]
+    fn test_dead_units_still_count_towards_memory() {
+        let mut state = BattleState::new(100.0, 100.0);
+        for id in 0..4 {
+            let mut unit = CombatUnit::new(UnitId(id), format!("U{}", id), 0, 10.0);
+            unit.weapons.push(Weapon {
+                name: "Laser".to_string(),
+                weapon_type: WeaponType::Energy,
+                range: 10.0,
+                damage: 1.0,
+                accuracy: 1.0,
+                cooldown: 1.0,
+                current_cooldown: 0.0,
+                aoe_radius: 0.0,
+            });
+            state.add_unit(unit);
+        }
+        let before = state.memory_stats();
+        state.units[0].is_alive = false;
+        let after = state.memory_stats();
+
+        assert_eq!((after.units, after.living_units, after.dead_units, after.weapons), (4, 3, 1, 4));
+        assert_eq!(before.approx_bytes, after.approx_bytes);
+        assert!(after.approx_bytes >= 4 * size_of::<CombatUnit>());
+    }
+}
@@ -1,11 +1,46 @@
-use crate::{BattleState, CombatUnit};
-use std::collections::HashMap;
+use crate::{BattleState, CombatUnit, UnitId};
+use std::collections::{BTreeSet, HashMap};
+
+/// Faction pairs that don't fight each other (allies, treaty partners).
+/// Every other pair of distinct factions is hostile.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Hostility {
+    allied: BTreeSet<(u8, u8)>,
+}
+
+impl Hostility {
+    pub fn from_alliances(pairs: impl IntoIterator<Item = (u8, u8)>) -> Self {
+        let mut hostility = Self::default();
+        for (a, b) in pairs {
+            hostility.ally(a, b);
+        }
+        hostility
+    }
+
+    pub fn ally(&mut self, a: u8, b: u8) {
+        if a != b {
+            self.allied.insert((a.min(b), a.max(b)));
+        }
+    }
+
+    pub fn is_hostile(&self, a: u8, b: u8) -> bool {
+        a != b && !self.allied.contains(&(a.min(b), a.max(b)))
+    }
+
+    /// Allied pairs, each as `(lower, higher)` faction index.
+    pub fn alliances(&self) -> impl Iterator<Item = (u8, u8)> + '_ {
+        self.allied.iter().copied()
+    }
+}
 
 /// Simple Spatial Hash for O(N log N) targeting performance.
 /// Divides the 500x500 grid into cells.
 pub struct SpatialHash {
-    pub cells: HashMap<(i32, i32), Vec<u32>>,
+    pub cells: HashMap<(i32, i32), Vec<UnitId>>,
     pub cell_size: f32,
+    /// Index in `state.units` of every unit `build` saw, dead or alive,
+    /// so hits are looked up without scanning the units.
+    slots: HashMap<UnitId, usize>,
 }
 
 impl SpatialHash {
@@ -13,18 +48,24 @@ impl SpatialHash {
         Self {
             cells: HashMap::new(),
             cell_size,
+            slots: HashMap::new(),
         }
     }
 
+    fn cell(&self, coord: f32) -> i32 {
+        (coord / self.cell_size).floor() as i32
+    }
+
     pub fn insert(&mut self, unit: &CombatUnit) {
-        let x = (unit.position.0 / self.cell_size) as i32;
-        let y = (unit.position.1 / self.cell_size) as i32;
-        self.cells.entry((x, y)).or_insert_with(Vec::new).push(unit.id);
+        let key = (self.cell(unit.position.0), self.cell(unit.position.1));
+        self.cells.entry(key).or_default().push(unit.id);
     }
 
     pub fn build(state: &BattleState, cell_size: f32) -> Self {
         let mut hash = Self::new(cell_size);
-        for unit in &state.units {
+        for (idx, unit) in state.units.iter().enumerate() {
+            // The first unit with an id wins, as in `BattleState::get_unit`.
+            hash.slots.entry(unit.id).or_insert(idx);
             if unit.is_alive {
                 hash.insert(unit);
             }
@@ -32,12 +73,23 @@ impl SpatialHash {
         hash
     }
 
-    pub fn get_nearby(&self, pos: (f32, f32), radius: f32) -> Vec<u32> {
+    /// Index of unit `id` in `state.units` as of `build`.
+    pub fn slot(&self, id: UnitId) -> Option<usize> {
+        self.slots.get(&id).copied()
+    }
+
+    /// `state.get_unit(id)` in constant time for units `build` saw.
+    pub fn unit<'a>(&self, state: &'a BattleState, id: UnitId) -> Option<&'a CombatUnit> {
+        match self.slot(id).and_then(|idx| state.units.get(idx)) {
+            Some(unit) if unit.id == id => Some(unit),
+            _ => state.get_unit(id),
+        }
+    }
+
+    pub fn get_nearby(&self, pos: (f32, f32), radius: f32) -> Vec<UnitId> {
         let mut nearby = Vec::new();
-        let min_x = ((pos.0 - radius) / self.cell_size) as i32;
-        let max_x = ((pos.0 + radius) / self.cell_size) as i32;
-        let min_y = ((pos.1 - radius) / self.cell_size) as i32;
-        let max_y = ((pos.1 + radius) / self.cell_size) as i32;
+        let (min_x, max_x) = (self.cell(pos.0 - radius), self.cell(pos.0 + radius));
+        let (min_y, max_y) = (self.cell(pos.1 - radius), self.cell(pos.1 + radius));
 
         for x in min_x..=max_x {
             for y in min_y..=max_y {
@@ -48,15 +100,65 @@ impl SpatialHash {
         }
         nearby
     }
+
+    /// Living units within `radius` of `pos`, nearest first. Unlike
+    /// `get_nearby`, which returns whole cells, this is an exact circle.
+    pub fn units_in_radius(&self, state: &BattleState, pos: (f32, f32), radius: f32) -> Vec<UnitId> {
+        let mut hits: Vec<(f32, UnitId)> = self
+            .get_nearby(pos, radius)
+            .into_iter()
+            .filter_map(|id| self.unit(state, id))
+            .map(|u| (dist_sq(u.position, pos), u.id))
+            .filter(|&(d, _)| d <= radius * radius)
+            .collect();
+        hits.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+        hits.into_iter().map(|(_, id)| id).collect()
+    }
+
+    /// Closest living unit of another faction, searching outward ring by
+    /// ring until the whole battlefield has been covered.
+    pub fn nearest_enemy(&self, state: &BattleState, unit: &CombatUnit) -> Option<UnitId> {
+        self.nearest_hostile(state, unit, &Hostility::default())
+    }
+
+    /// `nearest_enemy`, skipping factions allied to `unit`'s.
+    pub fn nearest_hostile(&self, state: &BattleState, unit: &CombatUnit, hostility: &Hostility) -> Option<UnitId> {
+        let limit = state.grid_size.0.hypot(state.grid_size.1).max(self.cell_size);
+        let mut radius = self.cell_size;
+        loop {
+            let best = self
+                .get_nearby(unit.position, radius)
+                .into_iter()
+                .filter_map(|id| self.unit(state, id))
+                .filter(|t| hostility.is_hostile(t.faction_idx, unit.faction_idx))
+                .map(|t| (dist_sq(t.position, unit.position), t.id))
+                .min_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+            // A hit outside the searched circle might not be the closest yet.
+            match best {
+                Some((d, id)) if d <= radius * radius => return Some(id),
+                _ if radius >= limit => return best.map(|(_, id)| id),
+                _ => radius *= 2.0,
+            }
+        }
+    }
+}
+
+fn dist_sq(a: (f32, f32), b: (f32, f32)) -> f32 {
+    let (dx, dy) = (a.0 - b.0, a.1 - b.1);
+    dx * dx + dy * dy
 }
 
-pub fn find_best_target(attacker: &CombatUnit, state: &BattleState) -> Option<u32> {
-    // FALLBACK: Linear Scan if no spatial index
+pub fn find_best_target(attacker: &CombatUnit, state: &BattleState) -> Option<UnitId> {
+    find_best_target_with(attacker, state, &Hostility::default())
+}
+
+/// Nearest living unit hostile to `attacker` (linear scan).
+pub fn find_best_target_with(attacker: &CombatUnit, state: &BattleState, hostility: &Hostility) -> Option<UnitId> {
     let mut best_target = None;
     let mut min_dist_sq = f32::MAX;
 
     for target in &state.units {
-        if !target.is_alive || target.id == attacker.id || target.faction_idx == attacker.faction_idx {
+        if !target.is_alive || target.id == attacker.id || !hostility.is_hostile(target.faction_idx, attacker.faction_idx) {
             continue;
         }
 
@@ -73,36 +175,84 @@ pub fn find_best_target(attacker: &CombatUnit, state: &BattleState) -> Option<u3
     best_target
 }
 
-/// Optimized targeting using a spatial index.
-pub fn find_best_target_spatial(attacker: &CombatUnit, state: &BattleState, hash: &SpatialHash) -> Option<u32> {
-    let mut best_target = None;
-    let mut min_dist_sq = f32::MAX;
+/// Optimized targeting using a spatial index built on `state`.
+pub fn find_best_target_spatial(attacker: &CombatUnit, state: &BattleState, hash: &SpatialHash) -> Option<UnitId> {
+    find_best_target_spatial_with(attacker, state, hash, &Hostility::default())
+}
 
-    // Determine search radius based on attacker's longest weapon
-    let max_range = attacker.weapons.iter().map(|w| w.range).fold(0.0, f32::max);
-    
-    // Search in the hash
-    let nearby_ids = hash.get_nearby(attacker.position, max_range.max(50.0)); // Min search radius 50.0
-
-    for &tid in &nearby_ids {
-        // Look up unit (O(1) if we had a map, but state.units is Vec)
-        // Optimization: We should really pass a map or use the hash to store units directly.
-        // For now, let's just use the Vec and find it.
-        if let Some(target) = state.get_unit(tid) {
-            if !target.is_alive || target.id == attacker.id || target.faction_idx == attacker.faction_idx {
-                continue;
-            }
+/// `find_best_target_with` through `hash`, ties included: searches rings
+/// of cells outward until the nearest hostile unit is certain, so dense
+/// battles cost about the units near each attacker rather than all of
+/// them.
+pub fn find_best_target_spatial_with(attacker: &CombatUnit, state: &BattleState, hash: &SpatialHash, hostility: &Hostility) -> Option<UnitId> {
+    let limit = state.grid_size.0.hypot(state.grid_size.1).max(hash.cell_size);
+    let mut radius = hash.cell_size;
+    loop {
+        // (distance, index) so equal distances go to the earlier unit, as
+        // in the linear scan.
+        let best = hash
+            .get_nearby(attacker.position, radius)
+            .into_iter()
+            .filter_map(|id| Some((hash.slot(id)?, hash.unit(state, id)?)))
+            .filter(|(_, t)| t.is_alive && t.id != attacker.id && hostility.is_hostile(t.faction_idx, attacker.faction_idx))
+            .map(|(idx, t)| (dist_sq(t.position, attacker.position), idx, t.id))
+            .min_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+        match best {
+            // Units outside the searched cells are further than `radius`.
+            Some((d, _, id)) if d <= radius * radius => return Some(id),
+            // Units off the grid may lie beyond every ring; settle it exactly.
+            _ if radius >= limit => return find_best_target_with(attacker, state, hostility),
+            _ => radius *= 2.0,
+        }
+    }
+}
 
-            let dx = target.position.0 - attacker.position.0;
-            let dy = target.position.1 - attacker.position.1;
-            let dist_sq = dx*dx + dy*dy;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-            if dist_sq < min_dist_sq {
-                min_dist_sq = dist_sq;
-                best_target = Some(target.id);
-            }
+    #[test]
+    fn test_radius_and_nearest_enemy() {
+        let mut state = BattleState::new(500.0, 500.0);
+        for (id, faction, x) in [(1, 0, 0.0), (2, 0, 10.0), (3, 1, -30.0), (4, 1, 400.0)] {
+            let mut unit = CombatUnit::new(UnitId(id), format!("U{}", id), faction, 10.0);
+            unit.position = (x, 0.0);
+            state.add_unit(unit);
         }
+        let hash = SpatialHash::build(&state, 50.0);
+
+        assert_eq!(hash.units_in_radius(&state, (0.0, 0.0), 30.0), vec![UnitId(1), UnitId(2), UnitId(3)]);
+        assert_eq!(hash.units_in_radius(&state, (5.0, 0.0), 5.0), vec![UnitId(1), UnitId(2)]);
+        assert_eq!(hash.nearest_enemy(&state, state.get_unit(UnitId(2)).unwrap()), Some(UnitId(3)));
+        assert_eq!(hash.nearest_enemy(&state, state.get_unit(UnitId(4)).unwrap()), Some(UnitId(2)));
+
+        let mut three_way = state.clone();
+        let mut unit = CombatUnit::new(UnitId(5), "U5".to_string(), 2, 10.0);
+        unit.position = (5.0, 0.0);
+        three_way.add_unit(unit);
+        let hash = SpatialHash::build(&three_way, 50.0);
+        let allied = Hostility::from_alliances([(2, 0)]);
+        assert_eq!(hash.nearest_hostile(&three_way, three_way.get_unit(UnitId(5)).unwrap(), &allied), Some(UnitId(3)));
+        assert_eq!(find_best_target_with(three_way.get_unit(UnitId(1)).unwrap(), &three_way, &allied), Some(UnitId(3)));
     }
 
-    best_target
+    #[test]
+    fn spatial_targeting_matches_the_linear_scan() {
+        let mut state = BattleState::new(500.0, 500.0);
+        for id in 0..300u32 {
+            let mut unit = CombatUnit::new(UnitId(id), format!("U{}", id), (id % 3) as u8, 10.0);
+            // Scattered, with exact ties and a few units off the grid.
+            unit.position = (((id * 37) % 520) as f32 - 10.0, ((id * 91) % 480) as f32);
+            unit.is_alive = id % 11 != 0;
+            state.add_unit(unit);
+        }
+        let hash = SpatialHash::build(&state, 50.0);
+        let allied = Hostility::from_alliances([(0, 2)]);
+        for hostility in [Hostility::default(), allied] {
+            for unit in &state.units {
+                let spatial = find_best_target_spatial_with(unit, &state, &hash, &hostility);
+                assert_eq!(spatial, find_best_target_with(unit, &state, &hostility), "unit {}", unit.id);
+            }
+        }
+    }
 }
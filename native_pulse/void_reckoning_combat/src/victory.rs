@@ -0,0 +1,132 @@
+//! When a battle is over. By default it ends only once no two hostile
+//! factions have units left; headless sims add a turn limit, morale,
+//! an objective to hold or a damage threshold so stalemates end too.
+
+use crate::targeting::Hostility;
+use crate::BattleState;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A circle a faction wins by holding, alone, for `turns` turns in a row.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ObjectiveZone {
+    pub center: (f32, f32),
+    pub radius: f32,
+    pub turns: u32,
+}
+
+/// Extra ways for a battle to end; every field off by default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct EndConditions {
+    #[serde(default)]
+    pub max_turns: Option<u32>,
+    /// A faction routs once its surviving hit points fall below this share
+    /// of its full strength; the battle ends when no hostile pair is left
+    /// among the factions still fighting.
+    #[serde(default)]
+    pub morale_break: Option<f32>,
+    #[serde(default)]
+    pub objective: Option<ObjectiveZone>,
+    /// The battle stops once this share of all hit points on the field
+    /// has been lost; the least-damaged faction wins.
+    #[serde(default)]
+    pub damage_threshold: Option<f32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EndReason {
+    /// No two hostile factions have units left.
+    Annihilation,
+    TurnLimit,
+    MoraleCollapse,
+    ObjectiveHeld,
+    DamageThreshold,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BattleEnd {
+    pub reason: EndReason,
+    pub winner: Option<u8>,
+}
+
+/// Surviving and full hit points per faction.
+fn strength(state: &BattleState) -> BTreeMap<u8, (f32, f32)> {
+    let mut strength: BTreeMap<u8, (f32, f32)> = BTreeMap::new();
+    for unit in &state.units {
+        let entry = strength.entry(unit.faction_idx).or_default();
+        entry.1 += unit.max_hp;
+        if unit.is_alive {
+            entry.0 += unit.hp;
+        }
+    }
+    strength
+}
+
+fn any_hostile(factions: &[u8], hostility: &Hostility) -> bool {
+    factions.iter().any(|&a| factions.iter().any(|&b| hostility.is_hostile(a, b)))
+}
+
+fn sole(factions: &[u8]) -> Option<u8> {
+    match factions {
+        [only] => Some(*only),
+        _ => None,
+    }
+}
+
+impl EndConditions {
+    /// Checks the battle after a step. `hold` carries the objective's
+    /// holder and how many turns it has held across calls.
+    pub fn evaluate(&self, state: &BattleState, hostility: &Hostility, hold: &mut Option<(u8, u32)>) -> Option<BattleEnd> {
+        let strength = strength(state);
+        let living: Vec<u8> = strength.iter().filter(|(_, s)| s.0 > 0.0).map(|(&f, _)| f).collect();
+        if !any_hostile(&living, hostility) {
+            return Some(BattleEnd { reason: EndReason::Annihilation, winner: sole(&living) });
+        }
+
+        if let Some(threshold) = self.morale_break {
+            let fighting: Vec<u8> = living.iter().copied().filter(|f| strength[f].0 >= threshold * strength[f].1).collect();
+            if !any_hostile(&fighting, hostility) {
+                return Some(BattleEnd { reason: EndReason::MoraleCollapse, winner: sole(&fighting) });
+            }
+        }
+
+        if let Some(zone) = self.objective {
+            let mut inside: Vec<u8> = state
+                .units
+                .iter()
+                .filter(|u| {
+                    let (dx, dy) = (u.position.0 - zone.center.0, u.position.1 - zone.center.1);
+                    u.is_alive && dx * dx + dy * dy <= zone.radius * zone.radius
+                })
+                .map(|u| u.faction_idx)
+                .collect();
+            inside.sort_unstable();
+            inside.dedup();
+            *hold = match (sole(&inside), *hold) {
+                (Some(f), Some((held, turns))) if f == held => Some((f, turns + 1)),
+                (Some(f), _) => Some((f, 1)),
+                (None, _) => None,
+            };
+            if let Some((holder, turns)) = *hold
+                && turns >= zone.turns
+            {
+                return Some(BattleEnd { reason: EndReason::ObjectiveHeld, winner: Some(holder) });
+            }
+        }
+
+        if let Some(threshold) = self.damage_threshold {
+            let (left, full) = strength.values().fold((0.0, 0.0), |acc, s| (acc.0 + s.0, acc.1 + s.1));
+            if full > 0.0 && full - left >= threshold * full {
+                let share = |f: &u8| if strength[f].1 > 0.0 { strength[f].0 / strength[f].1 } else { 0.0 };
+                let best = living.iter().map(share).fold(f32::MIN, f32::max);
+                let leaders: Vec<u8> = living.iter().copied().filter(|f| share(f) == best).collect();
+                return Some(BattleEnd { reason: EndReason::DamageThreshold, winner: sole(&leaders) });
+            }
+        }
+
+        match self.max_turns {
+            Some(max) if state.turn >= max => Some(BattleEnd { reason: EndReason::TurnLimit, winner: None }),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,382 @@
+use crate::types::{DiplomacySnapshot, OpinionModifier, Relation, Treaty, TreatyKind, Tribute, War, SCORE_LIMIT};
+use std::collections::BTreeMap;
+use void_reckoning_combat::resolve::BattleOutcome;
+use void_reckoning_combat::targeting::Hostility;
+use void_reckoning_economy::trade::TradeRouteManager;
+use void_reckoning_economy::types::{EconomicNode, ResourceState};
+use void_reckoning_shared::desync;
+use void_reckoning_shared::ids::FactionId;
+use void_reckoning_shared::scope::ContextStack;
+use void_reckoning_shared::simtime::SimTime;
+use void_reckoning_shared::{CorrelationContext, Event, EventLog, EventSeverity};
+
+/// War score for wiping out the other side outright; partial losses
+/// score proportionally.
+const BATTLE_SCORE: f32 = 20.0;
+/// Extra war score for being the side left standing.
+const VICTORY_BONUS: i32 = 5;
+const TREATY_BROKEN_OPINION: i32 = -30;
+const WAR_DECLARED_OPINION: i32 = -50;
+
+pub struct DiplomacyEngine {
+    state: DiplomacySnapshot,
+    pub event_log: Option<EventLog>,
+    /// Correlation contexts; nested operations push scopes onto this.
+    pub contexts: ContextStack,
+}
+
+impl Default for DiplomacyEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DiplomacyEngine {
+    pub fn new() -> Self {
+        Self::from_snapshot(DiplomacySnapshot::default())
+    }
+
+    pub fn from_snapshot(state: DiplomacySnapshot) -> Self {
+        Self { state, event_log: None, contexts: ContextStack::default() }
+    }
+
+    pub fn snapshot(&self) -> &DiplomacySnapshot {
+        &self.state
+    }
+
+    /// Replaces the diplomatic state, keeping the logging setup.
+    pub fn restore(&mut self, state: DiplomacySnapshot) {
+        self.state = state;
+    }
+
+    /// FNV-1a of the snapshot's JSON encoding.
+    pub fn state_hash(&self) -> u64 {
+        desync::hash_json(&self.state)
+    }
+
+    pub fn set_event_log(&mut self, log: EventLog) {
+        self.event_log = Some(log);
+    }
+
+    pub fn set_correlation_context(&mut self, context: CorrelationContext) {
+        self.contexts.set_root(context);
+    }
+
+    pub fn turn(&self) -> u64 {
+        self.state.turn
+    }
+
+    pub fn treaties(&self) -> &[Treaty] {
+        &self.state.treaties
+    }
+
+    pub fn wars(&self) -> &[War] {
+        &self.state.wars
+    }
+
+    pub fn has_treaty(&self, kind: TreatyKind, a: &str, b: &str) -> bool {
+        self.state.treaties.iter().any(|t| t.binds(kind, a, b))
+    }
+
+    /// Signs a treaty (a no-op if it already exists). Only embargoes can be
+    /// imposed on a faction at war with `from`, and a vassal has one overlord.
+    pub fn sign_treaty(&mut self, kind: TreatyKind, from: &str, to: &str) -> Result<(), String> {
+        if from == to {
+            return Err(format!("A faction can't sign a {} with itself", kind.as_str()));
+        }
+        if self.has_treaty(kind, from, to) {
+            return Ok(());
+        }
+        if kind.is_peaceful() && self.at_war(from, to) {
+            return Err(format!("{} and {} are at war; make peace before signing a {}", from, to, kind.as_str()));
+        }
+        if kind == TreatyKind::Vassalage {
+            if let Some(overlord) = self.overlord_of(to) {
+                return Err(format!("{} is already a vassal of {}", to, overlord));
+            }
+            if self.has_treaty(TreatyKind::Vassalage, to, from) {
+                return Err(format!("{} is a vassal of {}", from, to));
+            }
+        }
+        self.state.treaties.push(Treaty {
+            kind,
+            from: from.into(),
+            to: to.into(),
+            since_turn: self.state.turn,
+        });
+        self.emit(EventSeverity::Info, format!("{} signed a {} with {}", from, kind.as_str(), to), from, to);
+        Ok(())
+    }
+
+    /// Ends a treaty; returns false if there was none. Breaking a peaceful
+    /// treaty sours `to`'s opinion of `from`.
+    pub fn break_treaty(&mut self, kind: TreatyKind, from: &str, to: &str) -> bool {
+        let before = self.state.treaties.len();
+        self.state.treaties.retain(|t| !t.binds(kind, from, to));
+        let broken = self.state.treaties.len() != before;
+        if broken && kind.is_peaceful() {
+            self.add_modifier(OpinionModifier {
+                name: format!("broke_{}", kind.as_str()),
+                from: to.into(),
+                to: from.into(),
+                value: TREATY_BROKEN_OPINION,
+                decay: 1,
+            });
+            self.emit(EventSeverity::Warning, format!("{} broke its {} with {}", from, kind.as_str(), to), from, to);
+        }
+        broken
+    }
+
+    pub fn overlord_of(&self, vassal: &str) -> Option<&str> {
+        self.state
+            .treaties
+            .iter()
+            .find(|t| t.kind == TreatyKind::Vassalage && t.to == vassal)
+            .map(|t| t.from.as_str())
+    }
+
+    pub fn add_modifier(&mut self, modifier: OpinionModifier) {
+        self.state.modifiers.push(modifier);
+    }
+
+    /// `from`'s opinion of `to`: the sum of its modifiers, clamped to ±100.
+    pub fn opinion(&self, from: &str, to: &str) -> i32 {
+        self.state
+            .modifiers
+            .iter()
+            .filter(|m| m.from == from && m.to == to)
+            .map(|m| m.value)
+            .sum::<i32>()
+            .clamp(-SCORE_LIMIT, SCORE_LIMIT)
+    }
+
+    pub fn at_war(&self, a: &str, b: &str) -> bool {
+        self.state.wars.iter().any(|w| w.between(a, b))
+    }
+
+    /// `faction`'s war score against `enemy`; 0 if they're at peace.
+    pub fn war_score(&self, faction: &str, enemy: &str) -> i32 {
+        self.state.wars.iter().find(|w| w.between(faction, enemy)).map_or(0, |w| w.score_for(faction))
+    }
+
+    /// Starts a war, tearing up every peaceful treaty between the two.
+    pub fn declare_war(&mut self, attacker: &str, defender: &str) -> Result<(), String> {
+        if attacker == defender {
+            return Err("A faction can't declare war on itself".to_string());
+        }
+        if self.at_war(attacker, defender) {
+            return Err(format!("{} and {} are already at war", attacker, defender));
+        }
+        self.state.treaties.retain(|t| !(t.kind.is_peaceful() && t.involves(attacker, defender)));
+        self.add_modifier(OpinionModifier {
+            name: "war_declared".to_string(),
+            from: defender.into(),
+            to: attacker.into(),
+            value: WAR_DECLARED_OPINION,
+            decay: 2,
+        });
+        self.state.wars.push(War {
+            attacker: attacker.into(),
+            defender: defender.into(),
+            since_turn: self.state.turn,
+            score: 0,
+        });
+        self.emit(EventSeverity::Warning, format!("{} declared war on {}", attacker, defender), attacker, defender);
+        Ok(())
+    }
+
+    /// Ends the war between `a` and `b`, returning it with its final score.
+    pub fn make_peace(&mut self, a: &str, b: &str) -> Option<War> {
+        let index = self.state.wars.iter().position(|w| w.between(a, b))?;
+        let war = self.state.wars.remove(index);
+        self.emit(
+            EventSeverity::Info,
+            format!("{} and {} made peace (war score {})", war.attacker, war.defender, war.score),
+            &war.attacker,
+            &war.defender,
+        );
+        Some(war)
+    }
+
+    pub fn relation(&self, a: &str, b: &str) -> Relation {
+        Relation {
+            a: a.into(),
+            b: b.into(),
+            opinion_of_b: self.opinion(a, b),
+            opinion_of_a: self.opinion(b, a),
+            treaties: self.state.treaties.iter().filter(|t| t.involves(a, b)).cloned().collect(),
+            at_war: self.at_war(a, b),
+            war_score: self.war_score(a, b),
+        }
+    }
+
+    /// Scores a battle for every war fought in it. `roster[i]` names the
+    /// faction behind combat faction index `i`. Each side gains
+    /// `BATTLE_SCORE` times the fraction of its enemy's units destroyed
+    /// beyond its own, plus `VICTORY_BONUS` for holding the field. Returns
+    /// the attacker-side score change per war touched.
+    pub fn record_battle(&mut self, outcome: &BattleOutcome, roster: &[FactionId]) -> Vec<(FactionId, FactionId, i32)> {
+        let mut losses: BTreeMap<&str, (usize, usize)> = BTreeMap::new();
+        for result in &outcome.factions {
+            if let Some(name) = roster.get(result.faction_idx as usize) {
+                let entry = losses.entry(name.as_str()).or_default();
+                entry.0 += result.units;
+                entry.1 += result.units - result.survivors;
+            }
+        }
+        let loss_ratio = |name: &str| losses.get(name).map(|&(units, lost)| lost as f32 / units.max(1) as f32);
+        let winner = outcome.winner.and_then(|idx| roster.get(idx as usize)).map(FactionId::as_str);
+
+        let mut changes = Vec::new();
+        for war in &mut self.state.wars {
+            let (Some(attacker_loss), Some(defender_loss)) = (loss_ratio(&war.attacker), loss_ratio(&war.defender)) else {
+                continue;
+            };
+            let mut delta = ((defender_loss - attacker_loss) * BATTLE_SCORE).round() as i32;
+            if winner == Some(war.attacker.as_str()) {
+                delta += VICTORY_BONUS;
+            } else if winner == Some(war.defender.as_str()) {
+                delta -= VICTORY_BONUS;
+            }
+            war.score = (war.score + delta).clamp(-SCORE_LIMIT, SCORE_LIMIT);
+            changes.push((war.attacker.clone(), war.defender.clone(), delta));
+        }
+        changes
+    }
+
+    /// Whether `a` and `b` fight when they meet: different factions with
+    /// no peaceful treaty between them.
+    pub fn hostile(&self, a: &str, b: &str) -> bool {
+        a != b && !self.state.treaties.iter().any(|t| t.kind.is_peaceful() && t.involves(a, b))
+    }
+
+    /// Combat alliances for a battle whose faction index `i` is
+    /// `roster[i]`: allies, NAP partners, overlords and vassals (and
+    /// indices naming the same faction) don't fight each other.
+    pub fn hostility(&self, roster: &[FactionId]) -> Hostility {
+        let mut hostility = Hostility::default();
+        for (i, a) in roster.iter().enumerate() {
+            for (j, b) in roster.iter().enumerate().skip(i + 1) {
+                let (Ok(i), Ok(j)) = (u8::try_from(i), u8::try_from(j)) else { continue };
+                if !self.hostile(a, b) {
+                    hostility.ally(i, j);
+                }
+            }
+        }
+        hostility
+    }
+
+    /// Whether trade between the two is cut: an embargo either way, or war.
+    pub fn trade_blocked(&self, a: &str, b: &str) -> bool {
+        a != b
+            && (self.at_war(a, b)
+                || self.state.treaties.iter().any(|t| t.kind == TreatyKind::Embargo && t.involves(a, b)))
+    }
+
+    /// Severs routes between systems whose owners (per `nodes`) can't trade.
+    /// Run after `calculate_efficiencies`; returns the routes severed.
+    pub fn apply_embargoes(&self, trade: &mut TradeRouteManager, nodes: &[EconomicNode]) -> usize {
+        let owners: BTreeMap<&str, &str> = nodes.iter().map(|n| (n.id.as_str(), n.owner_faction.as_str())).collect();
+        trade.block_routes(|route| match (owners.get(route.from.as_str()), owners.get(route.to.as_str())) {
+            (Some(a), Some(b)) => self.trade_blocked(a, b),
+            _ => false,
+        })
+    }
+
+    /// What each vassal owes its overlord: `rate_scaled` (fixed-point) of
+    /// every positive part of its net income.
+    pub fn tribute(&self, net_income: &BTreeMap<String, ResourceState>, rate_scaled: i128) -> Vec<Tribute> {
+        self.state
+            .treaties
+            .iter()
+            .filter(|t| t.kind == TreatyKind::Vassalage)
+            .filter_map(|t| {
+                let net = net_income.get(t.to.as_str())?;
+                let mut amount = ResourceState {
+                    credits: net.credits.max(0),
+                    minerals: net.minerals.max(0),
+                    energy: net.energy.max(0),
+                    research: net.research.max(0),
+                };
+                amount.multiply_fixed(rate_scaled);
+                Some(Tribute { vassal: t.to.clone(), overlord: t.from.clone(), amount })
+            })
+            .collect()
+    }
+
+    /// Moves to the next turn, decaying opinion modifiers toward zero.
+    pub fn advance_turn(&mut self) {
+        self.state.turn += 1;
+        for modifier in &mut self.state.modifiers {
+            let step = modifier.decay.min(modifier.value.abs());
+            modifier.value -= step * modifier.value.signum();
+        }
+        self.state.modifiers.retain(|m| m.value != 0);
+    }
+
+    fn emit(&self, severity: EventSeverity, message: String, from: &str, to: &str) {
+        if let Some(log) = &self.event_log {
+            let evt = Event::new(severity, "Diplomacy".to_string(), message, self.contexts.current().child(), None)
+                .with_field("from", from)
+                .with_field("to", to)
+                .at(SimTime::at_turn(Some(self.state.turn)));
+            log.add(evt);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use void_reckoning_combat::resolve::FactionResult;
+
+    fn names(roster: &[&str]) -> Vec<FactionId> {
+        roster.iter().map(|&s| s.into()).collect()
+    }
+
+    #[test]
+    fn treaties_wars_and_decay() {
+        let mut diplomacy = DiplomacyEngine::new();
+        diplomacy.sign_treaty(TreatyKind::Alliance, "Imperium", "Tau").unwrap();
+        diplomacy.sign_treaty(TreatyKind::Vassalage, "Imperium", "Kroot").unwrap();
+        assert!(diplomacy.sign_treaty(TreatyKind::Vassalage, "Tau", "Kroot").is_err());
+
+        let hostility = diplomacy.hostility(&names(&["Imperium", "Tau", "Orks", "Kroot"]));
+        assert!(!hostility.is_hostile(0, 1) && !hostility.is_hostile(0, 3));
+        assert!(hostility.is_hostile(1, 2) && hostility.is_hostile(1, 3));
+
+        diplomacy.declare_war("Tau", "Imperium").unwrap();
+        assert!(!diplomacy.has_treaty(TreatyKind::Alliance, "Imperium", "Tau"));
+        assert_eq!(diplomacy.opinion("Imperium", "Tau"), -50);
+        assert!(diplomacy.trade_blocked("Imperium", "Tau"));
+
+        let outcome = BattleOutcome {
+            name: "b".to_string(),
+            turns: 3,
+            winner: Some(0),
+            timed_out: false,
+            survivors: vec![],
+            casualties: vec![],
+            factions: vec![
+                FactionResult { faction_idx: 0, units: 4, survivors: 1, hp_remaining: 0.0 },
+                FactionResult { faction_idx: 1, units: 4, survivors: 0, hp_remaining: 0.0 },
+            ],
+            reason: None,
+        };
+        // Faction 0 lost 3/4 against the enemy's 4/4 and held the field: 20 * 0.25 + 5.
+        diplomacy.record_battle(&outcome, &names(&["Tau", "Imperium"]));
+        assert_eq!(diplomacy.war_score("Tau", "Imperium"), 10);
+        assert_eq!(diplomacy.war_score("Imperium", "Tau"), -10);
+        diplomacy.record_battle(&outcome, &names(&["Imperium", "Tau"]));
+        assert_eq!(diplomacy.war_score("Imperium", "Tau"), 0);
+
+        for _ in 0..24 {
+            diplomacy.advance_turn();
+        }
+        assert_eq!(diplomacy.opinion("Imperium", "Tau"), -2);
+        diplomacy.advance_turn();
+        assert_eq!(diplomacy.opinion("Imperium", "Tau"), 0);
+        assert_eq!(diplomacy.make_peace("Imperium", "Tau").map(|w| w.attacker), Some("Tau".into()));
+        assert!(!diplomacy.at_war("Tau", "Imperium"));
+    }
+}
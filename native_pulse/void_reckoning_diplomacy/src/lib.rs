@@ -0,0 +1,13 @@
+//! Relations between factions: treaties, opinion and wars. Feeds economy
+//! (embargoes sever trade, vassals pay tribute) and combat (allies don't
+//! fight each other).
+
+pub mod types;
+pub mod engine;
+
+pub use engine::DiplomacyEngine;
+pub use types::{DiplomacySnapshot, OpinionModifier, Relation, Treaty, TreatyKind, Tribute, War};
+
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Version of the `DiplomacySnapshot` layout; bumped on incompatible changes.
+pub const SCHEMA_VERSION: u32 = 1;
@@ -0,0 +1,133 @@
+use serde::{Deserialize, Serialize};
+use void_reckoning_economy::types::ResourceState;
+use void_reckoning_shared::ids::FactionId;
+
+/// Opinion and war score are clamped to this magnitude.
+pub const SCORE_LIMIT: i32 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum TreatyKind {
+    NonAggression,
+    Alliance,
+    /// `from` is the overlord, `to` the vassal.
+    Vassalage,
+    /// `from` refuses trade with `to`.
+    Embargo,
+}
+
+impl TreatyKind {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "nap" | "non_aggression" | "nonaggression" => Some(TreatyKind::NonAggression),
+            "alliance" => Some(TreatyKind::Alliance),
+            "vassalage" => Some(TreatyKind::Vassalage),
+            "embargo" => Some(TreatyKind::Embargo),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TreatyKind::NonAggression => "non_aggression",
+            TreatyKind::Alliance => "alliance",
+            TreatyKind::Vassalage => "vassalage",
+            TreatyKind::Embargo => "embargo",
+        }
+    }
+
+    /// Whether `from` and `to` play different roles.
+    pub fn is_directed(&self) -> bool {
+        matches!(self, TreatyKind::Vassalage | TreatyKind::Embargo)
+    }
+
+    /// Whether the parties stay out of each other's way in battle.
+    pub fn is_peaceful(&self) -> bool {
+        !matches!(self, TreatyKind::Embargo)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Treaty {
+    pub kind: TreatyKind,
+    pub from: FactionId,
+    pub to: FactionId,
+    pub since_turn: u64,
+}
+
+impl Treaty {
+    /// Whether this treaty is of `kind` between `a` and `b` (in that order
+    /// for directed kinds).
+    pub fn binds(&self, kind: TreatyKind, a: &str, b: &str) -> bool {
+        self.kind == kind
+            && ((self.from == a && self.to == b) || (!kind.is_directed() && self.from == b && self.to == a))
+    }
+
+    pub fn involves(&self, a: &str, b: &str) -> bool {
+        (self.from == a && self.to == b) || (self.from == b && self.to == a)
+    }
+}
+
+/// `from`'s opinion of `to` shifts by `value`, which moves `decay` points
+/// toward zero each turn (0 = permanent) and is dropped on reaching it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpinionModifier {
+    pub name: String,
+    pub from: FactionId,
+    pub to: FactionId,
+    pub value: i32,
+    #[serde(default)]
+    pub decay: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct War {
+    pub attacker: FactionId,
+    pub defender: FactionId,
+    pub since_turn: u64,
+    /// Positive favours the attacker.
+    pub score: i32,
+}
+
+impl War {
+    pub fn between(&self, a: &str, b: &str) -> bool {
+        (self.attacker == a && self.defender == b) || (self.attacker == b && self.defender == a)
+    }
+
+    /// Score from `faction`'s side.
+    pub fn score_for(&self, faction: &str) -> i32 {
+        if self.attacker == faction {
+            self.score
+        } else {
+            -self.score
+        }
+    }
+}
+
+/// Everything between two factions, as seen from `a`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Relation {
+    pub a: FactionId,
+    pub b: FactionId,
+    pub opinion_of_b: i32,
+    pub opinion_of_a: i32,
+    pub treaties: Vec<Treaty>,
+    pub at_war: bool,
+    /// `a`'s war score; 0 at peace.
+    pub war_score: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tribute {
+    pub vassal: FactionId,
+    pub overlord: FactionId,
+    pub amount: ResourceState,
+}
+
+/// Serializable copy of a `DiplomacyEngine`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DiplomacySnapshot {
+    pub turn: u64,
+    pub treaties: Vec<Treaty>,
+    pub modifiers: Vec<OpinionModifier>,
+    pub wars: Vec<War>,
+}
@@ -1,45 +1,188 @@
-use crate::types::{EconomicNode, EconomicReport, GlobalEconomicRules, NodeType, ResourceState, SCALE_FACTOR};
-use std::collections::HashMap;
+use crate::types::{EconomicNode, EconomicReport, GlobalEconomicRules, MemoryStats, NodeType, ResourceState, SCALE_FACTOR};
+use std::collections::{BTreeMap, BTreeSet};
+#[cfg(feature = "observability")]
+use std::sync::atomic::{AtomicU64, Ordering};
 
+#[cfg(feature = "observability")]
 use void_reckoning_shared::{Event, EventLog, EventSeverity, CorrelationContext};
+#[cfg(feature = "observability")]
+use void_reckoning_shared::scope::ContextStack;
+#[cfg(feature = "observability")]
+use void_reckoning_shared::simtime::{SimClock, SimTime};
+#[cfg(feature = "observability")]
+use void_reckoning_shared::span::Span;
+
+/// Everything the `observability` feature adds to the engine.
+#[cfg(feature = "observability")]
+#[derive(Default)]
+pub struct Observability {
+    pub event_log: Option<EventLog>,
+    /// Correlation contexts; nested operations push scopes onto this.
+    pub contexts: ContextStack,
+    /// Campaign clock whose turn and tick are stamped on emitted events.
+    clock: Option<SimClock>,
+    /// Emit a `Memory` event every this many `process_all` calls (None = never).
+    pub memory_event_interval: Option<u64>,
+    process_all_calls: AtomicU64,
+}
 
 pub struct IncomeEngine {
     nodes: Vec<EconomicNode>,
     rules: GlobalEconomicRules,
-    pub event_log: Option<EventLog>,
-    pub current_context: CorrelationContext,
+    #[cfg(feature = "observability")]
+    pub obs: Observability,
 }
 
 impl IncomeEngine {
     pub fn new(rules: GlobalEconomicRules) -> Self {
-        Self { 
-            nodes: Vec::new(), 
-            rules, 
-            event_log: None,
-            current_context: CorrelationContext::new(),
+        Self {
+            nodes: Vec::new(),
+            rules,
+            #[cfg(feature = "observability")]
+            obs: Observability::default(),
         }
     }
     
+    #[cfg(feature = "observability")]
     pub fn set_event_log(&mut self, log: EventLog) {
-        self.event_log = Some(log);
+        self.obs.event_log = Some(log);
     }
 
+    #[cfg(feature = "observability")]
     pub fn set_correlation_context(&mut self, context: CorrelationContext) {
-        self.current_context = context;
+        self.obs.contexts.set_root(context);
+    }
+
+    /// Reads the time from the campaign's clock (shared with the other
+    /// engines), or from none with None.
+    #[cfg(feature = "observability")]
+    pub fn set_clock(&mut self, clock: Option<SimClock>) {
+        self.obs.clock = clock;
+    }
+
+    #[cfg(feature = "observability")]
+    pub fn clock(&self) -> Option<&SimClock> {
+        self.obs.clock.as_ref()
+    }
+
+    /// Moves the clock to `turn` (and every engine sharing it), starting
+    /// one of the economy's own if it has none. None detaches the clock.
+    pub fn set_campaign_turn(&mut self, turn: Option<u64>) {
+        #[cfg(feature = "observability")]
+        match (turn, &self.obs.clock) {
+            (Some(turn), Some(clock)) => clock.set_turn(turn),
+            (Some(turn), None) => self.obs.clock = Some(SimClock::at_turn(turn)),
+            (None, _) => self.obs.clock = None,
+        }
+        #[cfg(not(feature = "observability"))]
+        let _ = turn;
+    }
+
+    pub fn campaign_turn(&self) -> Option<u64> {
+        #[cfg(feature = "observability")]
+        {
+            self.obs.clock.as_ref().map(SimClock::turn)
+        }
+        #[cfg(not(feature = "observability"))]
+        {
+            None
+        }
+    }
+
+    #[cfg(feature = "observability")]
+    fn sim_time(&self) -> SimTime {
+        self.obs.clock.as_ref().map(SimClock::now).unwrap_or_default()
+    }
+
+    #[cfg(feature = "observability")]
+    pub fn set_memory_event_interval(&mut self, calls: Option<u64>) {
+        self.obs.memory_event_interval = calls.filter(|&n| n > 0);
+    }
+
+    pub fn memory_stats(&self) -> MemoryStats {
+        MemoryStats {
+            nodes: self.nodes.len(),
+            modifiers: self.nodes.iter().map(|n| n.modifiers.len()).sum(),
+            approx_bytes: std::mem::size_of::<Self>()
+                + (self.nodes.capacity() - self.nodes.len()) * std::mem::size_of::<EconomicNode>()
+                + self.nodes.iter().map(EconomicNode::footprint).sum::<usize>(),
+            ..MemoryStats::default()
+        }
     }
 
     pub fn add_node(&mut self, node: EconomicNode) {
         self.nodes.push(node);
     }
 
+    pub fn node_mut(&mut self, id: &str) -> Option<&mut EconomicNode> {
+        self.nodes.iter_mut().find(|n| n.id == id)
+    }
+
+    pub fn remove_node(&mut self, id: &str) -> Option<EconomicNode> {
+        let index = self.nodes.iter().position(|n| n.id == id)?;
+        Some(self.nodes.remove(index))
+    }
+
+    /// Replaces every node, keeping the rules and logging setup.
+    pub fn replace_nodes(&mut self, nodes: Vec<EconomicNode>) {
+        self.nodes = nodes;
+    }
+
     pub fn set_rules(&mut self, rules: GlobalEconomicRules) {
         self.rules = rules;
     }
 
+    pub fn nodes(&self) -> &[EconomicNode] {
+        &self.nodes
+    }
+
+    pub fn rules(&self) -> &GlobalEconomicRules {
+        &self.rules
+    }
+
+    /// A node's income and upkeep this turn, before the faction-wide navy
+    /// penalty.
+    pub fn node_yield(&self, node: &EconomicNode) -> (ResourceState, ResourceState) {
+        // Apply node efficiency & Global Rules
+        let mut node_income = node.base_income;
+        let mut node_upkeep = node.base_upkeep;
+
+        node_income.multiply_fixed(node.efficiency_scaled);
+
+        // Specialized Discounts
+        if node.efficiency_scaled < SCALE_FACTOR {
+            if node.node_type == NodeType::Fleet {
+                // Efficiency < 1.0 on Fleet implies "In Orbit" (Discount)
+                node_upkeep.multiply_fixed(self.rules.orbit_discount_scaled);
+            } else if node.node_type == NodeType::Army {
+                // Efficiency < 1.0 on Army implies "In Garrison" (Discount)
+                node_upkeep.multiply_fixed(self.rules.garrison_discount_scaled);
+            }
+        }
+
+        // Apply Global Fleet Upkeep Scalar
+        if node.node_type == NodeType::Fleet {
+            node_upkeep.multiply_fixed(self.rules.fleet_upkeep_scalar_scaled);
+        }
+
+        // Apply modifiers
+        for modifier in &node.modifiers {
+            node_income.multiply_fixed(modifier.multiplier_scaled);
+            node_income.add(&modifier.flat_bonus);
+        }
+        (node_income, node_upkeep)
+    }
+
     pub fn process_faction(&self, faction_name: &str) -> EconomicReport {
+        #[cfg(feature = "observability")]
+        let sim_time = self.sim_time();
+        #[cfg(feature = "observability")]
+        let span = Span::start("economy.process_faction", &self.obs.contexts.current()).at(sim_time);
+        #[cfg(feature = "observability")]
+        let _scope = self.obs.contexts.enter_context(span.context.clone());
         let mut total_income = ResourceState::default();
         let mut total_upkeep = ResourceState::default();
-        let mut income_by_category: HashMap<String, ResourceState> = HashMap::new();
+        let mut income_by_category: BTreeMap<String, ResourceState> = BTreeMap::new();
         let mut active_nodes = 0;
         let mut planet_count = 0;
         let mut fleet_count = 0;
@@ -59,33 +202,7 @@ impl IncomeEngine {
                     fleet_count += 1;
                 }
 
-                // Apply node efficiency & Global Rules
-                let mut node_income = node.base_income;
-                let mut node_upkeep = node.base_upkeep;
-
-                node_income.multiply_fixed(node.efficiency_scaled);
-
-                // Specialized Discounts
-                if node.efficiency_scaled < SCALE_FACTOR {
-                    if node.node_type == NodeType::Fleet {
-                        // Efficiency < 1.0 on Fleet implies "In Orbit" (Discount)
-                        node_upkeep.multiply_fixed(self.rules.orbit_discount_scaled);
-                    } else if node.node_type == NodeType::Army {
-                        // Efficiency < 1.0 on Army implies "In Garrison" (Discount)
-                        node_upkeep.multiply_fixed(self.rules.garrison_discount_scaled);
-                    }
-                }
-
-                // Apply Global Fleet Upkeep Scalar
-                if node.node_type == NodeType::Fleet {
-                    node_upkeep.multiply_fixed(self.rules.fleet_upkeep_scalar_scaled);
-                }
-
-                // Apply modifiers
-                for modifier in &node.modifiers {
-                    node_income.multiply_fixed(modifier.multiplier_scaled);
-                    node_income.add(&modifier.flat_bonus);
-                }
+                let (node_income, node_upkeep) = self.node_yield(node);
 
                 total_income.add(&node_income);
                 total_upkeep.add(&node_upkeep);
@@ -108,19 +225,31 @@ impl IncomeEngine {
         let mut net_profit = total_income;
         net_profit.subtract(&total_upkeep);
 
+        #[cfg(feature = "observability")]
         if net_profit.credits < 0 {
-            if let Some(log) = &self.event_log {
+            if let Some(log) = &self.obs.event_log {
                 let evt = Event::new(
                     EventSeverity::Warning,
                     "Economy".to_string(),
                     format!("Faction {} is insolvent! Deficit: {}", faction_name, net_profit.credits),
-                    self.current_context.child(),
+                    span.context.child(),
                     None
-                );
+                )
+                .with_field("faction", faction_name)
+                .with_field("deficit", net_profit.to_floats().0)
+                .at(sim_time);
                 log.add(evt);
             }
         }
 
+        #[cfg(feature = "observability")]
+        if let Some(log) = &self.obs.event_log {
+            log.end_span(
+                span.with_attribute("faction", faction_name)
+                    .with_attribute("active_nodes", active_nodes)
+            );
+        }
+
         EconomicReport {
             faction_name: faction_name.to_string(),
             total_income,
@@ -132,16 +261,40 @@ impl IncomeEngine {
         }
     }
 
-    pub fn process_all(&self) -> HashMap<String, EconomicReport> {
-        let mut faction_names = std::collections::HashSet::new();
-        for node in &self.nodes {
-            faction_names.insert(node.owner_faction.clone());
-        }
+    /// Reports for every faction owning a node. Factions are processed (and
+    /// their events emitted) in name order, so runs are reproducible.
+    pub fn process_all(&self) -> BTreeMap<String, EconomicReport> {
+        let faction_names: BTreeSet<&str> = self.nodes.iter().map(|n| n.owner_faction.as_str()).collect();
 
-        let mut reports = HashMap::new();
+        let mut reports = BTreeMap::new();
         for faction in faction_names {
-            reports.insert(faction.clone(), self.process_faction(&faction));
+            reports.insert(faction.to_string(), self.process_faction(faction));
         }
+
+        #[cfg(feature = "observability")]
+        self.emit_memory_event();
         reports
     }
+
+    #[cfg(feature = "observability")]
+    fn emit_memory_event(&self) {
+        let calls = self.obs.process_all_calls.fetch_add(1, Ordering::Relaxed) + 1;
+        let (Some(log), Some(every)) = (&self.obs.event_log, self.obs.memory_event_interval) else { return };
+        if !calls.is_multiple_of(every) {
+            return;
+        }
+        let stats = self.memory_stats();
+        let evt = Event::new(
+            EventSeverity::Debug,
+            "Memory".to_string(),
+            format!("Economy holds {} nodes, ~{} bytes", stats.nodes, stats.approx_bytes),
+            self.obs.contexts.current().child(),
+            None,
+        )
+        .with_field("nodes", stats.nodes)
+        .with_field("modifiers", stats.modifiers)
+        .with_field("approx_bytes", stats.approx_bytes)
+        .at(self.sim_time());
+        log.add(evt);
+    }
 }
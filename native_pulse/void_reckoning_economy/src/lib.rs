@@ -1,7 +1,23 @@
 pub mod types;
 pub mod engine;
 pub mod trade;
+pub mod lockstep;
+pub mod logistics;
 
 pub use types::*;
 pub use engine::*;
 pub use trade::*;
+
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Version of the `EconomicNode`/`GlobalEconomicRules`/`EconomicReport`
+/// layouts; bumped on incompatible changes.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Cargo features this build was compiled with.
+pub fn enabled_features() -> Vec<&'static str> {
+    let mut features = Vec::new();
+    if cfg!(feature = "observability") {
+        features.push("observability");
+    }
+    features
+}
@@ -0,0 +1,26 @@
+//! Lockstep support: hashes of economy state that are identical on every
+//! platform, so clients can confirm each turn that they still agree.
+
+use crate::engine::IncomeEngine;
+use crate::trade::TradeRouteManager;
+// All economy values are fixed-point integers or strings, so their JSON
+// encoding is canonical.
+use void_reckoning_hash::hash_json;
+
+impl IncomeEngine {
+    /// Hash of the rules and every node, in id order so the order nodes
+    /// were added in doesn't matter.
+    pub fn state_hash(&self) -> u64 {
+        let mut nodes: Vec<_> = self.nodes().iter().collect();
+        nodes.sort_by(|a, b| a.id.cmp(&b.id));
+        hash_json(&(self.rules(), nodes))
+    }
+}
+
+impl TradeRouteManager {
+    /// Hash of every route and its current efficiency, in route order
+    /// (income is split per route, so order is part of the state).
+    pub fn state_hash(&self) -> u64 {
+        hash_json(&self.routes())
+    }
+}
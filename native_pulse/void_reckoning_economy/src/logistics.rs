@@ -0,0 +1,394 @@
+//! Supply logistics: physical resources travel along lanes from the nodes
+//! that produce them to the nodes whose upkeep needs them, instead of
+//! landing in a faction-wide pool. Each turn a min-cost flow over the map
+//! routes every producer's surplus to consumers along the cheapest lanes
+//! (lane weight is the cost per unit, so hazards and blockades show up),
+//! within each lane's capacity. Upkeep nothing can reach is a shortage at
+//! that node.
+//!
+//! Nodes are placed by id: a node whose id is a system on the map sits in
+//! it. Others (fleets, armies) stay on the pooled books. A system holding
+//! another faction's node is closed to the flow.
+
+use crate::engine::IncomeEngine;
+use crate::types::{EconomicModifier, ResourceState, SCALE_FACTOR};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use void_reckoning_ids::SystemId;
+use void_reckoning_pathfinder::GraphTopology;
+
+/// Name of the modifier `apply_shortages` puts on undersupplied nodes.
+pub const SHORTAGE_MODIFIER: &str = "supply_shortage";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Resource {
+    Credits,
+    Minerals,
+    Energy,
+    Research,
+}
+
+impl Resource {
+    pub fn of(self, state: &ResourceState) -> i128 {
+        match self {
+            Resource::Credits => state.credits,
+            Resource::Minerals => state.minerals,
+            Resource::Energy => state.energy,
+            Resource::Research => state.research,
+        }
+    }
+
+    pub fn of_mut(self, state: &mut ResourceState) -> &mut i128 {
+        match self {
+            Resource::Credits => &mut state.credits,
+            Resource::Minerals => &mut state.minerals,
+            Resource::Energy => &mut state.energy,
+            Resource::Research => &mut state.research,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LaneCapacity {
+    pub from: String,
+    pub to: String,
+    /// Most of each resource the lane carries per turn (scaled).
+    pub capacity: i128,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogisticsRules {
+    /// Resources that travel; the rest stay pooled.
+    #[serde(default = "physical")]
+    pub resources: Vec<Resource>,
+    /// Capacity of lanes `capacities` doesn't list; None for no limit.
+    #[serde(default)]
+    pub default_capacity: Option<i128>,
+    #[serde(default)]
+    pub capacities: Vec<LaneCapacity>,
+}
+
+fn physical() -> Vec<Resource> {
+    vec![Resource::Minerals, Resource::Energy]
+}
+
+impl Default for LogisticsRules {
+    fn default() -> Self {
+        Self { resources: physical(), default_capacity: None, capacities: Vec::new() }
+    }
+}
+
+impl LogisticsRules {
+    fn capacity(&self, from: &str, to: &str) -> Option<i128> {
+        match self.capacities.iter().find(|c| c.from == from && c.to == to) {
+            Some(lane) => Some(lane.capacity),
+            None => self.default_capacity,
+        }
+    }
+}
+
+/// What one lane carried of one resource.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Flow {
+    pub resource: Resource,
+    pub from: String,
+    pub to: String,
+    pub amount: i128,
+}
+
+/// Upkeep at a node that neither its own output nor the lanes covered.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Shortage {
+    pub node: String,
+    pub resource: Resource,
+    /// The node's whole upkeep of the resource.
+    pub upkeep: i128,
+    pub missing: i128,
+}
+
+impl Shortage {
+    /// Share of the upkeep that was met, scaled by `SCALE_FACTOR`.
+    pub fn met_scaled(&self) -> i128 {
+        match self.upkeep {
+            0 => SCALE_FACTOR,
+            upkeep => (upkeep - self.missing) * SCALE_FACTOR / upkeep,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SupplyReport {
+    pub faction: String,
+    pub flows: Vec<Flow>,
+    pub shortages: Vec<Shortage>,
+    /// Upkeep met on the spot or over lanes.
+    pub delivered: ResourceState,
+    /// Upkeep nothing could reach; the sum of `shortages`.
+    pub missing: ResourceState,
+    /// Output left over after every reachable consumer was supplied.
+    pub surplus: ResourceState,
+    /// Units moved times the weight of the lanes they crossed.
+    pub cost: f64,
+}
+
+/// Routes `faction`'s travelling resources over `topology` for this turn.
+pub fn plan_supply(economy: &IncomeEngine, topology: &GraphTopology, rules: &LogisticsRules, faction: &str) -> SupplyReport {
+    let systems = topology.node_ids();
+    let index: HashMap<&str, usize> = systems.iter().enumerate().map(|(i, s)| (s.as_str(), i)).collect();
+    let closed: BTreeSet<&str> = economy
+        .nodes()
+        .iter()
+        .filter(|n| n.owner_faction != faction && index.contains_key(n.id.as_str()))
+        .map(|n| n.id.as_str())
+        .collect();
+    let placed: Vec<(usize, &str, ResourceState, ResourceState)> = economy
+        .nodes()
+        .iter()
+        .filter(|n| n.owner_faction == faction)
+        .filter_map(|n| {
+            let (income, upkeep) = economy.node_yield(n);
+            Some((*index.get(n.id.as_str())?, n.id.as_str(), income, upkeep))
+        })
+        .collect();
+    // Parallel edges are one lane, at the cheapest weight.
+    let mut lanes: Vec<(usize, usize, f32)> = Vec::new();
+    for (from, to, weight) in topology.edges() {
+        if !weight.is_finite() || closed.contains(from.as_str()) || closed.contains(to.as_str()) {
+            continue;
+        }
+        let (from, to) = (index[from.as_str()], index[to.as_str()]);
+        match lanes.iter_mut().find(|(f, t, _)| *f == from && *t == to) {
+            Some(lane) => lane.2 = lane.2.min(weight),
+            None => lanes.push((from, to, weight)),
+        }
+    }
+
+    let mut report = SupplyReport { faction: faction.to_string(), ..SupplyReport::default() };
+    for &resource in &rules.resources {
+        let (source, sink) = (systems.len(), systems.len() + 1);
+        let mut graph = FlowGraph::new(systems.len() + 2);
+        let lane_arcs: Vec<usize> = lanes
+            .iter()
+            .map(|&(from, to, weight)| {
+                let capacity = rules.capacity(&systems[from], &systems[to]).unwrap_or(i128::MAX);
+                graph.add(from, to, capacity.max(0), weight as f64)
+            })
+            .collect();
+        let mut demands: BTreeMap<usize, (i128, usize)> = BTreeMap::new();
+        for &(system, _, income, upkeep) in &placed {
+            let (made, used) = (resource.of(&income), resource.of(&upkeep));
+            *resource.of_mut(&mut report.delivered) += made.min(used).max(0);
+            if made > used {
+                graph.add(source, system, made - used, 0.0);
+                *resource.of_mut(&mut report.surplus) += made - used;
+            } else if used > made {
+                let arc = graph.add(system, sink, used - made, 0.0);
+                demands.insert(system, (used - made, arc));
+            }
+        }
+        report.cost += graph.solve(source, sink);
+
+        for (&(from, to, _), &arc) in lanes.iter().zip(&lane_arcs) {
+            let amount = graph.flow(arc);
+            if amount > 0 {
+                report.flows.push(Flow { resource, from: systems[from].clone(), to: systems[to].clone(), amount });
+            }
+        }
+        for &(system, node, _, upkeep) in &placed {
+            let Some(&(demand, arc)) = demands.get(&system) else { continue };
+            let received = graph.flow(arc);
+            *resource.of_mut(&mut report.delivered) += received;
+            *resource.of_mut(&mut report.surplus) -= received;
+            if received < demand {
+                let missing = demand - received;
+                *resource.of_mut(&mut report.missing) += missing;
+                report.shortages.push(Shortage { node: node.to_string(), resource, upkeep: resource.of(&upkeep), missing });
+            }
+        }
+    }
+    report
+}
+
+/// Cuts the output of every node short in `report` to the share of its
+/// upkeep that arrived (the worst share over its resources) until the next
+/// call, and lifts the cut from the faction's other nodes.
+pub fn apply_shortages(economy: &mut IncomeEngine, report: &SupplyReport) {
+    let mut met: BTreeMap<&str, i128> = BTreeMap::new();
+    for shortage in &report.shortages {
+        let share = met.entry(shortage.node.as_str()).or_insert(SCALE_FACTOR);
+        *share = (*share).min(shortage.met_scaled());
+    }
+    let nodes: Vec<SystemId> = economy.nodes().iter().filter(|n| n.owner_faction == report.faction).map(|n| n.id.clone()).collect();
+    for id in nodes {
+        let Some(node) = economy.node_mut(&id) else { continue };
+        node.modifiers.retain(|m| m.name != SHORTAGE_MODIFIER);
+        if let Some(&share) = met.get(id.as_str()) {
+            node.modifiers.push(EconomicModifier {
+                name: SHORTAGE_MODIFIER.to_string(),
+                multiplier_scaled: share,
+                flat_bonus: ResourceState::default(),
+            });
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct FlowArc {
+    to: usize,
+    capacity: i128,
+    cost: f64,
+}
+
+/// Residual graph for successive shortest paths; arc `i ^ 1` is arc `i`'s
+/// reverse.
+struct FlowGraph {
+    arcs: Vec<FlowArc>,
+    out: Vec<Vec<usize>>,
+}
+
+impl FlowGraph {
+    fn new(nodes: usize) -> Self {
+        Self { arcs: Vec::new(), out: vec![Vec::new(); nodes] }
+    }
+
+    fn add(&mut self, from: usize, to: usize, capacity: i128, cost: f64) -> usize {
+        let arc = self.arcs.len();
+        self.arcs.push(FlowArc { to, capacity, cost });
+        self.arcs.push(FlowArc { to: from, capacity: 0, cost: -cost });
+        self.out[from].push(arc);
+        self.out[to].push(arc + 1);
+        arc
+    }
+
+    /// What has been pushed along `arc`.
+    fn flow(&self, arc: usize) -> i128 {
+        self.arcs[arc ^ 1].capacity
+    }
+
+    /// Pushes as much as fits from `source` to `sink`, cheapest paths
+    /// first; returns the total cost.
+    fn solve(&mut self, source: usize, sink: usize) -> f64 {
+        let mut total = 0.0;
+        while let Some(path) = self.cheapest_path(source, sink) {
+            let push = path.iter().map(|&arc| self.arcs[arc].capacity).min().unwrap_or(0);
+            for &arc in &path {
+                self.arcs[arc].capacity -= push;
+                self.arcs[arc ^ 1].capacity += push;
+                total += self.arcs[arc].cost * push as f64;
+            }
+        }
+        total
+    }
+
+    /// Bellman-Ford over arcs with room left (reverse arcs cost negative);
+    /// the arcs from `source` to `sink`.
+    fn cheapest_path(&self, source: usize, sink: usize) -> Option<Vec<usize>> {
+        let mut distance = vec![f64::INFINITY; self.out.len()];
+        let mut via: Vec<Option<usize>> = vec![None; self.out.len()];
+        let mut queued = vec![false; self.out.len()];
+        let mut queue = VecDeque::from([source]);
+        distance[source] = 0.0;
+        while let Some(node) = queue.pop_front() {
+            queued[node] = false;
+            for &arc in &self.out[node] {
+                let FlowArc { to, capacity, cost } = self.arcs[arc];
+                // The tolerance keeps float round-off from cycling on ties.
+                if capacity > 0 && distance[node] + cost < distance[to] - 1e-9 {
+                    distance[to] = distance[node] + cost;
+                    via[to] = Some(arc);
+                    if !queued[to] {
+                        queued[to] = true;
+                        queue.push_back(to);
+                    }
+                }
+            }
+        }
+        via[sink]?;
+        let mut path = Vec::new();
+        let mut node = sink;
+        while let Some(arc) = via[node] {
+            path.push(arc);
+            node = self.arcs[arc ^ 1].to;
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{EconomicNode, GlobalEconomicRules, NodeType};
+
+    fn minerals(amount: i128) -> ResourceState {
+        ResourceState { minerals: amount * SCALE_FACTOR, ..ResourceState::default() }
+    }
+
+    fn economy(nodes: &[(&str, i128, i128)]) -> IncomeEngine {
+        let mut economy = IncomeEngine::new(GlobalEconomicRules::default());
+        for &(id, mined, used) in nodes {
+            economy.add_node(EconomicNode {
+                id: id.into(),
+                owner_faction: "Imperium".into(),
+                node_type: NodeType::Planet,
+                base_income: minerals(mined),
+                base_upkeep: minerals(used),
+                efficiency_scaled: SCALE_FACTOR,
+                modifiers: Vec::new(),
+            });
+        }
+        economy
+    }
+
+    /// Terra to Luna costs 2 through Mars, 5 direct.
+    fn galaxy() -> GraphTopology {
+        let mut topology = GraphTopology::new();
+        for (from, to, weight) in [("Terra", "Mars", 1.0), ("Mars", "Luna", 1.0), ("Terra", "Luna", 5.0)] {
+            topology.add_edge(from, to, weight);
+        }
+        topology
+    }
+
+    fn flows(report: &SupplyReport) -> Vec<(&str, &str, i128)> {
+        report.flows.iter().map(|f| (f.from.as_str(), f.to.as_str(), f.amount / SCALE_FACTOR)).collect()
+    }
+
+    #[test]
+    fn supply_takes_the_cheapest_route() {
+        let report = plan_supply(&economy(&[("Terra", 30, 0), ("Luna", 0, 30)]), &galaxy(), &LogisticsRules::default(), "Imperium");
+        assert_eq!(flows(&report), [("Terra", "Mars", 30), ("Mars", "Luna", 30)]);
+        assert_eq!(report.cost, (60 * SCALE_FACTOR) as f64);
+        assert!(report.shortages.is_empty());
+    }
+
+    #[test]
+    fn full_lanes_spill_onto_dearer_ones() {
+        let rules = LogisticsRules {
+            capacities: vec![LaneCapacity { from: "Terra".into(), to: "Mars".into(), capacity: 10 * SCALE_FACTOR }],
+            ..LogisticsRules::default()
+        };
+        let report = plan_supply(&economy(&[("Terra", 30, 0), ("Luna", 0, 30)]), &galaxy(), &rules, "Imperium");
+        assert_eq!(flows(&report), [("Terra", "Mars", 10), ("Mars", "Luna", 10), ("Terra", "Luna", 20)]);
+        assert_eq!(report.cost, (120 * SCALE_FACTOR) as f64);
+        assert_eq!(report.delivered, minerals(30));
+    }
+
+    #[test]
+    fn unreachable_upkeep_goes_short() {
+        let mut topology = galaxy();
+        topology.add_node("Pluto".to_string(), None);
+        let report = plan_supply(&economy(&[("Terra", 50, 0), ("Luna", 0, 30), ("Pluto", 0, 15)]), &topology, &LogisticsRules::default(), "Imperium");
+        assert_eq!(report.shortages, [Shortage { node: "Pluto".into(), resource: Resource::Minerals, upkeep: 15 * SCALE_FACTOR, missing: 15 * SCALE_FACTOR }]);
+        assert_eq!(report.shortages[0].met_scaled(), 0);
+        assert_eq!((report.delivered, report.missing, report.surplus), (minerals(30), minerals(15), minerals(20)));
+    }
+
+    #[test]
+    fn surplus_stays_where_it_was_made() {
+        let report = plan_supply(&economy(&[("Terra", 50, 0), ("Mars", 10, 0), ("Luna", 0, 30)]), &galaxy(), &LogisticsRules::default(), "Imperium");
+        // Mars is one lane closer, so all of its output ships first.
+        assert_eq!(flows(&report), [("Terra", "Mars", 20), ("Mars", "Luna", 30)]);
+        assert_eq!((report.delivered, report.surplus), (minerals(30), minerals(30)));
+        assert!(report.shortages.is_empty());
+    }
+}
@@ -1,7 +1,7 @@
-use crate::types::{ResourceState, SCALE_FACTOR};
+use crate::types::{MemoryStats, ResourceState, SCALE_FACTOR};
 use void_reckoning_pathfinder::GraphTopology;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TradeRoute {
@@ -9,12 +9,29 @@ pub struct TradeRoute {
     pub to: String,
     pub base_value: ResourceState,
     pub efficiency_scaled: i128, // 1.0 = SCALE_FACTOR
+    /// Factors on the efficiency the route's path gives it, by what set them.
+    #[serde(default)]
+    pub modifiers: Vec<RouteModifier>,
+}
+
+/// A named factor on a route's efficiency (a hazard along the way, a
+/// scripted blockade), applied by `calculate_efficiencies`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RouteModifier {
+    pub source: String,
+    pub multiplier_scaled: i128, // Scaled by SCALE_FACTOR
 }
 
 pub struct TradeRouteManager {
     routes: Vec<TradeRoute>,
 }
 
+impl Default for TradeRouteManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl TradeRouteManager {
     pub fn new() -> Self {
         Self { routes: Vec::new() }
@@ -24,6 +41,23 @@ impl TradeRouteManager {
         self.routes.push(route);
     }
 
+    pub fn replace_routes(&mut self, routes: Vec<TradeRoute>) {
+        self.routes = routes;
+    }
+
+    pub fn routes(&self) -> &[TradeRoute] {
+        &self.routes
+    }
+
+    pub fn memory_stats(&self) -> MemoryStats {
+        MemoryStats {
+            routes: self.routes.len(),
+            approx_bytes: self.routes.capacity() * std::mem::size_of::<TradeRoute>()
+                + self.routes.iter().map(|r| r.from.len() + r.to.len()).sum::<usize>(),
+            ..MemoryStats::default()
+        }
+    }
+
     pub fn calculate_efficiencies(&mut self, topology: &GraphTopology) {
         for route in &mut self.routes {
             if let Some((path, weight)) = topology.find_path(&route.from, &route.to, None) {
@@ -46,11 +80,53 @@ impl TradeRouteManager {
                 // No path
                 route.efficiency_scaled = 0;
             }
+            for modifier in &route.modifiers {
+                route.efficiency_scaled = route.efficiency_scaled * modifier.multiplier_scaled / SCALE_FACTOR;
+            }
+        }
+    }
+
+    /// Sets `source`'s factor on every route `hit` matches, replacing any
+    /// it had there, and returns how many it hit. Takes effect at the next
+    /// `calculate_efficiencies`.
+    pub fn set_route_modifier(&mut self, source: &str, multiplier_scaled: i128, hit: impl Fn(&TradeRoute) -> bool) -> usize {
+        let mut count = 0;
+        for route in self.routes.iter_mut().filter(|r| hit(r)) {
+            match route.modifiers.iter_mut().find(|m| m.source == source) {
+                Some(modifier) => modifier.multiplier_scaled = multiplier_scaled,
+                None => route.modifiers.push(RouteModifier { source: source.to_string(), multiplier_scaled }),
+            }
+            count += 1;
+        }
+        count
+    }
+
+    /// Takes `source`'s factor off every route; returns how many had one.
+    pub fn remove_route_modifiers(&mut self, source: &str) -> usize {
+        let mut count = 0;
+        for route in &mut self.routes {
+            let before = route.modifiers.len();
+            route.modifiers.retain(|m| m.source != source);
+            count += before - route.modifiers.len();
+        }
+        count
+    }
+
+    /// Zeroes the efficiency of every route `blocked` matches (e.g. under an
+    /// embargo) and returns how many it hit. `calculate_efficiencies`
+    /// recomputes efficiencies, so apply this after it.
+    pub fn block_routes(&mut self, blocked: impl Fn(&TradeRoute) -> bool) -> usize {
+        let mut hit = 0;
+        for route in self.routes.iter_mut().filter(|r| blocked(r)) {
+            route.efficiency_scaled = 0;
+            hit += 1;
         }
+        hit
     }
 
-    pub fn get_total_trade_income(&self) -> HashMap<String, ResourceState> {
-        let mut income = HashMap::new();
+    /// Income per system, keyed (and so serialized) in id order.
+    pub fn get_total_trade_income(&self) -> BTreeMap<String, ResourceState> {
+        let mut income = BTreeMap::new();
         for route in &self.routes {
             let mut route_gain = route.base_value;
             route_gain.multiply_fixed(route.efficiency_scaled);
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
+use void_reckoning_ids::{FactionId, SystemId};
 
 pub const SCALE_FACTOR: i128 = 1_000_000;
 
@@ -70,8 +71,10 @@ pub enum NodeType {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EconomicNode {
-    pub id: String,
-    pub owner_faction: String,
+    /// A planet's node shares its system's id; fleet, army and station
+    /// nodes have their own, which supply routing looks up as systems too.
+    pub id: SystemId,
+    pub owner_faction: FactionId,
     pub node_type: NodeType,
     pub base_income: ResourceState,
     pub base_upkeep: ResourceState,
@@ -79,6 +82,28 @@ pub struct EconomicNode {
     pub modifiers: Vec<EconomicModifier>,
 }
 
+/// Memory held by an `IncomeEngine` or `TradeRouteManager`; nodes are rarely
+/// removed and routes never are, so these mostly grow until the engine is
+/// dropped.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryStats {
+    pub nodes: usize,
+    pub modifiers: usize,
+    pub routes: usize,
+    /// Rough heap + inline footprint.
+    pub approx_bytes: usize,
+}
+
+impl EconomicNode {
+    pub(crate) fn footprint(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.id.len()
+            + self.owner_faction.len()
+            + self.modifiers.capacity() * std::mem::size_of::<EconomicModifier>()
+            + self.modifiers.iter().map(|m| m.name.len()).sum::<usize>()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GlobalEconomicRules {
     pub orbit_discount_scaled: i128,      // 0.5 * SCALE_FACTOR
@@ -115,7 +140,7 @@ pub struct EconomicReport {
     pub total_income: ResourceState,
     pub total_upkeep: ResourceState,
     pub net_profit: ResourceState,
-    pub income_by_category: HashMap<String, ResourceState>,
+    pub income_by_category: BTreeMap<String, ResourceState>,
     pub is_insolvent: bool,
     pub active_nodes: usize,
 }
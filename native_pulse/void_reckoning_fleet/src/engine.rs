@@ -0,0 +1,450 @@
+use crate::types::{BattleRoster, Engagement, Fleet, FleetSnapshot, FleetTurnReport, LogisticsRules, Movement, UnitSlot};
+use std::collections::BTreeMap;
+use void_reckoning_combat::engine::BattleEngine;
+use void_reckoning_combat::resolve::{BattleOutcome, DEFAULT_MAX_TURNS};
+use void_reckoning_combat::CombatUnit;
+use void_reckoning_shared::desync;
+use void_reckoning_shared::ids::{FactionId, SystemId, UnitId};
+use void_reckoning_shared::scope::ContextStack;
+use void_reckoning_shared::simtime::SimTime;
+use void_reckoning_shared::span::Span;
+use void_reckoning_shared::{CorrelationContext, Event, EventLog, EventSeverity};
+
+/// Side of the square battlefield fleet battles are fought on.
+const BATTLE_SIZE: f32 = 1000.0;
+/// Gap between ships in a faction's battle line.
+const LINE_SPACING: f32 = 40.0;
+const LINE_LENGTH: usize = 20;
+
+pub struct FleetEngine {
+    fleets: BTreeMap<String, Fleet>,
+    rules: LogisticsRules,
+    turn: u64,
+    pub event_log: Option<EventLog>,
+    /// Correlation contexts; nested operations push scopes onto this.
+    pub contexts: ContextStack,
+}
+
+impl Default for FleetEngine {
+    fn default() -> Self {
+        Self::new(LogisticsRules::default())
+    }
+}
+
+impl FleetEngine {
+    pub fn new(rules: LogisticsRules) -> Self {
+        Self::from_snapshot(FleetSnapshot { rules, ..FleetSnapshot::default() })
+    }
+
+    pub fn from_snapshot(snapshot: FleetSnapshot) -> Self {
+        Self {
+            fleets: snapshot.fleets.into_iter().map(|f| (f.id.clone(), f)).collect(),
+            rules: snapshot.rules,
+            turn: snapshot.turn,
+            event_log: None,
+            contexts: ContextStack::default(),
+        }
+    }
+
+    /// Puts the fleets back as `snapshot` has them, keeping the event log
+    /// and contexts.
+    pub fn restore(&mut self, snapshot: FleetSnapshot) {
+        let FleetEngine { event_log, contexts, .. } = std::mem::replace(self, Self::from_snapshot(snapshot));
+        self.event_log = event_log;
+        self.contexts = contexts;
+    }
+
+    /// Fleets in id order.
+    pub fn snapshot(&self) -> FleetSnapshot {
+        FleetSnapshot { turn: self.turn, rules: self.rules.clone(), fleets: self.fleets.values().cloned().collect() }
+    }
+
+    /// FNV-1a of the snapshot's JSON encoding.
+    pub fn state_hash(&self) -> u64 {
+        desync::hash_json(&self.snapshot())
+    }
+
+    pub fn set_event_log(&mut self, log: EventLog) {
+        self.event_log = Some(log);
+    }
+
+    pub fn set_correlation_context(&mut self, context: CorrelationContext) {
+        self.contexts.set_root(context);
+    }
+
+    pub fn rules(&self) -> &LogisticsRules {
+        &self.rules
+    }
+
+    pub fn set_rules(&mut self, rules: LogisticsRules) {
+        self.rules = rules;
+    }
+
+    pub fn turn(&self) -> u64 {
+        self.turn
+    }
+
+    /// Adds a fleet, returning the one it replaced under the same id.
+    pub fn add_fleet(&mut self, fleet: Fleet) -> Option<Fleet> {
+        self.fleets.insert(fleet.id.clone(), fleet)
+    }
+
+    pub fn remove_fleet(&mut self, id: &str) -> Option<Fleet> {
+        self.fleets.remove(id)
+    }
+
+    pub fn fleet(&self, id: &str) -> Option<&Fleet> {
+        self.fleets.get(id)
+    }
+
+    pub fn fleet_mut(&mut self, id: &str) -> Option<&mut Fleet> {
+        self.fleets.get_mut(id)
+    }
+
+    pub fn fleets(&self) -> impl Iterator<Item = &Fleet> {
+        self.fleets.values()
+    }
+
+    /// Runs one campaign turn: movement, then supply and fuel, then finds
+    /// the systems where hostile fleets now meet. `owners` maps systems to
+    /// their owning faction; fleets resupply only in their own. `hostile`
+    /// says whether two factions fight.
+    pub fn advance_turn<F>(&mut self, owners: &BTreeMap<String, String>, hostile: F) -> FleetTurnReport
+    where
+        F: Fn(&str, &str) -> bool,
+    {
+        self.turn += 1;
+        let sim_time = SimTime::at_turn(Some(self.turn));
+
+        let span = Span::start("fleet.movement", &self.contexts.current()).at(sim_time);
+        let movements = {
+            let _scope = self.contexts.enter_context(span.context.clone());
+            self.advance_movement(&hostile)
+        };
+        self.end_span(span.with_attribute("moved", movements.len()));
+
+        let span = Span::start("fleet.logistics", &self.contexts.current()).at(sim_time);
+        let (attrition, destroyed) = {
+            let _scope = self.contexts.enter_context(span.context.clone());
+            self.apply_logistics(owners)
+        };
+        self.end_span(span.with_attribute("destroyed", destroyed.len()));
+
+        FleetTurnReport { turn: self.turn, movements, attrition, destroyed, engagements: self.engagements(&hostile) }
+    }
+
+    /// Moves every fleet with orders along its route, spending `speed` (plus
+    /// progress carried over) on whole legs and `fuel_per_jump` per jump. A
+    /// fleet stops on entering a system a hostile fleet held at the start
+    /// of the phase, so the order fleets move in doesn't matter.
+    fn advance_movement(&mut self, hostile: &impl Fn(&str, &str) -> bool) -> Vec<Movement> {
+        let held: Vec<(SystemId, FactionId)> = self.fleets.values().map(|f| (f.location.clone(), f.faction.clone())).collect();
+        let fuel_per_jump = self.rules.fuel_per_jump;
+        let mut movements = Vec::new();
+        for fleet in self.fleets.values_mut().filter(|f| !f.route.is_empty()) {
+            let from = fleet.location.clone();
+            let mut budget = fleet.progress + fleet.speed;
+            let (mut jumps, mut stopped) = (0, None);
+            while let Some(leg) = fleet.route.front() {
+                if budget < leg.cost {
+                    break;
+                }
+                if fleet.fuel < fuel_per_jump {
+                    stopped = Some("fuel");
+                    break;
+                }
+                budget -= leg.cost;
+                fleet.fuel -= fuel_per_jump;
+                fleet.location = leg.to.clone();
+                fleet.route.pop_front();
+                jumps += 1;
+                if held.iter().any(|(system, faction)| *system == fleet.location && hostile(&fleet.faction, faction)) {
+                    fleet.route.clear();
+                    stopped = Some("intercepted");
+                }
+            }
+            fleet.progress = fleet.route.front().map_or(0.0, |leg| budget.min(leg.cost));
+            movements.push(Movement {
+                fleet: fleet.id.clone(),
+                from,
+                to: fleet.location.clone(),
+                jumps,
+                stopped: stopped.map(str::to_string),
+            });
+        }
+        for movement in &movements {
+            if let Some(reason) = &movement.stopped {
+                let severity = if reason == "fuel" { EventSeverity::Warning } else { EventSeverity::Info };
+                self.emit(severity, format!("Fleet {} stopped at {} ({})", movement.fleet, movement.to, reason), &movement.fleet);
+            }
+        }
+        movements
+    }
+
+    /// Resupplies and refuels fleets in friendly systems; elsewhere they eat
+    /// supply, and once it runs out lose ships to attrition. Returns ships
+    /// lost per fleet and the fleets wiped out (which are removed).
+    fn apply_logistics(&mut self, owners: &BTreeMap<String, String>) -> (Vec<(String, u32)>, Vec<String>) {
+        let rules = &self.rules;
+        let mut attrition = Vec::new();
+        for fleet in self.fleets.values_mut() {
+            if owners.get(fleet.location.as_str()).is_some_and(|owner| fleet.faction == *owner) {
+                fleet.supply = (fleet.supply + fleet.max_supply * rules.resupply_rate).min(fleet.max_supply);
+                fleet.fuel = (fleet.fuel + fleet.max_fuel * rules.refuel_rate).min(fleet.max_fuel);
+                continue;
+            }
+            let needed = fleet.ship_count() as f32 * rules.supply_per_ship;
+            if fleet.supply >= needed {
+                fleet.supply -= needed;
+                continue;
+            }
+            fleet.supply = 0.0;
+            let mut lost = 0;
+            for stack in &mut fleet.ships {
+                let stack_lost = ((stack.count as f32 * rules.attrition_rate).ceil() as u32).min(stack.count);
+                stack.count -= stack_lost;
+                lost += stack_lost;
+            }
+            fleet.ships.retain(|s| s.count > 0);
+            if lost > 0 {
+                attrition.push((fleet.id.clone(), lost));
+            }
+        }
+        for (fleet, lost) in &attrition {
+            self.emit(EventSeverity::Warning, format!("Fleet {} lost {} ships to attrition", fleet, lost), fleet);
+        }
+        (attrition, self.remove_destroyed())
+    }
+
+    /// Systems where fleets of at least two hostile factions meet, in
+    /// system order. Fleets between systems count as being where they
+    /// last were.
+    pub fn engagements<F>(&self, hostile: F) -> Vec<Engagement>
+    where
+        F: Fn(&str, &str) -> bool,
+    {
+        let mut by_system: BTreeMap<&SystemId, Vec<&Fleet>> = BTreeMap::new();
+        for fleet in self.fleets.values() {
+            by_system.entry(&fleet.location).or_default().push(fleet);
+        }
+        by_system
+            .into_iter()
+            .filter(|(_, fleets)| {
+                fleets.iter().enumerate().any(|(i, a)| fleets[i + 1..].iter().any(|b| hostile(&a.faction, &b.faction)))
+            })
+            .map(|(system, fleets)| Engagement {
+                system: system.clone(),
+                fleets: fleets.iter().map(|f| f.id.clone()).collect(),
+            })
+            .collect()
+    }
+
+    /// Builds the battle for an engagement: one combat unit per ship (hull
+    /// damage carried over), each faction in its own line of columns
+    /// across the field, allies per `hostile`. Unit ids index into the returned roster.
+    pub fn build_battle<F>(&self, engagement: &Engagement, seed: u64, hostile: F) -> (BattleEngine, BattleRoster)
+    where
+        F: Fn(&str, &str) -> bool,
+    {
+        let fleets: Vec<&Fleet> = engagement.fleets.iter().filter_map(|id| self.fleets.get(id)).collect();
+        let mut roster = BattleRoster { system: engagement.system.clone(), factions: Vec::new(), units: Vec::new() };
+        for fleet in &fleets {
+            if !roster.factions.contains(&fleet.faction) {
+                roster.factions.push(fleet.faction.clone());
+            }
+        }
+
+        let mut engine = BattleEngine::new(BATTLE_SIZE, BATTLE_SIZE);
+        engine.set_seed(seed);
+        engine.set_campaign_turn(Some(self.turn));
+        let line_gap = BATTLE_SIZE / roster.factions.len().max(1) as f32;
+        let mut line_fill = vec![0usize; roster.factions.len()];
+        for fleet in fleets {
+            let faction_idx = roster.factions.iter().position(|f| *f == fleet.faction).unwrap_or_default();
+            for (stack_idx, stack) in fleet.ships.iter().enumerate() {
+                for _ in 0..stack.count {
+                    let slot = line_fill[faction_idx];
+                    line_fill[faction_idx] += 1;
+                    let mut unit = stack.template.build();
+                    unit.id = UnitId(roster.units.len() as u32);
+                    unit.faction_idx = faction_idx as u8;
+                    unit.hp = unit.max_hp * stack.hp_fraction.clamp(0.0, 1.0);
+                    unit.position = (
+                        line_gap * faction_idx as f32 + (slot / LINE_LENGTH) as f32 * LINE_SPACING,
+                        (slot % LINE_LENGTH) as f32 * LINE_SPACING,
+                    );
+                    engine.add_unit(unit);
+                    roster.units.push(UnitSlot { fleet: fleet.id.clone(), stack: stack_idx });
+                }
+            }
+        }
+        engine.set_hostility(roster.hostility(hostile));
+        (engine, roster)
+    }
+
+    /// Writes a finished battle back into the fleets: each stack keeps its
+    /// surviving ships at their average remaining hull. Returns the fleets
+    /// wiped out (which are removed).
+    pub fn apply_battle(&mut self, roster: &BattleRoster, units: &[CombatUnit]) -> Vec<String> {
+        let mut tally: BTreeMap<(&str, usize), (u32, f32)> = BTreeMap::new();
+        for slot in &roster.units {
+            tally.entry((&slot.fleet, slot.stack)).or_default();
+        }
+        for unit in units {
+            let Some(slot) = roster.units.get(unit.id.get() as usize) else { continue };
+            if unit.is_alive {
+                let entry = tally.entry((&slot.fleet, slot.stack)).or_default();
+                entry.0 += 1;
+                entry.1 += unit.hp / unit.max_hp;
+            }
+        }
+        for ((fleet_id, stack_idx), (survivors, hp_total)) in tally {
+            let Some(stack) = self.fleets.get_mut(fleet_id).and_then(|f| f.ships.get_mut(stack_idx)) else { continue };
+            stack.count = survivors;
+            if survivors > 0 {
+                stack.hp_fraction = hp_total / survivors as f32;
+            }
+        }
+        for fleet in self.fleets.values_mut() {
+            fleet.ships.retain(|s| s.count > 0);
+        }
+        self.remove_destroyed()
+    }
+
+    /// Fights every current engagement to completion and applies the
+    /// results. Battles get seeds derived from `seed` in system order.
+    pub fn resolve_engagements<F>(&mut self, seed: u64, hostile: F) -> Vec<(BattleRoster, BattleOutcome)>
+    where
+        F: Fn(&str, &str) -> bool,
+    {
+        let mut results = Vec::new();
+        for (index, engagement) in self.engagements(&hostile).into_iter().enumerate() {
+            let span = Span::start("fleet.battle", &self.contexts.current())
+                .with_attribute("system", engagement.system.as_str())
+                .at(SimTime::at_turn(Some(self.turn)));
+            let battle_seed = seed.wrapping_add((index as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+            let (mut battle, roster) = self.build_battle(&engagement, battle_seed, &hostile);
+            if let Some(log) = &self.event_log {
+                battle.set_event_log(log.clone());
+                battle.set_correlation_context(span.context.clone());
+            }
+            let outcome = battle.run_to_completion(&engagement.system, DEFAULT_MAX_TURNS);
+            let destroyed = {
+                let _scope = self.contexts.enter_context(span.context.clone());
+                self.apply_battle(&roster, &battle.state.units)
+            };
+            self.end_span(span.with_attribute("units", roster.units.len()).with_attribute("destroyed", destroyed.len()));
+            results.push((roster, outcome));
+        }
+        results
+    }
+
+    fn remove_destroyed(&mut self) -> Vec<String> {
+        let destroyed: Vec<String> = self.fleets.values().filter(|f| f.is_destroyed()).map(|f| f.id.clone()).collect();
+        for id in &destroyed {
+            self.fleets.remove(id);
+            self.emit(EventSeverity::Warning, format!("Fleet {} was destroyed", id), id);
+        }
+        destroyed
+    }
+
+    fn end_span(&self, span: Span) {
+        if let Some(log) = &self.event_log {
+            log.end_span(span);
+        }
+    }
+
+    fn emit(&self, severity: EventSeverity, message: String, fleet: &str) {
+        if let Some(log) = &self.event_log {
+            let evt = Event::new(severity, "Fleet".to_string(), message, self.contexts.current().child(), Some(fleet.to_string()))
+                .at(SimTime::at_turn(Some(self.turn)));
+            log.add(evt);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ShipStack;
+    use void_reckoning_combat::resolve::{UnitSetup, WeaponSetup};
+    use void_reckoning_combat::{CoverType, WeaponType};
+    use void_reckoning_pathfinder::GraphTopology;
+
+    fn fleet(id: &str, faction: &str, location: &str, count: u32) -> Fleet {
+        let template = UnitSetup {
+            id: UnitId(0),
+            name: "Frigate".to_string(),
+            faction_idx: 0,
+            max_hp: 100.0,
+            x: 0.0,
+            y: 0.0,
+            weapons: vec![WeaponSetup {
+                name: "Laser".to_string(),
+                weapon_type: WeaponType::Energy,
+                range: 2000.0,
+                damage: 40.0,
+                accuracy: 1.0,
+                cooldown: 1.0,
+                aoe_radius: 0.0,
+            }],
+            speed: 20.0,
+            evasion: 0.0,
+            shields: 0.0,
+            armor: 0.0,
+            cover: CoverType::None,
+        };
+        Fleet {
+            id: id.to_string(),
+            faction: faction.into(),
+            location: location.into(),
+            ships: vec![ShipStack { template, count, hp_fraction: 1.0 }],
+            supply: 10.0,
+            max_supply: 10.0,
+            fuel: 2.0,
+            max_fuel: 4.0,
+            speed: 10.0,
+            movement_profile: None,
+            route: Default::default(),
+            progress: 0.0,
+        }
+    }
+
+    #[test]
+    fn fleets_move_meet_and_fight() {
+        let mut topology = GraphTopology::new();
+        topology.add_edge("A", "B", 10.0);
+        topology.add_edge("B", "C", 10.0);
+        topology.add_edge("C", "D", 10.0);
+
+        let mut engine = FleetEngine::default();
+        engine.add_fleet(fleet("red", "Imperium", "A", 5));
+        engine.add_fleet(fleet("blue", "Orks", "C", 2));
+        assert_eq!(engine.fleet_mut("red").unwrap().order_move("D", &topology).unwrap(), 30.0);
+
+        let owners = BTreeMap::from([("A".to_string(), "Imperium".to_string())]);
+        let hostile = |a: &str, b: &str| a != b;
+        let report = engine.advance_turn(&owners, hostile);
+        assert_eq!(engine.fleet("red").unwrap().location, "B");
+        assert!(report.engagements.is_empty());
+
+        // Stopped at C by the Ork fleet, short of D.
+        let report = engine.advance_turn(&owners, hostile);
+        assert_eq!(report.movements[0].stopped.as_deref(), Some("intercepted"));
+        assert_eq!(report.engagements, vec![Engagement { system: "C".into(), fleets: vec!["blue".to_string(), "red".to_string()] }]);
+        let red = engine.fleet("red").unwrap();
+        assert_eq!((red.fuel, red.supply), (0.0, 0.0));
+
+        let results = engine.resolve_engagements(7, hostile);
+        assert_eq!(results[0].0.factions, vec!["Orks", "Imperium"]);
+        assert_eq!(results[0].1.winner, Some(1));
+        assert!(engine.fleet("blue").is_none());
+        assert!((1..=5).contains(&engine.fleet("red").unwrap().ship_count()));
+
+        // Out of fuel and supply outside friendly space.
+        engine.fleet_mut("red").unwrap().order_move("D", &topology).unwrap();
+        let report = engine.advance_turn(&owners, hostile);
+        assert_eq!(report.movements[0].stopped.as_deref(), Some("fuel"));
+        assert_eq!(report.attrition.len(), 1);
+    }
+}
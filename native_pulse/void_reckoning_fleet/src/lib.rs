@@ -0,0 +1,14 @@
+//! Fleets as persistent campaign entities: ship stacks built from combat
+//! `UnitSetup` templates, supply and fuel, and movement along pathfinder
+//! routes. Hostile fleets meeting in a system become a combat battle, and
+//! the result is written back into their stacks.
+
+pub mod types;
+pub mod engine;
+
+pub use engine::FleetEngine;
+pub use types::{BattleRoster, Engagement, Fleet, FleetSnapshot, FleetTurnReport, Leg, LogisticsRules, Movement, ShipStack, UnitSlot};
+
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Version of the `FleetSnapshot` layout; bumped on incompatible changes.
+pub const SCHEMA_VERSION: u32 = 1;
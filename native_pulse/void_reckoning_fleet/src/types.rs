@@ -0,0 +1,182 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use void_reckoning_combat::resolve::UnitSetup;
+use void_reckoning_combat::targeting::Hostility;
+use void_reckoning_pathfinder::GraphTopology;
+use void_reckoning_shared::errors::EngineError;
+use void_reckoning_shared::ids::{FactionId, SystemId};
+
+/// `count` identical ships. The template's `id`, `x` and `y` are ignored;
+/// battles assign their own.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShipStack {
+    pub template: UnitSetup,
+    pub count: u32,
+    /// Average hull left, as a fraction of `template.max_hp`.
+    #[serde(default = "full_hp")]
+    pub hp_fraction: f32,
+}
+
+fn full_hp() -> f32 {
+    1.0
+}
+
+/// One jump of a route: the next system and the pathfinder cost to reach it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Leg {
+    pub to: SystemId,
+    pub cost: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Fleet {
+    pub id: String,
+    pub faction: FactionId,
+    /// System the fleet is in (or last left, while between systems).
+    pub location: SystemId,
+    pub ships: Vec<ShipStack>,
+    pub supply: f32,
+    pub max_supply: f32,
+    pub fuel: f32,
+    pub max_fuel: f32,
+    /// Route cost covered per campaign turn.
+    pub speed: f32,
+    /// Pathfinder movement profile ("Space", "Ground", "Hover").
+    #[serde(default)]
+    pub movement_profile: Option<String>,
+    /// Jumps still to make, in order.
+    #[serde(default)]
+    pub route: VecDeque<Leg>,
+    /// Cost already covered toward the next leg.
+    #[serde(default)]
+    pub progress: f32,
+}
+
+impl Fleet {
+    pub fn ship_count(&self) -> u32 {
+        self.ships.iter().map(|s| s.count).sum()
+    }
+
+    pub fn is_destroyed(&self) -> bool {
+        self.ship_count() == 0
+    }
+
+    /// Where the fleet is headed; its location if it has no orders.
+    pub fn destination(&self) -> &str {
+        self.route.back().map_or(&self.location, |leg| &leg.to)
+    }
+
+    /// Plots the cheapest route to `destination`, replacing any earlier
+    /// orders, and returns its total cost.
+    pub fn order_move(&mut self, destination: &str, topology: &GraphTopology) -> Result<f32, EngineError> {
+        let not_found = || EngineError::PathNotFound { start: self.location.to_string(), end: destination.to_string() };
+        let (path, cost) = topology
+            .find_path(&self.location, destination, self.movement_profile.clone())
+            .ok_or_else(not_found)?;
+        // Every hop of a shortest path is itself the cheapest way between
+        // its two systems, so per-leg costs come from the same search.
+        let mut route = VecDeque::with_capacity(path.len().saturating_sub(1));
+        for hop in path.windows(2) {
+            let (_, leg_cost) = topology.find_path(&hop[0], &hop[1], self.movement_profile.clone()).ok_or_else(not_found)?;
+            route.push_back(Leg { to: SystemId::new(&hop[1]), cost: leg_cost });
+        }
+        self.route = route;
+        self.progress = 0.0;
+        Ok(cost)
+    }
+
+    /// Drops any orders; the fleet stays where it is.
+    pub fn halt(&mut self) {
+        self.route.clear();
+        self.progress = 0.0;
+    }
+}
+
+/// Campaign-wide supply and fuel rules.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogisticsRules {
+    pub fuel_per_jump: f32,
+    /// Supply each ship consumes per turn outside friendly systems.
+    pub supply_per_ship: f32,
+    /// Fraction of `max_supply` regained per turn in a friendly system.
+    pub resupply_rate: f32,
+    /// Fraction of `max_fuel` regained per turn in a friendly system.
+    pub refuel_rate: f32,
+    /// Fraction of each stack (rounded up) lost per turn without supply.
+    pub attrition_rate: f32,
+}
+
+impl Default for LogisticsRules {
+    fn default() -> Self {
+        Self { fuel_per_jump: 1.0, supply_per_ship: 1.0, resupply_rate: 0.25, refuel_rate: 0.5, attrition_rate: 0.1 }
+    }
+}
+
+/// What a fleet did during the movement phase.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Movement {
+    pub fleet: String,
+    pub from: SystemId,
+    pub to: SystemId,
+    pub jumps: u32,
+    /// Why the fleet stopped short: "fuel" or "intercepted".
+    pub stopped: Option<String>,
+}
+
+/// Fleets sharing a system in which at least two are hostile.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Engagement {
+    pub system: SystemId,
+    pub fleets: Vec<String>,
+}
+
+/// Ties a battle built from fleets back to them: combat faction index `i`
+/// is `factions[i]`, and unit id `n` is `units[n]`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BattleRoster {
+    pub system: SystemId,
+    pub factions: Vec<FactionId>,
+    pub units: Vec<UnitSlot>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnitSlot {
+    pub fleet: String,
+    pub stack: usize,
+}
+
+impl BattleRoster {
+    /// Combat alliances: faction indices `hostile` says don't fight.
+    pub fn hostility(&self, hostile: impl Fn(&str, &str) -> bool) -> Hostility {
+        let mut hostility = Hostility::default();
+        for (i, a) in self.factions.iter().enumerate() {
+            for (j, b) in self.factions.iter().enumerate().skip(i + 1) {
+                if let (Ok(i), Ok(j)) = (u8::try_from(i), u8::try_from(j)) {
+                    if !hostile(a, b) {
+                        hostility.ally(i, j);
+                    }
+                }
+            }
+        }
+        hostility
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FleetTurnReport {
+    pub turn: u64,
+    pub movements: Vec<Movement>,
+    /// Ships lost to attrition, by fleet.
+    pub attrition: Vec<(String, u32)>,
+    /// Fleets left with no ships (and removed).
+    pub destroyed: Vec<String>,
+    pub engagements: Vec<Engagement>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FleetSnapshot {
+    pub turn: u64,
+    #[serde(default)]
+    pub rules: LogisticsRules,
+    pub fleets: Vec<Fleet>,
+}
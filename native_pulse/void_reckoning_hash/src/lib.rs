@@ -0,0 +1,42 @@
+//! FNV-1a, the hash behind every state hash, save checksum and registry
+//! content hash. Unlike `std::hash` it gives the same result in every
+//! process and on every platform, which is what lockstep clients and saves
+//! need. Free of pyo3 so the pathfinder, combat and economy crates can use
+//! it without the Python bindings; everything else reaches it through
+//! `void_reckoning_shared::desync`.
+
+use serde::Serialize;
+
+/// Starting value: the hash of no bytes.
+pub const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+/// FNV-1a of `bytes`.
+pub fn hash_bytes(bytes: &[u8]) -> u64 {
+    extend_hash(FNV_OFFSET, bytes)
+}
+
+/// Carries on hashing `bytes` from `hash`, so a value can be hashed a
+/// field at a time: `extend_hash(hash_bytes(a), b)` is the hash of `a`
+/// followed by `b`.
+pub fn extend_hash(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, byte| (hash ^ *byte as u64).wrapping_mul(FNV_PRIME))
+}
+
+/// FNV-1a of the JSON encoding. Canonical for state kept in vectors and
+/// ordered maps, which is how every engine snapshot is laid out.
+pub fn hash_json(value: &impl Serialize) -> u64 {
+    hash_bytes(&serde_json::to_vec(value).unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extending_matches_hashing_the_whole() {
+        assert_eq!(hash_bytes(b""), FNV_OFFSET);
+        assert_eq!(hash_bytes(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(extend_hash(hash_bytes(b"void"), b"reckoning"), hash_bytes(b"voidreckoning"));
+    }
+}
@@ -0,0 +1,346 @@
+//! Typed ids shared across engines. Systems, factions and audited
+//! entities are interned strings: cloning one copies a pointer, equal ids
+//! share one allocation, and a `SystemId` can't be passed where a
+//! `FactionId` is wanted. Units are combat's `u32` ids. Each serializes
+//! exactly as the string or number it wraps, so JSON and save layouts are
+//! unchanged.
+//!
+//! Kept apart from void_reckoning_shared so the pathfinder and combat
+//! crates can use it in wasm builds, which have no Python. The `python`
+//! feature adds conversions to and from Python strings and ints.
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+#[cfg(feature = "python")]
+use pyo3::types::{PyInt, PyString};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::borrow::Borrow;
+use std::collections::HashSet;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Interned id strings, and the size at which to next drop those no id
+/// holds any more.
+#[derive(Default)]
+struct Interned {
+    ids: HashSet<Arc<str>>,
+    prune_at: usize,
+}
+
+/// Below this many strings the set is never pruned.
+const MIN_PRUNE_AT: usize = 1024;
+
+static INTERNED: OnceLock<Mutex<Interned>> = OnceLock::new();
+
+fn intern(id: &str) -> Arc<str> {
+    let mut interned = INTERNED.get_or_init(Default::default).lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(existing) = interned.ids.get(id) {
+        return Arc::clone(existing);
+    }
+    // A string only the set holds can't be handed out again without this
+    // lock, so dropping it never splits an id in two. Pruning at twice the
+    // surviving size keeps interning amortized O(1).
+    if interned.ids.len() >= interned.prune_at.max(MIN_PRUNE_AT) {
+        interned.ids.retain(|id| Arc::strong_count(id) > 1);
+        interned.prune_at = interned.ids.len() * 2;
+    }
+    let id: Arc<str> = Arc::from(id);
+    interned.ids.insert(Arc::clone(&id));
+    id
+}
+
+/// How many id strings are interned; those no id holds any more are
+/// dropped as the set grows.
+pub fn interned_count() -> usize {
+    INTERNED.get().map_or(0, |interned| interned.lock().unwrap_or_else(|e| e.into_inner()).ids.len())
+}
+
+macro_rules! interned_id {
+    ($(#[$doc:meta])* $name:ident) => {
+        $(#[$doc])*
+        #[derive(Clone)]
+        pub struct $name(Arc<str>);
+
+        impl $name {
+            pub fn new(id: &str) -> Self {
+                Self(intern(id))
+            }
+
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::new("")
+            }
+        }
+
+        impl PartialEq for $name {
+            fn eq(&self, other: &Self) -> bool {
+                // Interning makes equal ids the same allocation.
+                Arc::ptr_eq(&self.0, &other.0)
+            }
+        }
+
+        impl Eq for $name {}
+
+        impl Hash for $name {
+            // Hashes as the string, to agree with `Borrow<str>`.
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                self.as_str().hash(state);
+            }
+        }
+
+        impl PartialOrd for $name {
+            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl Ord for $name {
+            fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+                self.as_str().cmp(other.as_str())
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}({:?})", stringify!($name), self.as_str())
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl Deref for $name {
+            type Target = str;
+            fn deref(&self) -> &str {
+                self.as_str()
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                self.as_str()
+            }
+        }
+
+        impl Borrow<str> for $name {
+            fn borrow(&self) -> &str {
+                self.as_str()
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(id: &str) -> Self {
+                Self::new(id)
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(id: String) -> Self {
+                Self::new(&id)
+            }
+        }
+
+        impl From<&String> for $name {
+            fn from(id: &String) -> Self {
+                Self::new(id)
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(id: $name) -> Self {
+                id.as_str().to_string()
+            }
+        }
+
+        impl PartialEq<str> for $name {
+            fn eq(&self, other: &str) -> bool {
+                self.as_str() == other
+            }
+        }
+
+        impl PartialEq<&str> for $name {
+            fn eq(&self, other: &&str) -> bool {
+                self.as_str() == *other
+            }
+        }
+
+        impl PartialEq<String> for $name {
+            fn eq(&self, other: &String) -> bool {
+                self.as_str() == other
+            }
+        }
+
+        impl PartialEq<$name> for String {
+            fn eq(&self, other: &$name) -> bool {
+                self == other.as_str()
+            }
+        }
+
+        impl PartialEq<$name> for &str {
+            fn eq(&self, other: &$name) -> bool {
+                *self == other.as_str()
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                String::deserialize(deserializer).map(Self::from)
+            }
+        }
+
+        #[cfg(feature = "python")]
+        impl<'py> FromPyObject<'py> for $name {
+            fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+                Ok(Self::new(ob.downcast::<PyString>()?.to_str()?))
+            }
+        }
+
+        #[cfg(feature = "python")]
+        impl<'py> IntoPyObject<'py> for $name {
+            type Target = PyString;
+            type Output = Bound<'py, PyString>;
+            type Error = std::convert::Infallible;
+
+            fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
+                Ok(PyString::new(py, self.as_str()))
+            }
+        }
+
+        #[cfg(feature = "python")]
+        impl<'py> IntoPyObject<'py> for &$name {
+            type Target = PyString;
+            type Output = Bound<'py, PyString>;
+            type Error = std::convert::Infallible;
+
+            fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
+                Ok(PyString::new(py, self.as_str()))
+            }
+        }
+    };
+}
+
+interned_id!(
+    /// A star system: a pathfinder node, and the id of any planet in it.
+    SystemId
+);
+
+interned_id!(
+    /// A faction, as economy node owners, fleets and treaties name it.
+    FactionId
+);
+
+interned_id!(
+    /// Anything the auditor validates: a unit, a faction, a registry entry.
+    EntityId
+);
+
+/// A combat unit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct UnitId(pub u32);
+
+impl UnitId {
+    pub fn get(self) -> u32 {
+        self.0
+    }
+}
+
+impl fmt::Display for UnitId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<u32> for UnitId {
+    fn from(id: u32) -> Self {
+        Self(id)
+    }
+}
+
+impl From<UnitId> for u32 {
+    fn from(id: UnitId) -> Self {
+        id.0
+    }
+}
+
+impl PartialEq<u32> for UnitId {
+    fn eq(&self, other: &u32) -> bool {
+        self.0 == *other
+    }
+}
+
+#[cfg(feature = "python")]
+impl<'py> FromPyObject<'py> for UnitId {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        ob.extract::<u32>().map(Self)
+    }
+}
+
+#[cfg(feature = "python")]
+impl<'py> IntoPyObject<'py> for UnitId {
+    type Target = PyInt;
+    type Output = Bound<'py, PyInt>;
+    type Error = std::convert::Infallible;
+
+    fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
+        self.0.into_pyobject(py)
+    }
+}
+
+#[cfg(feature = "python")]
+impl<'py> IntoPyObject<'py> for &UnitId {
+    type Target = PyInt;
+    type Output = Bound<'py, PyInt>;
+    type Error = std::convert::Infallible;
+
+    fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
+        self.0.into_pyobject(py)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn ids_intern_and_serialize_as_their_value() {
+        let terra = SystemId::new("Terra");
+        let again: SystemId = serde_json::from_str("\"Terra\"").unwrap();
+        assert!(Arc::ptr_eq(&terra.0, &again.0));
+        assert_eq!(serde_json::to_string(&terra).unwrap(), "\"Terra\"");
+        assert_eq!(bincode::serialize(&terra).unwrap(), bincode::serialize("Terra").unwrap());
+        assert_eq!(serde_json::to_string(&UnitId(7)).unwrap(), "7");
+
+        let owners = BTreeMap::from([(terra.clone(), FactionId::new("Imperium"))]);
+        assert_eq!(owners.get("Terra").map(FactionId::as_str), Some("Imperium"));
+        assert_eq!(terra, "Terra");
+    }
+
+    #[test]
+    fn unused_ids_are_pruned_as_the_set_grows() {
+        let kept = SystemId::new("Kept");
+        for i in 0..4 * MIN_PRUNE_AT {
+            SystemId::new(&format!("Transient{}", i));
+        }
+        assert!(interned_count() < 3 * MIN_PRUNE_AT);
+        // Ids still held survive, and new ones still intern to them.
+        assert!(Arc::ptr_eq(&kept.0, &SystemId::new("Kept").0));
+    }
+}
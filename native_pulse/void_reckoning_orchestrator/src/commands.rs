@@ -0,0 +1,258 @@
+//! Actions: the one path through which players and the AI change the
+//! campaign. Actions are submitted to the turn engine's queue and resolved
+//! in submission order at the start of the next turn. Each is checked
+//! first, by the auditor when there is one and then by the engine it
+//! touches, and applied whole or not at all; a rejected action is reported
+//! with its reasons.
+
+use crate::engine::covers;
+use crate::types::ProductionOrder;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use void_reckoning_auditor::engine::ValidationEngine;
+use void_reckoning_auditor::registry::RegistryKind;
+use void_reckoning_auditor::types::{EntityType, ValidationSeverity};
+use void_reckoning_economy::engine::IncomeEngine;
+use void_reckoning_economy::types::{EconomicModifier, ResourceState};
+use void_reckoning_fleet::FleetEngine;
+use void_reckoning_pathfinder::GraphTopology;
+use void_reckoning_shared::ids::{FactionId, SystemId};
+
+/// Prefix of the economic modifier a policy puts on its faction's nodes.
+pub const POLICY_PREFIX: &str = "policy:";
+
+/// Externally tagged, like `Command`: `{"MoveFleet": {...}}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Action {
+    /// Sends `fleet` to `destination` along the cheapest lanes.
+    MoveFleet { faction: FactionId, fleet: String, destination: SystemId },
+    /// Builds `building`, an id in the auditor's buildings registry, at
+    /// `node`: its `cost` comes out of the treasury and its `yield` and
+    /// `upkeep` add to the node's.
+    BuildStructure { faction: FactionId, node: String, building: String },
+    /// Scales the output of every node the faction owns by
+    /// `multiplier_scaled` under the name `policy`, replacing the policy's
+    /// earlier setting; None repeals it.
+    SetPolicy {
+        faction: FactionId,
+        policy: String,
+        #[serde(default)]
+        multiplier_scaled: Option<i128>,
+    },
+    /// Queues production, as `TurnEngine::queue_production` does.
+    Produce(ProductionOrder),
+}
+
+impl Action {
+    pub fn faction(&self) -> &FactionId {
+        match self {
+            Action::MoveFleet { faction, .. } | Action::BuildStructure { faction, .. } | Action::SetPolicy { faction, .. } => faction,
+            Action::Produce(order) => &order.faction,
+        }
+    }
+
+    /// The entity the auditor checks the action as.
+    fn subject(&self) -> (String, EntityType) {
+        match self {
+            Action::MoveFleet { fleet, .. } => (fleet.clone(), EntityType::Fleet),
+            Action::BuildStructure { building, .. } => (building.clone(), EntityType::Building),
+            Action::SetPolicy { faction, .. } => (faction.to_string(), EntityType::Faction),
+            Action::Produce(order) => (order.id.clone(), EntityType::Unit),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedAction {
+    /// Given by `submit`, in submission order.
+    pub id: u64,
+    pub action: Action,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionOutcome {
+    pub id: u64,
+    pub action: Action,
+    pub applied: bool,
+    /// Why the action was rejected; empty when applied.
+    #[serde(default)]
+    pub reasons: Vec<String>,
+}
+
+/// What actions check against and change.
+pub(crate) struct ActionTargets<'a> {
+    pub topology: &'a GraphTopology,
+    pub economy: &'a mut IncomeEngine,
+    pub auditor: Option<&'a ValidationEngine>,
+    pub fleets: Option<&'a mut FleetEngine>,
+    pub treasuries: &'a mut BTreeMap<FactionId, ResourceState>,
+    pub production: &'a mut Vec<ProductionOrder>,
+    pub universe_id: &'a str,
+    pub turn: u64,
+}
+
+/// A building's registry entry, e.g. `{"cost": {"credits": 200},
+/// "yield": {"minerals": 5}, "upkeep": {"energy": 1}}`.
+struct Building {
+    cost: ResourceState,
+    income: ResourceState,
+    upkeep: ResourceState,
+}
+
+fn building(auditor: Option<&ValidationEngine>, id: &str) -> Result<Building, String> {
+    let auditor = auditor.ok_or("no buildings registry: the turn engine has no auditor")?;
+    let registries = auditor.registries();
+    let entry = registries.get(RegistryKind::Buildings).get(id).ok_or_else(|| format!("unknown building '{}'", id))?;
+    let resources = |key: &str| {
+        let amount = |name: &str| entry.get(key).and_then(|r| r.get(name)).and_then(Value::as_f64).unwrap_or(0.0);
+        ResourceState::new(amount("credits"), amount("minerals"), amount("energy"), amount("research"))
+    };
+    Ok(Building { cost: resources("cost"), income: resources("yield"), upkeep: resources("upkeep") })
+}
+
+/// Reasons `action` can't be applied now; empty if it can.
+pub(crate) fn check(action: &Action, targets: &ActionTargets<'_>) -> Vec<String> {
+    let mut reasons = Vec::new();
+    if let Some(auditor) = targets.auditor {
+        let (entity_id, entity_type) = action.subject();
+        let data = serde_json::to_value(action).unwrap_or_default();
+        for result in auditor.validate_entity(entity_id, entity_type, data, targets.universe_id.to_string(), targets.turn) {
+            if matches!(result.severity, ValidationSeverity::Error | ValidationSeverity::Critical) {
+                reasons.push(format!("[Rule: {}] {}", result.rule_name, result.message));
+            }
+        }
+    }
+    let treasury = targets.treasuries.get(action.faction()).copied().unwrap_or_default();
+    match action {
+        Action::MoveFleet { faction, fleet, destination } => match targets.fleets.as_deref().map(|f| f.fleet(fleet)) {
+            None => reasons.push("no fleet engine".to_string()),
+            Some(None) => reasons.push(format!("unknown fleet '{}'", fleet)),
+            Some(Some(found)) if &found.faction != faction => reasons.push(format!("fleet '{}' belongs to {}", fleet, found.faction)),
+            Some(Some(found)) => {
+                if let Err(e) = found.clone().order_move(destination, targets.topology) {
+                    reasons.push(e.to_string());
+                }
+            }
+        },
+        Action::BuildStructure { faction, node, building: id } => {
+            match targets.economy.nodes().iter().find(|n| &n.id == node) {
+                None => reasons.push(format!("unknown node '{}'", node)),
+                Some(found) if found.owner_faction != faction.as_str() => {
+                    reasons.push(format!("node '{}' belongs to {}", node, found.owner_faction))
+                }
+                Some(_) => {}
+            }
+            match building(targets.auditor, id) {
+                Ok(building) if !covers(&treasury, &building.cost) => reasons.push(format!("treasury can't cover '{}'", id)),
+                Ok(_) => {}
+                Err(reason) => reasons.push(reason),
+            }
+        }
+        Action::SetPolicy { faction, multiplier_scaled, .. } => {
+            if !targets.economy.nodes().iter().any(|n| n.owner_faction == faction.as_str()) {
+                reasons.push(format!("{} owns no nodes", faction));
+            }
+            if multiplier_scaled.is_some_and(|m| m < 0) {
+                reasons.push("policy multiplier is negative".to_string());
+            }
+        }
+        Action::Produce(order) => {
+            if targets.production.iter().any(|o| o.id == order.id) {
+                reasons.push(format!("order '{}' is already queued", order.id));
+            }
+            if !covers(&order.cost, &ResourceState::default()) {
+                reasons.push("cost is negative".to_string());
+            }
+        }
+    }
+    reasons
+}
+
+/// Carries out an action `check` passed.
+pub(crate) fn apply(action: &Action, targets: &mut ActionTargets<'_>) {
+    match action {
+        Action::MoveFleet { fleet, destination, .. } => {
+            if let Some(fleet) = targets.fleets.as_deref_mut().and_then(|f| f.fleet_mut(fleet)) {
+                let _ = fleet.order_move(destination, targets.topology);
+            }
+        }
+        Action::BuildStructure { faction, node, building: id } => {
+            let Ok(building) = building(targets.auditor, id) else { return };
+            targets.treasuries.entry(faction.clone()).or_default().subtract(&building.cost);
+            if let Some(node) = targets.economy.node_mut(node) {
+                node.base_income.add(&building.income);
+                node.base_upkeep.add(&building.upkeep);
+            }
+        }
+        Action::SetPolicy { faction, policy, multiplier_scaled } => {
+            let name = format!("{}{}", POLICY_PREFIX, policy);
+            let nodes: Vec<SystemId> =
+                targets.economy.nodes().iter().filter(|n| n.owner_faction == *faction).map(|n| n.id.clone()).collect();
+            for id in nodes {
+                let Some(node) = targets.economy.node_mut(&id) else { continue };
+                node.modifiers.retain(|m| m.name != name);
+                if let Some(multiplier_scaled) = *multiplier_scaled {
+                    node.modifiers.push(EconomicModifier { name: name.clone(), multiplier_scaled, flat_bonus: ResourceState::default() });
+                }
+            }
+        }
+        Action::Produce(order) => targets.production.push(order.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{TurnEngine, TurnEngines};
+    use void_reckoning_economy::trade::TradeRouteManager;
+    use void_reckoning_economy::types::{EconomicNode, GlobalEconomicRules, NodeType, SCALE_FACTOR};
+
+    #[test]
+    fn actions_resolve_in_order_and_rejections_change_nothing() {
+        let credits = |amount: i128| ResourceState { credits: amount * SCALE_FACTOR, ..ResourceState::default() };
+        let topology = GraphTopology::new();
+        let mut economy = IncomeEngine::new(GlobalEconomicRules::default());
+        economy.add_node(EconomicNode {
+            id: "Terra".into(),
+            owner_faction: "Imperium".into(),
+            node_type: NodeType::Planet,
+            base_income: credits(100),
+            base_upkeep: ResourceState::default(),
+            efficiency_scaled: SCALE_FACTOR,
+            modifiers: Vec::new(),
+        });
+        let mut trade = TradeRouteManager::new();
+        let mut turns = TurnEngine::new("u1".to_string());
+        let policy = turns.submit(Action::SetPolicy {
+            faction: "Imperium".into(),
+            policy: "war_economy".to_string(),
+            multiplier_scaled: Some(2 * SCALE_FACTOR),
+        });
+        let moved =
+            turns.submit(Action::MoveFleet { faction: "Imperium".into(), fleet: "fleet-1".to_string(), destination: "Mars".into() });
+        let built = turns.submit(Action::BuildStructure {
+            faction: "Imperium".into(),
+            node: "Terra".to_string(),
+            building: "forge".to_string(),
+        });
+        assert_eq!(turns.pending_actions().len(), 3);
+
+        let report = turns.run_turn(TurnEngines {
+            topology: &topology,
+            economy: &mut economy,
+            trade: &mut trade,
+            auditor: None,
+            diplomacy: None,
+            colonies: None,
+            fleets: None,
+        });
+
+        let outcome = |id: u64| report.actions.iter().find(|o| o.id == id).unwrap();
+        assert!(outcome(policy).applied);
+        assert_eq!(outcome(moved).reasons, vec!["no fleet engine"]);
+        assert!(!outcome(built).applied);
+        assert_eq!(report.factions["Imperium"].treasury, credits(200));
+        assert!(turns.pending_actions().is_empty());
+    }
+}
@@ -0,0 +1,789 @@
+use crate::commands::{self, Action, ActionOutcome, ActionTargets, QueuedAction};
+use crate::invasion::{resolve_invasion, InvasionOrder, InvasionOutcome};
+use crate::replay::{Command, Recording, TurnState};
+use crate::types::{ProductionOrder, TurnReport};
+use crate::victory::{Verdict, VictoryRules, VictoryTracker};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use void_reckoning_auditor::engine::ValidationEngine;
+use void_reckoning_auditor::types::EntityType;
+use void_reckoning_colony::ColonyEngine;
+use void_reckoning_diplomacy::DiplomacyEngine;
+use void_reckoning_economy::engine::IncomeEngine;
+use void_reckoning_economy::logistics::{self, LogisticsRules};
+use void_reckoning_economy::trade::TradeRouteManager;
+use void_reckoning_economy::types::ResourceState;
+use void_reckoning_fleet::FleetEngine;
+use void_reckoning_pathfinder::GraphTopology;
+use void_reckoning_shared::desync;
+use void_reckoning_shared::ids::{EntityId, FactionId};
+use void_reckoning_shared::scope::ContextStack;
+use void_reckoning_shared::simtime::{SimClock, SimTime};
+use void_reckoning_shared::span::Span;
+use void_reckoning_shared::{CorrelationContext, Event, EventLog, EventSeverity};
+
+/// The engines a turn runs over, borrowed for its duration.
+pub struct TurnEngines<'a> {
+    pub topology: &'a GraphTopology,
+    pub economy: &'a mut IncomeEngine,
+    pub trade: &'a mut TradeRouteManager,
+    pub auditor: Option<&'a mut ValidationEngine>,
+    /// Embargoes and wars sever trade, vassals pay tribute, and opinion
+    /// decays once per turn.
+    pub diplomacy: Option<&'a mut DiplomacyEngine>,
+    /// Credits colony ships from completed production, lands voyages and
+    /// grows populations once per turn.
+    pub colonies: Option<&'a mut ColonyEngine>,
+    /// What `MoveFleet` actions order about.
+    pub fleets: Option<&'a mut FleetEngine>,
+}
+
+impl TurnEngines<'_> {
+    pub fn reborrow(&mut self) -> TurnEngines<'_> {
+        TurnEngines {
+            topology: self.topology,
+            economy: self.economy,
+            trade: self.trade,
+            auditor: self.auditor.as_deref_mut(),
+            diplomacy: self.diplomacy.as_deref_mut(),
+            colonies: self.colonies.as_deref_mut(),
+            fleets: self.fleets.as_deref_mut(),
+        }
+    }
+}
+
+/// Campaign state that lives between turns (treasuries, the production
+/// queue, pending invasions and audits) and the phase sequence that
+/// advances it.
+pub struct TurnEngine {
+    universe_id: String,
+    /// Its turn is the last turn run; each turn starts the next.
+    clock: SimClock,
+    treasuries: BTreeMap<FactionId, ResourceState>,
+    production: Vec<ProductionOrder>,
+    invasions: Vec<InvasionOrder>,
+    audits: Vec<(EntityId, EntityType, Value)>,
+    actions: Vec<QueuedAction>,
+    next_action: u64,
+    victory: Option<VictoryTracker>,
+    /// When set, travelling resources are routed over the map each turn
+    /// and only upkeep that arrives is paid.
+    logistics: Option<LogisticsRules>,
+    pub event_log: Option<EventLog>,
+    /// Correlation contexts; each turn opens a span under the current one.
+    pub contexts: ContextStack,
+    /// Commands and keyframes since `start_recording`, if recording.
+    pub(crate) recording: Option<Recording>,
+}
+
+impl TurnEngine {
+    pub fn new(universe_id: String) -> Self {
+        Self {
+            universe_id,
+            clock: SimClock::default(),
+            treasuries: BTreeMap::new(),
+            production: Vec::new(),
+            invasions: Vec::new(),
+            audits: Vec::new(),
+            actions: Vec::new(),
+            next_action: 0,
+            victory: None,
+            logistics: None,
+            event_log: None,
+            contexts: ContextStack::default(),
+            recording: None,
+        }
+    }
+
+    pub fn set_event_log(&mut self, log: EventLog) {
+        self.event_log = Some(log);
+    }
+
+    pub fn set_correlation_context(&mut self, context: CorrelationContext) {
+        self.contexts.set_root(context);
+    }
+
+    /// Last turn run (0 before the first).
+    pub fn turn(&self) -> u64 {
+        self.clock.turn()
+    }
+
+    /// The next `run_turn` will be turn `turn + 1`. Ends any recording,
+    /// whose turns would no longer line up.
+    pub fn set_turn(&mut self, turn: u64) {
+        self.clock.set_turn(turn);
+        self.recording = None;
+    }
+
+    /// Runs the campaign on `clock`, carrying on from its turn, and hands
+    /// it to the economy each turn so both read the same time. Ends any
+    /// recording, like `set_turn`.
+    pub fn set_clock(&mut self, clock: SimClock) {
+        self.clock = clock;
+        self.recording = None;
+    }
+
+    pub fn clock(&self) -> &SimClock {
+        &self.clock
+    }
+
+    pub fn treasury(&self, faction: &str) -> ResourceState {
+        self.treasuries.get(faction).copied().unwrap_or_default()
+    }
+
+    pub fn treasuries(&self) -> &BTreeMap<FactionId, ResourceState> {
+        &self.treasuries
+    }
+
+    pub fn set_treasury(&mut self, faction: FactionId, resources: ResourceState) {
+        self.record(|| Command::SetTreasury { faction: faction.clone(), resources });
+        self.treasuries.insert(faction, resources);
+    }
+
+    pub fn queue_production(&mut self, order: ProductionOrder) {
+        self.record(|| Command::QueueProduction(order.clone()));
+        self.production.push(order);
+    }
+
+    pub fn production_queue(&self) -> &[ProductionOrder] {
+        &self.production
+    }
+
+    /// Landings for the next turn's invasion phase, which runs first so
+    /// captured planets pay their new owner that turn.
+    pub fn queue_invasion(&mut self, order: InvasionOrder) {
+        self.record(|| Command::QueueInvasion(order.clone()));
+        self.invasions.push(order);
+    }
+
+    pub fn pending_invasions(&self) -> &[InvasionOrder] {
+        &self.invasions
+    }
+
+    /// Resolves an invasion at once, outside the turn sequence. With
+    /// `diplomacy`, the attacker must be at war with the planet's owner.
+    pub fn invade(
+        &mut self,
+        order: &InvasionOrder,
+        topology: &GraphTopology,
+        economy: &mut IncomeEngine,
+        diplomacy: Option<&DiplomacyEngine>,
+    ) -> Result<InvasionOutcome, String> {
+        let phase = self.phase("turn.invasion", self.clock.now());
+        let outcome = self.land(order, topology, economy, diplomacy, &phase);
+        self.end_phase(phase.with_attribute("invasions", 1));
+        if outcome.is_ok() {
+            self.record(|| Command::Invade(order.clone()));
+            if let Some(recording) = &mut self.recording {
+                recording.economy_changed(economy);
+            }
+        }
+        outcome
+    }
+
+    /// Entities for the next turn's audit phase.
+    pub fn queue_audit(&mut self, entity_id: EntityId, entity_type: EntityType, data: Value) {
+        self.record(|| Command::QueueAudit { entity_id: entity_id.clone(), entity_type: entity_type.clone(), data: data.clone() });
+        self.audits.push((entity_id, entity_type, data));
+    }
+
+    pub fn pending_audits(&self) -> usize {
+        self.audits.len()
+    }
+
+    /// Queues `action` for the start of the next turn; returns its id.
+    pub fn submit(&mut self, action: Action) -> u64 {
+        self.record(|| Command::Submit(action.clone()));
+        let id = self.next_action;
+        self.next_action += 1;
+        self.actions.push(QueuedAction { id, action });
+        id
+    }
+
+    pub fn pending_actions(&self) -> &[QueuedAction] {
+        &self.actions
+    }
+
+    /// Why `action` would be rejected if resolved now, against the
+    /// current treasuries and queue; empty if it would be applied.
+    /// Changes nothing.
+    pub fn check_action(&mut self, action: &Action, engines: TurnEngines<'_>) -> Vec<String> {
+        let TurnEngines { topology, economy, auditor, fleets, .. } = engines;
+        let targets = ActionTargets {
+            topology,
+            economy,
+            auditor: auditor.as_deref(),
+            fleets,
+            treasuries: &mut self.treasuries,
+            production: &mut self.production,
+            universe_id: &self.universe_id,
+            turn: self.clock.turn(),
+        };
+        commands::check(action, &targets)
+    }
+
+    /// Checks `rules` at the end of every turn from now on, starting from
+    /// no progress; None stops checking.
+    pub fn set_victory_rules(&mut self, rules: Option<VictoryRules>) {
+        self.victory = rules.map(VictoryTracker::new);
+    }
+
+    pub fn victory_rules(&self) -> Option<&VictoryRules> {
+        self.victory.as_ref().map(|v| v.rules())
+    }
+
+    pub fn verdict(&self) -> Option<&Verdict> {
+        self.victory.as_ref().and_then(|v| v.verdict())
+    }
+
+    /// Routes `rules.resources` from producing to consuming nodes over the
+    /// map every turn from now on. Upkeep nothing reaches goes unpaid, but
+    /// the node's output drops to the share that arrived until it is
+    /// supplied again. None pools everything, as before.
+    pub fn set_logistics_rules(&mut self, rules: Option<LogisticsRules>) {
+        self.logistics = rules;
+    }
+
+    pub fn logistics_rules(&self) -> Option<&LogisticsRules> {
+        self.logistics.as_ref()
+    }
+
+    /// Advances to the next turn and runs every phase. Engines without an
+    /// event log write into this engine's log for the turn, so the whole
+    /// turn lands in one trace. Audits stay queued when no auditor is given.
+    pub fn run_turn(&mut self, mut engines: TurnEngines<'_>) -> TurnReport {
+        let Some(mut recording) = self.recording.take() else {
+            return self.play_turn(engines);
+        };
+        recording.before_turn(self, &engines);
+        let report = self.play_turn(engines.reborrow());
+        recording.after_turn(self.clock.turn(), &engines);
+        self.recording = Some(recording);
+        report
+    }
+
+    /// FNV-1a over the between-turn state: treasuries, queues, pending
+    /// audits and actions, and the verdict. Victory streaks are left out.
+    pub fn state_hash(&self) -> u64 {
+        let verdict = self.victory.as_ref().and_then(VictoryTracker::verdict);
+        desync::hash_json(&(self.clock.turn(), &self.treasuries, &self.production, &self.invasions, &self.audits, &self.actions, verdict))
+    }
+
+    /// Between-turn state, for keyframes.
+    pub(crate) fn state(&self) -> TurnState {
+        TurnState {
+            turn: self.clock.turn(),
+            treasuries: self.treasuries.clone(),
+            production: self.production.clone(),
+            invasions: self.invasions.clone(),
+            audits: self.audits.clone(),
+            actions: self.actions.clone(),
+            next_action: self.next_action,
+            victory: self.victory.clone(),
+        }
+    }
+
+    pub(crate) fn restore_state(&mut self, state: TurnState) {
+        self.clock.set_turn(state.turn);
+        self.treasuries = state.treasuries;
+        self.production = state.production;
+        self.invasions = state.invasions;
+        self.audits = state.audits;
+        self.actions = state.actions;
+        self.next_action = state.next_action;
+        self.victory = state.victory;
+    }
+
+    fn record(&mut self, command: impl FnOnce() -> Command) {
+        if let Some(recording) = &mut self.recording {
+            recording.record(self.clock.turn(), command());
+        }
+    }
+
+    fn play_turn(&mut self, engines: TurnEngines<'_>) -> TurnReport {
+        let TurnEngines { topology, economy, trade, mut auditor, mut diplomacy, mut colonies, fleets } = engines;
+        self.clock.advance_turn();
+        let sim_time = self.clock.now();
+        let span = Span::start("turn.run", &self.contexts.current())
+            .with_attribute("turn", self.clock.turn())
+            .at(sim_time);
+        let _scope = self.contexts.enter_context(span.context.clone());
+
+        let economy_log_lent = lend_log(&mut economy.obs.event_log, &self.event_log);
+        let auditor_log_lent = auditor.as_deref_mut().is_some_and(|a| lend_log(&mut a.event_log, &self.event_log));
+        let diplomacy_log_lent = diplomacy.as_deref_mut().is_some_and(|d| lend_log(&mut d.event_log, &self.event_log));
+        let colonies_log_lent = colonies.as_deref_mut().is_some_and(|c| lend_log(&mut c.event_log, &self.event_log));
+        economy.set_clock(Some(self.clock.clone()));
+
+        let mut report = TurnReport {
+            turn: self.clock.turn(),
+            trace_id: span.context.trace_id.clone(),
+            factions: BTreeMap::new(),
+            unattributed_trade: ResourceState::default(),
+            severed_routes: 0,
+            embargoed_routes: 0,
+            invasions: Vec::new(),
+            rejected_invasions: Vec::new(),
+            completed: Vec::new(),
+            stalled: Vec::new(),
+            audit: None,
+            colonization: None,
+            victory: None,
+            actions: Vec::new(),
+        };
+
+        if !self.actions.is_empty() {
+            let phase = self.phase("turn.actions", sim_time);
+            let mut targets = ActionTargets {
+                topology,
+                economy: &mut *economy,
+                auditor: auditor.as_deref(),
+                fleets,
+                treasuries: &mut self.treasuries,
+                production: &mut self.production,
+                universe_id: &self.universe_id,
+                turn: self.clock.turn(),
+            };
+            for QueuedAction { id, action } in std::mem::take(&mut self.actions) {
+                let reasons = commands::check(&action, &targets);
+                let applied = reasons.is_empty();
+                if applied {
+                    commands::apply(&action, &mut targets);
+                }
+                report.actions.push(ActionOutcome { id, action, applied, reasons });
+            }
+            for outcome in report.actions.iter().filter(|o| !o.applied) {
+                let message = format!("Action {} rejected: {}", outcome.id, outcome.reasons.join("; "));
+                self.emit(EventSeverity::Warning, message, &phase, outcome.action.faction());
+            }
+            let rejected = report.actions.iter().filter(|o| !o.applied).count();
+            self.end_phase(phase.with_attribute("actions", report.actions.len()).with_attribute("rejected", rejected));
+        }
+
+        if !self.invasions.is_empty() {
+            let phase = self.phase("turn.invasion", sim_time);
+            for order in std::mem::take(&mut self.invasions) {
+                match self.land(&order, topology, economy, diplomacy.as_deref(), &phase) {
+                    Ok(outcome) => report.invasions.push(outcome),
+                    Err(_) => report.rejected_invasions.push(order.id),
+                }
+            }
+            let captured = report.invasions.iter().filter(|o| o.planet_changed_hands()).count();
+            self.end_phase(
+                phase
+                    .with_attribute("invasions", report.invasions.len())
+                    .with_attribute("captured", captured),
+            );
+        }
+
+        let phase = self.phase("turn.trade", sim_time);
+        trade.calculate_efficiencies(topology);
+        if let Some(diplomacy) = diplomacy.as_deref() {
+            report.embargoed_routes = diplomacy.apply_embargoes(trade, economy.nodes());
+        }
+        report.severed_routes = trade.routes().iter().filter(|r| r.efficiency_scaled == 0).count();
+        self.end_phase(
+            phase
+                .with_attribute("severed_routes", report.severed_routes)
+                .with_attribute("embargoed_routes", report.embargoed_routes),
+        );
+
+        let phase = self.phase("turn.income", sim_time);
+        {
+            let _economy_scope = economy.obs.contexts.enter_context(phase.context.clone());
+            for (faction, faction_report) in economy.process_all() {
+                let entry = report.factions.entry(faction.into()).or_default();
+                entry.income = faction_report.total_income;
+                entry.upkeep = faction_report.total_upkeep;
+            }
+        }
+        let owners: BTreeMap<&str, &FactionId> =
+            economy.nodes().iter().map(|n| (n.id.as_str(), &n.owner_faction)).collect();
+        for (system, gain) in trade.get_total_trade_income() {
+            match owners.get(system.as_str()) {
+                Some(owner) => {
+                    let entry = report.factions.entry((*owner).clone()).or_default();
+                    entry.trade_income.add(&gain);
+                    entry.income.add(&gain);
+                }
+                None => report.unattributed_trade.add(&gain),
+            }
+        }
+        self.end_phase(phase.with_attribute("factions", report.factions.len()));
+
+        if let Some(rules) = &self.logistics {
+            let phase = self.phase("turn.logistics", sim_time);
+            let mut shortages = 0;
+            for (faction, entry) in report.factions.iter_mut() {
+                let supply = logistics::plan_supply(economy, topology, rules, faction);
+                logistics::apply_shortages(economy, &supply);
+                entry.upkeep.subtract(&supply.missing);
+                for shortage in &supply.shortages {
+                    let message = format!("{} is short of {:?}: {} of its upkeep did not arrive", shortage.node, shortage.resource, shortage.missing);
+                    self.emit(EventSeverity::Warning, message, &phase, &shortage.node);
+                }
+                shortages += supply.shortages.len();
+                entry.supply = Some(supply);
+            }
+            self.end_phase(phase.with_attribute("shortages", shortages));
+        }
+
+        let phase = self.phase("turn.upkeep", sim_time);
+        let factions: BTreeSet<FactionId> = self.treasuries.keys().chain(report.factions.keys()).cloned().collect();
+        if let Some(diplomacy) = diplomacy.as_deref() {
+            let net: BTreeMap<String, ResourceState> = report
+                .factions
+                .iter()
+                .map(|(name, f)| {
+                    let mut net = f.income;
+                    net.subtract(&f.upkeep);
+                    (name.to_string(), net)
+                })
+                .collect();
+            for tribute in diplomacy.tribute(&net, economy.rules().vassal_tribute_rate_scaled) {
+                report.factions.entry(tribute.vassal).or_default().tribute_paid.add(&tribute.amount);
+                report.factions.entry(tribute.overlord).or_default().tribute_received.add(&tribute.amount);
+            }
+        }
+        for faction in factions {
+            let entry = report.factions.entry(faction.clone()).or_default();
+            let treasury = self.treasuries.entry(faction.clone()).or_default();
+            treasury.add(&entry.income);
+            treasury.subtract(&entry.upkeep);
+            treasury.add(&entry.tribute_received);
+            treasury.subtract(&entry.tribute_paid);
+            entry.is_insolvent = treasury.credits < 0;
+            let deficit = treasury.to_floats().0;
+            if entry.is_insolvent {
+                self.emit(
+                    EventSeverity::Warning,
+                    format!("Faction {} ends upkeep in deficit: {} credits", faction, deficit),
+                    &phase,
+                    &faction,
+                );
+            }
+        }
+        let insolvent = report.factions.values().filter(|f| f.is_insolvent).count();
+        self.end_phase(phase.with_attribute("insolvent", insolvent));
+
+        let phase = self.phase("turn.production", sim_time);
+        let mut blocked: BTreeSet<FactionId> = BTreeSet::new();
+        for order in std::mem::take(&mut self.production) {
+            let treasury = self.treasuries.entry(order.faction.clone()).or_default();
+            // Strict queue order per faction: once one order waits, the rest do too.
+            if blocked.contains(&order.faction) || !covers(treasury, &order.cost) {
+                blocked.insert(order.faction.clone());
+                report.stalled.push(order.id.clone());
+                self.production.push(order);
+                continue;
+            }
+            treasury.subtract(&order.cost);
+            let entry = report.factions.entry(order.faction.clone()).or_default();
+            entry.production_spent.add(&order.cost);
+            self.emit(
+                EventSeverity::Info,
+                format!("Faction {} completed {} ({})", order.faction, order.id, order.item),
+                &phase,
+                &order.id,
+            );
+            report.completed.push(order);
+        }
+        for (faction, entry) in &mut report.factions {
+            entry.treasury = self.treasury(faction);
+        }
+        self.end_phase(
+            phase
+                .with_attribute("completed", report.completed.len())
+                .with_attribute("stalled", report.stalled.len()),
+        );
+
+        if let Some(colonies) = colonies.as_deref_mut() {
+            let phase = self.phase("turn.colonization", sim_time);
+            let mut ships_built: BTreeMap<FactionId, u32> = BTreeMap::new();
+            for order in report.completed.iter().filter(|o| o.item == colonies.rules().colony_ship_item) {
+                *ships_built.entry(order.faction.clone()).or_default() += 1;
+            }
+            let colonization = {
+                let _colony_scope = colonies.contexts.enter_context(phase.context.clone());
+                colonies.advance_turn(self.clock.turn(), ships_built, topology, economy, &mut self.treasuries)
+            };
+            for (faction, entry) in &mut report.factions {
+                entry.treasury = self.treasuries.get(faction).copied().unwrap_or_default();
+            }
+            self.end_phase(
+                phase
+                    .with_attribute("founded", colonization.founded.len())
+                    .with_attribute("failed", colonization.failed.len()),
+            );
+            report.colonization = Some(colonization);
+        }
+
+        if let Some(auditor) = auditor.as_deref_mut() {
+            let phase = self.phase("turn.audit", sim_time);
+            let audits = std::mem::take(&mut self.audits);
+            let audited = audits.len();
+            let audit = {
+                let _auditor_scope = auditor.contexts.enter_context(phase.context.clone());
+                auditor.validate_batch(audits, self.universe_id.clone(), self.clock.turn())
+            };
+            self.end_phase(
+                phase
+                    .with_attribute("entities", audited)
+                    .with_attribute("errors", audit.summary.errors + audit.summary.critical),
+            );
+            report.audit = Some(audit);
+        }
+
+        if let Some(diplomacy) = diplomacy.as_deref_mut() {
+            let phase = self.phase("turn.diplomacy", sim_time);
+            {
+                let _diplomacy_scope = diplomacy.contexts.enter_context(phase.context.clone());
+                diplomacy.advance_turn();
+            }
+            self.end_phase(phase.with_attribute("wars", diplomacy.wars().len()));
+        }
+
+        if let Some(mut tracker) = self.victory.take() {
+            let phase = self.phase("turn.victory", sim_time);
+            let decided = tracker.verdict().is_some();
+            let victory = tracker.evaluate(&report, economy, &self.treasuries);
+            for milestone in &victory.milestones {
+                self.emit(
+                    EventSeverity::Info,
+                    format!("{} is {}% of the way to {}", milestone.faction, milestone.percent, milestone.condition),
+                    &phase,
+                    &milestone.faction,
+                );
+            }
+            for faction in &victory.defeated {
+                let reason = victory.standings[faction].defeated.clone().unwrap_or_default();
+                self.emit(EventSeverity::Warning, format!("{} is defeated ({})", faction, reason), &phase, faction);
+            }
+            if let (false, Some(verdict)) = (decided, &victory.verdict) {
+                self.emit(
+                    EventSeverity::Warning,
+                    format!("{} wins by {} on turn {}", verdict.winner, verdict.condition, verdict.turn),
+                    &phase,
+                    &verdict.winner,
+                );
+            }
+            self.end_phase(
+                phase
+                    .with_attribute("defeated", victory.defeated.len())
+                    .with_attribute("decided", victory.verdict.is_some()),
+            );
+            report.victory = Some(victory);
+            self.victory = Some(tracker);
+        }
+
+        if economy_log_lent {
+            economy.obs.event_log = None;
+        }
+        if let (true, Some(auditor)) = (auditor_log_lent, auditor) {
+            auditor.event_log = None;
+        }
+        if let (true, Some(diplomacy)) = (diplomacy_log_lent, diplomacy) {
+            diplomacy.event_log = None;
+        }
+        if let (true, Some(colonies)) = (colonies_log_lent, colonies) {
+            colonies.event_log = None;
+        }
+        if let Some(log) = &self.event_log {
+            log.end_span(
+                span.with_attribute("factions", report.factions.len())
+                    .with_attribute("completed", report.completed.len()),
+            );
+        }
+        report
+    }
+
+    fn land(
+        &self,
+        order: &InvasionOrder,
+        topology: &GraphTopology,
+        economy: &mut IncomeEngine,
+        diplomacy: Option<&DiplomacyEngine>,
+        phase: &Span,
+    ) -> Result<InvasionOutcome, String> {
+        let owner = economy.nodes().iter().find(|n| n.id == order.planet).map(|n| n.owner_faction.clone());
+        let outcome = match (diplomacy, owner) {
+            (Some(diplomacy), Some(owner)) if owner != order.attacker && !diplomacy.at_war(&order.attacker, &owner) => {
+                Err(format!("Invasion {}: {} is not at war with {}", order.id, order.attacker, owner))
+            }
+            _ => resolve_invasion(order, topology, economy),
+        };
+        match &outcome {
+            Ok(outcome) if outcome.planet_changed_hands() => {
+                if let Some(log) = &self.event_log {
+                    let message = format!("{} took {} from {}", outcome.attacker, outcome.planet, outcome.defender);
+                    let evt = Event::new(EventSeverity::Warning, "Turn".to_string(), message, phase.context.child(), Some(outcome.planet.to_string()))
+                        .with_field("planet", outcome.planet.as_str())
+                        .with_field("from", outcome.defender.as_str())
+                        .with_field("to", outcome.attacker.as_str())
+                        .at(phase.sim_time);
+                    log.add(evt);
+                }
+            }
+            Ok(outcome) => self.emit(
+                EventSeverity::Info,
+                format!("{} repelled {}'s landing on {}", outcome.defender, outcome.attacker, outcome.planet),
+                phase,
+                &outcome.planet,
+            ),
+            Err(reason) => self.emit(EventSeverity::Warning, reason.clone(), phase, &order.planet),
+        }
+        outcome
+    }
+
+    fn phase(&self, name: &str, sim_time: SimTime) -> Span {
+        Span::start(name, &self.contexts.current()).at(sim_time)
+    }
+
+    fn end_phase(&self, span: Span) {
+        if let Some(log) = &self.event_log {
+            log.end_span(span);
+        }
+    }
+
+    fn emit(&self, severity: EventSeverity, message: String, phase: &Span, entity: &str) {
+        if let Some(log) = &self.event_log {
+            let evt = Event::new(severity, "Turn".to_string(), message, phase.context.child(), Some(entity.to_string()))
+                .at(phase.sim_time);
+            log.add(evt);
+        }
+    }
+}
+
+/// Installs `log` on an engine that has none; true if it did.
+fn lend_log(slot: &mut Option<EventLog>, log: &Option<EventLog>) -> bool {
+    let lent = slot.is_none() && log.is_some();
+    if lent {
+        *slot = log.clone();
+    }
+    lent
+}
+
+pub(crate) fn covers(treasury: &ResourceState, cost: &ResourceState) -> bool {
+    treasury.credits >= cost.credits
+        && treasury.minerals >= cost.minerals
+        && treasury.energy >= cost.energy
+        && treasury.research >= cost.research
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use void_reckoning_auditor::registry::Registries;
+    use void_reckoning_economy::trade::TradeRoute;
+    use void_reckoning_economy::types::{EconomicNode, GlobalEconomicRules, NodeType, SCALE_FACTOR};
+
+    fn credits(amount: i128) -> ResourceState {
+        ResourceState { credits: amount * SCALE_FACTOR, ..ResourceState::default() }
+    }
+
+    fn node(id: &str, faction: &str, income: i128, upkeep: i128) -> EconomicNode {
+        EconomicNode {
+            id: id.into(),
+            owner_faction: faction.into(),
+            node_type: NodeType::Planet,
+            base_income: credits(income),
+            base_upkeep: credits(upkeep),
+            efficiency_scaled: SCALE_FACTOR,
+            modifiers: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn turn_runs_every_phase_in_one_trace() {
+        let mut topology = GraphTopology::new();
+        topology.add_edge("Terra", "Mars", 1.0);
+        let mut economy = IncomeEngine::new(GlobalEconomicRules::default());
+        economy.add_node(node("Terra", "Imperium", 100, 20));
+        economy.add_node(node("Kor", "Orks", 10, 40));
+        let mut trade = TradeRouteManager::new();
+        trade.add_route(TradeRoute {
+            from: "Terra".to_string(),
+            to: "Mars".to_string(),
+            base_value: credits(30),
+            efficiency_scaled: 0,
+            modifiers: Vec::new(),
+        });
+        let mut auditor = ValidationEngine::new(Arc::new(Registries::new()));
+
+        let mut turns = TurnEngine::new("u1".to_string());
+        let log = EventLog::new();
+        turns.set_event_log(log.clone());
+        for (id, cost) in [("ship-1", 50), ("ship-2", 500), ("ship-3", 1)] {
+            turns.queue_production(ProductionOrder {
+                id: id.to_string(),
+                faction: "Imperium".into(),
+                item: "Frigate".to_string(),
+                cost: credits(cost),
+            });
+        }
+        turns.queue_audit("fleet-1".into(), EntityType::Unit, serde_json::json!({}));
+
+        let report = turns.run_turn(TurnEngines {
+            topology: &topology,
+            economy: &mut economy,
+            trade: &mut trade,
+            auditor: Some(&mut auditor),
+            diplomacy: None,
+            colonies: None,
+            fleets: None,
+        });
+
+        let imperium = &report.factions["Imperium"];
+        assert_eq!(imperium.trade_income, credits(15));
+        assert_eq!(imperium.treasury, credits(100 + 15 - 20 - 50));
+        assert!(report.factions["Orks"].is_insolvent);
+        assert_eq!(report.unattributed_trade, credits(15));
+        // ship-2 is unaffordable, and ship-3 waits behind it.
+        assert_eq!(report.completed.len(), 1);
+        assert_eq!(report.stalled, vec!["ship-2", "ship-3"]);
+        assert_eq!(report.audit.as_ref().map(|a| a.summary.total_checks), Some(1));
+        assert_eq!(turns.pending_audits(), 0);
+        assert!(economy.obs.event_log.is_none());
+        assert!(log.get_all().iter().all(|e| e.context.trace_id == report.trace_id));
+    }
+
+    #[test]
+    fn logistics_charges_only_upkeep_that_arrives() {
+        let minerals = |amount: i128| ResourceState { minerals: amount * SCALE_FACTOR, ..ResourceState::default() };
+        let mut topology = GraphTopology::new();
+        for (from, to, weight) in [("Terra", "Mars", 1.0), ("Mars", "Luna", 1.0), ("Terra", "Luna", 5.0)] {
+            topology.add_edge(from, to, weight);
+        }
+        let mut economy = IncomeEngine::new(GlobalEconomicRules::default());
+        economy.add_node(EconomicNode { base_income: minerals(50), ..node("Terra", "Imperium", 0, 0) });
+        economy.add_node(EconomicNode { base_upkeep: minerals(30), ..node("Luna", "Imperium", 0, 0) });
+        // Orks hold Mars, so the cheap route is shut.
+        economy.add_node(node("Mars", "Orks", 0, 0));
+        let mut trade = TradeRouteManager::new();
+        let mut turns = TurnEngine::new("u1".to_string());
+        turns.set_logistics_rules(Some(LogisticsRules {
+            capacities: vec![logistics::LaneCapacity { from: "Terra".into(), to: "Luna".into(), capacity: 10 * SCALE_FACTOR }],
+            ..LogisticsRules::default()
+        }));
+
+        let report = turns.run_turn(TurnEngines {
+            topology: &topology,
+            economy: &mut economy,
+            trade: &mut trade,
+            auditor: None,
+            diplomacy: None,
+            colonies: None,
+            fleets: None,
+        });
+
+        let imperium = &report.factions["Imperium"];
+        let supply = imperium.supply.as_ref().unwrap();
+        assert_eq!(supply.flows.len(), 1);
+        assert_eq!(supply.missing, minerals(20));
+        assert_eq!(imperium.upkeep, minerals(10));
+        assert_eq!(imperium.treasury, minerals(50 - 10));
+        let luna = economy.nodes().iter().find(|n| n.id == "Luna").unwrap();
+        assert_eq!(luna.modifiers[0].multiplier_scaled, SCALE_FACTOR / 3);
+    }
+}
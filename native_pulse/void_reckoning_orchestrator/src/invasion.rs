@@ -0,0 +1,207 @@
+//! Ground invasions: orbital bombardment thins the garrison, terrain
+//! multiplies what is left, and the landing force either takes the planet
+//! or is thrown back.
+
+use serde::{Deserialize, Serialize};
+use void_reckoning_economy::engine::IncomeEngine;
+use void_reckoning_economy::types::{NodeType, SCALE_FACTOR};
+use void_reckoning_pathfinder::{GraphTopology, TerrainType};
+use void_reckoning_shared::ids::{FactionId, SystemId};
+
+/// Share of the defense a victorious landing force loses.
+const ASSAULT_CASUALTIES: f32 = 0.5;
+/// Share of a repelled landing force's strength the garrison loses.
+const REPULSE_CASUALTIES: f32 = 0.5;
+
+/// A landing on `planet` (an economic Planet node, and the system of the
+/// same id in the topology). `garrison` lists the defender's Army nodes
+/// stationed there; an Army node's strength is its credit upkeep.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvasionOrder {
+    pub id: String,
+    pub planet: SystemId,
+    pub attacker: FactionId,
+    pub troops: f32,
+    /// Damage delivered from orbit before the landing.
+    #[serde(default)]
+    pub bombardment: f32,
+    #[serde(default)]
+    pub garrison: Vec<SystemId>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InvasionResult {
+    Occupied,
+    Repelled,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvasionOutcome {
+    pub id: String,
+    pub planet: SystemId,
+    pub attacker: FactionId,
+    pub defender: FactionId,
+    pub result: InvasionResult,
+    /// None if the planet isn't in the topology (fought as open ground).
+    pub terrain: Option<TerrainType>,
+    pub garrison_strength: f32,
+    /// Garrison strength destroyed from orbit.
+    pub bombarded: f32,
+    /// Surviving garrison strength after the terrain multiplier.
+    pub defense: f32,
+    pub troops: f32,
+    pub attacker_losses: f32,
+    /// Garrison strength lost, bombardment included.
+    pub defender_losses: f32,
+    /// Army nodes removed from the economy.
+    pub destroyed_garrisons: Vec<SystemId>,
+}
+
+impl InvasionOutcome {
+    pub fn planet_changed_hands(&self) -> bool {
+        self.result == InvasionResult::Occupied
+    }
+}
+
+/// Defender strength multiplier for fighting on `terrain`.
+pub fn terrain_defense(terrain: Option<TerrainType>) -> f32 {
+    match terrain {
+        Some(TerrainType::Forest) => 1.25,
+        Some(TerrainType::Water) => 1.5,
+        Some(TerrainType::Mountain) => 1.75,
+        _ => 1.0,
+    }
+}
+
+/// How much of a bombardment lands on troops dug into `terrain`.
+pub fn bombardment_effect(terrain: Option<TerrainType>) -> f32 {
+    match terrain {
+        Some(TerrainType::Forest) => 0.75,
+        Some(TerrainType::Mountain) => 0.5,
+        _ => 1.0,
+    }
+}
+
+/// Resolves `order` and applies it to `economy`: an occupied planet passes
+/// to the attacker and its garrison is removed; a repelled landing leaves
+/// the garrison's armies scaled down by their losses. Nothing changes when
+/// the order is invalid.
+pub fn resolve_invasion(order: &InvasionOrder, topology: &GraphTopology, economy: &mut IncomeEngine) -> Result<InvasionOutcome, String> {
+    if !(order.troops.is_finite() && order.troops > 0.0) {
+        return Err(format!("Invasion {} needs a positive troop strength, got {}", order.id, order.troops));
+    }
+    let planet = economy
+        .nodes()
+        .iter()
+        .find(|n| n.id == order.planet && n.node_type == NodeType::Planet)
+        .ok_or_else(|| format!("Invasion {}: no planet {}", order.id, order.planet))?;
+    let defender = planet.owner_faction.clone();
+    if defender == order.attacker {
+        return Err(format!("Invasion {}: {} already owns {}", order.id, order.attacker, order.planet));
+    }
+    let garrison: Vec<(SystemId, f32)> = economy
+        .nodes()
+        .iter()
+        .filter(|n| n.node_type == NodeType::Army && n.owner_faction == defender && order.garrison.contains(&n.id))
+        .map(|n| (n.id.clone(), (n.base_upkeep.credits.max(0) as f64 / SCALE_FACTOR as f64) as f32))
+        .collect();
+
+    let terrain = topology.terrain(&order.planet);
+    let garrison_strength: f32 = garrison.iter().map(|(_, strength)| strength).sum();
+    let bombarded = (order.bombardment.max(0.0) * bombardment_effect(terrain)).min(garrison_strength);
+    let remaining = garrison_strength - bombarded;
+    let defense = remaining * terrain_defense(terrain);
+
+    let (result, attacker_losses, ground_losses) = if order.troops > defense {
+        (InvasionResult::Occupied, (defense * ASSAULT_CASUALTIES).min(order.troops), remaining)
+    } else {
+        let losses = (order.troops * REPULSE_CASUALTIES / terrain_defense(terrain)).min(remaining);
+        (InvasionResult::Repelled, order.troops, losses)
+    };
+
+    let mut destroyed_garrisons = Vec::new();
+    if result == InvasionResult::Occupied {
+        for (id, _) in &garrison {
+            economy.remove_node(id);
+            destroyed_garrisons.push(id.clone());
+        }
+        if let Some(planet) = economy.node_mut(&order.planet) {
+            planet.owner_faction = order.attacker.clone();
+        }
+    } else if garrison_strength > 0.0 {
+        let surviving = (remaining - ground_losses) / garrison_strength;
+        let surviving_scaled = (surviving as f64 * SCALE_FACTOR as f64).round() as i128;
+        for (id, _) in &garrison {
+            if let Some(army) = economy.node_mut(id) {
+                army.base_upkeep.multiply_fixed(surviving_scaled);
+            }
+        }
+    }
+
+    Ok(InvasionOutcome {
+        id: order.id.clone(),
+        planet: order.planet.clone(),
+        attacker: order.attacker.clone(),
+        defender,
+        result,
+        terrain,
+        garrison_strength,
+        bombarded,
+        defense,
+        troops: order.troops,
+        attacker_losses,
+        defender_losses: bombarded + ground_losses,
+        destroyed_garrisons,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use void_reckoning_economy::types::{EconomicNode, GlobalEconomicRules, ResourceState};
+
+    fn node(id: &str, node_type: NodeType, upkeep: i128) -> EconomicNode {
+        EconomicNode {
+            id: id.into(),
+            owner_faction: "Imperium".into(),
+            node_type,
+            base_income: ResourceState::default(),
+            base_upkeep: ResourceState { credits: upkeep * SCALE_FACTOR, ..ResourceState::default() },
+            efficiency_scaled: SCALE_FACTOR,
+            modifiers: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn terrain_and_bombardment_shape_the_landing() {
+        let mut topology = GraphTopology::new();
+        topology.add_node("Cadia".to_string(), Some("Mountain".to_string()));
+        let mut economy = IncomeEngine::new(GlobalEconomicRules::default());
+        economy.add_node(node("Cadia", NodeType::Planet, 0));
+        economy.add_node(node("Cadia-PDF", NodeType::Army, 10));
+        economy.add_node(node("Cadia-Guard", NodeType::Army, 10));
+        let mut order = InvasionOrder {
+            id: "waaagh".to_string(),
+            planet: "Cadia".into(),
+            attacker: "Orks".into(),
+            troops: 20.0,
+            bombardment: 10.0,
+            garrison: vec!["Cadia-PDF".into(), "Cadia-Guard".into()],
+        };
+
+        // Mountains halve the bombardment (5) and make the other 15 worth 26.25.
+        let repelled = resolve_invasion(&order, &topology, &mut economy).unwrap();
+        assert_eq!(repelled.result, InvasionResult::Repelled);
+        assert_eq!((repelled.bombarded, repelled.defense), (5.0, 26.25));
+        let pdf = economy.nodes().iter().find(|n| n.id == "Cadia-PDF").unwrap();
+        assert!(pdf.base_upkeep.credits < 5 * SCALE_FACTOR);
+
+        order.troops = 40.0;
+        let occupied = resolve_invasion(&order, &topology, &mut economy).unwrap();
+        assert!(occupied.planet_changed_hands());
+        assert_eq!(occupied.destroyed_garrisons.len(), 2);
+        assert_eq!(economy.nodes().len(), 1);
+        assert_eq!(economy.nodes()[0].owner_faction, "Orks");
+        assert!(resolve_invasion(&order, &topology, &mut economy).is_err());
+    }
+}
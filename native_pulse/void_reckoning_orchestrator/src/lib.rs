@@ -0,0 +1,30 @@
+//! Runs a whole campaign turn natively: submitted actions, invasions, trade
+//! efficiencies, income, upkeep, production and audits, in that order,
+//! under one trace. Actions are the audited way to change the campaign:
+//! each is checked before it is applied, and rejections come back with
+//! reasons. With a diplomacy
+//! engine, embargoes cut trade, vassals pay tribute and opinion decays. With
+//! a colony engine, colony ships land and populations grow after production.
+//! With logistics rules, minerals and energy travel over the lanes between
+//! income and upkeep, and upkeep that can't be reached goes short.
+//! Victory rules are checked last, each turn. A recording lets the
+//! campaign be rewound and re-simulated to any turn.
+
+pub mod types;
+pub mod commands;
+pub mod engine;
+pub mod invasion;
+pub mod replay;
+pub mod victory;
+
+pub use commands::{Action, ActionOutcome, QueuedAction};
+pub use engine::{TurnEngine, TurnEngines};
+pub use invasion::{resolve_invasion, InvasionOrder, InvasionOutcome, InvasionResult};
+pub use replay::{Command, Recording, ReplayEngines};
+pub use types::{FactionTurn, ProductionOrder, TurnReport};
+pub use victory::{Condition, Defeat, Verdict, VictoryReport, VictoryRules, VictoryTracker};
+
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Version of the `TurnReport`/`ProductionOrder` layouts; bumped on
+/// incompatible changes.
+pub const SCHEMA_VERSION: u32 = 1;
@@ -0,0 +1,364 @@
+//! Campaign recording and time travel. While recording, the turn engine
+//! logs every command it is given, keyed by the turn it was given after,
+//! and keeps keyframes of the engines it runs over. `rewind_to` restores
+//! the nearest keyframe at or before a turn and re-simulates from there.
+//!
+//! Keyframes are taken when recording starts, every `keyframe_interval`
+//! turns, and whenever the engines changed between turns in a way the
+//! recording didn't see (a node added from Python, a treaty signed), so
+//! edits made directly on the engines survive a rewind too.
+
+use crate::commands::{Action, QueuedAction};
+use crate::engine::{TurnEngine, TurnEngines};
+use crate::invasion::InvasionOrder;
+use crate::types::{ProductionOrder, TurnReport};
+use crate::victory::VictoryTracker;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use void_reckoning_auditor::engine::ValidationEngine;
+use void_reckoning_auditor::types::EntityType;
+use void_reckoning_colony::{ColonyEngine, ColonySnapshot};
+use void_reckoning_diplomacy::{DiplomacyEngine, DiplomacySnapshot};
+use void_reckoning_economy::engine::IncomeEngine;
+use void_reckoning_economy::trade::{TradeRoute, TradeRouteManager};
+use void_reckoning_economy::types::{EconomicNode, GlobalEconomicRules, ResourceState};
+use void_reckoning_fleet::{FleetEngine, FleetSnapshot};
+use void_reckoning_pathfinder::{GraphTopology, TopologySnapshot};
+use void_reckoning_shared::ids::{EntityId, FactionId};
+
+/// Keyframe spacing when none is given.
+pub const DEFAULT_KEYFRAME_INTERVAL: u64 = 10;
+
+/// An input to the turn engine, as recorded. Externally tagged
+/// (`{"QueueProduction": {...}}`): resource amounts are `i128`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Command {
+    SetTreasury { faction: FactionId, resources: ResourceState },
+    QueueProduction(ProductionOrder),
+    QueueInvasion(InvasionOrder),
+    /// An invasion resolved at once with `TurnEngine::invade`.
+    Invade(InvasionOrder),
+    QueueAudit { entity_id: EntityId, entity_type: EntityType, data: Value },
+    Submit(Action),
+}
+
+/// The engines a rewind restores and re-runs. Unlike `TurnEngines` the
+/// topology is mutable, since keyframes restore it too.
+pub struct ReplayEngines<'a> {
+    pub topology: &'a mut GraphTopology,
+    pub economy: &'a mut IncomeEngine,
+    pub trade: &'a mut TradeRouteManager,
+    pub auditor: Option<&'a mut ValidationEngine>,
+    pub diplomacy: Option<&'a mut DiplomacyEngine>,
+    pub colonies: Option<&'a mut ColonyEngine>,
+    pub fleets: Option<&'a mut FleetEngine>,
+}
+
+impl ReplayEngines<'_> {
+    fn turn_engines(&mut self) -> TurnEngines<'_> {
+        TurnEngines {
+            topology: self.topology,
+            economy: self.economy,
+            trade: self.trade,
+            auditor: self.auditor.as_deref_mut(),
+            diplomacy: self.diplomacy.as_deref_mut(),
+            colonies: self.colonies.as_deref_mut(),
+            fleets: self.fleets.as_deref_mut(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct EngineHashes {
+    topology: u64,
+    economy: u64,
+    trade: u64,
+    diplomacy: Option<u64>,
+    colonies: Option<u64>,
+    fleets: Option<u64>,
+}
+
+impl EngineHashes {
+    fn of(engines: &TurnEngines<'_>) -> Self {
+        Self {
+            topology: engines.topology.state_hash(),
+            economy: engines.economy.state_hash(),
+            trade: engines.trade.state_hash(),
+            diplomacy: engines.diplomacy.as_deref().map(DiplomacyEngine::state_hash),
+            colonies: engines.colonies.as_deref().map(ColonyEngine::state_hash),
+            fleets: engines.fleets.as_deref().map(FleetEngine::state_hash),
+        }
+    }
+}
+
+/// What the turn engine carries between turns.
+#[derive(Clone)]
+pub(crate) struct TurnState {
+    pub turn: u64,
+    pub treasuries: BTreeMap<FactionId, ResourceState>,
+    pub production: Vec<ProductionOrder>,
+    pub invasions: Vec<InvasionOrder>,
+    pub audits: Vec<(EntityId, EntityType, Value)>,
+    pub actions: Vec<QueuedAction>,
+    pub next_action: u64,
+    pub victory: Option<VictoryTracker>,
+}
+
+struct Keyframe {
+    turn: u64,
+    /// How many of `turn`'s commands had been given when it was taken.
+    applied: usize,
+    state: TurnState,
+    topology: TopologySnapshot,
+    rules: GlobalEconomicRules,
+    nodes: Vec<EconomicNode>,
+    routes: Vec<TradeRoute>,
+    diplomacy: Option<DiplomacySnapshot>,
+    colonies: Option<ColonySnapshot>,
+    fleets: Option<FleetSnapshot>,
+}
+
+impl Keyframe {
+    fn capture(turns: &TurnEngine, applied: usize, engines: &TurnEngines<'_>) -> Self {
+        Self {
+            turn: turns.turn(),
+            applied,
+            state: turns.state(),
+            topology: engines.topology.snapshot(),
+            rules: engines.economy.rules().clone(),
+            nodes: engines.economy.nodes().to_vec(),
+            routes: engines.trade.routes().to_vec(),
+            diplomacy: engines.diplomacy.as_deref().map(|d| d.snapshot().clone()),
+            colonies: engines.colonies.as_deref().map(ColonyEngine::snapshot),
+            fleets: engines.fleets.as_deref().map(FleetEngine::snapshot),
+        }
+    }
+
+    fn restore(&self, turns: &mut TurnEngine, engines: &mut ReplayEngines<'_>) {
+        turns.restore_state(self.state.clone());
+        *engines.topology = GraphTopology::from_snapshot(self.topology.clone());
+        engines.economy.set_rules(self.rules.clone());
+        engines.economy.replace_nodes(self.nodes.clone());
+        engines.trade.replace_routes(self.routes.clone());
+        if let (Some(diplomacy), Some(snapshot)) = (engines.diplomacy.as_deref_mut(), &self.diplomacy) {
+            diplomacy.restore(snapshot.clone());
+        }
+        if let (Some(colonies), Some(snapshot)) = (engines.colonies.as_deref_mut(), &self.colonies) {
+            colonies.restore(snapshot.clone());
+        }
+        if let (Some(fleets), Some(snapshot)) = (engines.fleets.as_deref_mut(), &self.fleets) {
+            fleets.restore(snapshot.clone());
+        }
+    }
+}
+
+/// Everything needed to re-simulate the campaign from the turn recording
+/// started. Auditor registries are not keyframed; audits replay against
+/// whatever registries the auditor has at rewind time.
+pub struct Recording {
+    keyframe_interval: u64,
+    keyframes: Vec<Keyframe>,
+    commands: BTreeMap<u64, Vec<Command>>,
+    /// Engine hashes after each recorded turn, checked when replaying.
+    turn_hashes: BTreeMap<u64, EngineHashes>,
+    /// Engine hashes as the recording last saw them; anything else at the
+    /// start of a turn means the engines were edited behind its back.
+    expected: EngineHashes,
+}
+
+impl Recording {
+    /// First turn the recording can rewind to.
+    pub fn first_turn(&self) -> u64 {
+        self.keyframes.first().map_or(0, |k| k.turn)
+    }
+
+    /// Commands by the turn they were given after.
+    pub fn commands(&self) -> &BTreeMap<u64, Vec<Command>> {
+        &self.commands
+    }
+
+    pub fn keyframe_turns(&self) -> Vec<u64> {
+        self.keyframes.iter().map(|k| k.turn).collect()
+    }
+
+    pub(crate) fn record(&mut self, turn: u64, command: Command) {
+        self.commands.entry(turn).or_default().push(command);
+    }
+
+    /// Notes an economy change the recording made itself (an invasion),
+    /// so it isn't mistaken for an outside edit.
+    pub(crate) fn economy_changed(&mut self, economy: &IncomeEngine) {
+        self.expected.economy = economy.state_hash();
+    }
+
+    pub(crate) fn before_turn(&mut self, turns: &TurnEngine, engines: &TurnEngines<'_>) {
+        let turn = turns.turn();
+        let last = self.keyframes.last().map_or(0, |k| k.turn);
+        if EngineHashes::of(engines) != self.expected || turn >= last + self.keyframe_interval.max(1) {
+            let applied = self.commands.get(&turn).map_or(0, Vec::len);
+            self.keyframes.push(Keyframe::capture(turns, applied, engines));
+        }
+    }
+
+    pub(crate) fn after_turn(&mut self, turn: u64, engines: &TurnEngines<'_>) {
+        self.expected = EngineHashes::of(engines);
+        self.turn_hashes.insert(turn, self.expected);
+    }
+
+    /// Drops everything after `turn`'s turn ran, so recording continues
+    /// from there on a new timeline.
+    fn truncate(&mut self, turn: u64, engines: &TurnEngines<'_>) {
+        self.commands.split_off(&turn);
+        self.turn_hashes.split_off(&(turn + 1));
+        self.keyframes.retain(|k| k.turn < turn || (k.turn == turn && k.applied == 0));
+        self.expected = EngineHashes::of(engines);
+    }
+}
+
+impl TurnEngine {
+    /// Starts recording from the current state, keyframing the engines
+    /// every `keyframe_interval` turns. Replaces any earlier recording.
+    pub fn start_recording(&mut self, keyframe_interval: u64, engines: &TurnEngines<'_>) {
+        self.recording = Some(Recording {
+            keyframe_interval,
+            keyframes: vec![Keyframe::capture(self, 0, engines)],
+            commands: BTreeMap::new(),
+            turn_hashes: BTreeMap::new(),
+            expected: EngineHashes::of(engines),
+        });
+    }
+
+    pub fn stop_recording(&mut self) -> Option<Recording> {
+        self.recording.take()
+    }
+
+    pub fn recording(&self) -> Option<&Recording> {
+        self.recording.as_ref()
+    }
+
+    /// Puts the campaign back to just after `turn` ran, before any command
+    /// given after it, by re-simulating from the nearest keyframe. Returns
+    /// the reports of the turns re-run. The recorded future is discarded,
+    /// so recording carries on from `turn`. Fails, leaving the engines
+    /// partway, if a re-run turn doesn't reproduce the recorded state.
+    pub fn rewind_to(&mut self, turn: u64, mut engines: ReplayEngines<'_>) -> Result<Vec<TurnReport>, String> {
+        let mut recording = self.recording.take().ok_or("Not recording")?;
+        let result = self.replay(&recording, turn, &mut engines);
+        if result.is_ok() {
+            recording.truncate(turn, &engines.turn_engines());
+        }
+        self.recording = Some(recording);
+        result
+    }
+
+    fn replay(&mut self, recording: &Recording, turn: u64, engines: &mut ReplayEngines<'_>) -> Result<Vec<TurnReport>, String> {
+        if turn > self.turn() {
+            return Err(format!("Turn {} hasn't been played yet (current turn is {})", turn, self.turn()));
+        }
+        let keyframe = recording
+            .keyframes
+            .iter()
+            .rev()
+            .find(|k| k.turn < turn || (k.turn == turn && k.applied == 0))
+            .ok_or_else(|| format!("Turn {} is before the recording started (turn {})", turn, recording.first_turn()))?;
+        keyframe.restore(self, engines);
+
+        let mut reports = Vec::new();
+        let mut skip = keyframe.applied;
+        for current in keyframe.turn..turn {
+            for command in recording.commands.get(&current).into_iter().flatten().skip(skip) {
+                self.apply(command, engines);
+            }
+            skip = 0;
+            let report = self.run_turn(engines.turn_engines());
+            if recording.turn_hashes.get(&report.turn) != Some(&EngineHashes::of(&engines.turn_engines())) {
+                return Err(format!("Replay diverged from the recording at turn {}", report.turn));
+            }
+            reports.push(report);
+        }
+        Ok(reports)
+    }
+
+    fn apply(&mut self, command: &Command, engines: &mut ReplayEngines<'_>) {
+        match command.clone() {
+            Command::SetTreasury { faction, resources } => self.set_treasury(faction, resources),
+            Command::QueueProduction(order) => self.queue_production(order),
+            Command::QueueInvasion(order) => self.queue_invasion(order),
+            Command::Invade(order) => {
+                // It succeeded when recorded; a failure now shows up as a
+                // diverged hash after the turn.
+                let _ = self.invade(&order, engines.topology, engines.economy, engines.diplomacy.as_deref());
+            }
+            Command::QueueAudit { entity_id, entity_type, data } => self.queue_audit(entity_id, entity_type, data),
+            Command::Submit(action) => {
+                self.submit(action);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use void_reckoning_economy::types::{NodeType, SCALE_FACTOR};
+
+    fn planet(id: &str, income: i128) -> EconomicNode {
+        EconomicNode {
+            id: id.into(),
+            owner_faction: "Imperium".into(),
+            node_type: NodeType::Planet,
+            base_income: ResourceState { credits: income * SCALE_FACTOR, ..ResourceState::default() },
+            base_upkeep: ResourceState::default(),
+            efficiency_scaled: SCALE_FACTOR,
+            modifiers: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn rewind_re_simulates_commands_and_outside_edits() {
+        let mut topology = GraphTopology::new();
+        topology.add_edge("Terra", "Mars", 1.0);
+        let mut economy = IncomeEngine::new(GlobalEconomicRules::default());
+        economy.add_node(planet("Terra", 100));
+        let mut trade = TradeRouteManager::new();
+        let mut turns = TurnEngine::new("u1".to_string());
+        macro_rules! engines {
+            () => {
+                TurnEngines { topology: &topology, economy: &mut economy, trade: &mut trade, auditor: None, diplomacy: None, colonies: None, fleets: None }
+            };
+        }
+
+        turns.start_recording(100, &engines!());
+        let mut treasuries = Vec::new();
+        for turn in 0..4u64 {
+            turns.queue_production(ProductionOrder {
+                id: format!("ship-{}", turn),
+                faction: "Imperium".into(),
+                item: "Frigate".to_string(),
+                cost: ResourceState { credits: 30 * SCALE_FACTOR, ..ResourceState::default() },
+            });
+            if turn == 2 {
+                // Behind the recording's back: forces a keyframe.
+                economy.add_node(planet("Mars", 50));
+            }
+            turns.run_turn(engines!());
+            treasuries.push(turns.treasury("Imperium"));
+        }
+        assert_eq!(turns.recording().unwrap().keyframe_turns(), vec![0, 2]);
+
+        let engines = ReplayEngines { topology: &mut topology, economy: &mut economy, trade: &mut trade, auditor: None, diplomacy: None, colonies: None, fleets: None };
+        let reports = turns.rewind_to(3, engines).unwrap();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(turns.turn(), 3);
+        assert_eq!(turns.treasury("Imperium"), treasuries[2]);
+        assert_eq!(economy.nodes().len(), 2);
+
+        let engines = ReplayEngines { topology: &mut topology, economy: &mut economy, trade: &mut trade, auditor: None, diplomacy: None, colonies: None, fleets: None };
+        assert_eq!(turns.rewind_to(1, engines).unwrap().len(), 1);
+        assert_eq!(turns.treasury("Imperium"), treasuries[0]);
+        assert_eq!(economy.nodes().len(), 1);
+        assert_eq!(turns.recording().unwrap().commands().len(), 1);
+        assert!(turns.production_queue().is_empty());
+    }
+}
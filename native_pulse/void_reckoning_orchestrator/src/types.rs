@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use crate::commands::ActionOutcome;
+use crate::invasion::InvasionOutcome;
+use crate::victory::VictoryReport;
+use void_reckoning_auditor::types::ValidationReport;
+use void_reckoning_colony::ColonyTurnReport;
+use void_reckoning_economy::logistics::SupplyReport;
+use void_reckoning_economy::types::ResourceState;
+use void_reckoning_shared::ids::FactionId;
+
+/// Something a faction pays for out of its treasury. Orders are paid in
+/// full, in queue order, once the treasury covers every resource.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProductionOrder {
+    pub id: String,
+    pub faction: FactionId,
+    #[serde(default)]
+    pub item: String,
+    pub cost: ResourceState,
+}
+
+/// One faction's books for a turn.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FactionTurn {
+    /// Node income plus `trade_income`.
+    pub income: ResourceState,
+    /// Trade at systems this faction owns a node in.
+    pub trade_income: ResourceState,
+    pub upkeep: ResourceState,
+    pub production_spent: ResourceState,
+    /// Paid to an overlord, taken from positive net income.
+    pub tribute_paid: ResourceState,
+    pub tribute_received: ResourceState,
+    /// Treasury after upkeep, tribute, production and colony bootstrap costs.
+    pub treasury: ResourceState,
+    pub is_insolvent: bool,
+    /// Present when logistics rules are set. Upkeep above leaves out what
+    /// didn't arrive.
+    #[serde(default)]
+    pub supply: Option<SupplyReport>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TurnReport {
+    pub turn: u64,
+    /// Trace every phase's span and event belongs to.
+    pub trace_id: String,
+    pub factions: BTreeMap<FactionId, FactionTurn>,
+    /// Trade at systems no economic node sits in.
+    pub unattributed_trade: ResourceState,
+    pub severed_routes: usize,
+    /// Routes cut by an embargo or war (counted in `severed_routes` too).
+    pub embargoed_routes: usize,
+    pub invasions: Vec<InvasionOutcome>,
+    /// Ids of invasion orders that couldn't be carried out.
+    pub rejected_invasions: Vec<String>,
+    pub completed: Vec<ProductionOrder>,
+    /// Ids of orders still waiting for funds.
+    pub stalled: Vec<String>,
+    /// Present when an auditor ran the queued audits.
+    pub audit: Option<ValidationReport>,
+    /// Present when a colony engine ran.
+    #[serde(default)]
+    pub colonization: Option<ColonyTurnReport>,
+    /// Present when victory rules are set.
+    #[serde(default)]
+    pub victory: Option<VictoryReport>,
+    /// Actions resolved at the start of the turn, in submission order.
+    #[serde(default)]
+    pub actions: Vec<ActionOutcome>,
+}
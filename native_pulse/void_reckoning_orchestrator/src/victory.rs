@@ -0,0 +1,306 @@
+//! Victory and defeat: configurable conditions checked at the end of every
+//! turn against planets held, income, treasuries and solvency, with each
+//! faction's progress toward them and, once one is met, the verdict.
+
+use crate::types::TurnReport;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use void_reckoning_economy::engine::IncomeEngine;
+use void_reckoning_economy::types::{NodeType, ResourceState, SCALE_FACTOR};
+use void_reckoning_shared::ids::FactionId;
+
+/// A way to win. Externally tagged: `{"Conquest": {"share": 0.6}}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Condition {
+    /// Hold `share` of all planets.
+    Conquest { share: f32 },
+    /// Take `share` of all income for `turns` turns running.
+    EconomicDominance {
+        share: f32,
+        #[serde(default = "one_turn")]
+        turns: u32,
+    },
+    /// Stockpile `research` in the treasury.
+    TechAscension { research: f64 },
+    /// Still hold a planet on turn `turns`.
+    Survival { turns: u64 },
+    /// Be the only faction left undefeated.
+    LastStanding,
+}
+
+fn one_turn() -> u32 {
+    1
+}
+
+impl Condition {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Condition::Conquest { .. } => "Conquest",
+            Condition::EconomicDominance { .. } => "EconomicDominance",
+            Condition::TechAscension { .. } => "TechAscension",
+            Condition::Survival { .. } => "Survival",
+            Condition::LastStanding => "LastStanding",
+        }
+    }
+}
+
+/// A way to lose. A defeated faction can no longer win.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Defeat {
+    /// Lose every planet, having held one.
+    Elimination,
+    /// End upkeep insolvent `turns` turns running.
+    Bankruptcy { turns: u32 },
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VictoryRules {
+    #[serde(default)]
+    pub victory: Vec<Condition>,
+    #[serde(default)]
+    pub defeat: Vec<Defeat>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Verdict {
+    pub winner: FactionId,
+    /// `Condition::name` of the condition met.
+    pub condition: String,
+    pub turn: u64,
+}
+
+/// A faction passing a quarter of the way to a condition.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Milestone {
+    pub faction: FactionId,
+    pub condition: String,
+    /// 25, 50, 75 or 100.
+    pub percent: u8,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Standing {
+    /// Progress toward each victory condition by name, 0 to 1.
+    pub progress: BTreeMap<String, f32>,
+    /// Why the faction was defeated, if it was.
+    pub defeated: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VictoryReport {
+    pub standings: BTreeMap<FactionId, Standing>,
+    pub milestones: Vec<Milestone>,
+    /// Factions defeated this turn.
+    pub defeated: Vec<FactionId>,
+    /// Set from the turn a condition is first met on.
+    pub verdict: Option<Verdict>,
+}
+
+/// Rules plus what they need remembered between turns: streaks, who has
+/// held a planet, who is out, and the verdict once there is one.
+#[derive(Debug, Clone, Default)]
+pub struct VictoryTracker {
+    rules: VictoryRules,
+    dominance_streaks: BTreeMap<FactionId, u32>,
+    insolvency_streaks: BTreeMap<FactionId, u32>,
+    held_planets: BTreeSet<FactionId>,
+    defeated: BTreeMap<FactionId, String>,
+    milestones: BTreeMap<(FactionId, String), u8>,
+    verdict: Option<Verdict>,
+}
+
+impl VictoryTracker {
+    pub fn new(rules: VictoryRules) -> Self {
+        Self { rules, ..Self::default() }
+    }
+
+    pub fn rules(&self) -> &VictoryRules {
+        &self.rules
+    }
+
+    pub fn verdict(&self) -> Option<&Verdict> {
+        self.verdict.as_ref()
+    }
+
+    /// Updates streaks and defeats for the turn `report` describes and
+    /// reports every faction's standing. The first condition met (in rule
+    /// order, then faction name order) decides the verdict; later turns
+    /// keep it.
+    pub fn evaluate(&mut self, report: &TurnReport, economy: &IncomeEngine, treasuries: &BTreeMap<FactionId, ResourceState>) -> VictoryReport {
+        let mut planets: BTreeMap<FactionId, usize> = BTreeMap::new();
+        for node in economy.nodes().iter().filter(|n| n.node_type == NodeType::Planet) {
+            *planets.entry(node.owner_faction.clone()).or_default() += 1;
+        }
+        let total_planets: usize = planets.values().sum();
+        self.held_planets.extend(planets.keys().cloned());
+        let income: BTreeMap<&str, f64> =
+            report.factions.iter().map(|(name, books)| (name.as_str(), credits(&books.income).max(0.0))).collect();
+        let total_income: f64 = income.values().sum();
+
+        let factions: BTreeSet<FactionId> = planets
+            .keys()
+            .cloned()
+            .chain(report.factions.keys().cloned())
+            .chain(treasuries.keys().cloned())
+            .chain(self.held_planets.iter().cloned())
+            .collect();
+
+        let mut victory = VictoryReport::default();
+        for faction in &factions {
+            let insolvent = report.factions.get(faction).is_some_and(|books| books.is_insolvent);
+            let streak = self.insolvency_streaks.entry(faction.clone()).or_default();
+            *streak = if insolvent { *streak + 1 } else { 0 };
+            let streak = *streak;
+            if self.defeated.contains_key(faction) {
+                continue;
+            }
+            let held = planets.get(faction.as_str()).copied().unwrap_or(0);
+            let reason = self.rules.defeat.iter().find_map(|defeat| match defeat {
+                Defeat::Elimination if held == 0 && self.held_planets.contains(faction) => Some("Elimination"),
+                Defeat::Bankruptcy { turns } if streak >= *turns => Some("Bankruptcy"),
+                _ => None,
+            });
+            if let Some(reason) = reason {
+                self.defeated.insert(faction.clone(), reason.to_string());
+                victory.defeated.push(faction.clone());
+            }
+        }
+
+        let rivals = factions.len().saturating_sub(1);
+        let mut met = Vec::new();
+        for faction in &factions {
+            let mut standing = Standing { defeated: self.defeated.get(faction).cloned(), ..Standing::default() };
+            let held = planets.get(faction.as_str()).copied().unwrap_or(0);
+            for condition in &self.rules.victory {
+                let progress = match condition {
+                    Condition::Conquest { share } => ratio(held as f64 / total_planets.max(1) as f64, *share as f64),
+                    Condition::EconomicDominance { share, turns } => {
+                        let taken = income.get(faction.as_str()).copied().unwrap_or(0.0) / total_income.max(f64::MIN_POSITIVE);
+                        let streak = self.dominance_streaks.entry(faction.clone()).or_default();
+                        *streak = if taken >= *share as f64 && total_income > 0.0 { *streak + 1 } else { 0 };
+                        let turns = (*turns).max(1) as f32;
+                        match *streak {
+                            0 => ratio(taken, *share as f64) / turns,
+                            streak => (streak as f32 / turns).min(1.0),
+                        }
+                    }
+                    Condition::TechAscension { research } => {
+                        let stock = treasuries.get(faction).map_or(0.0, |t| t.research as f64 / SCALE_FACTOR as f64);
+                        ratio(stock, *research)
+                    }
+                    Condition::Survival { turns } => match held {
+                        0 => 0.0,
+                        _ => ratio(report.turn as f64, *turns as f64),
+                    },
+                    Condition::LastStanding => {
+                        let out = self.defeated.keys().filter(|f| *f != faction).count();
+                        if rivals == 0 { 0.0 } else { out as f32 / rivals as f32 }
+                    }
+                };
+                let progress = if standing.defeated.is_some() { 0.0 } else { progress };
+                standing.progress.insert(condition.name().to_string(), progress);
+
+                let percent = ((progress * 4.0).floor() as u8).min(4) * 25;
+                let reached = self.milestones.entry((faction.clone(), condition.name().to_string())).or_default();
+                if percent > *reached {
+                    *reached = percent;
+                    victory.milestones.push(Milestone { faction: faction.clone(), condition: condition.name().to_string(), percent });
+                }
+                if progress >= 1.0 {
+                    met.push((condition.name(), faction.clone()));
+                }
+            }
+            victory.standings.insert(faction.clone(), standing);
+        }
+
+        if self.verdict.is_none() {
+            let rule_order = |name: &str| self.rules.victory.iter().position(|c| c.name() == name);
+            met.sort_by(|a, b| rule_order(a.0).cmp(&rule_order(b.0)).then_with(|| a.1.cmp(&b.1)));
+            self.verdict = met
+                .into_iter()
+                .next()
+                .map(|(condition, winner)| Verdict { winner, condition: condition.to_string(), turn: report.turn });
+        }
+        victory.verdict = self.verdict.clone();
+        victory
+    }
+}
+
+fn credits(resources: &ResourceState) -> f64 {
+    resources.credits as f64 / SCALE_FACTOR as f64
+}
+
+/// `value / target`, capped at 1; a target of 0 or less is always met.
+fn ratio(value: f64, target: f64) -> f32 {
+    if target <= 0.0 {
+        return 1.0;
+    }
+    (value / target).clamp(0.0, 1.0) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FactionTurn;
+    use void_reckoning_economy::types::{EconomicNode, GlobalEconomicRules};
+
+    fn planet(id: &str, owner: &str) -> EconomicNode {
+        EconomicNode {
+            id: id.into(),
+            owner_faction: owner.into(),
+            node_type: NodeType::Planet,
+            base_income: ResourceState::default(),
+            base_upkeep: ResourceState::default(),
+            efficiency_scaled: SCALE_FACTOR,
+            modifiers: Vec::new(),
+        }
+    }
+
+    fn report(turn: u64, insolvent: &[&str]) -> TurnReport {
+        TurnReport {
+            turn,
+            trace_id: String::new(),
+            factions: insolvent
+                .iter()
+                .map(|&f| (f.into(), FactionTurn { is_insolvent: true, ..FactionTurn::default() }))
+                .collect(),
+            unattributed_trade: ResourceState::default(),
+            severed_routes: 0,
+            embargoed_routes: 0,
+            invasions: Vec::new(),
+            rejected_invasions: Vec::new(),
+            completed: Vec::new(),
+            stalled: Vec::new(),
+            audit: None,
+            colonization: None,
+            victory: None,
+            actions: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn conquest_wins_after_rivals_fall() {
+        let mut economy = IncomeEngine::new(GlobalEconomicRules::default());
+        for (id, owner) in [("Terra", "Imperium"), ("Mars", "Imperium"), ("Kor", "Orks"), ("Fenris", "Eldar")] {
+            economy.add_node(planet(id, owner));
+        }
+        let rules = VictoryRules {
+            victory: vec![Condition::Conquest { share: 0.75 }, Condition::LastStanding],
+            defeat: vec![Defeat::Elimination, Defeat::Bankruptcy { turns: 2 }],
+        };
+        let mut tracker = VictoryTracker::new(rules);
+        let treasuries = BTreeMap::new();
+
+        let first = tracker.evaluate(&report(1, &["Eldar"]), &economy, &treasuries);
+        assert!(first.verdict.is_none());
+        assert_eq!(first.standings["Imperium"].progress["Conquest"], 2.0 / 3.0);
+        assert!(first.milestones.contains(&Milestone { faction: "Imperium".into(), condition: "Conquest".to_string(), percent: 50 }));
+
+        economy.node_mut("Kor").unwrap().owner_faction = "Imperium".into();
+        let second = tracker.evaluate(&report(2, &["Eldar"]), &economy, &treasuries);
+        assert_eq!(second.defeated, vec!["Eldar", "Orks"]);
+        assert_eq!(second.standings["Eldar"].defeated.as_deref(), Some("Bankruptcy"));
+        let verdict = second.verdict.unwrap();
+        assert_eq!((verdict.winner.as_str(), verdict.condition.as_str(), verdict.turn), ("Imperium", "Conquest", 2));
+    }
+}
@@ -0,0 +1,127 @@
+//! Least-recently-used cache of `find_path` results. Trade routes ask for
+//! the same pairs every turn; the topology drops the whole cache whenever
+//! the graph changes, so an entry is never stale.
+
+use crate::MovementProfile;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+pub(crate) type PathKey = (String, String, MovementProfile);
+pub(crate) type PathResult = Option<(Vec<String>, f32)>;
+
+/// Entries `GraphTopology::new` caches before evicting.
+pub const DEFAULT_PATH_CACHE_CAPACITY: usize = 1024;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PathCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// Times a change to the graph emptied the cache.
+    pub invalidations: u64,
+    pub entries: usize,
+    /// 0 when caching is off.
+    pub capacity: usize,
+}
+
+#[derive(Clone)]
+pub(crate) struct PathCache {
+    entries: HashMap<PathKey, (PathResult, u64)>,
+    /// Each entry's key by the tick it was last used on, oldest first.
+    recency: BTreeMap<u64, PathKey>,
+    /// Bumped on every hit and insert, so no two entries share a tick.
+    clock: u64,
+    stats: PathCacheStats,
+}
+
+impl PathCache {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            clock: 0,
+            stats: PathCacheStats { capacity, ..PathCacheStats::default() },
+        }
+    }
+
+    pub(crate) fn get(&mut self, key: &PathKey) -> Option<PathResult> {
+        match self.entries.get_mut(key) {
+            Some((result, used)) => {
+                self.clock += 1;
+                if let Some(key) = self.recency.remove(used) {
+                    self.recency.insert(self.clock, key);
+                }
+                *used = self.clock;
+                self.stats.hits += 1;
+                Some(result.clone())
+            }
+            None => {
+                self.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    pub(crate) fn insert(&mut self, key: PathKey, result: PathResult) {
+        if self.stats.capacity == 0 {
+            return;
+        }
+        self.clock += 1;
+        self.recency.insert(self.clock, key.clone());
+        if let Some((_, used)) = self.entries.insert(key, (result, self.clock)) {
+            self.recency.remove(&used);
+        }
+        self.evict_to(self.stats.capacity);
+    }
+
+    /// Drops the least recently used entries until at most `capacity` remain.
+    fn evict_to(&mut self, capacity: usize) {
+        while self.entries.len() > capacity {
+            let Some((_, oldest)) = self.recency.pop_first() else { break };
+            self.entries.remove(&oldest);
+        }
+    }
+
+    pub(crate) fn invalidate(&mut self) {
+        if !self.entries.is_empty() {
+            self.entries.clear();
+            self.recency.clear();
+            self.stats.invalidations += 1;
+        }
+    }
+
+    /// Evicts the least recently used entries down to `capacity`.
+    pub(crate) fn set_capacity(&mut self, capacity: usize) {
+        self.stats.capacity = capacity;
+        self.evict_to(capacity);
+    }
+
+    pub(crate) fn stats(&self) -> PathCacheStats {
+        PathCacheStats { entries: self.entries.len(), ..self.stats }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(to: &str) -> PathKey {
+        ("A".to_string(), to.to_string(), MovementProfile::Space)
+    }
+
+    #[test]
+    fn evicts_the_least_recently_used() {
+        let mut cache = PathCache::new(2);
+        cache.insert(key("B"), None);
+        cache.insert(key("C"), None);
+        assert!(cache.get(&key("B")).is_some());
+        cache.insert(key("D"), None);
+        assert!(cache.get(&key("C")).is_none());
+        assert!(cache.get(&key("B")).is_some() && cache.get(&key("D")).is_some());
+
+        // Re-inserting an entry refreshes it rather than taking a new slot
+        cache.insert(key("B"), Some((vec!["A".to_string(), "B".to_string()], 1.0)));
+        cache.set_capacity(1);
+        assert_eq!(cache.stats().entries, 1);
+        assert!(cache.get(&key("B")).is_some_and(|r| r.is_some()));
+    }
+}
@@ -0,0 +1,107 @@
+//! The topology as Graphviz DOT or GraphML, for looking at the live map
+//! in Graphviz, yEd or Gephi when a route comes out wrong. Nodes carry
+//! their terrain, position and danger; lanes their current weight (with
+//! modifiers) and, where described, kind and owner.
+
+use crate::{GraphTopology, LaneInfo};
+use petgraph::visit::EdgeRef;
+use std::fmt::Write;
+
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Weights as both formats spell them; closed lanes are infinite.
+fn number(value: f32) -> String {
+    match value {
+        v if v == f32::INFINITY => "INF".to_string(),
+        v if v == f32::NEG_INFINITY => "-INF".to_string(),
+        v => v.to_string(),
+    }
+}
+
+impl GraphTopology {
+    fn lane(&self, e: petgraph::graph::EdgeReference<f32>) -> (&str, &str, Option<&LaneInfo>) {
+        let (from, to) = (&self.graph[e.source()].id, &self.graph[e.target()].id);
+        (from, to, self.lanes.get(&(from.clone(), to.clone())))
+    }
+
+    /// Graphviz `digraph`: one node per system, labelled with its terrain,
+    /// pinned at its position if it has one; one edge per lane, labelled
+    /// with its weight and dashed if closed. Undirected topologies show
+    /// both directions of every lane.
+    pub fn export_dot(&self) -> String {
+        let mut out = String::from("digraph topology {\n");
+        for node in self.graph.node_weights() {
+            let terrain = self.movement.terrain_name(node.terrain);
+            let _ = write!(out, "  \"{}\" [label=\"{}\\n{}\"", dot_escape(&node.id), dot_escape(&node.id), dot_escape(terrain));
+            let _ = write!(out, ", terrain=\"{}\"", dot_escape(terrain));
+            if let Some((x, y)) = node.position {
+                let _ = write!(out, ", pos=\"{},{}!\"", x, y);
+            }
+            if node.danger > 0.0 {
+                let _ = write!(out, ", danger={}", node.danger);
+            }
+            out.push_str("];\n");
+        }
+        for e in self.graph.edge_references() {
+            let (from, to, info) = self.lane(e);
+            let weight = number(*e.weight());
+            let _ = write!(out, "  \"{}\" -> \"{}\" [label=\"{}\", weight=\"{}\"", dot_escape(from), dot_escape(to), weight, weight);
+            if e.weight().is_infinite() {
+                out.push_str(", style=dashed");
+            }
+            if let Some(info) = info {
+                let _ = write!(out, ", kind=\"{:?}\"", info.kind);
+                if let Some(owner) = &info.owner {
+                    let _ = write!(out, ", owner=\"{}\"", dot_escape(owner));
+                }
+            }
+            out.push_str("];\n");
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// GraphML with `terrain`, `x`, `y` and `danger` on nodes and
+    /// `weight`, `kind` and `owner` on edges; `x`/`y` are left out for
+    /// nodes without a position.
+    pub fn export_graphml(&self) -> String {
+        let mut out = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+            "  <key id=\"terrain\" for=\"node\" attr.name=\"terrain\" attr.type=\"string\"/>\n",
+            "  <key id=\"x\" for=\"node\" attr.name=\"x\" attr.type=\"double\"/>\n",
+            "  <key id=\"y\" for=\"node\" attr.name=\"y\" attr.type=\"double\"/>\n",
+            "  <key id=\"danger\" for=\"node\" attr.name=\"danger\" attr.type=\"double\"/>\n",
+            "  <key id=\"weight\" for=\"edge\" attr.name=\"weight\" attr.type=\"double\"/>\n",
+            "  <key id=\"kind\" for=\"edge\" attr.name=\"kind\" attr.type=\"string\"/>\n",
+            "  <key id=\"owner\" for=\"edge\" attr.name=\"owner\" attr.type=\"string\"/>\n",
+            "  <graph id=\"topology\" edgedefault=\"directed\">\n",
+        ));
+        for node in self.graph.node_weights() {
+            let _ = writeln!(out, "    <node id=\"{}\">", xml_escape(&node.id));
+            let _ = writeln!(out, "      <data key=\"terrain\">{}</data>", xml_escape(self.movement.terrain_name(node.terrain)));
+            if let Some((x, y)) = node.position {
+                let _ = writeln!(out, "      <data key=\"x\">{}</data>\n      <data key=\"y\">{}</data>", x, y);
+            }
+            let _ = writeln!(out, "      <data key=\"danger\">{}</data>\n    </node>", node.danger);
+        }
+        for e in self.graph.edge_references() {
+            let (from, to, info) = self.lane(e);
+            let _ = writeln!(out, "    <edge source=\"{}\" target=\"{}\">", xml_escape(from), xml_escape(to));
+            let _ = writeln!(out, "      <data key=\"weight\">{}</data>", number(*e.weight()));
+            let _ = writeln!(out, "      <data key=\"kind\">{:?}</data>", info.map(|i| i.kind).unwrap_or_default());
+            if let Some(owner) = info.and_then(|i| i.owner.as_deref()) {
+                let _ = writeln!(out, "      <data key=\"owner\">{}</data>", xml_escape(owner));
+            }
+            out.push_str("    </edge>\n");
+        }
+        out.push_str("  </graph>\n</graphml>\n");
+        out
+    }
+}
@@ -1,170 +1,2361 @@
 use petgraph::graph::{DiGraph, NodeIndex};
-use petgraph::algo::astar;
+use petgraph::algo::{astar, dijkstra};
+use petgraph::unionfind::UnionFind;
 use petgraph::visit::EdgeRef;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BinaryHeap, HashMap, HashSet};
+use std::mem::size_of;
+use std::sync::{Mutex, OnceLock};
+use void_reckoning_ids::{FactionId, SystemId};
+use void_reckoning_hash::{extend_hash, FNV_OFFSET};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+mod cache;
+mod export;
+mod movement;
+mod replan;
+
+use cache::{PathCache, PathKey, PathResult};
+use movement::MovementRules;
+pub use cache::{PathCacheStats, DEFAULT_PATH_CACHE_CAPACITY};
+pub use replan::Replanner;
+
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Version of the `TopologySnapshot` layout; bumped on incompatible changes.
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum TerrainType {
     Space,
     Plains,
     Forest, 
     Mountain,
     Water,
+    /// The nth terrain registered with `GraphTopology::register_terrain`.
+    Custom(u16),
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum MovementProfile {
     Space,
     Ground,
     Hover,
+    /// Moves as `Space`, but entering a node also costs its danger, so
+    /// routes detour around threats when the detour is cheaper.
+    Cautious,
+    /// The nth profile registered with `GraphTopology::register_profile`.
+    Custom(u16),
+}
+
+/// A named factor on the cost of a lane (every `from` -> `to` edge), so
+/// whatever slowed a lane down can be found and taken back off.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LaneModifier {
+    pub from: SystemId,
+    pub to: SystemId,
+    /// What applied it: a hazard id, a script rule, a Python plugin.
+    pub source: String,
+    /// Multiplies the lane's unmodified weight; should be positive.
+    pub factor: f32,
+}
+
+/// How a lane is crossed; drives that can only use some kinds route with
+/// `find_path_on_lanes`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LaneKind {
+    #[default]
+    Hyperlane,
+    Wormhole,
+    Slipstream,
+}
+
+impl LaneKind {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "Hyperlane" => Some(LaneKind::Hyperlane),
+            "Wormhole" => Some(LaneKind::Wormhole),
+            "Slipstream" => Some(LaneKind::Slipstream),
+            _ => None,
+        }
+    }
+}
+
+/// What a lane is beyond its cost. Lanes nobody described are plain
+/// hyperlanes with no capacity limit or owner.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LaneInfo {
+    pub kind: LaneKind,
+    /// Ships (or fleets) the lane carries per turn, for congestion models
+    /// to enforce; None for unlimited.
+    #[serde(default)]
+    pub capacity: Option<u32>,
+    /// Faction controlling the lane, if any.
+    #[serde(default)]
+    pub owner: Option<FactionId>,
+}
+
+/// What one hop of a route cost and why, from `explain_path`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PathHop {
+    pub from: String,
+    pub to: String,
+    /// The lane's weight before modifiers.
+    pub base_cost: f32,
+    /// `(source, factor)` of every modifier on the lane, in the order set.
+    pub modifiers: Vec<(String, f32)>,
+    /// `base_cost` with the modifiers applied.
+    pub lane_cost: f32,
+    /// Terrain of the system entered.
+    pub terrain: String,
+    /// What the profile multiplies `lane_cost` by for that terrain.
+    pub terrain_multiplier: f32,
+    /// Danger of the system entered, added for `Cautious` and 0 otherwise.
+    pub danger: f32,
+    pub cost: f32,
+    /// Cost of the route up to and including this hop.
+    pub cumulative: f32,
+}
+
+/// Lane metrics beyond cost for weighted routing. Lanes without any
+/// take as long as they cost and carry no risk of their own.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct LaneMetrics {
+    /// Turns (or any time unit) to cross; None for the lane's cost.
+    #[serde(default)]
+    pub time: Option<f32>,
+    /// Risk of crossing, added to the entered system's danger.
+    #[serde(default)]
+    pub risk: f32,
+}
+
+impl LaneMetrics {
+    /// Errors unless time (if set) and risk are finite and not negative.
+    pub fn validate(&self) -> Result<(), String> {
+        let valid = |value: f32| value.is_finite() && value >= 0.0;
+        match (self.time.is_none_or(valid), valid(self.risk)) {
+            (false, _) => Err(format!("lane time must be finite and not negative, got {:?}", self.time)),
+            (_, false) => Err(format!("lane risk must be finite and not negative, got {}", self.risk)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// What a route minimises: `cost` times the profile's lane cost, plus
+/// `time` times travel time, plus `risk` times lane risk and danger.
+/// Negative weights count as 0.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RouteWeights {
+    pub cost: f32,
+    pub time: f32,
+    pub risk: f32,
+}
+
+impl Default for RouteWeights {
+    fn default() -> Self {
+        Self { cost: 1.0, time: 0.0, risk: 0.0 }
+    }
 }
 
 /// A lightweight wrapper around petgraph to manage the universe topology.
 pub struct GraphTopology {
     graph: DiGraph<NodeData, f32>,
-    node_map: HashMap<String, NodeIndex>,
+    node_map: HashMap<SystemId, NodeIndex>,
     pub run_id: String,
+    /// In the order they were first set; the graph holds the modified
+    /// weights so searches don't look them up.
+    modifiers: Vec<LaneModifier>,
+    /// Unmodified weight of every lane with modifiers on it.
+    base_weights: BTreeMap<(SystemId, SystemId), f32>,
+    /// Cost per unit of distance for the A* heuristic, worked out on the
+    /// first search after the graph changes.
+    heuristic_scale: OnceLock<f32>,
+    /// Recent `find_path` results, emptied by every change to the graph.
+    path_cache: Mutex<PathCache>,
+    /// Whether `add_edge`, `set_edge_weight` and `remove_edge` work on
+    /// both directions of a lane.
+    undirected: bool,
+    /// Registered terrains and movement profiles; kept by `clear`.
+    movement: MovementRules,
+    /// Kind, capacity and owner of every lane that isn't a plain hyperlane.
+    lanes: BTreeMap<(SystemId, SystemId), LaneInfo>,
+    /// Time and risk of every lane that has them.
+    metrics: BTreeMap<(SystemId, SystemId), LaneMetrics>,
+    /// Bumped when node indices move or costs change wholesale, which
+    /// `Replanner`s answer with a fresh search.
+    layout: u64,
+    /// `(from, to)` of every lane whose cost changed since `layout` was
+    /// last bumped, for `Replanner`s to repair around.
+    edits: Vec<(NodeIndex, NodeIndex)>,
 }
 
+/// Lane edits kept for `Replanner`s before the log is dropped and they
+/// start over.
+const MAX_EDITS: usize = 4096;
+
 #[derive(Clone)]
 pub struct NodeData {
-    pub id: String,
+    pub id: SystemId,
     pub terrain: TerrainType,
+    /// Map coordinates; searches use straight-line distance to the goal as
+    /// a heuristic once every node has them.
+    pub position: Option<(f32, f32)>,
+    /// Extra cost for `Cautious` movement into the node; never negative.
+    pub danger: f32,
+    /// Faction holding the system, whose zone of control covers it and
+    /// its neighbours.
+    pub owner: Option<FactionId>,
+    /// Fought over; in everyone's zone of control.
+    pub contested: bool,
+}
+
+/// What entering a hostile zone of control does to a route in
+/// `find_path_zoc`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ZocRule {
+    /// Movement stops there, so a route only enters a zone at its end
+    /// (leaving the start is always allowed).
+    EndsMovement,
+    /// Entering a system in a zone costs this much more.
+    Penalty(f32),
+}
+
+fn lane_key(from_id: &str, to_id: &str) -> (SystemId, SystemId) {
+    (SystemId::new(from_id), SystemId::new(to_id))
+}
+
+/// A registered movement profile's cost multiplier per terrain name.
+pub type TerrainCosts = BTreeMap<String, f32>;
+
+/// Serializable copy of a `GraphTopology`: nodes and edges in insertion order.
+/// JSON writes infinite costs (closed lanes, impassable terrain) as null,
+/// which reads back as infinite.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopologySnapshot {
+    pub nodes: Vec<(SystemId, TerrainType)>,
+    #[serde(deserialize_with = "closed_lanes")]
+    pub edges: Vec<(SystemId, SystemId, f32)>,
+    pub run_id: String,
+    #[serde(default)]
+    pub modifiers: Vec<LaneModifier>,
+    /// Unmodified weights of the lanes `modifiers` touch; `edges` holds
+    /// the modified ones.
+    #[serde(default, deserialize_with = "closed_lanes")]
+    pub base_weights: Vec<(SystemId, SystemId, f32)>,
+    /// `(id, x, y)` of every node with coordinates.
+    #[serde(default)]
+    pub positions: Vec<(SystemId, f32, f32)>,
+    /// `(id, danger)` of every node with a danger score.
+    #[serde(default)]
+    pub dangers: Vec<(SystemId, f32)>,
+    #[serde(default)]
+    pub undirected: bool,
+    /// Registered terrain names; `TerrainType::Custom(n)` is the nth.
+    #[serde(default)]
+    pub terrains: Vec<String>,
+    /// Registered movement profiles as `(name, terrain costs)`, in order.
+    #[serde(default, deserialize_with = "impassable_terrain")]
+    pub profiles: Vec<(String, TerrainCosts)>,
+    /// `(from, to, info)` of every lane that isn't a plain hyperlane.
+    #[serde(default)]
+    pub lanes: Vec<(SystemId, SystemId, LaneInfo)>,
+    /// `(id, faction)` of every owned node.
+    #[serde(default)]
+    pub owners: Vec<(SystemId, FactionId)>,
+    /// Ids of contested nodes.
+    #[serde(default)]
+    pub contested: Vec<SystemId>,
+    /// `(from, to, metrics)` of every lane with time or risk.
+    #[serde(default)]
+    pub metrics: Vec<(SystemId, SystemId, LaneMetrics)>,
+}
+
+impl TopologySnapshot {
+    /// Checks that every lane description, metric, modifier and base
+    /// weight names a lane in `edges`.
+    pub fn validate(&self) -> Result<(), String> {
+        let lanes: HashSet<(&str, &str)> = self.edges.iter().map(|(from, to, _)| (from.as_str(), to.as_str())).collect();
+        let pairs = self
+            .lanes
+            .iter()
+            .map(|(from, to, _)| ("lane info", from, to))
+            .chain(self.metrics.iter().map(|(from, to, _)| ("lane metrics", from, to)))
+            .chain(self.modifiers.iter().map(|m| ("lane modifier", &m.from, &m.to)))
+            .chain(self.base_weights.iter().map(|(from, to, _)| ("base weight", from, to)));
+        for (what, from, to) in pairs {
+            if !lanes.contains(&(from.as_str(), to.as_str())) {
+                return Err(format!("{what} for unknown lane {from} -> {to}"));
+            }
+        }
+        self.metrics.iter().try_for_each(|(from, to, metrics)| metrics.validate().map_err(|e| format!("{from} -> {to}: {e}")))
+    }
+}
+
+// Binary formats (bincode campaign chunks) store infinities as they are;
+// only text formats need the null read back.
+fn closed_lanes<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Vec<(SystemId, SystemId, f32)>, D::Error> {
+    if !deserializer.is_human_readable() {
+        return Deserialize::deserialize(deserializer);
+    }
+    let lanes: Vec<(SystemId, SystemId, Option<f32>)> = Deserialize::deserialize(deserializer)?;
+    Ok(lanes.into_iter().map(|(from, to, weight)| (from, to, weight.unwrap_or(f32::INFINITY))).collect())
+}
+
+fn impassable_terrain<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Vec<(String, TerrainCosts)>, D::Error> {
+    if !deserializer.is_human_readable() {
+        return Deserialize::deserialize(deserializer);
+    }
+    let profiles: Vec<(String, BTreeMap<String, Option<f32>>)> = Deserialize::deserialize(deserializer)?;
+    Ok(profiles
+        .into_iter()
+        .map(|(name, costs)| (name, costs.into_iter().map(|(terrain, cost)| (terrain, cost.unwrap_or(f32::INFINITY))).collect()))
+        .collect())
 }
 
+/// Size of a `GraphTopology`; `sync_topology` rebuilds it, so steady growth
+/// across turns means nodes are only ever added.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryStats {
+    pub nodes: usize,
+    pub edges: usize,
+    /// Rough heap footprint of the graph and its id index.
+    pub approx_bytes: usize,
+}
+
+/// A route request: start, goal and movement profile.
+pub type PathQuery = (String, String, Option<String>);
+
+/// Everything a route can be asked to honour beyond its profile, for
+/// `find_path_with`; the default asks for a plain `find_path`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RouteOptions {
+    /// Systems to route around; the start and end never are.
+    pub avoid: Vec<String>,
+    /// Kinds of lane the route may use; None for every kind.
+    pub lane_kinds: Option<Vec<LaneKind>>,
+    /// With `weights`, `max_cost` bounds the weighted cost.
+    pub limits: PathLimits,
+    /// None to minimise cost alone.
+    pub weights: Option<RouteWeights>,
+}
+
+/// How far a fleet can go on one route: jumps its drive allows and the
+/// fuel (route cost) it carries. None for no limit.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PathLimits {
+    pub max_hops: Option<usize>,
+    pub max_cost: Option<f32>,
+}
+
+impl PathLimits {
+    pub fn is_unlimited(&self) -> bool {
+        self.max_hops.is_none() && self.max_cost.is_none()
+    }
+
+    /// Whether a route of `hops` lanes costing `cost` is within them.
+    pub fn allows(&self, hops: usize, cost: f32) -> bool {
+        self.max_hops.is_none_or(|max| hops <= max) && self.max_cost.is_none_or(|max| cost <= max)
+    }
+}
+
+/// Batches smaller than this resolve on the calling thread; spawning costs
+/// more than the searches.
+#[cfg(feature = "parallel")]
+const MIN_PARALLEL_BATCH: usize = 64;
+
 impl GraphTopology {
     pub fn new() -> Self {
         Self {
             graph: DiGraph::new(),
             node_map: HashMap::new(),
             run_id: uuid::Uuid::new_v4().to_string(),
+            modifiers: Vec::new(),
+            base_weights: BTreeMap::new(),
+            heuristic_scale: OnceLock::new(),
+            path_cache: Mutex::new(PathCache::new(DEFAULT_PATH_CACHE_CAPACITY)),
+            undirected: false,
+            movement: MovementRules::default(),
+            lanes: BTreeMap::new(),
+            metrics: BTreeMap::new(),
+            layout: 0,
+            edits: Vec::new(),
         }
     }
 
+    /// A topology whose lanes all run both ways: `add_edge`,
+    /// `set_edge_weight` and `remove_edge` act on both directions. Lane
+    /// modifiers stay one-way.
+    pub fn undirected() -> Self {
+        Self { undirected: true, ..Self::new() }
+    }
+
+    pub fn is_undirected(&self) -> bool {
+        self.undirected
+    }
+
     /// Adds a node (system) to the graph. Returns the NodeIndex.
-    pub fn add_node(&mut self, id: String, terrain_str: Option<String>) -> NodeIndex {
+    pub fn add_node(&mut self, id: impl Into<SystemId>, terrain_str: Option<String>) -> NodeIndex {
+        let id = id.into();
         if let Some(&idx) = self.node_map.get(&id) {
             // Update terrain if needed? For now just return.
             return idx;
         }
         
-        let terrain = match terrain_str.as_deref() {
-            Some("Mountain") => TerrainType::Mountain,
-            Some("Water") => TerrainType::Water,
-            Some("Forest") => TerrainType::Forest,
-            Some("Plains") => TerrainType::Plains,
-            _ => TerrainType::Space,
-        };
-        
-        let node_data = NodeData { id: id.clone(), terrain };
+        // Unknown terrain names fall back to Space.
+        let terrain = terrain_str.as_deref().and_then(|name| self.movement.terrain(name)).unwrap_or(TerrainType::Space);
+
+        let node_data = NodeData { id: id.clone(), terrain, position: None, danger: 0.0, owner: None, contested: false };
         let idx = self.graph.add_node(node_data);
         self.node_map.insert(id, idx);
+        self.changed();
+        idx
+    }
+
+    /// Registers a terrain kind for `add_node` and profile cost tables, or
+    /// returns the one already called `name` (built-ins included).
+    pub fn register_terrain(&mut self, name: &str) -> TerrainType {
+        let terrain = self.movement.register_terrain(name);
+        self.reshaped();
+        terrain
+    }
+
+    /// Registers a movement profile `find_path` and friends accept by
+    /// `name`: a lane into a node costs its weight times the node's
+    /// terrain's entry in `costs` (1 when unlisted, infinite for
+    /// impassable). Re-registering a name replaces its table. Errors on a
+    /// built-in profile name, an unregistered terrain or a negative cost.
+    pub fn register_profile(&mut self, name: &str, costs: TerrainCosts) -> Result<MovementProfile, String> {
+        let profile = self.movement.register_profile(name, costs)?;
+        self.reshaped();
+        Ok(profile)
+    }
+
+    /// The name `add_node` takes for `terrain`.
+    pub fn terrain_name(&self, terrain: TerrainType) -> &str {
+        self.movement.terrain_name(terrain)
+    }
+
+    /// Adds a node at `(x, y)` on the map, or moves an existing one there.
+    pub fn add_node_with_position(&mut self, id: impl Into<SystemId>, terrain_str: Option<String>, x: f32, y: f32) -> NodeIndex {
+        let idx = self.add_node(id, terrain_str);
+        self.graph[idx].position = Some((x, y));
+        self.changed();
         idx
     }
 
-    /// Adds a directional edge between two systems with a given cost (weight).
+    /// Moves a node to `(x, y)`; false if there is no such node.
+    pub fn set_position(&mut self, id: &str, x: f32, y: f32) -> bool {
+        let Some(&idx) = self.node_map.get(id) else {
+            return false;
+        };
+        self.graph[idx].position = Some((x, y));
+        self.changed();
+        true
+    }
+
+    pub fn position(&self, id: &str) -> Option<(f32, f32)> {
+        self.graph[*self.node_map.get(id)?].position
+    }
+
+    /// Sets the danger `Cautious` movement pays to enter `id` (enemy
+    /// fleets, hazards); negative values count as 0. False if there is no
+    /// such node.
+    pub fn set_node_danger(&mut self, id: &str, danger: f32) -> bool {
+        let Some(&idx) = self.node_map.get(id) else {
+            return false;
+        };
+        self.graph[idx].danger = danger.max(0.0);
+        self.reshaped();
+        true
+    }
+
+    pub fn node_danger(&self, id: &str) -> Option<f32> {
+        self.node_map.get(id).map(|&idx| self.graph[idx].danger)
+    }
+
+    /// Gives `id` to `owner` (None for nobody). False if there is no such
+    /// node.
+    pub fn set_node_owner(&mut self, id: &str, owner: Option<FactionId>) -> bool {
+        let Some(&idx) = self.node_map.get(id) else {
+            return false;
+        };
+        self.graph[idx].owner = owner;
+        self.changed();
+        true
+    }
+
+    pub fn node_owner(&self, id: &str) -> Option<&str> {
+        self.graph[*self.node_map.get(id)?].owner.as_deref()
+    }
+
+    /// Marks `id` contested or not. False if there is no such node.
+    pub fn set_contested(&mut self, id: &str, contested: bool) -> bool {
+        let Some(&idx) = self.node_map.get(id) else {
+            return false;
+        };
+        self.graph[idx].contested = contested;
+        self.changed();
+        true
+    }
+
+    pub fn is_contested(&self, id: &str) -> bool {
+        self.node_map.get(id).is_some_and(|&idx| self.graph[idx].contested)
+    }
+
+    /// Adds a directional edge between two systems with a given cost
+    /// (weight), and the edge back in an undirected topology.
     pub fn add_edge(&mut self, from_id: &str, to_id: &str, weight: f32) {
+        if self.undirected {
+            self.add_edge_bidirectional(from_id, to_id, weight);
+        } else {
+            self.insert_edge(from_id, to_id, weight);
+        }
+    }
+
+    /// Adds a two-way lane: an edge each way with the same cost.
+    pub fn add_edge_bidirectional(&mut self, a_id: &str, b_id: &str, weight: f32) {
+        self.insert_edge(a_id, b_id, weight);
+        if a_id != b_id {
+            self.insert_edge(b_id, a_id, weight);
+        }
+    }
+
+    fn insert_edge(&mut self, from_id: &str, to_id: &str, weight: f32) {
         // Default terrain to Space if nodes don't exist yet (auto-create)
         let from_idx = self.add_node(from_id.to_string(), None);
         let to_idx = self.add_node(to_id.to_string(), None);
         self.graph.add_edge(from_idx, to_idx, weight);
+        self.lane_edited(from_idx, to_idx);
     }
-    
-    /// Clears the graph state.
-    pub fn clear(&mut self) {
-        self.graph.clear();
-        self.node_map.clear();
+
+    /// Directions `set_edge_weight` and `remove_edge` act on.
+    fn lane_directions<'a>(&self, from_id: &'a str, to_id: &'a str) -> Vec<(&'a str, &'a str)> {
+        if self.undirected && from_id != to_id {
+            vec![(from_id, to_id), (to_id, from_id)]
+        } else {
+            vec![(from_id, to_id)]
+        }
     }
 
-    /// Finds the shortest path between two systems using A*.
-    /// Returns a vector of system IDs (strings) including start and end.
-    pub fn find_path(&self, start_id: &str, end_id: &str, profile_str: Option<String>) -> Option<(Vec<String>, f32)> {
-        let start_idx = *self.node_map.get(start_id)?;
-        let end_idx = *self.node_map.get(end_id)?;
+    /// Sets the cost of every `from` -> `to` edge (and `to` -> `from` in an
+    /// undirected topology). Returns how many there were. On a lane with
+    /// modifiers this is the unmodified weight; they still apply.
+    pub fn set_edge_weight(&mut self, from_id: &str, to_id: &str, weight: f32) -> usize {
+        self.lane_directions(from_id, to_id).into_iter().map(|(from, to)| self.set_lane_weight(from, to, weight)).sum()
+    }
 
-        let profile = match profile_str.as_deref() {
-            Some("Ground") => MovementProfile::Ground,
-            Some("Hover") => MovementProfile::Hover,
-            _ => MovementProfile::Space,
-        };
-
-        // Custom cost function closure
-        let edge_cost = |e: petgraph::graph::EdgeReference<f32> | -> f32 {
-            let base_cost = *e.weight();
-            let target_node = &self.graph[e.target()];
-            
-            match profile {
-                MovementProfile::Space => {
-                    // Space units ignore terrain penalties (usually)
-                    base_cost 
-                },
-                MovementProfile::Ground => {
-                    match target_node.terrain {
-                        TerrainType::Mountain => base_cost * 2.0,
-                        TerrainType::Water => f32::INFINITY, // Impassable
-                        TerrainType::Forest => base_cost * 1.5,
-                        _ => base_cost,
-                    }
-                },
-                MovementProfile::Hover => {
-                    // Hover ignores water/forest penalties, but maybe mountain doubles?
-                    match target_node.terrain {
-                        TerrainType::Mountain => base_cost * 2.0,
-                        _ => base_cost,
-                    }
-                }
+    fn set_lane_weight(&mut self, from_id: &str, to_id: &str, weight: f32) -> usize {
+        let lane = lane_key(from_id, to_id);
+        if let Some(base) = self.base_weights.get_mut(&lane) {
+            *base = weight;
+            return self.reweigh(from_id, to_id);
+        }
+        self.write_weight(from_id, to_id, weight)
+    }
+
+    /// Removes every `from` -> `to` edge (and `to` -> `from` in an
+    /// undirected topology) with the lane's modifiers. Returns how many
+    /// edges there were.
+    pub fn remove_edge(&mut self, from_id: &str, to_id: &str) -> usize {
+        self.lane_directions(from_id, to_id).into_iter().map(|(from, to)| self.remove_lane(from, to)).sum()
+    }
+
+    fn remove_lane(&mut self, from_id: &str, to_id: &str) -> usize {
+        let (Some(&from), Some(&to)) = (self.node_map.get(from_id), self.node_map.get(to_id)) else {
+            return 0;
+        };
+        let mut removed = 0;
+        while let Some(edge) = self.graph.find_edge(from, to) {
+            self.graph.remove_edge(edge);
+            removed += 1;
+        }
+        self.forget_lanes(|from, to| from == from_id && to == to_id);
+        self.lane_edited(from, to);
+        removed
+    }
+
+    /// Removes a system with every lane into or out of it. The last node
+    /// added takes its place in graph order. False if there was no such node.
+    pub fn remove_node(&mut self, id: &str) -> bool {
+        let Some(idx) = self.node_map.remove(id) else {
+            return false;
+        };
+        self.graph.remove_node(idx);
+        // petgraph moves the last node into the freed index.
+        if let Some(moved) = self.graph.node_weight(idx) {
+            self.node_map.insert(moved.id.clone(), idx);
+        }
+        self.forget_lanes(|from, to| from == id || to == id);
+        self.reshaped();
+        true
+    }
+
+    /// Drops the modifiers and base weights of lanes that are gone.
+    fn forget_lanes(&mut self, gone: impl Fn(&str, &str) -> bool) {
+        self.modifiers.retain(|m| !gone(&m.from, &m.to));
+        self.base_weights.retain(|(from, to), _| !gone(from, to));
+        self.lanes.retain(|(from, to), _| !gone(from, to));
+        self.metrics.retain(|(from, to), _| !gone(from, to));
+    }
+
+    fn write_weight(&mut self, from_id: &str, to_id: &str, weight: f32) -> usize {
+        let (Some(&from), Some(&to)) = (self.node_map.get(from_id), self.node_map.get(to_id)) else {
+            return 0;
+        };
+        let edges: Vec<_> = self.graph.edges_connecting(from, to).map(|e| e.id()).collect();
+        for &edge in &edges {
+            self.graph[edge] = weight;
+        }
+        self.lane_edited(from, to);
+        edges.len()
+    }
+
+    fn weight(&self, from_id: &str, to_id: &str) -> Option<f32> {
+        let (&from, &to) = (self.node_map.get(from_id)?, self.node_map.get(to_id)?);
+        self.graph.edges_connecting(from, to).next().map(|e| *e.weight())
+    }
+
+    /// Sets `source`'s factor on the `from` -> `to` lane, replacing any it
+    /// had there. Returns how many edges the lane has (0 if none, and
+    /// nothing is recorded).
+    pub fn set_lane_modifier(&mut self, from_id: &str, to_id: &str, source: &str, factor: f32) -> usize {
+        let Some(weight) = self.weight(from_id, to_id) else {
+            return 0;
+        };
+        self.base_weights.entry(lane_key(from_id, to_id)).or_insert(weight);
+        match self.modifiers.iter_mut().find(|m| m.from == from_id && m.to == to_id && m.source == source) {
+            Some(modifier) => modifier.factor = factor,
+            None => self.modifiers.push(LaneModifier {
+                from: from_id.into(),
+                to: to_id.into(),
+                source: source.to_string(),
+                factor,
+            }),
+        }
+        self.reweigh(from_id, to_id)
+    }
+
+    /// Takes every modifier `source` set off, restoring lanes left with
+    /// none to their unmodified weight. Returns how many it removed.
+    pub fn remove_lane_modifiers(&mut self, source: &str) -> usize {
+        let (removed, kept): (Vec<_>, Vec<_>) = std::mem::take(&mut self.modifiers).into_iter().partition(|m| m.source == source);
+        self.modifiers = kept;
+        for modifier in &removed {
+            self.reweigh(&modifier.from, &modifier.to);
+        }
+        removed.len()
+    }
+
+    /// Describes the `from` -> `to` lane (and `to` -> `from` in an
+    /// undirected topology). Returns how many edges it covers; 0 if there
+    /// is no such lane, and nothing is recorded.
+    pub fn set_lane_info(&mut self, from_id: &str, to_id: &str, info: LaneInfo) -> usize {
+        let mut described = 0;
+        for (from, to) in self.lane_directions(from_id, to_id) {
+            let (Some(&a), Some(&b)) = (self.node_map.get(from), self.node_map.get(to)) else { continue };
+            let edges = self.graph.edges_connecting(a, b).count();
+            if edges == 0 {
+                continue;
+            }
+            let lane = lane_key(from, to);
+            if info == LaneInfo::default() {
+                self.lanes.remove(&lane);
+            } else {
+                self.lanes.insert(lane, info.clone());
+            }
+            described += edges;
+        }
+        if described > 0 {
+            self.changed();
+        }
+        described
+    }
+
+    /// Sets the travel time and risk weighted routes see on the `from` ->
+    /// `to` lane (and `to` -> `from` in an undirected topology). Returns
+    /// how many edges it covers; 0 if there is no such lane. Errors on
+    /// metrics `LaneMetrics::validate` rejects.
+    pub fn set_lane_metrics(&mut self, from_id: &str, to_id: &str, metrics: LaneMetrics) -> Result<usize, String> {
+        metrics.validate()?;
+        let mut described = 0;
+        for (from, to) in self.lane_directions(from_id, to_id) {
+            let (Some(&a), Some(&b)) = (self.node_map.get(from), self.node_map.get(to)) else { continue };
+            let edges = self.graph.edges_connecting(a, b).count();
+            if edges == 0 {
+                continue;
+            }
+            let lane = lane_key(from, to);
+            if metrics == LaneMetrics::default() {
+                self.metrics.remove(&lane);
+            } else {
+                self.metrics.insert(lane, metrics);
+            }
+            described += edges;
+        }
+        if described > 0 {
+            self.changed();
+        }
+        Ok(described)
+    }
+
+    /// The `from` -> `to` lane's time and risk; None if there is no such
+    /// lane.
+    pub fn lane_metrics(&self, from_id: &str, to_id: &str) -> Option<LaneMetrics> {
+        self.weight(from_id, to_id)?;
+        Some(self.metrics.get(&lane_key(from_id, to_id)).copied().unwrap_or_default())
+    }
+
+    /// The `from` -> `to` lane's kind, capacity and owner; None if there
+    /// is no such lane.
+    pub fn lane_info(&self, from_id: &str, to_id: &str) -> Option<LaneInfo> {
+        self.weight(from_id, to_id)?;
+        Some(self.lanes.get(&lane_key(from_id, to_id)).cloned().unwrap_or_default())
+    }
+
+    /// `(from, to)` of every lane of `kind`, in graph order.
+    pub fn lanes_of_kind(&self, kind: LaneKind) -> Vec<(String, String)> {
+        self.lanes_where(|info| info.map_or(LaneKind::default(), |i| i.kind) == kind)
+    }
+
+    /// `(from, to)` of every lane `faction` owns, in graph order.
+    pub fn lanes_owned_by(&self, faction: &str) -> Vec<(String, String)> {
+        self.lanes_where(|info| info.and_then(|i| i.owner.as_deref()) == Some(faction))
+    }
+
+    fn lanes_where(&self, keep: impl Fn(Option<&LaneInfo>) -> bool) -> Vec<(String, String)> {
+        let mut lanes: Vec<(String, String)> = Vec::new();
+        for edge in self.graph.edge_references() {
+            let (from, to) = (&self.graph[edge.source()].id, &self.graph[edge.target()].id);
+            if keep(self.lanes.get(&(from.clone(), to.clone()))) && !lanes.iter().any(|(f, t)| f == from && t == to) {
+                lanes.push((from.to_string(), to.to_string()));
             }
+        }
+        lanes
+    }
+
+    /// Every lane modifier, in the order they were first set.
+    pub fn lane_modifiers(&self) -> &[LaneModifier] {
+        &self.modifiers
+    }
+
+    /// The `from` -> `to` weight before modifiers, if the lane exists.
+    pub fn base_weight(&self, from_id: &str, to_id: &str) -> Option<f32> {
+        match self.base_weights.get(&lane_key(from_id, to_id)) {
+            Some(&base) => Some(base),
+            None => self.weight(from_id, to_id),
+        }
+    }
+
+    /// Writes base weight times every factor on the lane, multiplied in
+    /// the order they were set so every client rounds the same way.
+    fn reweigh(&mut self, from_id: &str, to_id: &str) -> usize {
+        let lane = lane_key(from_id, to_id);
+        let Some(&base) = self.base_weights.get(&lane) else {
+            return 0;
         };
+        let factors: Vec<f32> = self.modifiers.iter().filter(|m| m.from == from_id && m.to == to_id).map(|m| m.factor).collect();
+        if factors.is_empty() {
+            self.base_weights.remove(&lane);
+        }
+        self.write_weight(from_id, to_id, factors.into_iter().fold(base, |weight, factor| weight * factor))
+    }
+
+    /// Every system, in insertion order.
+    pub fn node_ids(&self) -> Vec<String> {
+        self.graph.node_weights().map(|n| n.id.to_string()).collect()
+    }
+
+    /// Every edge into or out of `id` as `(from, to, weight)`, with the
+    /// weight before lane modifiers (what `set_edge_weight` takes).
+    pub fn edges_touching(&self, id: &str) -> Vec<(String, String, f32)> {
+        let Some(&idx) = self.node_map.get(id) else {
+            return Vec::new();
+        };
+        self.graph
+            .edge_references()
+            .filter(|e| e.source() == idx || e.target() == idx)
+            .map(|e| {
+                let (from, to) = (&self.graph[e.source()].id, &self.graph[e.target()].id);
+                let weight = self.base_weights.get(&(from.clone(), to.clone())).copied().unwrap_or(*e.weight());
+                (from.to_string(), to.to_string(), weight)
+            })
+            .collect()
+    }
+
+    pub fn terrain(&self, id: &str) -> Option<TerrainType> {
+        self.node_map.get(id).map(|&idx| self.graph[idx].terrain)
+    }
+
+    /// Every edge as `(from, to, weight)` in insertion order, weights
+    /// modified.
+    pub fn edges(&self) -> Vec<(String, String, f32)> {
+        self.lanes_with_weights().map(|(from, to, weight)| (from.to_string(), to.to_string(), weight)).collect()
+    }
+
+    fn lanes_with_weights(&self) -> impl Iterator<Item = (SystemId, SystemId, f32)> + '_ {
+        self.graph.edge_references().map(|e| (self.graph[e.source()].id.clone(), self.graph[e.target()].id.clone(), *e.weight()))
+    }
+
+    pub fn snapshot(&self) -> TopologySnapshot {
+        let nodes = self.graph.node_weights().map(|n| (n.id.clone(), n.terrain)).collect();
+        TopologySnapshot {
+            nodes,
+            edges: self.lanes_with_weights().collect(),
+            run_id: self.run_id.clone(),
+            modifiers: self.modifiers.clone(),
+            base_weights: self.base_weights.iter().map(|((from, to), weight)| (from.clone(), to.clone(), *weight)).collect(),
+            positions: self.graph.node_weights().filter_map(|n| n.position.map(|(x, y)| (n.id.clone(), x, y))).collect(),
+            dangers: self.graph.node_weights().filter(|n| n.danger > 0.0).map(|n| (n.id.clone(), n.danger)).collect(),
+            owners: self.graph.node_weights().filter_map(|n| n.owner.clone().map(|owner| (n.id.clone(), owner))).collect(),
+            contested: self.graph.node_weights().filter(|n| n.contested).map(|n| n.id.clone()).collect(),
+            metrics: self.metrics.iter().map(|((from, to), metrics)| (from.clone(), to.clone(), *metrics)).collect(),
+            undirected: self.undirected,
+            terrains: self.movement.terrains().to_vec(),
+            profiles: self.movement.profiles(),
+            lanes: self.lanes.iter().map(|((from, to), info)| (from.clone(), to.clone(), info.clone())).collect(),
+        }
+    }
 
-        let path_result: Option<(f32, Vec<NodeIndex>)> = astar(
-            &self.graph,
-            start_idx,
-            |finish| finish == end_idx,
-            edge_cost,
-            |_| 0.0, // Heuristic
-        );
+    /// The snapshot as JSON: nodes with terrain, positions and danger,
+    /// edges with weights, and lane modifiers.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(&self.snapshot())
+    }
+
+    /// Restores a topology `to_json` wrote; searches on it match the
+    /// original's, tie-breaks included. Errors on a snapshot that fails
+    /// `TopologySnapshot::validate`.
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        let snapshot: TopologySnapshot = serde_json::from_str(json)?;
+        snapshot.validate().map_err(<serde_json::Error as serde::de::Error>::custom)?;
+        Ok(Self::from_snapshot(snapshot))
+    }
+
+    /// Rebuilds a topology from `snapshot`, preserving node and edge order.
+    /// Lane entries for lanes that aren't in `edges` are dropped.
+    pub fn from_snapshot(snapshot: TopologySnapshot) -> Self {
+        let mut topology = Self::new();
+        topology.run_id = snapshot.run_id;
+        for terrain in &snapshot.terrains {
+            topology.movement.register_terrain(terrain);
+        }
+        for (name, costs) in snapshot.profiles {
+            // Only tables `register_profile` accepted get into snapshots.
+            let _ = topology.movement.register_profile(&name, costs);
+        }
+        for (id, terrain) in snapshot.nodes {
+            let idx = topology.graph.add_node(NodeData { id: id.clone(), terrain, position: None, danger: 0.0, owner: None, contested: false });
+            topology.node_map.insert(id, idx);
+        }
+        for (id, x, y) in snapshot.positions {
+            topology.set_position(&id, x, y);
+        }
+        for (id, danger) in snapshot.dangers {
+            topology.set_node_danger(&id, danger);
+        }
+        for (id, owner) in snapshot.owners {
+            topology.set_node_owner(&id, Some(owner));
+        }
+        for id in snapshot.contested {
+            topology.set_contested(&id, true);
+        }
+        // Both directions of each lane are in `edges`.
+        for (from, to, weight) in snapshot.edges {
+            topology.insert_edge(&from, &to, weight);
+        }
+        topology.undirected = snapshot.undirected;
+        let known = |from: &str, to: &str| topology.weight(from, to).is_some();
+        let lanes = snapshot.lanes.into_iter().filter(|(from, to, _)| known(from, to)).map(|(from, to, info)| ((from, to), info)).collect();
+        let metrics = snapshot.metrics.into_iter().filter(|(from, to, m)| known(from, to) && m.validate().is_ok()).map(|(from, to, metrics)| ((from, to), metrics)).collect();
+        let modifiers = snapshot.modifiers.into_iter().filter(|m| known(&m.from, &m.to)).collect();
+        let base_weights =
+            snapshot.base_weights.into_iter().filter(|(from, to, _)| known(from, to)).map(|(from, to, weight)| ((from, to), weight)).collect();
+        topology.lanes = lanes;
+        topology.metrics = metrics;
+        topology.modifiers = modifiers;
+        topology.base_weights = base_weights;
+        topology
+    }
+
+    pub fn memory_stats(&self) -> MemoryStats {
+        let ids: usize = self.graph.node_weights().map(|n| n.id.len()).sum();
+        MemoryStats {
+            nodes: self.graph.node_count(),
+            edges: self.graph.edge_count(),
+            // petgraph adds two u32 edge links per node and two node + two edge
+            // links per edge; the node data and `node_map` keys share each id.
+            approx_bytes: self.graph.node_count() * (size_of::<NodeData>() + 8 + size_of::<SystemId>() + size_of::<NodeIndex>())
+                + self.graph.edge_count() * (size_of::<f32>() + 16)
+                + ids,
+        }
+    }
 
-        match path_result {
-            Some((cost, path_indices)) => {
-                if cost.is_infinite() { return None; }
-                
-                let path_ids: Vec<String> = path_indices
-                    .into_iter()
-                    .map(|idx| self.graph[idx].id.clone())
-                    .collect();
-                Some((path_ids, cost))
+    /// Platform-independent hash of the nodes, terrain, positions, edge
+    /// weights, lane modifiers, lane kinds and registered profiles, for lockstep clients to compare each
+    /// turn. Taken in graph order, since insertion order (and the
+    /// heuristic) breaks ties between equal-cost routes; `run_id` is left
+    /// out.
+    pub fn state_hash(&self) -> u64 {
+        let mut hash = FNV_OFFSET;
+        for node in self.graph.node_weights() {
+            hash = extend_hash(hash, &(node.id.len() as u64).to_le_bytes());
+            hash = extend_hash(hash, node.id.as_bytes());
+            hash = extend_hash(hash, &[node.terrain.code()]);
+            if let TerrainType::Custom(_) = node.terrain {
+                hash = extend_hash(hash, self.terrain_name(node.terrain).as_bytes());
+            }
+            // Nodes without coordinates hash as they did before there were any.
+            if let Some((x, y)) = node.position {
+                hash = extend_hash(hash, &x.to_bits().to_le_bytes());
+                hash = extend_hash(hash, &y.to_bits().to_le_bytes());
+            }
+            if node.danger > 0.0 {
+                hash = extend_hash(hash, &node.danger.to_bits().to_le_bytes());
+            }
+            if let Some(owner) = &node.owner {
+                hash = extend_hash(hash, &(owner.len() as u64).to_le_bytes());
+                hash = extend_hash(hash, owner.as_bytes());
+            }
+            if node.contested {
+                hash = extend_hash(hash, &[1]);
+            }
+        }
+        for edge in self.graph.edge_references() {
+            hash = extend_hash(hash, &(edge.source().index() as u64).to_le_bytes());
+            hash = extend_hash(hash, &(edge.target().index() as u64).to_le_bytes());
+            hash = extend_hash(hash, &edge.weight().to_bits().to_le_bytes());
+        }
+        for modifier in &self.modifiers {
+            for id in [modifier.from.as_str(), modifier.to.as_str(), modifier.source.as_str()] {
+                hash = extend_hash(hash, &(id.len() as u64).to_le_bytes());
+                hash = extend_hash(hash, id.as_bytes());
+            }
+            hash = extend_hash(hash, &modifier.factor.to_bits().to_le_bytes());
+        }
+        for ((from, to), metrics) in &self.metrics {
+            for id in [from, to] {
+                hash = extend_hash(hash, &(id.len() as u64).to_le_bytes());
+                hash = extend_hash(hash, id.as_bytes());
+            }
+            hash = extend_hash(hash, &metrics.time.unwrap_or(-1.0).to_bits().to_le_bytes());
+            hash = extend_hash(hash, &metrics.risk.to_bits().to_le_bytes());
+        }
+        for ((from, to), info) in &self.lanes {
+            for id in [from, to] {
+                hash = extend_hash(hash, &(id.len() as u64).to_le_bytes());
+                hash = extend_hash(hash, id.as_bytes());
+            }
+            hash = extend_hash(hash, &[info.kind as u8]);
+            hash = extend_hash(hash, &info.capacity.map_or(u64::MAX, u64::from).to_le_bytes());
+            hash = extend_hash(hash, info.owner.as_deref().unwrap_or("").as_bytes());
+        }
+        for (name, costs) in self.movement.profiles() {
+            hash = extend_hash(hash, &(name.len() as u64).to_le_bytes());
+            hash = extend_hash(hash, name.as_bytes());
+            for (terrain, cost) in costs {
+                hash = extend_hash(hash, &(terrain.len() as u64).to_le_bytes());
+                hash = extend_hash(hash, terrain.as_bytes());
+                hash = extend_hash(hash, &cost.to_bits().to_le_bytes());
             }
-            None => None,
         }
+        hash
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Closeness centrality of every system: how cheaply it reaches the
+    /// rest of the graph, scaled by the share it can reach at all
+    /// (Wasserman-Faust), so hubs score near 1 and dead ends near 0.
+    /// Edge weights are the costs; terrain is ignored.
+    pub fn closeness_centrality(&self) -> HashMap<String, f32> {
+        let others = self.graph.node_count().saturating_sub(1) as f32;
+        self.graph
+            .node_indices()
+            .map(|idx| {
+                let distances = dijkstra(&self.graph, idx, None, |e| *e.weight());
+                let reached = (distances.len() - 1) as f32;
+                let total: f32 = distances.values().sum();
+                let closeness = if total > 0.0 { (reached / others) * (reached / total) } else { 0.0 };
+                (self.graph[idx].id.to_string(), closeness)
+            })
+            .collect()
+    }
 
-    #[test]
-    fn test_simple_path() {
-        let mut topo = GraphTopology::new();
-        topo.add_edge("A", "B", 10.0);
-        topo.add_edge("B", "C", 20.0);
-        topo.add_edge("A", "C", 100.0); // Expensive shortcut
+    /// Choke points: each system's share of the cheapest routes between
+    /// other systems that pass through it (Brandes' betweenness, split
+    /// evenly among equal-cost routes), 0 to 1. Closed lanes carry
+    /// nothing; terrain is ignored. Highest first, ties in id order.
+    pub fn betweenness_centrality(&self) -> Vec<(String, f32)> {
+        let n = self.graph.node_count();
+        let mut scores = vec![0.0f64; n];
+        for source in self.graph.node_indices() {
+            // Dijkstra, keeping every predecessor on a cheapest route and
+            // how many cheapest routes reach each node.
+            let mut cost = vec![f32::INFINITY; n];
+            let mut routes = vec![0.0f64; n];
+            let mut preds: Vec<Vec<usize>> = vec![Vec::new(); n];
+            let mut settled = Vec::with_capacity(n);
+            let mut done = vec![false; n];
+            let mut frontier = BinaryHeap::from([Frontier(0.0, source)]);
+            cost[source.index()] = 0.0;
+            routes[source.index()] = 1.0;
+            while let Some(Frontier(at_cost, at)) = frontier.pop() {
+                if std::mem::replace(&mut done[at.index()], true) {
+                    continue;
+                }
+                settled.push(at.index());
+                for edge in self.graph.edges(at).filter(|e| e.weight().is_finite()) {
+                    let (next, via) = (edge.target().index(), at_cost + *edge.weight());
+                    if via < cost[next] {
+                        cost[next] = via;
+                        routes[next] = routes[at.index()];
+                        preds[next] = vec![at.index()];
+                        frontier.push(Frontier(via, edge.target()));
+                    } else if via == cost[next] && !done[next] {
+                        routes[next] += routes[at.index()];
+                        preds[next].push(at.index());
+                    }
+                }
+            }
+            let mut dependency = vec![0.0f64; n];
+            for &node in settled.iter().rev() {
+                for &pred in &preds[node] {
+                    dependency[pred] += routes[pred] / routes[node] * (1.0 + dependency[node]);
+                }
+                if node != source.index() {
+                    scores[node] += dependency[node];
+                }
+            }
+        }
+        let pairs = (n.saturating_sub(1) * n.saturating_sub(2)).max(1) as f64;
+        let mut ranked: Vec<(String, f32)> =
+            self.graph.node_indices().map(|idx| (self.graph[idx].id.to_string(), (scores[idx.index()] / pairs) as f32)).collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        ranked
+    }
 
-        // A -> B -> C is 30.0
-        // A -> C is 100.0
-        let (path, cost) = topo.find_path("A", "C").unwrap();
-        
-        assert_eq!(path, vec!["A", "B", "C"]);
-        assert_eq!(cost, 30.0);
+    /// A component id per system: systems share one when lanes join them,
+    /// whichever way the lanes run. Closed (infinite-cost) lanes join
+    /// nothing, so a collapse that cuts systems off shows as a new
+    /// component. Ids count from 0 in graph order.
+    pub fn connected_components(&self) -> HashMap<String, usize> {
+        let mut sets = UnionFind::new(self.graph.node_count());
+        for edge in self.graph.edge_references().filter(|e| e.weight().is_finite()) {
+            sets.union(edge.source().index(), edge.target().index());
+        }
+        let mut ids: HashMap<usize, usize> = HashMap::new();
+        self.graph
+            .node_indices()
+            .map(|idx| {
+                let next = ids.len();
+                let id = *ids.entry(sets.find(idx.index())).or_insert(next);
+                (self.graph[idx].id.to_string(), id)
+            })
+            .collect()
     }
-    
-    #[test]
-    fn test_no_path() {
-        let mut topo = GraphTopology::new();
-        topo.add_edge("A", "B", 10.0);
-        topo.add_edge("C", "D", 10.0);
-        
-        let result = topo.find_path("A", "D");
-        assert!(result.is_none());
+
+    /// Whether open lanes lead from `from_id` to `to_id`, following their
+    /// direction; ignores terrain and movement profiles.
+    pub fn is_reachable(&self, from_id: &str, to_id: &str) -> bool {
+        let (Some(&from), Some(&to)) = (self.node_map.get(from_id), self.node_map.get(to_id)) else {
+            return false;
+        };
+        let mut seen = HashSet::from([from]);
+        let mut stack = vec![from];
+        while let Some(idx) = stack.pop() {
+            if idx == to {
+                return true;
+            }
+            for edge in self.graph.edges(idx).filter(|e| e.weight().is_finite()) {
+                if seen.insert(edge.target()) {
+                    stack.push(edge.target());
+                }
+            }
+        }
+        false
+    }
+
+    /// Clears the graph state.
+    pub fn clear(&mut self) {
+        self.graph.clear();
+        self.node_map.clear();
+        self.modifiers.clear();
+        self.base_weights.clear();
+        self.lanes.clear();
+        self.metrics.clear();
+        self.reshaped();
+    }
+
+    /// Drops everything worked out from the old graph.
+    fn changed(&mut self) {
+        self.heuristic_scale = OnceLock::new();
+        self.path_cache.get_mut().unwrap_or_else(|e| e.into_inner()).invalidate();
+    }
+
+    /// A change to the cost of the `from` -> `to` lane.
+    fn lane_edited(&mut self, from: NodeIndex, to: NodeIndex) {
+        self.changed();
+        if self.edits.len() < MAX_EDITS {
+            self.edits.push((from, to));
+        } else {
+            self.reshaped();
+        }
+    }
+
+    /// A change `Replanner`s can't repair edge by edge.
+    fn reshaped(&mut self) {
+        self.changed();
+        self.layout += 1;
+        self.edits.clear();
+    }
+
+    fn path_cache(&self) -> std::sync::MutexGuard<'_, PathCache> {
+        self.path_cache.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Hits, misses and size of the `find_path` cache.
+    pub fn path_cache_stats(&self) -> PathCacheStats {
+        self.path_cache().stats()
+    }
+
+    /// Caps the `find_path` cache at `capacity` entries, evicting the least
+    /// recently used; 0 turns caching off.
+    pub fn set_path_cache_capacity(&mut self, capacity: usize) {
+        self.path_cache.get_mut().unwrap_or_else(|e| e.into_inner()).set_capacity(capacity);
+    }
+
+    /// The least cost per unit of straight-line distance of any lane, so
+    /// distance times it never overestimates the cost of a route (terrain
+    /// only raises costs). 0, turning the heuristic off, unless every node
+    /// has a position.
+    fn heuristic_scale(&self) -> f32 {
+        *self.heuristic_scale.get_or_init(|| {
+            if self.graph.node_weights().any(|n| n.position.is_none()) {
+                return 0.0;
+            }
+            let scale = self
+                .graph
+                .edge_references()
+                .filter_map(|e| {
+                    let distance = distance(self.graph[e.source()].position?, self.graph[e.target()].position?);
+                    (distance > 0.0).then(|| *e.weight() / distance)
+                })
+                .fold(f32::INFINITY, f32::min);
+            // Shaved by a part in a thousand so rounding never tips it over.
+            if scale.is_finite() {
+                scale.max(0.0) * 0.999
+            } else {
+                0.0
+            }
+        })
+    }
+
+    /// Cost of moving along `e` for `profile`; infinite where it can't go.
+    fn edge_cost(&self, e: petgraph::graph::EdgeReference<f32>, profile: MovementProfile) -> f32 {
+        let cost = self.lane_cost(e, profile);
+        match profile {
+            MovementProfile::Cautious => cost + self.graph[e.target()].danger,
+            _ => cost,
+        }
+    }
+
+    /// `edge_cost` without the danger `Cautious` adds.
+    fn lane_cost(&self, e: petgraph::graph::EdgeReference<f32>, profile: MovementProfile) -> f32 {
+        *e.weight() * self.terrain_multiplier(profile, self.graph[e.target()].terrain)
+    }
+
+    /// What `profile` multiplies a lane's cost by for entering `terrain`.
+    fn terrain_multiplier(&self, profile: MovementProfile, terrain: TerrainType) -> f32 {
+        match profile {
+            // Space units ignore terrain penalties (usually)
+            MovementProfile::Space | MovementProfile::Cautious => 1.0,
+            MovementProfile::Ground => match terrain {
+                TerrainType::Mountain => 2.0,
+                TerrainType::Water => f32::INFINITY, // Impassable
+                TerrainType::Forest => 1.5,
+                _ => 1.0,
+            },
+            // Hover ignores water/forest penalties, but maybe mountain doubles?
+            MovementProfile::Hover => match terrain {
+                TerrainType::Mountain => 2.0,
+                _ => 1.0,
+            },
+            MovementProfile::Custom(index) => self.movement.multiplier(index, terrain),
+        }
+    }
+
+    /// A* from `start` to `end` that treats the `avoid_nodes` and the
+    /// `avoid_lanes` (`(from, to)` pairs) as impassable.
+    fn search(
+        &self,
+        start: NodeIndex,
+        end: NodeIndex,
+        profile: MovementProfile,
+        avoid_nodes: &HashSet<NodeIndex>,
+        avoid_lanes: &HashSet<(NodeIndex, NodeIndex)>,
+    ) -> Option<(f32, Vec<NodeIndex>)> {
+        let edge_cost = |e: petgraph::graph::EdgeReference<f32>| -> f32 {
+            if avoid_nodes.contains(&e.target()) || avoid_lanes.contains(&(e.source(), e.target())) {
+                return f32::INFINITY;
+            }
+            self.edge_cost(e, profile)
+        };
+        self.search_by(start, end, profile, 1.0, edge_cost)
+    }
+
+    /// A* from `start` to `end` with `edge_cost` in place of the
+    /// profile's; it must never be below `factor` times
+    /// `edge_cost(e, profile)`.
+    fn search_by(
+        &self,
+        start: NodeIndex,
+        end: NodeIndex,
+        profile: MovementProfile,
+        factor: f32,
+        edge_cost: impl FnMut(petgraph::graph::EdgeReference<f32>) -> f32,
+    ) -> Option<(f32, Vec<NodeIndex>)> {
+        let scale = self.heuristic_scale() * self.movement.floor(profile) * factor;
+        let goal = self.graph[end].position;
+        let heuristic = |idx: NodeIndex| match (self.graph[idx].position, goal) {
+            (Some(at), Some(goal)) if scale > 0.0 => scale * distance(at, goal),
+            _ => 0.0,
+        };
+
+        astar(&self.graph, start, |finish| finish == end, edge_cost, heuristic).filter(|(cost, _)| cost.is_finite())
+    }
+
+    /// Finds the shortest path between two systems using A*, with
+    /// straight-line distance as the heuristic when nodes have positions.
+    /// Returns a vector of system IDs (strings) including start and end.
+    /// Results are cached until the graph next changes.
+    pub fn find_path(&self, start_id: &str, end_id: &str, profile_str: Option<String>) -> Option<(Vec<String>, f32)> {
+        let key = (start_id.to_string(), end_id.to_string(), self.movement.profile(profile_str.as_deref()));
+        if let Some(result) = self.path_cache().get(&key) {
+            return result;
+        }
+        let result = self.route(start_id, end_id, key.2, &RouteOptions::default());
+        self.path_cache().insert(key, result.clone());
+        result
+    }
+
+    /// Like `find_path`, but never passes through the `avoid` systems
+    /// (enemy space, say). The start and end are always allowed, so a
+    /// fleet can still leave or attack an avoided system. Only routes
+    /// avoiding nothing are cached.
+    pub fn find_path_avoiding(
+        &self,
+        start_id: &str,
+        end_id: &str,
+        profile_str: Option<String>,
+        avoid: &[String],
+    ) -> Option<(Vec<String>, f32)> {
+        self.find_path_with(start_id, end_id, profile_str, &RouteOptions { avoid: avoid.to_vec(), ..RouteOptions::default() })
+    }
+
+    /// Like `find_path_avoiding`, but only over lanes of the given
+    /// `kinds`, for drives that can't use the rest (a wormhole-only drive
+    /// passes `[LaneKind::Wormhole]`).
+    pub fn find_path_on_lanes(
+        &self,
+        start_id: &str,
+        end_id: &str,
+        profile_str: Option<String>,
+        kinds: &[LaneKind],
+        avoid: &[String],
+        limits: PathLimits,
+    ) -> Option<(Vec<String>, f32)> {
+        let options = RouteOptions { avoid: avoid.to_vec(), lane_kinds: Some(kinds.to_vec()), limits, weights: None };
+        self.find_path_with(start_id, end_id, profile_str, &options)
+    }
+
+    /// Like `find_path_avoiding`, but only routes within `limits`: the
+    /// cheapest of at most `max_hops` lanes costing at most `max_cost`.
+    /// Routes past either are never explored. None if there is none.
+    pub fn find_path_limited(
+        &self,
+        start_id: &str,
+        end_id: &str,
+        profile_str: Option<String>,
+        limits: PathLimits,
+        avoid: &[String],
+    ) -> Option<(Vec<String>, f32)> {
+        self.find_path_with(start_id, end_id, profile_str, &RouteOptions { avoid: avoid.to_vec(), limits, ..RouteOptions::default() })
+    }
+
+    /// The cheapest route minimising `weights` (see `RouteWeights`), so
+    /// AI personalities can trade cost for speed or safety on one map.
+    /// The cost returned is the weighted one.
+    pub fn find_path_weighted(
+        &self,
+        start_id: &str,
+        end_id: &str,
+        profile_str: Option<String>,
+        weights: RouteWeights,
+    ) -> Option<(Vec<String>, f32)> {
+        self.find_path_with(start_id, end_id, profile_str, &RouteOptions { weights: Some(weights), ..RouteOptions::default() })
+    }
+
+    /// The cheapest route honouring every one of `options` at once; with
+    /// none it is `find_path`, and cached like it.
+    pub fn find_path_with(
+        &self,
+        start_id: &str,
+        end_id: &str,
+        profile_str: Option<String>,
+        options: &RouteOptions,
+    ) -> Option<(Vec<String>, f32)> {
+        if *options == RouteOptions::default() {
+            self.find_path(start_id, end_id, profile_str)
+        } else {
+            self.route(start_id, end_id, self.movement.profile(profile_str.as_deref()), options)
+        }
+    }
+
+    /// As `find_path_with`, but never reads or fills the cache, so threads
+    /// sharing one frozen topology search it without taking its lock.
+    pub fn find_path_uncached(
+        &self,
+        start_id: &str,
+        end_id: &str,
+        profile_str: Option<String>,
+        options: &RouteOptions,
+    ) -> Option<(Vec<String>, f32)> {
+        self.route(start_id, end_id, self.movement.profile(profile_str.as_deref()), options)
+    }
+
+    /// How far along the cheapest route to `end_id` a fleet gets within
+    /// `limits`: the route up to the last system it reaches, and what
+    /// that part costs. It ends at `end_id` when the fuel suffices, and is
+    /// just the start when not even the first jump does. None if there is
+    /// no route at all.
+    pub fn find_path_partial(
+        &self,
+        start_id: &str,
+        end_id: &str,
+        profile_str: Option<String>,
+        limits: PathLimits,
+    ) -> Option<(Vec<String>, f32)> {
+        let profile = self.movement.profile(profile_str.as_deref());
+        let (mut path, _) = self.find_path(start_id, end_id, profile_str)?;
+        let mut cost = 0.0;
+        let mut reached = 1;
+        for (hops, pair) in path.windows(2).enumerate() {
+            let step = self.step_cost(self.node_map[pair[0].as_str()], self.node_map[pair[1].as_str()], profile);
+            if !limits.allows(hops + 1, cost + step) {
+                break;
+            }
+            cost += step;
+            reached += 1;
+        }
+        path.truncate(reached);
+        Some((path, cost))
+    }
+
+    fn route(
+        &self,
+        start_id: &str,
+        end_id: &str,
+        profile: MovementProfile,
+        options: &RouteOptions,
+    ) -> Option<(Vec<String>, f32)> {
+        let start_idx = *self.node_map.get(start_id)?;
+        let end_idx = *self.node_map.get(end_id)?;
+        let avoid_nodes: HashSet<NodeIndex> =
+            options.avoid.iter().filter_map(|id| self.node_map.get(id.as_str()).copied()).filter(|&idx| idx != end_idx).collect();
+        let avoid_lanes: HashSet<(NodeIndex, NodeIndex)> = match &options.lane_kinds {
+            Some(kinds) => self
+                .graph
+                .edge_references()
+                .filter(|e| {
+                    let lane = (self.graph[e.source()].id.clone(), self.graph[e.target()].id.clone());
+                    !kinds.contains(&self.lanes.get(&lane).map_or(LaneKind::default(), |info| info.kind))
+                })
+                .map(|e| (e.source(), e.target()))
+                .collect(),
+            None => HashSet::new(),
+        };
+
+        let weights = options.weights.map(|w| RouteWeights { cost: w.cost.max(0.0), time: w.time.max(0.0), risk: w.risk.max(0.0) });
+        let metrics: HashMap<(NodeIndex, NodeIndex), LaneMetrics> = match weights {
+            Some(_) => self
+                .metrics
+                .iter()
+                .filter_map(|((from, to), metrics)| Some(((*self.node_map.get(from)?, *self.node_map.get(to)?), *metrics)))
+                .collect(),
+            None => HashMap::new(),
+        };
+        let edge_cost = |e: petgraph::graph::EdgeReference<f32>| -> f32 {
+            if avoid_nodes.contains(&e.target()) || avoid_lanes.contains(&(e.source(), e.target())) {
+                return f32::INFINITY;
+            }
+            match weights {
+                None => self.edge_cost(e, profile),
+                // Danger counts once, as risk, even for `Cautious`.
+                Some(w) => {
+                    let cost = self.lane_cost(e, profile);
+                    if !cost.is_finite() {
+                        return cost;
+                    }
+                    let lane = metrics.get(&(e.source(), e.target())).copied().unwrap_or_default();
+                    let risk = lane.risk + self.graph[e.target()].danger;
+                    w.cost * cost + w.time * lane.time.unwrap_or(cost) + w.risk * risk
+                }
+            }
+        };
+
+        let (cost, path_indices) = if options.limits.is_unlimited() {
+            self.search_by(start_idx, end_idx, profile, weights.map_or(1.0, |w| w.cost), edge_cost)?
+        } else {
+            self.search_limited(start_idx, end_idx, options.limits, edge_cost)?
+        };
+        Some((self.ids(&path_indices), cost))
+    }
+
+    /// Dijkstra over (system, lanes taken) for the cheapest route within
+    /// `limits`, never queueing one past them. Without a hop limit every
+    /// system has one state. A state is skipped once the system has been
+    /// reached as cheaply in no more hops.
+    fn search_limited(
+        &self,
+        start: NodeIndex,
+        end: NodeIndex,
+        limits: PathLimits,
+        edge_cost: impl Fn(petgraph::graph::EdgeReference<f32>) -> f32,
+    ) -> Option<(f32, Vec<NodeIndex>)> {
+        let n = self.graph.node_count();
+        let layers = limits.max_hops.map_or(1, |max| max + 1);
+        let state = |hops: usize, node: NodeIndex| hops * n + node.index();
+        let mut cost = vec![f32::INFINITY; layers * n];
+        let mut previous: Vec<Option<usize>> = vec![None; layers * n];
+        let mut fewest_hops = vec![usize::MAX; n];
+        let mut frontier = BinaryHeap::from([Frontier(0.0, (0, start))]);
+        cost[state(0, start)] = 0.0;
+
+        while let Some(Frontier(at_cost, (hops, at))) = frontier.pop() {
+            if at_cost > cost[state(hops, at)] || hops >= fewest_hops[at.index()] {
+                continue;
+            }
+            fewest_hops[at.index()] = hops;
+            if at == end {
+                let mut path = vec![at];
+                let mut current = state(hops, at);
+                while let Some(before) = previous[current] {
+                    path.push(NodeIndex::new(before % n));
+                    current = before;
+                }
+                path.reverse();
+                return Some((at_cost, path));
+            }
+            let next_hops = match limits.max_hops {
+                Some(max) if hops >= max => continue,
+                Some(_) => hops + 1,
+                None => 0,
+            };
+            for edge in self.graph.edges(at) {
+                let via = at_cost + edge_cost(edge);
+                let next = state(next_hops, edge.target());
+                if via.is_finite() && limits.max_cost.is_none_or(|max| via <= max) && via < cost[next] {
+                    cost[next] = via;
+                    previous[next] = Some(state(hops, at));
+                    frontier.push(Frontier(via, (next_hops, edge.target())));
+                }
+            }
+        }
+        None
+    }
+
+    /// The cheapest route from `start_id` through each of `waypoints` in
+    /// order to `end_id`, as one path (a system shared by two legs appears
+    /// once) and its total cost. None if any leg has no route.
+    pub fn find_route_via(
+        &self,
+        start_id: &str,
+        waypoints: &[String],
+        end_id: &str,
+        profile_str: Option<String>,
+    ) -> Option<(Vec<String>, f32)> {
+        let stops: Vec<&str> = std::iter::once(start_id).chain(waypoints.iter().map(String::as_str)).chain([end_id]).collect();
+        let mut route = vec![start_id.to_string()];
+        let mut total = 0.0;
+        for leg in stops.windows(2) {
+            let (path, cost) = self.find_path(leg[0], leg[1], profile_str.clone())?;
+            route.extend(path.into_iter().skip(1));
+            total += cost;
+        }
+        Some((route, total))
+    }
+
+    /// Hop by hop, what following `path` costs `profile` and why: the
+    /// lane's base weight, the modifiers on it, the entered system's
+    /// terrain penalty and danger, and the running total. Takes the
+    /// cheapest lane where there are several; None if `path` names an
+    /// unknown system or a missing lane. Pairs with `find_path` to show
+    /// why a route is expensive.
+    pub fn explain_path(&self, path: &[String], profile_str: Option<String>) -> Option<Vec<PathHop>> {
+        let profile = self.movement.profile(profile_str.as_deref());
+        let mut cumulative = 0.0;
+        let mut hops = Vec::with_capacity(path.len().saturating_sub(1));
+        for pair in path.windows(2) {
+            let (from, to) = (*self.node_map.get(pair[0].as_str())?, *self.node_map.get(pair[1].as_str())?);
+            let edge = self
+                .graph
+                .edges_connecting(from, to)
+                .min_by(|a, b| self.edge_cost(*a, profile).total_cmp(&self.edge_cost(*b, profile)))?;
+            let entered = &self.graph[to];
+            let cost = self.edge_cost(edge, profile);
+            cumulative += cost;
+            hops.push(PathHop {
+                from: pair[0].clone(),
+                to: pair[1].clone(),
+                base_cost: self.base_weights.get(&(self.graph[from].id.clone(), entered.id.clone())).copied().unwrap_or(*edge.weight()),
+                modifiers: self
+                    .modifiers
+                    .iter()
+                    .filter(|m| m.from == pair[0] && m.to == pair[1])
+                    .map(|m| (m.source.clone(), m.factor))
+                    .collect(),
+                lane_cost: *edge.weight(),
+                terrain: self.movement.terrain_name(entered.terrain).to_string(),
+                terrain_multiplier: self.terrain_multiplier(profile, entered.terrain),
+                danger: match profile {
+                    MovementProfile::Cautious => entered.danger,
+                    _ => 0.0,
+                },
+                cost,
+                cumulative,
+            });
+        }
+        Some(hops)
+    }
+
+    /// Systems in the zone of control of any of the `hostile` factions,
+    /// in graph order: those they own, those a lane (either way) links to
+    /// one they own, and every contested system.
+    pub fn zone_of_control(&self, hostile: &[String]) -> Vec<String> {
+        let zone = self.zone(hostile);
+        self.graph.node_indices().filter(|idx| zone.contains(idx)).map(|idx| self.graph[idx].id.to_string()).collect()
+    }
+
+    fn zone(&self, hostile: &[String]) -> HashSet<NodeIndex> {
+        let mut zone: HashSet<NodeIndex> = self.graph.node_indices().filter(|&idx| self.graph[idx].contested).collect();
+        for idx in self.graph.node_indices() {
+            if self.graph[idx].owner.as_ref().is_some_and(|owner| hostile.iter().any(|faction| owner == faction)) {
+                zone.insert(idx);
+                zone.extend(self.graph.neighbors_undirected(idx));
+            }
+        }
+        zone
+    }
+
+    /// `find_path` for a fleet that honours the `hostile` factions' zones
+    /// of control (see `zone_of_control`) by `rule`: stopping where it
+    /// enters one, or paying a penalty for each system in one it enters.
+    pub fn find_path_zoc(
+        &self,
+        start_id: &str,
+        end_id: &str,
+        profile_str: Option<String>,
+        hostile: &[String],
+        rule: ZocRule,
+    ) -> Option<(Vec<String>, f32)> {
+        let (&start, &end) = (self.node_map.get(start_id)?, self.node_map.get(end_id)?);
+        let profile = self.movement.profile(profile_str.as_deref());
+        let zone = self.zone(hostile);
+        let edge_cost = |e: petgraph::graph::EdgeReference<f32>| -> f32 {
+            match rule {
+                ZocRule::EndsMovement if e.source() != start && zone.contains(&e.source()) => f32::INFINITY,
+                ZocRule::Penalty(penalty) if zone.contains(&e.target()) => self.edge_cost(e, profile) + penalty.max(0.0),
+                _ => self.edge_cost(e, profile),
+            }
+        };
+        self.search_by(start, end, profile, 1.0, edge_cost).map(|(cost, path)| (self.ids(&path), cost))
+    }
+
+    /// Every system reachable from `start_id` for at most `max_cost`, with
+    /// the cheapest cost there (the start at 0), cheapest first and ties in
+    /// id order: where a fleet can move this turn.
+    pub fn reachable_within(&self, start_id: &str, max_cost: f32, profile_str: Option<String>) -> Vec<(String, f32)> {
+        let Some(&start_idx) = self.node_map.get(start_id).filter(|_| max_cost >= 0.0) else {
+            return Vec::new();
+        };
+        let profile = self.movement.profile(profile_str.as_deref());
+        // Dijkstra that never queues a system past the budget, so the
+        // search stops once the cheapest frontier costs more than it.
+        let mut cost: HashMap<NodeIndex, f32> = HashMap::from([(start_idx, 0.0)]);
+        let mut settled = HashSet::new();
+        let mut frontier = BinaryHeap::from([Frontier(0.0, start_idx)]);
+        let mut reached = Vec::new();
+        while let Some(Frontier(at_cost, at)) = frontier.pop() {
+            if !settled.insert(at) {
+                continue;
+            }
+            reached.push((self.graph[at].id.to_string(), at_cost));
+            for edge in self.graph.edges(at) {
+                let via = at_cost + self.edge_cost(edge, profile);
+                if via <= max_cost && cost.get(&edge.target()).is_none_or(|&known| via < known) {
+                    cost.insert(edge.target(), via);
+                    frontier.push(Frontier(via, edge.target()));
+                }
+            }
+        }
+        reached.sort_by(|a, b| a.1.total_cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+        reached
+    }
+
+    /// Up to `k` loopless paths from `start_id` to `end_id`, cheapest
+    /// first (Yen's algorithm), for planning alternate routes. The first is
+    /// `find_path`'s.
+    pub fn find_k_paths(&self, start_id: &str, end_id: &str, k: usize, profile_str: Option<String>) -> Vec<(Vec<String>, f32)> {
+        if k == 0 {
+            return Vec::new();
+        }
+        let (Some(&start_idx), Some(&end_idx)) = (self.node_map.get(start_id), self.node_map.get(end_id)) else {
+            return Vec::new();
+        };
+        let profile = self.movement.profile(profile_str.as_deref());
+        let Some(first) = self.search(start_idx, end_idx, profile, &HashSet::new(), &HashSet::new()) else {
+            return Vec::new();
+        };
+
+        let mut found = vec![first];
+        let mut candidates: Vec<(f32, Vec<NodeIndex>)> = Vec::new();
+        while found.len() < k {
+            let last = found[found.len() - 1].1.clone();
+            for i in 0..last.len() - 1 {
+                let (root, spur) = (&last[..=i], last[i]);
+                // Leave the root by a lane no path found so far takes from it.
+                let avoid_lanes: HashSet<(NodeIndex, NodeIndex)> =
+                    found.iter().filter(|(_, path)| path.len() > i + 1 && path[..=i] == *root).map(|(_, path)| (path[i], path[i + 1])).collect();
+                let avoid_nodes: HashSet<NodeIndex> = root[..i].iter().copied().collect();
+                let Some((spur_cost, spur_path)) = self.search(spur, end_idx, profile, &avoid_nodes, &avoid_lanes) else {
+                    continue;
+                };
+                let root_cost: f32 = root.windows(2).map(|step| self.step_cost(step[0], step[1], profile)).sum();
+                let path: Vec<NodeIndex> = root[..i].iter().chain(&spur_path).copied().collect();
+                if !found.iter().chain(&candidates).any(|(_, known)| *known == path) {
+                    candidates.push((root_cost + spur_cost, path));
+                }
+            }
+            // Cheapest candidate; the earliest found wins ties.
+            let Some(best) = candidates.iter().enumerate().min_by(|(_, a), (_, b)| a.0.total_cmp(&b.0)).map(|(i, _)| i) else {
+                break;
+            };
+            found.push(candidates.remove(best));
+        }
+        found.into_iter().map(|(cost, path)| (self.ids(&path), cost)).collect()
+    }
+
+    /// Cost of the cheapest edge from `from` to `to`.
+    fn step_cost(&self, from: NodeIndex, to: NodeIndex, profile: MovementProfile) -> f32 {
+        self.graph.edges_connecting(from, to).map(|e| self.edge_cost(e, profile)).fold(f32::INFINITY, f32::min)
+    }
+
+    fn ids(&self, path: &[NodeIndex]) -> Vec<String> {
+        path.iter().map(|&idx| self.graph[idx].id.to_string()).collect()
+    }
+
+    /// Resolves every query, splitting the batch over one thread per core.
+    /// Results line up with `queries`; unreachable pairs yield `None`. The
+    /// cache is read once before the search and filled once after it, so
+    /// workers never wait on it.
+    pub fn find_paths_batch(&self, queries: &[PathQuery]) -> Vec<Option<(Vec<String>, f32)>> {
+        let keys: Vec<PathKey> =
+            queries.iter().map(|(start, end, profile)| (start.clone(), end.clone(), self.movement.profile(profile.as_deref()))).collect();
+        let mut results: Vec<Option<PathResult>> = {
+            let mut cache = self.path_cache();
+            keys.iter().map(|key| cache.get(key)).collect()
+        };
+        let misses: Vec<&PathKey> = keys.iter().zip(&results).filter(|(_, result)| result.is_none()).map(|(key, _)| key).collect();
+        let mut routed = self.route_all(&misses).into_iter();
+        let mut cache = self.path_cache();
+        for (key, result) in keys.iter().zip(&mut results) {
+            if result.is_none() {
+                let route = routed.next().unwrap_or_default();
+                cache.insert(key.clone(), route.clone());
+                *result = Some(route);
+            }
+        }
+        results.into_iter().flatten().collect()
+    }
+
+    /// As `find_paths_batch`, but never reads or fills the cache.
+    pub fn find_paths_batch_uncached(&self, queries: &[PathQuery]) -> Vec<Option<(Vec<String>, f32)>> {
+        let keys: Vec<PathKey> =
+            queries.iter().map(|(start, end, profile)| (start.clone(), end.clone(), self.movement.profile(profile.as_deref()))).collect();
+        self.route_all(&keys.iter().collect::<Vec<_>>())
+    }
+
+    /// Cheapest routes between `keys`, with no options and no cache.
+    #[cfg(feature = "parallel")]
+    fn route_all(&self, keys: &[&PathKey]) -> Vec<PathResult> {
+        let workers = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+        if workers == 1 || keys.len() < MIN_PARALLEL_BATCH {
+            return keys.iter().map(|key| self.route(&key.0, &key.1, key.2, &RouteOptions::default())).collect();
+        }
+        let chunk = keys.len().div_ceil(workers);
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = keys
+                .chunks(chunk)
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        chunk.iter().map(|key| self.route(&key.0, &key.1, key.2, &RouteOptions::default())).collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            handles.into_iter().flat_map(|handle| handle.join().unwrap_or_else(|e| std::panic::resume_unwind(e))).collect()
+        })
+    }
+
+    /// Serial fallback for targets without threads (wasm32); results match
+    /// the threaded version.
+    #[cfg(not(feature = "parallel"))]
+    fn route_all(&self, keys: &[&PathKey]) -> Vec<PathResult> {
+        keys.iter().map(|key| self.route(&key.0, &key.1, key.2, &RouteOptions::default())).collect()
+    }
+}
+
+/// A node (or search state) waiting in Dijkstra's queue; the heap pops
+/// the cheapest first.
+struct Frontier<T = NodeIndex>(f32, T);
+
+impl<T: Ord> PartialEq for Frontier<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T: Ord> Eq for Frontier<T> {}
+
+impl<T: Ord> PartialOrd for Frontier<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Ord> Ord for Frontier<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.0.total_cmp(&self.0).then_with(|| other.1.cmp(&self.1))
+    }
+}
+
+fn distance(a: (f32, f32), b: (f32, f32)) -> f32 {
+    ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()
+}
+
+impl Default for GraphTopology {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An independent copy, cached routes included, for readers that
+/// shouldn't wait on the original's writers. Every search takes `&self`,
+/// so one copy behind an `Arc` serves any number of threads.
+impl Clone for GraphTopology {
+    fn clone(&self) -> Self {
+        Self {
+            graph: self.graph.clone(),
+            node_map: self.node_map.clone(),
+            run_id: self.run_id.clone(),
+            modifiers: self.modifiers.clone(),
+            base_weights: self.base_weights.clone(),
+            heuristic_scale: self.heuristic_scale.clone(),
+            path_cache: Mutex::new(self.path_cache().clone()),
+            undirected: self.undirected,
+            movement: self.movement.clone(),
+            lanes: self.lanes.clone(),
+            metrics: self.metrics.clone(),
+            layout: self.layout,
+            edits: self.edits.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simple_path() {
+        let mut topo = GraphTopology::new();
+        topo.add_edge("A", "B", 10.0);
+        topo.add_edge("B", "C", 20.0);
+        topo.add_edge("A", "C", 100.0); // Expensive shortcut
+
+        // A -> B -> C is 30.0
+        // A -> C is 100.0
+        let (path, cost) = topo.find_path("A", "C", None).unwrap();
+        
+        assert_eq!(path, vec!["A", "B", "C"]);
+        assert_eq!(cost, 30.0);
+    }
+    
+    #[test]
+    fn hubs_are_more_central() {
+        let mut topo = GraphTopology::new();
+        for spoke in ["A", "B", "C"] {
+            topo.add_edge("Hub", spoke, 1.0);
+            topo.add_edge(spoke, "Hub", 1.0);
+        }
+        let centrality = topo.closeness_centrality();
+        assert_eq!(centrality["Hub"], 1.0);
+        assert!(centrality["A"] < centrality["Hub"]);
+    }
+
+    #[test]
+    fn batches_match_single_queries() {
+        let mut topo = GraphTopology::new();
+        topo.add_node("Ocean".to_string(), Some("Water".to_string()));
+        for i in 0..20 {
+            topo.add_edge(&format!("S{}", i), &format!("S{}", (i + 1) % 20), 1.0 + i as f32);
+        }
+        topo.add_edge("S0", "Ocean", 1.0);
+        let mut queries: Vec<PathQuery> =
+            (0..200).map(|i| (format!("S{}", i % 20), format!("S{}", (i * 7) % 20), None)).collect();
+        queries.push(("S0".to_string(), "Ocean".to_string(), Some("Ground".to_string())));
+        queries.push(("S0".to_string(), "Nowhere".to_string(), None));
+
+        let batch = topo.find_paths_batch(&queries);
+        let single: Vec<_> = queries.iter().map(|(s, e, p)| topo.find_path(s, e, p.clone())).collect();
+        assert_eq!(batch, single);
+        assert!(batch[200].is_none() && batch[201].is_none());
+    }
+
+    #[test]
+    fn positions_guide_the_search_without_changing_routes() {
+        let mut plain = GraphTopology::new();
+        let mut placed = GraphTopology::new();
+        for y in 0..10 {
+            for x in 0..10 {
+                placed.add_node_with_position(format!("{}_{}", x, y), None, x as f32, y as f32);
+            }
+        }
+        for topo in [&mut plain, &mut placed] {
+            for y in 0..10 {
+                for x in 0..10 {
+                    let here = format!("{}_{}", x, y);
+                    if x < 9 {
+                        topo.add_edge(&here, &format!("{}_{}", x + 1, y), 1.0 + ((x * y) % 3) as f32);
+                    }
+                    if y < 9 {
+                        topo.add_edge(&here, &format!("{}_{}", x, y + 1), 1.0 + ((x + y) % 2) as f32);
+                    }
+                }
+            }
+            // A warp lane far cheaper than the distance it covers.
+            topo.add_edge("0_0", "9_8", 0.5);
+        }
+        assert_eq!(placed.position("3_4"), Some((3.0, 4.0)));
+        for goal in ["9_9", "5_5", "9_0", "0_9"] {
+            let cost = |topo: &GraphTopology| topo.find_path("0_0", goal, None).map(|(_, cost)| cost);
+            assert_eq!(cost(&placed), cost(&plain));
+        }
+        assert_eq!(placed.find_path("0_0", "9_9", None).map(|(_, cost)| cost), Some(2.5));
+
+        let restored = GraphTopology::from_snapshot(placed.snapshot());
+        assert_eq!(restored.state_hash(), placed.state_hash());
+        assert_ne!(restored.state_hash(), plain.state_hash());
+    }
+
+    #[test]
+    fn k_paths_are_loopless_and_cheapest_first() {
+        let mut topo = GraphTopology::new();
+        for (from, to, weight) in [
+            ("C", "D", 3.0),
+            ("C", "E", 2.0),
+            ("D", "F", 4.0),
+            ("E", "D", 1.0),
+            ("E", "F", 2.0),
+            ("E", "G", 3.0),
+            ("F", "G", 2.0),
+            ("F", "H", 1.0),
+            ("G", "H", 2.0),
+        ] {
+            topo.add_edge(from, to, weight);
+        }
+        let paths = topo.find_k_paths("C", "H", 3, None);
+        let routes: Vec<(String, f32)> = paths.iter().map(|(path, cost)| (path.join(""), *cost)).collect();
+        assert_eq!(routes, vec![("CEFH".to_string(), 5.0), ("CEGH".to_string(), 7.0), ("CDFH".to_string(), 8.0)]);
+        assert_eq!(paths[0], topo.find_path("C", "H", None).unwrap());
+
+        // Asking for more than exist returns them all.
+        assert_eq!(topo.find_k_paths("C", "H", 20, None).len(), 7);
+        assert!(topo.find_k_paths("H", "C", 3, None).is_empty());
+        assert!(topo.find_k_paths("C", "H", 0, None).is_empty());
+    }
+
+    #[test]
+    fn removals_keep_the_node_map_consistent() {
+        let mut topo = GraphTopology::new();
+        topo.add_edge("A", "B", 1.0);
+        topo.add_edge("B", "C", 1.0);
+        topo.add_edge("A", "C", 5.0);
+        topo.add_edge("C", "D", 1.0);
+        topo.set_lane_modifier("B", "C", "storm", 3.0);
+
+        assert!(topo.remove_node("B"));
+        assert!(!topo.remove_node("B"));
+        assert!(topo.lane_modifiers().is_empty());
+        assert_eq!(topo.node_ids().len(), 3);
+        // D moved into B's slot and is still found by id.
+        assert_eq!(topo.find_path("A", "D", None), Some((vec!["A".to_string(), "C".to_string(), "D".to_string()], 6.0)));
+
+        assert_eq!(topo.set_edge_weight("A", "C", 2.0), 1);
+        assert_eq!(topo.remove_edge("C", "D"), 1);
+        assert_eq!(topo.remove_edge("C", "D"), 0);
+        assert!(topo.find_path("A", "D", None).is_none());
+        assert_eq!(topo.find_path("A", "C", None).map(|(_, cost)| cost), Some(2.0));
+
+        let rebuilt = GraphTopology::from_snapshot(topo.snapshot());
+        assert_eq!(rebuilt.state_hash(), topo.state_hash());
+    }
+
+    #[test]
+    fn avoided_systems_are_routed_around() {
+        let mut topo = GraphTopology::new();
+        topo.add_edge("A", "B", 1.0);
+        topo.add_edge("B", "D", 1.0);
+        topo.add_edge("A", "C", 2.0);
+        topo.add_edge("C", "D", 2.0);
+        let avoiding = |avoid: &[&str]| {
+            let avoid: Vec<String> = avoid.iter().map(|id| id.to_string()).collect();
+            topo.find_path_avoiding("A", "D", None, &avoid).map(|(path, _)| path.concat())
+        };
+        assert_eq!(avoiding(&[]), Some("ABD".to_string()));
+        assert_eq!(avoiding(&["B", "Nowhere"]), Some("ACD".to_string()));
+        assert_eq!(avoiding(&["B", "C"]), None);
+        assert_eq!(avoiding(&["A", "D"]), Some("ABD".to_string()));
+    }
+
+    #[test]
+    fn reachable_within_stops_at_the_budget() {
+        let mut topo = GraphTopology::new();
+        topo.add_node("Swamp".to_string(), Some("Water".to_string()));
+        topo.add_edge("A", "B", 1.0);
+        topo.add_edge("B", "C", 2.0);
+        topo.add_edge("A", "C", 5.0);
+        topo.add_edge("C", "D", 1.0);
+        topo.add_edge("A", "Swamp", 1.0);
+        let reached = topo.reachable_within("A", 3.0, None);
+        assert_eq!(reached, vec![("A".to_string(), 0.0), ("B".to_string(), 1.0), ("Swamp".to_string(), 1.0), ("C".to_string(), 3.0)]);
+        // Ground units can't enter water at any cost.
+        assert!(!topo.reachable_within("A", 10.0, Some("Ground".to_string())).iter().any(|(id, _)| id == "Swamp"));
+        assert!(topo.reachable_within("Nowhere", 3.0, None).is_empty());
+    }
+
+    #[test]
+    fn cached_paths_are_dropped_when_the_graph_changes() {
+        let mut topo = GraphTopology::new();
+        topo.add_edge("A", "B", 1.0);
+        topo.add_edge("B", "C", 1.0);
+        topo.add_edge("A", "C", 5.0);
+        let cost = |topo: &GraphTopology| topo.find_path("A", "C", None).map(|(_, cost)| cost);
+        assert_eq!(cost(&topo), Some(2.0));
+        assert_eq!(cost(&topo), Some(2.0));
+        let stats = topo.path_cache_stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (1, 1, 1));
+
+        topo.set_lane_modifier("B", "C", "blockade", 10.0);
+        assert_eq!(topo.path_cache_stats().entries, 0);
+        assert_eq!(cost(&topo), Some(5.0));
+        topo.remove_lane_modifiers("blockade");
+        assert_eq!(cost(&topo), Some(2.0));
+
+        topo.set_path_cache_capacity(1);
+        topo.find_path("A", "B", None);
+        topo.find_path("A", "C", Some("Ground".to_string()));
+        let stats = topo.path_cache_stats();
+        assert_eq!((stats.entries, stats.capacity, stats.invalidations), (1, 1, 2));
+    }
+
+    #[test]
+    fn uncached_searches_leave_the_cache_alone() {
+        let mut topo = GraphTopology::new();
+        topo.add_edge("A", "B", 1.0);
+        topo.add_edge("B", "C", 1.0);
+        let found = topo.find_path_uncached("A", "C", None, &RouteOptions::default());
+        assert_eq!(found, topo.find_path("A", "C", None));
+        let batch = topo.find_paths_batch_uncached(&[("A".to_string(), "C".to_string(), None), ("C".to_string(), "Z".to_string(), None)]);
+        assert_eq!(batch, vec![found, None]);
+        let stats = topo.path_cache_stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (0, 1, 1));
+    }
+
+    #[test]
+    fn cautious_routes_trade_distance_for_safety() {
+        let mut topo = GraphTopology::new();
+        topo.add_edge("A", "B", 1.0);
+        topo.add_edge("B", "D", 1.0);
+        topo.add_edge("A", "C", 2.0);
+        topo.add_edge("C", "D", 2.0);
+        assert!(topo.set_node_danger("B", 5.0));
+        assert!(!topo.set_node_danger("Nowhere", 5.0));
+
+        let route = |topo: &GraphTopology, profile: &str| topo.find_path("A", "D", Some(profile.to_string())).unwrap();
+        assert_eq!(route(&topo, "Space"), (vec!["A".to_string(), "B".to_string(), "D".to_string()], 2.0));
+        assert_eq!(route(&topo, "Cautious"), (vec!["A".to_string(), "C".to_string(), "D".to_string()], 4.0));
+
+        // Worth the risk once the detour costs more than the danger.
+        topo.set_node_danger("B", 1.0);
+        assert_eq!(route(&topo, "Cautious").1, 3.0);
+        assert_eq!(GraphTopology::from_snapshot(topo.snapshot()).node_danger("B"), Some(1.0));
+    }
+
+    #[test]
+    fn explained_hops_add_up_to_the_route() {
+        let mut topo = GraphTopology::new();
+        topo.add_node("F".to_string(), Some("Forest".to_string()));
+        topo.add_edge("A", "F", 2.0);
+        topo.add_edge("F", "B", 1.0);
+        topo.set_lane_modifier("A", "F", "ion_storm", 3.0);
+
+        let (path, cost) = topo.find_path("A", "B", Some("Ground".to_string())).unwrap();
+        let hops = topo.explain_path(&path, Some("Ground".to_string())).unwrap();
+        assert_eq!(hops.len(), 2);
+        let storm = &hops[0];
+        assert_eq!((storm.base_cost, storm.lane_cost, storm.terrain.as_str(), storm.terrain_multiplier), (2.0, 6.0, "Forest", 1.5));
+        assert_eq!(storm.modifiers, vec![("ion_storm".to_string(), 3.0)]);
+        assert_eq!((storm.cost, hops[1].cost, hops[1].cumulative), (9.0, 1.0, cost));
+        assert!(topo.explain_path(&["B".to_string(), "A".to_string()], None).is_none());
+    }
+
+    #[test]
+    fn exports_show_terrain_and_weights() {
+        let mut topo = GraphTopology::new();
+        topo.add_node("Sol \"Prime\"".to_string(), Some("Forest".to_string()));
+        topo.add_edge("Sol \"Prime\"", "Vega", 2.5);
+        topo.add_edge("Vega", "A&B", f32::INFINITY);
+        topo.set_lane_info("Vega", "A&B", LaneInfo { kind: LaneKind::Wormhole, capacity: None, owner: Some(FactionId::new("Empire")) });
+
+        let dot = topo.export_dot();
+        assert!(dot.starts_with("digraph topology {"));
+        assert!(dot.contains("\"Sol \\\"Prime\\\"\" [label=\"Sol \\\"Prime\\\"\\nForest\""));
+        assert!(dot.contains("\"Vega\" -> \"A&B\" [label=\"INF\", weight=\"INF\", style=dashed, kind=\"Wormhole\", owner=\"Empire\"]"));
+
+        let graphml = topo.export_graphml();
+        assert!(graphml.contains("<node id=\"Sol &quot;Prime&quot;\">\n      <data key=\"terrain\">Forest</data>"));
+        assert!(graphml.contains("<edge source=\"Vega\" target=\"A&amp;B\">\n      <data key=\"weight\">INF</data>"));
+        assert_eq!(graphml.matches("<edge ").count(), 2);
+    }
+
+    #[test]
+    fn zones_of_control_stop_or_slow_routes() {
+        let mut topo = GraphTopology::undirected();
+        // A - B - C - D along the border, A - E - F - D the long way round.
+        for (from, to, weight) in [("A", "B", 1.0), ("B", "C", 1.0), ("C", "D", 1.0), ("A", "E", 1.0), ("E", "F", 1.0), ("F", "D", 2.0)] {
+            topo.add_edge(from, to, weight);
+        }
+        topo.add_edge("B", "X", 1.0);
+        assert!(topo.set_node_owner("X", Some(FactionId::new("Empire"))));
+        assert!(topo.set_contested("C", true));
+        let hostile = ["Empire".to_string()];
+        assert_eq!(topo.zone_of_control(&hostile), vec!["B", "C", "X"]);
+
+        let route = |rule| topo.find_path_zoc("A", "D", None, &hostile, rule).unwrap();
+        assert_eq!(route(ZocRule::EndsMovement), (vec!["A".to_string(), "E".to_string(), "F".to_string(), "D".to_string()], 4.0));
+        assert_eq!(route(ZocRule::Penalty(0.25)), (vec!["A".to_string(), "B".to_string(), "C".to_string(), "D".to_string()], 3.5));
+        assert_eq!(route(ZocRule::Penalty(1.0)).1, 4.0);
+        // A route may end in a zone, and leave the one it starts in.
+        assert_eq!(topo.find_path_zoc("A", "B", None, &hostile, ZocRule::EndsMovement).unwrap().1, 1.0);
+        assert_eq!(topo.find_path_zoc("C", "D", None, &hostile, ZocRule::EndsMovement).unwrap().1, 1.0);
+
+        let restored = GraphTopology::from_snapshot(topo.snapshot());
+        assert_eq!((restored.node_owner("X"), restored.is_contested("C")), (Some("Empire"), true));
+        assert_eq!(restored.state_hash(), topo.state_hash());
+    }
+
+    #[test]
+    fn limits_prune_routes_and_cut_them_short() {
+        let mut topo = GraphTopology::new();
+        // Cheap five-jump chain A..F against a dear direct jump.
+        for (from, to) in [("A", "B"), ("B", "C"), ("C", "D"), ("D", "E"), ("E", "F")] {
+            topo.add_edge(from, to, 1.0);
+        }
+        topo.add_edge("A", "F", 8.0);
+        topo.add_edge("B", "F", 5.0);
+        let limited = |max_hops, max_cost| topo.find_path_limited("A", "F", None, PathLimits { max_hops, max_cost }, &[]);
+
+        assert_eq!(limited(None, None).unwrap().1, 5.0);
+        assert_eq!(limited(Some(2), None).unwrap(), (vec!["A".to_string(), "B".to_string(), "F".to_string()], 6.0));
+        assert_eq!(limited(Some(1), None).unwrap().1, 8.0);
+        assert_eq!(limited(Some(1), Some(7.0)), None);
+        assert_eq!(limited(None, Some(4.0)), None);
+
+        let partial = |max_hops, max_cost| topo.find_path_partial("A", "F", None, PathLimits { max_hops, max_cost }).unwrap();
+        assert_eq!(partial(None, Some(3.5)), (vec!["A".to_string(), "B".to_string(), "C".to_string(), "D".to_string()], 3.0));
+        assert_eq!(partial(Some(1), None).0, vec!["A", "B"]);
+        assert_eq!(partial(None, Some(0.5)).0, vec!["A"]);
+        assert_eq!(partial(None, None).0.last().map(String::as_str), Some("F"));
+    }
+
+    #[test]
+    fn weights_pick_between_cheap_fast_and_safe() {
+        let mut topo = GraphTopology::new();
+        // Three ways from A to D: cheap but slow, fast but dear, and safe.
+        for (via, cost) in [("Cheap", 1.0), ("Fast", 3.0), ("Safe", 2.0)] {
+            topo.add_edge("A", via, cost);
+            topo.add_edge(via, "D", cost);
+        }
+        topo.set_lane_metrics("A", "Cheap", LaneMetrics { time: Some(10.0), risk: 0.0 }).unwrap();
+        topo.set_lane_metrics("A", "Fast", LaneMetrics { time: Some(1.0), risk: 2.0 }).unwrap();
+        topo.set_lane_metrics("Fast", "D", LaneMetrics { time: Some(1.0), risk: 0.0 }).unwrap();
+        topo.set_node_danger("Cheap", 4.0);
+        assert_eq!(topo.lane_metrics("A", "Fast").map(|m| m.risk), Some(2.0));
+        for bad in [LaneMetrics { time: Some(-1.0), risk: 0.0 }, LaneMetrics { time: Some(f32::NAN), risk: 0.0 }, LaneMetrics { time: None, risk: f32::INFINITY }] {
+            assert!(topo.set_lane_metrics("A", "Safe", bad).is_err());
+        }
+        assert_eq!(topo.lane_metrics("A", "Safe"), Some(LaneMetrics::default()));
+
+        let via = |cost, time, risk| topo.find_path_weighted("A", "D", None, RouteWeights { cost, time, risk }).unwrap().0[1].clone();
+        assert_eq!(via(1.0, 0.0, 0.0), "Cheap");
+        assert_eq!(via(0.0, 1.0, 0.0), "Fast");
+        assert_eq!(via(0.0, 0.0, 1.0), "Safe");
+        assert_eq!(topo.find_path_weighted("A", "D", None, RouteWeights::default()), topo.find_path("A", "D", None));
+        // Cautious routes pay Cheap's danger once, as risk.
+        let cautious = |risk| topo.find_path_weighted("A", "D", Some("Cautious".to_string()), RouteWeights { cost: 1.0, time: 0.0, risk }).unwrap();
+        assert_eq!(cautious(0.0), (vec!["A".to_string(), "Cheap".to_string(), "D".to_string()], 2.0));
+        assert_eq!(cautious(1.0).1, 4.0);
+
+        let restored = GraphTopology::from_snapshot(topo.snapshot());
+        assert_eq!(restored.lane_metrics("A", "Cheap"), topo.lane_metrics("A", "Cheap"));
+        assert_eq!(restored.state_hash(), topo.state_hash());
+    }
+
+    #[test]
+    fn copies_answer_queries_from_many_threads() {
+        let mut topo = GraphTopology::new();
+        for i in 0..50 {
+            topo.add_edge(&format!("S{}", i), &format!("S{}", i + 1), 1.0 + (i % 3) as f32);
+            topo.add_edge(&format!("S{}", i), &format!("S{}", (i + 7) % 51), 4.0);
+        }
+        topo.find_path("S0", "S50", None);
+        let shared = std::sync::Arc::new(topo.clone());
+        assert_eq!(shared.path_cache_stats().entries, 1);
+        assert_eq!(shared.state_hash(), topo.state_hash());
+
+        let expected: Vec<_> = (0..50).map(|i| topo.find_path(&format!("S{}", i), "S50", None)).collect();
+        let found: Vec<Vec<_>> = std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..4)
+                .map(|_| {
+                    let shared = std::sync::Arc::clone(&shared);
+                    scope.spawn(move || (0..50).map(|i| shared.find_path(&format!("S{}", i), "S50", None)).collect())
+                })
+                .collect();
+            workers.into_iter().map(|worker| worker.join().unwrap()).collect()
+        });
+        assert!(found.iter().all(|paths| *paths == expected));
+
+        // The copy doesn't see later edits to the original.
+        topo.remove_edge("S49", "S50");
+        assert!(shared.find_path("S49", "S50", None).is_some());
+    }
+
+    #[test]
+    fn routes_via_waypoints_chain_their_legs() {
+        let mut topo = GraphTopology::new();
+        for (from, to) in [("A", "B"), ("B", "C"), ("C", "A"), ("B", "A"), ("C", "B")] {
+            topo.add_edge(from, to, 1.0);
+        }
+        let ids = |ids: &[&str]| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+        // A patrol loop: out to C, back round to A.
+        assert_eq!(topo.find_route_via("A", &ids(&["C"]), "A", None), Some((ids(&["A", "B", "C", "A"]), 3.0)));
+        assert_eq!(topo.find_route_via("A", &[], "C", None), topo.find_path("A", "C", None));
+        assert_eq!(topo.find_route_via("A", &ids(&["Nowhere"]), "C", None), None);
+    }
+
+    #[test]
+    fn closed_lanes_split_components() {
+        let mut topo = GraphTopology::new();
+        topo.add_edge("A", "B", 1.0);
+        topo.add_edge("B", "C", 1.0);
+        topo.add_edge("D", "E", 1.0);
+        topo.add_node("F".to_string(), None);
+        let components = topo.connected_components();
+        assert_eq!(components["A"], 0);
+        assert_eq!(components["C"], 0);
+        assert_eq!((components["D"], components["E"], components["F"]), (1, 1, 2));
+        assert!(topo.is_reachable("A", "C"));
+        assert!(!topo.is_reachable("C", "A"));
+
+        topo.set_edge_weight("B", "C", f32::INFINITY);
+        assert_ne!(topo.connected_components()["C"], topo.connected_components()["A"]);
+        assert!(!topo.is_reachable("A", "C"));
+        assert!(topo.is_reachable("A", "A"));
+    }
+
+    #[test]
+    fn betweenness_ranks_choke_points_first() {
+        // Two triangles joined through the Gate.
+        let mut topo = GraphTopology::new();
+        for (a, b) in [("A1", "A2"), ("A2", "A3"), ("A3", "A1"), ("A1", "Gate"), ("Gate", "B1"), ("B1", "B2"), ("B2", "B3"), ("B3", "B1")] {
+            topo.add_edge(a, b, 1.0);
+            topo.add_edge(b, a, 1.0);
+        }
+        let ranked = topo.betweenness_centrality();
+        let order: Vec<&str> = ranked.iter().take(3).map(|(id, _)| id.as_str()).collect();
+        assert_eq!(order, vec!["Gate", "A1", "B1"]);
+        // Every route between the halves runs through the Gate.
+        assert!((ranked[0].1 - 18.0 / 30.0).abs() < 1e-6);
+        assert_eq!(ranked.last().unwrap().1, 0.0);
+    }
+
+    #[test]
+    fn json_round_trip_keeps_everything_searches_use() {
+        let mut topo = GraphTopology::new();
+        topo.add_node_with_position("A".to_string(), Some("Plains".to_string()), 0.0, 0.0);
+        topo.add_node_with_position("B".to_string(), Some("Mountain".to_string()), 1.0, 0.0);
+        topo.add_node_with_position("C".to_string(), None, 1.0, 1.0);
+        topo.add_edge("A", "B", 1.5);
+        topo.add_edge("B", "C", 1.0);
+        topo.set_lane_modifier("A", "B", "storm", 2.0);
+        topo.set_node_danger("C", 3.0);
+        // Closed lanes are infinite, which JSON writes as null.
+        topo.add_edge("C", "A", f32::INFINITY);
+
+        let restored = GraphTopology::from_json(&topo.to_json().unwrap()).unwrap();
+        assert_eq!(restored.state_hash(), topo.state_hash());
+        assert_eq!(restored.terrain("B"), Some(TerrainType::Mountain));
+        assert_eq!(restored.position("C"), Some((1.0, 1.0)));
+        assert_eq!(restored.base_weight("A", "B"), Some(1.5));
+        assert_eq!(restored.find_path("A", "C", Some("Ground".to_string())), topo.find_path("A", "C", Some("Ground".to_string())));
+        assert!(GraphTopology::from_json("{\"nodes\": 3}").is_err());
+
+        // Lane entries must name a lane the snapshot has.
+        let mut snapshot = topo.snapshot();
+        snapshot.metrics.push(("A".into(), "Nowhere".into(), LaneMetrics::default()));
+        assert!(snapshot.validate().is_err());
+        assert!(GraphTopology::from_json(&serde_json::to_string(&snapshot).unwrap()).is_err());
+        let lenient = GraphTopology::from_snapshot(snapshot);
+        assert_eq!(lenient.lane_metrics("A", "Nowhere"), None);
+        assert_eq!(lenient.state_hash(), topo.state_hash());
+    }
+
+    #[test]
+    fn undirected_topologies_keep_lanes_two_way() {
+        let mut topo = GraphTopology::undirected();
+        topo.add_edge("A", "B", 2.0);
+        topo.add_edge_bidirectional("B", "C", 1.0);
+        assert_eq!(topo.edges().len(), 4);
+        assert_eq!(topo.find_path("C", "A", None).map(|(_, cost)| cost), Some(3.0));
+
+        assert_eq!(topo.set_edge_weight("B", "A", 5.0), 2);
+        assert_eq!(topo.find_path("A", "C", None).map(|(_, cost)| cost), Some(6.0));
+        let restored = GraphTopology::from_snapshot(topo.snapshot());
+        assert!(restored.is_undirected());
+        assert_eq!(restored.edges(), topo.edges());
+
+        assert_eq!(topo.remove_edge("A", "B"), 2);
+        assert!(!topo.is_reachable("C", "A"));
+
+        let mut directed = GraphTopology::new();
+        directed.add_edge_bidirectional("A", "B", 1.0);
+        directed.add_edge("B", "C", 1.0);
+        assert_eq!(directed.remove_edge("B", "A"), 1);
+        assert!(!directed.is_reachable("C", "B"));
+    }
+
+    #[test]
+    fn registered_profiles_price_registered_terrain() {
+        let mut topo = GraphTopology::new();
+        let swamp = topo.register_terrain("Swamp");
+        assert_eq!(topo.register_terrain("Swamp"), swamp);
+        assert_eq!(topo.register_terrain("Water"), TerrainType::Water);
+        topo.add_node("Bog".to_string(), Some("Swamp".to_string()));
+        topo.add_node("Lake".to_string(), Some("Water".to_string()));
+        topo.add_node("Peak".to_string(), Some("Mountain".to_string()));
+        for (from, to, weight) in [("A", "Bog", 1.0), ("Bog", "B", 1.0), ("A", "Lake", 1.0), ("Lake", "B", 1.0), ("A", "Peak", 1.0), ("Peak", "B", 1.0)] {
+            topo.add_edge(from, to, weight);
+        }
+        assert_eq!(topo.terrain("Bog"), Some(swamp));
+        assert_eq!(topo.terrain_name(swamp), "Swamp");
+
+        let costs = |pairs: &[(&str, f32)]| pairs.iter().map(|(t, c)| (t.to_string(), *c)).collect::<BTreeMap<_, _>>();
+        let amphibious = |topo: &mut GraphTopology, pairs: &[(&str, f32)]| topo.register_profile("Amphibious", costs(pairs));
+        assert!(amphibious(&mut topo, &[("Lava", 2.0)]).is_err());
+        assert!(topo.register_profile("Ground", BTreeMap::new()).is_err());
+        amphibious(&mut topo, &[("Water", 1.2), ("Swamp", 3.0), ("Mountain", f32::INFINITY), ("Space", 0.5)]).unwrap();
+
+        let route = |topo: &GraphTopology| topo.find_path("A", "B", Some("Amphibious".to_string())).unwrap();
+        assert_eq!(route(&topo), (vec!["A".to_string(), "Lake".to_string(), "B".to_string()], 1.2 + 0.5));
+        // Re-registering replaces the table, and the cached route with it.
+        amphibious(&mut topo, &[("Water", 4.0), ("Mountain", f32::INFINITY)]).unwrap();
+        assert_eq!(route(&topo).0[1], "Bog");
+
+        let restored = GraphTopology::from_json(&topo.to_json().unwrap()).unwrap();
+        assert_eq!(restored.state_hash(), topo.state_hash());
+        assert_eq!(route(&restored), route(&topo));
+    }
+
+    #[test]
+    fn lane_kinds_limit_which_drives_can_route() {
+        let mut topo = GraphTopology::new();
+        topo.add_edge("A", "B", 1.0);
+        topo.add_edge("B", "C", 1.0);
+        topo.add_edge("A", "C", 5.0);
+        let wormhole = LaneInfo { kind: LaneKind::Wormhole, capacity: Some(3), owner: Some(FactionId::new("Eldar")) };
+        assert_eq!(topo.set_lane_info("A", "C", wormhole.clone()), 1);
+        assert_eq!(topo.set_lane_info("C", "A", wormhole.clone()), 0);
+        assert_eq!(topo.lane_info("A", "C"), Some(wormhole));
+        assert_eq!(topo.lane_info("A", "B"), Some(LaneInfo::default()));
+        assert_eq!(topo.lanes_of_kind(LaneKind::Wormhole), vec![("A".to_string(), "C".to_string())]);
+        assert_eq!(topo.lanes_owned_by("Eldar").len(), 1);
+
+        let path = |kinds: &[LaneKind]| topo.find_path_on_lanes("A", "C", None, kinds, &[], PathLimits::default()).map(|(path, _)| path.concat());
+        assert_eq!(path(&[LaneKind::Hyperlane, LaneKind::Wormhole]), Some("ABC".to_string()));
+        assert_eq!(path(&[LaneKind::Wormhole]), Some("AC".to_string()));
+        assert_eq!(path(&[LaneKind::Slipstream]), None);
+
+        let restored = GraphTopology::from_snapshot(topo.snapshot());
+        assert_eq!(restored.state_hash(), topo.state_hash());
+        topo.remove_edge("A", "C");
+        assert!(topo.lanes_of_kind(LaneKind::Wormhole).is_empty());
+    }
+
+    #[test]
+    fn test_no_path() {
+        let mut topo = GraphTopology::new();
+        topo.add_edge("A", "B", 10.0);
+        topo.add_edge("C", "D", 10.0);
+        
+        let result = topo.find_path("A", "D", None);
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let mut topo = GraphTopology::new();
+        topo.add_node("A".to_string(), None);
+        topo.add_node("B".to_string(), Some("Water".to_string()));
+        topo.add_edge("A", "B", 1.0);
+        topo.add_edge("A", "C", 5.0);
+
+        let restored = GraphTopology::from_snapshot(topo.snapshot());
+        assert_eq!(restored.run_id, topo.run_id);
+        assert_eq!(restored.find_path("A", "C", None), Some((vec!["A".to_string(), "C".to_string()], 5.0)));
+        // Terrain survives: water is impassable on the ground
+        assert!(restored.find_path("A", "B", Some("Ground".to_string())).is_none());
+        assert_eq!(restored.state_hash(), topo.state_hash());
+    }
+
+    #[test]
+    fn lane_modifiers_stack_and_come_off_by_source() {
+        let mut topo = GraphTopology::new();
+        topo.add_edge("A", "B", 2.0);
+        let unmodified = topo.state_hash();
+        assert_eq!(topo.set_lane_modifier("A", "B", "ion_storm@3", 3.0), 1);
+        assert_eq!(topo.set_lane_modifier("A", "B", "nebula@1", 0.5), 1);
+        assert_eq!(topo.set_lane_modifier("B", "A", "nebula@1", 0.5), 0);
+        assert_eq!(topo.find_path("A", "B", None).map(|(_, cost)| cost), Some(3.0));
+
+        // Resetting the lane changes what the modifiers multiply.
+        topo.set_edge_weight("A", "B", 4.0);
+        assert_eq!(topo.edges_touching("A"), vec![("A".to_string(), "B".to_string(), 4.0)]);
+        let restored = GraphTopology::from_snapshot(topo.snapshot());
+        assert_eq!(restored.lane_modifiers(), topo.lane_modifiers());
+        assert_eq!(restored.state_hash(), topo.state_hash());
+
+        assert_eq!(topo.remove_lane_modifiers("ion_storm@3"), 1);
+        assert_eq!(topo.find_path("A", "B", None).map(|(_, cost)| cost), Some(2.0));
+        topo.remove_lane_modifiers("nebula@1");
+        topo.set_edge_weight("A", "B", 2.0);
+        assert_eq!(topo.base_weight("A", "B"), Some(2.0));
+        assert_eq!(topo.state_hash(), unmodified);
     }
 }
@@ -0,0 +1,164 @@
+//! Terrain kinds and movement profiles registered at runtime, so mods can
+//! add movement classes (`"Amphibious"`: water at 1.2, mountains
+//! impassable) without a new build. The built-in terrains and profiles
+//! keep their enum variants; registered ones are numbered in the order
+//! they were added.
+
+use crate::{MovementProfile, TerrainCosts, TerrainType};
+
+const BUILTIN_TERRAINS: [(&str, TerrainType); 5] = [
+    ("Space", TerrainType::Space),
+    ("Plains", TerrainType::Plains),
+    ("Forest", TerrainType::Forest),
+    ("Mountain", TerrainType::Mountain),
+    ("Water", TerrainType::Water),
+];
+
+const BUILTIN_PROFILES: [(&str, MovementProfile); 4] = [
+    ("Space", MovementProfile::Space),
+    ("Ground", MovementProfile::Ground),
+    ("Hover", MovementProfile::Hover),
+    ("Cautious", MovementProfile::Cautious),
+];
+
+impl TerrainType {
+    /// Stable per-terrain byte for state hashes; registered terrains hash
+    /// their name after it.
+    pub(crate) fn code(self) -> u8 {
+        match self {
+            TerrainType::Space => 0,
+            TerrainType::Plains => 1,
+            TerrainType::Forest => 2,
+            TerrainType::Mountain => 3,
+            TerrainType::Water => 4,
+            TerrainType::Custom(_) => 5,
+        }
+    }
+
+    /// Index into a profile's cost table: built-ins first, then
+    /// registered terrains in order.
+    fn slot(self) -> usize {
+        match self {
+            TerrainType::Custom(index) => BUILTIN_TERRAINS.len() + index as usize,
+            builtin => builtin.code() as usize,
+        }
+    }
+}
+
+#[derive(Clone)]
+struct ProfileTable {
+    name: String,
+    /// Terrain name to cost multiplier; unlisted terrain costs 1.
+    costs: TerrainCosts,
+    /// `costs` by terrain slot, rebuilt when terrains are registered.
+    by_slot: Vec<f32>,
+}
+
+#[derive(Clone, Default)]
+pub(crate) struct MovementRules {
+    terrains: Vec<String>,
+    profiles: Vec<ProfileTable>,
+}
+
+impl MovementRules {
+    /// The built-in or registered terrain called `name`.
+    pub(crate) fn terrain(&self, name: &str) -> Option<TerrainType> {
+        match BUILTIN_TERRAINS.iter().find(|(builtin, _)| *builtin == name) {
+            Some(&(_, terrain)) => Some(terrain),
+            None => self.terrains.iter().position(|t| t == name).map(|index| TerrainType::Custom(index as u16)),
+        }
+    }
+
+    pub(crate) fn terrain_name(&self, terrain: TerrainType) -> &str {
+        match terrain {
+            TerrainType::Custom(index) => self.terrains.get(index as usize).map_or("Unknown", String::as_str),
+            builtin => BUILTIN_TERRAINS[builtin.code() as usize].0,
+        }
+    }
+
+    /// Registers `name` as a terrain, or returns it if it already is one.
+    pub(crate) fn register_terrain(&mut self, name: &str) -> TerrainType {
+        if let Some(terrain) = self.terrain(name) {
+            return terrain;
+        }
+        self.terrains.push(name.to_string());
+        self.resolve();
+        TerrainType::Custom(self.terrains.len() as u16 - 1)
+    }
+
+    /// Registers `name` as a profile that multiplies a lane's cost by the
+    /// entered node's terrain's entry in `costs` (1 when unlisted,
+    /// infinite for impassable), replacing any earlier table of that name.
+    pub(crate) fn register_profile(&mut self, name: &str, costs: TerrainCosts) -> Result<MovementProfile, String> {
+        if BUILTIN_PROFILES.iter().any(|(builtin, _)| *builtin == name) {
+            return Err(format!("'{}' is a built-in movement profile", name));
+        }
+        if let Some(terrain) = costs.keys().find(|terrain| self.terrain(terrain).is_none()) {
+            return Err(format!("unknown terrain '{}'; register it first", terrain));
+        }
+        if let Some((terrain, cost)) = costs.iter().find(|(_, cost)| cost.is_nan() || **cost < 0.0) {
+            return Err(format!("cost {} for '{}' is not a non-negative number", cost, terrain));
+        }
+        let table = ProfileTable { name: name.to_string(), costs, by_slot: Vec::new() };
+        let index = match self.profiles.iter().position(|p| p.name == name) {
+            Some(index) => {
+                self.profiles[index] = table;
+                index
+            }
+            None => {
+                self.profiles.push(table);
+                self.profiles.len() - 1
+            }
+        };
+        self.resolve();
+        Ok(MovementProfile::Custom(index as u16))
+    }
+
+    /// `"Ground"`, `"Hover"`, `"Cautious"` or a registered profile;
+    /// anything else moves as `Space`.
+    pub(crate) fn profile(&self, name: Option<&str>) -> MovementProfile {
+        let Some(name) = name else {
+            return MovementProfile::Space;
+        };
+        match BUILTIN_PROFILES.iter().find(|(builtin, _)| *builtin == name) {
+            Some(&(_, profile)) => profile,
+            None => self
+                .profiles
+                .iter()
+                .position(|p| p.name == name)
+                .map_or(MovementProfile::Space, |index| MovementProfile::Custom(index as u16)),
+        }
+    }
+
+    /// Cost multiplier for a registered profile entering `terrain`.
+    pub(crate) fn multiplier(&self, profile: u16, terrain: TerrainType) -> f32 {
+        self.profiles.get(profile as usize).and_then(|p| p.by_slot.get(terrain.slot())).copied().unwrap_or(1.0)
+    }
+
+    /// The smallest multiplier `profile` can apply, at most 1, so the A*
+    /// heuristic scaled by it still never overestimates.
+    pub(crate) fn floor(&self, profile: MovementProfile) -> f32 {
+        match profile {
+            MovementProfile::Custom(index) => {
+                self.profiles.get(index as usize).map_or(1.0, |p| p.by_slot.iter().copied().fold(1.0, f32::min))
+            }
+            _ => 1.0,
+        }
+    }
+
+    pub(crate) fn terrains(&self) -> &[String] {
+        &self.terrains
+    }
+
+    /// `(name, costs)` of every registered profile, in order.
+    pub(crate) fn profiles(&self) -> Vec<(String, TerrainCosts)> {
+        self.profiles.iter().map(|p| (p.name.clone(), p.costs.clone())).collect()
+    }
+
+    fn resolve(&mut self) {
+        let slots: Vec<String> = BUILTIN_TERRAINS.iter().map(|(name, _)| name.to_string()).chain(self.terrains.iter().cloned()).collect();
+        for profile in &mut self.profiles {
+            profile.by_slot = slots.iter().map(|terrain| profile.costs.get(terrain).copied().unwrap_or(1.0)).collect();
+        }
+    }
+}
@@ -0,0 +1,283 @@
+//! Incremental replanning for a fleet under way (D* Lite, Koenig and
+//! Likhachev). The planner searches back from the goal once and keeps its
+//! search state; when lanes change cost, a blockade say, it repairs only
+//! the part of that state the edited lanes reach, and the fleet moving
+//! along its route costs nothing at all.
+//!
+//! The heuristic is 0, which keeps keys independent of where the fleet is
+//! and still repairs far less than a fresh search after local changes.
+
+use crate::{GraphTopology, MovementProfile};
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// A node queued under `min(g, rhs)`, all there is to a D* Lite key with
+/// no heuristic; lower pops first.
+struct Pending(f32, NodeIndex);
+
+impl PartialEq for Pending {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Pending {}
+
+impl PartialOrd for Pending {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Pending {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.0.total_cmp(&self.0).then_with(|| other.1.cmp(&self.1))
+    }
+}
+
+/// One fleet's route to `goal`, kept current as the fleet moves and the
+/// map changes. Built on a topology and repaired against later versions
+/// of the same one.
+pub struct Replanner {
+    start: String,
+    goal: String,
+    profile: Option<String>,
+    /// Topology layout the search state belongs to, and how many of its
+    /// lane edits have been repaired.
+    layout: u64,
+    edits_seen: usize,
+    resolved: Option<(NodeIndex, NodeIndex, MovementProfile)>,
+    /// Cost to the goal as last expanded, and as its successors say.
+    g: Vec<f32>,
+    rhs: Vec<f32>,
+    queue: BinaryHeap<Pending>,
+    /// Key each node is queued under; entries with another are stale.
+    queued: Vec<Option<f32>>,
+    expanded: usize,
+}
+
+impl Replanner {
+    /// A planner from `start` to `goal`; nothing is searched until `path`.
+    pub fn new(start: &str, goal: &str, profile: Option<String>) -> Self {
+        Self {
+            start: start.to_string(),
+            goal: goal.to_string(),
+            profile,
+            layout: 0,
+            edits_seen: 0,
+            resolved: None,
+            g: Vec::new(),
+            rhs: Vec::new(),
+            queue: BinaryHeap::new(),
+            queued: Vec::new(),
+            expanded: 0,
+        }
+    }
+
+    pub fn start(&self) -> &str {
+        &self.start
+    }
+
+    pub fn goal(&self) -> &str {
+        &self.goal
+    }
+
+    /// The fleet has moved to `system`; the search state stays valid.
+    pub fn move_to(&mut self, system: &str) {
+        self.start = system.to_string();
+    }
+
+    /// Nodes expanded by the last `path`, to see what a repair cost.
+    pub fn last_expanded(&self) -> usize {
+        self.expanded
+    }
+
+    /// The cheapest route from where the fleet is to the goal on
+    /// `topology` as it is now, repairing the search for lanes edited
+    /// since the last call. None while there is no route.
+    pub fn path(&mut self, topology: &GraphTopology) -> Option<(Vec<String>, f32)> {
+        self.expanded = 0;
+        let current = topology.node_map.get(self.start.as_str()).copied();
+        let goal = topology.node_map.get(self.goal.as_str()).copied()?;
+        let start = current?;
+        match self.resolved {
+            Some((_, known_goal, profile)) if self.layout == topology.layout && known_goal == goal => {
+                self.resolved = Some((start, goal, profile));
+                for &(from, _) in &topology.edits[self.edits_seen.min(topology.edits.len())..] {
+                    self.update(topology, from);
+                }
+            }
+            _ => self.reset(topology, start, goal),
+        }
+        self.edits_seen = topology.edits.len();
+        self.compute(topology);
+        self.extract(topology)
+    }
+
+    fn reset(&mut self, topology: &GraphTopology, start: NodeIndex, goal: NodeIndex) {
+        let n = topology.graph.node_count();
+        self.layout = topology.layout;
+        self.resolved = Some((start, goal, topology.movement.profile(self.profile.as_deref())));
+        self.g = vec![f32::INFINITY; n];
+        self.rhs = vec![f32::INFINITY; n];
+        self.queued = vec![None; n];
+        self.queue.clear();
+        self.rhs[goal.index()] = 0.0;
+        self.enqueue(goal);
+    }
+
+    fn grow(&mut self, n: usize) {
+        if self.g.len() < n {
+            self.g.resize(n, f32::INFINITY);
+            self.rhs.resize(n, f32::INFINITY);
+            self.queued.resize(n, None);
+        }
+    }
+
+    fn key(&self, node: NodeIndex) -> f32 {
+        self.g[node.index()].min(self.rhs[node.index()])
+    }
+
+    fn enqueue(&mut self, node: NodeIndex) {
+        let key = self.key(node);
+        self.queued[node.index()] = Some(key);
+        self.queue.push(Pending(key, node));
+    }
+
+    /// Recomputes `node`'s cost from its successors and queues it if that
+    /// disagrees with what it was expanded at.
+    fn update(&mut self, topology: &GraphTopology, node: NodeIndex) {
+        let Some((_, goal, profile)) = self.resolved else { return };
+        self.grow(topology.graph.node_count());
+        if node.index() >= topology.graph.node_count() {
+            return;
+        }
+        if node != goal {
+            self.rhs[node.index()] = topology
+                .graph
+                .edges(node)
+                .map(|e| topology.edge_cost(e, profile) + self.g[e.target().index()])
+                .fold(f32::INFINITY, f32::min);
+        }
+        self.queued[node.index()] = None;
+        if self.g[node.index()] != self.rhs[node.index()] {
+            self.enqueue(node);
+        }
+    }
+
+    fn compute(&mut self, topology: &GraphTopology) {
+        let Some((start, _, _)) = self.resolved else { return };
+        self.grow(topology.graph.node_count());
+        while let Some(Pending(key, node)) = self.queue.pop() {
+            if self.queued[node.index()] != Some(key) {
+                continue;
+            }
+            let start_settled = self.rhs[start.index()] == self.g[start.index()];
+            if key >= self.key(start) && start_settled {
+                self.queue.push(Pending(key, node));
+                break;
+            }
+            self.queued[node.index()] = None;
+            self.expanded += 1;
+            let fresh = self.key(node);
+            if key < fresh {
+                self.enqueue(node);
+            } else if self.g[node.index()] > self.rhs[node.index()] {
+                self.g[node.index()] = self.rhs[node.index()];
+                self.update_predecessors(topology, node);
+            } else {
+                self.g[node.index()] = f32::INFINITY;
+                self.update(topology, node);
+                self.update_predecessors(topology, node);
+            }
+        }
+    }
+
+    fn update_predecessors(&mut self, topology: &GraphTopology, node: NodeIndex) {
+        let predecessors: Vec<NodeIndex> = topology.graph.edges_directed(node, Direction::Incoming).map(|e| e.source()).collect();
+        for predecessor in predecessors {
+            self.update(topology, predecessor);
+        }
+    }
+
+    /// Walks from the start to the goal, always to the successor the
+    /// search says is cheapest.
+    fn extract(&self, topology: &GraphTopology) -> Option<(Vec<String>, f32)> {
+        let (start, goal, profile) = self.resolved?;
+        if !self.g[start.index()].is_finite() {
+            return None;
+        }
+        let mut path = vec![start];
+        let mut cost = 0.0;
+        let mut at = start;
+        while at != goal {
+            let (step, next) = topology
+                .graph
+                .edges(at)
+                .map(|e| (topology.edge_cost(e, profile), e.target()))
+                .filter(|(step, next)| (step + self.g[next.index()]).is_finite())
+                .min_by(|a, b| (a.0 + self.g[a.1.index()]).total_cmp(&(b.0 + self.g[b.1.index()])).then_with(|| a.1.cmp(&b.1)))?;
+            if path.len() > topology.graph.node_count() {
+                return None;
+            }
+            cost += step;
+            path.push(next);
+            at = next;
+        }
+        Some((topology.ids(&path), cost))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(size: usize) -> GraphTopology {
+        let mut topo = GraphTopology::new();
+        for y in 0..size {
+            for x in 0..size {
+                let here = format!("{}_{}", x, y);
+                if x + 1 < size {
+                    topo.add_edge_bidirectional(&here, &format!("{}_{}", x + 1, y), 1.0 + ((x * 7 + y * 3) % 4) as f32);
+                }
+                if y + 1 < size {
+                    topo.add_edge_bidirectional(&here, &format!("{}_{}", x, y + 1), 1.0 + ((x * 5 + y) % 3) as f32);
+                }
+            }
+        }
+        topo
+    }
+
+    #[test]
+    fn repairs_match_fresh_searches() {
+        let mut topo = grid(12);
+        let goal = "11_11";
+        let mut planner = Replanner::new("0_0", goal, None);
+        let first = planner.path(&topo).unwrap();
+        assert_eq!(first.1, topo.find_path("0_0", goal, None).unwrap().1);
+        let full = planner.last_expanded();
+
+        // Move two systems along the route, then blockade the next lane.
+        planner.move_to(&first.0[2]);
+        let (from, to) = (first.0[2].clone(), first.0[3].clone());
+        topo.set_lane_modifier(&from, &to, "blockade", 50.0);
+        let repaired = planner.path(&topo).unwrap();
+        assert_eq!(repaired.0[0], from);
+        assert_eq!(repaired.1, topo.find_path(&from, goal, None).unwrap().1);
+        assert!(planner.last_expanded() < full);
+
+        // Lifting it and closing a lane elsewhere repairs just as well.
+        topo.remove_lane_modifiers("blockade");
+        topo.set_edge_weight("10_11", "11_11", f32::INFINITY);
+        assert_eq!(planner.path(&topo).unwrap().1, topo.find_path(&from, goal, None).unwrap().1);
+
+        // Removing a node moves indices; the planner starts over.
+        topo.remove_node("5_5");
+        assert_eq!(planner.path(&topo).unwrap().1, topo.find_path(&from, goal, None).unwrap().1);
+        topo.remove_edge("11_10", "11_11");
+        assert!(planner.path(&topo).is_none());
+    }
+}
@@ -0,0 +1,356 @@
+//! Galactic anomalies: a seeded generator that rolls against a weighted
+//! table each turn and applies what comes up (a supernova closing every
+//! lane around a system, a plague cutting a planet's income, pirates
+//! appearing) through the pathfinder, economy and fleet engines, undoing
+//! timed effects when they run out. The table is data, loaded as JSON;
+//! the generator's random state is part of its snapshot, so a restored
+//! generator rolls what the original would have.
+
+use serde::{Deserialize, Serialize};
+use void_reckoning_economy::engine::IncomeEngine;
+use void_reckoning_economy::types::{EconomicModifier, NodeType, SCALE_FACTOR};
+use void_reckoning_fleet::{Fleet, FleetEngine};
+use void_reckoning_pathfinder::GraphTopology;
+use void_reckoning_shared::desync;
+use void_reckoning_shared::rng::SplitMix64;
+use void_reckoning_shared::ids::SystemId;
+use void_reckoning_shared::scope::ContextStack;
+use void_reckoning_shared::simtime::SimTime;
+use void_reckoning_shared::span::Span;
+use void_reckoning_shared::{CorrelationContext, Event, EventLog, EventSeverity};
+
+/// What an anomaly does. Externally tagged: `{"Plague": {"income_factor":
+/// 0.5, "turns": 3}}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum AnomalyEffect {
+    /// Closes every lane into and out of a random system for `turns` turns.
+    Supernova { turns: u64 },
+    /// Multiplies a random planet's income by `income_factor` for `turns`
+    /// turns.
+    Plague { income_factor: f32, turns: u64 },
+    /// Adds `fleet` at a random system. Its id gets the turn appended and
+    /// its location is replaced.
+    PirateSpawn { fleet: Fleet },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnomalyEntry {
+    pub id: String,
+    /// Relative odds against the table's other entries.
+    pub weight: u32,
+    pub effect: AnomalyEffect,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AnomalyTable {
+    /// Odds (0 to 1) that anything happens on a turn.
+    #[serde(default = "always")]
+    pub chance: f32,
+    pub entries: Vec<AnomalyEntry>,
+}
+
+fn always() -> f32 {
+    1.0
+}
+
+/// An anomaly that struck, or whose effect ran out.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Anomaly {
+    /// `AnomalyEntry::id`.
+    pub entry: String,
+    pub turn: u64,
+    /// System or planet hit; for pirates, the system they appeared in.
+    pub target: String,
+    /// Set for timed effects: the turn they wear off on.
+    pub expires: Option<u64>,
+}
+
+/// An entry that came up but couldn't be applied.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnomalyError {
+    pub entry: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AnomalyReport {
+    pub turn: u64,
+    pub struck: Vec<Anomaly>,
+    pub expired: Vec<Anomaly>,
+    pub errors: Vec<AnomalyError>,
+}
+
+/// What a timed effect needs to be undone.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Undo {
+    /// Lanes closed, with the weights to put back.
+    Lanes(Vec<(String, String, f32)>),
+    /// Economy modifier to remove from the target planet.
+    Modifier(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActiveAnomaly {
+    pub anomaly: Anomaly,
+    pub undo: Undo,
+}
+
+/// Serializable copy of an `AnomalyGenerator`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AnomalySnapshot {
+    pub table: AnomalyTable,
+    /// Random state; the seed until the first roll.
+    #[serde(default)]
+    pub rng: SplitMix64,
+    #[serde(default)]
+    pub active: Vec<ActiveAnomaly>,
+}
+
+/// What anomalies act on. Without `fleets`, pirate spawns fail.
+pub struct AnomalyTargets<'a> {
+    pub topology: &'a mut GraphTopology,
+    pub economy: &'a mut IncomeEngine,
+    pub fleets: Option<&'a mut FleetEngine>,
+}
+
+pub struct AnomalyGenerator {
+    table: AnomalyTable,
+    rng: SplitMix64,
+    active: Vec<ActiveAnomaly>,
+    pub event_log: Option<EventLog>,
+    /// Correlation contexts; nested operations push scopes onto this.
+    pub contexts: ContextStack,
+}
+
+impl AnomalyGenerator {
+    pub fn new(table: AnomalyTable, seed: u64) -> Self {
+        Self::from_snapshot(AnomalySnapshot { table, rng: SplitMix64::new(seed), active: Vec::new() })
+    }
+
+    pub fn from_snapshot(snapshot: AnomalySnapshot) -> Self {
+        Self { table: snapshot.table, rng: snapshot.rng, active: snapshot.active, event_log: None, contexts: ContextStack::default() }
+    }
+
+    pub fn snapshot(&self) -> AnomalySnapshot {
+        AnomalySnapshot { table: self.table.clone(), rng: self.rng, active: self.active.clone() }
+    }
+
+    /// FNV-1a of the snapshot's JSON encoding, so the generator's RNG
+    /// position is covered too.
+    pub fn state_hash(&self) -> u64 {
+        desync::hash_json(&self.snapshot())
+    }
+
+    pub fn set_event_log(&mut self, log: EventLog) {
+        self.event_log = Some(log);
+    }
+
+    pub fn set_correlation_context(&mut self, context: CorrelationContext) {
+        self.contexts.set_root(context);
+    }
+
+    pub fn table(&self) -> &AnomalyTable {
+        &self.table
+    }
+
+    /// Replaces the table; anomalies already in effect run their course.
+    pub fn set_table(&mut self, table: AnomalyTable) {
+        self.table = table;
+    }
+
+    /// Timed anomalies still in effect.
+    pub fn active(&self) -> &[ActiveAnomaly] {
+        &self.active
+    }
+
+    /// Ends the anomalies that run out by `turn`, then rolls for a new one.
+    pub fn run(&mut self, turn: u64, targets: AnomalyTargets<'_>) -> AnomalyReport {
+        let AnomalyTargets { topology, economy, fleets } = targets;
+        let sim_time = SimTime::at_turn(Some(turn));
+        let span = Span::start("anomaly.run", &self.contexts.current()).at(sim_time);
+        let mut report = AnomalyReport { turn, ..AnomalyReport::default() };
+
+        let (expired, active): (Vec<_>, Vec<_>) =
+            std::mem::take(&mut self.active).into_iter().partition(|a| a.anomaly.expires.is_some_and(|t| t <= turn));
+        self.active = active;
+        for ended in expired {
+            match &ended.undo {
+                Undo::Lanes(lanes) => {
+                    // A lane shared with a supernova still burning stays closed.
+                    for (from, to, weight) in lanes.iter().filter(|(from, to, _)| self.closed_weight(from, to).is_none()) {
+                        topology.set_edge_weight(from, to, *weight);
+                    }
+                }
+                Undo::Modifier(name) => {
+                    if let Some(node) = economy.node_mut(&ended.anomaly.target) {
+                        node.modifiers.retain(|m| m.name != *name);
+                    }
+                }
+            }
+            let message = format!("{} at {} has passed", ended.anomaly.entry, ended.anomaly.target);
+            self.emit(EventSeverity::Info, message, &span, &ended.anomaly);
+            report.expired.push(ended.anomaly);
+        }
+
+        if let Some(index) = self.roll() {
+            let entry = self.table.entries[index].clone();
+            match self.strike(&entry, turn, topology, economy, fleets) {
+                Ok((anomaly, message)) => {
+                    self.emit(EventSeverity::Warning, message, &span, &anomaly);
+                    report.struck.push(anomaly);
+                }
+                Err(reason) => report.errors.push(AnomalyError { entry: entry.id, reason }),
+            }
+        }
+
+        if let Some(log) = &self.event_log {
+            log.end_span(
+                span.with_attribute("struck", report.struck.len())
+                    .with_attribute("expired", report.expired.len())
+                    .with_attribute("active", self.active.len()),
+            );
+        }
+        report
+    }
+
+    /// Index of the entry that comes up this turn, if any.
+    fn roll(&mut self) -> Option<usize> {
+        if self.rng.next_unit() >= self.table.chance as f64 {
+            return None;
+        }
+        let total: u64 = self.table.entries.iter().map(|e| e.weight as u64).sum();
+        if total == 0 {
+            return None;
+        }
+        let mut pick = self.rng.next_u64() % total;
+        self.table.entries.iter().position(|e| {
+            let hit = pick < e.weight as u64;
+            pick = pick.saturating_sub(e.weight as u64);
+            hit
+        })
+    }
+
+    fn strike(
+        &mut self,
+        entry: &AnomalyEntry,
+        turn: u64,
+        topology: &mut GraphTopology,
+        economy: &mut IncomeEngine,
+        fleets: Option<&mut FleetEngine>,
+    ) -> Result<(Anomaly, String), String> {
+        let anomaly = |target: &str, expires: Option<u64>| Anomaly { entry: entry.id.clone(), turn, target: target.to_string(), expires };
+        match &entry.effect {
+            AnomalyEffect::Supernova { turns } => {
+                let dark: Vec<String> = self.active.iter().filter(|a| matches!(a.undo, Undo::Lanes(_))).map(|a| a.anomaly.target.clone()).collect();
+                let systems: Vec<String> = topology.node_ids().into_iter().filter(|s| !dark.contains(s)).collect();
+                let system = self.rng.pick(&systems).cloned().ok_or("no system left to go supernova")?;
+                let mut lanes = topology.edges_touching(&system);
+                for (from, to, weight) in &mut lanes {
+                    *weight = self.closed_weight(from, to).unwrap_or(*weight);
+                    topology.set_edge_weight(from, to, f32::INFINITY);
+                }
+                let struck = anomaly(&system, Some(turn + turns.max(&1)));
+                let message = format!("A supernova at {} closes {} lanes until turn {}", system, lanes.len(), turn + turns.max(&1));
+                self.active.push(ActiveAnomaly { anomaly: struck.clone(), undo: Undo::Lanes(lanes) });
+                Ok((struck, message))
+            }
+            AnomalyEffect::Plague { income_factor, turns } => {
+                let planets: Vec<SystemId> =
+                    economy.nodes().iter().filter(|n| n.node_type == NodeType::Planet).map(|n| n.id.clone()).collect();
+                let planet = self.rng.pick(&planets).cloned().ok_or("no planet to strike")?;
+                let name = format!("{}@{}", entry.id, turn);
+                if let Some(node) = economy.node_mut(&planet) {
+                    node.modifiers.push(EconomicModifier {
+                        name: name.clone(),
+                        multiplier_scaled: (*income_factor as f64 * SCALE_FACTOR as f64) as i128,
+                        flat_bonus: Default::default(),
+                    });
+                }
+                let struck = anomaly(&planet, Some(turn + turns.max(&1)));
+                let message = format!("Plague on {}: income at {}% until turn {}", planet, (income_factor * 100.0).round(), turn + turns.max(&1));
+                self.active.push(ActiveAnomaly { anomaly: struck.clone(), undo: Undo::Modifier(name) });
+                Ok((struck, message))
+            }
+            AnomalyEffect::PirateSpawn { fleet } => {
+                let fleets = fleets.ok_or("no fleet engine to spawn pirates into")?;
+                let system = self.rng.pick(&topology.node_ids()).cloned().ok_or("no system for pirates to appear in")?;
+                let mut pirates = fleet.clone();
+                pirates.id = format!("{}-{}", fleet.id, turn);
+                pirates.location = SystemId::new(&system);
+                if fleets.fleet(&pirates.id).is_some() {
+                    return Err(format!("fleet {} already exists", pirates.id));
+                }
+                let message = format!("{} ships of {} appear at {}", pirates.ship_count(), pirates.faction, system);
+                fleets.add_fleet(pirates);
+                Ok((anomaly(&system, None), message))
+            }
+        }
+    }
+
+    /// The open weight of a lane an active supernova has closed.
+    fn closed_weight(&self, from: &str, to: &str) -> Option<f32> {
+        self.active.iter().find_map(|a| match &a.undo {
+            Undo::Lanes(lanes) => lanes.iter().find(|(f, t, _)| f == from && t == to).map(|(_, _, w)| *w),
+            Undo::Modifier(_) => None,
+        })
+    }
+
+    fn emit(&self, severity: EventSeverity, message: String, span: &Span, anomaly: &Anomaly) {
+        if let Some(log) = &self.event_log {
+            let evt = Event::new(severity, "Anomaly".to_string(), message, span.context.child(), Some(anomaly.target.clone()))
+                .with_field("anomaly", anomaly.entry.as_str())
+                .with_field("struck_turn", anomaly.turn)
+                .at(span.sim_time);
+            log.add(evt);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use void_reckoning_economy::types::{EconomicNode, GlobalEconomicRules, ResourceState};
+
+    #[test]
+    fn supernova_closes_lanes_until_it_passes() {
+        let table = AnomalyTable {
+            chance: 1.0,
+            entries: vec![
+                AnomalyEntry { id: "supernova".to_string(), weight: 1, effect: AnomalyEffect::Supernova { turns: 2 } },
+                AnomalyEntry { id: "never".to_string(), weight: 0, effect: AnomalyEffect::Supernova { turns: 9 } },
+            ],
+        };
+        let mut topology = GraphTopology::new();
+        topology.add_edge("Terra", "Mars", 1.0);
+        let mut economy = IncomeEngine::new(GlobalEconomicRules::default());
+        economy.add_node(EconomicNode {
+            id: "Terra".into(),
+            owner_faction: "Imperium".into(),
+            node_type: NodeType::Planet,
+            base_income: ResourceState::default(),
+            base_upkeep: ResourceState::default(),
+            efficiency_scaled: SCALE_FACTOR,
+            modifiers: Vec::new(),
+        });
+        let mut anomalies = AnomalyGenerator::new(table.clone(), 7);
+        let mut replay = AnomalyGenerator::from_snapshot(anomalies.snapshot());
+        let mut run = |anomalies: &mut AnomalyGenerator, turn| {
+            anomalies.run(turn, AnomalyTargets { topology: &mut topology, economy: &mut economy, fleets: None })
+        };
+
+        let first = run(&mut anomalies, 1);
+        assert_eq!(first.struck.len(), 1);
+        assert_eq!(first.struck[0].expires, Some(3));
+        let second = run(&mut anomalies, 2);
+        assert_ne!(second.struck[0].target, first.struck[0].target);
+        anomalies.set_table(AnomalyTable { chance: 0.0, ..table });
+        // The lane stays shut until both supernovae have passed.
+        assert_eq!(run(&mut anomalies, 3).expired, first.struck);
+        assert_eq!(anomalies.active().len(), 1);
+        run(&mut anomalies, 4);
+        assert_eq!(topology.find_path("Terra", "Mars", None).map(|(_, cost)| cost), Some(1.0));
+
+        topology.add_edge("Mars", "Kor", 1.0);
+        assert_eq!(replay.run(1, AnomalyTargets { topology: &mut topology, economy: &mut economy, fleets: None }).struck, first.struck);
+    }
+}
@@ -0,0 +1,253 @@
+use crate::types::{Effect, Firing, Rule, Scenario, ScenarioReport, ScenarioSnapshot, ScriptError, Trigger};
+use std::collections::BTreeSet;
+use void_reckoning_fleet::FleetEngine;
+use void_reckoning_orchestrator::{TurnEngine, TurnReport};
+use void_reckoning_pathfinder::GraphTopology;
+use void_reckoning_shared::desync;
+use void_reckoning_shared::ids::{FactionId, SystemId};
+use void_reckoning_shared::scope::ContextStack;
+use void_reckoning_shared::simtime::SimTime;
+use void_reckoning_shared::span::Span;
+use void_reckoning_shared::{CorrelationContext, Event, EventLog, EventSeverity};
+
+/// What effects act on. Without `fleets`, `SpawnFleet` effects fail.
+pub struct ScenarioTargets<'a> {
+    pub turns: &'a mut TurnEngine,
+    pub topology: &'a mut GraphTopology,
+    pub fleets: Option<&'a mut FleetEngine>,
+}
+
+pub struct ScenarioEngine {
+    scenario: Scenario,
+    fired: BTreeSet<String>,
+    /// Falls back to the turn engine's log when None.
+    pub event_log: Option<EventLog>,
+    /// Correlation contexts; nested operations push scopes onto this.
+    pub contexts: ContextStack,
+}
+
+impl ScenarioEngine {
+    pub fn new(scenario: Scenario) -> Self {
+        Self::from_snapshot(ScenarioSnapshot { scenario, ..ScenarioSnapshot::default() })
+    }
+
+    pub fn from_snapshot(snapshot: ScenarioSnapshot) -> Self {
+        Self { scenario: snapshot.scenario, fired: snapshot.fired, event_log: None, contexts: ContextStack::default() }
+    }
+
+    pub fn snapshot(&self) -> ScenarioSnapshot {
+        ScenarioSnapshot { scenario: self.scenario.clone(), fired: self.fired.clone() }
+    }
+
+    /// FNV-1a of the snapshot's JSON encoding.
+    pub fn state_hash(&self) -> u64 {
+        desync::hash_json(&self.snapshot())
+    }
+
+    pub fn set_event_log(&mut self, log: EventLog) {
+        self.event_log = Some(log);
+    }
+
+    pub fn set_correlation_context(&mut self, context: CorrelationContext) {
+        self.contexts.set_root(context);
+    }
+
+    pub fn scenario(&self) -> &Scenario {
+        &self.scenario
+    }
+
+    /// Ids of one-shot rules that have already fired.
+    pub fn fired(&self) -> &BTreeSet<String> {
+        &self.fired
+    }
+
+    /// The rules `report` sets off, in rule order, without applying them.
+    /// A one-shot rule yields at most one firing and none once it has fired.
+    pub fn evaluate(&self, report: &TurnReport) -> Vec<Firing> {
+        let mut firings = Vec::new();
+        for rule in &self.scenario.rules {
+            if !rule.repeat && self.fired.contains(&rule.id) {
+                continue;
+            }
+            let matches = matches(rule, report);
+            let take = if rule.repeat { matches.len() } else { 1 };
+            firings.extend(matches.into_iter().take(take));
+        }
+        firings
+    }
+
+    /// Fires the rules `report` sets off and applies their effects.
+    pub fn run(&mut self, report: &TurnReport, targets: ScenarioTargets<'_>) -> ScenarioReport {
+        let ScenarioTargets { turns, topology, mut fleets } = targets;
+        let log = self.event_log.clone().or_else(|| turns.event_log.clone());
+        let sim_time = SimTime::at_turn(Some(report.turn));
+        let span = Span::start("scenario.run", &self.contexts.current())
+            .with_attribute("scenario", self.scenario.name.as_str())
+            .at(sim_time);
+
+        let fired = self.evaluate(report);
+        let mut errors = Vec::new();
+        for firing in &fired {
+            let Some(rule) = self.scenario.rules.iter().find(|r| r.id == firing.rule) else {
+                continue;
+            };
+            if !rule.repeat {
+                self.fired.insert(rule.id.clone());
+            }
+            for effect in &rule.effects {
+                let applied = apply(effect, firing, turns, topology, fleets.as_deref_mut());
+                let message = match &applied {
+                    Ok(message) => message.clone(),
+                    Err(reason) => format!("Rule {} failed: {}", rule.id, reason),
+                };
+                if let Some(log) = &log {
+                    let severity = if applied.is_ok() { EventSeverity::Info } else { EventSeverity::Warning };
+                    let evt = Event::new(severity, "Scenario".to_string(), message, span.context.child(), Some(rule.id.clone()))
+                        .with_field("rule", rule.id.as_str())
+                        .at(sim_time);
+                    log.add(evt);
+                }
+                if let Err(reason) = applied {
+                    errors.push(ScriptError { rule: rule.id.clone(), reason });
+                }
+            }
+        }
+
+        if let Some(log) = &log {
+            log.end_span(span.with_attribute("fired", fired.len()).with_attribute("errors", errors.len()));
+        }
+        ScenarioReport { turn: report.turn, fired, errors }
+    }
+}
+
+fn matches(rule: &Rule, report: &TurnReport) -> Vec<Firing> {
+    let firing = |faction: Option<&str>, system: Option<&str>| Firing {
+        rule: rule.id.clone(),
+        turn: report.turn,
+        faction: faction.map(FactionId::new),
+        system: system.map(SystemId::new),
+    };
+    match &rule.trigger {
+        Trigger::OnTurn { turn } if *turn == report.turn => vec![firing(None, None)],
+        Trigger::EveryTurns { interval, start }
+            if *interval > 0 && report.turn >= *start && (report.turn - start).is_multiple_of(*interval) =>
+        {
+            vec![firing(None, None)]
+        }
+        Trigger::FactionInsolvent { faction } => report
+            .factions
+            .iter()
+            .filter(|(name, books)| books.is_insolvent && faction.as_ref().is_none_or(|f| f == *name))
+            .map(|(name, _)| firing(Some(name), None))
+            .collect(),
+        Trigger::SystemCaptured { system, by } => report
+            .invasions
+            .iter()
+            .filter(|o| o.planet_changed_hands())
+            .filter(|o| system.as_ref().is_none_or(|s| *s == o.planet) && by.as_ref().is_none_or(|b| *b == o.attacker))
+            .map(|o| firing(Some(&o.attacker), Some(&o.planet)))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Applies one effect; Ok carries the message logged for it.
+fn apply(
+    effect: &Effect,
+    firing: &Firing,
+    turns: &mut TurnEngine,
+    topology: &mut GraphTopology,
+    fleets: Option<&mut FleetEngine>,
+) -> Result<String, String> {
+    match effect {
+        Effect::SpawnFleet { fleet } => {
+            let fleets = fleets.ok_or("no fleet engine to spawn into")?;
+            if fleets.fleet(&fleet.id).is_some() {
+                return Err(format!("fleet {} already exists", fleet.id));
+            }
+            fleets.add_fleet(fleet.clone());
+            Ok(format!("Spawned fleet {} for {} at {}", fleet.id, fleet.faction, fleet.location))
+        }
+        Effect::GrantResources { faction, resources } => {
+            let faction = faction.as_ref().or(firing.faction.as_ref()).ok_or("no faction to grant resources to")?;
+            let mut treasury = turns.treasury(faction);
+            treasury.add(resources);
+            turns.set_treasury(faction.clone(), treasury);
+            Ok(format!("Granted {} resources", faction))
+        }
+        Effect::SetEdgeWeight { from, to, weight } => match topology.set_edge_weight(from, to, *weight) {
+            0 => Err(format!("no route from {} to {}", from, to)),
+            _ => Ok(format!("Route {} -> {} now costs {}", from, to, weight)),
+        },
+        Effect::Message { text } => Ok(text.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use void_reckoning_economy::types::{ResourceState, SCALE_FACTOR};
+    use void_reckoning_orchestrator::FactionTurn;
+
+    fn report(turn: u64, insolvent: &[&str]) -> TurnReport {
+        TurnReport {
+            turn,
+            trace_id: String::new(),
+            factions: insolvent
+                .iter()
+                .map(|&f| (f.into(), FactionTurn { is_insolvent: true, ..FactionTurn::default() }))
+                .collect::<BTreeMap<_, _>>(),
+            unattributed_trade: ResourceState::default(),
+            severed_routes: 0,
+            embargoed_routes: 0,
+            invasions: Vec::new(),
+            rejected_invasions: Vec::new(),
+            completed: Vec::new(),
+            stalled: Vec::new(),
+            audit: None,
+            colonization: None,
+            victory: None,
+            actions: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn rules_fire_once_unless_repeating() {
+        let bailout = ResourceState { credits: 100 * SCALE_FACTOR, ..ResourceState::default() };
+        let scenario = Scenario {
+            name: "Test".to_string(),
+            rules: vec![
+                Rule {
+                    id: "bailout".to_string(),
+                    trigger: Trigger::FactionInsolvent { faction: None },
+                    effects: vec![Effect::GrantResources { faction: None, resources: bailout }],
+                    repeat: true,
+                },
+                Rule {
+                    id: "warp-storm".to_string(),
+                    trigger: Trigger::OnTurn { turn: 2 },
+                    effects: vec![
+                        Effect::SetEdgeWeight { from: "Terra".into(), to: "Mars".into(), weight: 9.0 },
+                        Effect::SetEdgeWeight { from: "Mars".into(), to: "Kor".into(), weight: 9.0 },
+                    ],
+                    repeat: false,
+                },
+            ],
+        };
+        let mut engine = ScenarioEngine::new(scenario);
+        let mut turns = TurnEngine::new("u1".to_string());
+        let mut topology = GraphTopology::new();
+        topology.add_edge("Terra", "Mars", 1.0);
+
+        let first = engine.run(&report(1, &["Orks", "Eldar"]), ScenarioTargets { turns: &mut turns, topology: &mut topology, fleets: None });
+        assert_eq!(first.fired.len(), 2);
+        assert_eq!(turns.treasury("Orks"), bailout);
+
+        let second = engine.run(&report(2, &[]), ScenarioTargets { turns: &mut turns, topology: &mut topology, fleets: None });
+        assert_eq!(second.fired.len(), 1);
+        assert_eq!(second.errors.len(), 1);
+        assert_eq!(topology.find_path("Terra", "Mars", None).map(|(_, cost)| cost), Some(9.0));
+        assert!(engine.evaluate(&report(2, &[])).is_empty());
+    }
+}
@@ -0,0 +1,357 @@
+//! Space weather: ion storms, nebulae and the like, each covering a region
+//! of systems that forms, spreads along lanes and dissipates turn by turn.
+//! Hazards act only through named modifiers: a lane modifier on every lane
+//! into or out of the region, a route modifier on every trade route with an
+//! end in it, and an accuracy factor battles there ask for. Each modifier
+//! carries the hazard's id as its source, so the pathfinder and trade
+//! manager can say what slowed a lane down and drop it when the hazard
+//! passes. Kinds are data, loaded as JSON; like the anomaly generator the
+//! random state is part of the snapshot.
+
+use serde::{Deserialize, Serialize};
+use void_reckoning_economy::trade::TradeRouteManager;
+use void_reckoning_economy::types::SCALE_FACTOR;
+use void_reckoning_pathfinder::GraphTopology;
+use void_reckoning_shared::desync;
+use void_reckoning_shared::rng::SplitMix64;
+use void_reckoning_shared::scope::ContextStack;
+use void_reckoning_shared::simtime::SimTime;
+use void_reckoning_shared::span::Span;
+use void_reckoning_shared::{CorrelationContext, Event, EventLog, EventSeverity};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HazardKind {
+    pub id: String,
+    /// Relative odds against the table's other kinds when a hazard forms.
+    pub weight: u32,
+    /// How many turns a hazard of this kind lasts.
+    pub turns: u64,
+    /// Multiplies the cost of every lane into or out of the region.
+    #[serde(default = "unchanged")]
+    pub lane_factor: f32,
+    /// Multiplies weapon accuracy in battles inside the region.
+    #[serde(default = "unchanged")]
+    pub accuracy_factor: f32,
+    /// Multiplies the efficiency of trade routes with an end in the region.
+    #[serde(default = "unchanged")]
+    pub trade_factor: f32,
+    /// Odds (0 to 1) each turn of spreading into a neighbouring system.
+    #[serde(default)]
+    pub spread: f32,
+    /// The most systems the region grows to.
+    #[serde(default = "one_system")]
+    pub max_systems: usize,
+}
+
+fn unchanged() -> f32 {
+    1.0
+}
+
+fn one_system() -> usize {
+    1
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HazardTable {
+    /// Odds (0 to 1) that a new hazard forms on a turn.
+    #[serde(default)]
+    pub chance: f32,
+    pub kinds: Vec<HazardKind>,
+}
+
+/// A hazard in effect.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Hazard {
+    /// `kind@turn`; the source of every modifier it sets.
+    pub id: String,
+    /// `HazardKind::id`.
+    pub kind: String,
+    /// Systems covered, in the order it reached them.
+    pub region: Vec<String>,
+    pub formed: u64,
+    /// The turn it dissipates on.
+    pub expires: u64,
+}
+
+/// A hazard that reached another system.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Spread {
+    pub hazard: String,
+    pub system: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HazardReport {
+    pub turn: u64,
+    pub formed: Vec<Hazard>,
+    pub spread: Vec<Spread>,
+    pub dissipated: Vec<Hazard>,
+}
+
+/// Serializable copy of a `HazardLayer`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HazardSnapshot {
+    pub table: HazardTable,
+    /// Random state; the seed until the first roll.
+    #[serde(default)]
+    pub rng: SplitMix64,
+    #[serde(default)]
+    pub active: Vec<Hazard>,
+}
+
+pub struct HazardLayer {
+    table: HazardTable,
+    rng: SplitMix64,
+    active: Vec<Hazard>,
+    pub event_log: Option<EventLog>,
+    /// Correlation contexts; nested operations push scopes onto this.
+    pub contexts: ContextStack,
+}
+
+impl HazardLayer {
+    pub fn new(table: HazardTable, seed: u64) -> Self {
+        Self::from_snapshot(HazardSnapshot { table, rng: SplitMix64::new(seed), active: Vec::new() })
+    }
+
+    pub fn from_snapshot(snapshot: HazardSnapshot) -> Self {
+        Self { table: snapshot.table, rng: snapshot.rng, active: snapshot.active, event_log: None, contexts: ContextStack::default() }
+    }
+
+    pub fn snapshot(&self) -> HazardSnapshot {
+        HazardSnapshot { table: self.table.clone(), rng: self.rng, active: self.active.clone() }
+    }
+
+    /// FNV-1a of the snapshot's JSON encoding.
+    pub fn state_hash(&self) -> u64 {
+        desync::hash_json(&self.snapshot())
+    }
+
+    pub fn set_event_log(&mut self, log: EventLog) {
+        self.event_log = Some(log);
+    }
+
+    pub fn set_correlation_context(&mut self, context: CorrelationContext) {
+        self.contexts.set_root(context);
+    }
+
+    pub fn table(&self) -> &HazardTable {
+        &self.table
+    }
+
+    /// Replaces the table. Hazards of kinds it drops stop spreading and
+    /// lose their effects at the next `apply`, but still dissipate on time.
+    pub fn set_table(&mut self, table: HazardTable) {
+        self.table = table;
+    }
+
+    pub fn active(&self) -> &[Hazard] {
+        &self.active
+    }
+
+    /// Dissipates hazards that run out by `turn`, spreads the rest, rolls
+    /// for a new one, then applies them all to `topology` and `trade`.
+    pub fn advance(&mut self, turn: u64, topology: &mut GraphTopology, mut trade: Option<&mut TradeRouteManager>) -> HazardReport {
+        let sim_time = SimTime::at_turn(Some(turn));
+        let span = Span::start("hazard.advance", &self.contexts.current()).at(sim_time);
+        let mut report = HazardReport { turn, ..HazardReport::default() };
+
+        let (dissipated, active): (Vec<_>, Vec<_>) = std::mem::take(&mut self.active).into_iter().partition(|h| h.expires <= turn);
+        self.active = active;
+        for hazard in dissipated {
+            topology.remove_lane_modifiers(&hazard.id);
+            if let Some(trade) = trade.as_deref_mut() {
+                trade.remove_route_modifiers(&hazard.id);
+            }
+            self.emit(EventSeverity::Info, format!("{} over {} has dissipated", hazard.kind, hazard.region.join(", ")), &span, &hazard);
+            report.dissipated.push(hazard);
+        }
+
+        for index in 0..self.active.len() {
+            let Some(kind) = self.kind(&self.active[index].kind).cloned() else { continue };
+            if self.active[index].region.len() >= kind.max_systems || self.rng.next_unit() >= kind.spread as f64 {
+                continue;
+            }
+            let region = &self.active[index].region;
+            let mut frontier: Vec<String> = Vec::new();
+            for system in region {
+                for (from, to, _) in topology.edges_touching(system) {
+                    let neighbour = if from == *system { to } else { from };
+                    if !region.contains(&neighbour) && !frontier.contains(&neighbour) {
+                        frontier.push(neighbour);
+                    }
+                }
+            }
+            if let Some(system) = self.rng.pick(&frontier).cloned() {
+                let hazard = &mut self.active[index];
+                hazard.region.push(system.clone());
+                let message = format!("{} spreads to {}", hazard.kind, system);
+                let hazard = hazard.clone();
+                self.emit(EventSeverity::Info, message, &span, &hazard);
+                report.spread.push(Spread { hazard: hazard.id, system });
+            }
+        }
+
+        if let Some(index) = self.roll() {
+            let kind = self.table.kinds[index].clone();
+            if let Some(system) = self.rng.pick(&topology.node_ids()).cloned() {
+                let expires = turn + kind.turns.max(1);
+                let hazard = Hazard { id: format!("{}@{}", kind.id, turn), kind: kind.id, region: vec![system], formed: turn, expires };
+                let message = format!("{} forms over {} until turn {}", hazard.kind, hazard.region[0], expires);
+                self.emit(EventSeverity::Warning, message, &span, &hazard);
+                self.active.push(hazard.clone());
+                report.formed.push(hazard);
+            }
+        }
+
+        self.apply(topology, trade);
+        if let Some(log) = &self.event_log {
+            log.end_span(
+                span.with_attribute("formed", report.formed.len())
+                    .with_attribute("spread", report.spread.len())
+                    .with_attribute("dissipated", report.dissipated.len())
+                    .with_attribute("active", self.active.len()),
+            );
+        }
+        report
+    }
+
+    /// Sets every active hazard's lane and route modifiers afresh, e.g.
+    /// after the topology was rebuilt. Route modifiers take effect at the
+    /// next `calculate_efficiencies`.
+    pub fn apply(&self, topology: &mut GraphTopology, mut trade: Option<&mut TradeRouteManager>) {
+        for hazard in &self.active {
+            topology.remove_lane_modifiers(&hazard.id);
+            if let Some(trade) = trade.as_deref_mut() {
+                trade.remove_route_modifiers(&hazard.id);
+            }
+            let Some(kind) = self.kind(&hazard.kind) else { continue };
+            if kind.lane_factor != 1.0 {
+                for system in &hazard.region {
+                    for (from, to, _) in topology.edges_touching(system) {
+                        topology.set_lane_modifier(&from, &to, &hazard.id, kind.lane_factor);
+                    }
+                }
+            }
+            if let Some(trade) = trade.as_deref_mut().filter(|_| kind.trade_factor != 1.0) {
+                let multiplier = (kind.trade_factor as f64 * SCALE_FACTOR as f64) as i128;
+                trade.set_route_modifier(&hazard.id, multiplier, |route| hazard.region.iter().any(|s| *s == route.from || *s == route.to));
+            }
+        }
+    }
+
+    /// Product of the lane factors of the hazards over either end.
+    pub fn lane_factor(&self, from: &str, to: &str) -> f32 {
+        self.factor(|kind| kind.lane_factor, |region| region.iter().any(|s| s == from || s == to))
+    }
+
+    /// Product of the accuracy factors of the hazards over `system`; hand
+    /// it to `BattleEngine::scale_accuracy` for a battle fought there.
+    pub fn accuracy_factor(&self, system: &str) -> f32 {
+        self.factor(|kind| kind.accuracy_factor, |region| region.iter().any(|s| s == system))
+    }
+
+    /// Product of the trade factors of the hazards over either end.
+    pub fn trade_factor(&self, from: &str, to: &str) -> f32 {
+        self.factor(|kind| kind.trade_factor, |region| region.iter().any(|s| s == from || s == to))
+    }
+
+    fn factor(&self, of: impl Fn(&HazardKind) -> f32, covers: impl Fn(&[String]) -> bool) -> f32 {
+        self.active
+            .iter()
+            .filter(|h| covers(&h.region))
+            .filter_map(|h| self.kind(&h.kind))
+            .fold(1.0, |factor, kind| factor * of(kind))
+    }
+
+    fn kind(&self, id: &str) -> Option<&HazardKind> {
+        self.table.kinds.iter().find(|k| k.id == id)
+    }
+
+    /// Index of the kind that forms this turn, if any.
+    fn roll(&mut self) -> Option<usize> {
+        if self.rng.next_unit() >= self.table.chance as f64 {
+            return None;
+        }
+        let total: u64 = self.table.kinds.iter().map(|k| k.weight as u64).sum();
+        if total == 0 {
+            return None;
+        }
+        let mut pick = self.rng.next_u64() % total;
+        self.table.kinds.iter().position(|k| {
+            let hit = pick < k.weight as u64;
+            pick = pick.saturating_sub(k.weight as u64);
+            hit
+        })
+    }
+
+    fn emit(&self, severity: EventSeverity, message: String, span: &Span, hazard: &Hazard) {
+        if let Some(log) = &self.event_log {
+            let evt = Event::new(severity, "Hazard".to_string(), message, span.context.child(), hazard.region.first().cloned())
+                .with_field("hazard", hazard.id.as_str())
+                .with_field("systems", hazard.region.len())
+                .at(span.sim_time);
+            log.add(evt);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use void_reckoning_economy::trade::TradeRoute;
+    use void_reckoning_economy::types::ResourceState;
+
+    #[test]
+    fn ion_storm_spreads_slows_lanes_and_lifts() {
+        let storm = HazardKind {
+            id: "ion_storm".to_string(),
+            weight: 1,
+            turns: 3,
+            lane_factor: 1.5,
+            accuracy_factor: 0.5,
+            trade_factor: 0.5,
+            spread: 1.0,
+            max_systems: 2,
+        };
+        let table = HazardTable { chance: 1.0, kinds: vec![storm] };
+        let mut topology = GraphTopology::new();
+        topology.add_edge("Terra", "Mars", 1.0);
+        topology.add_edge("Mars", "Terra", 1.0);
+        let mut trade = TradeRouteManager::new();
+        trade.add_route(TradeRoute {
+            from: "Terra".to_string(),
+            to: "Mars".to_string(),
+            base_value: ResourceState::default(),
+            efficiency_scaled: 0,
+            modifiers: Vec::new(),
+        });
+        let mut hazards = HazardLayer::new(table.clone(), 11);
+        let mut replay = HazardLayer::from_snapshot(hazards.snapshot());
+
+        let first = hazards.advance(1, &mut topology, Some(&mut trade));
+        assert_eq!(first.formed.len(), 1);
+        let id = first.formed[0].id.clone();
+        assert!(topology.lane_modifiers().iter().all(|m| m.source == id && m.factor == 1.5));
+        assert_eq!(topology.find_path("Terra", "Mars", None).map(|(_, cost)| cost), Some(1.5));
+        assert_eq!(hazards.accuracy_factor(&first.formed[0].region[0]), 0.5);
+        trade.calculate_efficiencies(&topology);
+        // Half the efficiency from the slower lane, half of that from the storm.
+        assert_eq!(trade.routes()[0].efficiency_scaled, SCALE_FACTOR / 4);
+
+        hazards.set_table(HazardTable { chance: 0.0, ..table });
+        let second = hazards.advance(2, &mut topology, Some(&mut trade));
+        assert_eq!(second.spread.len(), 1);
+        assert_eq!(hazards.active()[0].region.len(), 2);
+        assert_eq!(hazards.accuracy_factor("Terra"), 0.5);
+        assert_eq!(hazards.accuracy_factor("Kor"), 1.0);
+
+        assert_eq!(hazards.advance(4, &mut topology, Some(&mut trade)).dissipated[0].id, id);
+        assert!(topology.lane_modifiers().is_empty() && trade.routes()[0].modifiers.is_empty());
+        assert_eq!(topology.find_path("Terra", "Mars", None).map(|(_, cost)| cost), Some(1.0));
+
+        let mut fresh = GraphTopology::new();
+        fresh.add_edge("Terra", "Mars", 1.0);
+        fresh.add_edge("Mars", "Terra", 1.0);
+        assert_eq!(replay.advance(1, &mut fresh, None).formed, first.formed);
+    }
+}
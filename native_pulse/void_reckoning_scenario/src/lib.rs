@@ -0,0 +1,27 @@
+//! Scenario scripting: rules that fire on campaign events (a given turn, a
+//! faction going insolvent, a planet captured) and apply effects (spawn a
+//! fleet, grant resources, change a route's cost) natively after each
+//! turn. Scenarios are data, loaded as JSON, so scripted campaigns need no
+//! Python plugin. Anomalies are the random counterpart: seeded rolls
+//! against a weighted table of galactic events. Hazards are the weather:
+//! storms and nebulae that drift over regions of the map and act on lanes,
+//! trade and battles through named modifiers. Names gives systems,
+//! planets, characters and fleets reproducible names from per-faction
+//! phoneme and grammar tables.
+
+pub mod types;
+pub mod engine;
+pub mod anomaly;
+pub mod hazard;
+pub mod names;
+
+pub use anomaly::{Anomaly, AnomalyEffect, AnomalyEntry, AnomalyGenerator, AnomalyReport, AnomalySnapshot, AnomalyTable, AnomalyTargets};
+pub use engine::{ScenarioEngine, ScenarioTargets};
+pub use hazard::{Hazard, HazardKind, HazardLayer, HazardReport, HazardSnapshot, HazardTable, Spread};
+pub use names::{NameGenerator, NameKind, NameRequest, NameTables, Phonology, Syllables};
+pub use types::{Effect, Firing, Rule, Scenario, ScenarioReport, ScenarioSnapshot, ScriptError, Trigger};
+
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Version of the `Scenario`/`ScenarioReport` layouts; bumped on
+/// incompatible changes.
+pub const SCHEMA_VERSION: u32 = 1;
@@ -0,0 +1,356 @@
+//! Procedural names: systems, planets, characters and fleets, plus short
+//! lore lines, drawn from per-faction phoneme and grammar tables loaded as
+//! data.
+//!
+//! A faction's `Phonology` builds root words from syllables (onset,
+//! nucleus, coda) and fills templates per kind, e.g. `"{root} Prime"` or
+//! `"{title} {root}"`. In a template, `{root}` is a fresh word, `{parent}`
+//! and `{numeral}`/`{number}` come from the request, and any other
+//! `{name}` picks from the faction's word list of that name, whose entries
+//! are templates in turn.
+//!
+//! Names don't depend on call order: each is drawn from a stream seeded by
+//! the generator's seed, the kind, the faction and the request's key, so
+//! the same key always gets the same name.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use void_reckoning_shared::desync;
+use void_reckoning_shared::rng::SplitMix64;
+use void_reckoning_shared::errors::EngineError;
+
+/// How deep word lists may refer to other lists.
+const MAX_DEPTH: usize = 8;
+/// Draws per name before `names` falls back to a numeral suffix.
+const MAX_ATTEMPTS: u32 = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NameKind {
+    System,
+    Planet,
+    Character,
+    Fleet,
+    /// A line of flavour text about `parent`.
+    Lore,
+}
+
+impl NameKind {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "system" => Some(NameKind::System),
+            "planet" => Some(NameKind::Planet),
+            "character" => Some(NameKind::Character),
+            "fleet" => Some(NameKind::Fleet),
+            "lore" => Some(NameKind::Lore),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NameKind::System => "system",
+            NameKind::Planet => "planet",
+            NameKind::Character => "character",
+            NameKind::Fleet => "fleet",
+            NameKind::Lore => "lore",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Syllables {
+    /// Leading consonants; `""` allows a syllable without one.
+    #[serde(default)]
+    pub onsets: Vec<String>,
+    pub nuclei: Vec<String>,
+    #[serde(default)]
+    pub codas: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Phonology {
+    pub syllables: Syllables,
+    #[serde(default = "one")]
+    pub min_syllables: u32,
+    #[serde(default = "three")]
+    pub max_syllables: u32,
+    /// Templates per kind; a kind without any is just `{root}`.
+    #[serde(default)]
+    pub templates: BTreeMap<NameKind, Vec<String>>,
+    /// Word lists templates refer to by name, e.g. `"title"`.
+    #[serde(default)]
+    pub words: BTreeMap<String, Vec<String>>,
+}
+
+fn one() -> u32 {
+    1
+}
+
+fn three() -> u32 {
+    3
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NameTables {
+    pub factions: BTreeMap<String, Phonology>,
+    /// Table for factions not listed; None makes them an error.
+    #[serde(default)]
+    pub fallback: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NameRequest {
+    pub kind: NameKind,
+    pub faction: String,
+    /// Stable id of what is being named, e.g. a system id or a seed index.
+    pub key: String,
+    /// Fills `{parent}`: the system a planet orbits, the subject of lore.
+    #[serde(default)]
+    pub parent: Option<String>,
+    /// Fills `{numeral}` and `{number}`; defaults to 1.
+    #[serde(default)]
+    pub index: Option<u32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NameGenerator {
+    tables: NameTables,
+    seed: u64,
+}
+
+impl NameGenerator {
+    /// Checks every table can produce a name: nuclei to build words from,
+    /// a sane syllable range, known lists in templates and a listed
+    /// fallback.
+    pub fn new(tables: NameTables, seed: u64) -> Result<Self, EngineError> {
+        let invalid = |faction: &str, message: String| EngineError::Registry(format!("name table '{}': {}", faction, message));
+        if let Some(fallback) = &tables.fallback {
+            if !tables.factions.contains_key(fallback) {
+                return Err(EngineError::Registry(format!("fallback name table '{}' is not defined", fallback)));
+            }
+        }
+        for (faction, phonology) in &tables.factions {
+            if phonology.syllables.nuclei.is_empty() {
+                return Err(invalid(faction, "no nuclei".to_string()));
+            }
+            if phonology.min_syllables == 0 || phonology.min_syllables > phonology.max_syllables {
+                return Err(invalid(faction, format!("bad syllable range {}..={}", phonology.min_syllables, phonology.max_syllables)));
+            }
+            let templates = phonology.templates.values().chain(phonology.words.values()).flatten();
+            for template in templates {
+                for token in tokens(template) {
+                    let known = matches!(token, "root" | "parent" | "numeral" | "number") || phonology.words.contains_key(token);
+                    if !known {
+                        return Err(invalid(faction, format!("unknown word list '{}' in \"{}\"", token, template)));
+                    }
+                }
+            }
+            if let Some((list, _)) = phonology.words.iter().find(|(_, entries)| entries.is_empty()) {
+                return Err(invalid(faction, format!("word list '{}' is empty", list)));
+            }
+        }
+        Ok(Self { tables, seed })
+    }
+
+    pub fn from_json(json: &str, seed: u64) -> Result<Self, EngineError> {
+        Self::new(serde_json::from_str(json).map_err(EngineError::json)?, seed)
+    }
+
+    pub fn tables(&self) -> &NameTables {
+        &self.tables
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// The name for `request`; the same for the same seed and request.
+    pub fn name(&self, request: &NameRequest) -> Result<String, EngineError> {
+        self.draw(request, 0)
+    }
+
+    /// Names every request, giving no two the same name: a clash is drawn
+    /// again, and after `MAX_ATTEMPTS` tries gets a numeral suffix.
+    pub fn names(&self, requests: &[NameRequest]) -> Result<Vec<String>, EngineError> {
+        let mut taken = BTreeSet::new();
+        let mut names = Vec::with_capacity(requests.len());
+        for request in requests {
+            let mut name = self.draw(request, 0)?;
+            let mut attempt = 1;
+            while taken.contains(&name) && attempt < MAX_ATTEMPTS {
+                name = self.draw(request, attempt)?;
+                attempt += 1;
+            }
+            let base = name.clone();
+            let mut suffix = 2;
+            while taken.contains(&name) {
+                name = format!("{} {}", base, roman(suffix));
+                suffix += 1;
+            }
+            taken.insert(name.clone());
+            names.push(name);
+        }
+        Ok(names)
+    }
+
+    fn phonology(&self, faction: &str) -> Result<&Phonology, EngineError> {
+        self.tables
+            .factions
+            .get(faction)
+            .or_else(|| self.tables.fallback.as_ref().and_then(|f| self.tables.factions.get(f)))
+            .ok_or_else(|| EngineError::Registry(format!("no name table for faction '{}'", faction)))
+    }
+
+    fn draw(&self, request: &NameRequest, attempt: u32) -> Result<String, EngineError> {
+        let phonology = self.phonology(&request.faction)?;
+        let stream = format!("{}\0{}\0{}\0{}\0{}", self.seed, request.kind.as_str(), request.faction, request.key, attempt);
+        let mut draw = Draw { phonology, request, rng: SplitMix64::new(desync::hash_bytes(stream.as_bytes())) };
+        let template = match phonology.templates.get(&request.kind) {
+            Some(templates) if !templates.is_empty() => draw.pick(templates).to_string(),
+            _ => "{root}".to_string(),
+        };
+        Ok(draw.expand(&template, 0))
+    }
+}
+
+/// Names between braces in `template`.
+fn tokens(template: &str) -> impl Iterator<Item = &str> {
+    template.split('{').skip(1).filter_map(|part| part.split_once('}').map(|(token, _)| token))
+}
+
+struct Draw<'a> {
+    phonology: &'a Phonology,
+    request: &'a NameRequest,
+    rng: SplitMix64,
+}
+
+impl Draw<'_> {
+    fn expand(&mut self, template: &str, depth: usize) -> String {
+        let mut out = String::new();
+        let mut rest = template;
+        while let Some(open) = rest.find('{') {
+            let Some(close) = rest[open..].find('}').map(|c| open + c) else { break };
+            out.push_str(&rest[..open]);
+            let index = self.request.index.unwrap_or(1);
+            match &rest[open + 1..close] {
+                "root" => out.push_str(&self.root()),
+                "parent" => out.push_str(self.request.parent.as_deref().unwrap_or_default()),
+                "numeral" => out.push_str(&roman(index)),
+                "number" => out.push_str(&index.to_string()),
+                list => {
+                    let phonology = self.phonology;
+                    if let (Some(entries), true) = (phonology.words.get(list), depth < MAX_DEPTH) {
+                        let entry = self.pick(entries).to_string();
+                        out.push_str(&self.expand(&entry, depth + 1));
+                    }
+                }
+            }
+            rest = &rest[close + 1..];
+        }
+        out.push_str(rest);
+        out
+    }
+
+    fn root(&mut self) -> String {
+        let phonology = self.phonology;
+        let span = (phonology.max_syllables - phonology.min_syllables + 1) as u64;
+        let count = phonology.min_syllables as u64 + self.rng.next_u64() % span;
+        let mut word = String::new();
+        for _ in 0..count {
+            for part in [&phonology.syllables.onsets, &phonology.syllables.nuclei, &phonology.syllables.codas] {
+                if !part.is_empty() {
+                    word.push_str(self.pick(part));
+                }
+            }
+        }
+        let mut chars = word.chars();
+        match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => word,
+        }
+    }
+
+    fn pick<'s>(&mut self, items: &'s [String]) -> &'s str {
+        self.rng.pick(items).map_or("", String::as_str)
+    }
+}
+
+/// Roman numeral for `n`; 0 is written as a plain digit.
+fn roman(mut n: u32) -> String {
+    if n == 0 {
+        return "0".to_string();
+    }
+    const NUMERALS: [(u32, &str); 13] = [
+        (1000, "M"),
+        (900, "CM"),
+        (500, "D"),
+        (400, "CD"),
+        (100, "C"),
+        (90, "XC"),
+        (50, "L"),
+        (40, "XL"),
+        (10, "X"),
+        (9, "IX"),
+        (5, "V"),
+        (4, "IV"),
+        (1, "I"),
+    ];
+    let mut out = String::new();
+    for (value, numeral) in NUMERALS {
+        while n >= value {
+            out.push_str(numeral);
+            n -= value;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tables() -> NameTables {
+        serde_json::from_str(
+            r#"{
+                "factions": {"Imperium": {
+                    "syllables": {"onsets": ["t", "m", "k", ""], "nuclei": ["a", "e", "o"], "codas": ["r", "n", ""]},
+                    "min_syllables": 2,
+                    "max_syllables": 3,
+                    "templates": {
+                        "planet": ["{parent} {numeral}"],
+                        "character": ["{title} {root}"]
+                    },
+                    "words": {"title": ["Lord", "Saint {root}"]}
+                }},
+                "fallback": "Imperium"
+            }"#,
+        )
+        .unwrap()
+    }
+
+    fn request(kind: NameKind, key: &str) -> NameRequest {
+        NameRequest { kind, faction: "Imperium".to_string(), key: key.to_string(), parent: None, index: None }
+    }
+
+    #[test]
+    fn names_follow_the_grammar_and_repeat_for_the_same_key() {
+        let names = NameGenerator::new(tables(), 7).unwrap();
+        let system = names.name(&request(NameKind::System, "sys-1")).unwrap();
+        assert!(system.chars().next().unwrap().is_uppercase());
+        assert_eq!(names.name(&request(NameKind::System, "sys-1")).unwrap(), system);
+        assert_eq!(NameGenerator::new(tables(), 7).unwrap().name(&request(NameKind::System, "sys-1")).unwrap(), system);
+
+        let planet = NameRequest { parent: Some(system.clone()), index: Some(4), ..request(NameKind::Planet, "p") };
+        assert_eq!(names.name(&planet).unwrap(), format!("{} IV", system));
+        let character = names.name(&NameRequest { faction: "Orks".to_string(), ..request(NameKind::Character, "c") }).unwrap();
+        assert!(character.starts_with("Lord ") || character.starts_with("Saint "));
+
+        let batch: Vec<_> = (0..3).map(|_| NameRequest { parent: Some("Sol".into()), ..request(NameKind::Planet, "same") }).collect();
+        assert_eq!(names.names(&batch).unwrap(), vec!["Sol I", "Sol I II", "Sol I III"]);
+
+        let mut broken = tables();
+        broken.factions.get_mut("Imperium").unwrap().words.clear();
+        assert!(NameGenerator::new(broken, 7).is_err());
+    }
+}
@@ -0,0 +1,99 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use void_reckoning_economy::types::ResourceState;
+use void_reckoning_fleet::Fleet;
+use void_reckoning_shared::ids::{FactionId, SystemId};
+
+/// When a rule fires, checked against each turn's report. Externally
+/// tagged: `{"OnTurn": {"turn": 10}}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Trigger {
+    OnTurn { turn: u64 },
+    /// Turns `start`, `start + interval`, ...
+    EveryTurns {
+        interval: u64,
+        #[serde(default)]
+        start: u64,
+    },
+    /// A faction ends upkeep in deficit; any faction if `faction` is None.
+    FactionInsolvent {
+        #[serde(default)]
+        faction: Option<FactionId>,
+    },
+    /// An invasion takes a planet; `system` and `by` (the attacker)
+    /// narrow which.
+    SystemCaptured {
+        #[serde(default)]
+        system: Option<SystemId>,
+        #[serde(default)]
+        by: Option<FactionId>,
+    },
+}
+
+/// What a rule does when it fires. Externally tagged, like `Trigger`;
+/// resource amounts are `i128`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Effect {
+    /// Adds `fleet`; fails if a fleet with its id exists.
+    SpawnFleet { fleet: Fleet },
+    /// Adds `resources` to a treasury: `faction`'s, or if None the faction
+    /// that triggered the rule.
+    GrantResources {
+        #[serde(default)]
+        faction: Option<FactionId>,
+        resources: ResourceState,
+    },
+    /// Sets the cost of the `from` -> `to` route.
+    SetEdgeWeight { from: SystemId, to: SystemId, weight: f32 },
+    /// Writes `text` to the event log.
+    Message { text: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rule {
+    pub id: String,
+    pub trigger: Trigger,
+    pub effects: Vec<Effect>,
+    /// Fire every time the trigger matches, instead of only the first.
+    #[serde(default)]
+    pub repeat: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Scenario {
+    #[serde(default)]
+    pub name: String,
+    pub rules: Vec<Rule>,
+}
+
+/// A rule firing, with the faction and system that set it off.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Firing {
+    pub rule: String,
+    pub turn: u64,
+    pub faction: Option<FactionId>,
+    pub system: Option<SystemId>,
+}
+
+/// An effect that couldn't be applied; the rule's other effects still are.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScriptError {
+    pub rule: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScenarioReport {
+    pub turn: u64,
+    pub fired: Vec<Firing>,
+    pub errors: Vec<ScriptError>,
+}
+
+/// Serializable copy of a `ScenarioEngine`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScenarioSnapshot {
+    pub scenario: Scenario,
+    /// Ids of one-shot rules that have fired.
+    #[serde(default)]
+    pub fired: BTreeSet<String>,
+}
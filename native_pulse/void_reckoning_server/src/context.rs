@@ -0,0 +1,66 @@
+//! Correlation context propagation from request headers.
+//!
+//! A W3C `traceparent` header wins; otherwise `x-trace-id` and
+//! `x-parent-span-id` are used. Each request gets a fresh span id, and a
+//! fresh trace when the caller sent none.
+
+use crate::http::Request;
+use void_reckoning_shared::CorrelationContext;
+
+pub fn from_request(request: &Request) -> CorrelationContext {
+    let mut context = CorrelationContext::new();
+    if let Some((trace_id, parent_id)) = request.header("traceparent").and_then(parse_traceparent) {
+        context.trace_id = trace_id;
+        context.parent_id = Some(parent_id);
+    } else if let Some(trace_id) = request.header("x-trace-id").filter(|id| !id.is_empty()) {
+        context.trace_id = trace_id.to_string();
+        context.parent_id = request
+            .header("x-parent-span-id")
+            .filter(|id| !id.is_empty())
+            .map(str::to_string);
+    }
+    context
+}
+
+/// `version-traceid-parentid-flags`, e.g.
+/// `00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01`.
+fn parse_traceparent(value: &str) -> Option<(String, String)> {
+    let mut fields = value.trim().split('-');
+    let (_version, trace_id, parent_id, _flags) = (fields.next()?, fields.next()?, fields.next()?, fields.next()?);
+    let is_hex = |s: &str, len: usize| s.len() == len && s.bytes().all(|b| b.is_ascii_hexdigit());
+    if !is_hex(trace_id, 32) || !is_hex(parent_id, 16) {
+        return None;
+    }
+    Some((trace_id.to_string(), parent_id.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(headers: &[(&str, &str)]) -> Request {
+        Request {
+            method: "POST".to_string(),
+            path: "/".to_string(),
+            headers: headers.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            body: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_traceparent_takes_precedence() {
+        let context = from_request(&request(&[
+            ("x-trace-id", "ignored"),
+            ("traceparent", "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"),
+        ]));
+        assert_eq!(context.trace_id, "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(context.parent_id.as_deref(), Some("00f067aa0ba902b7"));
+
+        let context = from_request(&request(&[("x-trace-id", "campaign-7"), ("x-parent-span-id", "turn-3")]));
+        assert_eq!(context.trace_id, "campaign-7");
+        assert_eq!(context.parent_id.as_deref(), Some("turn-3"));
+
+        let context = from_request(&request(&[("traceparent", "garbage")]));
+        assert!(context.parent_id.is_none());
+    }
+}
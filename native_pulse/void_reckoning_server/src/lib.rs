@@ -0,0 +1,70 @@
+//! JSON-over-HTTP service mode for the engines, for deployments that call
+//! them remotely instead of embedding the Python extension.
+//!
+//! Endpoints (all JSON):
+//! - `GET  /health`
+//! - `POST /v1/pathfinding/find_path` — `{topology, start, end, profile?}`
+//! - `POST /v1/combat/resolve` — `{setups, seed?, workers?}`
+//! - `POST /v1/economy/process` — `{nodes, rules?, turn?}`
+//! - `POST /v1/validation/batch` — `{entities, registries?, universe_id?, turn?}`
+//!
+//! Correlation context comes from the `traceparent` or `x-trace-id` /
+//! `x-parent-span-id` headers and is echoed back as `X-Trace-Id` and
+//! `X-Span-Id`.
+
+pub mod context;
+pub mod http;
+pub mod routes;
+
+use http::Request;
+use routes::Service;
+use std::io::{self, BufReader};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::Arc;
+
+pub struct Server {
+    listener: TcpListener,
+    service: Arc<Service>,
+}
+
+impl Server {
+    pub fn bind(addr: impl ToSocketAddrs, service: Service) -> io::Result<Self> {
+        Ok(Self { listener: TcpListener::bind(addr)?, service: Arc::new(service) })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Accepts connections until the listener fails, one thread per
+    /// connection.
+    pub fn serve(&self) -> io::Result<()> {
+        for stream in self.listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err),
+            };
+            let service = Arc::clone(&self.service);
+            std::thread::Builder::new()
+                .name("vr-request".to_string())
+                .spawn(move || {
+                    if let Err(err) = handle_connection(&service, stream) {
+                        eprintln!("void-reckoning-server: connection error: {}", err);
+                    }
+                })?;
+        }
+        Ok(())
+    }
+}
+
+fn handle_connection(service: &Service, stream: TcpStream) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let response = match Request::read_from(&mut reader) {
+        Ok(Some(request)) => service.handle(&request),
+        Ok(None) => return Ok(()),
+        Err(response) => response,
+    };
+    let mut stream = stream;
+    response.write_to(&mut stream)
+}
@@ -0,0 +1,35 @@
+use void_reckoning_server::routes::Service;
+use void_reckoning_server::Server;
+
+const DEFAULT_BIND: &str = "127.0.0.1:8787";
+
+fn main() {
+    let mut bind = DEFAULT_BIND.to_string();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--bind" => match args.next() {
+                Some(addr) => bind = addr,
+                None => exit("--bind needs an address"),
+            },
+            "-h" | "--help" => {
+                println!("usage: void-reckoning-server [--bind ADDR]  (default {})", DEFAULT_BIND);
+                return;
+            }
+            other => exit(&format!("unknown argument '{}'", other)),
+        }
+    }
+
+    let server = Server::bind(&bind, Service::new()).unwrap_or_else(|e| exit(&format!("cannot bind {}: {}", bind, e)));
+    if let Ok(addr) = server.local_addr() {
+        eprintln!("void-reckoning-server listening on http://{}", addr);
+    }
+    if let Err(err) = server.serve() {
+        exit(&format!("server stopped: {}", err));
+    }
+}
+
+fn exit(message: &str) -> ! {
+    eprintln!("void-reckoning-server: {}", message);
+    std::process::exit(2);
+}
@@ -0,0 +1,227 @@
+//! Request handlers. Every endpoint takes and returns JSON in the same
+//! shapes as the Python bridge and the C API.
+
+use crate::context;
+use crate::http::{Request, Response};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use void_reckoning_auditor::engine::ValidationEngine;
+use void_reckoning_auditor::registry::{Registries, RegistryKind};
+use void_reckoning_auditor::types::EntityType;
+use void_reckoning_combat::resolve::{resolve_battles_with, BattleSetup};
+use void_reckoning_economy::engine::IncomeEngine;
+use void_reckoning_economy::types::{EconomicNode, GlobalEconomicRules};
+use void_reckoning_pathfinder::{GraphTopology, TopologySnapshot};
+use void_reckoning_shared::errors::EngineError;
+use void_reckoning_shared::{CorrelationContext, EventLog};
+
+/// Routes requests to the engines. Stateless apart from the optional event
+/// log that engine events are written to.
+#[derive(Clone, Default)]
+pub struct Service {
+    event_log: Option<EventLog>,
+}
+
+impl Service {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_event_log(log: EventLog) -> Self {
+        Self { event_log: Some(log) }
+    }
+
+    pub fn handle(&self, request: &Request) -> Response {
+        let context = context::from_request(request);
+        let result = match (request.method.as_str(), request.path.as_str()) {
+            ("GET", "/health") => Ok(Response::json(200, br#"{"status":"ok"}"#.to_vec())),
+            ("POST", "/v1/pathfinding/find_path") => self.find_path(request),
+            ("POST", "/v1/combat/resolve") => self.resolve(request, &context),
+            ("POST", "/v1/economy/process") => self.process_economy(request, &context),
+            ("POST", "/v1/validation/batch") => self.validate(request, &context),
+            (_, "/health" | "/v1/pathfinding/find_path" | "/v1/combat/resolve" | "/v1/economy/process" | "/v1/validation/batch") => {
+                Ok(Response::error(405, format!("{} not allowed on {}", request.method, request.path)))
+            }
+            _ => Ok(Response::error(404, format!("no route for {}", request.path))),
+        };
+        result
+            .unwrap_or_else(|err| Response::error(status_for(&err), err.to_string()))
+            .with_header("X-Trace-Id", context.trace_id.clone())
+            .with_header("X-Span-Id", context.span_id.clone())
+    }
+
+    fn find_path(&self, request: &Request) -> Result<Response, EngineError> {
+        let body: FindPathRequest = parse(request)?;
+        let topology = GraphTopology::from_snapshot(body.topology);
+        let (path, cost) = topology
+            .find_path(&body.start, &body.end, body.profile)
+            .ok_or(EngineError::PathNotFound { start: body.start, end: body.end })?;
+        reply(&Route { path, cost })
+    }
+
+    fn resolve(&self, request: &Request, context: &CorrelationContext) -> Result<Response, EngineError> {
+        let body: ResolveRequest = parse(request)?;
+        // The client asks for threads; it doesn't get more than there are cores.
+        let cores = std::thread::available_parallelism().map_or(1, |n| n.get());
+        let outcomes = resolve_battles_with(&body.setups, body.seed, body.workers.min(cores), |engine| {
+            engine.set_correlation_context(context.child());
+            if let Some(log) = &self.event_log {
+                engine.set_event_log(log.clone());
+            }
+        });
+        reply(&outcomes)
+    }
+
+    fn process_economy(&self, request: &Request, context: &CorrelationContext) -> Result<Response, EngineError> {
+        let body: EconomyRequest = parse(request)?;
+        let mut engine = IncomeEngine::new(body.rules.unwrap_or_default());
+        engine.set_correlation_context(context.clone());
+        engine.set_campaign_turn(body.turn);
+        if let Some(log) = &self.event_log {
+            engine.set_event_log(log.clone());
+        }
+        for node in body.nodes {
+            engine.add_node(node);
+        }
+        reply(&engine.process_all())
+    }
+
+    fn validate(&self, request: &Request, context: &CorrelationContext) -> Result<Response, EngineError> {
+        let body: ValidationRequest = parse(request)?;
+        let mut registries = Registries::new();
+        for (name, data) in body.registries {
+            let kind = RegistryKind::parse(&name)
+                .ok_or_else(|| EngineError::Registry(format!("unknown registry type '{}'", name)))?;
+            registries.replace(kind, data);
+        }
+        let mut entities = Vec::with_capacity(body.entities.len());
+        for entity in body.entities {
+            let kind = EntityType::parse(&entity.entity_type)
+                .ok_or_else(|| EngineError::Registry(format!("unknown entity type '{}'", entity.entity_type)))?;
+            entities.push((entity.id.into(), kind, entity.data));
+        }
+
+        let mut engine = ValidationEngine::new(Arc::new(registries));
+        engine.set_correlation_context(context.clone());
+        if let Some(log) = &self.event_log {
+            engine.set_event_log(log.clone());
+        }
+        reply(&engine.validate_batch(entities, body.universe_id, body.turn))
+    }
+}
+
+/// Bad input is the caller's fault (4xx); anything else is ours.
+fn status_for(err: &EngineError) -> u16 {
+    match err {
+        EngineError::Serialization { .. } => 400,
+        EngineError::Registry(_) => 422,
+        EngineError::PathNotFound { .. } => 404,
+        EngineError::NotInitialized(_) => 500,
+    }
+}
+
+fn parse<T: DeserializeOwned>(request: &Request) -> Result<T, EngineError> {
+    serde_json::from_slice(&request.body).map_err(EngineError::json)
+}
+
+fn reply<T: Serialize>(value: &T) -> Result<Response, EngineError> {
+    Ok(Response::json(200, serde_json::to_vec(value).map_err(EngineError::json)?))
+}
+
+#[derive(Deserialize)]
+struct FindPathRequest {
+    topology: TopologySnapshot,
+    start: String,
+    end: String,
+    #[serde(default)]
+    profile: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Route {
+    path: Vec<String>,
+    cost: f32,
+}
+
+#[derive(Deserialize)]
+struct ResolveRequest {
+    setups: Vec<BattleSetup>,
+    #[serde(default)]
+    seed: u64,
+    /// Worker threads, at most one per core; 0 means one per core.
+    #[serde(default)]
+    workers: usize,
+}
+
+#[derive(Deserialize)]
+struct EconomyRequest {
+    #[serde(default)]
+    rules: Option<GlobalEconomicRules>,
+    nodes: Vec<EconomicNode>,
+    #[serde(default)]
+    turn: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct ValidationRequest {
+    entities: Vec<EntitySpec>,
+    /// Registry name (`"weapons"`, `"factions"`, ...) to its entries.
+    #[serde(default)]
+    registries: HashMap<String, Map<String, Value>>,
+    #[serde(default)]
+    universe_id: String,
+    #[serde(default)]
+    turn: u64,
+}
+
+#[derive(Deserialize)]
+struct EntitySpec {
+    id: String,
+    entity_type: String,
+    data: Value,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn post(path: &str, body: Value) -> Request {
+        Request {
+            method: "POST".to_string(),
+            path: path.to_string(),
+            headers: vec![("x-trace-id".to_string(), "trace-1".to_string())],
+            body: body.to_string().into_bytes(),
+        }
+    }
+
+    #[test]
+    fn test_find_path_round_trip() {
+        let mut topology = GraphTopology::new();
+        for id in ["Sol", "Vega", "Rigel"] {
+            topology.add_node(id.to_string(), None);
+        }
+        topology.add_edge("Sol", "Vega", 2.0);
+        topology.add_edge("Vega", "Rigel", 3.0);
+        let snapshot = serde_json::to_value(topology.snapshot()).unwrap();
+
+        let service = Service::new();
+        let response = service.handle(&post(
+            "/v1/pathfinding/find_path",
+            serde_json::json!({ "topology": snapshot, "start": "Sol", "end": "Rigel" }),
+        ));
+        assert_eq!(response.status, 200);
+        assert!(response.headers.contains(&("X-Trace-Id".to_string(), "trace-1".to_string())));
+        let route: Value = serde_json::from_slice(&response.body).unwrap();
+        assert_eq!(route["path"], serde_json::json!(["Sol", "Vega", "Rigel"]));
+
+        let response = service.handle(&post(
+            "/v1/pathfinding/find_path",
+            serde_json::json!({ "topology": snapshot, "start": "Sol", "end": "Nowhere" }),
+        ));
+        assert_eq!(response.status, 404);
+        assert_eq!(service.handle(&post("/v1/combat/resolve", serde_json::json!({}))).status, 400);
+    }
+}
@@ -0,0 +1,222 @@
+use crate::callback::{self, CallbackSubscription};
+use crate::escalation::{EscalationRule, Escalator};
+use crate::sink::EventSink;
+use crate::{Event, EventLog, EventSeverity};
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+
+/// Selects events by minimum severity, category and/or trace.
+/// Unset criteria match everything.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[pyclass]
+pub struct EventFilter {
+    #[pyo3(get, set)]
+    pub min_severity: Option<EventSeverity>,
+    #[pyo3(get, set)]
+    pub categories: Option<Vec<String>>,
+    #[pyo3(get, set)]
+    pub trace_id: Option<String>,
+}
+
+#[pymethods]
+impl EventFilter {
+    #[new]
+    #[pyo3(signature = (min_severity=None, categories=None, trace_id=None))]
+    pub fn new(min_severity: Option<EventSeverity>, categories: Option<Vec<String>>, trace_id: Option<String>) -> Self {
+        Self { min_severity, categories, trace_id }
+    }
+
+    pub fn matches(&self, event: &Event) -> bool {
+        self.min_severity.is_none_or(|min| event.severity as u8 >= min as u8)
+            && self.categories.as_ref().is_none_or(|c| c.contains(&event.category))
+            && self.trace_id.as_ref().is_none_or(|t| *t == event.context.trace_id)
+    }
+}
+
+struct Subscriber {
+    id: u64,
+    filter: EventFilter,
+    target: Arc<dyn EventSink>,
+}
+
+#[derive(Default)]
+struct BusInner {
+    subscribers: RwLock<Vec<Subscriber>>,
+    next_id: AtomicU64,
+    escalator: Mutex<Escalator>,
+}
+
+/// Fan-in point for events from every engine. Attach the bus to each engine's
+/// `EventLog`, then subscribe one `EventLog` per consumer with its own filter.
+///
+/// Don't attach the bus to a log that is also subscribed to it: events would
+/// be routed back into it forever.
+#[pyclass]
+#[derive(Clone, Default)]
+pub struct EventBus {
+    inner: Arc<BusInner>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Delivers matching events into `target`. Returns a subscription id.
+    pub fn subscribe_sink(&self, target: Arc<dyn EventSink>, filter: Option<EventFilter>) -> u64 {
+        let id = self.inner.next_id.fetch_add(1, Ordering::Relaxed);
+        if let Ok(mut subscribers) = self.inner.subscribers.write() {
+            subscribers.push(Subscriber {
+                id,
+                filter: filter.unwrap_or_default(),
+                target,
+            });
+        }
+        id
+    }
+
+    /// Routes `event` to every subscriber whose filter accepts it, followed
+    /// by any escalation events it triggers.
+    pub fn publish(&self, event: &Event) {
+        self.route(event);
+        let escalations = match self.inner.escalator.lock() {
+            Ok(mut escalator) if !escalator.is_empty() => escalator.observe(event),
+            _ => Vec::new(),
+        };
+        for escalation in &escalations {
+            self.route(escalation);
+        }
+    }
+
+    fn route(&self, event: &Event) {
+        if let Ok(subscribers) = self.inner.subscribers.read() {
+            for subscriber in subscribers.iter().filter(|s| s.filter.matches(event)) {
+                subscriber.target.write(event);
+            }
+        }
+    }
+}
+
+impl EventSink for EventBus {
+    fn write(&self, event: &Event) {
+        self.publish(event);
+    }
+
+    fn flush(&self) {
+        if let Ok(subscribers) = self.inner.subscribers.read() {
+            for subscriber in subscribers.iter() {
+                subscriber.target.flush();
+            }
+        }
+    }
+}
+
+#[pymethods]
+impl EventBus {
+    #[new]
+    fn py_new() -> Self {
+        Self::new()
+    }
+
+    /// Forwards everything added to `log` (e.g. an engine's log) onto the
+    /// bus. Events are routed as they are added, so subscribers have them
+    /// by the time `add` returns.
+    pub fn attach(&self, log: &EventLog) {
+        log.attach_relay(Arc::new(self.clone()));
+    }
+
+    /// Delivers matching events into `target`. Returns a subscription id.
+    #[pyo3(signature = (target, filter=None))]
+    pub fn subscribe(&self, target: EventLog, filter: Option<EventFilter>) -> u64 {
+        self.subscribe_sink(Arc::new(target), filter)
+    }
+
+    /// Calls `callback(events)` with batches of matching events from a
+    /// background thread. Use the returned handle to flush or cancel.
+    #[pyo3(signature = (callback, filter=None, batch_size=64, max_latency_ms=100))]
+    pub fn subscribe_callback(
+        &self,
+        callback: PyObject,
+        filter: Option<EventFilter>,
+        batch_size: usize,
+        max_latency_ms: u64,
+    ) -> PyResult<CallbackSubscription> {
+        // The bus filter does the selecting; the subscription itself accepts everything
+        let subscription = callback::create_subscription(callback, None, batch_size, max_latency_ms)?;
+        self.subscribe_sink(subscription.inner.clone(), filter);
+        Ok(subscription)
+    }
+
+    pub fn unsubscribe(&self, subscription_id: u64) -> bool {
+        self.inner
+            .subscribers
+            .write()
+            .map(|mut subscribers| {
+                let before = subscribers.len();
+                subscribers.retain(|s| s.id != subscription_id);
+                subscribers.len() != before
+            })
+            .unwrap_or(false)
+    }
+
+    #[pyo3(name = "publish")]
+    fn py_publish(&self, event: Event) {
+        self.publish(&event);
+    }
+
+    /// Evaluates `rule` on everything published from now on.
+    pub fn add_escalation_rule(&self, rule: EscalationRule) {
+        if let Ok(mut escalator) = self.inner.escalator.lock() {
+            escalator.add_rule(rule);
+        }
+    }
+
+    pub fn clear_escalation_rules(&self) {
+        if let Ok(mut escalator) = self.inner.escalator.lock() {
+            escalator.clear();
+        }
+    }
+
+    pub fn subscriber_count(&self) -> usize {
+        self.inner.subscribers.read().map(|s| s.len()).unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CorrelationContext;
+
+    fn event(severity: EventSeverity, category: &str) -> Event {
+        Event::new(severity, category.to_string(), "msg".to_string(), CorrelationContext::new(), None)
+    }
+
+    #[test]
+    fn subscribers_get_their_events_as_soon_as_they_are_added() {
+        for _ in 0..200 {
+            let (bus, engine) = (EventBus::new(), EventLog::new());
+            bus.attach(&engine);
+            let (everything, warnings, combat) = (EventLog::new(), EventLog::new(), EventLog::new());
+            bus.subscribe(everything.clone(), None);
+            bus.subscribe(warnings.clone(), Some(EventFilter::new(Some(EventSeverity::Warning), None, None)));
+            let combat_id = bus.subscribe(combat.clone(), Some(EventFilter::new(None, Some(vec!["Combat".to_string()]), None)));
+
+            for (severity, category) in [
+                (EventSeverity::Info, "Combat"),
+                (EventSeverity::Warning, "Economy"),
+                (EventSeverity::Debug, "Combat"),
+                (EventSeverity::Error, "Combat"),
+                (EventSeverity::Info, "Economy"),
+            ] {
+                engine.add(event(severity, category));
+            }
+            assert_eq!((everything.len(), warnings.len(), combat.len()), (5, 2, 3));
+
+            assert!(bus.unsubscribe(combat_id));
+            engine.add(event(EventSeverity::Critical, "Combat"));
+            assert_eq!((everything.len(), warnings.len(), combat.len()), (6, 3, 3));
+        }
+    }
+}
@@ -0,0 +1,195 @@
+use crate::bus::EventFilter;
+use crate::sink::EventSink;
+use crate::Event;
+use pyo3::prelude::*;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+enum DispatchMessage {
+    Event(Box<Event>),
+    Flush(Sender<()>),
+}
+
+/// Delivers matching events to a Python callable in batches.
+///
+/// `write` (called from an `EventLog` collector) only filters and enqueues.
+/// A dispatcher thread gathers up to `batch_size` events, or whatever arrived
+/// within `max_latency`, and takes the GIL once per batch to invoke
+/// `callback(list_of_events)`. Exceptions raised by the callback are printed
+/// and do not stop delivery.
+pub struct CallbackSink {
+    filter: EventFilter,
+    sender: Mutex<Option<Sender<DispatchMessage>>>,
+    worker: Mutex<Option<JoinHandle<()>>>,
+    active: Arc<AtomicBool>,
+    delivered: Arc<AtomicU64>,
+}
+
+impl CallbackSink {
+    pub fn new(callback: PyObject, filter: EventFilter, batch_size: usize, max_latency: Duration) -> std::io::Result<Self> {
+        let (sender, receiver) = mpsc::channel::<DispatchMessage>();
+        let active = Arc::new(AtomicBool::new(true));
+        let delivered = Arc::new(AtomicU64::new(0));
+        let batch_size = batch_size.max(1);
+
+        let worker_delivered = Arc::clone(&delivered);
+        let worker = std::thread::Builder::new()
+            .name("event-callback-dispatch".to_string())
+            .spawn(move || {
+                let mut batch: Vec<Event> = Vec::with_capacity(batch_size);
+                let mut deadline: Option<Instant> = None;
+
+                let deliver = |batch: &mut Vec<Event>| {
+                    if batch.is_empty() {
+                        return;
+                    }
+                    let events = std::mem::take(batch);
+                    let count = events.len() as u64;
+                    Python::with_gil(|py| {
+                        if let Err(err) = callback.call1(py, (events,)) {
+                            err.print(py);
+                        }
+                    });
+                    worker_delivered.fetch_add(count, Ordering::Relaxed);
+                };
+
+                loop {
+                    let timeout = deadline
+                        .map(|d| d.saturating_duration_since(Instant::now()))
+                        .unwrap_or(Duration::from_secs(3600));
+                    match receiver.recv_timeout(timeout) {
+                        Ok(DispatchMessage::Event(event)) => {
+                            deadline.get_or_insert_with(|| Instant::now() + max_latency);
+                            batch.push(*event);
+                            if batch.len() >= batch_size {
+                                deliver(&mut batch);
+                                deadline = None;
+                            }
+                        }
+                        Ok(DispatchMessage::Flush(ack)) => {
+                            deliver(&mut batch);
+                            deadline = None;
+                            let _ = ack.send(());
+                        }
+                        Err(RecvTimeoutError::Timeout) => {
+                            deliver(&mut batch);
+                            deadline = None;
+                        }
+                        Err(RecvTimeoutError::Disconnected) => {
+                            deliver(&mut batch);
+                            break;
+                        }
+                    }
+                }
+            })?;
+
+        Ok(Self {
+            filter,
+            sender: Mutex::new(Some(sender)),
+            worker: Mutex::new(Some(worker)),
+            active,
+            delivered,
+        })
+    }
+
+    /// Stops delivery after handing over anything already queued.
+    /// Must not be called while holding the GIL (the dispatcher may need it).
+    pub fn cancel(&self) {
+        self.active.store(false, Ordering::Release);
+        // Dropping the sender lets the dispatcher deliver its last batch and exit
+        if let Ok(mut sender) = self.sender.lock() {
+            sender.take();
+        }
+        if let Some(worker) = self.worker.lock().ok().and_then(|mut w| w.take()) {
+            let _ = worker.join();
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active.load(Ordering::Acquire)
+    }
+
+    pub fn delivered_count(&self) -> u64 {
+        self.delivered.load(Ordering::Relaxed)
+    }
+}
+
+impl EventSink for CallbackSink {
+    fn write(&self, event: &Event) {
+        if !self.is_active() || !self.filter.matches(event) {
+            return;
+        }
+        if let Ok(sender) = self.sender.lock()
+            && let Some(sender) = sender.as_ref()
+        {
+            let _ = sender.send(DispatchMessage::Event(Box::new(event.clone())));
+        }
+    }
+
+    /// Blocks until every queued event has been passed to the callback.
+    /// Must not be called while holding the GIL.
+    fn flush(&self) {
+        let (ack, done) = mpsc::channel();
+        let sent = self
+            .sender
+            .lock()
+            .ok()
+            .and_then(|s| s.as_ref().map(|s| s.send(DispatchMessage::Flush(ack)).is_ok()))
+            .unwrap_or(false);
+        if sent {
+            let _ = done.recv();
+        }
+    }
+}
+
+impl Drop for CallbackSink {
+    fn drop(&mut self) {
+        // Don't join here: the last handle may be dropped on a thread holding the GIL
+        self.active.store(false, Ordering::Release);
+    }
+}
+
+/// Handle returned by `subscribe_callback`; keep it to flush or cancel.
+#[pyclass]
+#[derive(Clone)]
+pub struct CallbackSubscription {
+    pub inner: Arc<CallbackSink>,
+}
+
+#[pymethods]
+impl CallbackSubscription {
+    /// Waits until all queued events have been delivered to the callback.
+    pub fn flush(&self, py: Python<'_>) {
+        let inner = Arc::clone(&self.inner);
+        py.allow_threads(move || inner.flush());
+    }
+
+    /// Delivers anything still queued, then stops the subscription.
+    pub fn cancel(&self, py: Python<'_>) {
+        let inner = Arc::clone(&self.inner);
+        py.allow_threads(move || inner.cancel());
+    }
+
+    #[getter]
+    pub fn active(&self) -> bool {
+        self.inner.is_active()
+    }
+
+    pub fn delivered_count(&self) -> u64 {
+        self.inner.delivered_count()
+    }
+}
+
+pub(crate) fn create_subscription(
+    callback: PyObject,
+    filter: Option<EventFilter>,
+    batch_size: usize,
+    max_latency_ms: u64,
+) -> PyResult<CallbackSubscription> {
+    let sink = CallbackSink::new(callback, filter.unwrap_or_default(), batch_size, Duration::from_millis(max_latency_ms))
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Callback error: {}", e)))?;
+    Ok(CallbackSubscription { inner: Arc::new(sink) })
+}
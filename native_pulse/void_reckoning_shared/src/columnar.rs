@@ -0,0 +1,48 @@
+//! Column-oriented views for dataframe libraries. Columns are Python lists
+//! keyed by name, which `polars.DataFrame` and `pandas.DataFrame` accept as
+//! is; `arrow_table` wraps them in a `pyarrow.Table` without a JSON pass.
+
+use crate::Event;
+use pyo3::exceptions::PyImportError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+
+/// Adds `values` to `columns` as a list under `name`.
+pub fn set_column<'py, T: IntoPyObject<'py>>(columns: &Bound<'py, PyDict>, name: &str, values: Vec<T>) -> PyResult<()> {
+    columns.set_item(name, PyList::new(columns.py(), values)?)
+}
+
+/// Wraps a column dict in a `pyarrow.Table`; `None` entries become nulls.
+pub fn arrow_table<'py>(columns: &Bound<'py, PyDict>) -> PyResult<Bound<'py, PyAny>> {
+    let pyarrow = columns
+        .py()
+        .import("pyarrow")
+        .map_err(|e| PyImportError::new_err(format!("Arrow export requires pyarrow: {}", e)))?;
+    pyarrow.call_method1("table", (columns,))
+}
+
+/// One row per event. `fields` holds the structured fields as JSON text
+/// (None when empty) since their keys vary from event to event.
+pub fn event_columns<'py>(py: Python<'py>, events: &[Event]) -> PyResult<Bound<'py, PyDict>> {
+    let columns = PyDict::new(py);
+    set_column(&columns, "timestamp", events.iter().map(|e| e.timestamp).collect())?;
+    set_column(&columns, "severity", events.iter().map(|e| format!("{:?}", e.severity)).collect())?;
+    set_column(&columns, "category", events.iter().map(|e| e.category.as_str()).collect())?;
+    set_column(&columns, "message", events.iter().map(|e| e.message.as_str()).collect())?;
+    set_column(&columns, "trace_id", events.iter().map(|e| e.context.trace_id.as_str()).collect())?;
+    set_column(&columns, "span_id", events.iter().map(|e| e.context.span_id.as_str()).collect())?;
+    set_column(&columns, "parent_id", events.iter().map(|e| e.context.parent_id.as_deref()).collect())?;
+    set_column(&columns, "turn", events.iter().map(|e| e.sim_time.turn).collect())?;
+    set_column(&columns, "tick", events.iter().map(|e| e.sim_time.tick).collect())?;
+    set_column(&columns, "engine_time", events.iter().map(|e| e.sim_time.engine_time).collect())?;
+    set_column(&columns, "data", events.iter().map(|e| e.data.as_deref()).collect())?;
+    set_column(
+        &columns,
+        "fields",
+        events
+            .iter()
+            .map(|e| (!e.fields.is_empty()).then(|| serde_json::to_string(&e.fields).unwrap_or_default()))
+            .collect(),
+    )?;
+    Ok(columns)
+}
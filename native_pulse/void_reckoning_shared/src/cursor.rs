@@ -0,0 +1,112 @@
+use crate::event_log::EventLog;
+use crate::Event;
+use pyo3::prelude::*;
+use std::collections::VecDeque;
+
+pub const DEFAULT_CURSOR_BATCH: usize = 256;
+
+/// Read position in an `EventLog`. Events are fetched `batch_size` at a
+/// time, so iterating a large log never copies it whole. The cursor follows
+/// the log as it grows; events evicted or drained before the cursor reached
+/// them are skipped and counted in `missed_count`.
+#[pyclass]
+pub struct EventCursor {
+    log: EventLog,
+    /// Sequence number of the next event to fetch from the log.
+    next_seq: u64,
+    pending: VecDeque<Event>,
+    batch_size: usize,
+    missed: u64,
+}
+
+impl EventCursor {
+    pub fn new(log: EventLog, next_seq: u64, batch_size: usize) -> Self {
+        Self {
+            log,
+            next_seq,
+            pending: VecDeque::new(),
+            batch_size: batch_size.max(1),
+            missed: 0,
+        }
+    }
+
+    fn fetch(&mut self, max: usize) -> Vec<Event> {
+        let (events, start) = self.log.read_from(self.next_seq, max);
+        self.missed += start - self.next_seq;
+        self.next_seq = start + events.len() as u64;
+        events
+    }
+}
+
+#[pymethods]
+impl EventCursor {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self) -> Option<Event> {
+        if self.pending.is_empty() {
+            let batch = self.fetch(self.batch_size);
+            self.pending.extend(batch);
+        }
+        self.pending.pop_front()
+    }
+
+    /// Up to `n` events not yet seen (empty once caught up). Calling again
+    /// later picks up anything added since.
+    #[pyo3(signature = (n=None))]
+    pub fn next_batch(&mut self, n: Option<usize>) -> Vec<Event> {
+        let n = n.unwrap_or(self.batch_size);
+        let mut batch: Vec<Event> = self.pending.drain(..n.min(self.pending.len())).collect();
+        if batch.len() < n {
+            batch.extend(self.fetch(n - batch.len()));
+        }
+        batch
+    }
+
+    /// Events skipped because the log dropped them before they were read.
+    #[getter]
+    pub fn missed_count(&self) -> u64 {
+        self.missed
+    }
+
+    /// Sequence number of the next event this cursor will return.
+    #[getter]
+    pub fn position(&self) -> u64 {
+        self.next_seq - self.pending.len() as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CorrelationContext, EventSeverity};
+
+    fn event(message: &str) -> Event {
+        Event::new(EventSeverity::Info, "Test".to_string(), message.to_string(), CorrelationContext::new(), None)
+    }
+
+    #[test]
+    fn test_cursor_follows_log_and_counts_evictions() {
+        let log = EventLog::with_capacity(Some(3));
+        for msg in ["a", "b"] {
+            log.add(event(msg));
+        }
+        let mut cursor = log.cursor(true, 1);
+        assert_eq!(cursor.__next__().map(|e| e.message).as_deref(), Some("a"));
+
+        // "b" and "c" are evicted before the cursor gets to them
+        for msg in ["c", "d", "e", "f"] {
+            log.add(event(msg));
+        }
+        let messages: Vec<String> = cursor.next_batch(Some(10)).into_iter().map(|e| e.message).collect();
+        assert_eq!(messages, vec!["d", "e", "f"]);
+        assert_eq!(cursor.missed_count(), 2);
+        assert!(cursor.next_batch(None).is_empty());
+
+        assert_eq!(log.drain(Some(2)).len(), 2);
+        log.add(event("g"));
+        assert_eq!(cursor.__next__().map(|e| e.message).as_deref(), Some("g"));
+        assert_eq!(cursor.missed_count(), 2);
+    }
+}
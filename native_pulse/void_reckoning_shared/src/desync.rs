@@ -0,0 +1,191 @@
+//! Desync detection for lockstep play. Each client digests its engines
+//! (a state hash per subsystem, plus hashes of the fields of that
+//! subsystem's JSON state) and clients swap digests every turn. Comparing
+//! two digests names the subsystems that diverged and the fields that
+//! differ without either side sending its state.
+
+use crate::simtime::SimTime;
+use crate::{CorrelationContext, Event, EventSeverity};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Field paths go this many levels into a subsystem's state
+/// (`nodes`, then `nodes.3`).
+const FIELD_DEPTH: usize = 2;
+
+pub use void_reckoning_hash::{extend_hash, hash_bytes, hash_json};
+
+/// One subsystem's share of a digest.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubsystemDigest {
+    pub hash: u64,
+    /// Hash of each field of the state, by dotted path; empty when only
+    /// the overall hash is known.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields: BTreeMap<String, u64>,
+}
+
+impl SubsystemDigest {
+    pub fn new(hash: u64) -> Self {
+        Self { hash, fields: BTreeMap::new() }
+    }
+
+    /// `hash` plus a hash per field of `state`: object keys and array
+    /// indices, two levels deep.
+    pub fn with_fields(hash: u64, state: &Value) -> Self {
+        let mut fields = BTreeMap::new();
+        collect_fields(state, "", FIELD_DEPTH, &mut fields);
+        Self { hash, fields }
+    }
+}
+
+fn collect_fields(value: &Value, prefix: &str, depth: usize, out: &mut BTreeMap<String, u64>) {
+    if depth == 0 {
+        return;
+    }
+    let path = |key: &str| if prefix.is_empty() { key.to_string() } else { format!("{}.{}", prefix, key) };
+    let children: Vec<(String, &Value)> = match value {
+        Value::Object(map) => map.iter().map(|(key, child)| (path(key), child)).collect(),
+        Value::Array(items) => items.iter().enumerate().map(|(i, child)| (path(&i.to_string()), child)).collect(),
+        _ => return,
+    };
+    for (path, child) in children {
+        out.insert(path.clone(), hash_json(child));
+        collect_fields(child, &path, depth - 1, out);
+    }
+}
+
+/// Every subsystem's digest at one point in the campaign.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateDigest {
+    #[serde(default)]
+    pub turn: Option<u64>,
+    pub subsystems: BTreeMap<String, SubsystemDigest>,
+}
+
+impl StateDigest {
+    pub fn new(turn: Option<u64>) -> Self {
+        Self { turn, subsystems: BTreeMap::new() }
+    }
+
+    pub fn insert(&mut self, subsystem: impl Into<String>, digest: SubsystemDigest) {
+        self.subsystems.insert(subsystem.into(), digest);
+    }
+
+    /// The turn and every subsystem hash folded into one number.
+    pub fn combined_hash(&self) -> u64 {
+        let hashes = self.subsystems.iter().map(|(name, digest)| (name.as_str(), digest.hash)).collect::<Vec<_>>();
+        hash_json(&(self.turn, hashes))
+    }
+}
+
+/// A subsystem whose hash differs between two digests, or that only one
+/// of them has.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Divergence {
+    pub subsystem: String,
+    pub local: Option<u64>,
+    pub remote: Option<u64>,
+    /// The most specific field paths whose hashes differ or that only one
+    /// side has. Empty when either side sent no field hashes.
+    pub fields: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DesyncReport {
+    pub local_turn: Option<u64>,
+    pub remote_turn: Option<u64>,
+    pub divergences: Vec<Divergence>,
+}
+
+impl DesyncReport {
+    pub fn compare(local: &StateDigest, remote: &StateDigest) -> Self {
+        let names: BTreeSet<&String> = local.subsystems.keys().chain(remote.subsystems.keys()).collect();
+        let divergences = names
+            .into_iter()
+            .filter_map(|name| {
+                let (ours, theirs) = (local.subsystems.get(name), remote.subsystems.get(name));
+                if ours.map(|d| d.hash) == theirs.map(|d| d.hash) {
+                    return None;
+                }
+                let fields = match (ours, theirs) {
+                    (Some(ours), Some(theirs)) => differing_fields(&ours.fields, &theirs.fields),
+                    _ => Vec::new(),
+                };
+                Some(Divergence { subsystem: name.clone(), local: ours.map(|d| d.hash), remote: theirs.map(|d| d.hash), fields })
+            })
+            .collect();
+        Self { local_turn: local.turn, remote_turn: remote.turn, divergences }
+    }
+
+    /// True when the digests agree (turns included).
+    pub fn is_empty(&self) -> bool {
+        self.divergences.is_empty() && self.local_turn == self.remote_turn
+    }
+
+    pub fn subsystems(&self) -> Vec<&str> {
+        self.divergences.iter().map(|d| d.subsystem.as_str()).collect()
+    }
+
+    /// A Critical `Desync` event naming the diverged subsystems, with the
+    /// differing fields of each under `fields`.
+    pub fn to_event(&self, context: CorrelationContext) -> Event {
+        let subsystems = self.subsystems();
+        let message = match (subsystems.is_empty(), self.local_turn == self.remote_turn) {
+            (true, _) => format!("Desync: turn {:?} against {:?}", self.local_turn, self.remote_turn),
+            (false, true) => format!("Desync in {}", subsystems.join(", ")),
+            (false, false) => format!("Desync in {} (turn {:?} against {:?})", subsystems.join(", "), self.local_turn, self.remote_turn),
+        };
+        let fields: serde_json::Map<String, Value> =
+            self.divergences.iter().map(|d| (d.subsystem.clone(), json!(d.fields))).collect();
+        Event::new(EventSeverity::Critical, "Desync".to_string(), message, context, None)
+            .with_field("subsystems", json!(subsystems))
+            .with_field("fields", Value::Object(fields))
+            .with_field("remote_turn", json!(self.remote_turn))
+            .at(SimTime::at_turn(self.local_turn))
+    }
+}
+
+fn differing_fields(ours: &BTreeMap<String, u64>, theirs: &BTreeMap<String, u64>) -> Vec<String> {
+    let paths: BTreeSet<&String> = ours.keys().chain(theirs.keys()).collect();
+    let differing: Vec<&String> = paths.into_iter().filter(|path| ours.get(*path) != theirs.get(*path)).collect();
+    // A parent differs whenever a child does; report only the child.
+    differing
+        .iter()
+        .filter(|path| {
+            let prefix = format!("{}.", path);
+            !differing.iter().any(|other| other.starts_with(&prefix))
+        })
+        .map(|path| path.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digest(turn: u64, economy: Value) -> StateDigest {
+        let mut digest = StateDigest::new(Some(turn));
+        digest.insert("economy", SubsystemDigest::with_fields(hash_json(&economy), &economy));
+        digest.insert("pathfinder", SubsystemDigest::new(7));
+        digest
+    }
+
+    #[test]
+    fn compare_names_the_subsystem_and_field() {
+        let ours = digest(3, json!({"rules": {"tax": 1}, "nodes": [{"id": "Cadia", "credits": 10}, {"id": "Armageddon"}]}));
+        let theirs = digest(3, json!({"rules": {"tax": 1}, "nodes": [{"id": "Cadia", "credits": 11}, {"id": "Armageddon"}]}));
+        assert!(DesyncReport::compare(&ours, &ours.clone()).is_empty());
+        assert_ne!(ours.combined_hash(), theirs.combined_hash());
+
+        let report = DesyncReport::compare(&ours, &theirs);
+        assert_eq!(report.subsystems(), vec!["economy"]);
+        assert_eq!(report.divergences[0].fields, vec!["nodes.0".to_string()]);
+
+        let event = report.to_event(CorrelationContext::new());
+        assert_eq!(event.severity, EventSeverity::Critical);
+        assert_eq!(event.field("fields"), Some(&json!({"economy": ["nodes.0"]})));
+        assert_eq!(event.sim_time, SimTime::at_turn(Some(3)));
+    }
+}
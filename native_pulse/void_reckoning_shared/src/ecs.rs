@@ -0,0 +1,251 @@
+//! A small entity-component store. An `Entity` is a generational index;
+//! components are plain Rust values kept in one column per type, so any
+//! mix can be attached to any entity and layers that know nothing of each
+//! other (combat, economy) can read and write the same entities.
+//!
+//! Columns are ordered by entity index, so iteration is deterministic.
+
+use serde::{Deserialize, Serialize};
+use std::any::{Any, TypeId};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+/// Anything that can be stored on an entity.
+pub trait Component: Send + Sync + 'static {}
+
+impl<T: Send + Sync + 'static> Component for T {}
+
+/// A handle to an entity. The generation changes when a slot is reused,
+/// so a handle to a despawned entity never reaches its replacement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Entity {
+    index: u32,
+    generation: u32,
+}
+
+impl Entity {
+    pub fn index(self) -> u32 {
+        self.index
+    }
+
+    pub fn generation(self) -> u32 {
+        self.generation
+    }
+
+    /// Packs the handle into one integer (generation in the high half),
+    /// for callers that can only hold a number.
+    pub fn to_bits(self) -> u64 {
+        (u64::from(self.generation) << 32) | u64::from(self.index)
+    }
+
+    pub fn from_bits(bits: u64) -> Self {
+        Self { index: bits as u32, generation: (bits >> 32) as u32 }
+    }
+}
+
+impl fmt::Display for Entity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}v{}", self.index, self.generation)
+    }
+}
+
+trait Column: Send + Sync {
+    fn remove_index(&mut self, index: u32);
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: Component> Column for BTreeMap<u32, T> {
+    fn remove_index(&mut self, index: u32) {
+        self.remove(&index);
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[derive(Default)]
+pub struct World {
+    generations: Vec<u32>,
+    alive: Vec<bool>,
+    free: Vec<u32>,
+    columns: HashMap<TypeId, Box<dyn Column>>,
+}
+
+impl World {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn spawn(&mut self) -> Entity {
+        match self.free.pop() {
+            Some(index) => {
+                self.alive[index as usize] = true;
+                Entity { index, generation: self.generations[index as usize] }
+            }
+            None => {
+                let index = self.generations.len() as u32;
+                self.generations.push(0);
+                self.alive.push(true);
+                Entity { index, generation: 0 }
+            }
+        }
+    }
+
+    /// Removes the entity and every component on it; false if it was
+    /// already gone.
+    pub fn despawn(&mut self, entity: Entity) -> bool {
+        if !self.is_alive(entity) {
+            return false;
+        }
+        let index = entity.index as usize;
+        for column in self.columns.values_mut() {
+            column.remove_index(entity.index);
+        }
+        self.alive[index] = false;
+        self.generations[index] = self.generations[index].wrapping_add(1);
+        self.free.push(entity.index);
+        true
+    }
+
+    pub fn is_alive(&self, entity: Entity) -> bool {
+        let index = entity.index as usize;
+        self.alive.get(index).copied().unwrap_or(false) && self.generations[index] == entity.generation
+    }
+
+    pub fn len(&self) -> usize {
+        self.alive.iter().filter(|&&alive| alive).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Living entities in index order.
+    pub fn entities(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.alive
+            .iter()
+            .enumerate()
+            .filter(|(_, alive)| **alive)
+            .map(|(index, _)| Entity { index: index as u32, generation: self.generations[index] })
+    }
+
+    /// Attaches `component`, returning the one of the same type it
+    /// replaces. Does nothing (and hands `component` back) for a dead entity.
+    pub fn insert<T: Component>(&mut self, entity: Entity, component: T) -> Result<Option<T>, T> {
+        if !self.is_alive(entity) {
+            return Err(component);
+        }
+        Ok(self.column_mut::<T>().insert(entity.index, component))
+    }
+
+    pub fn remove<T: Component>(&mut self, entity: Entity) -> Option<T> {
+        if !self.is_alive(entity) {
+            return None;
+        }
+        self.columns
+            .get_mut(&TypeId::of::<T>())?
+            .as_any_mut()
+            .downcast_mut::<BTreeMap<u32, T>>()?
+            .remove(&entity.index)
+    }
+
+    pub fn get<T: Component>(&self, entity: Entity) -> Option<&T> {
+        if !self.is_alive(entity) {
+            return None;
+        }
+        self.column::<T>()?.get(&entity.index)
+    }
+
+    pub fn get_mut<T: Component>(&mut self, entity: Entity) -> Option<&mut T> {
+        if !self.is_alive(entity) {
+            return None;
+        }
+        self.columns
+            .get_mut(&TypeId::of::<T>())?
+            .as_any_mut()
+            .downcast_mut::<BTreeMap<u32, T>>()?
+            .get_mut(&entity.index)
+    }
+
+    pub fn has<T: Component>(&self, entity: Entity) -> bool {
+        self.get::<T>(entity).is_some()
+    }
+
+    /// Every entity with a `T`, in index order.
+    pub fn query<T: Component>(&self) -> impl Iterator<Item = (Entity, &T)> + '_ {
+        self.column::<T>()
+            .into_iter()
+            .flatten()
+            .map(|(&index, component)| (Entity { index, generation: self.generations[index as usize] }, component))
+    }
+
+    pub fn query_mut<T: Component>(&mut self) -> impl Iterator<Item = (Entity, &mut T)> + '_ {
+        let generations = &self.generations;
+        self.columns
+            .get_mut(&TypeId::of::<T>())
+            .and_then(|column| column.as_any_mut().downcast_mut::<BTreeMap<u32, T>>())
+            .into_iter()
+            .flatten()
+            .map(|(&index, component)| (Entity { index, generation: generations[index as usize] }, component))
+    }
+
+    fn column<T: Component>(&self) -> Option<&BTreeMap<u32, T>> {
+        self.columns.get(&TypeId::of::<T>())?.as_any().downcast_ref()
+    }
+
+    fn column_mut<T: Component>(&mut self) -> &mut BTreeMap<u32, T> {
+        self.columns
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(BTreeMap::<u32, T>::new()))
+            .as_any_mut()
+            .downcast_mut()
+            .expect("column stored under its own TypeId")
+    }
+}
+
+impl fmt::Debug for World {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("World").field("entities", &self.len()).field("columns", &self.columns.len()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Hull(f32);
+    #[derive(Debug)]
+    struct Owner(&'static str);
+
+    #[test]
+    fn components_follow_their_entity() {
+        let mut world = World::new();
+        let ship = world.spawn();
+        let planet = world.spawn();
+        world.insert(ship, Hull(10.0)).unwrap();
+        world.insert(ship, Owner("Imperium")).unwrap();
+        world.insert(planet, Owner("Orks")).unwrap();
+
+        let owners: Vec<_> = world.query::<Owner>().map(|(e, o)| (e, o.0)).collect();
+        assert_eq!(owners, vec![(ship, "Imperium"), (planet, "Orks")]);
+        for (_, hull) in world.query_mut::<Hull>() {
+            hull.0 -= 4.0;
+        }
+        assert_eq!(world.get::<Hull>(ship), Some(&Hull(6.0)));
+
+        assert!(world.despawn(ship));
+        let wreck = world.spawn();
+        assert_eq!(wreck.index(), ship.index());
+        assert!(world.get::<Hull>(ship).is_none() && world.get::<Hull>(wreck).is_none());
+        assert!(world.insert(ship, Hull(1.0)).is_err());
+        assert_eq!(Entity::from_bits(wreck.to_bits()), wreck);
+        assert_eq!(world.len(), 2);
+    }
+}
@@ -0,0 +1,83 @@
+//! Python exception hierarchy for engine failures. Rust code returns
+//! `EngineError`; converting it to `PyErr` raises the matching subclass of
+//! `VoidReckoningError`, so callers can catch engine errors as a family.
+
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+use std::fmt;
+
+create_exception!(void_reckoning_bridge, VoidReckoningError, PyException, "Base class for errors raised by the engines.");
+create_exception!(void_reckoning_bridge, RegistryError, VoidReckoningError, "Unknown registry or rejected registry contents.");
+create_exception!(void_reckoning_bridge, PathNotFound, VoidReckoningError, "No route between the requested nodes.");
+create_exception!(void_reckoning_bridge, EngineNotInitialized, VoidReckoningError, "Engine used before it was initialized.");
+create_exception!(void_reckoning_bridge, SerializationError, VoidReckoningError, "Payload could not be encoded or decoded.");
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EngineError {
+    Registry(String),
+    PathNotFound { start: String, end: String },
+    /// Names the engine, e.g. "Auditor".
+    NotInitialized(&'static str),
+    /// `format` names the encoding or container, e.g. "JSON" or "Snapshot".
+    Serialization { format: &'static str, message: String },
+}
+
+impl EngineError {
+    pub fn serialization(format: &'static str, error: impl fmt::Display) -> Self {
+        Self::Serialization { format, message: error.to_string() }
+    }
+
+    pub fn json(error: impl fmt::Display) -> Self {
+        Self::serialization("JSON", error)
+    }
+}
+
+impl fmt::Display for EngineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Registry(message) => write!(f, "Registry error: {}", message),
+            Self::PathNotFound { start, end } => write!(f, "No path from {} to {}", start, end),
+            Self::NotInitialized(engine) => write!(f, "{} not initialized", engine),
+            Self::Serialization { format, message } => write!(f, "{} error: {}", format, message),
+        }
+    }
+}
+
+impl std::error::Error for EngineError {}
+
+impl From<EngineError> for PyErr {
+    fn from(error: EngineError) -> Self {
+        let message = error.to_string();
+        match error {
+            EngineError::Registry(_) => RegistryError::new_err(message),
+            EngineError::PathNotFound { .. } => PathNotFound::new_err(message),
+            EngineError::NotInitialized(_) => EngineNotInitialized::new_err(message),
+            EngineError::Serialization { .. } => SerializationError::new_err(message),
+        }
+    }
+}
+
+/// Adds the exception classes to `module`.
+pub fn register(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = module.py();
+    module.add("VoidReckoningError", py.get_type::<VoidReckoningError>())?;
+    module.add("RegistryError", py.get_type::<RegistryError>())?;
+    module.add("PathNotFound", py.get_type::<PathNotFound>())?;
+    module.add("EngineNotInitialized", py.get_type::<EngineNotInitialized>())?;
+    module.add("SerializationError", py.get_type::<SerializationError>())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_messages_name_the_failure() {
+        assert_eq!(EngineError::NotInitialized("Auditor").to_string(), "Auditor not initialized");
+        assert_eq!(EngineError::json("expected value").to_string(), "JSON error: expected value");
+        let missing = EngineError::PathNotFound { start: "Sol".to_string(), end: "Vega".to_string() };
+        assert_eq!(missing.to_string(), "No path from Sol to Vega");
+    }
+}
@@ -0,0 +1,161 @@
+use crate::{CorrelationContext, Event, EventSeverity};
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use uuid::Uuid;
+
+/// Field set on synthetic escalation events; such events are never escalated again.
+pub const ESCALATION_FIELD: &str = "escalation_rule";
+
+/// "When `threshold` events of `severity` (optionally in `category`) occur in
+/// one trace, emit a single `escalate_to` event linking them."
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[pyclass]
+pub struct EscalationRule {
+    #[pyo3(get, set)]
+    pub name: String,
+    #[pyo3(get, set)]
+    pub threshold: usize,
+    #[pyo3(get, set)]
+    pub severity: EventSeverity,
+    /// Only count events in this category; `None` counts each category separately.
+    #[pyo3(get, set)]
+    pub category: Option<String>,
+    #[pyo3(get, set)]
+    pub escalate_to: EventSeverity,
+    /// Only events this close together (by timestamp) count towards the threshold.
+    #[pyo3(get, set)]
+    pub window_secs: Option<f64>,
+}
+
+#[pymethods]
+impl EscalationRule {
+    #[new]
+    #[pyo3(signature = (name, threshold, severity=EventSeverity::Warning, category=None, escalate_to=EventSeverity::Error, window_secs=None))]
+    pub fn new(
+        name: String,
+        threshold: usize,
+        severity: EventSeverity,
+        category: Option<String>,
+        escalate_to: EventSeverity,
+        window_secs: Option<f64>,
+    ) -> Self {
+        Self { name, threshold: threshold.max(1), severity, category, escalate_to, window_secs }
+    }
+
+    fn applies_to(&self, event: &Event) -> bool {
+        event.severity == self.severity && self.category.as_ref().is_none_or(|c| *c == event.category)
+    }
+}
+
+/// Evaluates escalation rules over a stream of events. Counts are kept per
+/// (rule, trace, category); reaching the threshold emits one synthetic event
+/// and starts the count over.
+#[derive(Debug, Clone, Default)]
+pub struct Escalator {
+    rules: Vec<EscalationRule>,
+    pending: HashMap<(usize, String, String), VecDeque<(f64, String)>>,
+}
+
+impl Escalator {
+    pub fn add_rule(&mut self, rule: EscalationRule) {
+        self.rules.push(rule);
+    }
+
+    pub fn clear(&mut self) {
+        self.rules.clear();
+        self.pending.clear();
+    }
+
+    pub fn rules(&self) -> &[EscalationRule] {
+        &self.rules
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Records `event` and returns any escalation events it triggered.
+    pub fn observe(&mut self, event: &Event) -> Vec<Event> {
+        if event.fields.contains_key(ESCALATION_FIELD) {
+            return Vec::new();
+        }
+
+        let mut escalations = Vec::new();
+        for (index, rule) in self.rules.iter().enumerate() {
+            if !rule.applies_to(event) {
+                continue;
+            }
+            let key = (index, event.context.trace_id.clone(), event.category.clone());
+            let seen = self.pending.entry(key).or_default();
+            seen.push_back((event.timestamp, event.context.span_id.clone()));
+            if let Some(window) = rule.window_secs {
+                while seen.front().is_some_and(|(t, _)| event.timestamp - t > window) {
+                    seen.pop_front();
+                }
+            }
+            if seen.len() >= rule.threshold {
+                let linked: Vec<String> = seen.drain(..).map(|(_, span)| span).collect();
+                escalations.push(escalation_event(rule, event, linked));
+            }
+        }
+        escalations
+    }
+}
+
+/// Sibling of the triggering event, carrying the span ids it summarises.
+fn escalation_event(rule: &EscalationRule, trigger: &Event, linked: Vec<String>) -> Event {
+    let context = CorrelationContext {
+        trace_id: trigger.context.trace_id.clone(),
+        span_id: Uuid::new_v4().to_string(),
+        parent_id: trigger.context.parent_id.clone(),
+    };
+    let mut event = Event::new(
+        rule.escalate_to,
+        trigger.category.clone(),
+        format!(
+            "Escalated by rule '{}': {} {:?} events in '{}'",
+            rule.name,
+            linked.len(),
+            rule.severity,
+            trigger.category
+        ),
+        context,
+        None,
+    )
+    .with_field(ESCALATION_FIELD, rule.name.as_str())
+    .with_field("count", linked.len())
+    .with_field("linked_spans", Value::from(linked))
+    .at(trigger.sim_time);
+    event.timestamp = trigger.timestamp;
+    event
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_threshold_escalates_per_trace_and_resets() {
+        let mut escalator = Escalator::default();
+        escalator.add_rule(EscalationRule::new("insolvency".to_string(), 3, EventSeverity::Warning, None, EventSeverity::Error, None));
+
+        let trace = CorrelationContext::new();
+        let warning = |ctx: &CorrelationContext| {
+            Event::new(EventSeverity::Warning, "Economy".to_string(), "Insolvent".to_string(), ctx.child(), None)
+        };
+
+        let mut escalations = Vec::new();
+        for _ in 0..7 {
+            escalations.extend(escalator.observe(&warning(&trace)));
+        }
+        // Another trace keeps its own count
+        escalations.extend(escalator.observe(&warning(&CorrelationContext::new())));
+
+        assert_eq!(escalations.len(), 2);
+        assert_eq!(escalations[0].severity, EventSeverity::Error);
+        assert_eq!(escalations[0].field("linked_spans").and_then(|v| v.as_array()).map(Vec::len), Some(3));
+        assert!(escalator.observe(&escalations[0]).is_empty());
+    }
+}
@@ -0,0 +1,675 @@
+use crate::bus::EventFilter;
+use crate::errors::EngineError;
+use crate::callback::{self, CallbackSubscription};
+use crate::columnar;
+use crate::cursor::{EventCursor, DEFAULT_CURSOR_BATCH};
+use crate::filter::EmissionFilter;
+use crate::ratelimit::RateLimiter;
+use crate::{export, ingest};
+use crate::sink::{EventSink, JsonlFileSink};
+use crate::span::Span;
+use crate::simtime::{self, SimClock, SimRange};
+use crate::{CorrelationContext, Event, EventSeverity};
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict};
+use std::cmp::Ordering as CmpOrdering;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, OnceLock, RwLock};
+
+/// Storage behind an `EventLog`. With a capacity set it behaves as a ring
+/// buffer: the oldest events are evicted and counted as dropped. Attached
+/// sinks see every event, including ones the buffer later evicts.
+#[derive(Default)]
+struct EventBuffer {
+    events: VecDeque<Event>,
+    capacity: Option<usize>,
+    dropped: u64,
+    high_water_mark: usize,
+    sinks: Vec<Arc<dyn EventSink>>,
+    /// Number of events the collector has moved out of the channel.
+    collected: u64,
+    /// Events that have left the front of `events` (evicted, drained or
+    /// cleared). `removed + i` is the sequence number of `events[i]`.
+    removed: u64,
+}
+
+impl EventBuffer {
+    fn push(&mut self, event: Event) {
+        for sink in &self.sinks {
+            sink.write(&event);
+        }
+        if let Some(capacity) = self.capacity {
+            if capacity == 0 {
+                self.dropped += 1;
+                return;
+            }
+            while self.events.len() >= capacity {
+                self.events.pop_front();
+                self.dropped += 1;
+                self.removed += 1;
+            }
+        }
+        self.events.push_back(event);
+        self.high_water_mark = self.high_water_mark.max(self.events.len());
+    }
+
+    fn set_capacity(&mut self, capacity: Option<usize>) {
+        self.capacity = capacity;
+        if let Some(capacity) = capacity {
+            let excess = self.events.len().saturating_sub(capacity);
+            self.events.drain(..excess);
+            self.dropped += excess as u64;
+            self.removed += excess as u64;
+        }
+    }
+}
+
+struct LogState {
+    buffer: Mutex<EventBuffer>,
+    /// Signalled by the collector whenever `buffer.collected` advances.
+    progress: Condvar,
+}
+
+/// Thread-safe event log shared between engines and Python.
+///
+/// `add` only enqueues onto a channel; a background collector thread moves
+/// events into the buffer and fans them out to sinks. Every read first waits
+/// until the collector has caught up with all events enqueued before it, so
+/// callers still observe their own writes.
+///
+/// The collector is started by the first `add`, so logs that are only ever
+/// read (merges, snapshots) cost no thread. If it can't be started, events
+/// go straight into the buffer instead.
+#[pyclass]
+#[derive(Clone)]
+pub struct EventLog {
+    state: Arc<LogState>,
+    /// None once starting the collector has failed.
+    sender: Arc<OnceLock<Option<Sender<Event>>>>,
+    /// Sinks called from `add` itself rather than from the collector.
+    relays: Arc<RwLock<Vec<Arc<dyn EventSink>>>>,
+    enqueued: Arc<AtomicU64>,
+    filter: Arc<EmissionFilter>,
+    limiter: Arc<RateLimiter>,
+    /// Stamps turn and tick on events that arrive without them.
+    clock: Arc<Mutex<Option<SimClock>>>,
+}
+
+impl EventLog {
+    pub fn new() -> Self {
+        Self::with_capacity(None)
+    }
+
+    /// Creates a log that keeps at most `capacity` events (unbounded if `None`).
+    pub fn with_capacity(capacity: Option<usize>) -> Self {
+        let state = Arc::new(LogState {
+            buffer: Mutex::new(EventBuffer {
+                capacity,
+                ..EventBuffer::default()
+            }),
+            progress: Condvar::new(),
+        });
+        Self {
+            state,
+            sender: Arc::new(OnceLock::new()),
+            relays: Arc::new(RwLock::new(Vec::new())),
+            enqueued: Arc::new(AtomicU64::new(0)),
+            filter: Arc::new(EmissionFilter::default()),
+            limiter: Arc::new(RateLimiter::default()),
+            clock: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Cheap pre-check so engines can skip building events below the minimum severity.
+    pub fn enabled_for(&self, severity: EventSeverity) -> bool {
+        self.filter.enabled_for(severity)
+    }
+
+    /// Streams every subsequently added event to `sink` as well.
+    pub fn attach_sink(&self, sink: Arc<dyn EventSink>) {
+        if let Some(mut buffer) = self.synced() {
+            buffer.sinks.push(sink);
+        }
+    }
+
+    /// Hands every later event to `sink` from `add` itself, before it is
+    /// queued, so logs `sink` forwards into are current as soon as `add`
+    /// returns. For cheap forwarders such as the event bus; anything slow
+    /// belongs on `attach_sink`.
+    pub fn attach_relay(&self, sink: Arc<dyn EventSink>) {
+        self.relays.write().unwrap_or_else(|e| e.into_inner()).push(sink);
+    }
+
+    /// Waits for the collector to catch up, then flushes every attached sink.
+    /// Pending rate-limit roll-ups are recorded first.
+    pub fn flush(&self) {
+        for rollup in self.limiter.flush() {
+            self.enqueue(rollup);
+        }
+        let mut sinks = self.synced().map(|b| b.sinks.clone()).unwrap_or_default();
+        sinks.extend(self.relays.read().unwrap_or_else(|e| e.into_inner()).iter().cloned());
+        for sink in sinks {
+            sink.flush();
+        }
+    }
+
+    /// Unbounded log pre-filled with `events` in the given order, bypassing
+    /// filters, rate limiting and sinks.
+    pub fn from_events(events: Vec<Event>) -> Self {
+        let log = Self::new();
+        if let Some(mut buffer) = log.synced() {
+            buffer.high_water_mark = events.len();
+            buffer.events = events.into();
+        }
+        log
+    }
+
+    /// Combines the buffered events of `logs` into one new log, ordered by
+    /// timestamp. Each log is first stable-sorted by `chronological`, so its
+    /// own events sharing a timestamp keep their recorded order; events from
+    /// different logs sharing a timestamp are then ordered by trace id, then
+    /// span id, then by position in `logs`. The inputs are left untouched.
+    pub fn merge(logs: &[EventLog]) -> Self {
+        let mut sources: Vec<std::iter::Peekable<std::vec::IntoIter<Event>>> = logs
+            .iter()
+            .map(|log| {
+                let mut events = log.get_all();
+                events.sort_by(chronological);
+                events.into_iter().peekable()
+            })
+            .collect();
+        let mut events = Vec::with_capacity(sources.iter().map(|s| s.len()).sum());
+        loop {
+            // First source whose next event sorts strictly earliest; full ties go to the lower index
+            let mut next: Option<(usize, &Event)> = None;
+            for (idx, source) in sources.iter_mut().enumerate() {
+                if let Some(event) = source.peek()
+                    && next.is_none_or(|(_, best)| merge_order(event, best).is_lt())
+                {
+                    next = Some((idx, event));
+                }
+            }
+            let Some(idx) = next.map(|(idx, _)| idx) else {
+                break;
+            };
+            events.extend(sources[idx].next());
+        }
+        Self::from_events(events)
+    }
+
+    /// Moves all buffered events out, leaving the log empty (stats are kept).
+    pub fn take_events(&self) -> Vec<Event> {
+        self.drain(None)
+    }
+
+    /// Sequence numbers of the oldest buffered event and one past the newest.
+    pub(crate) fn sequence_bounds(&self) -> (u64, u64) {
+        self.synced()
+            .map(|b| (b.removed, b.removed + b.events.len() as u64))
+            .unwrap_or_default()
+    }
+
+    /// Clones up to `max` events starting at sequence number `from` (or the
+    /// oldest still buffered, if later). Returns them with the sequence number
+    /// of the first one.
+    pub(crate) fn read_from(&self, from: u64, max: usize) -> (Vec<Event>, u64) {
+        let Some(buffer) = self.synced() else {
+            return (Vec::new(), from);
+        };
+        let start = from.max(buffer.removed);
+        let offset = (start - buffer.removed) as usize;
+        let events = buffer.events.iter().skip(offset).take(max).cloned().collect();
+        (events, start)
+    }
+
+    fn enqueue(&self, event: Event) {
+        for relay in self.relays.read().unwrap_or_else(|e| e.into_inner()).iter() {
+            relay.write(&event);
+        }
+        // Count before sending so a concurrent reader can never overtake the collector
+        self.enqueued.fetch_add(1, Ordering::AcqRel);
+        let event = match self.collector() {
+            Some(sender) => match sender.send(event) {
+                Ok(()) => return,
+                Err(mpsc::SendError(event)) => event,
+            },
+            None => event,
+        };
+        // No collector: do its job here.
+        if let Ok(mut buffer) = self.state.buffer.lock() {
+            buffer.push(event);
+            buffer.collected += 1;
+        } else {
+            self.enqueued.fetch_sub(1, Ordering::AcqRel);
+        }
+        self.state.progress.notify_all();
+    }
+
+    /// The collector's channel, starting the collector on first use.
+    fn collector(&self) -> Option<&Sender<Event>> {
+        self.sender
+            .get_or_init(|| {
+                let (sender, receiver) = mpsc::channel();
+                // The collector exits once every EventLog handle (and so the sender) is dropped
+                let state = Arc::clone(&self.state);
+                std::thread::Builder::new()
+                    .name("event-log-collector".to_string())
+                    .spawn(move || collect(state, receiver))
+                    .ok()
+                    .map(|_| sender)
+            })
+            .as_ref()
+    }
+
+    /// Locks the buffer once the collector has absorbed everything enqueued so far.
+    fn synced(&self) -> Option<MutexGuard<'_, EventBuffer>> {
+        let target = self.enqueued.load(Ordering::Acquire);
+        let buffer = self.state.buffer.lock().ok()?;
+        self.state
+            .progress
+            .wait_while(buffer, |b| b.collected < target)
+            .ok()
+    }
+}
+
+/// Orders events by timestamp alone. Use it with a stable sort so events
+/// sharing a timestamp keep the order they were recorded in; trace and span
+/// ids are random and say nothing about which came first.
+pub fn chronological(a: &Event, b: &Event) -> CmpOrdering {
+    a.timestamp.total_cmp(&b.timestamp)
+}
+
+/// `chronological`, with ties broken by trace id and then span id, so
+/// events from separate logs interleave the same way on every merge.
+fn merge_order(a: &Event, b: &Event) -> CmpOrdering {
+    chronological(a, b)
+        .then_with(|| a.context.trace_id.cmp(&b.context.trace_id))
+        .then_with(|| a.context.span_id.cmp(&b.context.span_id))
+}
+
+fn collect(state: Arc<LogState>, receiver: Receiver<Event>) {
+    while let Ok(first) = receiver.recv() {
+        let Ok(mut buffer) = state.buffer.lock() else {
+            return;
+        };
+        buffer.push(first);
+        let mut collected = 1;
+
+        // Drain whatever else is already queued under the same lock
+        while let Ok(event) = receiver.try_recv() {
+            buffer.push(event);
+            collected += 1;
+        }
+
+        buffer.collected += collected;
+        drop(buffer);
+        state.progress.notify_all();
+    }
+}
+
+impl EventSink for EventLog {
+    fn write(&self, event: &Event) {
+        self.add(event.clone());
+    }
+
+    fn flush(&self) {
+        EventLog::flush(self);
+    }
+}
+
+impl Default for EventLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[pymethods]
+impl EventLog {
+    #[new]
+    #[pyo3(signature = (capacity=None))]
+    fn py_new(capacity: Option<usize>) -> Self {
+        Self::with_capacity(capacity)
+    }
+
+    /// New log holding every event of `logs` in deterministic chronological order.
+    #[staticmethod]
+    #[pyo3(name = "merge")]
+    fn py_merge(logs: Vec<EventLog>) -> Self {
+        Self::merge(&logs)
+    }
+
+    pub fn add(&self, mut event: Event) {
+        if !self.filter.admits(event.severity) {
+            return;
+        }
+        if let Some(clock) = self.clock.lock().unwrap_or_else(|e| e.into_inner()).as_ref() {
+            let now = clock.now();
+            // A tick only means something within the clock's own turn.
+            if event.sim_time.turn.is_none_or(|turn| Some(turn) == now.turn) {
+                event.sim_time.tick = event.sim_time.tick.or(now.tick);
+            }
+            event.sim_time.turn = event.sim_time.turn.or(now.turn);
+        }
+        if self.limiter.is_enabled() {
+            let (admit, rollups) = self.limiter.check(&event);
+            for rollup in rollups {
+                self.enqueue(rollup);
+            }
+            if !admit {
+                return;
+            }
+        }
+        self.enqueue(event);
+    }
+
+    /// Lets at most `max_events` events with the same category and message
+    /// template (digits ignored) through per `window_secs`; the rest are folded
+    /// into one "Suppressed N duplicates" event when the window closes.
+    /// Critical events are never suppressed.
+    pub fn set_rate_limit(&self, max_events: u32, window_secs: f64) {
+        for rollup in self.limiter.configure(Some((max_events, window_secs))) {
+            self.enqueue(rollup);
+        }
+    }
+
+    /// Turns rate limiting off, recording roll-ups for anything still pending.
+    pub fn clear_rate_limit(&self) {
+        for rollup in self.limiter.configure(None) {
+            self.enqueue(rollup);
+        }
+    }
+
+    /// Stamps `clock`'s turn and tick on every later event that doesn't
+    /// carry its own; None stops stamping.
+    #[pyo3(signature = (clock=None))]
+    pub fn set_clock(&self, clock: Option<SimClock>) {
+        *self.clock.lock().unwrap_or_else(|e| e.into_inner()) = clock;
+    }
+
+    /// Events held back by the rate limiter since creation.
+    pub fn suppressed_count(&self) -> u64 {
+        self.limiter.suppressed_count()
+    }
+
+    /// Events below this severity are discarded before reaching the buffer or sinks.
+    pub fn set_min_severity(&self, severity: EventSeverity) {
+        self.filter.set_min_severity(severity);
+    }
+
+    pub fn min_severity(&self) -> EventSeverity {
+        self.filter.min_severity()
+    }
+
+    /// Keeps only this fraction (0.0..=1.0) of Debug/Info events; Warning and above are never sampled.
+    pub fn set_sample_rate(&self, rate: f64) {
+        self.filter.set_sample_rate(rate);
+    }
+
+    pub fn sample_rate(&self) -> f64 {
+        self.filter.sample_rate()
+    }
+
+    /// Seeds this log's sampler so which events survive sampling is
+    /// reproducible. Each log samples independently of every other.
+    pub fn seed_sampling(&self, seed: u64) {
+        self.filter.seed_sampler(seed);
+    }
+
+    /// Number of events rejected by the severity filter or sampling.
+    pub fn filtered_count(&self) -> u64 {
+        self.filter.filtered_count()
+    }
+
+    pub fn get_all(&self) -> Vec<Event> {
+        self.synced()
+            .map(|buffer| buffer.events.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Snapshot of the buffered events in the format `CausalGraph.add_events_bincode` reads.
+    pub fn to_bincode<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        let events = self.get_all();
+        let bytes = ingest::encode_bincode(&events)
+            .map_err(|e| PyErr::from(EngineError::serialization("Bincode", e)))?;
+        Ok(PyBytes::new(py, &bytes))
+    }
+
+    /// Buffered events as a dict of column lists, e.g. for `polars.DataFrame(...)`.
+    pub fn to_columns<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        columnar::event_columns(py, &self.get_all())
+    }
+
+    /// Buffered events as a `pyarrow.Table` (requires pyarrow).
+    pub fn to_arrow<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        columnar::arrow_table(&self.to_columns(py)?)
+    }
+
+    /// Buffered events and spans in Chrome trace-event JSON, for Perfetto.
+    pub fn to_chrome_trace(&self) -> String {
+        export::to_chrome_trace(&self.get_all()).to_string()
+    }
+
+    /// Writes `to_chrome_trace()` to `path`.
+    pub fn write_chrome_trace(&self, path: &str) -> PyResult<()> {
+        std::fs::write(path, self.to_chrome_trace())
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("Export error: {}", e)))
+    }
+
+    /// Buffered events within the inclusive turn/tick bounds, in simulation order.
+    /// Bounding an axis excludes events not stamped on it.
+    #[pyo3(signature = (turn_min=None, turn_max=None, tick_min=None, tick_max=None))]
+    pub fn get_by_sim_time(
+        &self,
+        turn_min: Option<u64>,
+        turn_max: Option<u64>,
+        tick_min: Option<u64>,
+        tick_max: Option<u64>,
+    ) -> Vec<Event> {
+        let range = SimRange { turn_min, turn_max, tick_min, tick_max };
+        let mut events: Vec<Event> = self
+            .synced()
+            .map(|buffer| buffer.events.iter().filter(|e| range.matches(e)).cloned().collect())
+            .unwrap_or_default();
+        simtime::sort_by_sim_time(&mut events);
+        events
+    }
+
+    pub fn clear(&self) {
+        self.drain(None);
+    }
+
+    /// Removes and returns up to `n` of the oldest events (all if `None`).
+    /// Events are moved out, so repeated calls page through a live log cheaply.
+    #[pyo3(signature = (n=None))]
+    pub fn drain(&self, n: Option<usize>) -> Vec<Event> {
+        let Some(mut buffer) = self.synced() else {
+            return Vec::new();
+        };
+        let count = n.unwrap_or(usize::MAX).min(buffer.events.len());
+        buffer.removed += count as u64;
+        buffer.events.drain(..count).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.synced().map(|b| b.events.len()).unwrap_or(0)
+    }
+
+    fn __len__(&self) -> usize {
+        self.len()
+    }
+
+    /// Iterates the buffered events oldest first, continuing into events added
+    /// while iterating. Does not consume the log.
+    fn __iter__(&self) -> EventCursor {
+        self.cursor(true, DEFAULT_CURSOR_BATCH)
+    }
+
+    /// Cursor for polling the log incrementally: from the oldest buffered
+    /// event, or with `from_start=False` only events added from now on.
+    #[pyo3(signature = (from_start=true, batch_size=DEFAULT_CURSOR_BATCH))]
+    pub fn cursor(&self, from_start: bool, batch_size: usize) -> EventCursor {
+        let (first, end) = self.sequence_bounds();
+        EventCursor::new(self.clone(), if from_start { first } else { end }, batch_size)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn capacity(&self) -> Option<usize> {
+        self.state.buffer.lock().ok().and_then(|b| b.capacity)
+    }
+
+    /// Changes the capacity; shrinking below the current size evicts the oldest events.
+    #[pyo3(signature = (capacity=None))]
+    pub fn set_capacity(&self, capacity: Option<usize>) {
+        if let Some(mut buffer) = self.synced() {
+            buffer.set_capacity(capacity);
+        }
+    }
+
+    /// Number of events evicted or rejected because the log was full.
+    pub fn dropped_count(&self) -> u64 {
+        self.synced().map(|b| b.dropped).unwrap_or(0)
+    }
+
+    /// Largest number of events held at once since creation or the last reset.
+    pub fn high_water_mark(&self) -> usize {
+        self.synced().map(|b| b.high_water_mark).unwrap_or(0)
+    }
+
+    #[pyo3(name = "attach_sink")]
+    fn py_attach_sink(&self, sink: &JsonlFileSink) {
+        self.attach_sink(sink.inner.clone());
+    }
+
+    /// Opens a span under `parent` (a fresh root context if omitted).
+    #[pyo3(signature = (name, parent=None))]
+    pub fn start_span(&self, name: &str, parent: Option<CorrelationContext>) -> Span {
+        Span::start(name, &parent.unwrap_or_default())
+    }
+
+    /// Closes the span (if still open) and records it as a `Span` event.
+    pub fn end_span(&self, mut span: Span) -> Span {
+        span.finish();
+        if self.enabled_for(EventSeverity::Debug) {
+            self.add(span.to_event());
+        }
+        span
+    }
+
+    /// All recorded spans currently held in the log.
+    pub fn get_spans(&self) -> Vec<Span> {
+        self.synced()
+            .map(|buffer| buffer.events.iter().filter_map(Span::from_event).collect())
+            .unwrap_or_default()
+    }
+
+    /// Waits for the collector to catch up, then flushes every attached sink.
+    /// The GIL is released meanwhile so callback subscriptions can drain.
+    #[pyo3(name = "flush")]
+    fn py_flush(&self, py: Python<'_>) {
+        py.allow_threads(|| self.flush());
+    }
+
+    /// Calls `callback(events)` with batches of matching events from a
+    /// background thread. Use the returned handle to flush or cancel.
+    #[pyo3(signature = (callback, filter=None, batch_size=64, max_latency_ms=100))]
+    pub fn subscribe_callback(
+        &self,
+        callback: PyObject,
+        filter: Option<EventFilter>,
+        batch_size: usize,
+        max_latency_ms: u64,
+    ) -> PyResult<CallbackSubscription> {
+        let subscription = callback::create_subscription(callback, filter, batch_size, max_latency_ms)?;
+        self.attach_sink(subscription.inner.clone());
+        Ok(subscription)
+    }
+
+    pub fn reset_stats(&self) {
+        if let Some(mut buffer) = self.synced() {
+            buffer.dropped = 0;
+            buffer.high_water_mark = buffer.events.len();
+        }
+        self.filter.reset_count();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(message: &str) -> Event {
+        Event::new(EventSeverity::Info, "Test".to_string(), message.to_string(), CorrelationContext::new(), None)
+    }
+
+    #[test]
+    fn test_bounded_event_log_drops_oldest() {
+        let log = EventLog::with_capacity(Some(2));
+        for msg in ["a", "b", "c"] {
+            log.add(event(msg));
+        }
+
+        let messages: Vec<String> = log.get_all().into_iter().map(|e| e.message).collect();
+        assert_eq!(messages, vec!["b", "c"]);
+        assert_eq!(log.dropped_count(), 1);
+        assert_eq!(log.high_water_mark(), 2);
+
+        log.set_capacity(Some(1));
+        assert_eq!(log.len(), 1);
+        assert_eq!(log.dropped_count(), 2);
+    }
+
+    #[test]
+    fn test_concurrent_producers_are_all_collected() {
+        let log = EventLog::new();
+        let handles: Vec<_> = (0..4)
+            .map(|t| {
+                let log = log.clone();
+                std::thread::spawn(move || {
+                    for i in 0..250 {
+                        log.add(event(&format!("{}-{}", t, i)));
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(log.len(), 1000);
+    }
+
+    #[test]
+    fn test_merge_orders_by_timestamp_then_ids() {
+        let (a, b) = (EventLog::new(), EventLog::new());
+        // a3 is stamped earlier than a2 (a span recorded when it closes, say).
+        for (log, msg, at, trace) in [
+            (&a, "a1", 100.0, "t2"),
+            (&b, "b1", 100.0, "t1"),
+            (&a, "a2", 100.0, "t2"),
+            (&b, "b2", 100.0, "t1"),
+            (&a, "a3", 50.0, "t2"),
+            (&b, "b3", 120.0, "t1"),
+        ] {
+            let mut e = event(msg);
+            e.timestamp = at;
+            e.context.trace_id = trace.to_string();
+            e.context.span_id = "s".to_string();
+            log.add(e);
+        }
+        let merged = |logs: &[EventLog]| -> Vec<Event> { EventLog::merge(logs).get_all() };
+        let messages = |events: Vec<Event>| -> Vec<String> { events.into_iter().map(|e| e.message).collect() };
+
+        let events = merged(&[a.clone(), b.clone()]);
+        assert!(events.windows(2).all(|pair| pair[0].timestamp <= pair[1].timestamp));
+        assert_eq!(messages(events), ["a3", "b1", "b2", "a1", "a2", "b3"]);
+        for _ in 0..50 {
+            assert_eq!(messages(merged(&[b.clone(), a.clone()])), ["a3", "b1", "b2", "a1", "a2", "b3"]);
+            assert_eq!(messages(merged(std::slice::from_ref(&a))), ["a3", "a1", "a2"]);
+        }
+    }
+}
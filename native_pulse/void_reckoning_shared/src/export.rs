@@ -0,0 +1,205 @@
+use crate::span::{Span, SPAN_CATEGORY};
+use crate::{CausalGraph, Event, EventSeverity};
+use serde_json::{json, Map, Value};
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write;
+
+/// `root` followed by all of its descendants in breadth-first order.
+fn subtree<'a>(graph: &'a CausalGraph, root: &'a str) -> Vec<&'a str> {
+    let mut order = vec![root];
+    let mut queue = VecDeque::from([root]);
+    while let Some(id) = queue.pop_front() {
+        for child in graph.children_map.get(id).into_iter().flatten() {
+            if graph.events.contains_key(child) {
+                order.push(child);
+                queue.push_back(child);
+            }
+        }
+    }
+    order
+}
+
+fn severity_color(severity: EventSeverity) -> &'static str {
+    match severity {
+        EventSeverity::Debug => "gray",
+        EventSeverity::Info => "black",
+        EventSeverity::Warning => "orange",
+        EventSeverity::Error => "red",
+        EventSeverity::Critical => "darkred",
+    }
+}
+
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// Renders the tree under `root` as a Graphviz digraph, one node per event
+/// labelled "category: message" and coloured by severity.
+pub fn to_dot(graph: &CausalGraph, root: &str) -> Option<String> {
+    graph.events.get(root)?;
+    let nodes = subtree(graph, root);
+
+    let mut dot = String::from("digraph causal {\n    rankdir=TB;\n    node [shape=box, fontname=\"monospace\"];\n");
+    for id in &nodes {
+        let event = &graph.events[*id];
+        let _ = writeln!(
+            dot,
+            "    \"{}\" [label=\"{}: {}\", color={}];",
+            escape_dot(id),
+            escape_dot(&event.category),
+            escape_dot(&event.message),
+            severity_color(event.severity)
+        );
+    }
+    for id in &nodes {
+        for child in graph.children_map.get(*id).into_iter().flatten() {
+            if graph.events.contains_key(child) {
+                let _ = writeln!(dot, "    \"{}\" -> \"{}\";", escape_dot(id), escape_dot(child));
+            }
+        }
+    }
+    dot.push_str("}\n");
+    Some(dot)
+}
+
+fn node_value(span_id: &str, event: &Event) -> Map<String, Value> {
+    let mut node = Map::new();
+    node.insert("span_id".to_string(), json!(span_id));
+    node.insert("category".to_string(), json!(event.category));
+    node.insert("message".to_string(), json!(event.message));
+    node.insert("severity".to_string(), json!(format!("{:?}", event.severity)));
+    node.insert("timestamp".to_string(), json!(event.timestamp));
+    if !event.fields.is_empty() {
+        node.insert("fields".to_string(), Value::Object(event.fields.clone()));
+    }
+    node
+}
+
+/// Nested `{span_id, category, message, severity, timestamp, fields?, children}`
+/// tree under `root`. Built bottom-up so deep chains don't recurse.
+pub fn to_json_tree(graph: &CausalGraph, root: &str) -> Option<Value> {
+    graph.events.get(root)?;
+    let nodes = subtree(graph, root);
+
+    let mut built: HashMap<&str, Value> = HashMap::with_capacity(nodes.len());
+    for id in nodes.iter().rev() {
+        let mut node = node_value(id, &graph.events[*id]);
+        let children: Vec<Value> = graph
+            .children_map
+            .get(*id)
+            .into_iter()
+            .flatten()
+            .filter_map(|child| built.remove(child.as_str()))
+            .collect();
+        node.insert("children".to_string(), Value::Array(children));
+        built.insert(id, Value::Object(node));
+    }
+    built.remove(root)
+}
+
+/// Track an event is drawn on: spans group by engine (the `combat` in
+/// `combat.step`), everything else by category.
+fn track_name(event: &Event) -> &str {
+    if event.category == SPAN_CATEGORY {
+        event.message.split('.').next().unwrap_or(&event.message)
+    } else {
+        &event.category
+    }
+}
+
+/// Chrome trace-event JSON (openable in Perfetto or chrome://tracing).
+/// Spans become complete ("X") slices, other events instant ("i") markers,
+/// one thread track per engine/category. Times are microseconds from the
+/// earliest event.
+pub fn to_chrome_trace<'a>(events: impl IntoIterator<Item = &'a Event>) -> Value {
+    let mut events: Vec<&Event> = events.into_iter().collect();
+    events.sort_by(|a, b| a.timestamp.total_cmp(&b.timestamp));
+    let origin = events.first().map_or(0.0, |e| e.timestamp);
+    let micros = |secs: f64| (secs - origin) * 1_000_000.0;
+
+    let mut tracks: HashMap<&str, usize> = HashMap::new();
+    let mut trace_events = Vec::with_capacity(events.len());
+    for event in &events {
+        let next_tid = tracks.len() + 1;
+        let track = track_name(event);
+        let tid = *tracks.entry(track).or_insert_with(|| {
+            trace_events.push(json!({
+                "name": "thread_name", "ph": "M", "pid": 1, "tid": next_tid,
+                "args": { "name": track },
+            }));
+            next_tid
+        });
+
+        let mut ids = Map::new();
+        ids.insert("span_id".to_string(), json!(event.context.span_id));
+        ids.insert("trace_id".to_string(), json!(event.context.trace_id));
+        if let Some(parent) = &event.context.parent_id {
+            ids.insert("parent_id".to_string(), json!(parent));
+        }
+
+        // Span events carry their timing in `fields`; only the attributes are worth showing
+        let entry = match Span::from_event(event) {
+            Some(span) => {
+                let mut args = span.attributes.clone();
+                args.extend(ids);
+                json!({
+                    "name": span.name, "cat": track, "ph": "X", "pid": 1, "tid": tid,
+                    "ts": micros(span.start),
+                    "dur": span.duration().unwrap_or(0.0) * 1_000_000.0,
+                    "args": args,
+                })
+            }
+            None => {
+                let mut args = event.fields.clone();
+                args.extend(ids);
+                args.insert("severity".to_string(), json!(format!("{:?}", event.severity)));
+                json!({
+                    "name": event.message, "cat": event.category, "ph": "i", "s": "t", "pid": 1, "tid": tid,
+                    "ts": micros(event.timestamp),
+                    "args": args,
+                })
+            }
+        };
+        trace_events.push(entry);
+    }
+
+    json!({ "traceEvents": trace_events, "displayTimeUnit": "ms" })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CorrelationContext;
+
+    #[test]
+    fn test_export_follows_causal_tree() {
+        let root_ctx = CorrelationContext::new();
+        let child_ctx = root_ctx.child();
+        let mut graph = CausalGraph::new();
+        graph.add_event(Event::new(EventSeverity::Error, "Economy".to_string(), "Insolvent".to_string(), root_ctx.clone(), None));
+        graph.add_event(Event::new(EventSeverity::Warning, "Fleet".to_string(), "Disbanded \"3rd\"".to_string(), child_ctx.clone(), None));
+
+        let dot = to_dot(&graph, &root_ctx.span_id).unwrap();
+        assert!(dot.contains(&format!("\"{}\" -> \"{}\"", root_ctx.span_id, child_ctx.span_id)));
+        assert!(dot.contains("Disbanded \\\"3rd\\\""));
+
+        let tree = to_json_tree(&graph, &root_ctx.span_id).unwrap();
+        assert_eq!(tree["children"][0]["span_id"], json!(child_ctx.span_id));
+        assert!(to_dot(&graph, "missing").is_none());
+    }
+
+    #[test]
+    fn test_chrome_trace_tracks_by_engine() {
+        let root = CorrelationContext::new();
+        let mut span = Span::start("combat.step", &root);
+        span.finish();
+        let hit = Event::new(EventSeverity::Info, "Combat".to_string(), "Hit".to_string(), span.context.child(), None);
+
+        let trace = to_chrome_trace([&span.to_event(), &hit]);
+        let entries = trace["traceEvents"].as_array().unwrap();
+        let phases: Vec<&str> = entries.iter().map(|e| e["ph"].as_str().unwrap()).collect();
+        assert_eq!(phases.iter().filter(|p| **p == "M").count(), 2);
+        assert!(entries.iter().any(|e| e["ph"] == "X" && e["name"] == "combat.step" && e["ts"] == json!(0.0)));
+        assert!(entries.iter().any(|e| e["ph"] == "i" && e["cat"] == "Combat"));
+    }
+}
@@ -0,0 +1,150 @@
+use crate::EventSeverity;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU8, AtomicU32, AtomicU64, Ordering};
+
+const SAMPLE_SCALE: u32 = 1_000_000;
+
+/// Lock-free emission settings for an `EventLog`, checked before the event
+/// buffer's mutex is taken so rejected events never contend on it.
+#[derive(Debug)]
+pub struct EmissionFilter {
+    min_severity: AtomicU8,
+    /// Fraction of Debug/Info events kept, in parts per million.
+    sample_rate_ppm: AtomicU32,
+    filtered: AtomicU64,
+    /// xorshift state for sampling; per filter so one log's sampling never
+    /// shifts another's.
+    sampler: AtomicU64,
+}
+
+impl Default for EmissionFilter {
+    fn default() -> Self {
+        Self {
+            min_severity: AtomicU8::new(EventSeverity::Debug as u8),
+            sample_rate_ppm: AtomicU32::new(SAMPLE_SCALE),
+            filtered: AtomicU64::new(0),
+            sampler: AtomicU64::new(seed()),
+        }
+    }
+}
+
+impl EmissionFilter {
+    /// Whether an event of this severity passes the minimum-severity gate.
+    /// Engines can use this to skip building events that would be discarded.
+    pub fn enabled_for(&self, severity: EventSeverity) -> bool {
+        severity as u8 >= self.min_severity.load(Ordering::Relaxed)
+    }
+
+    /// Applies the severity gate and, for Debug/Info, the sampling rate.
+    /// Rejections are counted.
+    pub fn admits(&self, severity: EventSeverity) -> bool {
+        let admitted = self.enabled_for(severity) && match severity {
+            EventSeverity::Debug | EventSeverity::Info => {
+                let rate = self.sample_rate_ppm.load(Ordering::Relaxed);
+                rate >= SAMPLE_SCALE || (rate > 0 && self.next_random() % SAMPLE_SCALE < rate)
+            }
+            _ => true,
+        };
+        if !admitted {
+            self.filtered.fetch_add(1, Ordering::Relaxed);
+        }
+        admitted
+    }
+
+    pub fn min_severity(&self) -> EventSeverity {
+        match self.min_severity.load(Ordering::Relaxed) {
+            0 => EventSeverity::Debug,
+            1 => EventSeverity::Info,
+            2 => EventSeverity::Warning,
+            3 => EventSeverity::Error,
+            _ => EventSeverity::Critical,
+        }
+    }
+
+    pub fn set_min_severity(&self, severity: EventSeverity) {
+        self.min_severity.store(severity as u8, Ordering::Relaxed);
+    }
+
+    pub fn sample_rate(&self) -> f64 {
+        self.sample_rate_ppm.load(Ordering::Relaxed) as f64 / SAMPLE_SCALE as f64
+    }
+
+    /// Sets the fraction (0.0..=1.0) of Debug/Info events to keep.
+    pub fn set_sample_rate(&self, rate: f64) {
+        let ppm = (rate.clamp(0.0, 1.0) * SAMPLE_SCALE as f64).round() as u32;
+        self.sample_rate_ppm.store(ppm, Ordering::Relaxed);
+    }
+
+    /// Makes sampling reproducible: two filters seeded alike at the same
+    /// rate keep the same events.
+    pub fn seed_sampler(&self, seed: u64) {
+        // xorshift never leaves zero
+        self.sampler.store(seed | 1, Ordering::Relaxed);
+    }
+
+    pub fn filtered_count(&self) -> u64 {
+        self.filtered.load(Ordering::Relaxed)
+    }
+
+    pub fn reset_count(&self) {
+        self.filtered.store(0, Ordering::Relaxed);
+    }
+}
+
+fn seed() -> u64 {
+    // RandomState is freshly keyed per instance, which is all the entropy sampling needs
+    RandomState::new().build_hasher().finish() | 1
+}
+
+/// xorshift64*: statistically fine for sampling.
+fn xorshift(mut x: u64) -> u64 {
+    x ^= x >> 12;
+    x ^= x << 25;
+    x ^= x >> 27;
+    x
+}
+
+impl EmissionFilter {
+    fn next_random(&self) -> u32 {
+        let previous = self
+            .sampler
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |x| Some(xorshift(x)))
+            .unwrap_or_else(|x| x);
+        (xorshift(previous).wrapping_mul(0x2545_f491_4f6c_dd1d) >> 32) as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kept(filter: &EmissionFilter) -> Vec<bool> {
+        (0..64).map(|_| filter.admits(EventSeverity::Info)).collect()
+    }
+
+    #[test]
+    fn seeded_filters_sample_identically() {
+        let (a, b) = (EmissionFilter::default(), EmissionFilter::default());
+        for filter in [&a, &b] {
+            filter.set_sample_rate(0.5);
+            filter.seed_sampler(42);
+        }
+        let first = kept(&a);
+        // Sampling on another filter must not advance this one.
+        kept(&EmissionFilter::default());
+        assert_eq!(first, kept(&b));
+        assert!(first.contains(&true) && first.contains(&false));
+    }
+
+    #[test]
+    fn sampling_never_drops_warnings_and_above() {
+        let filter = EmissionFilter::default();
+        filter.set_sample_rate(0.0);
+        for severity in [EventSeverity::Warning, EventSeverity::Error, EventSeverity::Critical] {
+            assert!((0..64).all(|_| filter.admits(severity)));
+        }
+        assert!(!filter.admits(EventSeverity::Debug) && !filter.admits(EventSeverity::Info));
+        assert_eq!(filter.filtered_count(), 2);
+    }
+}
@@ -0,0 +1,5 @@
+//! Typed ids shared across engines; see `void_reckoning_ids`, which holds
+//! them outside this crate so wasm builds of the pathfinder and combat
+//! can use them too. Re-exported here with their Python conversions.
+
+pub use void_reckoning_ids::*;
@@ -0,0 +1,113 @@
+use crate::simtime::SimTime;
+use crate::{CausalGraph, CorrelationContext, Event, EventSeverity};
+use serde::{Deserialize, Serialize};
+
+/// Bincode representation of an `Event`. Bincode is not self-describing, so
+/// the free-form `fields` map travels as a JSON string.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct WireEvent {
+    timestamp: f64,
+    severity: EventSeverity,
+    category: String,
+    message: String,
+    context: CorrelationContext,
+    data: Option<String>,
+    fields: String,
+    sim_time: SimTime,
+}
+
+impl From<&Event> for WireEvent {
+    fn from(event: &Event) -> Self {
+        Self {
+            timestamp: event.timestamp,
+            severity: event.severity,
+            category: event.category.clone(),
+            message: event.message.clone(),
+            context: event.context.clone(),
+            data: event.data.clone(),
+            sim_time: event.sim_time,
+            fields: if event.fields.is_empty() {
+                String::new()
+            } else {
+                serde_json::to_string(&event.fields).unwrap_or_default()
+            },
+        }
+    }
+}
+
+impl TryFrom<WireEvent> for Event {
+    type Error = String;
+
+    fn try_from(wire: WireEvent) -> Result<Self, Self::Error> {
+        let fields = if wire.fields.is_empty() {
+            Default::default()
+        } else {
+            serde_json::from_str(&wire.fields).map_err(|e| format!("fields of {}: {}", wire.context.span_id, e))?
+        };
+        Ok(Self {
+            timestamp: wire.timestamp,
+            severity: wire.severity,
+            category: wire.category,
+            message: wire.message,
+            context: wire.context,
+            data: wire.data,
+            fields,
+            sim_time: wire.sim_time,
+        })
+    }
+}
+
+/// Encodes events as a bincode `Vec` readable by `decode_bincode`.
+pub fn encode_bincode<'a>(events: impl IntoIterator<Item = &'a Event>) -> Result<Vec<u8>, String> {
+    let wire: Vec<WireEvent> = events.into_iter().map(WireEvent::from).collect();
+    bincode::serialize(&wire).map_err(|e| e.to_string())
+}
+
+pub fn decode_bincode(bytes: &[u8]) -> Result<Vec<Event>, String> {
+    let wire: Vec<WireEvent> = bincode::deserialize(bytes).map_err(|e| e.to_string())?;
+    wire.into_iter().map(Event::try_from).collect()
+}
+
+/// Parses newline-delimited JSON events; blank lines are skipped.
+pub fn parse_jsonl(text: &str) -> Result<Vec<Event>, String> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| serde_json::from_str(line).map_err(|e| format!("line {}: {}", index + 1, e)))
+        .collect()
+}
+
+impl CausalGraph {
+    /// Inserts events by value, reserving index space up front.
+    pub fn add_events(&mut self, events: Vec<Event>) {
+        self.events.reserve(events.len());
+        self.parent_map.reserve(events.len());
+        self.order.reserve(events.len());
+        for event in events {
+            self.add_event(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bincode_round_trip_keeps_fields() {
+        let events = vec![
+            Event::new(EventSeverity::Info, "Test".to_string(), "plain".to_string(), CorrelationContext::new(), None),
+            Event::new(EventSeverity::Error, "Test".to_string(), "rich".to_string(), CorrelationContext::new(), None)
+                .with_field("deficit", 12.5),
+        ];
+
+        let decoded = decode_bincode(&encode_bincode(&events).unwrap()).unwrap();
+        assert_eq!(decoded.len(), 2);
+        assert!(decoded[0].fields.is_empty());
+        assert_eq!(decoded[1].get_float("deficit"), Some(12.5));
+
+        let jsonl: String = events.iter().map(|e| serde_json::to_string(e).unwrap() + "\n\n").collect();
+        assert_eq!(parse_jsonl(&jsonl).unwrap().len(), 2);
+        assert!(parse_jsonl("{not json}").unwrap_err().starts_with("line 1"));
+    }
+}
@@ -0,0 +1,216 @@
+use crate::{CausalGraph, CorrelationContext, Event, EventSeverity};
+use pyo3::prelude::*;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Category of the synthetic root that `repair` hangs orphans under.
+pub const INTEGRITY_CATEGORY: &str = "Integrity";
+
+/// Structural problems found in a `CausalGraph`. Each one means
+/// `get_causal_chain` stops short of the real root cause.
+#[derive(Debug, Clone, Default, Serialize)]
+#[pyclass]
+pub struct IntegrityReport {
+    /// Events whose parent span is not in the graph.
+    #[pyo3(get)]
+    pub orphans: Vec<String>,
+    /// Span ids referenced as a parent but never recorded as an event.
+    #[pyo3(get)]
+    pub unknown_parents: Vec<String>,
+    /// Links left behind for events that are no longer in the graph.
+    #[pyo3(get)]
+    pub dangling_links: Vec<String>,
+    /// Parent chains that loop back on themselves (a correlation bug).
+    #[pyo3(get)]
+    pub cycles: Vec<Vec<String>>,
+    /// Set by `repair` when orphans or cycles were re-rooted.
+    #[pyo3(get)]
+    pub synthetic_root: Option<String>,
+}
+
+#[pymethods]
+impl IntegrityReport {
+    pub fn is_healthy(&self) -> bool {
+        self.orphans.is_empty() && self.unknown_parents.is_empty() && self.dangling_links.is_empty() && self.cycles.is_empty()
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "IntegrityReport(orphans={}, unknown_parents={}, dangling_links={}, cycles={})",
+            self.orphans.len(),
+            self.unknown_parents.len(),
+            self.dangling_links.len(),
+            self.cycles.len()
+        )
+    }
+}
+
+impl CausalGraph {
+    fn find_cycles(&self) -> Vec<Vec<String>> {
+        // 1 = on the current walk, 2 = fully explored
+        let mut state: HashMap<&str, u8> = HashMap::with_capacity(self.parent_map.len());
+        let mut cycles = Vec::new();
+
+        for start in self.parent_map.keys() {
+            let mut walk: Vec<&str> = Vec::new();
+            let mut current = Some(start.as_str());
+            while let Some(id) = current {
+                match state.get(id) {
+                    Some(2) => break,
+                    Some(_) => {
+                        if let Some(pos) = walk.iter().position(|w| *w == id) {
+                            cycles.push(walk[pos..].iter().map(|s| s.to_string()).collect());
+                        }
+                        break;
+                    }
+                    None => {
+                        state.insert(id, 1);
+                        walk.push(id);
+                        current = self.parent_map.get(id).map(String::as_str);
+                    }
+                }
+            }
+            for id in walk {
+                state.insert(id, 2);
+            }
+        }
+        cycles
+    }
+
+    pub(crate) fn integrity_report(&self) -> IntegrityReport {
+        let mut orphans: Vec<String> = self
+            .parent_map
+            .iter()
+            .filter(|(child, parent)| self.events.contains_key(*child) && !self.events.contains_key(*parent))
+            .map(|(child, _)| child.clone())
+            .collect();
+        let mut unknown_parents: Vec<String> = self
+            .children_map
+            .keys()
+            .filter(|parent| !self.events.contains_key(*parent))
+            .cloned()
+            .collect();
+        let mut dangling_links: Vec<String> = self
+            .parent_map
+            .keys()
+            .chain(self.children_map.values().flatten())
+            .filter(|child| !self.events.contains_key(*child))
+            .cloned()
+            .collect();
+        orphans.sort();
+        unknown_parents.sort();
+        dangling_links.sort();
+        dangling_links.dedup();
+
+        IntegrityReport {
+            orphans,
+            unknown_parents,
+            dangling_links,
+            cycles: self.find_cycles(),
+            synthetic_root: None,
+        }
+    }
+
+    fn relink(&mut self, child: &str, parent: &str) {
+        if let Some(old_parent) = self.parent_map.insert(child.to_string(), parent.to_string())
+            && let Some(siblings) = self.children_map.get_mut(&old_parent)
+        {
+            siblings.retain(|s| s != child);
+            if siblings.is_empty() {
+                self.children_map.remove(&old_parent);
+            }
+        }
+        self.children_map.entry(parent.to_string()).or_default().push(child.to_string());
+        if let Some(event) = self.events.get_mut(child)
+            && let Some(original) = event.context.parent_id.replace(parent.to_string())
+        {
+            event.fields.insert("original_parent_id".to_string(), original.into());
+        }
+    }
+
+    /// Drops stale links, then attaches orphans and the oldest member of each
+    /// cycle under a single synthetic root event. Returns what was found.
+    pub(crate) fn repair_integrity(&mut self) -> IntegrityReport {
+        let mut report = self.integrity_report();
+
+        for stale in &report.dangling_links {
+            self.parent_map.remove(stale);
+        }
+        for children in self.children_map.values_mut() {
+            children.retain(|c| self.events.contains_key(c));
+        }
+        self.children_map.retain(|parent, children| !children.is_empty() || self.events.contains_key(parent));
+
+        let mut detached = report.orphans.clone();
+        for cycle in &report.cycles {
+            let oldest = cycle
+                .iter()
+                .filter_map(|id| self.events.get(id).map(|e| (id, e.timestamp)))
+                .min_by(|a, b| a.1.total_cmp(&b.1));
+            if let Some((id, _)) = oldest {
+                detached.push(id.clone());
+            }
+        }
+        if detached.is_empty() {
+            return report;
+        }
+
+        let root_context = CorrelationContext::new();
+        let root_id = root_context.span_id.clone();
+        let mut root = Event::new(
+            EventSeverity::Warning,
+            INTEGRITY_CATEGORY.to_string(),
+            format!("Synthetic root for {} detached events", detached.len()),
+            root_context,
+            None,
+        );
+        if let Some(earliest) = detached.iter().filter_map(|id| self.events.get(id)).map(|e| e.timestamp).min_by(f64::total_cmp) {
+            root.timestamp = earliest;
+        }
+        self.add_event(root);
+
+        for id in &detached {
+            self.relink(id, &root_id);
+        }
+        report.synthetic_root = Some(root_id);
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(context: CorrelationContext) -> Event {
+        Event::new(EventSeverity::Info, "Test".to_string(), "msg".to_string(), context, None)
+    }
+
+    #[test]
+    fn test_repair_reroots_orphans_and_cycles() {
+        let missing_parent = CorrelationContext::new();
+        let orphan = missing_parent.child();
+        let mut a = CorrelationContext::new();
+        let mut b = a.child();
+        a.parent_id = Some(b.span_id.clone());
+        b.parent_id = Some(a.span_id.clone());
+
+        let mut graph = CausalGraph::new();
+        for ctx in [&orphan, &a, &b] {
+            graph.add_event(event(ctx.clone()));
+        }
+
+        let report = graph.integrity_report();
+        assert_eq!(report.orphans, vec![orphan.span_id.clone()]);
+        assert_eq!(report.unknown_parents, vec![missing_parent.span_id.clone()]);
+        assert_eq!(report.cycles.len(), 1);
+
+        let repaired = graph.repair_integrity();
+        let root = repaired.synthetic_root.unwrap();
+        assert_eq!(graph.get_causal_chain(orphan.span_id.clone())[0].context.span_id, root);
+        assert!(graph.integrity_report().is_healthy());
+    }
+}
@@ -1,10 +1,62 @@
 use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use std::fmt;
-use std::sync::{Arc, Mutex};
+use std::collections::{HashMap, VecDeque};
 use uuid::Uuid;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+pub mod bus;
+pub mod callback;
+pub mod columnar;
+pub mod cursor;
+pub mod desync;
+pub mod ecs;
+pub mod errors;
+pub mod escalation;
+pub mod event_log;
+pub mod export;
+pub mod filter;
+pub mod ids;
+pub mod ingest;
+pub mod integrity;
+pub mod msgpack;
+mod persist;
+pub mod published;
+pub mod pyvalue;
+pub mod ratelimit;
+mod query;
+pub mod retention;
+pub mod rng;
+pub mod savegame;
+pub mod scope;
+pub mod simtime;
+pub mod sink;
+pub mod span;
+pub mod summary;
+
+pub use event_log::EventLog;
+pub use ids::{EntityId, FactionId, SystemId, UnitId};
+pub use persist::FORMAT_VERSION as SNAPSHOT_FORMAT_VERSION;
+
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+use errors::EngineError;
+use escalation::{EscalationRule, Escalator};
+use integrity::IntegrityReport;
+use retention::RetentionPolicy;
+use simtime::{SimRange, SimTime};
+use span::Span;
+use summary::TraceSummary;
+
+/// Wall-clock seconds since the Unix epoch.
+pub(crate) fn now_secs() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs_f64()
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[pyclass]
 pub struct CorrelationContext {
@@ -38,7 +90,7 @@ impl CorrelationContext {
     #[staticmethod]
     pub fn from_json(json: &str) -> PyResult<Self> {
         serde_json::from_str(json)
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("JSON error: {}", e)))
+            .map_err(|e| PyErr::from(EngineError::json(e)))
     }
 
     pub fn to_json(&self) -> String {
@@ -46,13 +98,19 @@ impl CorrelationContext {
     }
 }
 
+impl Default for CorrelationContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl fmt::Display for CorrelationContext {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "[Trace: {} | Span: {}]", self.trace_id, self.span_id)
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 #[pyclass(eq, eq_int)]
 #[derive(PartialEq)]
 pub enum EventSeverity {
@@ -78,76 +136,138 @@ pub struct Event {
     pub context: CorrelationContext,
     #[pyo3(get)]
     pub data: Option<String>,
+    /// Structured key-value payload for analytics (ordered by key).
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub fields: Map<String, Value>,
+    /// Simulation-time position (turn/tick/engine time), when the emitter knows it.
+    #[pyo3(get)]
+    #[serde(default, skip_serializing_if = "SimTime::is_unset")]
+    pub sim_time: SimTime,
+}
+
+impl Event {
+    /// Builder-style helper for attaching a structured field.
+    pub fn with_field(mut self, key: &str, value: impl Into<Value>) -> Self {
+        self.fields.insert(key.to_string(), value.into());
+        self
+    }
+
+    pub fn with_fields<I, K>(mut self, fields: I) -> Self
+    where
+        I: IntoIterator<Item = (K, Value)>,
+        K: Into<String>,
+    {
+        self.fields.extend(fields.into_iter().map(|(k, v)| (k.into(), v)));
+        self
+    }
+
+    pub fn field(&self, key: &str) -> Option<&Value> {
+        self.fields.get(key)
+    }
+
+    /// Builder-style helper for stamping the simulation time.
+    pub fn at(mut self, sim_time: SimTime) -> Self {
+        self.sim_time = sim_time;
+        self
+    }
 }
 
 #[pymethods]
 impl Event {
     #[new]
-    pub fn new(
+    #[pyo3(signature = (severity, category, message, context, data=None, fields=None, sim_time=None))]
+    fn py_new(
         severity: EventSeverity,
         category: String,
         message: String,
         context: CorrelationContext,
         data: Option<String>,
-    ) -> Self {
-        let start = SystemTime::now();
-        let since_the_epoch = start
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_default();
-        let timestamp = since_the_epoch.as_secs_f64();
-
-        Self {
-            timestamp,
-            severity,
-            category,
-            message,
-            context,
-            data,
+        fields: Option<&Bound<'_, PyDict>>,
+        sim_time: Option<SimTime>,
+    ) -> PyResult<Self> {
+        let mut event = Self::new(severity, category, message, context, data);
+        if let Some(fields) = fields {
+            event.fields = pyvalue::py_dict_to_map(fields)?;
         }
+        event.sim_time = sim_time.unwrap_or_default();
+        Ok(event)
     }
 
-    fn __repr__(&self) -> String {
-        format!("[{}] {:?} {}: {}", self.timestamp, self.severity, self.category, self.message)
+    pub fn set_sim_time(&mut self, sim_time: SimTime) {
+        self.sim_time = sim_time;
     }
-}
 
-#[pyclass]
-#[derive(Clone)]
-pub struct EventLog {
-   pub events: Arc<Mutex<Vec<Event>>>,
-}
+    /// Structured payload as a Python dict.
+    #[getter(fields)]
+    fn py_fields(&self, py: Python<'_>) -> PyResult<PyObject> {
+        pyvalue::map_to_py(py, &self.fields)
+    }
 
-#[pymethods]
-impl EventLog {
-    #[new]
-    pub fn new() -> Self {
-        Self {
-            events: Arc::new(Mutex::new(Vec::new())),
+    #[pyo3(name = "get_field", signature = (key, default=None))]
+    fn py_get_field(&self, py: Python<'_>, key: &str, default: Option<PyObject>) -> PyResult<PyObject> {
+        match self.fields.get(key) {
+            Some(value) => pyvalue::value_to_py(py, value),
+            None => Ok(default.unwrap_or_else(|| py.None())),
         }
     }
 
-    pub fn add(&self, event: Event) {
-        if let Ok(mut events) = self.events.lock() {
-            events.push(event);
-        }
+    #[pyo3(name = "set_field")]
+    fn py_set_field(&mut self, key: String, value: &Bound<'_, PyAny>) -> PyResult<()> {
+        self.fields.insert(key, pyvalue::py_to_value(value)?);
+        Ok(())
     }
 
-    pub fn get_all(&self) -> Vec<Event> {
-        if let Ok(events) = self.events.lock() {
-            events.clone()
-        } else {
-            Vec::new()
-        }
+    pub fn get_str(&self, key: &str) -> Option<String> {
+        self.fields.get(key).and_then(|v| v.as_str()).map(str::to_string)
     }
-    
-    pub fn clear(&self) {
-        if let Ok(mut events) = self.events.lock() {
-            events.clear();
+
+    pub fn get_int(&self, key: &str) -> Option<i64> {
+        self.fields.get(key).and_then(|v| v.as_i64())
+    }
+
+    pub fn get_float(&self, key: &str) -> Option<f64> {
+        self.fields.get(key).and_then(|v| v.as_f64())
+    }
+
+    pub fn get_bool(&self, key: &str) -> Option<bool> {
+        self.fields.get(key).and_then(|v| v.as_bool())
+    }
+
+    pub fn has_field(&self, key: &str) -> bool {
+        self.fields.contains_key(key)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("[{}] {:?} {}: {}", self.timestamp, self.severity, self.category, self.message)
+    }
+}
+
+impl Event {
+    pub fn new(
+        severity: EventSeverity,
+        category: String,
+        message: String,
+        context: CorrelationContext,
+        data: Option<String>,
+    ) -> Self {
+        Self {
+            timestamp: now_secs(),
+            severity,
+            category,
+            message,
+            context,
+            data,
+            fields: Map::new(),
+            sim_time: SimTime::default(),
         }
     }
 }
 
-use std::collections::{HashMap, VecDeque};
+
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[pyclass]
@@ -163,6 +283,22 @@ pub struct CausalGraph {
     pub parent_map: HashMap<String, String>,
     // Key: parent_span_id -> Vec<child_span_id>
     pub children_map: HashMap<String, Vec<String>>,
+    // Key: span_id of timed spans (recorded via `Span` events)
+    #[serde(default)]
+    pub spans: HashMap<String, Span>,
+    #[serde(default)]
+    pub retention: RetentionPolicy,
+    // Insertion order, used for oldest-first eviction
+    #[serde(default)]
+    pub(crate) order: VecDeque<String>,
+    #[serde(default)]
+    pub(crate) approx_bytes: usize,
+    #[serde(default)]
+    pub(crate) latest_timestamp: f64,
+    #[serde(default)]
+    pub(crate) pruned: u64,
+    #[serde(skip)]
+    pub(crate) escalator: Escalator,
 }
 
 #[pymethods]
@@ -173,29 +309,141 @@ impl CausalGraph {
             events: HashMap::new(),
             parent_map: HashMap::new(),
             children_map: HashMap::new(),
+            spans: HashMap::new(),
+            retention: RetentionPolicy::default(),
+            order: VecDeque::new(),
+            approx_bytes: 0,
+            latest_timestamp: 0.0,
+            pruned: 0,
+            escalator: Escalator::default(),
         }
     }
 
     pub fn add_event(&mut self, event: Event) {
+        let escalations = if self.escalator.is_empty() {
+            Vec::new()
+        } else {
+            self.escalator.observe(&event)
+        };
         let span_id = event.context.span_id.clone();
+
+        if let Some(span) = Span::from_event(&event) {
+            self.spans.insert(span_id.clone(), span);
+        }
         
         // Link parent if exists
         if let Some(parent_id) = &event.context.parent_id {
             self.parent_map.insert(span_id.clone(), parent_id.clone());
             self.children_map.entry(parent_id.clone()).or_default().push(span_id.clone());
         }
-        
-        self.events.insert(span_id, event);
+
+        self.approx_bytes += retention::event_footprint(&event);
+        self.latest_timestamp = self.latest_timestamp.max(event.timestamp);
+        if let Some(previous) = self.events.insert(span_id.clone(), event) {
+            self.approx_bytes = self.approx_bytes.saturating_sub(retention::event_footprint(&previous));
+        } else {
+            self.order.push_back(span_id);
+        }
+
+        if !self.retention.is_unbounded() {
+            self.enforce_retention();
+        }
+        for escalation in escalations {
+            self.add_event(escalation);
+        }
+    }
+
+    /// Evaluates `rule` on every event added from now on; escalations are
+    /// added to the graph as synthetic events.
+    pub fn add_escalation_rule(&mut self, rule: EscalationRule) {
+        self.escalator.add_rule(rule);
+    }
+
+    pub fn clear_escalation_rules(&mut self) {
+        self.escalator.clear();
+    }
+
+    pub fn escalation_rules(&self) -> Vec<EscalationRule> {
+        self.escalator.rules().to_vec()
     }
     
     /// Adds a raw JSON event string (for fast bulk loading from python)
     pub fn add_event_json(&mut self, json_str: &str) -> PyResult<()> {
         let event: Event = serde_json::from_str(json_str)
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("JSON error: {}", e)))?;
+            .map_err(|e| PyErr::from(EngineError::json(e)))?;
         self.add_event(event);
         Ok(())
     }
 
+    /// Adds newline-delimited JSON events in one call. Parsing happens without
+    /// the GIL; nothing is added if any line is malformed. Returns the count.
+    pub fn add_events_jsonl(&mut self, py: Python<'_>, text: &str) -> PyResult<usize> {
+        let events = py
+            .allow_threads(|| ingest::parse_jsonl(text))
+            .map_err(|e| PyErr::from(EngineError::json(e)))?;
+        let count = events.len();
+        self.add_events(events);
+        Ok(count)
+    }
+
+    /// Adds events encoded by `EventLog.to_bincode`. Returns the count.
+    pub fn add_events_bincode(&mut self, py: Python<'_>, data: &[u8]) -> PyResult<usize> {
+        let events = py
+            .allow_threads(|| ingest::decode_bincode(data))
+            .map_err(|e| PyErr::from(EngineError::serialization("Bincode", e)))?;
+        let count = events.len();
+        self.add_events(events);
+        Ok(count)
+    }
+
+    /// Builds a graph by moving every event out of `log`, leaving it empty.
+    #[staticmethod]
+    pub fn from_event_log(log: &EventLog) -> Self {
+        let mut graph = Self::new();
+        graph.add_events(log.take_events());
+        graph
+    }
+
+    /// Reports orphans, unknown parents, stale links and parent cycles.
+    pub fn check_integrity(&self) -> IntegrityReport {
+        self.integrity_report()
+    }
+
+    /// Cleans stale links and re-roots orphans and cycles under one synthetic
+    /// `Integrity` event. Returns the problems found before repairing.
+    pub fn repair(&mut self) -> IntegrityReport {
+        self.repair_integrity()
+    }
+
+    /// Serialises the whole graph (events, links, retention policy) to bytes.
+    pub fn to_bytes<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        let bytes = py.allow_threads(|| self.to_snapshot_bytes()).map_err(snapshot_error)?;
+        Ok(PyBytes::new(py, &bytes))
+    }
+
+    #[staticmethod]
+    pub fn from_bytes(py: Python<'_>, data: &[u8]) -> PyResult<Self> {
+        py.allow_threads(|| Self::from_snapshot_bytes(data)).map_err(snapshot_error)
+    }
+
+    /// Writes `to_bytes()` to `path` so a later session can `load` it.
+    pub fn save(&self, py: Python<'_>, path: &str) -> PyResult<()> {
+        py.allow_threads(|| {
+            let bytes = self.to_snapshot_bytes().map_err(snapshot_error)?;
+            std::fs::write(path, bytes)
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("Save error: {}", e)))
+        })
+    }
+
+    #[staticmethod]
+    pub fn load(py: Python<'_>, path: &str) -> PyResult<Self> {
+        py.allow_threads(|| {
+            let bytes = std::fs::read(path)
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("Load error: {}", e)))?;
+            Self::from_snapshot_bytes(&bytes).map_err(snapshot_error)
+        })
+    }
+
     /// Traces backward from the given event to find the root cause chain.
     /// Returns list of Events [Root ... -> Target].
     pub fn get_causal_chain(&self, span_id: String) -> Vec<Event> {
@@ -243,4 +491,124 @@ impl CausalGraph {
     pub fn size(&self) -> usize {
         self.events.len()
     }
+
+    pub fn get_span(&self, span_id: &str) -> Option<Span> {
+        self.spans.get(span_id).cloned()
+    }
+
+    /// All timed spans, ordered by start time.
+    pub fn get_spans(&self) -> Vec<Span> {
+        let mut spans: Vec<Span> = self.spans.values().cloned().collect();
+        spans.sort_by(|a, b| a.start.total_cmp(&b.start));
+        spans
+    }
+
+    /// Deepest event both spans descend from (either span itself counts).
+    /// Returns None when they share no root cause still in the graph.
+    pub fn lowest_common_ancestor(&self, span_a: &str, span_b: &str) -> Option<Event> {
+        self.common_ancestor_id(span_a, span_b)
+            .and_then(|id| self.events.get(id))
+            .cloned()
+    }
+
+    /// Events from `span_a` up to their common ancestor and down to `span_b`.
+    /// Empty when the spans are unrelated.
+    pub fn path_between(&self, span_a: &str, span_b: &str) -> Vec<Event> {
+        self.path_ids(span_a, span_b)
+            .into_iter()
+            .filter_map(|id| self.events.get(id).cloned())
+            .collect()
+    }
+
+    /// Events within the inclusive turn/tick bounds, in simulation order.
+    /// Bounding an axis excludes events not stamped on it.
+    #[pyo3(signature = (turn_min=None, turn_max=None, tick_min=None, tick_max=None))]
+    pub fn get_by_sim_time(
+        &self,
+        turn_min: Option<u64>,
+        turn_max: Option<u64>,
+        tick_min: Option<u64>,
+        tick_max: Option<u64>,
+    ) -> Vec<Event> {
+        let range = SimRange { turn_min, turn_max, tick_min, tick_max };
+        let mut events: Vec<Event> = self.events.values().filter(|e| range.matches(e)).cloned().collect();
+        simtime::sort_by_sim_time(&mut events);
+        events
+    }
+
+    /// Counts by severity/category, time span, deepest chain and the spans
+    /// that emitted the most Warning-or-worse events, for one trace.
+    #[pyo3(signature = (trace_id, top_n=5))]
+    pub fn summarize_trace(&self, trace_id: &str, top_n: usize) -> TraceSummary {
+        self.trace_summary(trace_id, top_n)
+    }
+
+    /// Replaces the retention policy and applies it immediately.
+    /// Returns the number of events removed.
+    pub fn set_retention(&mut self, policy: RetentionPolicy) -> usize {
+        self.retention = policy;
+        self.apply_retention()
+    }
+
+    #[getter]
+    pub fn retention(&self) -> RetentionPolicy {
+        self.retention.clone()
+    }
+
+    /// Applies the retention policy now. Returns the number of events removed.
+    pub fn prune(&mut self) -> usize {
+        self.apply_retention()
+    }
+
+    /// Removes all events timestamped before `cutoff`, regardless of policy.
+    pub fn prune_before(&mut self, cutoff: f64) -> usize {
+        self.remove_older_than(cutoff)
+    }
+
+    /// Estimated bytes held by the graph's events and indexes.
+    pub fn memory_usage(&self) -> usize {
+        self.approx_bytes
+    }
+
+    /// Total events evicted since creation.
+    pub fn pruned_count(&self) -> u64 {
+        self.pruned
+    }
+
+    /// Graphviz DOT rendering of `root_span` and everything downstream of it.
+    pub fn export_dot(&self, root_span: &str) -> PyResult<String> {
+        export::to_dot(self, root_span).ok_or_else(|| unknown_span(root_span))
+    }
+
+    /// Every event and span in Chrome trace-event JSON, for Perfetto.
+    pub fn to_chrome_trace(&self) -> String {
+        export::to_chrome_trace(self.events.values()).to_string()
+    }
+
+    /// Writes `to_chrome_trace()` to `path`.
+    pub fn write_chrome_trace(&self, path: &str) -> PyResult<()> {
+        std::fs::write(path, self.to_chrome_trace())
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("Export error: {}", e)))
+    }
+
+    /// Nested JSON tree of `root_span` and everything downstream of it.
+    pub fn export_json_tree(&self, root_span: &str) -> PyResult<String> {
+        let tree = export::to_json_tree(self, root_span).ok_or_else(|| unknown_span(root_span))?;
+        serde_json::to_string(&tree)
+            .map_err(|e| PyErr::from(EngineError::json(e)))
+    }
+}
+
+fn snapshot_error(message: String) -> PyErr {
+    EngineError::serialization("Snapshot", message).into()
+}
+
+fn unknown_span(span_id: &str) -> PyErr {
+    PyErr::new::<pyo3::exceptions::PyKeyError, _>(format!("Unknown span: {}", span_id))
+}
+
+impl Default for CausalGraph {
+    fn default() -> Self {
+        Self::new()
+    }
 }
@@ -0,0 +1,277 @@
+//! Minimal MessagePack codec for the FFI boundary. Payloads go through
+//! `serde_json::Value`, so anything that round-trips through JSON works here;
+//! Python reads and writes them with the standard `msgpack` package.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Number, Value};
+
+pub fn to_msgpack<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, String> {
+    let value = serde_json::to_value(value).map_err(|e| e.to_string())?;
+    let mut out = Vec::new();
+    encode(&value, &mut out);
+    Ok(out)
+}
+
+pub fn from_msgpack<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, String> {
+    serde_json::from_value(decode(bytes)?).map_err(|e| e.to_string())
+}
+
+pub fn encode(value: &Value, out: &mut Vec<u8>) {
+    match value {
+        Value::Null => out.push(0xc0),
+        Value::Bool(b) => out.push(if *b { 0xc3 } else { 0xc2 }),
+        Value::Number(n) => {
+            if let Some(u) = n.as_u64() {
+                encode_uint(u, out);
+            } else if let Some(i) = n.as_i64() {
+                encode_int(i, out);
+            } else {
+                out.push(0xcb);
+                out.extend_from_slice(&n.as_f64().unwrap_or(0.0).to_be_bytes());
+            }
+        }
+        Value::String(s) => encode_str(s, out),
+        Value::Array(items) => {
+            encode_len(items.len(), [0x90, 0xdc, 0xdd], 16, out);
+            for item in items {
+                encode(item, out);
+            }
+        }
+        Value::Object(map) => {
+            encode_len(map.len(), [0x80, 0xde, 0xdf], 16, out);
+            for (key, item) in map {
+                encode_str(key, out);
+                encode(item, out);
+            }
+        }
+    }
+}
+
+fn encode_uint(u: u64, out: &mut Vec<u8>) {
+    if u < 0x80 {
+        out.push(u as u8);
+    } else if u <= u8::MAX as u64 {
+        out.extend_from_slice(&[0xcc, u as u8]);
+    } else if u <= u16::MAX as u64 {
+        out.push(0xcd);
+        out.extend_from_slice(&(u as u16).to_be_bytes());
+    } else if u <= u32::MAX as u64 {
+        out.push(0xce);
+        out.extend_from_slice(&(u as u32).to_be_bytes());
+    } else {
+        out.push(0xcf);
+        out.extend_from_slice(&u.to_be_bytes());
+    }
+}
+
+/// Only called for negative values; non-negative ones go through `encode_uint`.
+fn encode_int(i: i64, out: &mut Vec<u8>) {
+    if i >= -32 {
+        out.push(i as u8);
+    } else if i >= i8::MIN as i64 {
+        out.extend_from_slice(&[0xd0, i as u8]);
+    } else if i >= i16::MIN as i64 {
+        out.push(0xd1);
+        out.extend_from_slice(&(i as i16).to_be_bytes());
+    } else if i >= i32::MIN as i64 {
+        out.push(0xd2);
+        out.extend_from_slice(&(i as i32).to_be_bytes());
+    } else {
+        out.push(0xd3);
+        out.extend_from_slice(&i.to_be_bytes());
+    }
+}
+
+fn encode_str(s: &str, out: &mut Vec<u8>) {
+    let len = s.len();
+    if len < 32 {
+        out.push(0xa0 | len as u8);
+    } else if len <= u8::MAX as usize {
+        out.extend_from_slice(&[0xd9, len as u8]);
+    } else {
+        encode_len(len, [0, 0xda, 0xdb], 0, out);
+    }
+    out.extend_from_slice(s.as_bytes());
+}
+
+/// Writes a fix/16/32-bit length header; `fix_limit` is the fixed-form bound (0 for none).
+fn encode_len(len: usize, [fix, m16, m32]: [u8; 3], fix_limit: usize, out: &mut Vec<u8>) {
+    if len < fix_limit {
+        out.push(fix | len as u8);
+    } else if len <= u16::MAX as usize {
+        out.push(m16);
+        out.extend_from_slice(&(len as u16).to_be_bytes());
+    } else {
+        out.push(m32);
+        out.extend_from_slice(&(len as u32).to_be_bytes());
+    }
+}
+
+/// Decodes a single MessagePack value; trailing bytes are an error. Binary
+/// blobs become arrays of byte values; extension types are rejected.
+pub fn decode(bytes: &[u8]) -> Result<Value, String> {
+    let mut reader = Reader { bytes, pos: 0 };
+    let value = reader.value()?;
+    if reader.pos != bytes.len() {
+        return Err(format!("{} trailing bytes after msgpack value", bytes.len() - reader.pos));
+    }
+    Ok(value)
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], String> {
+        let end = self.pos.checked_add(n).filter(|&end| end <= self.bytes.len());
+        let end = end.ok_or_else(|| format!("truncated msgpack at byte {}", self.pos))?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        let mut buf = [0u8; N];
+        buf.copy_from_slice(self.take(N)?);
+        Ok(buf)
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn len16(&mut self) -> Result<usize, String> {
+        Ok(u16::from_be_bytes(self.array()?) as usize)
+    }
+
+    fn len32(&mut self) -> Result<usize, String> {
+        Ok(u32::from_be_bytes(self.array()?) as usize)
+    }
+
+    fn value(&mut self) -> Result<Value, String> {
+        let marker = self.u8()?;
+        Ok(match marker {
+            0x00..=0x7f => Value::from(marker),
+            0x80..=0x8f => self.map((marker & 0x0f) as usize)?,
+            0x90..=0x9f => self.seq((marker & 0x0f) as usize)?,
+            0xa0..=0xbf => self.string((marker & 0x1f) as usize)?,
+            0xc0 => Value::Null,
+            0xc2 => Value::Bool(false),
+            0xc3 => Value::Bool(true),
+            0xc4 => {
+                let len = self.u8()? as usize;
+                self.binary(len)?
+            }
+            0xc5 => {
+                let len = self.len16()?;
+                self.binary(len)?
+            }
+            0xc6 => {
+                let len = self.len32()?;
+                self.binary(len)?
+            }
+            0xca => float(f32::from_be_bytes(self.array()?) as f64),
+            0xcb => float(f64::from_be_bytes(self.array()?)),
+            0xcc => Value::from(self.u8()?),
+            0xcd => Value::from(u16::from_be_bytes(self.array()?)),
+            0xce => Value::from(u32::from_be_bytes(self.array()?)),
+            0xcf => Value::from(u64::from_be_bytes(self.array()?)),
+            0xd0 => Value::from(self.u8()? as i8),
+            0xd1 => Value::from(i16::from_be_bytes(self.array()?)),
+            0xd2 => Value::from(i32::from_be_bytes(self.array()?)),
+            0xd3 => Value::from(i64::from_be_bytes(self.array()?)),
+            0xd9 => {
+                let len = self.u8()? as usize;
+                self.string(len)?
+            }
+            0xda => {
+                let len = self.len16()?;
+                self.string(len)?
+            }
+            0xdb => {
+                let len = self.len32()?;
+                self.string(len)?
+            }
+            0xdc => {
+                let len = self.len16()?;
+                self.seq(len)?
+            }
+            0xdd => {
+                let len = self.len32()?;
+                self.seq(len)?
+            }
+            0xde => {
+                let len = self.len16()?;
+                self.map(len)?
+            }
+            0xdf => {
+                let len = self.len32()?;
+                self.map(len)?
+            }
+            0xe0..=0xff => Value::from(marker as i8),
+            other => return Err(format!("unsupported msgpack type 0x{:02x} at byte {}", other, self.pos - 1)),
+        })
+    }
+
+    fn string(&mut self, len: usize) -> Result<Value, String> {
+        let raw = self.take(len)?;
+        std::str::from_utf8(raw).map(|s| Value::String(s.to_string())).map_err(|e| e.to_string())
+    }
+
+    fn binary(&mut self, len: usize) -> Result<Value, String> {
+        Ok(Value::Array(self.take(len)?.iter().map(|&b| Value::from(b)).collect()))
+    }
+
+    fn seq(&mut self, len: usize) -> Result<Value, String> {
+        // Every element takes at least one byte, which bounds the preallocation
+        let mut items = Vec::with_capacity(len.min(self.bytes.len() - self.pos));
+        for _ in 0..len {
+            items.push(self.value()?);
+        }
+        Ok(Value::Array(items))
+    }
+
+    fn map(&mut self, len: usize) -> Result<Value, String> {
+        let mut map = Map::new();
+        for _ in 0..len {
+            let key = match self.value()? {
+                Value::String(s) => s,
+                Value::Number(n) => n.to_string(),
+                other => return Err(format!("unsupported msgpack map key {}", other)),
+            };
+            map.insert(key, self.value()?);
+        }
+        Ok(Value::Object(map))
+    }
+}
+
+fn float(f: f64) -> Value {
+    Number::from_f64(f).map(Value::Number).unwrap_or(Value::Null)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_round_trip_covers_every_width() {
+        let value = json!({
+            "ints": [0, 127, 128, 300, 70000, 5_000_000_000u64, -1, -33, -200, -40000, -3_000_000_000i64],
+            "floats": [0.5, -1e300],
+            "text": ["", "short", "x".repeat(40), "y".repeat(300)],
+            "nested": {"flag": true, "none": null, "list": (0..20).collect::<Vec<_>>()},
+        });
+        let bytes = to_msgpack(&value).unwrap();
+        assert_eq!(from_msgpack::<Value>(&bytes).unwrap(), value);
+
+        // Spec examples: fixmap {"a": 1} and negative fixint
+        assert_eq!(decode(&[0x81, 0xa1, b'a', 0x01]).unwrap(), json!({"a": 1}));
+        assert_eq!(decode(&[0xff]).unwrap(), json!(-1));
+        assert!(decode(&[0x92, 0x01]).is_err());
+        assert!(decode(&[0x01, 0x02]).is_err());
+    }
+}
@@ -0,0 +1,107 @@
+use crate::ingest::WireEvent;
+use crate::retention::{self, RetentionPolicy};
+use crate::span::Span;
+use crate::{CausalGraph, Event};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+const MAGIC: &[u8; 4] = b"VRCG";
+pub const FORMAT_VERSION: u32 = 2;
+
+/// On-disk form of a `CausalGraph`. Events are stored in insertion order so
+/// retention keeps evicting oldest-first after a reload; spans and the memory
+/// estimate are rebuilt from the events.
+#[derive(Serialize, Deserialize)]
+struct GraphSnapshot {
+    events: Vec<WireEvent>,
+    parent_map: HashMap<String, String>,
+    children_map: HashMap<String, Vec<String>>,
+    retention: RetentionPolicy,
+    latest_timestamp: f64,
+    pruned: u64,
+}
+
+impl CausalGraph {
+    /// Encodes the graph as `VRCG` + format version + bincode snapshot.
+    pub fn to_snapshot_bytes(&self) -> Result<Vec<u8>, String> {
+        let events = self
+            .order
+            .iter()
+            .filter_map(|id| self.events.get(id))
+            .map(WireEvent::from)
+            .collect();
+        let snapshot = GraphSnapshot {
+            events,
+            parent_map: self.parent_map.clone(),
+            children_map: self.children_map.clone(),
+            retention: self.retention.clone(),
+            latest_timestamp: self.latest_timestamp,
+            pruned: self.pruned,
+        };
+
+        let mut bytes = Vec::with_capacity(8 + self.approx_bytes);
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        bincode::serialize_into(&mut bytes, &snapshot).map_err(|e| e.to_string())?;
+        Ok(bytes)
+    }
+
+    pub fn from_snapshot_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let (header, body) = bytes.split_at_checked(8).ok_or("truncated causal graph snapshot")?;
+        if &header[..4] != MAGIC {
+            return Err("not a causal graph snapshot".to_string());
+        }
+        let version = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+        if version != FORMAT_VERSION {
+            return Err(format!("unsupported snapshot version {} (expected {})", version, FORMAT_VERSION));
+        }
+
+        let snapshot: GraphSnapshot = bincode::deserialize(body).map_err(|e| e.to_string())?;
+        let mut graph = CausalGraph::new();
+        graph.events.reserve(snapshot.events.len());
+        for wire in snapshot.events {
+            let event = Event::try_from(wire)?;
+            let span_id = event.context.span_id.clone();
+            if let Some(span) = Span::from_event(&event) {
+                graph.spans.insert(span_id.clone(), span);
+            }
+            graph.approx_bytes += retention::event_footprint(&event);
+            graph.order.push_back(span_id.clone());
+            graph.events.insert(span_id, event);
+        }
+        graph.parent_map = snapshot.parent_map;
+        graph.children_map = snapshot.children_map;
+        graph.retention = snapshot.retention;
+        graph.latest_timestamp = snapshot.latest_timestamp;
+        graph.pruned = snapshot.pruned;
+        Ok(graph)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CorrelationContext, EventSeverity};
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let root = CorrelationContext::new();
+        let child = root.child();
+        let mut graph = CausalGraph::new();
+        graph.add_event(Event::new(EventSeverity::Error, "Economy".to_string(), "Insolvent".to_string(), root.clone(), None));
+        graph.add_event(
+            Event::new(EventSeverity::Warning, "Fleet".to_string(), "Disbanded".to_string(), child.clone(), None)
+                .with_field("fleet_id", "f-3"),
+        );
+
+        let bytes = graph.to_snapshot_bytes().unwrap();
+        let restored = CausalGraph::from_snapshot_bytes(&bytes).unwrap();
+        assert_eq!(restored.size(), 2);
+        assert_eq!(restored.memory_usage(), graph.memory_usage());
+        let chain = restored.get_causal_chain(child.span_id.clone());
+        assert_eq!(chain.len(), 2);
+        assert_eq!(chain[1].get_str("fleet_id").as_deref(), Some("f-3"));
+
+        assert!(CausalGraph::from_snapshot_bytes(b"nope").is_err());
+    }
+}
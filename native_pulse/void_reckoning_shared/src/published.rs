@@ -0,0 +1,73 @@
+//! Copy-on-write publication of engine state. The thread that mutates an
+//! engine publishes an immutable copy whenever the state is whole (after a
+//! turn, after a battle step); readers on other threads take the latest
+//! copy without waiting on the engine's lock and keep it as long as they
+//! like. Publishing swaps a pointer, so a reader holds either the old copy
+//! or the new one, never something half-applied.
+
+use std::sync::{Arc, RwLock};
+
+struct Slot<T> {
+    version: u64,
+    value: Option<Arc<T>>,
+}
+
+/// The latest published copy of some state, and how many have been
+/// published. Share it behind an `Arc` between writer and readers.
+pub struct Published<T> {
+    slot: RwLock<Slot<T>>,
+}
+
+impl<T> Default for Published<T> {
+    fn default() -> Self {
+        Self { slot: RwLock::new(Slot { version: 0, value: None }) }
+    }
+}
+
+impl<T> Published<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Makes `value` the copy readers see and returns its version, one
+    /// more than the last. Copies readers already hold are unaffected.
+    pub fn publish(&self, value: T) -> u64 {
+        let value = Arc::new(value);
+        let mut slot = self.slot.write().unwrap_or_else(|e| e.into_inner());
+        slot.version += 1;
+        slot.value = Some(value);
+        slot.version
+    }
+
+    /// The latest copy, None before the first `publish`.
+    pub fn load(&self) -> Option<Arc<T>> {
+        self.slot.read().unwrap_or_else(|e| e.into_inner()).value.clone()
+    }
+
+    /// The latest copy with its version.
+    pub fn load_versioned(&self) -> Option<(u64, Arc<T>)> {
+        let slot = self.slot.read().unwrap_or_else(|e| e.into_inner());
+        slot.value.clone().map(|value| (slot.version, value))
+    }
+
+    /// Copies published so far; 0 before the first.
+    pub fn version(&self) -> u64 {
+        self.slot.read().unwrap_or_else(|e| e.into_inner()).version
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn readers_keep_the_copy_they_took() {
+        let published = Published::new();
+        assert!(published.load().is_none());
+        assert_eq!(published.publish(vec![1, 2]), 1);
+        let held = published.load().unwrap();
+        assert_eq!(published.publish(vec![3]), 2);
+        assert_eq!(*held, vec![1, 2]);
+        assert_eq!(published.load_versioned().map(|(v, value)| (v, (*value).clone())), Some((2, vec![3])));
+    }
+}
@@ -0,0 +1,78 @@
+use pyo3::exceptions::PyTypeError;
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDict, PyFloat, PyInt, PyList, PyString, PyTuple};
+use pyo3::IntoPyObjectExt;
+use serde_json::{Map, Number, Value};
+
+/// Converts a JSON value into the equivalent native Python object.
+pub fn value_to_py(py: Python<'_>, value: &Value) -> PyResult<PyObject> {
+    match value {
+        Value::Null => Ok(py.None()),
+        Value::Bool(b) => b.into_py_any(py),
+        Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                i.into_py_any(py)
+            } else if let Some(u) = n.as_u64() {
+                u.into_py_any(py)
+            } else {
+                n.as_f64().unwrap_or(f64::NAN).into_py_any(py)
+            }
+        }
+        Value::String(s) => s.into_py_any(py),
+        Value::Array(items) => {
+            let list = PyList::empty(py);
+            for item in items {
+                list.append(value_to_py(py, item)?)?;
+            }
+            list.into_py_any(py)
+        }
+        Value::Object(map) => map_to_py(py, map),
+    }
+}
+
+pub fn map_to_py(py: Python<'_>, map: &Map<String, Value>) -> PyResult<PyObject> {
+    let dict = PyDict::new(py);
+    for (key, value) in map {
+        dict.set_item(key, value_to_py(py, value)?)?;
+    }
+    dict.into_py_any(py)
+}
+
+/// Converts a Python object made of None/bool/int/float/str/list/tuple/dict
+/// into a JSON value. Anything else is rejected with a TypeError.
+pub fn py_to_value(obj: &Bound<'_, PyAny>) -> PyResult<Value> {
+    if obj.is_none() {
+        Ok(Value::Null)
+    } else if obj.is_instance_of::<PyBool>() {
+        Ok(Value::Bool(obj.extract()?))
+    } else if obj.is_instance_of::<PyInt>() {
+        match obj.extract::<i64>() {
+            Ok(i) => Ok(Value::from(i)),
+            Err(_) => Ok(Value::from(obj.extract::<u64>()?)),
+        }
+    } else if obj.is_instance_of::<PyFloat>() {
+        Ok(Number::from_f64(obj.extract()?).map(Value::Number).unwrap_or(Value::Null))
+    } else if obj.is_instance_of::<PyString>() {
+        Ok(Value::String(obj.extract()?))
+    } else if let Ok(dict) = obj.downcast::<PyDict>() {
+        py_dict_to_map(dict).map(Value::Object)
+    } else if obj.is_instance_of::<PyList>() || obj.is_instance_of::<PyTuple>() {
+        obj.try_iter()?
+            .map(|item| py_to_value(&item?))
+            .collect::<PyResult<Vec<Value>>>()
+            .map(Value::Array)
+    } else {
+        Err(PyTypeError::new_err(format!(
+            "Cannot convert {} to a JSON value",
+            obj.get_type().name()?
+        )))
+    }
+}
+
+pub fn py_dict_to_map(dict: &Bound<'_, PyDict>) -> PyResult<Map<String, Value>> {
+    let mut map = Map::new();
+    for (key, value) in dict.iter() {
+        map.insert(key.extract::<String>()?, py_to_value(&value)?);
+    }
+    Ok(map)
+}
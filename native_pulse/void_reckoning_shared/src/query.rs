@@ -0,0 +1,66 @@
+use crate::CausalGraph;
+use std::collections::HashSet;
+
+impl CausalGraph {
+    /// `span_id` followed by its ancestors, nearest first, stopping at the
+    /// first event that is not in the graph. Cycles are cut.
+    pub(crate) fn ancestry<'a>(&'a self, span_id: &'a str) -> Vec<&'a str> {
+        let mut lineage = Vec::new();
+        let mut seen = HashSet::new();
+        let mut current = Some(span_id);
+        while let Some(id) = current {
+            if !self.events.contains_key(id) || !seen.insert(id) {
+                break;
+            }
+            lineage.push(id);
+            current = self.parent_map.get(id).map(String::as_str);
+        }
+        lineage
+    }
+
+    /// Deepest event that is an ancestor of (or equal to) both spans.
+    pub(crate) fn common_ancestor_id<'a>(&'a self, span_a: &'a str, span_b: &'a str) -> Option<&'a str> {
+        let lineage_a: HashSet<&str> = self.ancestry(span_a).into_iter().collect();
+        self.ancestry(span_b).into_iter().find(|id| lineage_a.contains(id))
+    }
+
+    /// Span ids from `span_a` up to the common ancestor and back down to
+    /// `span_b`, both ends inclusive. Empty if the spans share no ancestor.
+    pub(crate) fn path_ids<'a>(&'a self, span_a: &'a str, span_b: &'a str) -> Vec<&'a str> {
+        let Some(common) = self.common_ancestor_id(span_a, span_b) else {
+            return Vec::new();
+        };
+        let mut path: Vec<&str> = self.ancestry(span_a).into_iter().take_while(|id| *id != common).collect();
+        path.push(common);
+        let down: Vec<&str> = self.ancestry(span_b).into_iter().take_while(|id| *id != common).collect();
+        path.extend(down.into_iter().rev());
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CausalGraph, CorrelationContext, Event, EventSeverity};
+
+    #[test]
+    fn test_common_ancestor_and_path() {
+        let root = CorrelationContext::new();
+        let left = root.child();
+        let left_leaf = left.child();
+        let right = root.child();
+        let unrelated = CorrelationContext::new();
+
+        let mut graph = CausalGraph::new();
+        for ctx in [&root, &left, &left_leaf, &right, &unrelated] {
+            graph.add_event(Event::new(EventSeverity::Info, "Test".to_string(), "msg".to_string(), ctx.clone(), None));
+        }
+
+        assert_eq!(graph.common_ancestor_id(&left_leaf.span_id, &right.span_id), Some(root.span_id.as_str()));
+        assert_eq!(graph.common_ancestor_id(&left_leaf.span_id, &left.span_id), Some(left.span_id.as_str()));
+        assert_eq!(
+            graph.path_ids(&left_leaf.span_id, &right.span_id),
+            vec![left_leaf.span_id.as_str(), left.span_id.as_str(), root.span_id.as_str(), right.span_id.as_str()]
+        );
+        assert!(graph.path_ids(&left.span_id, &unrelated.span_id).is_empty());
+    }
+}
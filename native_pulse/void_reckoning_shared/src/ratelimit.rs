@@ -0,0 +1,216 @@
+use crate::{CorrelationContext, Event, EventSeverity};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use uuid::Uuid;
+
+/// Category-preserving roll-up events carry this field so consumers can tell
+/// them apart from the events they summarise.
+pub const SUPPRESSED_FIELD: &str = "suppressed";
+
+/// Number of tracked keys above which expired windows are swept.
+const SWEEP_THRESHOLD: usize = 1024;
+
+/// Replaces digit runs with `#` so "Faction 3 deficit: -1200" and
+/// "Faction 3 deficit: -950" share one template.
+pub fn message_template(message: &str) -> String {
+    let mut template = String::with_capacity(message.len());
+    let mut in_number = false;
+    for c in message.chars() {
+        if c.is_ascii_digit() || (in_number && c == '.') {
+            if !in_number {
+                template.push('#');
+                in_number = true;
+            }
+        } else {
+            in_number = false;
+            template.push(c);
+        }
+    }
+    template
+}
+
+#[derive(Debug, Clone, Copy)]
+struct RateLimitConfig {
+    max_events: u32,
+    window_secs: f64,
+}
+
+struct Window {
+    start: f64,
+    admitted: u32,
+    suppressed: u64,
+    /// First suppressed event of the window; the roll-up is modelled on it.
+    exemplar: Option<Event>,
+}
+
+impl Window {
+    fn rollup(&mut self, template: &str, window_secs: f64) -> Option<Event> {
+        let exemplar = self.exemplar.take()?;
+        let suppressed = std::mem::take(&mut self.suppressed);
+        // Sibling of the suppressed events so it hangs under the same cause
+        let context = CorrelationContext {
+            trace_id: exemplar.context.trace_id.clone(),
+            span_id: Uuid::new_v4().to_string(),
+            parent_id: exemplar.context.parent_id.clone(),
+        };
+        let mut rollup = Event::new(
+            exemplar.severity,
+            exemplar.category.clone(),
+            format!("Suppressed {} duplicates of '{}'", suppressed, template),
+            context,
+            None,
+        )
+        .with_field(SUPPRESSED_FIELD, suppressed)
+        .with_field("template", template)
+        .with_field("window_secs", window_secs)
+        .at(exemplar.sim_time);
+        rollup.timestamp = exemplar.timestamp;
+        Some(rollup)
+    }
+}
+
+/// Per-(category, message template) limiter: within each window at most
+/// `max_events` matching events pass; the rest are counted and reported by
+/// a single "Suppressed N duplicates" event when the window closes.
+/// Critical events are never suppressed. Windows use event timestamps.
+#[derive(Default)]
+pub struct RateLimiter {
+    enabled: AtomicBool,
+    suppressed_total: AtomicU64,
+    inner: Mutex<LimiterState>,
+}
+
+#[derive(Default)]
+struct LimiterState {
+    config: Option<RateLimitConfig>,
+    windows: HashMap<(String, String), Window>,
+}
+
+impl RateLimiter {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    pub fn suppressed_count(&self) -> u64 {
+        self.suppressed_total.load(Ordering::Relaxed)
+    }
+
+    /// Enables limiting, or disables it with `None`. Returns roll-ups for any
+    /// duplicates suppressed under the previous settings.
+    pub fn configure(&self, limit: Option<(u32, f64)>) -> Vec<Event> {
+        let Ok(mut state) = self.inner.lock() else {
+            return Vec::new();
+        };
+        let rollups = state.drain_rollups();
+        state.config = limit.map(|(max_events, window_secs)| RateLimitConfig { max_events, window_secs });
+        self.enabled.store(state.config.is_some(), Ordering::Relaxed);
+        rollups
+    }
+
+    /// Roll-ups for every window with pending suppressions.
+    pub fn flush(&self) -> Vec<Event> {
+        self.inner.lock().map(|mut state| state.drain_rollups()).unwrap_or_default()
+    }
+
+    /// Decides whether `event` passes. Any roll-up returned closes the previous
+    /// window for the same key and should be recorded before `event`.
+    pub fn check(&self, event: &Event) -> (bool, Vec<Event>) {
+        if event.severity == EventSeverity::Critical {
+            return (true, Vec::new());
+        }
+        let Ok(mut state) = self.inner.lock() else {
+            return (true, Vec::new());
+        };
+        let Some(config) = state.config else {
+            return (true, Vec::new());
+        };
+
+        let mut rollups = Vec::new();
+        if state.windows.len() > SWEEP_THRESHOLD {
+            rollups.extend(state.sweep(event.timestamp, config));
+        }
+
+        let template = message_template(&event.message);
+        let window = state
+            .windows
+            .entry((event.category.clone(), template.clone()))
+            .or_insert_with(|| Window { start: event.timestamp, admitted: 0, suppressed: 0, exemplar: None });
+
+        if event.timestamp - window.start >= config.window_secs {
+            rollups.extend(window.rollup(&template, config.window_secs));
+            window.start = event.timestamp;
+            window.admitted = 0;
+        }
+
+        if window.admitted < config.max_events {
+            window.admitted += 1;
+            return (true, rollups);
+        }
+
+        window.suppressed += 1;
+        window.exemplar.get_or_insert_with(|| event.clone());
+        self.suppressed_total.fetch_add(1, Ordering::Relaxed);
+        (false, rollups)
+    }
+}
+
+impl LimiterState {
+    fn drain_rollups(&mut self) -> Vec<Event> {
+        let window_secs = self.config.map_or(0.0, |c| c.window_secs);
+        let rollups = self
+            .windows
+            .iter_mut()
+            .filter_map(|((_, template), window)| window.rollup(template, window_secs))
+            .collect();
+        self.windows.clear();
+        rollups
+    }
+
+    /// Drops windows that have expired, rolling up what they suppressed.
+    fn sweep(&mut self, now: f64, config: RateLimitConfig) -> Vec<Event> {
+        let mut rollups = Vec::new();
+        self.windows.retain(|(_, template), window| {
+            if now - window.start < config.window_secs {
+                return true;
+            }
+            rollups.extend(window.rollup(template, config.window_secs));
+            false
+        });
+        rollups
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn insolvency(faction: u32, deficit: i64, timestamp: f64) -> Event {
+        let mut event = Event::new(
+            EventSeverity::Warning,
+            "Economy".to_string(),
+            format!("Faction {} is insolvent! Deficit: {}", faction, deficit),
+            CorrelationContext::new(),
+            None,
+        );
+        event.timestamp = timestamp;
+        event
+    }
+
+    #[test]
+    fn test_duplicates_roll_up_when_window_closes() {
+        assert_eq!(message_template("Deficit: -12.5 on turn 40"), "Deficit: -# on turn #");
+
+        let limiter = RateLimiter::default();
+        limiter.configure(Some((2, 10.0)));
+        let admitted = (0..5).filter(|i| limiter.check(&insolvency(1, -100 - i, *i as f64)).0).count();
+        assert_eq!(admitted, 2);
+        assert_eq!(limiter.suppressed_count(), 3);
+
+        let (admit, rollups) = limiter.check(&insolvency(1, -1, 20.0));
+        assert!(admit);
+        assert_eq!(rollups.len(), 1);
+        assert_eq!(rollups[0].get_int(SUPPRESSED_FIELD), Some(3));
+        assert!(limiter.flush().is_empty());
+    }
+}
@@ -0,0 +1,205 @@
+use crate::{CausalGraph, Event, EventSeverity};
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::mem::size_of;
+
+/// Limits applied to a `CausalGraph` whenever it grows and on `prune()`.
+/// Eviction is oldest-first by insertion order.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[pyclass]
+pub struct RetentionPolicy {
+    /// Keep at most this many events.
+    #[pyo3(get, set)]
+    pub max_events: Option<usize>,
+    /// Drop events older than this, measured against the newest event seen
+    /// (not wall-clock, so replayed runs prune the same way).
+    #[pyo3(get, set)]
+    pub max_age_secs: Option<f64>,
+    /// Keep the estimated footprint below this many bytes.
+    #[pyo3(get, set)]
+    pub max_bytes: Option<usize>,
+    /// Spare Warning-and-above events and their ancestors while anything else
+    /// can still be evicted, so the causes of problems outlive routine noise.
+    #[pyo3(get, set)]
+    pub keep_significant: bool,
+}
+
+#[pymethods]
+impl RetentionPolicy {
+    #[new]
+    #[pyo3(signature = (max_events=None, max_age_secs=None, max_bytes=None, keep_significant=false))]
+    pub fn new(max_events: Option<usize>, max_age_secs: Option<f64>, max_bytes: Option<usize>, keep_significant: bool) -> Self {
+        Self { max_events, max_age_secs, max_bytes, keep_significant }
+    }
+
+    pub fn is_unbounded(&self) -> bool {
+        self.max_events.is_none() && self.max_age_secs.is_none() && self.max_bytes.is_none()
+    }
+}
+
+/// Rough heap + inline footprint of one stored event, including its map entries.
+pub(crate) fn event_footprint(event: &Event) -> usize {
+    let context = &event.context;
+    let ids = context.trace_id.len() + context.span_id.len() + context.parent_id.as_ref().map_or(0, |p| p.len());
+    // span_id is duplicated as a key in `events`, `parent_map`/`children_map` and `order`
+    let index = 3 * (context.span_id.len() + size_of::<String>());
+    size_of::<Event>()
+        + ids
+        + index
+        + event.category.len()
+        + event.message.len()
+        + event.data.as_ref().map_or(0, |d| d.len())
+        + event.fields.iter().map(|(k, v)| k.len() + value_footprint(v)).sum::<usize>()
+}
+
+fn value_footprint(value: &Value) -> usize {
+    size_of::<Value>()
+        + match value {
+            Value::String(s) => s.len(),
+            Value::Array(items) => items.iter().map(value_footprint).sum(),
+            Value::Object(map) => map.iter().map(|(k, v)| k.len() + value_footprint(v)).sum(),
+            _ => 0,
+        }
+}
+
+impl CausalGraph {
+    /// Removes one event and unlinks it from its parent. Its children keep
+    /// their `parent_map` entry, so chains simply stop at the evicted event.
+    pub(crate) fn remove_event(&mut self, span_id: &str) -> Option<Event> {
+        let event = self.events.remove(span_id)?;
+        self.approx_bytes = self.approx_bytes.saturating_sub(event_footprint(&event));
+        self.spans.remove(span_id);
+        self.children_map.remove(span_id);
+        if let Some(parent_id) = self.parent_map.remove(span_id)
+            && let Some(siblings) = self.children_map.get_mut(&parent_id)
+        {
+            siblings.retain(|s| s != span_id);
+            if siblings.is_empty() {
+                self.children_map.remove(&parent_id);
+            }
+        }
+        self.pruned += 1;
+        Some(event)
+    }
+
+    /// Warning-and-above events plus every ancestor still in the graph.
+    fn significant_spans(&self) -> HashSet<String> {
+        let mut keep = HashSet::new();
+        for (span_id, event) in &self.events {
+            if (event.severity as u8) < EventSeverity::Warning as u8 {
+                continue;
+            }
+            let mut current = Some(span_id);
+            while let Some(id) = current {
+                if !self.events.contains_key(id) || !keep.insert(id.clone()) {
+                    break;
+                }
+                current = self.parent_map.get(id);
+            }
+        }
+        keep
+    }
+
+    fn over_limit(&self, max_events: Option<usize>) -> bool {
+        max_events.is_some_and(|max| self.events.len() > max)
+            || self.retention.max_bytes.is_some_and(|max| self.approx_bytes > max)
+    }
+
+    /// Evicts oldest-first until the count/size targets hold, sparing
+    /// significant events on a first pass when the policy asks for it.
+    fn evict_oldest(&mut self, max_events: Option<usize>) -> usize {
+        let mut removed = 0;
+        let protected = if self.retention.keep_significant {
+            self.significant_spans()
+        } else {
+            HashSet::new()
+        };
+
+        for spare_protected in [true, false] {
+            if !self.over_limit(max_events) {
+                break;
+            }
+            let mut survivors = std::collections::VecDeque::with_capacity(self.order.len());
+            while let Some(span_id) = self.order.pop_front() {
+                if !self.events.contains_key(&span_id) {
+                    continue;
+                }
+                if !self.over_limit(max_events) || (spare_protected && protected.contains(&span_id)) {
+                    survivors.push_back(span_id);
+                    continue;
+                }
+                self.remove_event(&span_id);
+                removed += 1;
+            }
+            self.order = survivors;
+        }
+        removed
+    }
+
+    /// Removes every event with a timestamp before `cutoff`.
+    pub(crate) fn remove_older_than(&mut self, cutoff: f64) -> usize {
+        let stale: Vec<String> = self
+            .events
+            .iter()
+            .filter(|(_, e)| e.timestamp < cutoff)
+            .map(|(id, _)| id.clone())
+            .collect();
+        for span_id in &stale {
+            self.remove_event(span_id);
+        }
+        if !stale.is_empty() {
+            self.order.retain(|id| self.events.contains_key(id));
+        }
+        stale.len()
+    }
+
+    /// Applies the full policy. Returns the number of events removed.
+    pub(crate) fn apply_retention(&mut self) -> usize {
+        let mut removed = 0;
+        if let Some(max_age) = self.retention.max_age_secs {
+            removed += self.remove_older_than(self.latest_timestamp - max_age);
+        }
+        removed + self.evict_oldest(self.retention.max_events)
+    }
+
+    /// Called after each insert. Pruning to a low-water mark 10% under the cap
+    /// keeps eviction amortised instead of running on every add.
+    pub(crate) fn enforce_retention(&mut self) {
+        if !self.over_limit(self.retention.max_events) {
+            return;
+        }
+        let low_water = self.retention.max_events.map(|max| max - max / 10);
+        self.evict_oldest(low_water);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CorrelationContext;
+
+    fn event(severity: EventSeverity, context: CorrelationContext) -> Event {
+        Event::new(severity, "Test".to_string(), "msg".to_string(), context, None)
+    }
+
+    #[test]
+    fn test_retention_spares_significant_ancestors() {
+        let mut graph = CausalGraph::new();
+        let root = CorrelationContext::new();
+        let failure = root.child();
+        graph.add_event(event(EventSeverity::Info, root.clone()));
+        graph.add_event(event(EventSeverity::Critical, failure.clone()));
+        for _ in 0..20 {
+            graph.add_event(event(EventSeverity::Debug, CorrelationContext::new()));
+        }
+
+        graph.set_retention(RetentionPolicy::new(Some(10), None, None, true));
+        assert!(graph.size() <= 10);
+        assert!(graph.events.contains_key(&root.span_id));
+        assert_eq!(graph.get_causal_chain(failure.span_id.clone()).len(), 2);
+        assert_eq!(graph.pruned_count() as usize, 22 - graph.size());
+        assert!(graph.memory_usage() > 0);
+    }
+}
@@ -0,0 +1,228 @@
+//! Versioned save container shared by every crate: a header listing named,
+//! separately versioned chunks (one per subsystem) with a checksum each, so
+//! one damaged chunk costs that subsystem rather than the whole save, plus
+//! a registry of migrations that bring old chunks up to date.
+//!
+//! Layout: `VRSV`, container version (u32 LE), header length (u32 LE),
+//! bincode `SaveHeader`, header checksum (u64 LE), then the chunk payloads
+//! back to back in header order.
+
+use crate::desync::hash_bytes as checksum;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+const MAGIC: &[u8; 4] = b"VRSV";
+/// Version of the container layout itself; chunk payloads carry their own.
+pub const SAVE_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SaveChunk {
+    /// Subsystem key, e.g. "economy" or "battle:front".
+    pub name: String,
+    /// Version of the payload's layout, as the writing crate defined it.
+    pub version: u32,
+    pub payload: Vec<u8>,
+}
+
+/// A chunk that couldn't be read or migrated, and why.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DamagedChunk {
+    pub name: String,
+    pub reason: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ChunkEntry {
+    name: String,
+    version: u32,
+    len: u64,
+    checksum: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SaveHeader {
+    engine_version: String,
+    chunks: Vec<ChunkEntry>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SaveGame {
+    /// Bridge version that wrote the save.
+    pub engine_version: String,
+    pub chunks: Vec<SaveChunk>,
+    /// Chunks left out on load because they failed their checksum, were
+    /// cut off, or couldn't be migrated.
+    pub damaged: Vec<DamagedChunk>,
+}
+
+impl SaveGame {
+    pub fn new(engine_version: impl Into<String>) -> Self {
+        Self { engine_version: engine_version.into(), ..Self::default() }
+    }
+
+    /// Adds a chunk, replacing any earlier one with the same name.
+    pub fn add_chunk(&mut self, name: impl Into<String>, version: u32, payload: Vec<u8>) {
+        let name = name.into();
+        self.chunks.retain(|c| c.name != name);
+        self.chunks.push(SaveChunk { name, version, payload });
+    }
+
+    pub fn chunk(&self, name: &str) -> Option<&SaveChunk> {
+        self.chunks.iter().find(|c| c.name == name)
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, String> {
+        let header = SaveHeader {
+            engine_version: self.engine_version.clone(),
+            chunks: self
+                .chunks
+                .iter()
+                .map(|c| ChunkEntry {
+                    name: c.name.clone(),
+                    version: c.version,
+                    len: c.payload.len() as u64,
+                    checksum: checksum(&c.payload),
+                })
+                .collect(),
+        };
+        let header = bincode::serialize(&header).map_err(|e| e.to_string())?;
+        let header_len = u32::try_from(header.len()).map_err(|_| "save header too large".to_string())?;
+        let payloads: usize = self.chunks.iter().map(|c| c.payload.len()).sum();
+
+        let mut bytes = Vec::with_capacity(20 + header.len() + payloads);
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&SAVE_FORMAT_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&header_len.to_le_bytes());
+        bytes.extend_from_slice(&header);
+        bytes.extend_from_slice(&checksum(&header).to_le_bytes());
+        for chunk in &self.chunks {
+            bytes.extend_from_slice(&chunk.payload);
+        }
+        Ok(bytes)
+    }
+
+    /// Reads a save. Fails only if the container or its header is unusable;
+    /// damaged chunks are listed in `damaged` and left out of `chunks`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let (prefix, rest) = bytes.split_at_checked(12).ok_or("truncated save")?;
+        if &prefix[..4] != MAGIC {
+            return Err("not a save game".to_string());
+        }
+        let version = u32::from_le_bytes([prefix[4], prefix[5], prefix[6], prefix[7]]);
+        if version > SAVE_FORMAT_VERSION {
+            return Err(format!("save format {} is newer than this engine supports ({})", version, SAVE_FORMAT_VERSION));
+        }
+        let header_len = u32::from_le_bytes([prefix[8], prefix[9], prefix[10], prefix[11]]) as usize;
+        let (header, rest) = rest.split_at_checked(header_len).ok_or("truncated save header")?;
+        let (stored, mut payloads) = rest.split_at_checked(8).ok_or("truncated save header")?;
+        if u64::from_le_bytes(stored.try_into().expect("8 bytes")) != checksum(header) {
+            return Err("save header checksum mismatch".to_string());
+        }
+        let header: SaveHeader = bincode::deserialize(header).map_err(|e| e.to_string())?;
+
+        let mut save = SaveGame::new(header.engine_version);
+        for entry in header.chunks {
+            let Some((payload, remaining)) = usize::try_from(entry.len).ok().and_then(|len| payloads.split_at_checked(len)) else {
+                save.damaged.push(DamagedChunk { name: entry.name, reason: "truncated".to_string() });
+                payloads = &[];
+                continue;
+            };
+            payloads = remaining;
+            if checksum(payload) != entry.checksum {
+                save.damaged.push(DamagedChunk { name: entry.name, reason: "checksum mismatch".to_string() });
+                continue;
+            }
+            save.chunks.push(SaveChunk { name: entry.name, version: entry.version, payload: payload.to_vec() });
+        }
+        Ok(save)
+    }
+}
+
+/// Rewrites a chunk payload from one version to the next.
+pub type Migration = Box<dyn Fn(Vec<u8>) -> Result<Vec<u8>, String> + Send + Sync>;
+
+/// Migrations by chunk kind and the version they upgrade from. Chunk names
+/// map to kinds by their prefix before `:`, so "battle:front" migrates as
+/// "battle". When a crate bumps a payload version it registers the step
+/// from the old one here, and saves made before the bump keep loading.
+#[derive(Default)]
+pub struct MigrationRegistry {
+    steps: BTreeMap<(String, u32), Migration>,
+}
+
+impl MigrationRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the step that turns a `kind` payload at version `from`
+    /// into version `from + 1`.
+    pub fn register(&mut self, kind: &str, from: u32, migration: impl Fn(Vec<u8>) -> Result<Vec<u8>, String> + Send + Sync + 'static) {
+        self.steps.insert((kind.to_string(), from), Box::new(migration));
+    }
+
+    /// Brings `chunk` up to version `target` one step at a time.
+    pub fn migrate(&self, chunk: &mut SaveChunk, target: u32) -> Result<(), String> {
+        let kind = chunk.name.split(':').next().unwrap_or_default();
+        if chunk.version > target {
+            return Err(format!("version {} is newer than this engine supports ({})", chunk.version, target));
+        }
+        while chunk.version < target {
+            let step = self
+                .steps
+                .get(&(kind.to_string(), chunk.version))
+                .ok_or_else(|| format!("no migration from version {}", chunk.version))?;
+            chunk.payload = step(std::mem::take(&mut chunk.payload))?;
+            chunk.version += 1;
+        }
+        Ok(())
+    }
+
+    /// Migrates every chunk whose kind has a current version in `current`;
+    /// chunks that can't be brought up to date move to `save.damaged`.
+    pub fn upgrade(&self, save: &mut SaveGame, current: &BTreeMap<&str, u32>) {
+        let mut kept = Vec::with_capacity(save.chunks.len());
+        for mut chunk in std::mem::take(&mut save.chunks) {
+            let kind = chunk.name.split(':').next().unwrap_or_default();
+            match current.get(kind).map(|&target| self.migrate(&mut chunk, target)) {
+                Some(Err(reason)) => save.damaged.push(DamagedChunk { name: chunk.name, reason }),
+                _ => kept.push(chunk),
+            }
+        }
+        save.chunks = kept;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn damaged_chunks_are_isolated_and_old_ones_migrated() {
+        let mut save = SaveGame::new("0.1.0");
+        save.add_chunk("economy", 1, b"old".to_vec());
+        save.add_chunk("battle:front", 2, b"battle".to_vec());
+        save.add_chunk("pathfinder", 1, b"map".to_vec());
+        let mut bytes = save.to_bytes().unwrap();
+        // Flip a byte inside the battle payload.
+        let at = bytes.len() - b"map".len() - 1;
+        bytes[at] ^= 0xff;
+
+        let mut loaded = SaveGame::from_bytes(&bytes).unwrap();
+        assert_eq!(loaded.damaged, vec![DamagedChunk { name: "battle:front".to_string(), reason: "checksum mismatch".to_string() }]);
+        assert_eq!(loaded.chunks.len(), 2);
+
+        let mut migrations = MigrationRegistry::new();
+        migrations.register("economy", 1, |mut payload| {
+            payload.extend_from_slice(b"+v2");
+            Ok(payload)
+        });
+        migrations.upgrade(&mut loaded, &BTreeMap::from([("economy", 2), ("pathfinder", 2)]));
+        assert_eq!(loaded.chunk("economy").map(|c| (c.version, c.payload.as_slice())), Some((2, &b"old+v2"[..])));
+        assert!(loaded.chunk("pathfinder").is_none());
+        assert_eq!(loaded.damaged[1].reason, "no migration from version 1");
+
+        bytes[14] ^= 0xff;
+        assert!(SaveGame::from_bytes(&bytes).is_err());
+    }
+}
@@ -0,0 +1,168 @@
+use crate::CorrelationContext;
+use pyo3::prelude::*;
+use std::sync::{Arc, Mutex};
+
+/// Per-engine stack of correlation contexts. The bottom frame is the root
+/// set via `set_root`; nested operations push scopes so anything emitted
+/// inside them takes the innermost context as its parent.
+///
+/// Clones share the same stack, which is how the Python `ContextScope`
+/// and the engine it came from see each other's frames.
+#[derive(Clone)]
+pub struct ContextStack {
+    frames: Arc<Mutex<Vec<CorrelationContext>>>,
+}
+
+impl ContextStack {
+    pub fn new(root: CorrelationContext) -> Self {
+        Self {
+            frames: Arc::new(Mutex::new(vec![root])),
+        }
+    }
+
+    /// Innermost context.
+    pub fn current(&self) -> CorrelationContext {
+        self.frames
+            .lock()
+            .ok()
+            .and_then(|frames| frames.last().cloned())
+            .unwrap_or_default()
+    }
+
+    /// Replaces the whole stack with `root`, discarding open scopes.
+    pub fn set_root(&self, root: CorrelationContext) {
+        if let Ok(mut frames) = self.frames.lock() {
+            frames.clear();
+            frames.push(root);
+        }
+    }
+
+    pub fn depth(&self) -> usize {
+        self.frames.lock().map(|f| f.len()).unwrap_or(0)
+    }
+
+    /// Opens a child of the current context; it is popped when the guard drops.
+    pub fn enter(&self) -> ContextScope {
+        self.enter_context(self.current().child())
+    }
+
+    /// Pushes `context` (e.g. a span's) for the lifetime of the guard.
+    pub fn enter_context(&self, context: CorrelationContext) -> ContextScope {
+        let depth = self.push(context.clone());
+        ContextScope {
+            stack: self.clone(),
+            context,
+            depth,
+        }
+    }
+
+    /// Returns the depth before the push, for `truncate`.
+    fn push(&self, context: CorrelationContext) -> usize {
+        self.frames
+            .lock()
+            .map(|mut frames| {
+                frames.push(context);
+                frames.len() - 1
+            })
+            .unwrap_or(0)
+    }
+
+    /// Pops back to `depth` frames, never removing the root.
+    fn truncate(&self, depth: usize) {
+        if let Ok(mut frames) = self.frames.lock() {
+            frames.truncate(depth.max(1));
+        }
+    }
+}
+
+impl Default for ContextStack {
+    fn default() -> Self {
+        Self::new(CorrelationContext::new())
+    }
+}
+
+/// RAII scope on a `ContextStack`. Dropping it pops its frame and any frames
+/// opened inside it that leaked.
+pub struct ContextScope {
+    stack: ContextStack,
+    context: CorrelationContext,
+    depth: usize,
+}
+
+impl ContextScope {
+    pub fn context(&self) -> &CorrelationContext {
+        &self.context
+    }
+}
+
+impl Drop for ContextScope {
+    fn drop(&mut self) {
+        self.stack.truncate(self.depth);
+    }
+}
+
+/// Python context manager over an engine's context stack:
+/// `with engine.context_scope() as ctx:` makes everything the engine emits
+/// inside the block descend from `ctx`.
+#[pyclass(name = "ContextScope")]
+pub struct PyContextScope {
+    stack: ContextStack,
+    context: Option<CorrelationContext>,
+    depth: Option<usize>,
+}
+
+impl PyContextScope {
+    /// `context` is pushed as-is; with `None` a child of the current top is opened on enter.
+    pub fn new(stack: ContextStack, context: Option<CorrelationContext>) -> Self {
+        Self { stack, context, depth: None }
+    }
+}
+
+#[pymethods]
+impl PyContextScope {
+    fn __enter__(&mut self) -> CorrelationContext {
+        let context = self.context.clone().unwrap_or_else(|| self.stack.current().child());
+        self.depth = Some(self.stack.push(context.clone()));
+        self.context = Some(context.clone());
+        context
+    }
+
+    #[pyo3(signature = (_exc_type=None, _exc_value=None, _traceback=None))]
+    fn __exit__(
+        &mut self,
+        _exc_type: Option<PyObject>,
+        _exc_value: Option<PyObject>,
+        _traceback: Option<PyObject>,
+    ) -> bool {
+        if let Some(depth) = self.depth.take() {
+            self.stack.truncate(depth);
+        }
+        false
+    }
+
+    #[getter]
+    fn context(&self) -> Option<CorrelationContext> {
+        self.context.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nested_scopes_chain_parent_ids() {
+        let root = CorrelationContext::new();
+        let stack = ContextStack::new(root.clone());
+        {
+            let outer = stack.enter();
+            assert_eq!(outer.context().parent_id.as_deref(), Some(root.span_id.as_str()));
+            let inner = stack.enter();
+            assert_eq!(inner.context().parent_id.as_deref(), Some(outer.context().span_id.as_str()));
+            assert_eq!(stack.depth(), 3);
+            drop(outer); // out-of-order drop also unwinds the inner frame
+            assert_eq!(stack.depth(), 1);
+        }
+        assert_eq!(stack.current().span_id, root.span_id);
+    }
+}
@@ -0,0 +1,307 @@
+use crate::errors::EngineError;
+use crate::event_log::chronological;
+use crate::Event;
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+
+/// Where in the simulation an event happened, alongside its wall-clock
+/// timestamp. Engines fill in what they know: the campaign turn, their own
+/// step counter (`tick`) and engine-local elapsed time.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[pyclass(eq)]
+pub struct SimTime {
+    #[pyo3(get, set)]
+    pub turn: Option<u64>,
+    #[pyo3(get, set)]
+    pub tick: Option<u64>,
+    #[pyo3(get, set)]
+    pub engine_time: Option<f64>,
+}
+
+impl SimTime {
+    pub fn at_turn(turn: Option<u64>) -> Self {
+        Self { turn, ..Self::default() }
+    }
+
+    pub fn with_tick(mut self, tick: u64) -> Self {
+        self.tick = Some(tick);
+        self
+    }
+
+    pub fn with_engine_time(mut self, engine_time: f64) -> Self {
+        self.engine_time = Some(engine_time);
+        self
+    }
+
+    pub fn is_unset(&self) -> bool {
+        self.turn.is_none() && self.tick.is_none() && self.engine_time.is_none()
+    }
+}
+
+#[pymethods]
+impl SimTime {
+    #[new]
+    #[pyo3(signature = (turn=None, tick=None, engine_time=None))]
+    pub fn new(turn: Option<u64>, tick: Option<u64>, engine_time: Option<f64>) -> Self {
+        Self { turn, tick, engine_time }
+    }
+
+    fn __repr__(&self) -> String {
+        format!("SimTime(turn={:?}, tick={:?}, engine_time={:?})", self.turn, self.tick, self.engine_time)
+    }
+}
+
+/// Maps campaign turns to in-world dates: turn 1 falls in the first
+/// period of `start_year`, and each year has `turns_per_year` periods,
+/// named by `periods` when given (e.g. seasons or months).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Calendar {
+    #[serde(default)]
+    pub start_year: i64,
+    #[serde(default = "one_turn")]
+    pub turns_per_year: u32,
+    #[serde(default)]
+    pub periods: Vec<String>,
+}
+
+fn one_turn() -> u32 {
+    1
+}
+
+impl Default for Calendar {
+    fn default() -> Self {
+        Self { start_year: 0, turns_per_year: 1, periods: Vec::new() }
+    }
+}
+
+impl Calendar {
+    pub fn date(&self, turn: u64) -> CalendarDate {
+        let per_year = self.turns_per_year.max(1) as u64;
+        let index = turn.saturating_sub(1);
+        let year = self.start_year + (index / per_year) as i64;
+        let period = (index % per_year) as u32;
+        let label = match self.periods.get(period as usize) {
+            Some(name) => format!("{} {}", name, year),
+            None if per_year == 1 => year.to_string(),
+            None => format!("{}.{}", year, period + 1),
+        };
+        CalendarDate { year, period, label }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[pyclass(get_all, eq)]
+pub struct CalendarDate {
+    pub year: i64,
+    /// Zero-based period within the year.
+    pub period: u32,
+    pub label: String,
+}
+
+#[pymethods]
+impl CalendarDate {
+    fn __repr__(&self) -> String {
+        format!("CalendarDate({:?})", self.label)
+    }
+}
+
+/// A clock's position, for saves.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ClockState {
+    pub turn: u64,
+    /// Ticks into the current turn.
+    pub tick: u64,
+    #[serde(default)]
+    pub calendar: Calendar,
+}
+
+/// The campaign's one notion of "when": turn, tick within the turn and
+/// the calendar. Clones share the same clock, so every engine handed one
+/// reads the same turn, and an `EventLog` given one stamps it on events
+/// that don't carry their own.
+#[pyclass(frozen)]
+#[derive(Debug, Clone, Default)]
+pub struct SimClock {
+    state: Arc<RwLock<ClockState>>,
+}
+
+impl SimClock {
+    pub fn at_turn(turn: u64) -> Self {
+        Self::from_state(ClockState { turn, ..ClockState::default() })
+    }
+
+    pub fn from_state(state: ClockState) -> Self {
+        Self { state: Arc::new(RwLock::new(state)) }
+    }
+
+    pub fn state(&self) -> ClockState {
+        self.read().clone()
+    }
+
+    pub fn restore(&self, state: ClockState) {
+        *self.write() = state;
+    }
+
+    pub fn calendar(&self) -> Calendar {
+        self.read().calendar.clone()
+    }
+
+    pub fn set_calendar(&self, calendar: Calendar) {
+        self.write().calendar = calendar;
+    }
+
+    /// Whether `other` is a handle to this same clock.
+    pub fn shares(&self, other: &SimClock) -> bool {
+        Arc::ptr_eq(&self.state, &other.state)
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, ClockState> {
+        self.state.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, ClockState> {
+        self.state.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[pymethods]
+impl SimClock {
+    /// `calendar_json` is a `Calendar`: `{"start_year": 2400,
+    /// "turns_per_year": 4, "periods": ["Spring", ...]}`.
+    #[new]
+    #[pyo3(signature = (turn=0, calendar_json=None))]
+    fn py_new(turn: u64, calendar_json: Option<&str>) -> PyResult<Self> {
+        let clock = Self::at_turn(turn);
+        if let Some(json) = calendar_json {
+            clock.py_set_calendar(json)?;
+        }
+        Ok(clock)
+    }
+
+    #[getter]
+    pub fn turn(&self) -> u64 {
+        self.read().turn
+    }
+
+    #[getter]
+    pub fn tick(&self) -> u64 {
+        self.read().tick
+    }
+
+    /// Turn and tick as a `SimTime`.
+    pub fn now(&self) -> SimTime {
+        let state = self.read();
+        SimTime::at_turn(Some(state.turn)).with_tick(state.tick)
+    }
+
+    /// Moves to `turn`, at its first tick.
+    pub fn set_turn(&self, turn: u64) {
+        let mut state = self.write();
+        state.turn = turn;
+        state.tick = 0;
+    }
+
+    /// Starts the next turn; returns it.
+    pub fn advance_turn(&self) -> u64 {
+        let mut state = self.write();
+        state.turn += 1;
+        state.tick = 0;
+        state.turn
+    }
+
+    /// Moves one tick into the current turn; returns the new tick.
+    pub fn advance_tick(&self) -> u64 {
+        let mut state = self.write();
+        state.tick += 1;
+        state.tick
+    }
+
+    /// Date of `turn`, or of the current turn.
+    #[pyo3(signature = (turn=None))]
+    pub fn date(&self, turn: Option<u64>) -> CalendarDate {
+        let state = self.read();
+        state.calendar.date(turn.unwrap_or(state.turn))
+    }
+
+    #[pyo3(name = "calendar")]
+    fn py_calendar(&self) -> PyResult<String> {
+        serde_json::to_string(&self.read().calendar).map_err(|e| PyErr::from(EngineError::json(e)))
+    }
+
+    #[pyo3(name = "set_calendar")]
+    fn py_set_calendar(&self, calendar_json: &str) -> PyResult<()> {
+        let calendar: Calendar = serde_json::from_str(calendar_json).map_err(|e| PyErr::from(EngineError::json(e)))?;
+        self.set_calendar(calendar);
+        Ok(())
+    }
+
+    fn __repr__(&self) -> String {
+        let state = self.read();
+        format!("SimClock(turn={}, tick={}, date={:?})", state.turn, state.tick, state.calendar.date(state.turn).label)
+    }
+}
+
+/// Inclusive turn/tick bounds. A bound on an axis excludes events that carry
+/// no value for it; an unbounded query matches everything.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SimRange {
+    pub turn_min: Option<u64>,
+    pub turn_max: Option<u64>,
+    pub tick_min: Option<u64>,
+    pub tick_max: Option<u64>,
+}
+
+fn within(value: Option<u64>, min: Option<u64>, max: Option<u64>) -> bool {
+    if min.is_none() && max.is_none() {
+        return true;
+    }
+    value.is_some_and(|v| min.is_none_or(|m| v >= m) && max.is_none_or(|m| v <= m))
+}
+
+impl SimRange {
+    pub fn matches(&self, event: &Event) -> bool {
+        within(event.sim_time.turn, self.turn_min, self.turn_max)
+            && within(event.sim_time.tick, self.tick_min, self.tick_max)
+    }
+}
+
+/// Orders events by turn, then tick, then `chronological`; unstamped events sort
+/// last. The sort is stable, so full ties keep their input order.
+pub fn sort_by_sim_time(events: &mut [Event]) {
+    events.sort_by(|a, b| {
+        let key = |e: &Event| (e.sim_time.turn.unwrap_or(u64::MAX), e.sim_time.tick.unwrap_or(u64::MAX));
+        key(a).cmp(&key(b)).then_with(|| chronological(a, b))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_log::EventLog;
+    use crate::{CorrelationContext, EventSeverity};
+
+    #[test]
+    fn clock_is_shared_and_stamps_unstamped_events() {
+        let calendar = Calendar { start_year: 2400, turns_per_year: 4, periods: vec!["Spring".into(), "Summer".into()] };
+        let clock = SimClock::at_turn(0);
+        clock.set_calendar(calendar);
+        let engine = clock.clone();
+        assert_eq!(engine.advance_turn(), 1);
+        engine.advance_tick();
+        assert_eq!(clock.now(), SimTime::at_turn(Some(1)).with_tick(1));
+        assert_eq!(clock.date(None).label, "Spring 2400");
+        assert_eq!(clock.date(Some(3)).label, "2400.3");
+        assert_eq!(clock.date(Some(5)).year, 2401);
+
+        let log = EventLog::new();
+        log.set_clock(Some(clock.clone()));
+        let event = |sim_time: SimTime| {
+            Event::new(EventSeverity::Info, "Test".to_string(), String::new(), CorrelationContext::new(), None).at(sim_time)
+        };
+        log.add(event(SimTime::default()));
+        log.add(event(SimTime::at_turn(Some(7))));
+        let stamped: Vec<SimTime> = log.get_all().into_iter().map(|e| e.sim_time).collect();
+        assert_eq!(stamped, vec![SimTime::at_turn(Some(1)).with_tick(1), SimTime::at_turn(Some(7))]);
+    }
+}
@@ -0,0 +1,259 @@
+use crate::Event;
+use pyo3::prelude::*;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// Destination that receives every event added to an `EventLog` it is
+/// attached to. Implementations must be cheap to call: they run on the
+/// engine's hot path.
+pub trait EventSink: Send + Sync {
+    fn write(&self, event: &Event);
+    fn flush(&self);
+}
+
+#[derive(Debug, Clone)]
+pub struct RotationPolicy {
+    /// Rotate once the active file reaches this many bytes.
+    pub max_bytes: Option<u64>,
+    /// Rotate once the active file has been open this long.
+    pub max_age: Option<Duration>,
+    /// Keep at most this many rotated files (oldest are deleted).
+    pub max_files: Option<usize>,
+}
+
+enum SinkMessage {
+    Event(Box<Event>),
+    Flush(Sender<()>),
+    Shutdown,
+}
+
+/// How long the writer may sit on buffered events before flushing to disk.
+const IDLE_FLUSH_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Streams events as newline-delimited JSON on a background writer thread.
+/// The active file is `path`; rotated files are renamed to `path.1`, `path.2`, ...
+pub struct JsonlWriter {
+    sender: Mutex<Option<Sender<SinkMessage>>>,
+    worker: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl JsonlWriter {
+    pub fn open(path: impl Into<PathBuf>, policy: RotationPolicy) -> std::io::Result<Self> {
+        let path = path.into();
+        let mut state = WriterState::open(path, policy)?;
+        let (sender, receiver) = mpsc::channel::<SinkMessage>();
+
+        let worker = std::thread::Builder::new()
+            .name("event-jsonl-sink".to_string())
+            .spawn(move || loop {
+                match receiver.recv_timeout(IDLE_FLUSH_INTERVAL) {
+                    Ok(SinkMessage::Event(event)) => state.write(&event),
+                    Ok(SinkMessage::Flush(ack)) => {
+                        state.flush();
+                        let _ = ack.send(());
+                    }
+                    Ok(SinkMessage::Shutdown) | Err(RecvTimeoutError::Disconnected) => {
+                        state.flush();
+                        break;
+                    }
+                    Err(RecvTimeoutError::Timeout) => state.flush(),
+                }
+            })?;
+
+        Ok(Self {
+            sender: Mutex::new(Some(sender)),
+            worker: Mutex::new(Some(worker)),
+        })
+    }
+
+    /// Flushes pending events and stops the writer thread. Further writes are ignored.
+    pub fn close(&self) {
+        if let Some(sender) = self.sender.lock().ok().and_then(|mut s| s.take()) {
+            let _ = sender.send(SinkMessage::Shutdown);
+        }
+        if let Some(worker) = self.worker.lock().ok().and_then(|mut w| w.take()) {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl EventSink for JsonlWriter {
+    fn write(&self, event: &Event) {
+        if let Ok(sender) = self.sender.lock()
+            && let Some(sender) = sender.as_ref()
+        {
+            let _ = sender.send(SinkMessage::Event(Box::new(event.clone())));
+        }
+    }
+
+    /// Blocks until everything queued so far has reached the file.
+    fn flush(&self) {
+        let (ack, done) = mpsc::channel();
+        let sent = self
+            .sender
+            .lock()
+            .ok()
+            .and_then(|s| s.as_ref().map(|s| s.send(SinkMessage::Flush(ack)).is_ok()))
+            .unwrap_or(false);
+        if sent {
+            let _ = done.recv();
+        }
+    }
+}
+
+impl Drop for JsonlWriter {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+struct WriterState {
+    path: PathBuf,
+    policy: RotationPolicy,
+    writer: BufWriter<File>,
+    bytes_written: u64,
+    opened_at: Instant,
+    rotations: usize,
+}
+
+impl WriterState {
+    fn open(path: PathBuf, policy: RotationPolicy) -> std::io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let bytes_written = file.metadata().map(|m| m.len()).unwrap_or(0);
+
+        // Continue numbering after rotated files left by a previous run
+        let mut rotations = 0;
+        while rotated_path(&path, rotations + 1).exists() {
+            rotations += 1;
+        }
+
+        Ok(Self {
+            path,
+            policy,
+            writer: BufWriter::new(file),
+            bytes_written,
+            opened_at: Instant::now(),
+            rotations,
+        })
+    }
+
+    fn write(&mut self, event: &Event) {
+        let Ok(mut line) = serde_json::to_string(event) else {
+            return;
+        };
+        line.push('\n');
+
+        if self.should_rotate(line.len() as u64) {
+            self.rotate();
+        }
+
+        if self.writer.write_all(line.as_bytes()).is_ok() {
+            self.bytes_written += line.len() as u64;
+        }
+    }
+
+    fn should_rotate(&self, incoming: u64) -> bool {
+        let size_exceeded = self
+            .policy
+            .max_bytes
+            .is_some_and(|max| self.bytes_written > 0 && self.bytes_written + incoming > max);
+        let age_exceeded = self.policy.max_age.is_some_and(|max| self.opened_at.elapsed() >= max);
+        size_exceeded || age_exceeded
+    }
+
+    fn rotate(&mut self) {
+        self.flush();
+        self.rotations += 1;
+        let _ = fs::rename(&self.path, rotated_path(&self.path, self.rotations));
+
+        if let Some(max_files) = self.policy.max_files
+            && self.rotations > max_files
+        {
+            let _ = fs::remove_file(rotated_path(&self.path, self.rotations - max_files));
+        }
+
+        if let Ok(file) = OpenOptions::new().create(true).write(true).truncate(true).open(&self.path) {
+            self.writer = BufWriter::new(file);
+            self.bytes_written = 0;
+            self.opened_at = Instant::now();
+        }
+    }
+
+    fn flush(&mut self) {
+        let _ = self.writer.flush();
+    }
+}
+
+fn rotated_path(path: &Path, index: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(format!(".{}", index));
+    PathBuf::from(name)
+}
+
+/// Python handle for a JSONL file sink; attach with `EventLog.attach_sink`.
+#[pyclass]
+#[derive(Clone)]
+pub struct JsonlFileSink {
+    pub inner: Arc<JsonlWriter>,
+}
+
+#[pymethods]
+impl JsonlFileSink {
+    #[new]
+    #[pyo3(signature = (path, max_bytes=None, max_age_secs=None, max_files=None))]
+    pub fn new(path: String, max_bytes: Option<u64>, max_age_secs: Option<f64>, max_files: Option<usize>) -> PyResult<Self> {
+        let policy = RotationPolicy {
+            max_bytes,
+            max_age: max_age_secs.map(Duration::from_secs_f64),
+            max_files,
+        };
+        let writer = JsonlWriter::open(path, policy)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(format!("Sink error: {}", e)))?;
+        Ok(Self { inner: Arc::new(writer) })
+    }
+
+    pub fn flush(&self) {
+        self.inner.flush();
+    }
+
+    pub fn close(&self) {
+        self.inner.close();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CorrelationContext, EventSeverity};
+
+    #[test]
+    fn test_jsonl_sink_rotates_by_size() {
+        let dir = std::env::temp_dir().join(format!("vr-sink-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("events.jsonl");
+
+        let policy = RotationPolicy { max_bytes: Some(300), max_age: None, max_files: None };
+        let writer = JsonlWriter::open(&path, policy).unwrap();
+        for i in 0..10 {
+            let event = Event::new(EventSeverity::Info, "Test".to_string(), format!("event {}", i), CorrelationContext::new(), None);
+            writer.write(&event);
+        }
+        writer.close();
+
+        let mut total_lines = fs::read_to_string(&path).unwrap().lines().count();
+        let mut index = 1;
+        while rotated_path(&path, index).exists() {
+            total_lines += fs::read_to_string(rotated_path(&path, index)).unwrap().lines().count();
+            index += 1;
+        }
+
+        assert!(index > 1, "expected at least one rotation");
+        assert_eq!(total_lines, 10);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,134 @@
+use crate::simtime::SimTime;
+use crate::{now_secs, pyvalue, CorrelationContext, Event, EventSeverity};
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Category used for the event a finished span is recorded as.
+pub const SPAN_CATEGORY: &str = "Span";
+
+/// A timed unit of work. The span owns a child context of whatever it was
+/// started under; events emitted while it is open should use
+/// `span.context.child()` so they hang off the span in the causal graph.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[pyclass]
+pub struct Span {
+    #[pyo3(get)]
+    pub name: String,
+    #[pyo3(get)]
+    pub context: CorrelationContext,
+    #[pyo3(get)]
+    pub start: f64,
+    #[pyo3(get)]
+    pub end: Option<f64>,
+    #[serde(default)]
+    pub attributes: Map<String, Value>,
+    #[pyo3(get, set)]
+    #[serde(default)]
+    pub sim_time: SimTime,
+}
+
+impl Span {
+    pub fn start(name: &str, parent: &CorrelationContext) -> Self {
+        Self {
+            name: name.to_string(),
+            context: parent.child(),
+            start: now_secs(),
+            end: None,
+            attributes: Map::new(),
+            sim_time: SimTime::default(),
+        }
+    }
+
+    pub fn with_attribute(mut self, key: &str, value: impl Into<Value>) -> Self {
+        self.attributes.insert(key.to_string(), value.into());
+        self
+    }
+
+    pub fn at(mut self, sim_time: SimTime) -> Self {
+        self.sim_time = sim_time;
+        self
+    }
+
+    pub fn finish(&mut self) {
+        if self.end.is_none() {
+            self.end = Some(now_secs());
+        }
+    }
+
+    /// Converts a finished span into the event that records it.
+    pub fn to_event(&self) -> Event {
+        let mut event = Event::new(
+            EventSeverity::Debug,
+            SPAN_CATEGORY.to_string(),
+            self.name.clone(),
+            self.context.clone(),
+            None,
+        )
+        .with_field("name", self.name.as_str())
+        .with_field("start", self.start)
+        .with_field("attributes", Value::Object(self.attributes.clone()));
+        if let Some(end) = self.end {
+            event = event.with_field("end", end).with_field("duration", end - self.start);
+        }
+        event.timestamp = self.start;
+        event.sim_time = self.sim_time;
+        event
+    }
+
+    /// Recovers a span from its recording event, if the event is one.
+    pub fn from_event(event: &Event) -> Option<Self> {
+        if event.category != SPAN_CATEGORY {
+            return None;
+        }
+        Some(Self {
+            name: event.get_str("name").unwrap_or_else(|| event.message.clone()),
+            context: event.context.clone(),
+            start: event.get_float("start").unwrap_or(event.timestamp),
+            end: event.get_float("end"),
+            attributes: event
+                .field("attributes")
+                .and_then(|v| v.as_object())
+                .cloned()
+                .unwrap_or_default(),
+            sim_time: event.sim_time,
+        })
+    }
+}
+
+#[pymethods]
+impl Span {
+    #[new]
+    #[pyo3(signature = (name, parent=None))]
+    fn py_new(name: String, parent: Option<CorrelationContext>) -> Self {
+        Self::start(&name, &parent.unwrap_or_default())
+    }
+
+    /// Seconds between start and end, or None while the span is still open.
+    #[getter]
+    pub fn duration(&self) -> Option<f64> {
+        self.end.map(|end| end - self.start)
+    }
+
+    #[getter(attributes)]
+    fn py_attributes(&self, py: Python<'_>) -> PyResult<PyObject> {
+        pyvalue::map_to_py(py, &self.attributes)
+    }
+
+    #[pyo3(name = "set_attribute")]
+    fn py_set_attribute(&mut self, key: String, value: &Bound<'_, PyAny>) -> PyResult<()> {
+        self.attributes.insert(key, pyvalue::py_to_value(value)?);
+        Ok(())
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.end.is_some()
+    }
+
+    fn __repr__(&self) -> String {
+        match self.duration() {
+            Some(duration) => format!("Span({}, {:.6}s)", self.name, duration),
+            None => format!("Span({}, open)", self.name),
+        }
+    }
+}
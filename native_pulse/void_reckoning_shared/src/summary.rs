@@ -0,0 +1,152 @@
+use crate::{CausalGraph, EventSeverity};
+use pyo3::prelude::*;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// A span that emitted many Warning-or-worse events directly beneath it.
+#[derive(Debug, Clone, Serialize)]
+#[pyclass]
+pub struct SpanOffender {
+    #[pyo3(get)]
+    pub span_id: String,
+    /// Span name if the span was timed, otherwise the event's category.
+    #[pyo3(get)]
+    pub name: String,
+    #[pyo3(get)]
+    pub problem_count: usize,
+}
+
+/// Aggregate view of one trace, cheap enough for nightly per-run reports.
+#[derive(Debug, Clone, Default, Serialize)]
+#[pyclass]
+pub struct TraceSummary {
+    #[pyo3(get)]
+    pub trace_id: String,
+    #[pyo3(get)]
+    pub event_count: usize,
+    #[pyo3(get)]
+    pub by_severity: BTreeMap<String, usize>,
+    #[pyo3(get)]
+    pub by_category: BTreeMap<String, usize>,
+    #[pyo3(get)]
+    pub first_timestamp: Option<f64>,
+    #[pyo3(get)]
+    pub last_timestamp: Option<f64>,
+    /// Span ids of the longest root-to-leaf chain, root first.
+    #[pyo3(get)]
+    pub deepest_chain: Vec<String>,
+    #[pyo3(get)]
+    pub top_offenders: Vec<SpanOffender>,
+}
+
+#[pymethods]
+impl TraceSummary {
+    /// Seconds from the first to the last event in the trace.
+    #[getter]
+    pub fn duration(&self) -> f64 {
+        match (self.first_timestamp, self.last_timestamp) {
+            (Some(first), Some(last)) => last - first,
+            _ => 0.0,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut value = serde_json::to_value(self).unwrap_or_default();
+        if let Some(map) = value.as_object_mut() {
+            map.insert("duration".to_string(), self.duration().into());
+        }
+        value.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "TraceSummary({}, events={}, duration={:.3}s, depth={})",
+            self.trace_id,
+            self.event_count,
+            self.duration(),
+            self.deepest_chain.len()
+        )
+    }
+}
+
+impl CausalGraph {
+    pub(crate) fn trace_summary(&self, trace_id: &str, top_n: usize) -> TraceSummary {
+        let mut summary = TraceSummary {
+            trace_id: trace_id.to_string(),
+            ..TraceSummary::default()
+        };
+        let mut problems: HashMap<&str, usize> = HashMap::new();
+        let mut deepest: Option<(usize, &str)> = None;
+
+        for (span_id, event) in self.events.iter().filter(|(_, e)| e.context.trace_id == trace_id) {
+            summary.event_count += 1;
+            *summary.by_severity.entry(format!("{:?}", event.severity)).or_default() += 1;
+            *summary.by_category.entry(event.category.clone()).or_default() += 1;
+            summary.first_timestamp = Some(summary.first_timestamp.map_or(event.timestamp, |t| t.min(event.timestamp)));
+            summary.last_timestamp = Some(summary.last_timestamp.map_or(event.timestamp, |t| t.max(event.timestamp)));
+
+            if event.severity as u8 >= EventSeverity::Warning as u8
+                && let Some(parent) = self.parent_map.get(span_id)
+                && self.events.contains_key(parent)
+            {
+                *problems.entry(parent.as_str()).or_default() += 1;
+            }
+
+            // Only leaves can end a deepest chain
+            if !self.children_map.contains_key(span_id) {
+                let depth = self.ancestry(span_id).len();
+                if deepest.is_none_or(|(d, _)| depth > d) {
+                    deepest = Some((depth, span_id.as_str()));
+                }
+            }
+        }
+
+        if let Some((_, leaf)) = deepest {
+            summary.deepest_chain = self.ancestry(leaf).into_iter().rev().map(str::to_string).collect();
+        }
+
+        let mut offenders: Vec<(&str, usize)> = problems.into_iter().collect();
+        offenders.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        summary.top_offenders = offenders
+            .into_iter()
+            .take(top_n)
+            .map(|(span_id, problem_count)| SpanOffender {
+                span_id: span_id.to_string(),
+                name: self
+                    .spans
+                    .get(span_id)
+                    .map(|s| s.name.clone())
+                    .unwrap_or_else(|| self.events[span_id].category.clone()),
+                problem_count,
+            })
+            .collect();
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::span::Span;
+    use crate::{CausalGraph, CorrelationContext, Event, EventSeverity};
+
+    #[test]
+    fn test_summary_counts_and_offenders() {
+        let root = CorrelationContext::new();
+        let mut span = Span::start("economy.process_faction", &root);
+        span.finish();
+        let mut graph = CausalGraph::new();
+        graph.add_event(span.to_event());
+        for _ in 0..3 {
+            let warning = Event::new(EventSeverity::Warning, "Economy".to_string(), "Insolvent".to_string(), span.context.child(), None);
+            graph.add_event(warning);
+        }
+        graph.add_event(Event::new(EventSeverity::Info, "Other".to_string(), "x".to_string(), CorrelationContext::new(), None));
+
+        let summary = graph.trace_summary(&root.trace_id, 5);
+        assert_eq!(summary.event_count, 4);
+        assert_eq!(summary.by_severity["Warning"], 3);
+        assert_eq!(summary.deepest_chain.len(), 2);
+        assert_eq!(summary.top_offenders[0].name, "economy.process_faction");
+        assert_eq!(summary.top_offenders[0].problem_count, 3);
+    }
+}
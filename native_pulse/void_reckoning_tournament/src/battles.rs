@@ -0,0 +1,149 @@
+//! Fleet-vs-fleet matchups: each matchup is fought once per seed at every
+//! grid point, and the outcomes are folded into win rates.
+
+use crate::grid::{self, Axis, GridError};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use void_reckoning_combat::resolve::{resolve_battles, BattleOutcome, BattleSetup};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BattleTournament {
+    pub matchups: Vec<BattleSetup>,
+    /// Applied to each matchup's `BattleSetup` JSON.
+    #[serde(default)]
+    pub grid: Vec<Axis>,
+    /// Battles per matchup and grid point, seeded `seed`, `seed + 1`, ...
+    /// Every grid point fights the same seeds, so differences between
+    /// points come from the parameters rather than the dice.
+    #[serde(default = "one")]
+    pub seeds: u32,
+    #[serde(default)]
+    pub seed: u64,
+}
+
+fn one() -> u32 {
+    1
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FactionStats {
+    pub faction_idx: u8,
+    pub wins: u32,
+    pub win_rate: f32,
+    pub mean_survivors: f32,
+    pub mean_hp_remaining: f32,
+}
+
+/// One matchup at one grid point, over every seed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatchupStats {
+    pub matchup: String,
+    /// Axis name and value, in grid order.
+    pub parameters: Vec<(String, Value)>,
+    pub battles: u32,
+    /// Mutual destruction and time-outs alike.
+    pub draws: u32,
+    pub timeouts: u32,
+    pub mean_turns: f32,
+    /// By faction index.
+    pub factions: Vec<FactionStats>,
+}
+
+impl MatchupStats {
+    fn aggregate(matchup: String, parameters: Vec<(String, Value)>, outcomes: &[BattleOutcome]) -> Self {
+        let battles = outcomes.len().max(1) as f32;
+        let mut factions: BTreeMap<u8, FactionStats> = BTreeMap::new();
+        for outcome in outcomes {
+            for result in &outcome.factions {
+                let stats = factions.entry(result.faction_idx).or_insert(FactionStats {
+                    faction_idx: result.faction_idx,
+                    wins: 0,
+                    win_rate: 0.0,
+                    mean_survivors: 0.0,
+                    mean_hp_remaining: 0.0,
+                });
+                stats.wins += u32::from(outcome.winner == Some(result.faction_idx));
+                stats.mean_survivors += result.survivors as f32 / battles;
+                stats.mean_hp_remaining += result.hp_remaining / battles;
+            }
+        }
+        for stats in factions.values_mut() {
+            stats.win_rate = stats.wins as f32 / battles;
+        }
+        Self {
+            matchup,
+            parameters,
+            battles: outcomes.len() as u32,
+            draws: outcomes.iter().filter(|o| o.winner.is_none()).count() as u32,
+            timeouts: outcomes.iter().filter(|o| o.timed_out).count() as u32,
+            mean_turns: outcomes.iter().map(|o| o.turns as f32).sum::<f32>() / battles,
+            factions: factions.into_values().collect(),
+        }
+    }
+}
+
+impl BattleTournament {
+    /// Fights every battle on up to `workers` threads (0 = one per core).
+    /// Results are in matchup order, then grid order, and depend only on
+    /// the spec.
+    pub fn run(&self, workers: usize) -> Result<Vec<MatchupStats>, GridError> {
+        let points = grid::points(&self.grid);
+        let mut cells = Vec::new();
+        let mut setups = Vec::new();
+        for (index, matchup) in self.matchups.iter().enumerate() {
+            let name = match matchup.name.is_empty() {
+                true => format!("matchup {}", index),
+                false => matchup.name.clone(),
+            };
+            let template = serde_json::to_value(matchup).map_err(|e| GridError::new("", e.to_string()))?;
+            for point in &points {
+                let setup: BattleSetup = serde_json::from_value(grid::apply(&template, &self.grid, point)?)
+                    .map_err(|e| GridError::new("", format!("{}: {}", name, e)))?;
+                setups.extend((0..self.seeds).map(|k| BattleSetup { seed: Some(self.seed.wrapping_add(u64::from(k))), ..setup.clone() }));
+                let parameters = self.grid.iter().map(|axis| axis.name.clone()).zip(point.iter().cloned()).collect();
+                cells.push((name.clone(), parameters));
+            }
+        }
+
+        let outcomes = resolve_battles(&setups, self.seed, workers);
+        let per_cell = self.seeds.max(1) as usize;
+        Ok(cells
+            .into_iter()
+            .zip(outcomes.chunks(per_cell))
+            .map(|((matchup, parameters), outcomes)| MatchupStats::aggregate(matchup, parameters, outcomes))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn stronger_guns_win_more_often() {
+        let tournament: BattleTournament = serde_json::from_value(json!({
+            "matchups": [{
+                "name": "duel", "width": 200.0, "height": 200.0, "max_turns": 200,
+                "units": [
+                    {"id": 0, "name": "a", "faction_idx": 0, "max_hp": 100.0,
+                     "weapons": [{"name": "Gun", "range": 500.0, "damage": 10.0, "accuracy": 0.6}]},
+                    {"id": 1, "name": "b", "faction_idx": 1, "max_hp": 100.0, "x": 50.0,
+                     "weapons": [{"name": "Gun", "range": 500.0, "damage": 10.0, "accuracy": 0.6}]},
+                ],
+            }],
+            "grid": [{"name": "a_damage", "path": "/units/0/weapons/0/damage", "values": [1.0, 50.0]}],
+            "seeds": 8,
+            "seed": 3,
+        }))
+        .unwrap();
+
+        let stats = tournament.run(2).unwrap();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[1].parameters, vec![("a_damage".to_string(), json!(50.0))]);
+        assert!(stats.iter().all(|s| s.battles == 8));
+        assert!(stats[1].factions[0].win_rate > stats[0].factions[0].win_rate);
+        assert_eq!(tournament.run(1).unwrap(), stats);
+    }
+}
@@ -0,0 +1,158 @@
+//! Economy scenarios: a starting economy run through the turn engine for
+//! a number of turns, recording each faction's books every turn.
+
+use crate::grid::{self, Axis, GridError};
+use crate::parallel_map;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use void_reckoning_economy::engine::IncomeEngine;
+use void_reckoning_economy::trade::{TradeRoute, TradeRouteManager};
+use void_reckoning_economy::types::{EconomicNode, GlobalEconomicRules, ResourceState};
+use void_reckoning_orchestrator::{ProductionOrder, TurnEngine, TurnEngines};
+use void_reckoning_pathfinder::{GraphTopology, TopologySnapshot};
+use void_reckoning_shared::ids::FactionId;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EconomyScenario {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub rules: GlobalEconomicRules,
+    pub nodes: Vec<EconomicNode>,
+    #[serde(default)]
+    pub routes: Vec<TradeRoute>,
+    /// Map the trade routes run over; without one routes earn nothing.
+    #[serde(default)]
+    pub topology: Option<TopologySnapshot>,
+    #[serde(default)]
+    pub treasuries: BTreeMap<FactionId, ResourceState>,
+    /// Queued before the first turn and paid for as funds allow.
+    #[serde(default)]
+    pub production: Vec<ProductionOrder>,
+    pub turns: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EconomyTournament {
+    pub scenarios: Vec<EconomyScenario>,
+    /// Applied to each scenario's JSON.
+    #[serde(default)]
+    pub grid: Vec<Axis>,
+}
+
+/// One faction's credits at the end of a turn.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct IncomePoint {
+    pub turn: u64,
+    pub income: f64,
+    pub upkeep: f64,
+    pub net: f64,
+    pub treasury: f64,
+    pub insolvent: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IncomeCurve {
+    pub scenario: String,
+    /// Axis name and value, in grid order.
+    pub parameters: Vec<(String, Value)>,
+    pub faction: FactionId,
+    pub points: Vec<IncomePoint>,
+}
+
+fn credits(resources: &ResourceState) -> f64 {
+    resources.to_floats().0
+}
+
+impl EconomyScenario {
+    /// Runs the scenario; one curve per faction, in faction order.
+    pub fn run(&self, parameters: &[(String, Value)]) -> Vec<IncomeCurve> {
+        let topology = self.topology.clone().map(GraphTopology::from_snapshot).unwrap_or_default();
+        let mut economy = IncomeEngine::new(self.rules.clone());
+        economy.replace_nodes(self.nodes.clone());
+        let mut trade = TradeRouteManager::new();
+        trade.replace_routes(self.routes.clone());
+
+        let mut turns = TurnEngine::new(self.name.clone());
+        for (faction, treasury) in &self.treasuries {
+            turns.set_treasury(faction.clone(), *treasury);
+        }
+        for order in &self.production {
+            turns.queue_production(order.clone());
+        }
+
+        let mut curves: BTreeMap<FactionId, Vec<IncomePoint>> = BTreeMap::new();
+        for _ in 0..self.turns {
+            let engines = TurnEngines { topology: &topology, economy: &mut economy, trade: &mut trade, auditor: None, diplomacy: None, colonies: None, fleets: None };
+            let report = turns.run_turn(engines);
+            for (faction, books) in report.factions {
+                let income = credits(&books.income);
+                let upkeep = credits(&books.upkeep);
+                curves.entry(faction).or_default().push(IncomePoint {
+                    turn: report.turn,
+                    income,
+                    upkeep,
+                    net: income - upkeep,
+                    treasury: credits(&books.treasury),
+                    insolvent: books.is_insolvent,
+                });
+            }
+        }
+        curves
+            .into_iter()
+            .map(|(faction, points)| IncomeCurve { scenario: self.name.clone(), parameters: parameters.to_vec(), faction, points })
+            .collect()
+    }
+}
+
+impl EconomyTournament {
+    /// Runs every scenario at every grid point on up to `workers` threads
+    /// (0 = one per core). Curves are in scenario order, then grid order,
+    /// then faction order.
+    pub fn run(&self, workers: usize) -> Result<Vec<IncomeCurve>, GridError> {
+        let points = grid::points(&self.grid);
+        let mut runs = Vec::new();
+        for scenario in &self.scenarios {
+            let template = serde_json::to_value(scenario).map_err(|e| GridError::new("", e.to_string()))?;
+            for point in &points {
+                let scenario: EconomyScenario = serde_json::from_value(grid::apply(&template, &self.grid, point)?)
+                    .map_err(|e| GridError::new("", format!("{}: {}", scenario.name, e)))?;
+                let parameters: Vec<(String, Value)> = self.grid.iter().map(|axis| axis.name.clone()).zip(point.iter().cloned()).collect();
+                runs.push((scenario, parameters));
+            }
+        }
+        Ok(parallel_map(&runs, workers, |(scenario, parameters)| scenario.run(parameters)).into_iter().flatten().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn income_curves_follow_the_grid() {
+        let tournament: EconomyTournament = serde_json::from_value(json!({
+            "scenarios": [{
+                "name": "frontier",
+                "nodes": [{
+                    "id": "Cadia", "owner_faction": "Imperium", "node_type": "Planet",
+                    "base_income": {"credits": 10_000_000, "minerals": 0, "energy": 0, "research": 0},
+                    "base_upkeep": {"credits": 4_000_000, "minerals": 0, "energy": 0, "research": 0},
+                    "efficiency_scaled": 1_000_000, "modifiers": [],
+                }],
+                "turns": 3,
+            }],
+            "grid": [{"name": "tax", "path": "/nodes/0/base_income/credits", "values": [1.0, 2.0], "scale": true}],
+        }))
+        .unwrap();
+
+        let curves = tournament.run(0).unwrap();
+        assert_eq!(curves.len(), 2);
+        let treasuries: Vec<f64> = curves[0].points.iter().map(|p| p.treasury).collect();
+        assert_eq!(treasuries, vec![6.0, 12.0, 18.0]);
+        assert_eq!(curves[1].points[0].net, 16.0);
+        assert_eq!(curves[1].faction, "Imperium");
+    }
+}
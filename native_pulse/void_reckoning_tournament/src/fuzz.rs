@@ -0,0 +1,482 @@
+//! Invariant fuzzing: random but well-formed campaigns (a map, economic
+//! nodes, trade routes and a battle, built from the registries' buildings
+//! and weapons where there are any) run for a number of turns, with the
+//! auditor's invariants checked after every turn. A panic or a violated
+//! invariant fails the run; failures are shrunk to the smallest campaign
+//! that still fails with the same seed, so each comes back as a short
+//! reproduction.
+
+use crate::parallel_map;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+use void_reckoning_auditor::consistency::InvariantValidator;
+use void_reckoning_auditor::engine::ValidationEngine;
+use void_reckoning_auditor::registry::{Registries, RegistryKind};
+use void_reckoning_auditor::types::ValidationSeverity;
+use void_reckoning_combat::engine::BattleEngine;
+use void_reckoning_combat::resolve::{UnitSetup, WeaponSetup};
+use void_reckoning_combat::UnitId;
+use void_reckoning_economy::engine::IncomeEngine;
+use void_reckoning_economy::trade::{TradeRoute, TradeRouteManager};
+use void_reckoning_economy::types::{EconomicModifier, EconomicNode, GlobalEconomicRules, NodeType, ResourceState, SCALE_FACTOR};
+use void_reckoning_orchestrator::{Action, ProductionOrder, TurnEngine, TurnEngines};
+use void_reckoning_pathfinder::GraphTopology;
+use void_reckoning_shared::rng::SplitMix64;
+
+/// Battle steps run per campaign turn.
+const STEPS_PER_TURN: u32 = 5;
+const FIELD: f32 = 200.0;
+
+/// The size of one fuzzed campaign. With its seed, all a run needs to be
+/// repeated exactly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FuzzCase {
+    pub seed: u64,
+    pub turns: u32,
+    pub systems: usize,
+    pub factions: usize,
+    pub nodes: usize,
+    pub routes: usize,
+    pub units: usize,
+}
+
+impl FuzzCase {
+    /// Cases one step smaller along a single dimension: halved first, then
+    /// one less.
+    fn smaller(&self) -> Vec<FuzzCase> {
+        let mut cases = Vec::new();
+        let mut shrink = |value: usize, least: usize, set: &dyn Fn(&mut FuzzCase, usize)| {
+            for smaller in [least.max(value / 2), value.saturating_sub(1).max(least)] {
+                if smaller < value {
+                    let mut case = *self;
+                    set(&mut case, smaller);
+                    cases.push(case);
+                }
+            }
+        };
+        shrink(self.turns as usize, 1, &|c, v| c.turns = v as u32);
+        shrink(self.units, 0, &|c, v| c.units = v);
+        shrink(self.nodes, 0, &|c, v| c.nodes = v);
+        shrink(self.routes, 0, &|c, v| c.routes = v);
+        shrink(self.systems, 1, &|c, v| c.systems = v);
+        shrink(self.factions, 1, &|c, v| c.factions = v);
+        cases
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FuzzSpec {
+    /// Runs are seeded `seed`, `seed + 1`, ...
+    #[serde(default)]
+    pub seed: u64,
+    #[serde(default = "default_runs")]
+    pub runs: u32,
+    #[serde(default = "default_turns")]
+    pub turns: u32,
+    #[serde(default = "default_systems")]
+    pub systems: usize,
+    #[serde(default = "default_factions")]
+    pub factions: usize,
+    #[serde(default = "default_nodes")]
+    pub nodes: usize,
+    #[serde(default = "default_routes")]
+    pub routes: usize,
+    #[serde(default = "default_units")]
+    pub units: usize,
+    /// Registry contents by kind, e.g. `{"Weapons": {"laser": {"range":
+    /// 40, "damage": 12}}, "Buildings": {"mine": {"yield": {"minerals":
+    /// 5}}}}`. Generated values stand in for empty registries.
+    #[serde(default)]
+    pub registries: HashMap<RegistryKind, Map<String, Value>>,
+    /// Shrink failures to their smallest reproduction.
+    #[serde(default = "yes")]
+    pub minimize: bool,
+}
+
+fn default_runs() -> u32 {
+    16
+}
+
+fn default_turns() -> u32 {
+    20
+}
+
+fn default_systems() -> usize {
+    12
+}
+
+fn default_factions() -> usize {
+    3
+}
+
+fn default_nodes() -> usize {
+    24
+}
+
+fn default_routes() -> usize {
+    8
+}
+
+fn default_units() -> usize {
+    16
+}
+
+fn yes() -> bool {
+    true
+}
+
+impl FuzzSpec {
+    pub fn case(&self, run: u32) -> FuzzCase {
+        FuzzCase {
+            seed: self.seed.wrapping_add(u64::from(run)),
+            turns: self.turns.max(1),
+            systems: self.systems.max(1),
+            factions: self.factions.max(1),
+            nodes: self.nodes,
+            routes: self.routes,
+            units: self.units,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FuzzFailure {
+    pub case: FuzzCase,
+    /// Turn the run failed on.
+    pub turn: u64,
+    /// The panic message, or the violated invariants' messages.
+    pub reasons: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FuzzReport {
+    pub runs: u32,
+    /// Campaign turns simulated, shrinking included.
+    pub turns: u64,
+    /// In seed order; minimized when the spec asks for it.
+    pub failures: Vec<FuzzFailure>,
+}
+
+/// Runs a `FuzzSpec` against the auditor's invariants and any added with
+/// `with_invariant`.
+pub struct Fuzzer {
+    spec: FuzzSpec,
+    registries: Arc<Registries>,
+    invariants: Vec<Arc<dyn InvariantValidator>>,
+}
+
+impl Fuzzer {
+    pub fn new(spec: FuzzSpec) -> Self {
+        let mut registries = Registries::new();
+        for (kind, data) in &spec.registries {
+            registries.replace(*kind, data.clone());
+        }
+        Self { spec, registries: Arc::new(registries), invariants: Vec::new() }
+    }
+
+    pub fn with_invariant(mut self, invariant: Arc<dyn InvariantValidator>) -> Self {
+        self.invariants.push(invariant);
+        self
+    }
+
+    pub fn spec(&self) -> &FuzzSpec {
+        &self.spec
+    }
+
+    /// Every run on up to `workers` threads (0 = one per core); the report
+    /// depends only on the spec.
+    pub fn run(&self, workers: usize) -> FuzzReport {
+        let cases: Vec<FuzzCase> = (0..self.spec.runs).map(|run| self.spec.case(run)).collect();
+        let results = parallel_map(&cases, workers, |case| {
+            let mut turns = 0;
+            let failure = self.run_case(case, &mut turns).map(|failure| match self.spec.minimize {
+                true => self.minimize(failure, &mut turns),
+                false => failure,
+            });
+            (turns, failure)
+        });
+        FuzzReport {
+            runs: self.spec.runs,
+            turns: results.iter().map(|(turns, _)| turns).sum(),
+            failures: results.into_iter().filter_map(|(_, failure)| failure).collect(),
+        }
+    }
+
+    /// Runs one campaign; None if it held every invariant to the end.
+    /// Adds the turns simulated to `turns`.
+    pub fn run_case(&self, case: &FuzzCase, turns: &mut u64) -> Option<FuzzFailure> {
+        let turn = Cell::new(0);
+        let result = panic::catch_unwind(AssertUnwindSafe(|| self.campaign(case, &turn)));
+        *turns += turn.get();
+        let reasons = match result {
+            Ok(reasons) => reasons,
+            Err(payload) => vec![panic_message(payload.as_ref())],
+        };
+        (!reasons.is_empty()).then(|| FuzzFailure { case: *case, turn: turn.get(), reasons })
+    }
+
+    /// The smallest case still failing with `failure`'s seed, found by
+    /// shrinking one dimension at a time while the run keeps failing.
+    pub fn minimize(&self, failure: FuzzFailure, turns: &mut u64) -> FuzzFailure {
+        let mut smallest = failure;
+        smallest.case.turns = smallest.case.turns.min(smallest.turn.max(1) as u32);
+        'shrink: loop {
+            for case in smallest.case.smaller() {
+                if let Some(failure) = self.run_case(&case, turns) {
+                    smallest = failure;
+                    continue 'shrink;
+                }
+            }
+            return smallest;
+        }
+    }
+
+    /// Builds the campaign and plays it, recording the turn in progress in
+    /// `turn`; returns the first turn's violations.
+    fn campaign(&self, case: &FuzzCase, turn: &Cell<u64>) -> Vec<String> {
+        let mut rng = SplitMix64::new(case.seed);
+        let mut auditor = ValidationEngine::new(Arc::clone(&self.registries));
+        for invariant in &self.invariants {
+            auditor.add_invariant(Arc::clone(invariant));
+        }
+        let systems: Vec<String> = (0..case.systems).map(|i| format!("S{}", i)).collect();
+        let factions: Vec<String> = (0..case.factions).map(|i| format!("F{}", i)).collect();
+
+        let mut topology = GraphTopology::new();
+        for (i, system) in systems.iter().enumerate() {
+            topology.add_node(system.clone(), None);
+            if systems.len() > 1 {
+                let next = &systems[(i + 1) % systems.len()];
+                let weight = 1.0 + rng.below(5) as f32;
+                topology.add_edge(system, next, weight);
+                topology.add_edge(next, system, weight);
+            }
+        }
+        for _ in 0..systems.len() / 2 {
+            let (from, to) = (rng.pick(&systems).cloned().unwrap_or_default(), rng.pick(&systems).cloned().unwrap_or_default());
+            topology.add_edge(&from, &to, 1.0 + rng.below(9) as f32);
+        }
+
+        let mut economy = IncomeEngine::new(GlobalEconomicRules::default());
+        for i in 0..case.nodes {
+            economy.add_node(self.node(&mut rng, i, &systems, &factions));
+        }
+        let mut trade = TradeRouteManager::new();
+        for _ in 0..case.routes {
+            trade.add_route(TradeRoute {
+                from: rng.pick(&systems).cloned().unwrap_or_default(),
+                to: rng.pick(&systems).cloned().unwrap_or_default(),
+                base_value: credits(rng.below(50) as i128),
+                efficiency_scaled: 0,
+                modifiers: Vec::new(),
+            });
+        }
+        let mut battle = BattleEngine::new(FIELD, FIELD);
+        battle.set_seed(case.seed);
+        for id in 0..case.units {
+            battle.add_unit(self.unit(&mut rng, id as u32, case.factions).build());
+        }
+
+        let mut turns = TurnEngine::new(format!("fuzz-{}", case.seed));
+        for faction in &factions {
+            turns.set_treasury(faction.as_str().into(), credits(rng.below(500) as i128));
+        }
+        for t in 1..=u64::from(case.turns) {
+            turn.set(t);
+            let faction = rng.pick(&factions).cloned().unwrap_or_default();
+            turns.queue_production(ProductionOrder {
+                id: format!("order-{}", t),
+                faction: faction.as_str().into(),
+                item: "Frigate".to_string(),
+                cost: credits(rng.below(200) as i128),
+            });
+            if rng.below(3) == 0 {
+                turns.submit(Action::SetPolicy {
+                    faction: faction.as_str().into(),
+                    policy: "fuzz".to_string(),
+                    multiplier_scaled: Some(rng.below(2 * SCALE_FACTOR as u64) as i128),
+                });
+            }
+            turns.run_turn(TurnEngines {
+                topology: &topology,
+                economy: &mut economy,
+                trade: &mut trade,
+                auditor: Some(&mut auditor),
+                diplomacy: None,
+                colonies: None,
+                fleets: None,
+            });
+            for _ in 0..STEPS_PER_TURN {
+                battle.step();
+            }
+
+            let state = json!({
+                "turn": t,
+                "units": battle.state.units,
+                "nodes": economy.nodes(),
+                "treasuries": turns.treasuries(),
+            });
+            let violations: Vec<String> = auditor
+                .validate_state(&state)
+                .into_iter()
+                .filter(|r| matches!(r.severity, ValidationSeverity::Error | ValidationSeverity::Critical))
+                .map(|r| format!("[Rule: {}] {}", r.rule_name, r.message))
+                .collect();
+            if !violations.is_empty() {
+                return violations;
+            }
+        }
+        Vec::new()
+    }
+
+    /// A node at a system (or, past the map's size, off it) with the
+    /// combined yield and upkeep of one to three registry buildings, or
+    /// random books when there are none.
+    fn node(&self, rng: &mut SplitMix64, index: usize, systems: &[String], factions: &[String]) -> EconomicNode {
+        let id = match systems.get(index) {
+            Some(system) => system.clone(),
+            None => format!("N{}", index),
+        };
+        let buildings: Vec<&Value> = self.registries.get(RegistryKind::Buildings).values().collect();
+        let (mut income, mut upkeep) = (ResourceState::default(), ResourceState::default());
+        if buildings.is_empty() {
+            income = ResourceState::new(rng.below(100) as f64, rng.below(20) as f64, rng.below(20) as f64, 0.0);
+            upkeep = ResourceState::new(rng.below(60) as f64, rng.below(10) as f64, rng.below(10) as f64, 0.0);
+        } else {
+            for _ in 0..=rng.below(3) {
+                let building = buildings[rng.below(buildings.len() as u64) as usize];
+                income.add(&resources(building, "yield"));
+                upkeep.add(&resources(building, "upkeep"));
+            }
+        }
+        let mut modifiers = Vec::new();
+        if rng.below(4) == 0 {
+            modifiers.push(EconomicModifier {
+                name: "fuzz".to_string(),
+                multiplier_scaled: rng.below(2 * SCALE_FACTOR as u64) as i128,
+                flat_bonus: ResourceState::default(),
+            });
+        }
+        EconomicNode {
+            id: id.into(),
+            owner_faction: rng.pick(factions).cloned().unwrap_or_default().into(),
+            node_type: NodeType::Planet,
+            base_income: income,
+            base_upkeep: upkeep,
+            efficiency_scaled: rng.below(SCALE_FACTOR as u64 + 1) as i128,
+            modifiers,
+        }
+    }
+
+    /// A unit armed from the weapons registry, or with a random gun when
+    /// it is empty.
+    fn unit(&self, rng: &mut SplitMix64, id: u32, factions: usize) -> UnitSetup {
+        let templates: Vec<(&String, &Value)> = self.registries.get(RegistryKind::Weapons).iter().collect();
+        let weapons = (0..=rng.below(2))
+            .filter_map(|_| match templates.is_empty() {
+                true => Some(WeaponSetup {
+                    name: "Gun".to_string(),
+                    weapon_type: void_reckoning_combat::WeaponType::Kinetic,
+                    range: 10.0 + rng.below(300) as f32,
+                    damage: rng.below(40) as f32,
+                    accuracy: rng.below(101) as f32 / 100.0,
+                    cooldown: 1.0 + rng.below(3) as f32,
+                    aoe_radius: 0.0,
+                }),
+                false => {
+                    let (name, template) = templates[rng.below(templates.len() as u64) as usize];
+                    let mut template = template.clone();
+                    if let Value::Object(fields) = &mut template {
+                        fields.entry("name").or_insert_with(|| Value::String(name.clone()));
+                    }
+                    serde_json::from_value(template).ok()
+                }
+            })
+            .collect();
+        UnitSetup {
+            id: UnitId(id),
+            name: format!("unit-{}", id),
+            faction_idx: (id as usize % factions) as u8,
+            max_hp: 10.0 + rng.below(190) as f32,
+            x: rng.below(FIELD as u64) as f32,
+            y: rng.below(FIELD as u64) as f32,
+            weapons,
+            speed: rng.below(20) as f32,
+            evasion: rng.below(50) as f32 / 100.0,
+            shields: rng.below(50) as f32,
+            armor: rng.below(10) as f32,
+            cover: void_reckoning_combat::CoverType::None,
+        }
+    }
+}
+
+fn credits(amount: i128) -> ResourceState {
+    ResourceState { credits: amount * SCALE_FACTOR, ..ResourceState::default() }
+}
+
+fn resources(building: &Value, key: &str) -> ResourceState {
+    let amount = |name: &str| building.get(key).and_then(|r| r.get(name)).and_then(Value::as_f64).unwrap_or(0.0);
+    ResourceState::new(amount("credits"), amount("minerals"), amount("energy"), amount("research"))
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    let message = match (payload.downcast_ref::<&str>(), payload.downcast_ref::<String>()) {
+        (Some(message), _) => message.to_string(),
+        (_, Some(message)) => message.clone(),
+        _ => "unknown panic".to_string(),
+    };
+    format!("panic: {}", message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use void_reckoning_auditor::types::{ValidationCategory, ValidationResult};
+
+    /// Fails any state with more than three units.
+    struct Crowded;
+
+    impl InvariantValidator for Crowded {
+        fn validate(&self, state: &Value) -> ValidationResult {
+            let units = state["units"].as_array().map_or(0, Vec::len);
+            ValidationResult {
+                category: ValidationCategory::CrossSystem,
+                severity: if units > 3 { ValidationSeverity::Critical } else { ValidationSeverity::Info },
+                entity_id: "global".into(),
+                message: format!("{} units", units),
+                rule_name: self.name().to_string(),
+                file_path: None,
+                timestamp: 0,
+            }
+        }
+
+        fn name(&self) -> &str {
+            "crowded"
+        }
+
+        fn description(&self) -> &str {
+            "At most three units"
+        }
+    }
+
+    #[test]
+    fn failures_shrink_to_the_smallest_reproduction() {
+        let spec: FuzzSpec = serde_json::from_value(json!({"seed": 7, "runs": 4, "turns": 6})).unwrap();
+        let clean = Fuzzer::new(spec.clone()).run(2);
+        assert!(clean.failures.is_empty(), "{:?}", clean.failures);
+        assert_eq!(clean.turns, 4 * 6);
+
+        let report = Fuzzer::new(spec).with_invariant(Arc::new(Crowded)).run(2);
+        assert_eq!(report.failures.len(), 4);
+        let failure = &report.failures[0];
+        assert_eq!(failure.reasons, vec!["[Rule: crowded] 4 units"]);
+        assert_eq!(
+            failure.case,
+            FuzzCase { seed: 7, turns: 1, systems: 1, factions: 1, nodes: 0, routes: 0, units: 4 }
+        );
+    }
+}
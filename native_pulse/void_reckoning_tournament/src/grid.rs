@@ -0,0 +1,148 @@
+//! Parameter grids: named axes that overwrite (or scale) values in a JSON
+//! template, so one matchup or scenario fans out into every combination
+//! of balance knobs.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+
+/// One balance knob and the values to try for it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Axis {
+    pub name: String,
+    /// JSON pointer into the template, e.g. `/units/0/max_hp`. A `*`
+    /// segment matches every element of an array or object, so
+    /// `/units/*/weapons/*/damage` reaches every gun in the battle.
+    pub path: String,
+    pub values: Vec<Value>,
+    /// Multiply the numbers at `path` by each value instead of replacing
+    /// them.
+    #[serde(default)]
+    pub scale: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GridError {
+    pub axis: String,
+    pub message: String,
+}
+
+impl fmt::Display for GridError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.axis.is_empty() {
+            true => write!(f, "{}", self.message),
+            false => write!(f, "axis '{}': {}", self.axis, self.message),
+        }
+    }
+}
+
+impl std::error::Error for GridError {}
+
+impl GridError {
+    pub(crate) fn new(axis: &str, message: impl Into<String>) -> Self {
+        Self { axis: axis.to_string(), message: message.into() }
+    }
+}
+
+/// Every combination of axis values, the last axis varying fastest. No
+/// axes give the single empty point.
+pub fn points(axes: &[Axis]) -> Vec<Vec<Value>> {
+    axes.iter().fold(vec![Vec::new()], |points, axis| {
+        points
+            .iter()
+            .flat_map(|point| {
+                axis.values.iter().map(move |value| {
+                    let mut point = point.clone();
+                    point.push(value.clone());
+                    point
+                })
+            })
+            .collect()
+    })
+}
+
+/// `template` with each axis set to (or scaled by) its value in `point`.
+pub fn apply(template: &Value, axes: &[Axis], point: &[Value]) -> Result<Value, GridError> {
+    let mut value = template.clone();
+    for (axis, knob) in axes.iter().zip(point) {
+        let segments: Vec<String> = match axis.path.strip_prefix('/') {
+            Some(rest) => rest.split('/').map(|s| s.replace("~1", "/").replace("~0", "~")).collect(),
+            None => return Err(GridError::new(&axis.name, "path must start with '/'")),
+        };
+        let matched = visit(&mut value, &segments, &mut |target| match axis.scale {
+            true => scale(target, knob),
+            false => {
+                *target = knob.clone();
+                Ok(())
+            }
+        })
+        .map_err(|message| GridError::new(&axis.name, message))?;
+        if matched == 0 {
+            return Err(GridError::new(&axis.name, format!("'{}' matches nothing", axis.path)));
+        }
+    }
+    Ok(value)
+}
+
+/// Calls `edit` on everything `segments` reaches; returns how many.
+fn visit(value: &mut Value, segments: &[String], edit: &mut dyn FnMut(&mut Value) -> Result<(), String>) -> Result<usize, String> {
+    let Some((segment, rest)) = segments.split_first() else {
+        edit(value)?;
+        return Ok(1);
+    };
+    let children: Vec<&mut Value> = match (value, segment.as_str()) {
+        (Value::Array(items), "*") => items.iter_mut().collect(),
+        (Value::Object(map), "*") => map.values_mut().collect(),
+        (Value::Array(items), index) => index.parse::<usize>().ok().and_then(|i| items.get_mut(i)).into_iter().collect(),
+        (Value::Object(map), key) => map.get_mut(key).into_iter().collect(),
+        _ => Vec::new(),
+    };
+    let mut matched = 0;
+    for child in children {
+        matched += visit(child, rest, edit)?;
+    }
+    Ok(matched)
+}
+
+/// Integers stay integers (rounded), so fixed-point amounts scale too.
+fn scale(target: &mut Value, factor: &Value) -> Result<(), String> {
+    let factor = factor.as_f64().ok_or("scale values must be numbers")?;
+    *target = match &*target {
+        Value::Number(n) if n.is_f64() => Value::from(n.as_f64().unwrap_or_default() * factor),
+        Value::Number(n) => Value::from((n.as_f64().unwrap_or_default() * factor).round() as i64),
+        other => return Err(format!("can't scale {}", other)),
+    };
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn axes_fan_out_and_reach_through_wildcards() {
+        let axes: Vec<Axis> = serde_json::from_value(json!([
+            {"name": "hp", "path": "/units/0/max_hp", "values": [100.0, 200.0]},
+            {"name": "damage", "path": "/units/*/weapons/*/damage", "values": [0.5, 2], "scale": true},
+        ]))
+        .unwrap();
+        let template = json!({"units": [
+            {"max_hp": 50.0, "weapons": [{"damage": 10.0}]},
+            {"max_hp": 50.0, "weapons": [{"damage": 4.0}, {"damage": 6}]},
+        ]});
+
+        let points = points(&axes);
+        assert_eq!(points, vec![json!([100.0, 0.5]), json!([100.0, 2]), json!([200.0, 0.5]), json!([200.0, 2])]
+            .into_iter()
+            .map(|p| p.as_array().unwrap().clone())
+            .collect::<Vec<_>>());
+        let applied = apply(&template, &axes, &points[1]).unwrap();
+        assert_eq!(applied["units"][0]["max_hp"], json!(100.0));
+        assert_eq!(applied["units"][1]["weapons"][0]["damage"], json!(8.0));
+        assert_eq!(applied["units"][1]["weapons"][1]["damage"], json!(12));
+
+        let missing = Axis { name: "speed".into(), path: "/units/5/speed".into(), values: vec![json!(1)], scale: false };
+        assert!(apply(&template, &[missing], &[json!(1)]).is_err());
+    }
+}
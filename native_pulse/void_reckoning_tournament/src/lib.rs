@@ -0,0 +1,53 @@
+//! Balance harness: fleet-vs-fleet matchups and economy scenarios run at
+//! scale. A spec fans out over a seed sweep and a parameter grid, every
+//! run goes to a pool of worker threads, and the results come back
+//! aggregated (win rates per matchup, income curves per faction) as
+//! tables ready for CSV or Arrow. The same harness fuzzes whole campaigns
+//! against the auditor's invariants.
+
+pub mod battles;
+pub mod economy;
+pub mod fuzz;
+pub mod grid;
+pub mod table;
+
+pub use battles::{BattleTournament, FactionStats, MatchupStats};
+pub use economy::{EconomyScenario, EconomyTournament, IncomeCurve, IncomePoint};
+pub use fuzz::{FuzzCase, FuzzFailure, FuzzReport, FuzzSpec, Fuzzer};
+pub use grid::{Axis, GridError};
+pub use table::Table;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Version of the tournament spec and result layouts; bumped on
+/// incompatible changes.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Maps `run` over `items` on up to `workers` threads (0 = one per core);
+/// results come back in item order whatever the thread count.
+pub(crate) fn parallel_map<T: Sync, R: Send>(items: &[T], workers: usize, run: impl Fn(&T) -> R + Sync) -> Vec<R> {
+    let workers = match workers {
+        0 => std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+        n => n,
+    }
+    .min(items.len())
+    .max(1);
+
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<R>>> = Mutex::new((0..items.len()).map(|_| None).collect());
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = items.get(index) else { break };
+                let result = run(item);
+                if let Ok(mut results) = results.lock() {
+                    results[index] = Some(result);
+                }
+            });
+        }
+    });
+    results.into_inner().unwrap_or_default().into_iter().flatten().collect()
+}
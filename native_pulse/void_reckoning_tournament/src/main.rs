@@ -0,0 +1,68 @@
+use std::io::Write;
+use void_reckoning_tournament::{BattleTournament, EconomyTournament, FuzzSpec, Fuzzer, Table};
+
+const USAGE: &str = "usage: void-reckoning-tournament (battles|economy|fuzz) SPEC.json [--workers N] [--out FILE] [--json]";
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let (mut kind, mut spec, mut out) = (None, None, None);
+    let (mut workers, mut as_json) = (0, false);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--workers" => match args.next().and_then(|n| n.parse().ok()) {
+                Some(n) => workers = n,
+                None => exit("--workers needs a number"),
+            },
+            "--out" => match args.next() {
+                Some(path) => out = Some(path),
+                None => exit("--out needs a path"),
+            },
+            "--json" => as_json = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return;
+            }
+            _ if kind.is_none() => kind = Some(arg),
+            _ if spec.is_none() => spec = Some(arg),
+            other => exit(&format!("unknown argument '{}'", other)),
+        }
+    }
+    let (Some(kind), Some(spec)) = (kind, spec) else { exit(USAGE) };
+    let text = std::fs::read_to_string(&spec).unwrap_or_else(|e| exit(&format!("cannot read {}: {}", spec, e)));
+
+    let (json, table) = match kind.as_str() {
+        "battles" => {
+            let tournament: BattleTournament = serde_json::from_str(&text).unwrap_or_else(|e| exit(&format!("{}: {}", spec, e)));
+            let stats = tournament.run(workers).unwrap_or_else(|e| exit(&e.to_string()));
+            (serde_json::to_string_pretty(&stats), Table::battles(&stats))
+        }
+        "economy" => {
+            let tournament: EconomyTournament = serde_json::from_str(&text).unwrap_or_else(|e| exit(&format!("{}: {}", spec, e)));
+            let curves = tournament.run(workers).unwrap_or_else(|e| exit(&e.to_string()));
+            (serde_json::to_string_pretty(&curves), Table::income(&curves))
+        }
+        "fuzz" => {
+            let spec: FuzzSpec = serde_json::from_str(&text).unwrap_or_else(|e| exit(&format!("{}: {}", spec, e)));
+            let report = Fuzzer::new(spec).run(workers);
+            (serde_json::to_string_pretty(&report), Table::fuzz(&report))
+        }
+        other => exit(&format!("unknown tournament '{}'; expected battles, economy or fuzz", other)),
+    };
+    let output = match as_json {
+        true => json.unwrap_or_else(|e| exit(&e.to_string())),
+        false => table.to_csv(),
+    };
+
+    let written = match &out {
+        Some(path) => std::fs::write(path, output),
+        None => std::io::stdout().write_all(output.as_bytes()),
+    };
+    if let Err(e) = written {
+        exit(&format!("cannot write results: {}", e));
+    }
+}
+
+fn exit(message: &str) -> ! {
+    eprintln!("void-reckoning-tournament: {}", message);
+    std::process::exit(2);
+}
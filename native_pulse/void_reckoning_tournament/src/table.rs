@@ -0,0 +1,157 @@
+//! Results as flat tables, one row per faction per result, with a column
+//! per grid axis, ready for CSV or a dataframe.
+
+use crate::battles::MatchupStats;
+use crate::economy::IncomeCurve;
+use crate::fuzz::FuzzReport;
+use serde_json::{json, Value};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Table {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Value>>,
+}
+
+/// Axis names across every result, first-seen order.
+fn parameter_columns<'a>(results: impl Iterator<Item = &'a [(String, Value)]>) -> Vec<String> {
+    let mut columns: Vec<String> = Vec::new();
+    for parameters in results {
+        for (name, _) in parameters {
+            if !columns.contains(name) {
+                columns.push(name.clone());
+            }
+        }
+    }
+    columns
+}
+
+fn parameter_cells(columns: &[String], parameters: &[(String, Value)]) -> Vec<Value> {
+    columns
+        .iter()
+        .map(|column| parameters.iter().find(|(name, _)| name == column).map_or(Value::Null, |(_, value)| value.clone()))
+        .collect()
+}
+
+impl Table {
+    /// One row per matchup, grid point and faction.
+    pub fn battles(stats: &[MatchupStats]) -> Self {
+        let parameters = parameter_columns(stats.iter().map(|s| s.parameters.as_slice()));
+        let mut columns = vec!["matchup".to_string()];
+        columns.extend(parameters.iter().cloned());
+        columns.extend(
+            ["faction_idx", "battles", "wins", "win_rate", "draws", "timeouts", "mean_turns", "mean_survivors", "mean_hp_remaining"]
+                .map(String::from),
+        );
+        let rows = stats
+            .iter()
+            .flat_map(|s| {
+                let cells = parameter_cells(&parameters, &s.parameters);
+                s.factions.iter().map(move |f| {
+                    let mut row = vec![json!(s.matchup)];
+                    row.extend(cells.iter().cloned());
+                    row.extend([
+                        json!(f.faction_idx),
+                        json!(s.battles),
+                        json!(f.wins),
+                        json!(f.win_rate),
+                        json!(s.draws),
+                        json!(s.timeouts),
+                        json!(s.mean_turns),
+                        json!(f.mean_survivors),
+                        json!(f.mean_hp_remaining),
+                    ]);
+                    row
+                })
+            })
+            .collect();
+        Self { columns, rows }
+    }
+
+    /// One row per scenario, grid point, faction and turn.
+    pub fn income(curves: &[IncomeCurve]) -> Self {
+        let parameters = parameter_columns(curves.iter().map(|c| c.parameters.as_slice()));
+        let mut columns = vec!["scenario".to_string()];
+        columns.extend(parameters.iter().cloned());
+        columns.extend(["faction", "turn", "income", "upkeep", "net", "treasury", "insolvent"].map(String::from));
+        let rows = curves
+            .iter()
+            .flat_map(|c| {
+                let cells = parameter_cells(&parameters, &c.parameters);
+                c.points.iter().map(move |p| {
+                    let mut row = vec![json!(c.scenario)];
+                    row.extend(cells.iter().cloned());
+                    row.extend([
+                        json!(c.faction),
+                        json!(p.turn),
+                        json!(p.income),
+                        json!(p.upkeep),
+                        json!(p.net),
+                        json!(p.treasury),
+                        json!(p.insolvent),
+                    ]);
+                    row
+                })
+            })
+            .collect();
+        Self { columns, rows }
+    }
+
+    /// One row per failed fuzz run: its minimized case, the turn it failed
+    /// on and why.
+    pub fn fuzz(report: &FuzzReport) -> Self {
+        let columns = ["seed", "turns", "systems", "factions", "nodes", "routes", "units", "failed_turn", "reasons"].map(String::from).to_vec();
+        let rows = report
+            .failures
+            .iter()
+            .map(|f| {
+                let case = &f.case;
+                vec![
+                    json!(case.seed),
+                    json!(case.turns),
+                    json!(case.systems),
+                    json!(case.factions),
+                    json!(case.nodes),
+                    json!(case.routes),
+                    json!(case.units),
+                    json!(f.turn),
+                    json!(f.reasons.join("; ")),
+                ]
+            })
+            .collect();
+        Self { columns, rows }
+    }
+
+    /// RFC 4180 CSV with a header row; nulls are empty cells.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::new();
+        let mut line = |cells: Vec<String>| {
+            csv.push_str(&cells.join(","));
+            csv.push_str("\r\n");
+        };
+        line(self.columns.iter().map(|c| csv_cell(c)).collect());
+        for row in &self.rows {
+            line(
+                row.iter()
+                    .map(|value| match value {
+                        Value::Null => String::new(),
+                        Value::String(s) => csv_cell(s),
+                        other => csv_cell(&other.to_string()),
+                    })
+                    .collect(),
+            );
+        }
+        csv
+    }
+
+    /// The values of column `index`, top to bottom.
+    pub fn column(&self, index: usize) -> impl Iterator<Item = &Value> + '_ {
+        self.rows.iter().map(move |row| &row[index])
+    }
+}
+
+fn csv_cell(text: &str) -> String {
+    match text.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", text.replace('"', "\"\"")),
+        false => text.to_string(),
+    }
+}
@@ -0,0 +1,82 @@
+//! wasm-bindgen wrapper for running battle previews in the browser viewer.
+//! Built without the `observability` and `parallel` features, so neither
+//! pyo3 nor threads are involved. Structured data crosses as JSON strings,
+//! in the same shapes the C API uses.
+
+use serde::Serialize;
+use void_reckoning_combat::engine::BattleEngine;
+use void_reckoning_combat::resolve::{resolve_battles, BattleSetup, DEFAULT_MAX_TURNS};
+use void_reckoning_pathfinder::{GraphTopology, TopologySnapshot};
+use wasm_bindgen::prelude::*;
+
+/// A battle stepped from JavaScript.
+#[wasm_bindgen]
+pub struct BattlePreview {
+    engine: BattleEngine,
+    name: String,
+    max_turns: u32,
+}
+
+#[wasm_bindgen]
+impl BattlePreview {
+    /// Builds a battle from a JSON `BattleSetup`; `seed` is used unless the
+    /// setup carries its own.
+    #[wasm_bindgen(constructor)]
+    pub fn new(setup_json: &str, seed: u64) -> Result<BattlePreview, JsError> {
+        let setup: BattleSetup = serde_json::from_str(setup_json)?;
+        Ok(Self {
+            engine: setup.build(seed),
+            name: setup.name.clone(),
+            max_turns: setup.max_turns.unwrap_or(DEFAULT_MAX_TURNS),
+        })
+    }
+
+    /// Advances one turn; false once at most one faction is left.
+    pub fn step(&mut self) -> bool {
+        self.engine.step()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn turn(&self) -> u32 {
+        self.engine.state.turn
+    }
+
+    /// The full `BattleState` as JSON, for drawing the current frame.
+    pub fn state(&self) -> Result<String, JsError> {
+        Ok(serde_json::to_string(&self.engine.state)?)
+    }
+
+    /// Steps until the battle ends or `max_turns` (default: the setup's
+    /// limit) is reached; returns the `BattleOutcome` as JSON.
+    pub fn run(&mut self, max_turns: Option<u32>) -> Result<String, JsError> {
+        let outcome = self.engine.run_to_completion(&self.name, max_turns.unwrap_or(self.max_turns));
+        Ok(serde_json::to_string(&outcome)?)
+    }
+}
+
+/// Resolves a JSON array of `BattleSetup`s one after another and returns
+/// the `BattleOutcome`s as JSON, in setup order.
+#[wasm_bindgen(js_name = resolveBattles)]
+pub fn resolve_battles_json(setups_json: &str, seed: u64) -> Result<String, JsError> {
+    let setups: Vec<BattleSetup> = serde_json::from_str(setups_json)?;
+    Ok(serde_json::to_string(&resolve_battles(&setups, seed, 1))?)
+}
+
+#[derive(Serialize)]
+struct Route {
+    path: Vec<String>,
+    cost: f32,
+}
+
+/// Finds a route over a JSON `TopologySnapshot`; returns
+/// `{"path": [...], "cost": ...}` or null if the nodes are not connected.
+#[wasm_bindgen(js_name = findPath)]
+pub fn find_path(snapshot_json: &str, start: &str, end: &str, profile: Option<String>) -> Result<Option<String>, JsError> {
+    let snapshot: TopologySnapshot = serde_json::from_str(snapshot_json)?;
+    let topology = GraphTopology::from_snapshot(snapshot);
+    topology
+        .find_path(start, end, profile)
+        .map(|(path, cost)| serde_json::to_string(&Route { path, cost }))
+        .transpose()
+        .map_err(JsError::from)
+}
@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+use void_reckoning_combat::resolve::WeaponSetup;
+use void_reckoning_combat::CoverType;
+use void_reckoning_economy::types::{NodeType, ResourceState, SCALE_FACTOR};
+use void_reckoning_shared::ecs::Entity;
+use void_reckoning_shared::ids::{FactionId, SystemId};
+
+/// The id other engines know the entity by: its economy node id, or the
+/// name its combat unit gets.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Name(pub String);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Owner(pub FactionId);
+
+/// The system the entity is in; battles are fought per system.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Location(pub SystemId);
+
+/// What an entity can take before it is destroyed. Damage taken in battle
+/// stays until something repairs it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Health {
+    pub hp: f32,
+    pub max_hp: f32,
+    #[serde(default)]
+    pub shields: f32,
+    #[serde(default)]
+    pub max_shields: f32,
+    #[serde(default)]
+    pub armor: f32,
+}
+
+impl Health {
+    pub fn new(max_hp: f32) -> Self {
+        Self { hp: max_hp, max_hp, shields: 0.0, max_shields: 0.0, armor: 0.0 }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Weapons(pub Vec<WeaponSetup>);
+
+/// How an entity moves and hides in battle; without one it stands still
+/// in the open.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Maneuver {
+    #[serde(default)]
+    pub speed: f32,
+    #[serde(default)]
+    pub evasion: f32,
+    #[serde(default = "no_cover")]
+    pub cover: CoverType,
+}
+
+fn no_cover() -> CoverType {
+    CoverType::None
+}
+
+/// What an entity earns and costs each turn, as an economy node.
+/// Modifiers stay with the economy engine.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Yield {
+    pub node_type: NodeType,
+    #[serde(default)]
+    pub income: ResourceState,
+    #[serde(default)]
+    pub upkeep: ResourceState,
+    #[serde(default = "full_efficiency")]
+    pub efficiency_scaled: i128,
+}
+
+fn full_efficiency() -> i128 {
+    SCALE_FACTOR
+}
+
+/// One entity and its components, as spawned from or read out to JSON.
+/// Components left out aren't attached.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EntityRecord {
+    /// Filled in when reading an entity; ignored when spawning.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entity: Option<Entity>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<Name>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<Owner>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<Location>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub health: Option<Health>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weapons: Option<Weapons>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub maneuver: Option<Maneuver>,
+    #[serde(rename = "yield", default, skip_serializing_if = "Option::is_none")]
+    pub yield_: Option<Yield>,
+}
+
+/// Serializable copy of a `CampaignWorld`, entities in index order.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorldSnapshot {
+    pub entities: Vec<EntityRecord>,
+}
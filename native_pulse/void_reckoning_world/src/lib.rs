@@ -0,0 +1,19 @@
+//! Campaign entities shared by the combat and economy layers. Units,
+//! fleets' ships and planets are entities in one `World` (from
+//! `void_reckoning_shared::ecs`) carrying whichever components apply:
+//! `Health` and `Weapons` make an entity fight, `Yield` makes it pay, and
+//! a fortified planet has both. Battles are built from and written back
+//! to the entities at a system, and the economy's nodes are synced from
+//! the entities with a `Yield`, so neither layer keeps a copy of the other.
+
+pub mod components;
+pub mod world;
+
+pub use components::{EntityRecord, Health, Location, Maneuver, Name, Owner, Weapons, WorldSnapshot, Yield};
+pub use world::{CampaignWorld, WorldBattle};
+pub use void_reckoning_shared::ecs::{Entity, World};
+
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Version of the `WorldSnapshot`/`EntityRecord` layouts; bumped on
+/// incompatible changes.
+pub const SCHEMA_VERSION: u32 = 1;
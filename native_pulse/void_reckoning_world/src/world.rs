@@ -0,0 +1,311 @@
+use crate::components::{EntityRecord, Health, Location, Maneuver, Name, Owner, Weapons, WorldSnapshot, Yield};
+use serde::{Deserialize, Serialize};
+use void_reckoning_combat::engine::BattleEngine;
+use void_reckoning_combat::resolve::UnitSetup;
+use void_reckoning_combat::targeting::Hostility;
+use void_reckoning_combat::CombatUnit;
+use void_reckoning_economy::engine::IncomeEngine;
+use void_reckoning_economy::types::{EconomicNode, NodeType};
+use void_reckoning_shared::desync;
+use void_reckoning_shared::ecs::{Component, Entity, World};
+use void_reckoning_shared::ids::{FactionId, SystemId, UnitId};
+
+/// Side of the square battlefield built for a system.
+const BATTLE_SIZE: f32 = 1000.0;
+/// Gap between entities in a faction's battle line.
+const LINE_SPACING: f32 = 40.0;
+const LINE_LENGTH: usize = 20;
+
+/// Ties a battle built from a system's entities back to them: combat
+/// faction index `i` is `factions[i]`, and unit id `n` is `entities[n]`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorldBattle {
+    pub system: SystemId,
+    pub factions: Vec<FactionId>,
+    pub entities: Vec<Entity>,
+}
+
+/// The campaign's entities. `world` is public for systems that need
+/// components of their own; the methods here cover combat and economy.
+#[derive(Debug, Default)]
+pub struct CampaignWorld {
+    pub world: World,
+}
+
+impl CampaignWorld {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Respawns every entity in the snapshot, in order. Entity handles
+    /// are new; look entities up again by name.
+    pub fn from_snapshot(snapshot: WorldSnapshot) -> Self {
+        let mut world = Self::new();
+        for record in snapshot.entities {
+            world.spawn(record);
+        }
+        world
+    }
+
+    pub fn snapshot(&self) -> WorldSnapshot {
+        WorldSnapshot { entities: self.world.entities().filter_map(|e| self.record(e)).collect() }
+    }
+
+    /// FNV-1a of the snapshot's JSON encoding; entity handles are part of
+    /// it, so worlds that spawned and despawned differently don't match.
+    pub fn state_hash(&self) -> u64 {
+        desync::hash_json(&self.snapshot())
+    }
+
+    pub fn spawn(&mut self, record: EntityRecord) -> Entity {
+        let entity = self.world.spawn();
+        self.attach(entity, record);
+        entity
+    }
+
+    /// Attaches the record's components to `entity`, replacing those of
+    /// the same kind; false if the entity is gone.
+    pub fn attach(&mut self, entity: Entity, record: EntityRecord) -> bool {
+        if !self.world.is_alive(entity) {
+            return false;
+        }
+        attach(&mut self.world, entity, record.name);
+        attach(&mut self.world, entity, record.owner);
+        attach(&mut self.world, entity, record.location);
+        attach(&mut self.world, entity, record.health);
+        attach(&mut self.world, entity, record.weapons);
+        attach(&mut self.world, entity, record.maneuver);
+        attach(&mut self.world, entity, record.yield_);
+        true
+    }
+
+    pub fn despawn(&mut self, entity: Entity) -> bool {
+        self.world.despawn(entity)
+    }
+
+    /// The entity's components; None once it is despawned.
+    pub fn record(&self, entity: Entity) -> Option<EntityRecord> {
+        self.world.is_alive(entity).then(|| EntityRecord {
+            entity: Some(entity),
+            name: self.world.get::<Name>(entity).cloned(),
+            owner: self.world.get::<Owner>(entity).cloned(),
+            location: self.world.get::<Location>(entity).cloned(),
+            health: self.world.get::<Health>(entity).cloned(),
+            weapons: self.world.get::<Weapons>(entity).cloned(),
+            maneuver: self.world.get::<Maneuver>(entity).cloned(),
+            yield_: self.world.get::<Yield>(entity).cloned(),
+        })
+    }
+
+    /// The first entity named `name`.
+    pub fn find(&self, name: &str) -> Option<Entity> {
+        self.world.query::<Name>().find(|(_, n)| n.0 == name).map(|(e, _)| e)
+    }
+
+    /// Spawns an entity for an economy node: planets are located in the
+    /// system of the same id.
+    pub fn spawn_node(&mut self, node: &EconomicNode) -> Entity {
+        self.spawn(EntityRecord {
+            name: Some(Name(node.id.to_string())),
+            owner: Some(Owner(node.owner_faction.clone())),
+            location: (node.node_type == NodeType::Planet).then(|| Location(node.id.clone())),
+            yield_: Some(Yield {
+                node_type: node.node_type,
+                income: node.base_income,
+                upkeep: node.base_upkeep,
+                efficiency_scaled: node.efficiency_scaled,
+            }),
+            ..EntityRecord::default()
+        })
+    }
+
+    /// Spawns a combat unit's entity at `location`. The setup's id,
+    /// faction index and position are only meaningful within one battle,
+    /// so they aren't kept.
+    pub fn spawn_unit(&mut self, unit: &UnitSetup, owner: &str, location: &str) -> Entity {
+        self.spawn(EntityRecord {
+            name: Some(Name(unit.name.clone())),
+            owner: Some(Owner(owner.into())),
+            location: Some(Location(location.into())),
+            health: Some(Health { shields: unit.shields, max_shields: unit.shields, armor: unit.armor, ..Health::new(unit.max_hp) }),
+            weapons: Some(Weapons(unit.weapons.clone())),
+            maneuver: Some(Maneuver { speed: unit.speed, evasion: unit.evasion, cover: unit.cover }),
+            ..EntityRecord::default()
+        })
+    }
+
+    /// An economy node for every named, owned entity with a `Yield`.
+    pub fn economic_nodes(&self) -> Vec<EconomicNode> {
+        self.world
+            .query::<Yield>()
+            .filter_map(|(entity, yield_)| {
+                let name = self.world.get::<Name>(entity)?;
+                let owner = self.world.get::<Owner>(entity)?;
+                Some(EconomicNode {
+                    id: SystemId::new(&name.0),
+                    owner_faction: owner.0.clone(),
+                    node_type: yield_.node_type,
+                    base_income: yield_.income,
+                    base_upkeep: yield_.upkeep,
+                    efficiency_scaled: yield_.efficiency_scaled,
+                    modifiers: Vec::new(),
+                })
+            })
+            .collect()
+    }
+
+    /// Writes `economic_nodes` into `economy`: existing nodes of the same
+    /// id are updated (keeping their modifiers), the rest added. Nodes no
+    /// entity yields are left alone. Returns the nodes written.
+    pub fn sync_economy(&self, economy: &mut IncomeEngine) -> usize {
+        let nodes = self.economic_nodes();
+        let synced = nodes.len();
+        for node in nodes {
+            match economy.node_mut(&node.id) {
+                Some(existing) => *existing = EconomicNode { modifiers: std::mem::take(&mut existing.modifiers), ..node },
+                None => economy.add_node(node),
+            }
+        }
+        synced
+    }
+
+    /// Builds the battle at `system` from every owned entity there with
+    /// `Health` still standing: each faction in its own line of columns
+    /// across the field, allies per `hostile`.
+    pub fn build_battle<F>(&self, system: &str, seed: u64, hostile: F) -> (BattleEngine, WorldBattle)
+    where
+        F: Fn(&str, &str) -> bool,
+    {
+        let fighters: Vec<(Entity, &Health, &FactionId)> = self
+            .world
+            .query::<Health>()
+            .filter(|(_, health)| health.hp > 0.0)
+            .filter(|(entity, _)| self.world.get::<Location>(*entity).is_some_and(|l| l.0 == system))
+            .filter_map(|(entity, health)| Some((entity, health, &self.world.get::<Owner>(entity)?.0)))
+            .collect();
+        let mut battle = WorldBattle { system: SystemId::new(system), factions: Vec::new(), entities: Vec::new() };
+        for (_, _, owner) in &fighters {
+            if !battle.factions.contains(owner) {
+                battle.factions.push((*owner).clone());
+            }
+        }
+
+        let mut engine = BattleEngine::new(BATTLE_SIZE, BATTLE_SIZE);
+        engine.set_seed(seed);
+        let line_gap = BATTLE_SIZE / battle.factions.len().max(1) as f32;
+        let mut line_fill = vec![0usize; battle.factions.len()];
+        for (entity, health, owner) in fighters {
+            let faction_idx = battle.factions.iter().position(|f| f == owner).unwrap_or_default();
+            let slot = line_fill[faction_idx];
+            line_fill[faction_idx] += 1;
+            let name = self.world.get::<Name>(entity).map_or_else(|| entity.to_string(), |n| n.0.clone());
+            let mut unit = CombatUnit::new(UnitId(battle.entities.len() as u32), name, faction_idx as u8, health.max_hp);
+            unit.hp = health.hp;
+            unit.shields = health.shields;
+            unit.max_shields = health.max_shields;
+            unit.armor = health.armor;
+            if let Some(weapons) = self.world.get::<Weapons>(entity) {
+                unit.weapons = weapons.0.iter().map(|w| w.build()).collect();
+            }
+            if let Some(maneuver) = self.world.get::<Maneuver>(entity) {
+                unit.speed = maneuver.speed;
+                unit.evasion = maneuver.evasion;
+                unit.cover = maneuver.cover;
+            }
+            unit.position = (
+                line_gap * faction_idx as f32 + (slot / LINE_LENGTH) as f32 * LINE_SPACING,
+                (slot % LINE_LENGTH) as f32 * LINE_SPACING,
+            );
+            engine.add_unit(unit);
+            battle.entities.push(entity);
+        }
+
+        let mut hostility = Hostility::default();
+        for (i, a) in battle.factions.iter().enumerate() {
+            for (j, b) in battle.factions.iter().enumerate().skip(i + 1) {
+                if let (Ok(i), Ok(j)) = (u8::try_from(i), u8::try_from(j)) {
+                    if !hostile(a, b) {
+                        hostility.ally(i, j);
+                    }
+                }
+            }
+        }
+        engine.set_hostility(hostility);
+        (engine, battle)
+    }
+
+    /// Writes a finished battle's hull and shields back into its entities.
+    /// The destroyed are despawned, except those with a `Yield`, which
+    /// lose only their `Health` and `Weapons` (a planet whose defenses
+    /// fall still pays). Returns the entities destroyed.
+    pub fn apply_battle(&mut self, battle: &WorldBattle, units: &[CombatUnit]) -> Vec<Entity> {
+        let mut destroyed = Vec::new();
+        for unit in units {
+            let Some(&entity) = battle.entities.get(unit.id.get() as usize) else { continue };
+            let Some(health) = self.world.get_mut::<Health>(entity) else { continue };
+            health.hp = unit.hp.max(0.0);
+            health.shields = unit.shields.max(0.0);
+            if unit.is_alive {
+                continue;
+            }
+            destroyed.push(entity);
+            if self.world.has::<Yield>(entity) {
+                self.world.remove::<Health>(entity);
+                self.world.remove::<Weapons>(entity);
+            } else {
+                self.world.despawn(entity);
+            }
+        }
+        destroyed
+    }
+
+}
+
+/// Attaches `component` if there is one; `entity` must be alive.
+fn attach<T: Component>(world: &mut World, entity: Entity, component: Option<T>) {
+    if let Some(component) = component {
+        let attached = world.insert(entity, component);
+        debug_assert!(attached.is_ok());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use void_reckoning_combat::resolve::DEFAULT_MAX_TURNS;
+    use void_reckoning_economy::types::{GlobalEconomicRules, ResourceState, SCALE_FACTOR};
+
+    #[test]
+    fn one_planet_entity_fights_and_pays() {
+        let mut world = CampaignWorld::new();
+        let planet = world.spawn_node(&EconomicNode {
+            id: "Cadia".into(),
+            owner_faction: "Imperium".into(),
+            node_type: NodeType::Planet,
+            base_income: ResourceState { credits: 10 * SCALE_FACTOR, ..ResourceState::default() },
+            base_upkeep: ResourceState::default(),
+            efficiency_scaled: SCALE_FACTOR,
+            modifiers: Vec::new(),
+        });
+        world.world.insert(planet, Health::new(1.0)).unwrap();
+        let raider: UnitSetup = serde_json::from_value(serde_json::json!({
+            "id": 0, "name": "raider", "faction_idx": 0, "max_hp": 500.0,
+            "weapons": [{"name": "lance", "range": 2000.0, "damage": 50.0, "accuracy": 1.0, "cooldown": 1.0}],
+        }))
+        .unwrap();
+        let raider = world.spawn_unit(&raider, "Orks", "Cadia");
+
+        let (mut engine, battle) = world.build_battle("Cadia", 7, |a, b| a != b);
+        assert_eq!(battle.entities, vec![planet, raider]);
+        engine.run_to_completion("Cadia", DEFAULT_MAX_TURNS);
+        assert_eq!(world.apply_battle(&battle, &engine.state.units), vec![planet]);
+        assert!(world.world.get::<Health>(planet).is_none());
+        assert!(world.world.get::<Health>(raider).unwrap().hp > 0.0);
+
+        let mut economy = IncomeEngine::new(GlobalEconomicRules::default());
+        assert_eq!(world.sync_economy(&mut economy), 1);
+        assert_eq!(economy.process_faction("Imperium").total_income.credits, 10 * SCALE_FACTOR);
+        let restored = CampaignWorld::from_snapshot(world.snapshot());
+        assert_eq!(restored.find("raider").and_then(|e| restored.record(e)).and_then(|r| r.owner), Some(Owner("Orks".into())));
+    }
+}
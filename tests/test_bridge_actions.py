@@ -0,0 +1,62 @@
+"""Actions: a move order and a policy are queued, checked against the
+fleets and the economy, and resolved at the start of the next turn; one
+the engines can't carry out is rejected with its reasons."""
+
+import json
+
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+SCALE = 1_000_000
+
+
+def fleet(fleet_id, faction, location):
+    return json.dumps({
+        "id": fleet_id,
+        "faction": faction,
+        "location": location,
+        "ships": [],
+        "supply": 20.0,
+        "max_supply": 20.0,
+        "fuel": 5.0,
+        "max_fuel": 5.0,
+        "speed": 10.0,
+    })
+
+
+def test_actions_are_checked_then_applied_next_turn():
+    pathfinder = bridge.RustPathfinder()
+    pathfinder.sync_topology([("Terra", ["Mars"]), ("Mars", ["Terra"])])
+    economy = bridge.RustEconomyEngine()
+    economy.add_node(json.dumps({
+        "id": "Terra",
+        "owner_faction": "Imperium",
+        "node_type": "Planet",
+        "base_income": {"credits": 100 * SCALE, "minerals": 0, "energy": 0, "research": 0},
+        "base_upkeep": {"credits": 0, "minerals": 0, "energy": 0, "research": 0},
+        "efficiency_scaled": SCALE,
+        "modifiers": [],
+    }))
+    fleets = bridge.RustFleetManager()
+    fleets.add_fleet(fleet("red", "Imperium", "Terra"))
+    fleets.add_fleet(fleet("green", "Orks", "Mars"))
+    turns = bridge.RustTurnEngine(pathfinder, economy, fleets=fleets)
+
+    stolen = {"MoveFleet": {"faction": "Imperium", "fleet": "green", "destination": "Terra"}}
+    assert turns.check_action(json.dumps(stolen)) == ["fleet 'green' belongs to Orks"]
+    move = turns.submit(json.dumps({"MoveFleet": {"faction": "Imperium", "fleet": "red", "destination": "Mars"}}))
+    policy = turns.submit(json.dumps({
+        "SetPolicy": {"faction": "Imperium", "policy": "war_economy", "multiplier_scaled": 2 * SCALE},
+    }))
+    rejected = turns.submit(json.dumps(stolen))
+    assert [a["id"] for a in json.loads(turns.pending_actions())] == [move, policy, rejected]
+    assert json.loads(fleets.fleet("red"))["route"] == []
+
+    report = json.loads(turns.run_turn())
+    outcomes = {o["id"]: o for o in report["actions"]}
+    assert outcomes[move]["applied"] and outcomes[policy]["applied"]
+    assert not outcomes[rejected]["applied"]
+    assert json.loads(fleets.fleet("red"))["route"]
+    assert turns.treasury("Imperium").credits == 200.0
+    assert json.loads(turns.pending_actions()) == []
@@ -0,0 +1,48 @@
+"""The native AI ranks candidate actions using the economy, the map and
+auto-resolved battle predictions."""
+
+import json
+
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+
+def army(first_id, faction_idx, count):
+    return [
+        {
+            "id": first_id + i,
+            "faction_idx": faction_idx,
+            "max_hp": 100.0,
+            "x": faction_idx * 50.0,
+            "y": i * 10.0,
+            "weapons": [{"name": "Gun", "range": 500.0, "damage": 25.0}],
+        }
+        for i in range(count)
+    ]
+
+
+def battle(ours, theirs):
+    return {"width": 500.0, "height": 500.0, "units": army(0, 0, ours) + army(ours, 1, theirs)}
+
+
+def test_rank_prefers_winnable_wars_and_reports_infeasible_moves():
+    pathfinder = bridge.RustPathfinder()
+    pathfinder.sync_topology([("Hub", ["A", "B"]), ("A", ["Hub"]), ("B", ["Hub"])])
+    economy = bridge.RustEconomyEngine()
+    ai = bridge.RustDecisionEngine(json.dumps({"economy": 1.0, "expansion": 1.0, "military": 2.0, "trade": 1.0, "risk_aversion": 1.0}), samples=8)
+
+    candidates = [
+        {"id": "war-orks", "action": {"DeclareWar": {"target": "Orks", "battle": battle(6, 2)}}},
+        {"id": "war-eldar", "action": {"DeclareWar": {"target": "Eldar", "battle": battle(2, 6)}}},
+        {"id": "lost", "action": {"MoveFleet": {"fleet": "f1", "from": "A", "to": "Nowhere"}}},
+        {"id": "route", "action": {"EstablishTradeRoute": {
+            "from": "A", "to": "B", "value": {"credits": 5_000_000, "minerals": 0, "energy": 0, "research": 0},
+        }}},
+    ]
+    decisions = json.loads(ai.rank("Imperium", json.dumps(candidates), economy, pathfinder))
+    ids = [d["id"] for d in decisions]
+    assert ids.index("war-orks") < ids.index("war-eldar")
+    assert ids[-1] == "lost" and not decisions[-1]["feasible"]
+    assert decisions[ids.index("war-orks")]["win_probability"] > 0.9
+    assert json.loads(ai.rank("Imperium", json.dumps(candidates), economy, pathfinder)) == decisions
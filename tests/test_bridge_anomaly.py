@@ -0,0 +1,78 @@
+"""Anomalies roll from a seeded, weighted table after each turn: a plague
+cuts a planet's income, pirates appear, and the same seed gives the same
+galaxy."""
+
+import json
+
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+CREDITS = 1_000_000
+
+
+def planet(node_id, income):
+    return json.dumps({
+        "id": node_id,
+        "owner_faction": "Imperium",
+        "node_type": "Planet",
+        "base_income": {"credits": income * CREDITS, "minerals": 0, "energy": 0, "research": 0},
+        "base_upkeep": {"credits": 0, "minerals": 0, "energy": 0, "research": 0},
+        "efficiency_scaled": 1_000_000,
+        "modifiers": [],
+    })
+
+
+PIRATES = {
+    "id": "pirates",
+    "faction": "Pirates",
+    "location": "",
+    "ships": [],
+    "supply": 10.0,
+    "max_supply": 10.0,
+    "fuel": 5.0,
+    "max_fuel": 5.0,
+    "speed": 10.0,
+}
+
+
+def test_plague_cuts_income_until_it_passes():
+    pathfinder = bridge.RustPathfinder()
+    pathfinder.add_edge("Terra", "Mars", 1.0)
+    economy = bridge.RustEconomyEngine()
+    economy.add_node(planet("Terra", 100))
+    table = {"entries": [{"id": "plague", "weight": 1, "effect": {"Plague": {"income_factor": 0.5, "turns": 1}}}]}
+    anomalies = bridge.RustAnomalyGenerator(json.dumps(table), seed=3)
+    turns = bridge.RustTurnEngine(pathfinder, economy, anomalies=anomalies)
+    log = turns.enable_event_logging()
+
+    first = json.loads(turns.run_turn())
+    assert first["factions"]["Imperium"]["income"]["credits"] == 100 * CREDITS
+    assert first["anomalies"]["struck"][0]["target"] == "Terra"
+    assert any(e.category == "Anomaly" and "Plague on Terra" in e.message for e in log.get_all())
+
+    anomalies.set_table(json.dumps(dict(table, chance=0.0)))
+    second = json.loads(turns.run_turn())
+    assert second["factions"]["Imperium"]["income"]["credits"] == 50 * CREDITS
+    assert second["anomalies"]["expired"] == first["anomalies"]["struck"]
+    assert json.loads(anomalies.active()) == []
+
+
+def test_same_seed_same_pirates():
+    table = json.dumps({"entries": [{"id": "raid", "weight": 1, "effect": {"PirateSpawn": {"fleet": PIRATES}}}]})
+    landings = []
+    for _ in range(2):
+        pathfinder = bridge.RustPathfinder()
+        for system in ["Terra", "Mars", "Kor", "Fenris"]:
+            pathfinder.add_node(system, None)
+        fleets = bridge.RustFleetManager()
+        anomalies = bridge.RustAnomalyGenerator(table, seed=42, fleets=fleets)
+        economy = bridge.RustEconomyEngine()
+        reports = [json.loads(anomalies.run(turn, economy, pathfinder)) for turn in (1, 2, 3)]
+        landings.append([r["struck"][0]["target"] for r in reports])
+        assert len(json.loads(fleets.fleets())) == 3
+    assert landings[0] == landings[1]
+
+    no_fleets = bridge.RustAnomalyGenerator(table)
+    report = json.loads(no_fleets.run(1, bridge.RustEconomyEngine(), pathfinder))
+    assert report["errors"][0]["entry"] == "raid"
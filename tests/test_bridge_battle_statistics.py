@@ -0,0 +1,28 @@
+"""Kills name their attacker and weapon, and the battle keeps a ledger of
+shots, damage and kills per unit."""
+
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+
+def test_kill_is_attributed():
+    battle = bridge.RustCombatEngine(200.0, 200.0, seed=1)
+    cannon = bridge.WeaponSpec("Cannon", 50.0, 40.0, "Missile")
+    battle.add_unit_spec(bridge.UnitSpec(1, 0, 500.0, weapons=[cannon]))
+    battle.add_unit_spec(bridge.UnitSpec(2, 1, 30.0, x=10.0))
+    log = battle.enable_event_logging()
+    for _ in range(10):
+        battle.step()
+
+    stats = battle.get_battle_statistics()
+    assert [(k["attacker_id"], k["target_id"], k["weapon"]) for k in stats["kills"]] == [(1, 2, "Cannon")]
+    shooter, victim = stats["units"][1], stats["units"][2]
+    assert shooter["kills"] == 1 and shooter["hits"] >= 1
+    assert shooter["shots_fired"] >= shooter["hits"]
+    assert shooter["damage_dealt"] == victim["damage_taken"] > 0.0
+
+    (destroyed,) = [e for e in log.get_all() if "destroyed" in e.message]
+    assert destroyed.message == "Unit 2 destroyed by Unit 1"
+    assert destroyed.get_field("attacker_id") == 1
+    assert destroyed.get_field("weapon") == "Cannon"
@@ -0,0 +1,15 @@
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+
+def test_gate_is_the_top_choke_point():
+    pf = bridge.RustPathfinder()
+    lanes = [("A1", "A2"), ("A2", "A3"), ("A3", "A1"), ("A1", "Gate"), ("Gate", "B1"), ("B1", "B2"), ("B2", "B3"), ("B3", "B1")]
+    for a, b in lanes:
+        pf.add_edge(a, b, 1.0)
+        pf.add_edge(b, a, 1.0)
+    top = pf.betweenness_centrality(top=3)
+    assert [name for name, _ in top] == ["Gate", "A1", "B1"]
+    assert top[0][1] == pytest.approx(0.6)
+    assert len(pf.betweenness_centrality()) == 7
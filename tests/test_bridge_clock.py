@@ -0,0 +1,48 @@
+"""One campaign clock: the turn engine advances it, and the economy, a
+battle and the event log all read the same turn and calendar date."""
+
+import json
+
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+obs = bridge.observability
+
+CALENDAR = {"start_year": 812, "turns_per_year": 4, "periods": ["Spring", "Summer", "Autumn", "Winter"]}
+
+
+def test_engines_share_the_campaign_clock():
+    clock = obs.SimClock(calendar_json=json.dumps(CALENDAR))
+    pathfinder = bridge.RustPathfinder()
+    pathfinder.add_edge("Terra", "Mars", 1.0)
+    economy = bridge.RustEconomyEngine()
+    turns = bridge.RustTurnEngine(pathfinder, economy)
+    turns.clock = clock
+    for _ in range(5):
+        turns.run_turn()
+    assert clock.turn == turns.turn == 5
+    assert clock.date().label == "Spring 813"
+    assert json.loads(turns.clock.calendar())["start_year"] == 812
+
+    battle = bridge.RustCombatEngine(200.0, 200.0)
+    laser = bridge.WeaponSpec("Laser", 30.0, 10.0, "Energy")
+    for i in range(4):
+        battle.add_unit_spec(bridge.UnitSpec(i, i % 2, 300.0, x=float(i * 20), weapons=[laser]))
+    battle.set_clock(clock)
+    log = battle.enable_event_logging()
+    battle.step()
+    battle.step()
+    assert clock.tick == 2
+    stamped = [e.sim_time for e in log.get_all() if e.sim_time.turn is not None]
+    assert stamped and all(t.turn == 5 for t in stamped)
+
+    # Events that arrive unstamped pick up the clock's time.
+    log.set_clock(clock)
+    context = obs.CorrelationContext()
+    log.add(obs.Event(obs.EventSeverity.Info, "Test", "hello", context))
+    assert log.get_all()[-1].sim_time.turn == 5
+
+    turns.run_turn()
+    assert (clock.turn, clock.tick) == (6, 0)
+    economy.set_campaign_turn(9)
+    assert turns.turn == 9
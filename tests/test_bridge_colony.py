@@ -0,0 +1,53 @@
+"""Colonization: colony ships built through production carry settlers to a
+habitable system, which becomes a paying planet once the bootstrap cost is
+paid; population then grows and adds to the planet's income."""
+
+import json
+
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+
+def credits(amount):
+    return {"credits": amount * 1_000_000, "minerals": 0, "energy": 0, "research": 0}
+
+
+def test_colony_ship_from_production_founds_a_colony():
+    pathfinder = bridge.RustPathfinder()
+    pathfinder.add_node("Terra", "Plains")
+    pathfinder.add_node("Eden", "Forest")
+    pathfinder.add_edge("Terra", "Eden", 7.0)
+    economy = bridge.RustEconomyEngine()
+    colonies = bridge.RustColonyEngine(json.dumps({"settlers_per_ship": 2.0}))
+    colonies.add_colony("Terra", "Imperium", 5.0)
+    assert colonies.habitability("Eden", pathfinder) == pytest.approx(0.8)
+
+    turns = bridge.RustTurnEngine(pathfinder, economy, colonies=colonies)
+    turns.set_treasury("Imperium", json.dumps(credits(500)))
+    turns.queue_production(json.dumps({
+        "id": "cs-1", "faction": "Imperium", "item": "ColonyShip", "cost": credits(50),
+    }))
+    first = json.loads(turns.run_turn())
+    assert first["colonization"]["ships_built"] == {"Imperium": 1}
+    assert colonies.colony_ships("Imperium") == 1
+
+    order = {"id": "c1", "faction": "Imperium", "from": "Terra", "to": "Eden"}
+    assert colonies.colonize(json.dumps(order), pathfinder) == 3
+    with pytest.raises(ValueError):
+        colonies.colonize(json.dumps(dict(order, id="c2")), pathfinder)
+
+    turns.run_turn()
+    before = turns.treasury("Imperium").credits
+    third = json.loads(turns.run_turn())
+    assert third["colonization"]["founded"] == ["Eden"]
+    eden = json.loads(colonies.colony("Eden"))
+    assert eden["faction"] == "Imperium"
+    assert eden["population"] == pytest.approx(2.15)
+    # The bootstrap cost outweighs a turn of population income.
+    assert turns.treasury("Imperium").credits < before
+
+    fourth = json.loads(turns.run_turn())
+    assert fourth["factions"]["Imperium"]["income"]["credits"] > 0
+    restored = bridge.RustColonyEngine.from_json(colonies.to_json())
+    assert json.loads(restored.colonies()) == json.loads(colonies.colonies())
@@ -0,0 +1,24 @@
+"""A battle built with a seed rolls the same damage every time it is run."""
+
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+
+def battle(seed):
+    engine = bridge.RustCombatEngine(200.0, 200.0, seed=seed)
+    laser = bridge.WeaponSpec("Laser", 40.0, 10.0, "Energy")
+    for i in range(10):
+        engine.add_unit_spec(bridge.UnitSpec(i, i % 2, 300.0, x=float(i * 15), weapons=[laser]))
+    for _ in range(25):
+        engine.step()
+    return engine
+
+
+def test_same_seed_same_battle():
+    first, second, other = battle(11), battle(11), battle(12)
+    assert first.seed == 11
+    assert first.get_state() == second.get_state()
+    assert first.state_hash() == second.state_hash()
+    assert first.state_hash() != other.state_hash()
+    assert bridge.RustCombatEngine(200.0, 200.0).seed is None
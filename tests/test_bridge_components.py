@@ -0,0 +1,17 @@
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+
+def test_collapse_cuts_systems_off():
+    pf = bridge.RustPathfinder()
+    pf.sync_topology([("A", ["B"]), ("B", ["C"]), ("D", ["E"])])
+    components = pf.connected_components()
+    assert components["A"] == components["C"] != components["D"]
+    assert pf.is_reachable("A", "C")
+    assert not pf.is_reachable("C", "A")
+
+    pf.update_edge_weight("B", "C", float("inf"))
+    components = pf.connected_components()
+    assert components["A"] != components["C"]
+    assert not pf.is_reachable("A", "C")
@@ -0,0 +1,138 @@
+"""Concurrent callers against the native bridge.
+
+The bridge classes lock internally, so sharing one engine between threads
+must neither crash nor raise "already borrowed" errors. These tests matter
+most on a free-threaded (3.13t) interpreter but also run under the GIL.
+"""
+
+import json
+from concurrent.futures import ThreadPoolExecutor
+
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+THREADS = 8
+ROUNDS = 200
+
+pytestmark = pytest.mark.slow
+
+
+def hammer(*workers):
+    """Runs every worker `THREADS` times concurrently and re-raises failures."""
+    with ThreadPoolExecutor(max_workers=THREADS) as pool:
+        futures = [pool.submit(w, i) for i in range(THREADS) for w in workers]
+        return [f.result() for f in futures]
+
+
+def ring(pathfinder, size):
+    ids = [f"S{i}" for i in range(size)]
+    pathfinder.sync_topology([(a, [b]) for a, b in zip(ids, ids[1:] + ids[:1])])
+    return ids
+
+
+def test_pathfinder_queries_during_edits():
+    pathfinder = bridge.RustPathfinder()
+    ids = ring(pathfinder, 32)
+
+    def query(_):
+        for _ in range(ROUNDS):
+            route = pathfinder.find_path(ids[0], ids[16])
+            # A concurrent resync swaps the whole graph at once.
+            assert route is None or route[0][0] == ids[0]
+
+    def edit(n):
+        for i in range(ROUNDS // 10):
+            pathfinder.add_node(f"X{n}-{i}")
+            ring(pathfinder, 32)
+
+    hammer(query, edit)
+    assert pathfinder.find_path(ids[0], ids[16]) is not None
+
+
+def test_combat_steps_and_reads_interleave():
+    engine = bridge.RustCombatEngine(200.0, 200.0)
+    engine.set_seed(7)
+    for i in range(40):
+        laser = bridge.WeaponSpec("Laser", 300.0, 1.0, "Energy")
+        engine.add_unit_spec(bridge.UnitSpec(i, i % 2, 500.0, name=f"U{i}", x=float(i * 4), weapons=[laser]))
+
+    def step(_):
+        for _ in range(ROUNDS // 4):
+            engine.step()
+
+    def read(_):
+        for _ in range(ROUNDS):
+            assert len(engine.get_state()) == 40
+            engine.get_units_in_radius(50.0, 0.0, 30.0)
+
+    hammer(step, read)
+
+
+def test_economy_and_auditor_shared_between_threads():
+    pathfinder = bridge.RustPathfinder()
+    ids = ring(pathfinder, 8)
+    economy = bridge.RustEconomyEngine()
+    auditor = bridge.RustAuditor()
+    auditor.load_registry("factions", json.dumps({"Imperium": {}}))
+    auditor.initialize()
+
+    def resources(credits):
+        return {"credits": credits, "minerals": 0, "energy": 0, "research": 0}
+
+    def add(n):
+        for i in range(ROUNDS // 4):
+            economy.add_node(json.dumps({
+                "id": f"N{n}-{i}",
+                "owner_faction": "Imperium",
+                "node_type": "Planet",
+                "base_income": resources(10_000_000),
+                "base_upkeep": resources(0),
+                "efficiency_scaled": 1_000_000,
+                "modifiers": [],
+            }))
+            economy.add_trade_route(json.dumps({
+                "from": ids[0],
+                "to": ids[4],
+                "base_value": resources(5_000_000),
+                "efficiency_scaled": 1_000_000,
+            }))
+
+    def report(_):
+        for _ in range(ROUNDS // 4):
+            json.loads(economy.process_all())
+            json.loads(economy.calculate_trade(pathfinder))
+
+    def validate(n):
+        entities = [(f"E{n}-{i}", "fleet", json.dumps({"faction": "Imperium"})) for i in range(20)]
+        for _ in range(ROUNDS // 10):
+            json.loads(auditor.validate_batch(entities, "u", 1))
+
+    def reload(_):
+        for i in range(ROUNDS // 10):
+            auditor.reload_registry("factions", json.dumps({"Imperium": {"v": i}}))
+
+    hammer(add, report, validate, reload)
+    reports = json.loads(economy.process_all())
+    assert reports
+
+
+def test_causal_graph_concurrent_ingest():
+    graph = bridge.RustCausalGraph()
+    log = bridge.observability.EventLog()
+    for i in range(50):
+        log.add(bridge.Event(bridge.EventSeverity.Info, "Combat", f"hit {i}", bridge.CorrelationContext()))
+    payload = log.to_bincode()
+
+    def ingest(_):
+        for _ in range(ROUNDS // 10):
+            graph.add_events_bincode(payload)
+
+    def query(_):
+        for _ in range(ROUNDS // 10):
+            graph.size()
+            graph.check_integrity()
+            graph.to_bytes()
+
+    hammer(ingest, query)
+    assert graph.size() == 50
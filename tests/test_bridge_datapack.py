@@ -0,0 +1,73 @@
+"""Mod support: base data plus ordered overlays merged into the registry
+every engine reads, with the conflicts between mods reported."""
+
+import json
+import os
+import tempfile
+
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+BASE = {
+    "id": "base",
+    "version": "1.0",
+    "registries": {
+        "weapons": {
+            "Lasgun": {"range": 30.0, "damage": 5.0},
+            "Bolter": {"range": 25.0, "damage": 9.0},
+        },
+        "buildings": {"Forge": {"yield": {"credits": 5}}},
+    },
+}
+
+
+def write_pack(root, manifest, weapons):
+    os.makedirs(root)
+    with open(os.path.join(root, "pack.json"), "w") as f:
+        json.dump(manifest, f)
+    with open(os.path.join(root, "weapons.json"), "w") as f:
+        json.dump(weapons, f)
+
+
+def test_mods_layer_over_base_data():
+    packs = bridge.RustDataPacks()
+    packs.add(json.dumps(BASE))
+    with tempfile.TemporaryDirectory() as tmp:
+        write_pack(os.path.join(tmp, "rebalance"), {"id": "rebalance", "requires": ["base"]}, {
+            "Lasgun": {"$patch": {"damage": 7.0}},
+            "Bolter": {"$delete": True},
+        })
+        assert packs.add_dir(os.path.join(tmp, "rebalance")) == "rebalance"
+    packs.add(json.dumps({"id": "xenos", "registries": {"weapons": {"Lasgun": {"$override": {"range": 10.0, "damage": 1.0}}}}}))
+    assert packs.pack_ids() == ["base", "rebalance", "xenos"]
+
+    registry = bridge.RustDataRegistry()
+    report = json.loads(packs.apply(registry))
+    assert registry.keys("weapons") == ["Lasgun"]
+    assert json.loads(registry.get("weapons", "Lasgun"))["damage"] == 1.0
+    assert registry.keys("buildings") == ["Forge"]
+    assert report["origins"]["weapons"]["Lasgun"] == ["base", "rebalance", "xenos"]
+    assert [(c["kind"], c["id"], c["packs"]) for c in report["conflicts"]] == [
+        ("Contested", "Lasgun", ["base", "rebalance", "xenos"]),
+    ]
+
+    battle = bridge.RustCombatEngine(100.0, 100.0)
+    battle.set_data_registry(registry)
+    battle.add_unit_spec(bridge.UnitSpec(0, 0, 100.0))
+    battle.equip(0, ["Lasgun"])
+    with pytest.raises(bridge.RegistryError):
+        battle.equip(0, ["Bolter"])
+
+    version = registry.version
+    packs.remove("xenos")
+    packs.apply(registry)
+    assert json.loads(registry.get("weapons", "Lasgun")) == {"range": 30.0, "damage": 7.0}
+    assert registry.version == version + 1
+
+
+def test_unknown_registry_is_rejected():
+    packs = bridge.RustDataPacks()
+    packs.add(json.dumps({"id": "odd", "registries": {"spaceships": {"X": {}}}}))
+    with pytest.raises(bridge.RegistryError):
+        packs.check()
@@ -0,0 +1,66 @@
+"""Diplomacy feeds combat (allies hold fire) and the campaign turn
+(embargoes cut trade, vassals pay tribute)."""
+
+import json
+
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+
+def resources(credits):
+    return {"credits": credits, "minerals": 0, "energy": 0, "research": 0}
+
+
+def test_allies_do_not_fight():
+    diplomacy = bridge.RustDiplomacyEngine()
+    diplomacy.sign_treaty("alliance", "Imperium", "Eldar")
+    battle = bridge.RustCombatEngine(200.0, 200.0)
+    laser = bridge.WeaponSpec("Laser", 30.0, 10.0, "Energy")
+    for i in range(6):
+        battle.add_unit_spec(bridge.UnitSpec(i, i % 3, 300.0, x=float(i * 20), weapons=[laser]))
+    diplomacy.apply_to_combat(battle, ["Imperium", "Orks", "Eldar"])
+    while battle.step():
+        pass
+    survivors = {unit_id % 3 for unit_id, _, _, _, alive in battle.get_state() if alive}
+    assert 1 not in survivors
+    assert survivors <= {0, 2}
+
+
+def test_war_cuts_trade_and_vassal_pays_tribute():
+    pathfinder = bridge.RustPathfinder()
+    pathfinder.sync_topology([("A", ["B"]), ("B", ["A"])])
+    economy = bridge.RustEconomyEngine()
+    for node_id, owner in [("A", "Imperium"), ("B", "Orks"), ("C", "Vassal")]:
+        economy.add_node(json.dumps({
+            "id": node_id,
+            "owner_faction": owner,
+            "node_type": "Planet",
+            "base_income": resources(10_000_000),
+            "base_upkeep": resources(0),
+            "efficiency_scaled": 1_000_000,
+            "modifiers": [],
+        }))
+    economy.add_trade_route(json.dumps({
+        "from": "A",
+        "to": "B",
+        "base_value": resources(5_000_000),
+        "efficiency_scaled": 1_000_000,
+    }))
+
+    diplomacy = bridge.RustDiplomacyEngine()
+    diplomacy.sign_treaty("vassalage", "Imperium", "Vassal")
+    diplomacy.declare_war("Orks", "Imperium")
+    assert diplomacy.opinion("Imperium", "Orks") < 0
+
+    turns = bridge.RustTurnEngine(pathfinder, economy, diplomacy=diplomacy)
+    report = json.loads(turns.run_turn())
+    assert report["embargoed_routes"] == 1
+    assert report["factions"]["Vassal"]["tribute_paid"]["credits"] == 2_000_000
+    assert report["factions"]["Imperium"]["tribute_received"]["credits"] == 2_000_000
+    assert diplomacy.turn == 1
+
+    restored = bridge.RustDiplomacyEngine.from_json(diplomacy.to_json())
+    assert restored.at_war("Imperium", "Orks")
+    with pytest.raises(ValueError):
+        restored.sign_treaty("marriage", "Imperium", "Orks")
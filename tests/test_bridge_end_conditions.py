@@ -0,0 +1,40 @@
+"""Battles end on configurable conditions as well as annihilation, and
+step() reports how they stand."""
+
+import json
+
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+
+def standoff():
+    battle = bridge.RustCombatEngine(200.0, 200.0, seed=2)
+    laser = bridge.WeaponSpec("Laser", 10.0, 10.0, "Energy")
+    battle.add_unit_spec(bridge.UnitSpec(1, 0, 100.0, weapons=[laser]))
+    battle.add_unit_spec(bridge.UnitSpec(2, 1, 100.0, x=150.0, weapons=[laser]))
+    return battle
+
+
+def test_turn_limit_ends_a_stalemate():
+    battle = standoff()
+    battle.set_end_conditions(max_turns=5)
+    turns = 0
+    while outcome := battle.step():
+        assert outcome.ongoing and outcome.reason is None
+        turns += 1
+    assert turns == 4
+    assert (outcome.turns, outcome.winner, outcome.reason) == (5, None, "TurnLimit")
+    assert outcome.timed_out
+    assert json.loads(outcome.to_json())["reason"] == "TurnLimit"
+
+
+def test_objective_and_validation():
+    battle = standoff()
+    battle.set_end_conditions(objective=(150.0, 0.0, 10.0, 2))
+    assert battle.step()
+    outcome = battle.step()
+    assert (outcome.winner, outcome.reason) == (1, "ObjectiveHeld")
+
+    with pytest.raises(ValueError):
+        battle.set_end_conditions(morale_break=1.5)
@@ -0,0 +1,75 @@
+"""Fleets move along pathfinder routes, stop where hostile fleets sit, and
+fight there with ships built from combat unit templates."""
+
+import json
+
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+
+def fleet(fleet_id, faction, location, count):
+    return json.dumps({
+        "id": fleet_id,
+        "faction": faction,
+        "location": location,
+        "ships": [{
+            "template": {
+                "id": 0,
+                "faction_idx": 0,
+                "max_hp": 100.0,
+                "speed": 20.0,
+                "weapons": [{"name": "Laser", "range": 2000.0, "damage": 40.0}],
+            },
+            "count": count,
+        }],
+        "supply": 20.0,
+        "max_supply": 20.0,
+        "fuel": 5.0,
+        "max_fuel": 5.0,
+        "speed": 10.0,
+    })
+
+
+def manager():
+    pathfinder = bridge.RustPathfinder()
+    pathfinder.sync_topology([("A", ["B"]), ("B", ["C"])])
+    fleets = bridge.RustFleetManager()
+    fleets.add_fleet(fleet("red", "Imperium", "A", 4))
+    fleets.add_fleet(fleet("blue", "Orks", "C", 2))
+    return pathfinder, fleets
+
+
+def test_hostile_fleets_meet_and_fight():
+    pathfinder, fleets = manager()
+    assert fleets.order_move("red", "C", pathfinder) > 0
+    with pytest.raises(KeyError):
+        fleets.order_move("green", "C", pathfinder)
+
+    while not json.loads(fleets.engagements()):
+        fleets.advance_turn({"A": "Imperium"})
+    results = json.loads(fleets.resolve_engagements(3))
+    assert results[0]["roster"]["system"] == "C"
+    assert results[0]["outcome"]["winner"] == results[0]["roster"]["factions"].index("Imperium")
+    assert fleets.fleet("blue") is None
+
+    restored = bridge.RustFleetManager.from_json(fleets.to_json())
+    assert json.loads(restored.fleet("red"))["location"] == "C"
+
+
+def test_allied_fleets_share_a_system():
+    pathfinder, fleets = manager()
+    diplomacy = bridge.RustDiplomacyEngine()
+    diplomacy.sign_treaty("nap", "Imperium", "Orks")
+    fleets.order_move("red", "C", pathfinder)
+    for _ in range(3):
+        fleets.advance_turn({}, diplomacy=diplomacy)
+    assert json.loads(fleets.fleet("red"))["location"] == "C"
+    assert json.loads(fleets.engagements(diplomacy=diplomacy)) == []
+
+    diplomacy.declare_war("Orks", "Imperium")
+    battle, roster = fleets.start_battle("C", 1, diplomacy=diplomacy)
+    while battle.step():
+        pass
+    destroyed = fleets.apply_battle(roster, battle)
+    assert destroyed == ["blue"]
@@ -0,0 +1,26 @@
+import os
+import tempfile
+
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+
+def test_export_graph_writes_dot_and_graphml():
+    pf = bridge.RustPathfinder()
+    pf.add_node("Sol", "Forest")
+    pf.add_edge("Sol", "Vega", 2.5)
+    assert '"Sol" -> "Vega" [label="2.5"' in pf.export_dot()
+    assert '<data key="terrain">Forest</data>' in pf.export_graphml()
+
+    with tempfile.TemporaryDirectory() as tmp:
+        dot = os.path.join(tmp, "map.dot")
+        graphml = os.path.join(tmp, "map.graphml")
+        pf.export_graph(dot)
+        pf.export_graph(graphml, format="graphml")
+        with open(dot) as f:
+            assert f.read() == pf.export_dot()
+        with open(graphml) as f:
+            assert f.read().startswith("<?xml")
+        with pytest.raises(ValueError):
+            pf.export_graph(dot, format="svg")
@@ -0,0 +1,54 @@
+"""Space weather: an ion storm forms over part of the map, slows its lanes
+and trade and spoils aim there through named modifiers, then lifts."""
+
+import json
+
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+CREDITS = 1_000_000
+
+STORM = {
+    "id": "ion_storm", "weight": 1, "turns": 2,
+    "lane_factor": 1.5, "accuracy_factor": 0.5, "trade_factor": 0.5,
+}
+
+
+def test_ion_storm_slows_lanes_and_trade_until_it_lifts():
+    pathfinder = bridge.RustPathfinder()
+    pathfinder.add_edge("Terra", "Mars", 1.0)
+    pathfinder.add_edge("Mars", "Terra", 1.0)
+    economy = bridge.RustEconomyEngine()
+    economy.add_trade_route(json.dumps({
+        "from": "Terra", "to": "Mars",
+        "base_value": {"credits": 40 * CREDITS, "minerals": 0, "energy": 0, "research": 0},
+        "efficiency_scaled": 0,
+    }))
+    hazards = bridge.RustHazardLayer(json.dumps({"chance": 1.0, "kinds": [STORM]}), seed=9)
+    turns = bridge.RustTurnEngine(pathfinder, economy, hazards=hazards)
+    log = turns.enable_event_logging()
+
+    first = json.loads(turns.run_turn())
+    storm = first["hazards"]["formed"][0]
+    assert storm["id"] == "ion_storm@1" and storm["expires"] == 3
+    assert {m[2] for m in pathfinder.lane_modifiers()} == {"ion_storm@1"}
+    assert pathfinder.find_path("Terra", "Mars")[1] == 1.5
+    assert pathfinder.base_weight("Terra", "Mars") == 1.0
+    assert any(e.category == "Hazard" for e in log.get_all())
+
+    # Half from the slower lane, half again from the storm: 40 * 0.25 / 2.
+    assert economy.calculate_trade_income(pathfinder)["Terra"].credits == 5.0
+    battle = bridge.RustCombatEngine(100.0, 100.0)
+    assert hazards.apply_to_battle(storm["region"][0], battle) == 0.5
+    assert hazards.accuracy_factor("Kor") == 1.0
+
+    hazards.set_table(json.dumps({"chance": 0.0, "kinds": [STORM]}))
+    turns.run_turn()
+    third = json.loads(turns.run_turn())
+    assert third["hazards"]["dissipated"][0]["id"] == "ion_storm@1"
+    assert pathfinder.lane_modifiers() == [] and len(hazards) == 0
+    assert economy.calculate_trade_income(pathfinder)["Terra"].credits == 20.0
+
+    restored = bridge.RustHazardLayer.from_json(hazards.to_json())
+    assert restored.state_hash() == hazards.state_hash()
@@ -0,0 +1,26 @@
+"""Shots roll to hit against weapon accuracy, target evasion and range;
+misses deal nothing and are logged."""
+
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+
+def test_evasive_target_is_never_hit():
+    battle = bridge.RustCombatEngine(200.0, 200.0, seed=3)
+    laser = bridge.WeaponSpec("Laser", 40.0, 10.0, "Energy", accuracy=0.9)
+    battle.add_unit_spec(bridge.UnitSpec(0, 0, 100.0, weapons=[laser], evasion=1.0))
+    battle.add_unit_spec(bridge.UnitSpec(1, 1, 1000.0, x=20.0, weapons=[laser]))
+    log = battle.enable_event_logging()
+    for _ in range(20):
+        battle.step()
+
+    assert battle.get_unit_status(0)[0] == 100.0
+    assert battle.get_unit_status(1)[0] < 1000.0
+
+    misses = [e for e in log.get_all() if e.category == "Combat" and "missed" in e.message]
+    by_attacker = {e.get_field("attacker_id") for e in misses}
+    assert 1 in by_attacker
+    assert all(e.get_field("hit_chance") == 0.0 for e in misses if e.get_field("attacker_id") == 1)
+    # Unit 0 fires at 90% accuracy, less up to a quarter for range.
+    assert all(0.0 < e.get_field("hit_chance") < 0.9 for e in misses if e.get_field("attacker_id") == 0)
@@ -0,0 +1,45 @@
+"""A queued invasion runs before income, so the captured planet pays its
+new owner the same turn."""
+
+import json
+
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+
+def node(node_id, owner, node_type, income, upkeep):
+    def credits(amount):
+        return {"credits": amount * 1_000_000, "minerals": 0, "energy": 0, "research": 0}
+
+    return json.dumps({
+        "id": node_id,
+        "owner_faction": owner,
+        "node_type": node_type,
+        "base_income": credits(income),
+        "base_upkeep": credits(upkeep),
+        "efficiency_scaled": 1_000_000,
+        "modifiers": [],
+    })
+
+
+def test_captured_planet_pays_its_new_owner():
+    pathfinder = bridge.RustPathfinder()
+    pathfinder.add_node("Cadia", "Forest")
+    economy = bridge.RustEconomyEngine()
+    economy.add_node(node("Cadia", "Imperium", "Planet", 50, 0))
+    economy.add_node(node("Cadia-PDF", "Imperium", "Army", 0, 10))
+    turns = bridge.RustTurnEngine(pathfinder, economy)
+
+    order = {"id": "i1", "planet": "Cadia", "attacker": "Orks", "troops": 5.0, "garrison": ["Cadia-PDF"]}
+    repelled = json.loads(turns.invade(json.dumps(order)))
+    assert repelled["result"] == "Repelled"
+    assert repelled["terrain"] == "Forest"
+
+    turns.queue_invasion(json.dumps(dict(order, troops=50.0)))
+    report = json.loads(turns.run_turn())
+    assert report["invasions"][0]["result"] == "Occupied"
+    assert report["factions"]["Orks"]["income"]["credits"] == 50_000_000
+
+    with pytest.raises(ValueError):
+        turns.invade(json.dumps(order))
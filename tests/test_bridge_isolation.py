@@ -0,0 +1,35 @@
+"""Engine instances in one process must not leak state into each other, and
+`reset_module_state()` must leave nothing running between tests."""
+
+import time
+
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+
+def info_messages(log, seed):
+    log.set_sample_rate(0.5)
+    log.seed_sampling(seed)
+    for i in range(64):
+        log.add(bridge.Event(bridge.EventSeverity.Info, "Test", str(i), bridge.CorrelationContext()))
+    return [event.message for event in log.get_all()]
+
+
+def test_sampling_is_per_log():
+    first = info_messages(bridge.observability.EventLog(), seed=9)
+    # Sampling on an unrelated log in between must not shift the next one.
+    info_messages(bridge.observability.EventLog(), seed=1)
+    assert info_messages(bridge.observability.EventLog(), seed=9) == first
+
+
+def test_reset_module_state_stops_background_runs():
+    engine = bridge.RustCombatEngine(200.0, 200.0)
+    laser = bridge.WeaponSpec("Laser", 300.0, 1.0, "Energy")
+    for i in range(10):
+        engine.add_unit_spec(bridge.UnitSpec(i, i % 2, 1e6, x=float(i * 4), weapons=[laser]))
+    run = engine.run_to_completion_async(progress_every=1, progress=lambda turn, living: time.sleep(0.01) or True)
+
+    assert bridge.reset_module_state() == {"battle_runs_stopped": 1}
+    assert not run.running and run.cancelled
+    assert bridge.reset_module_state() == {"battle_runs_stopped": 0}
@@ -0,0 +1,13 @@
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+
+def test_k_paths_cheapest_first():
+    pf = bridge.RustPathfinder()
+    for u, v, w in [("A", "B", 1.0), ("B", "D", 1.0), ("A", "C", 2.0), ("C", "D", 2.0), ("A", "D", 5.0)]:
+        pf.add_edge(u, v, w)
+    paths = pf.find_k_paths("A", "D", 5)
+    assert paths == [(["A", "B", "D"], 2.0), (["A", "C", "D"], 4.0), (["A", "D"], 5.0)]
+    assert pf.find_k_paths("A", "D", 1) == [pf.find_path("A", "D")]
+    assert pf.find_k_paths("D", "A", 3) == []
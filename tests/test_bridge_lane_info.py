@@ -0,0 +1,21 @@
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+
+def test_wormhole_only_routing():
+    pf = bridge.RustPathfinder()
+    pf.sync_topology([("A", ["B", "C"]), ("B", ["C"])])
+    pf.update_edge_weight("A", "C", 5.0)
+    assert pf.set_lane_info("A", "C", "Wormhole", capacity=3, owner="Eldar") == 1
+    assert pf.lane_info("A", "C") == ("Wormhole", 3, "Eldar")
+    assert pf.lane_info("A", "B") == ("Hyperlane", None, None)
+    assert pf.lane_info("C", "A") is None
+    assert pf.lanes_of_kind("Wormhole") == [("A", "C")]
+    assert pf.lanes_owned_by("Eldar") == [("A", "C")]
+
+    assert pf.find_path("A", "C")[0] == ["A", "B", "C"]
+    assert pf.find_path("A", "C", lane_kinds=["Wormhole"]) == (["A", "C"], 5.0)
+    assert pf.find_path("A", "C", lane_kinds=["Slipstream"]) is None
+    with pytest.raises(ValueError):
+        pf.set_lane_info("A", "B", "Warp")
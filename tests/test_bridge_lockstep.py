@@ -0,0 +1,62 @@
+"""Two clients running the same campaign in lockstep mode must report equal
+state hashes every turn."""
+
+import json
+
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+
+def campaign(seed):
+    battle = bridge.RustCombatEngine(200.0, 200.0)
+    laser = bridge.WeaponSpec("Laser", 30.0, 10.0, "Energy")
+    for i in range(8):
+        battle.add_unit_spec(bridge.UnitSpec(i, i % 2, 300.0, x=float(i * 20), weapons=[laser]))
+    pathfinder = bridge.RustPathfinder()
+    pathfinder.sync_topology([("A", ["B"]), ("B", ["C"])])
+
+    state = bridge.RustCampaignState(1)
+    state.pathfinder = pathfinder
+    state.economy = bridge.RustEconomyEngine()
+    state.add_battle("front", battle)
+    state.set_deterministic(seed)
+    return state, battle
+
+
+def test_lockstep_clients_stay_in_sync():
+    (first, first_battle), (second, second_battle) = campaign(5), campaign(5)
+    assert first_battle.deterministic
+    for _ in range(30):
+        first_battle.step()
+        second_battle.step()
+        assert first.state_hash() == second.state_hash()
+    assert first.state_hashes() == second.state_hashes()
+
+
+def test_diverged_battle_is_reported():
+    (first, first_battle), (second, _) = campaign(5), campaign(5)
+    first_battle.step()
+    first_hashes, second_hashes = first.state_hashes(), second.state_hashes()
+    assert first_hashes["battles"]["front"] != second_hashes["battles"]["front"]
+    assert first_hashes["pathfinder"] == second_hashes["pathfinder"]
+
+
+def test_desync_report_names_engine_and_field():
+    (first, _), (second, _) = campaign(5), campaign(5)
+    diplomacy = bridge.RustDiplomacyEngine()
+    first.track("diplomacy", diplomacy)
+    second.track("diplomacy", bridge.RustDiplomacyEngine())
+    assert first.check_desync(second.state_digest()) is None
+
+    diplomacy.sign_treaty("alliance", "Imperium", "Eldar")
+    log = bridge.observability.EventLog()
+    second.add_event_log("net", log)
+    report = json.loads(second.check_desync(first.state_digest()))
+    assert [d["subsystem"] for d in report["divergences"]] == ["diplomacy"]
+    assert report["divergences"][0]["fields"]
+
+    (event,) = log.get_all()
+    assert event.severity == bridge.observability.EventSeverity.Critical
+    assert event.category == "Desync" and "diplomacy" in event.message
+    assert event.get_field("fields")["diplomacy"] == report["divergences"][0]["fields"]
@@ -0,0 +1,63 @@
+"""Supply logistics: minerals travel from the mining world to the forge
+world along the cheapest open lanes, within lane capacity, and upkeep
+nothing can reach goes short."""
+
+import json
+
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+SCALE = 1_000_000
+
+
+def node(node_id, faction, mined=0, used=0):
+    return json.dumps({
+        "id": node_id,
+        "owner_faction": faction,
+        "node_type": "Planet",
+        "base_income": {"credits": 0, "minerals": mined * SCALE, "energy": 0, "research": 0},
+        "base_upkeep": {"credits": 0, "minerals": used * SCALE, "energy": 0, "research": 0},
+        "efficiency_scaled": SCALE,
+        "modifiers": [],
+    })
+
+
+def galaxy():
+    pathfinder = bridge.RustPathfinder()
+    for a, b, weight in [("Terra", "Mars", 1.0), ("Mars", "Luna", 1.0), ("Terra", "Luna", 5.0)]:
+        pathfinder.add_edge(a, b, weight)
+    economy = bridge.RustEconomyEngine()
+    economy.add_node(node("Terra", "Imperium", mined=50))
+    economy.add_node(node("Luna", "Imperium", used=30))
+    return pathfinder, economy
+
+
+def test_minerals_take_the_cheapest_open_route():
+    pathfinder, economy = galaxy()
+    supply = json.loads(economy.plan_supply(pathfinder, "Imperium"))
+    assert {(f["from"], f["to"]) for f in supply["flows"]} == {("Terra", "Mars"), ("Mars", "Luna")}
+    assert supply["shortages"] == [] and supply["cost"] == 60 * SCALE
+
+    # Orks on Mars shut the short route; the long lane only carries 10.
+    economy.add_node(node("Mars", "Orks"))
+    rules = {"capacities": [{"from": "Terra", "to": "Luna", "capacity": 10 * SCALE}]}
+    supply = json.loads(economy.plan_supply(pathfinder, "Imperium", json.dumps(rules)))
+    assert [(f["from"], f["to"], f["amount"]) for f in supply["flows"]] == [("Terra", "Luna", 10 * SCALE)]
+    assert supply["shortages"][0]["missing"] == 20 * SCALE
+
+
+def test_turn_engine_charges_only_delivered_upkeep():
+    pathfinder, economy = galaxy()
+    economy.add_node(node("Mars", "Orks"))
+    turns = bridge.RustTurnEngine(pathfinder, economy)
+    turns.set_logistics_rules(json.dumps({"capacities": [{"from": "Terra", "to": "Luna", "capacity": 10 * SCALE}]}))
+    assert json.loads(turns.logistics_rules())["resources"] == ["Minerals", "Energy"]
+
+    report = json.loads(turns.run_turn())
+    imperium = report["factions"]["Imperium"]
+    assert imperium["supply"]["missing"]["minerals"] == 20 * SCALE
+    assert imperium["upkeep"]["minerals"] == 10 * SCALE
+
+    turns.set_logistics_rules(None)
+    assert turns.logistics_rules() is None
@@ -0,0 +1,30 @@
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+INF = float("inf")
+
+
+def test_registered_profile_routes_by_terrain():
+    pf = bridge.RustPathfinder()
+    pf.register_terrain("Swamp")
+    pf.add_node("Bog", "Swamp")
+    pf.add_node("Lake", "Water")
+    pf.add_node("Peak", "Mountain")
+    for mid in ["Bog", "Lake", "Peak"]:
+        pf.add_edge("A", mid, 1.0)
+        pf.add_edge(mid, "B", 1.0)
+
+    pf.register_profile("Amphibious", {"Water": 1.2, "Swamp": 3.0, "Mountain": INF})
+    path, cost = pf.find_path("A", "B", "Amphibious")
+    assert path == ["A", "Lake", "B"]
+    assert cost == pytest.approx(2.2)
+    assert pf.find_path("A", "Peak", "Amphibious") is None
+
+    with pytest.raises(ValueError):
+        pf.register_profile("Amphibious", {"Lava": 2.0})
+    with pytest.raises(ValueError):
+        pf.register_profile("Ground", {})
+
+    restored = bridge.RustPathfinder.from_json(pf.to_json())
+    assert restored.find_path("A", "B", "Amphibious") == (path, cost)
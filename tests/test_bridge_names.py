@@ -0,0 +1,49 @@
+"""Procedural names: each faction names things in its own tongue, the same
+key always gets the same name, and a batch never repeats one."""
+
+import json
+
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+TABLES = {
+    "factions": {
+        "Imperium": {
+            "syllables": {"onsets": ["t", "c", "v", "s"], "nuclei": ["a", "e", "i"], "codas": ["r", "s", ""]},
+            "min_syllables": 2,
+            "max_syllables": 3,
+            "templates": {
+                "planet": ["{parent} {numeral}"],
+                "fleet": ["{number}th {host}"],
+                "lore": ["The {root} Schism of {parent}"],
+            },
+            "words": {"host": ["Crusade", "Armada"]},
+        },
+        "Orks": {
+            "syllables": {"onsets": ["g", "z", "k"], "nuclei": ["u", "o"], "codas": ["g", "k", "z"]},
+            "templates": {"character": ["{title} {root}"]},
+            "words": {"title": ["Warboss", "Big Mek"]},
+        },
+    },
+}
+
+
+def test_names_are_reproducible_and_follow_the_faction_grammar():
+    names = bridge.RustNameGenerator(json.dumps(TABLES), seed=11)
+    terra = names.name("system", "Imperium", "sys-0")
+    assert terra == bridge.RustNameGenerator(json.dumps(TABLES), seed=11).name("system", "Imperium", "sys-0")
+    assert names.name("planet", "Imperium", "p-0", parent=terra, index=3) == f"{terra} III"
+    assert names.name("fleet", "Imperium", "f-0", index=4).startswith("4th ")
+    assert names.name("lore", "Imperium", "l-0", parent=terra).endswith(f"Schism of {terra}")
+    assert names.name("character", "Orks", "boss").split(" ")[0] in {"Warboss", "Big"}
+
+    batch = [{"kind": "system", "faction": "Imperium", "key": f"sys-{i}"} for i in range(40)]
+    assert len(set(names.names(json.dumps(batch)))) == 40
+
+    with pytest.raises(bridge.RegistryError):
+        names.name("system", "Tau", "sys-0")
+    with pytest.raises(ValueError):
+        names.name("moon", "Imperium", "m")
+    with pytest.raises(bridge.RegistryError):
+        bridge.RustNameGenerator(json.dumps({"factions": {"X": {"syllables": {"nuclei": []}}}}))
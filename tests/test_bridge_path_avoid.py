@@ -0,0 +1,25 @@
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+
+def test_routes_around_avoided_and_enemy_systems():
+    pf = bridge.RustPathfinder()
+    for u, v, w in [("A", "B", 1.0), ("B", "D", 1.0), ("A", "C", 2.0), ("C", "D", 2.0)]:
+        pf.add_edge(u, v, w)
+    assert pf.find_path("A", "D")[0] == ["A", "B", "D"]
+    assert pf.find_path("A", "D", avoid=["B"])[0] == ["A", "C", "D"]
+    owners = {"B": "Orks", "C": "Eldar", "D": "Orks"}
+    assert pf.find_path("A", "D", blocked_factions=["Orks"], system_owners=owners)[0] == ["A", "C", "D"]
+    assert pf.find_path("A", "D", blocked_factions=["Orks", "Eldar"], system_owners=owners) is None
+
+
+def test_blocked_factions_fall_back_to_stored_owners():
+    pf = bridge.RustPathfinder()
+    for u, v, w in [("A", "B", 1.0), ("B", "D", 1.0), ("A", "C", 2.0), ("C", "D", 2.0)]:
+        pf.add_edge(u, v, w)
+    with pytest.raises(ValueError):
+        pf.find_path("A", "D", blocked_factions=["Orks"])
+    pf.set_node_owner("B", "Orks")
+    assert pf.find_path("A", "D", blocked_factions=["Orks"])[0] == ["A", "C", "D"]
+    assert pf.reader().find_path("A", "D", blocked_factions=["Orks"])[0] == ["A", "C", "D"]
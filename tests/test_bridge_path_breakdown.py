@@ -0,0 +1,21 @@
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+
+def test_detailed_path_explains_each_hop():
+    pf = bridge.RustPathfinder()
+    pf.add_node("F", "Forest")
+    pf.add_edge("A", "F", 2.0)
+    pf.add_edge("F", "B", 1.0)
+    pf.set_lane_modifier("A", "F", "ion_storm", 3.0)
+
+    detailed = pf.find_path_detailed("A", "B", "Ground")
+    assert detailed["path"] == ["A", "F", "B"]
+    assert detailed["cost"] == pf.find_path("A", "B", "Ground")[1] == 10.0
+    first, second = detailed["hops"]
+    assert (first["base_cost"], first["lane_cost"], first["terrain"]) == (2.0, 6.0, "Forest")
+    assert first["modifiers"] == [["ion_storm", 3.0]]
+    assert (first["terrain_multiplier"], first["cost"], first["cumulative"]) == (1.5, 9.0, 9.0)
+    assert (second["cost"], second["cumulative"]) == (1.0, 10.0)
+    assert pf.find_path_detailed("B", "A") is None
@@ -0,0 +1,20 @@
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+
+def test_path_cache_hits_and_invalidation():
+    pf = bridge.RustPathfinder()
+    pf.sync_topology([("A", ["B"]), ("B", ["C"])])
+    pf.find_path("A", "C")
+    pf.find_path("A", "C")
+    stats = pf.path_cache_stats()
+    assert (stats["hits"], stats["misses"], stats["entries"]) == (1, 1, 1)
+
+    pf.update_edge_weight("B", "C", 3.0)
+    assert pf.path_cache_stats()["entries"] == 0
+    assert pf.find_path("A", "C")[1] == 4.0
+
+    pf.set_path_cache_capacity(0)
+    pf.find_path("A", "C")
+    assert pf.path_cache_stats()["entries"] == 0
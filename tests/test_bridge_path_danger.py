@@ -0,0 +1,13 @@
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+
+def test_cautious_profile_avoids_danger():
+    pf = bridge.RustPathfinder()
+    for u, v, w in [("A", "B", 1.0), ("B", "D", 1.0), ("A", "C", 2.0), ("C", "D", 2.0)]:
+        pf.add_edge(u, v, w)
+    assert pf.set_node_danger("B", 5.0)
+    assert pf.node_danger("B") == 5.0
+    assert pf.find_path("A", "D")[0] == ["A", "B", "D"]
+    assert pf.find_path("A", "D", "Cautious") == (["A", "C", "D"], 4.0)
@@ -0,0 +1,17 @@
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+
+def test_hop_and_fuel_limits():
+    pf = bridge.RustPathfinder()
+    for u, v, w in [("A", "B", 1.0), ("B", "C", 1.0), ("C", "D", 1.0), ("A", "D", 5.0)]:
+        pf.add_edge(u, v, w)
+    assert pf.find_path("A", "D") == (["A", "B", "C", "D"], 3.0)
+    assert pf.find_path("A", "D", max_hops=2) == (["A", "D"], 5.0)
+    assert pf.find_path("A", "D", max_hops=2, max_cost=4.0) is None
+    assert pf.find_path("A", "D", max_cost=3.0) == (["A", "B", "C", "D"], 3.0)
+
+    assert pf.find_path_partial("A", "D", max_cost=2.5) == (["A", "B", "C"], 2.0)
+    assert pf.find_path_partial("A", "D", max_hops=1) == (["A", "B"], 1.0)
+    assert pf.find_path_partial("D", "A") is None
@@ -0,0 +1,28 @@
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+
+def build(positioned):
+    pf = bridge.RustPathfinder()
+    for y in range(6):
+        for x in range(6):
+            if positioned:
+                pf.add_node_with_position(f"{x}_{y}", float(x), float(y))
+            else:
+                pf.add_node(f"{x}_{y}")
+    for y in range(6):
+        for x in range(6):
+            if x < 5:
+                pf.add_edge(f"{x}_{y}", f"{x + 1}_{y}", 1.0 + (x * y) % 3)
+            if y < 5:
+                pf.add_edge(f"{x}_{y}", f"{x}_{y + 1}", 1.0 + (x + y) % 2)
+    return pf
+
+
+def test_positions_do_not_change_costs():
+    plain, placed = build(False), build(True)
+    assert placed.position("2_3") == (2.0, 3.0)
+    assert plain.position("2_3") is None
+    for goal in ["5_5", "5_0", "0_5", "3_2"]:
+        assert placed.find_path("0_0", goal)[1] == plain.find_path("0_0", goal)[1]
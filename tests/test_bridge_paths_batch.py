@@ -0,0 +1,25 @@
+"""Batch pathfinding: thousands of fleet routes, each with its own movement
+profile, resolved in one parallel call that agrees with single queries."""
+
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+
+def test_batch_matches_single_queries():
+    pathfinder = bridge.RustPathfinder()
+    pathfinder.add_node("Ocean", "Water")
+    for i in range(50):
+        pathfinder.add_edge(f"S{i}", f"S{(i + 1) % 50}", 1.0 + i % 3)
+    pathfinder.add_edge("S0", "Ocean", 1.0)
+
+    queries = [(f"S{i % 50}", f"S{(i * 7) % 50}") for i in range(2000)]
+    queries += [("S0", "Ocean", "Ground"), ("S0", "Ocean"), ("S0", "Nowhere", None)]
+    results = pathfinder.find_paths_batch(queries)
+
+    assert len(results) == len(queries)
+    assert results[:2000] == [pathfinder.find_path(s, e) for s, e in queries[:2000]]
+    assert results[2000] is None and results[2001] == (["S0", "Ocean"], 1.0)
+    assert results[2002] is None
+    # A batch-wide profile covers queries that don't name one.
+    assert pathfinder.find_paths_batch([("S0", "Ocean")], profile="Ground") == [None]
@@ -0,0 +1,12 @@
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+
+def test_reachable_within_budget():
+    pf = bridge.RustPathfinder()
+    for u, v, w in [("A", "B", 1.0), ("B", "C", 2.0), ("C", "D", 1.0)]:
+        pf.add_edge(u, v, w)
+    assert pf.reachable_within("A", 3.0) == [("A", 0.0), ("B", 1.0), ("C", 3.0)]
+    assert pf.reachable_within("A", 0.5) == [("A", 0.0)]
+    assert pf.reachable_within("Z", 3.0) == []
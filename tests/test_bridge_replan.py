@@ -0,0 +1,21 @@
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+
+def test_route_planner_repairs_after_blockade():
+    pf = bridge.RustPathfinder(undirected=True)
+    for u, v, w in [("A", "B", 1.0), ("B", "C", 1.0), ("C", "D", 1.0), ("A", "E", 2.0), ("E", "D", 2.0)]:
+        pf.add_edge(u, v, w)
+    planner = bridge.RustRoutePlanner(pf, "A", "D")
+    assert planner.path() == (["A", "B", "C", "D"], 3.0)
+
+    planner.move_to("B")
+    pf.set_lane_modifier("C", "D", "blockade", 10.0)
+    assert planner.start == "B"
+    assert planner.path() == pf.find_path("B", "D")
+    assert planner.path() == (["B", "A", "E", "D"], 5.0)
+
+    pf.remove_edge("E", "D")
+    pf.remove_edge("C", "D")
+    assert planner.path() is None
@@ -0,0 +1,64 @@
+"""A recorded campaign can be rewound to an earlier turn and re-simulated
+to the same state, including an invasion and edits made on the engines."""
+
+import json
+
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+
+def node(node_id, owner, node_type, income, upkeep):
+    def credits(amount):
+        return {"credits": amount * 1_000_000, "minerals": 0, "energy": 0, "research": 0}
+
+    return json.dumps({
+        "id": node_id,
+        "owner_faction": owner,
+        "node_type": node_type,
+        "base_income": credits(income),
+        "base_upkeep": credits(upkeep),
+        "efficiency_scaled": 1_000_000,
+        "modifiers": [],
+    })
+
+
+def snapshot(turns, economy):
+    return {f: r.credits for f, r in turns.treasuries().items()}, economy.state_hash()
+
+
+def test_rewind_replays_to_the_same_state():
+    pathfinder = bridge.RustPathfinder()
+    pathfinder.add_node("Cadia", "Plains")
+    economy = bridge.RustEconomyEngine()
+    economy.add_node(node("Cadia", "Imperium", "Planet", 50, 0))
+    turns = bridge.RustTurnEngine(pathfinder, economy)
+    turns.start_recording(keyframe_interval=3)
+    assert turns.is_recording
+
+    history = []
+    for turn in range(5):
+        if turn == 1:
+            turns.invade(json.dumps({"id": "i1", "planet": "Cadia", "attacker": "Orks", "troops": 5.0}))
+        if turn == 3:
+            economy.add_node(node("Armageddon", "Imperium", "Planet", 20, 0))
+        turns.run_turn()
+        history.append(snapshot(turns, economy))
+
+    commands = json.loads(turns.recorded_commands())
+    assert list(commands["1"][0]) == ["Invade"]
+
+    reports = json.loads(turns.rewind_to(2))
+    assert [r["turn"] for r in reports] == [1, 2]
+    assert turns.keyframe_turns() == [0]
+    assert turns.turn == 2
+    assert snapshot(turns, economy) == history[1]
+
+    # Playing on from the rewind reproduces the original timeline.
+    turns.run_turn()
+    economy.add_node(node("Armageddon", "Imperium", "Planet", 20, 0))
+    turns.run_turn()
+    assert snapshot(turns, economy) == history[3]
+
+    with pytest.raises(ValueError):
+        turns.rewind_to(10)
@@ -0,0 +1,11 @@
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+
+def test_route_via_waypoints():
+    pf = bridge.RustPathfinder()
+    pf.sync_topology([("A", ["B"]), ("B", ["C", "A"]), ("C", ["A", "B"])])
+    assert pf.find_route_via("A", ["C"], "A") == (["A", "B", "C", "A"], 3.0)
+    assert pf.find_route_via("A", [], "C") == pf.find_path("A", "C")
+    assert pf.find_route_via("A", ["Z"], "C") is None
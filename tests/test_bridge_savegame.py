@@ -0,0 +1,50 @@
+"""Campaign saves are chunked per engine: one corrupted chunk costs that
+engine, not the whole save."""
+
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+
+def campaign():
+    battle = bridge.RustCombatEngine(200.0, 200.0)
+    battle.add_unit_spec(bridge.UnitSpec(0, 0, 300.0))
+    pathfinder = bridge.RustPathfinder()
+    pathfinder.sync_topology([("A", ["B"]), ("B", ["C"])])
+
+    state = bridge.RustCampaignState(7)
+    state.pathfinder = pathfinder
+    state.economy = bridge.RustEconomyEngine()
+    state.add_battle("front", battle)
+    return state
+
+
+def test_round_trip_has_no_damage():
+    state = campaign()
+    loaded = bridge.RustCampaignState.from_bytes(state.to_bytes())
+    assert loaded.turn == 7
+    assert loaded.damaged_chunks() == []
+    assert loaded.state_hashes() == state.state_hashes()
+
+
+def test_corrupted_chunk_is_isolated():
+    state = campaign()
+    data = bytearray(state.to_bytes())
+    # The battle is the last chunk written; damage its final byte.
+    data[-1] ^= 0xFF
+
+    loaded = bridge.RustCampaignState.from_bytes(bytes(data))
+    assert loaded.damaged_chunks() == [("battle:front", "checksum mismatch")]
+    assert loaded.battle_names() == []
+    assert loaded.turn == 7
+    assert loaded.state_hashes()["pathfinder"] == state.state_hashes()["pathfinder"]
+
+    with pytest.raises(Exception):
+        bridge.RustCampaignState.from_bytes(bytes(data), strict=True)
+
+
+def test_damaged_header_fails_the_load():
+    data = bytearray(campaign().to_bytes())
+    data[14] ^= 0xFF
+    with pytest.raises(Exception):
+        bridge.RustCampaignState.from_bytes(bytes(data))
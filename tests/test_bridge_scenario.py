@@ -0,0 +1,77 @@
+"""Scenario scripts fire after each turn: a captured planet spawns a fleet
+and pays the attacker, and a warp storm on turn 2 makes a route costlier."""
+
+import json
+
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+CREDITS = 1_000_000
+
+
+def node(node_id, owner, node_type, income):
+    return json.dumps({
+        "id": node_id,
+        "owner_faction": owner,
+        "node_type": node_type,
+        "base_income": {"credits": income * CREDITS, "minerals": 0, "energy": 0, "research": 0},
+        "base_upkeep": {"credits": 0, "minerals": 0, "energy": 0, "research": 0},
+        "efficiency_scaled": 1_000_000,
+        "modifiers": [],
+    })
+
+
+SCRIPT = {
+    "name": "Fall of Cadia",
+    "rules": [
+        {
+            "id": "cadia-falls",
+            "trigger": {"SystemCaptured": {"system": "Cadia"}},
+            "effects": [
+                {"GrantResources": {"resources": {"credits": 500 * CREDITS, "minerals": 0, "energy": 0, "research": 0}}},
+                {"SpawnFleet": {"fleet": {
+                    "id": "black-crusade",
+                    "faction": "Chaos",
+                    "location": "Cadia",
+                    "ships": [],
+                    "supply": 10.0,
+                    "max_supply": 10.0,
+                    "fuel": 5.0,
+                    "max_fuel": 5.0,
+                    "speed": 10.0,
+                }}},
+            ],
+        },
+        {
+            "id": "warp-storm",
+            "trigger": {"OnTurn": {"turn": 2}},
+            "effects": [{"SetEdgeWeight": {"from": "Cadia", "to": "Terra", "weight": 7.5}}],
+        },
+    ],
+}
+
+
+def test_script_fires_on_capture_and_turn():
+    pathfinder = bridge.RustPathfinder()
+    pathfinder.add_edge("Cadia", "Terra", 1.0)
+    economy = bridge.RustEconomyEngine()
+    economy.add_node(node("Cadia", "Imperium", "Planet", 10))
+    fleets = bridge.RustFleetManager()
+    scenario = bridge.RustScenario(json.dumps(SCRIPT), fleets=fleets)
+    turns = bridge.RustTurnEngine(pathfinder, economy, scenario=scenario)
+
+    turns.queue_invasion(json.dumps({"id": "i1", "planet": "Cadia", "attacker": "Chaos", "troops": 5.0}))
+    report = json.loads(turns.run_turn())
+    assert [f["rule"] for f in report["scenario"]["fired"]] == ["cadia-falls"]
+    assert turns.treasury("Chaos").credits == 510.0
+    assert json.loads(fleets.fleet("black-crusade"))["location"] == "Cadia"
+    assert scenario.fired_rules() == ["cadia-falls"]
+
+    report = json.loads(turns.run_turn())
+    assert [f["rule"] for f in report["scenario"]["fired"]] == ["warp-storm"]
+    assert pathfinder.find_path("Cadia", "Terra", None)[1] == 7.5
+
+    # One-shot rules stay fired across a save.
+    restored = bridge.RustScenario.from_json(scenario.to_json())
+    assert restored.fired_rules() == ["cadia-falls", "warp-storm"]
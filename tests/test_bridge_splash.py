@@ -0,0 +1,42 @@
+"""Weapons with an aoe_radius splash everything hostile near the impact,
+falling off with distance; friendly fire extends that to allies."""
+
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+
+def bombardment(friendly_fire):
+    battle = bridge.RustCombatEngine(200.0, 200.0, seed=4)
+    battle.friendly_fire = friendly_fire
+    torpedo = bridge.WeaponSpec("Torpedo", 100.0, 40.0, "Missile", aoe_radius=20.0)
+    battle.add_unit_spec(bridge.UnitSpec(1, 0, 1000.0, weapons=[torpedo]))
+    battle.add_unit_spec(bridge.UnitSpec(2, 0, 1000.0, x=45.0))  # ally next to the target
+    battle.add_unit_spec(bridge.UnitSpec(3, 1, 1000.0, x=50.0))  # target
+    battle.add_unit_spec(bridge.UnitSpec(4, 1, 1000.0, x=60.0))  # halfway to the edge
+    battle.add_unit_spec(bridge.UnitSpec(5, 1, 1000.0, x=80.0))  # outside the blast
+    for _ in range(10):
+        battle.step()
+    return battle.get_battle_statistics()["units"]
+
+
+def test_splash_falls_off_and_spares_allies():
+    units = bombardment(friendly_fire=False)
+    target = units[3]["damage_taken"]
+    assert target > 0.0
+    assert units[4]["damage_taken"] == pytest.approx(target * 0.5)
+    assert 5 not in units and 2 not in units
+    # Splash is damage dealt but not extra hits.
+    assert units[1]["hits"] <= units[1]["shots_fired"] == 10
+    assert units[1]["damage_dealt"] == pytest.approx(target * 1.5)
+
+    units = bombardment(friendly_fire=True)
+    assert units[2]["damage_taken"] == pytest.approx(units[3]["damage_taken"] * 0.75)
+    # Splash on an ally is tallied apart from damage dealt.
+    assert units[1]["friendly_damage"] == pytest.approx(units[2]["damage_taken"])
+    assert units[1]["damage_dealt"] == pytest.approx(units[3]["damage_taken"] * 1.5)
+
+
+def test_negative_radius_is_rejected():
+    with pytest.raises(ValueError):
+        bridge.WeaponSpec("Torpedo", 100.0, 40.0, aoe_radius=-1.0)
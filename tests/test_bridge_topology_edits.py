@@ -0,0 +1,16 @@
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+
+def test_incremental_edits():
+    pf = bridge.RustPathfinder()
+    pf.sync_topology([("A", ["B", "C"]), ("B", ["D"]), ("C", ["D"])])
+    assert pf.update_edge_weight("A", "B", 5.0) == 1
+    assert pf.find_path("A", "D") == (["A", "C", "D"], 2.0)
+    assert pf.remove_edge("A", "C") == 1
+    assert pf.find_path("A", "D") == (["A", "B", "D"], 6.0)
+    assert pf.remove_node("B")
+    assert not pf.remove_node("B")
+    assert pf.find_path("A", "D") is None
+    assert pf.update_edge_weight("A", "B", 1.0) == 0
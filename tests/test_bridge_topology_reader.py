@@ -0,0 +1,32 @@
+import threading
+
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+
+def test_readers_search_a_frozen_copy_from_many_threads():
+    pf = bridge.RustPathfinder()
+    for i in range(30):
+        pf.add_edge(f"S{i}", f"S{i + 1}", 1.0)
+    reader = pf.reader()
+    assert reader.state_hash() == pf.state_hash()
+
+    results = []
+
+    def search():
+        results.append(reader.find_paths_batch([(f"S{i}", "S30") for i in range(30)]))
+
+    threads = [threading.Thread(target=search) for _ in range(4)]
+    for thread in threads:
+        thread.start()
+    # Edits to the live pathfinder don't reach the copy.
+    pf.remove_edge("S29", "S30")
+    for thread in threads:
+        thread.join()
+
+    assert all(r == results[0] for r in results) and len(results) == 4
+    assert results[0][0] == reader.find_path("S0", "S30")
+    assert reader.find_path("S29", "S30") == (["S29", "S30"], 1.0)
+    assert pf.find_path("S29", "S30") is None
+    assert reader.find_path("S0", "S30", max_hops=5) is None
@@ -0,0 +1,31 @@
+import os
+import tempfile
+
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+
+def test_topology_round_trips_through_a_file():
+    pf = bridge.RustPathfinder()
+    pf.add_node_with_position("A", 0.0, 0.0, "Plains")
+    pf.add_node_with_position("B", 1.0, 0.0)
+    pf.add_edge("A", "B", 2.5)
+    pf.set_node_danger("B", 1.0)
+
+    restored = bridge.RustPathfinder.from_json(pf.to_json())
+    assert restored.find_path("A", "B") == pf.find_path("A", "B")
+    assert restored.position("B") == (1.0, 0.0)
+
+    loaded = bridge.RustPathfinder()
+    loaded.sync_topology([("X", ["Y"])])
+    with tempfile.TemporaryDirectory() as tmp:
+        path = os.path.join(tmp, "map.json")
+        pf.save(path)
+        loaded.load(path)
+    assert loaded.find_path("X", "Y") is None
+    assert loaded.node_danger("B") == 1.0
+
+    with pytest.raises(Exception):
+        loaded.restore("not json")
+    assert loaded.find_path("A", "B") == (["A", "B"], 2.5)
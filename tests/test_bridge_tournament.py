@@ -0,0 +1,61 @@
+"""Balance tournaments: a parameter grid over a matchup, swept across
+seeds on every core, aggregated into win rates and income curves."""
+
+import csv
+import io
+import json
+
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+
+def gun(damage):
+    return {"name": "Gun", "range": 500.0, "damage": damage, "accuracy": 0.7}
+
+
+def test_damage_grid_shifts_win_rate():
+    spec = {
+        "matchups": [{
+            "name": "escorts", "width": 300.0, "height": 300.0, "max_turns": 300,
+            "units": [
+                {"id": i, "faction_idx": i % 2, "max_hp": 120.0, "x": 40.0 * (i % 2), "y": 10.0 * i, "weapons": [gun(12.0)]}
+                for i in range(6)
+            ],
+        }],
+        "grid": [{"name": "imperial_guns", "path": "/units/0/weapons/*/damage", "values": [0.5, 4.0], "scale": True}],
+        "seeds": 12,
+        "seed": 7,
+    }
+    result = bridge.run_battle_tournament(json.dumps(spec))
+    stats = json.loads(result.to_json())
+    assert [s["parameters"] for s in stats] == [[["imperial_guns", 0.5]], [["imperial_guns", 4.0]]]
+    assert stats[1]["factions"][0]["win_rate"] > stats[0]["factions"][0]["win_rate"]
+    assert result.to_json() == bridge.run_battle_tournament(json.dumps(spec), workers=1).to_json()
+
+    rows = list(csv.DictReader(io.StringIO(result.to_csv())))
+    assert len(rows) == len(result) == 4
+    assert rows[0]["matchup"] == "escorts" and rows[0]["imperial_guns"] == "0.5"
+    assert result.columns()["faction_idx"] == [0, 1, 0, 1]
+
+
+def test_income_curves_and_bad_axes():
+    spec = {
+        "scenarios": [{
+            "name": "core_worlds",
+            "nodes": [{
+                "id": "Terra", "owner_faction": "Imperium", "node_type": "Planet",
+                "base_income": {"credits": 5_000_000, "minerals": 0, "energy": 0, "research": 0},
+                "base_upkeep": {"credits": 1_000_000, "minerals": 0, "energy": 0, "research": 0},
+                "efficiency_scaled": 1_000_000, "modifiers": [],
+            }],
+            "turns": 4,
+        }],
+    }
+    result = bridge.run_economy_tournament(json.dumps(spec))
+    assert result.column_names()[:2] == ["scenario", "faction"]
+    assert result.columns()["treasury"] == [4.0, 8.0, 12.0, 16.0]
+
+    spec["grid"] = [{"name": "oops", "path": "/nodes/3/base_income", "values": [1]}]
+    with pytest.raises(ValueError):
+        bridge.run_economy_tournament(json.dumps(spec))
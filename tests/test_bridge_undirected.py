@@ -0,0 +1,17 @@
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+
+def test_bidirectional_lanes():
+    pf = bridge.RustPathfinder()
+    assert not pf.undirected
+    pf.add_edge_bidirectional("A", "B", 2.0)
+    assert pf.find_path("B", "A") == (["B", "A"], 2.0)
+
+    two_way = bridge.RustPathfinder(undirected=True)
+    two_way.sync_topology([("A", ["B"]), ("B", ["C"])])
+    assert two_way.find_path("C", "A") == (["C", "B", "A"], 2.0)
+    assert two_way.remove_edge("B", "C") == 2
+    assert two_way.find_path("C", "A") is None
+    assert bridge.RustPathfinder.from_json(two_way.to_json()).undirected
@@ -0,0 +1,45 @@
+"""Victory rules are checked at the end of every turn: progress is reported
+per faction and the first condition met decides the verdict."""
+
+import json
+
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+
+def planet(node_id, owner, income):
+    return json.dumps({
+        "id": node_id,
+        "owner_faction": owner,
+        "node_type": "Planet",
+        "base_income": {"credits": income * 1_000_000, "minerals": 0, "energy": 0, "research": 0},
+        "base_upkeep": {"credits": 0, "minerals": 0, "energy": 0, "research": 0},
+        "efficiency_scaled": 1_000_000,
+        "modifiers": [],
+    })
+
+
+def test_economic_dominance_decides_the_game():
+    economy = bridge.RustEconomyEngine()
+    economy.add_node(planet("Terra", "Imperium", 90))
+    economy.add_node(planet("Kor", "Orks", 10))
+    turns = bridge.RustTurnEngine(bridge.RustPathfinder(), economy)
+    turns.set_victory_rules(json.dumps({
+        "victory": [{"EconomicDominance": {"share": 0.8, "turns": 2}}, {"Survival": {"turns": 10}}],
+        "defeat": ["Elimination"],
+    }))
+    log = turns.enable_event_logging()
+
+    first = json.loads(turns.run_turn())["victory"]
+    assert first["verdict"] is None
+    assert first["standings"]["Imperium"]["progress"]["EconomicDominance"] == 0.5
+    assert first["standings"]["Orks"]["progress"]["Survival"] == 0.1
+
+    second = json.loads(turns.run_turn())["victory"]
+    assert second["verdict"] == {"winner": "Imperium", "condition": "EconomicDominance", "turn": 2}
+    assert json.loads(turns.verdict())["winner"] == "Imperium"
+    assert any("wins by EconomicDominance" in e.message for e in log.get_all())
+
+    turns.set_victory_rules(None)
+    assert json.loads(turns.run_turn())["victory"] is None
@@ -0,0 +1,70 @@
+"""Published views: a reader thread sees the topology, economy and
+treasuries as whole turns left them while turns run on another thread,
+and a background battle publishes at its progress points."""
+
+import json
+import threading
+
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+SCALE = 1_000_000
+
+
+def test_views_only_ever_show_whole_turns():
+    pathfinder = bridge.RustPathfinder()
+    pathfinder.add_edge("Terra", "Mars", 1.0)
+    economy = bridge.RustEconomyEngine()
+    economy.add_node(json.dumps({
+        "id": "Terra",
+        "owner_faction": "Imperium",
+        "node_type": "Planet",
+        "base_income": {"credits": 100 * SCALE, "minerals": 0, "energy": 0, "research": 0},
+        "base_upkeep": {"credits": 0, "minerals": 0, "energy": 0, "research": 0},
+        "efficiency_scaled": SCALE,
+        "modifiers": [],
+    }))
+    turns = bridge.RustTurnEngine(pathfinder, economy)
+    assert turns.view() is None and pathfinder.view() is None
+
+    seen = []
+    done = threading.Event()
+
+    def read():
+        while not done.is_set():
+            view = turns.view()
+            if view is not None:
+                seen.append((view.turn, view.treasuries()["Imperium"].credits))
+
+    reader = threading.Thread(target=read)
+    reader.start()
+    for _ in range(20):
+        turns.run_turn()
+    done.set()
+    reader.join()
+    assert all(credits == 100.0 * turn for turn, credits in seen)
+
+    view = turns.view()
+    assert (view.turn, view.version) == (20, 20)
+    assert json.loads(economy.view().economy())["nodes"][0]["id"] == "Terra"
+    assert json.loads(pathfinder.view().topology())["edges"] == [["Terra", "Mars", 1.0]]
+    # Edits stay out of views until published.
+    pathfinder.add_edge("Mars", "Terra", 1.0)
+    assert len(json.loads(pathfinder.view().topology())["edges"]) == 1
+    assert pathfinder.publish() == 21
+    assert pathfinder.view().economy() is None
+
+
+def test_background_battle_publishes_as_it_runs():
+    engine = bridge.RustCombatEngine(200.0, 200.0)
+    laser = bridge.WeaponSpec("Laser", 300.0, 1.0, "Energy")
+    for i in range(4):
+        engine.add_unit_spec(bridge.UnitSpec(i, i % 2, 50.0, x=float(i * 4), weapons=[laser]))
+    assert engine.view() is None
+    run = engine.run_to_completion_async(progress_every=1)
+    assert run.wait(10.0)
+    view = engine.view()
+    assert view.version >= 1 and view.turn is None
+    assert view.units() == engine.get_state()
+    assert json.loads(view.battle())["turn"] == run.turn
@@ -0,0 +1,23 @@
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+
+def test_weights_trade_cost_time_and_risk():
+    pf = bridge.RustPathfinder()
+    for via, cost in [("Cheap", 1.0), ("Fast", 3.0)]:
+        pf.add_edge("A", via, cost)
+        pf.add_edge(via, "D", cost)
+    assert pf.set_lane_metrics("A", "Cheap", time=10.0) == 1
+    assert pf.set_lane_metrics("A", "Fast", time=1.0, risk=10.0) == 1
+    assert pf.lane_metrics("A", "Fast") == (1.0, 10.0)
+
+    assert pf.find_path("A", "D")[0] == ["A", "Cheap", "D"]
+    assert pf.find_path("A", "D", weights={"time": 1.0}) == (["A", "Fast", "D"], 4.0)
+    assert pf.find_path("A", "D", weights={"time": 1.0, "risk": 1.0})[0] == ["A", "Cheap", "D"]
+    with pytest.raises(ValueError):
+        pf.find_path("A", "D", weights={"speed": 1.0})
+    with pytest.raises(ValueError):
+        pf.set_lane_metrics("A", "Cheap", time=-1.0)
+    with pytest.raises(ValueError):
+        pf.set_lane_metrics("A", "Cheap", risk=float("nan"))
@@ -0,0 +1,48 @@
+"""Campaign entities: a planet that both pays into the economy and fights
+with its defenses is one entity, read by both engines."""
+
+import json
+
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+CREDITS = 1_000_000
+
+
+def test_fortified_planet_fights_and_keeps_paying():
+    world = bridge.RustWorld()
+    cadia = world.spawn_node(json.dumps({
+        "id": "Cadia",
+        "owner_faction": "Imperium",
+        "node_type": "Planet",
+        "base_income": {"credits": 20 * CREDITS, "minerals": 0, "energy": 0, "research": 0},
+        "base_upkeep": {"credits": 0, "minerals": 0, "energy": 0, "research": 0},
+        "efficiency_scaled": CREDITS,
+        "modifiers": [],
+    }))
+    world.attach(cadia, json.dumps({"health": {"hp": 50.0, "max_hp": 50.0}}))
+    raider = world.spawn_unit(json.dumps({
+        "id": 0, "name": "raider", "faction_idx": 0, "max_hp": 400.0,
+        "weapons": [{"name": "Lance", "range": 2000.0, "damage": 60.0}],
+    }), "Orks", "Cadia")
+    assert world.find("Cadia") == cadia and len(world) == 2
+
+    battle, roster = world.start_battle("Cadia", seed=5)
+    assert json.loads(roster)["factions"] == ["Imperium", "Orks"]
+    while battle.step():
+        pass
+    assert world.apply_battle(roster, battle) == [cadia]
+    planet = json.loads(world.get(cadia))
+    assert "health" not in planet and planet["yield"]["node_type"] == "Planet"
+    assert json.loads(world.get(raider))["health"]["hp"] > 0
+
+    economy = bridge.RustEconomyEngine()
+    assert world.sync_economy(economy) == 1
+    assert json.loads(economy.process_faction("Imperium"))["total_income"]["credits"] == 20 * CREDITS
+
+    restored = bridge.RustWorld.from_json(world.to_json())
+    assert json.loads(restored.get(restored.find("raider")))["owner"] == "Orks"
+    assert world.despawn(raider) and not world.despawn(raider)
+    with pytest.raises(KeyError):
+        world.get(raider)
@@ -0,0 +1,21 @@
+import pytest
+
+bridge = pytest.importorskip("void_reckoning_bridge")
+
+
+def test_zone_of_control_routes():
+    pf = bridge.RustPathfinder(undirected=True)
+    for u, v, w in [("A", "B", 1.0), ("B", "D", 1.0), ("A", "E", 1.0), ("E", "F", 1.0), ("F", "D", 1.0)]:
+        pf.add_edge(u, v, w)
+    pf.add_edge("B", "X", 1.0)
+    assert pf.set_node_owner("X", "Empire")
+    assert pf.node_owner("X") == "Empire"
+    assert pf.zone_of_control(["Empire"]) == ["B", "X"]
+
+    assert pf.find_path_zoc("A", "D", ["Empire"]) == (["A", "E", "F", "D"], 3.0)
+    assert pf.find_path_zoc("A", "D", ["Empire"], penalty=0.5) == (["A", "B", "D"], 2.5)
+    assert pf.find_path_zoc("A", "D", ["Rebels"]) == (["A", "B", "D"], 2.0)
+
+    pf.set_contested("E")
+    assert pf.is_contested("E")
+    assert pf.find_path_zoc("A", "D", ["Empire"]) is None